@@ -0,0 +1,26 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Use of this source code is governed by the Business Source License
+// as found in the LICENSE-BSL file.
+pragma solidity ^0.8.24;
+
+import {Predicate, PredicateType} from "../src/types/Predicate.sol";
+
+/// @notice Exposes the onchain predicate evaluation, to check it against offchain implementations.
+contract PredicateEvaluator {
+    /// @notice Evaluates the predicate against the journal of a successful execution of the image.
+    /// @dev The predicate is given as its fields so that it can be called from any ABI encoder.
+    /// PredicateLibrary.eval leaves checking the image ID of DigestMatch and PrefixMatch
+    /// predicates to the caller, so it is checked here as it is when fulfilling a request.
+    function eval(uint8 predicateType, bytes calldata data, bytes32 imageId, bytes calldata journal)
+        external
+        pure
+        returns (bool)
+    {
+        Predicate memory predicate = Predicate({predicateType: PredicateType(predicateType), data: data});
+        if (predicate.predicateType != PredicateType.ClaimDigestMatch && bytes32(data[:32]) != imageId) {
+            return false;
+        }
+        return predicate.eval(imageId, journal, sha256(journal));
+    }
+}
@@ -133,6 +133,16 @@ impl AssessorInput {
     pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
         Ok(postcard::from_bytes(bytes)?)
     }
+
+    /// Encode the [AssessorInput] as the stdin of the assessor guest.
+    ///
+    /// The receipts of the fulfilled requests must be added as assumptions when proving, and the
+    /// claim of the resulting receipt included in the same set as theirs. See
+    /// [boundless_market::fulfillment] for building the fulfillments from the proven set.
+    #[cfg(not(target_os = "zkvm"))]
+    pub fn to_stdin(&self) -> Vec<u8> {
+        boundless_market::GuestEnv::builder().write_frame(&self.encode()).stdin
+    }
 }
 
 /// Processes a vector of leaves to compute the Merkle root.
@@ -169,7 +169,11 @@ pub async fn run(args: &MainArgs) -> Result<()> {
                     &PrivateKeySigner::random(),
                     boundless_client.deployment.boundless_market_address,
                     &db_url,
-                    IndexerServiceConfig { interval: Duration::from_secs(2), retries: 5 },
+                    IndexerServiceConfig {
+                        interval: Duration::from_secs(2),
+                        retries: 5,
+                        rate_limiter: None,
+                    },
                 )
                 .await?;
 
@@ -0,0 +1,107 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Timeouts and interruption of CLI commands.
+//!
+//! Commands run under [run_cancellable], which stops them once the `--command-timeout` elapses or
+//! on Ctrl-C. Dropping the command cancels its in-flight RPC calls, but transactions it already
+//! broadcast may still land. Clients built from the [GlobalConfig][crate::config::GlobalConfig]
+//! record their transactions in the [sent_transactions] log, so that their hashes can be printed.
+
+use std::{future::Future, sync::LazyLock, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use boundless_market::sent_tx_layer::SentTransactions;
+
+use crate::exit_code::{ExitCode, WithExitCode};
+
+static SENT_TRANSACTIONS: LazyLock<SentTransactions> = LazyLock::new(SentTransactions::new);
+
+/// Log of the transactions sent by this process.
+pub fn sent_transactions() -> SentTransactions {
+    SENT_TRANSACTIONS.clone()
+}
+
+/// Run the command to completion, unless it is interrupted by Ctrl-C or `timeout` elapses first.
+///
+/// An interrupted command fails with [ExitCode::Interrupted], and a timed out command with
+/// [ExitCode::Timeout]. In both cases the error lists the transactions that were sent.
+pub async fn run_cancellable(
+    command: impl Future<Output = Result<()>>,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let deadline = async {
+        match timeout {
+            Some(timeout) => tokio::time::sleep(timeout).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        result = command => result,
+        interrupt = tokio::signal::ctrl_c() => {
+            interrupt.context("Failed to listen for Ctrl-C")?;
+            cancelled("Interrupted".to_string(), ExitCode::Interrupted)
+        }
+        _ = deadline => cancelled(
+            format!("Command timed out after {}s", timeout.unwrap_or_default().as_secs()),
+            ExitCode::Timeout,
+        ),
+    }
+}
+
+fn cancelled(reason: String, code: ExitCode) -> Result<()> {
+    Err(anyhow!(cancellation_message(reason, &SENT_TRANSACTIONS.hashes()))).exit_code(code)
+}
+
+fn cancellation_message(reason: String, sent: &[alloy::primitives::B256]) -> String {
+    let mut message = reason;
+    if sent.is_empty() {
+        message.push_str("; no transactions were sent");
+    }
+    for hash in sent {
+        message.push_str(&format!("\n  Transaction may still land, hash: {hash}"));
+    }
+    if !sent.is_empty() {
+        message.push_str("\n  Check the transactions on a block explorer before retrying");
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn command_timeout() {
+        let err = run_cancellable(std::future::pending(), Some(Duration::from_millis(10)))
+            .await
+            .unwrap_err();
+        assert_eq!(ExitCode::of(&err), ExitCode::Timeout);
+        assert!(err.to_string().starts_with("Command timed out"));
+
+        run_cancellable(async { Ok(()) }, Some(Duration::from_secs(10))).await.unwrap();
+        run_cancellable(async { Ok(()) }, None).await.unwrap();
+    }
+
+    #[test]
+    fn message_lists_sent_transactions() {
+        let message = cancellation_message("Interrupted".to_string(), &[]);
+        assert_eq!(message, "Interrupted; no transactions were sent");
+
+        let hash = B256::repeat_byte(0xab);
+        let message = cancellation_message("Interrupted".to_string(), &[hash]);
+        assert!(message.contains(&format!("Transaction may still land, hash: {hash}")));
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    path::PathBuf,
+};
+
+use alloy::{
+    primitives::{
+        utils::{format_ether, format_units, parse_ether, parse_units},
+        Address, U256,
+    },
+    providers::Provider,
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{bail, ensure, Context};
+use boundless_market::Client;
+use clap::Args;
+use serde::Deserialize;
+
+use crate::config::GlobalConfig;
+
+/// Command to run a batch of account operations listed in a CSV file.
+///
+/// The CSV file must have a header row with the columns `operation` and `amount`, and optionally
+/// `key_env` and `address`:
+///
+/// - `operation` is one of `deposit`, `withdraw`, `deposit-collateral` or `withdraw-collateral`.
+/// - `amount` is in ETH for deposits and withdrawals, and in the collateral token otherwise.
+/// - `key_env` is the name of an environment variable holding the private key of the wallet to
+///   send the operation from. If empty, the configured private key is used.
+/// - `address`, if set, is checked against the address of the wallet before anything is sent.
+///
+/// Every row is validated before any transaction is sent. Rows are then executed in order, and the
+/// outcome of each row is reported.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct AccountBatch {
+    /// Path to the CSV file of operations.
+    #[clap(long)]
+    pub file: PathBuf,
+    /// Validate the operations and check balances without sending any transactions.
+    ///
+    /// Balances are carried from row to row, so that e.g. two withdrawals that each fit the
+    /// current balance, but not together, are reported.
+    #[clap(long)]
+    pub dry_run: bool,
+    /// Stop at the first failed operation instead of continuing with the remaining rows.
+    #[clap(long)]
+    pub stop_on_error: bool,
+}
+
+/// Account operation of a batch row.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BatchOperation {
+    /// Deposit ETH into the market.
+    Deposit,
+    /// Withdraw ETH from the market.
+    Withdraw,
+    /// Deposit collateral into the market.
+    DepositCollateral,
+    /// Withdraw collateral from the market.
+    WithdrawCollateral,
+}
+
+/// A row of the batch CSV file.
+#[derive(Clone, Debug, Deserialize)]
+#[non_exhaustive]
+pub struct BatchRow {
+    /// Operation to perform.
+    pub operation: BatchOperation,
+    /// Amount, as a decimal string.
+    pub amount: String,
+    /// Name of the environment variable holding the private key to use.
+    #[serde(default)]
+    pub key_env: Option<String>,
+    /// Expected address of the wallet.
+    #[serde(default)]
+    pub address: Option<Address>,
+}
+
+impl BatchRow {
+    /// Read the rows of a batch CSV file.
+    pub fn read_all(path: &PathBuf) -> anyhow::Result<Vec<Self>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        reader
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| row.with_context(|| format!("invalid row {}", i + 1)))
+            .collect()
+    }
+}
+
+/// A validated row, with its signer and the amount in base units.
+struct PlannedOp {
+    row: usize,
+    operation: BatchOperation,
+    signer: PrivateKeySigner,
+    amount: U256,
+    display: String,
+}
+
+impl AccountBatch {
+    /// Run the [AccountBatch] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let rows = BatchRow::read_all(&self.file)?;
+        ensure!(!rows.is_empty(), "no operations in {}", self.file.display());
+
+        let client = global_config.build_client().await?;
+        let symbol = client.boundless_market.collateral_token_symbol().await?;
+        let decimals = client.boundless_market.collateral_token_decimals().await?;
+
+        // Validate every row before sending anything, so a typo does not leave a batch half done.
+        let mut planned = Vec::with_capacity(rows.len());
+        for (i, row) in rows.into_iter().enumerate() {
+            let row_num = i + 1;
+            let signer = match &row.key_env {
+                Some(var) => std::env::var(var)
+                    .with_context(|| format!("row {row_num}: env var {var} is not set"))?
+                    .parse::<PrivateKeySigner>()
+                    .with_context(|| {
+                        format!("row {row_num}: env var {var} is not a private key")
+                    })?,
+                None => global_config.require_private_key()?,
+            };
+            if let Some(address) = row.address {
+                ensure!(
+                    address == signer.address(),
+                    "row {row_num}: expected address {address}, but the key is for {}",
+                    signer.address()
+                );
+            }
+            let (amount, display) = match row.operation {
+                BatchOperation::Deposit | BatchOperation::Withdraw => {
+                    let amount = parse_ether(&row.amount)
+                        .with_context(|| format!("row {row_num}: invalid amount"))?;
+                    (amount, format!("{} ETH", format_ether(amount)))
+                }
+                BatchOperation::DepositCollateral | BatchOperation::WithdrawCollateral => {
+                    let amount: U256 = parse_units(&row.amount, decimals)
+                        .with_context(|| format!("row {row_num}: invalid amount"))?
+                        .into();
+                    (amount, format!("{} {symbol}", format_units(amount, decimals)?))
+                }
+            };
+            ensure!(amount > U256::ZERO, "row {row_num}: amount must be greater than zero");
+            planned.push(PlannedOp {
+                row: row_num,
+                operation: row.operation,
+                signer,
+                amount,
+                display,
+            });
+        }
+
+        let mut clients = HashMap::new();
+        let mut balances = HashMap::new();
+        let mut failed = 0;
+        for op in &planned {
+            let address = op.signer.address();
+            if !clients.contains_key(&address) {
+                let client = global_config
+                    .client_builder()?
+                    .with_private_key(op.signer.clone())
+                    .build()
+                    .await
+                    .with_context(|| format!("failed to build client for {address}"))?;
+                clients.insert(address, client);
+            }
+            let client = &clients[&address];
+
+            let result = if self.dry_run {
+                match balances.entry(address) {
+                    Entry::Occupied(entry) => Ok(entry.into_mut()),
+                    Entry::Vacant(entry) => {
+                        Balances::fetch(client, address).await.map(|fetched| entry.insert(fetched))
+                    }
+                }
+                .and_then(|balances| balances.apply(op.operation, op.amount))
+            } else {
+                execute_op(client, op).await
+            };
+            match result {
+                Ok(()) => {
+                    let verb = if self.dry_run { "would" } else { "did" };
+                    println!(
+                        "✅ row {}: {address} {verb} {:?} {}",
+                        op.row, op.operation, op.display
+                    );
+                }
+                Err(err) => {
+                    failed += 1;
+                    println!(
+                        "❌ row {}: {address} {:?} {}: {err:#}",
+                        op.row, op.operation, op.display
+                    );
+                    if self.stop_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let succeeded = planned.len() - failed;
+        println!(
+            "\n{succeeded} of {} operations {}",
+            planned.len(),
+            match self.dry_run {
+                true => "passed checks",
+                false => "succeeded",
+            }
+        );
+        if failed > 0 {
+            bail!("{failed} operations failed");
+        }
+        Ok(())
+    }
+}
+
+/// Balances of an account, carried across the rows of a dry run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Balances {
+    wallet: U256,
+    market: U256,
+    wallet_collateral: U256,
+    market_collateral: U256,
+}
+
+impl Balances {
+    /// Query the current balances of the account.
+    async fn fetch<P, St, R>(
+        client: &Client<P, St, R, PrivateKeySigner>,
+        address: Address,
+    ) -> anyhow::Result<Self>
+    where
+        P: Provider + Clone + 'static,
+    {
+        let market = &client.boundless_market;
+        let provider = market.instance().provider();
+        let token = market.collateral_token_address().await?;
+        Ok(Self {
+            wallet: provider.get_balance(address).await?,
+            market: market.balance_of(address).await?,
+            wallet_collateral: boundless_market::contracts::token::IERC20::new(token, provider)
+                .balanceOf(address)
+                .call()
+                .await?,
+            market_collateral: market.balance_of_collateral(address).await?,
+        })
+    }
+
+    /// Move the amount of the operation between the balances, failing without changing them if
+    /// the balance it is taken from is insufficient.
+    ///
+    /// Gas costs are not accounted for.
+    fn apply(&mut self, operation: BatchOperation, amount: U256) -> anyhow::Result<()> {
+        let (from, to) = match operation {
+            BatchOperation::Deposit => (&mut self.wallet, &mut self.market),
+            BatchOperation::Withdraw => (&mut self.market, &mut self.wallet),
+            BatchOperation::DepositCollateral => {
+                (&mut self.wallet_collateral, &mut self.market_collateral)
+            }
+            BatchOperation::WithdrawCollateral => {
+                (&mut self.market_collateral, &mut self.wallet_collateral)
+            }
+        };
+        ensure!(*from >= amount, "insufficient funds: {from} < {amount}");
+        *from -= amount;
+        *to += amount;
+        Ok(())
+    }
+}
+
+/// Send the transaction for the operation and wait for it to confirm.
+async fn execute_op<P, St, R>(
+    client: &Client<P, St, R, PrivateKeySigner>,
+    op: &PlannedOp,
+) -> anyhow::Result<()>
+where
+    P: Provider + Clone + 'static,
+{
+    let market = &client.boundless_market;
+    match op.operation {
+        BatchOperation::Deposit => market.deposit(op.amount).await?,
+        BatchOperation::Withdraw => market.withdraw(op.amount).await?,
+        BatchOperation::DepositCollateral => {
+            if client.deployment.collateral_token_supports_permit() {
+                market.deposit_collateral_with_permit(op.amount, &op.signer).await?
+            } else {
+                market.approve_deposit_collateral(op.amount).await?;
+                market.deposit_collateral(op.amount).await?
+            }
+        }
+        BatchOperation::WithdrawCollateral => market.withdraw_collateral(op.amount).await?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_batch_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ops.csv");
+        std::fs::write(
+            &path,
+            "operation,amount,key_env,address\n\
+             deposit,0.5,,\n\
+             withdraw-collateral, 10 ,PROVER_1_KEY,0x0000000000000000000000000000000000000001\n",
+        )
+        .unwrap();
+
+        let rows = BatchRow::read_all(&path).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].operation, BatchOperation::Deposit);
+        assert_eq!(rows[0].key_env, None);
+        assert_eq!(rows[0].address, None);
+        assert_eq!(rows[1].operation, BatchOperation::WithdrawCollateral);
+        assert_eq!(rows[1].amount, "10");
+        assert_eq!(rows[1].key_env.as_deref(), Some("PROVER_1_KEY"));
+        assert_eq!(rows[1].address, Some(Address::with_last_byte(1)));
+
+        std::fs::write(&path, "operation,amount\nstake,1\n").unwrap();
+        assert!(BatchRow::read_all(&path).is_err());
+    }
+
+    #[test]
+    fn balances_carry_across_rows() {
+        let mut balances = Balances { market: U256::from(10), ..Default::default() };
+
+        // Each withdrawal fits the initial balance, but not both together.
+        balances.apply(BatchOperation::Withdraw, U256::from(6)).unwrap();
+        let err = balances.apply(BatchOperation::Withdraw, U256::from(6)).unwrap_err();
+        assert!(err.to_string().contains("insufficient funds"));
+        assert_eq!(balances, Balances { market: U256::from(4), wallet: U256::from(6), ..balances });
+
+        // Funds withdrawn by an earlier row can be deposited again.
+        balances.apply(BatchOperation::Deposit, U256::from(6)).unwrap();
+        assert_eq!(balances.market, U256::from(10));
+        assert!(balances.apply(BatchOperation::DepositCollateral, U256::from(1)).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{
+        utils::{format_units, parse_ether, parse_units},
+        Address, U256,
+    },
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{ensure, Context};
+use boundless_market::contracts::token::IERC20;
+use boundless_zkc::{contracts::IRewards, deployments::Deployment as ZkcDeployment};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{commands::zkc::get_rewards_delegates, config::GlobalConfig, format::FormattedAmount};
+
+/// Command to onboard a fresh prover in one step.
+///
+/// From the configured private key, the funder, it sends the prover the ETH it needs for gas and
+/// the collateral tokens it needs to deposit. From the prover key, it then approves and deposits
+/// the collateral, as collateral is credited to the account that deposits it, and optionally
+/// delegates the ZKC rewards of the prover. The result is checked once done.
+///
+/// Amounts are targets: only the shortfall of the prover is sent and deposited, so the command
+/// can be run again to resume after a failure.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct AccountFundProver {
+    /// Private key of the prover to onboard.
+    #[clap(long, env = "PROVER_PRIVATE_KEY", hide_env_values = true)]
+    pub prover_private_key: PrivateKeySigner,
+    /// Expected address of the prover, checked against the prover key before anything is sent.
+    #[clap(long)]
+    pub prover_address: Option<Address>,
+    /// ETH the prover should hold for gas before sending its own transactions.
+    #[clap(long, value_parser = parse_ether, default_value = "0.01")]
+    pub gas: U256,
+    /// Collateral the prover should have deposited in the market, in the collateral token.
+    #[clap(long)]
+    pub collateral: String,
+    /// Delegate the ZKC rewards of the prover to this address, e.g. the address staking on its
+    /// behalf.
+    #[clap(long)]
+    pub delegate_rewards_to: Option<Address>,
+    /// Print the onboarding steps without sending any transactions.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// Transfers and deposits needed to bring the prover to the requested amounts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct FundingPlan {
+    /// ETH to send to the prover.
+    gas_topup: U256,
+    /// Collateral tokens to send to the prover.
+    token_topup: U256,
+    /// Collateral the prover deposits into the market.
+    deposit: U256,
+}
+
+impl FundingPlan {
+    fn new(
+        gas: U256,
+        collateral: U256,
+        eth_balance: U256,
+        token_balance: U256,
+        deposited: U256,
+    ) -> Self {
+        let deposit = collateral.saturating_sub(deposited);
+        Self {
+            gas_topup: gas.saturating_sub(eth_balance),
+            token_topup: deposit.saturating_sub(token_balance),
+            deposit,
+        }
+    }
+}
+
+/// State of the prover once onboarded.
+#[derive(Clone, Debug, Serialize)]
+struct ProverFunding {
+    prover: Address,
+    eth_balance: FormattedAmount,
+    collateral: String,
+    collateral_symbol: String,
+    rewards_delegate: Option<Address>,
+}
+
+impl fmt::Display for ProverFunding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Prover {} is onboarded", self.prover)?;
+        writeln!(f, "  ETH balance: {}", self.eth_balance)?;
+        write!(f, "  Collateral:  {} {}", self.collateral, self.collateral_symbol)?;
+        if let Some(delegate) = self.rewards_delegate {
+            write!(f, "\n  Rewards delegated to: {delegate}")?;
+        }
+        Ok(())
+    }
+}
+
+impl AccountFundProver {
+    /// Run the [AccountFundProver] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let prover = self.prover_private_key.address();
+        if let Some(address) = self.prover_address {
+            ensure!(
+                address == prover,
+                "expected prover address {address}, but the prover key is for {prover}"
+            );
+        }
+
+        let funder_client = global_config.build_client_with_signer().await?;
+        let funder = funder_client.boundless_market.caller();
+        ensure!(funder != prover, "the prover key must differ from the funding private key");
+        let prover_client = global_config
+            .client_builder()?
+            .with_private_key(self.prover_private_key.clone())
+            .build()
+            .await
+            .context("failed to build client for the prover")?;
+
+        let market = &funder_client.boundless_market;
+        let provider = funder_client.provider();
+        let symbol = market.collateral_token_symbol().await?;
+        let decimals = market.collateral_token_decimals().await?;
+        let collateral: U256 = parse_units(&self.collateral, decimals)
+            .with_context(|| format!("invalid collateral amount {}", self.collateral))?
+            .into();
+        let token = IERC20::new(market.collateral_token_address().await?, provider.clone());
+        let output = global_config.output_format();
+
+        let rewards = match self.delegate_rewards_to {
+            Some(delegate) => {
+                let chain_id = provider.get_chain_id().await?;
+                let deployment = ZkcDeployment::from_chain_id(chain_id).with_context(|| {
+                    format!("no ZKC deployment is known for chain {chain_id} to delegate rewards")
+                })?;
+                Some((deployment.vezkc_address, delegate))
+            }
+            None => None,
+        };
+
+        let plan = FundingPlan::new(
+            self.gas,
+            collateral,
+            provider.get_balance(prover).await?,
+            token.balanceOf(prover).call().await?,
+            market.balance_of_collateral(prover).await?,
+        );
+        let format_collateral = |amount: U256| -> anyhow::Result<String> {
+            Ok(format!("{} {symbol}", format_units(amount, decimals)?))
+        };
+
+        // Check the funder can cover the plan before sending anything.
+        let funder_eth = provider.get_balance(funder).await?;
+        ensure!(
+            funder_eth >= plan.gas_topup,
+            "funder {funder} holds {}, but {} must be sent to the prover for gas",
+            output.amount(funder_eth),
+            output.amount(plan.gas_topup)
+        );
+        let funder_tokens = token.balanceOf(funder).call().await?;
+        ensure!(
+            funder_tokens >= plan.token_topup,
+            "funder {funder} holds {}, but {} must be sent to the prover as collateral",
+            format_collateral(funder_tokens)?,
+            format_collateral(plan.token_topup)?
+        );
+
+        if self.dry_run {
+            tracing::info!(
+                "Would send {} to prover {prover} for gas",
+                output.amount(plan.gas_topup)
+            );
+            tracing::info!(
+                "Would send {} to prover {prover}, and deposit {} as its collateral",
+                format_collateral(plan.token_topup)?,
+                format_collateral(plan.deposit)?
+            );
+            if let Some((_, delegate)) = rewards {
+                tracing::info!("Would delegate the rewards of prover {prover} to {delegate}");
+            }
+            return Ok(());
+        }
+
+        if plan.gas_topup > U256::ZERO {
+            tracing::info!("Sending {} to prover {prover} for gas", output.amount(plan.gas_topup));
+            let tx = TransactionRequest::default().with_to(prover).with_value(plan.gas_topup);
+            provider
+                .send_transaction(tx)
+                .await?
+                .with_timeout(global_config.tx_timeout)
+                .watch()
+                .await
+                .context("failed to confirm the transfer of gas funds")?;
+        } else {
+            tracing::info!("Prover {prover} already holds enough ETH for gas");
+        }
+
+        if plan.token_topup > U256::ZERO {
+            tracing::info!("Sending {} to prover {prover}", format_collateral(plan.token_topup)?);
+            token
+                .transfer(prover, plan.token_topup)
+                .send()
+                .await
+                .context("failed to send the collateral transfer")?
+                .with_timeout(global_config.tx_timeout)
+                .watch()
+                .await
+                .context("failed to confirm the collateral transfer")?;
+        }
+
+        let prover_market = &prover_client.boundless_market;
+        if plan.deposit > U256::ZERO {
+            let formatted = format_collateral(plan.deposit)?;
+            tracing::info!("Depositing {formatted} as collateral of prover {prover}");
+            if prover_client.deployment.collateral_token_supports_permit() {
+                prover_market
+                    .deposit_collateral_with_permit(plan.deposit, &self.prover_private_key)
+                    .await?;
+            } else {
+                prover_market.approve_deposit_collateral(plan.deposit).await?;
+                prover_market.deposit_collateral(plan.deposit).await?;
+            }
+        } else {
+            tracing::info!("Prover {prover} already has enough collateral deposited");
+        }
+
+        if let Some((vezkc_address, delegate)) = rewards {
+            if get_rewards_delegates(provider.clone(), vezkc_address, prover).await? == delegate {
+                tracing::info!("Rewards of prover {prover} are already delegated to {delegate}");
+            } else {
+                tracing::info!("Delegating the rewards of prover {prover} to {delegate}");
+                let receipt = IRewards::new(vezkc_address, prover_client.provider())
+                    .delegateRewards(delegate)
+                    .send()
+                    .await
+                    .context("failed to send the rewards delegation")?
+                    .with_timeout(global_config.tx_timeout)
+                    .get_receipt()
+                    .await
+                    .context("failed to confirm the rewards delegation")?;
+                ensure!(
+                    receipt.status(),
+                    "rewards delegation failed: tx_hash = {}",
+                    receipt.transaction_hash
+                );
+            }
+        }
+
+        // Verify the prover ended up with what was asked for.
+        let deposited = market.balance_of_collateral(prover).await?;
+        ensure!(
+            deposited >= collateral,
+            "prover {prover} has {} deposited, expected at least {}",
+            format_collateral(deposited)?,
+            format_collateral(collateral)?
+        );
+        let rewards_delegate = match rewards {
+            Some((vezkc_address, delegate)) => {
+                let actual = get_rewards_delegates(provider.clone(), vezkc_address, prover).await?;
+                ensure!(
+                    actual == delegate,
+                    "rewards of prover {prover} are delegated to {actual}, expected {delegate}"
+                );
+                Some(actual)
+            }
+            None => None,
+        };
+
+        let funding = ProverFunding {
+            prover,
+            eth_balance: output.amount(provider.get_balance(prover).await?),
+            collateral: format_units(deposited, decimals)?,
+            collateral_symbol: symbol.clone(),
+            rewards_delegate,
+        };
+        output.print(&funding, || funding.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn funding_plan_tops_up_shortfall() {
+        let plan = FundingPlan::new(
+            U256::from(100),
+            U256::from(50),
+            U256::from(30),
+            U256::from(15),
+            U256::from(20),
+        );
+        assert_eq!(
+            plan,
+            FundingPlan {
+                gas_topup: U256::from(70),
+                token_topup: U256::from(15),
+                deposit: U256::from(30)
+            }
+        );
+
+        // A prover that already holds the amounts needs nothing.
+        let plan = FundingPlan::new(
+            U256::from(100),
+            U256::from(50),
+            U256::from(200),
+            U256::ZERO,
+            U256::from(60),
+        );
+        assert_eq!(plan, FundingPlan::default());
+    }
+}
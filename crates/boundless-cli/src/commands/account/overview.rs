@@ -0,0 +1,229 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, str::FromStr};
+
+use alloy::primitives::{utils::format_units, Address};
+use anyhow::{bail, Context};
+use boundless_market::{deployments::NamedChain, Deployment};
+use clap::Args;
+use futures::future::join_all;
+use serde::Serialize;
+use url::Url;
+
+use crate::{
+    commands::ops::LockMonitor,
+    config::GlobalConfig,
+    format::{FormattedAmount, OutputFormat},
+    table::{Cell, Style, Table},
+};
+
+/// Command to print the balances and open requests of an account on several chains.
+///
+/// Each chain is queried concurrently, using the Boundless deployment known for it. The RPC URL of
+/// a chain is given with `--chain-rpc-url <chain>=<url>`, or read from the `RPC_URL_<CHAIN>` env
+/// var, e.g. `RPC_URL_BASE_SEPOLIA`. A chain that cannot be queried is reported in its row,
+/// without failing the others.
+///
+/// Open requests are those of the account, submitted onchain within --lookback-blocks, that are
+/// neither fulfilled nor expired.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct AccountOverview {
+    /// Comma-separated list of chains to query, e.g. `sepolia,base,base-sepolia`.
+    #[clap(long, value_delimiter = ',', required = true)]
+    pub chains: Vec<NamedChain>,
+    /// RPC URL of a chain, as `<chain>=<url>`. May be repeated.
+    #[clap(long, value_parser = parse_chain_rpc_url)]
+    pub chain_rpc_url: Vec<(NamedChain, Url)>,
+    /// Address of the account. Defaults to the address of the configured private key.
+    #[clap(long)]
+    pub address: Option<Address>,
+    /// Number of blocks before the current block from which to count open requests.
+    #[clap(long, default_value = "1000")]
+    pub lookback_blocks: u64,
+}
+
+fn parse_chain_rpc_url(value: &str) -> anyhow::Result<(NamedChain, Url)> {
+    let (chain, url) = value.split_once('=').context("expected <chain>=<url>")?;
+    let chain = NamedChain::from_str(chain).with_context(|| format!("unknown chain {chain}"))?;
+    Ok((chain, url.parse()?))
+}
+
+/// Balances and open requests of the account on one chain.
+#[derive(Clone, Debug, Serialize)]
+struct ChainOverview {
+    chain: String,
+    chain_id: u64,
+    #[serde(flatten)]
+    result: ChainResult,
+}
+
+/// Outcome of querying one chain.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+enum ChainResult {
+    Ok {
+        balance: FormattedAmount,
+        collateral: String,
+        collateral_symbol: String,
+        open_requests: usize,
+    },
+    Err {
+        error: String,
+    },
+}
+
+/// Overview of the account across the queried chains.
+#[derive(Clone, Debug, Serialize)]
+struct Overview {
+    address: Address,
+    chains: Vec<ChainOverview>,
+}
+
+impl AccountOverview {
+    /// Run the [AccountOverview] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let address = match self.address {
+            Some(address) => address,
+            None => global_config.require_private_key()?.address(),
+        };
+        let rpc_urls: HashMap<NamedChain, Url> = self.chain_rpc_url.iter().cloned().collect();
+        let output = global_config.output_format();
+
+        let chains = join_all(self.chains.iter().map(|&chain| {
+            let rpc_url = rpc_urls.get(&chain).cloned();
+            async move {
+                let result = match self.query(global_config, chain, rpc_url, address).await {
+                    Ok(result) => result,
+                    Err(err) => ChainResult::Err { error: format!("{err:#}") },
+                };
+                ChainOverview { chain: chain.to_string(), chain_id: chain as u64, result }
+            }
+        }))
+        .await;
+
+        let overview = Overview { address, chains };
+        output.print(&overview, || overview.render(&output))
+    }
+
+    async fn query(
+        &self,
+        global_config: &GlobalConfig,
+        chain: NamedChain,
+        rpc_url: Option<Url>,
+        address: Address,
+    ) -> anyhow::Result<ChainResult> {
+        let Some(deployment) = Deployment::from_chain(chain) else {
+            bail!("no Boundless deployment is known for {chain}");
+        };
+        let rpc_url = match rpc_url {
+            Some(rpc_url) => rpc_url,
+            None => chain_rpc_url_from_env(chain)?,
+        };
+        let config = GlobalConfig {
+            rpc_url: Some(rpc_url),
+            deployment: Some(deployment),
+            ..global_config.clone()
+        };
+        let client = config.build_client().await?;
+        let market = &client.boundless_market;
+
+        let (balance, collateral, symbol, decimals) = tokio::try_join!(
+            market.balance_of(address),
+            market.balance_of_collateral(address),
+            market.collateral_token_symbol(),
+            market.collateral_token_decimals(),
+        )?;
+
+        let provider = client.provider();
+        let mut monitor =
+            LockMonitor::new(&provider, address, 100, self.lookback_blocks, None).await?;
+        monitor
+            .poll(&provider, client.deployment.boundless_market_address, None)
+            .await
+            .context("failed to count open requests")?;
+
+        Ok(ChainResult::Ok {
+            balance: global_config.output_format().amount(balance),
+            collateral: format_units(collateral, decimals)?,
+            collateral_symbol: symbol,
+            open_requests: monitor.open_requests(),
+        })
+    }
+}
+
+/// Read the RPC URL of a chain from the `RPC_URL_<CHAIN>` env var.
+fn chain_rpc_url_from_env(chain: NamedChain) -> anyhow::Result<Url> {
+    let var = format!("RPC_URL_{}", chain.to_string().to_uppercase().replace('-', "_"));
+    let Ok(url) = std::env::var(&var) else {
+        bail!("no RPC URL; set --chain-rpc-url {chain}=<url> or the {var} env var");
+    };
+    url.parse().with_context(|| format!("invalid URL in {var}"))
+}
+
+impl Overview {
+    /// Render the overview as a table of chains, under the address of the account.
+    fn render(&self, output: &OutputFormat) -> String {
+        let mut table =
+            Table::new(["chain", "chain id", "balance", "collateral", "open requests", "status"])
+                .align_right([1, 2, 3, 4]);
+        for row in &self.chains {
+            let (chain, chain_id): (Cell, Cell) =
+                (row.chain.as_str().into(), row.chain_id.to_string().into());
+            match &row.result {
+                ChainResult::Ok { balance, collateral, collateral_symbol, open_requests } => {
+                    table.push([
+                        chain,
+                        chain_id,
+                        balance.to_string().into(),
+                        format!("{collateral} {collateral_symbol}").into(),
+                        open_requests.to_string().into(),
+                        Cell::styled("ok", Style::Green),
+                    ]);
+                }
+                ChainResult::Err { error } => {
+                    let placeholder = || Cell::styled("-", Style::Dim);
+                    table.push([
+                        chain,
+                        chain_id,
+                        placeholder(),
+                        placeholder(),
+                        placeholder(),
+                        Cell::styled(format!("error: {error}"), Style::Red),
+                    ]);
+                }
+            }
+        }
+        if output.quiet {
+            output.table(&table)
+        } else {
+            format!("Account {}:\n{}", self.address, output.table(&table))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chain_rpc_url() {
+        let (chain, url) = parse_chain_rpc_url("base-sepolia=https://rpc.example.com").unwrap();
+        assert_eq!(chain, NamedChain::BaseSepolia);
+        assert_eq!(url.as_str(), "https://rpc.example.com/");
+        assert!(parse_chain_rpc_url("https://rpc.example.com").is_err());
+        assert!(parse_chain_rpc_url("nowhere=https://rpc.example.com").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commands of the Boundless CLI for inspecting and clearing the local program cache.
+
+use alloy::primitives::B256;
+use anyhow::{Context, Result};
+use boundless_market::storage::{ProgramCache, ProgramCacheEntry, PROGRAM_CACHE_DIR_ENV};
+use chrono::{DateTime, Local};
+use clap::{Args, Subcommand};
+use risc0_zkvm::sha::Digest;
+use serde::Serialize;
+
+use crate::config::GlobalConfig;
+
+/// Commands for the local cache of programs fetched by `execute`, `fulfill`, and `benchmark`.
+#[derive(Subcommand, Clone, Debug)]
+pub enum CacheCommands {
+    /// List the cached programs, most recently used first.
+    List(CacheList),
+    /// Remove cached programs.
+    Clear(CacheClear),
+}
+
+impl CacheCommands {
+    /// Run the command.
+    pub fn run(&self, global_config: &GlobalConfig) -> Result<()> {
+        let cache = ProgramCache::from_default_dir().with_context(|| {
+            format!("HOME is not set; please set {PROGRAM_CACHE_DIR_ENV} to the program cache path")
+        })?;
+        match self {
+            Self::List(cmd) => cmd.run(&cache, global_config),
+            Self::Clear(cmd) => cmd.run(&cache, global_config),
+        }
+    }
+}
+
+/// A cached program, as printed with `--json`.
+#[derive(Serialize)]
+struct CachedProgram {
+    image_id: String,
+    size: u64,
+    last_used: DateTime<Local>,
+}
+
+impl From<&ProgramCacheEntry> for CachedProgram {
+    fn from(entry: &ProgramCacheEntry) -> Self {
+        Self {
+            image_id: entry.image_id.to_string(),
+            size: entry.size,
+            last_used: entry.last_used.into(),
+        }
+    }
+}
+
+/// Command to list the cached programs.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct CacheList {}
+
+impl CacheList {
+    /// Run the [CacheList] command.
+    pub fn run(&self, cache: &ProgramCache, global_config: &GlobalConfig) -> Result<()> {
+        let programs: Vec<CachedProgram> = cache.entries()?.iter().map(Into::into).collect();
+        global_config.output_format().print(&programs, || {
+            if programs.is_empty() {
+                return format!("No cached programs in {}", cache.dir().display());
+            }
+            let total: u64 = programs.iter().map(|program| program.size).sum();
+            let mut text = format!(
+                "{} programs in {}, using {} of {} bytes\n{:<64}  {:>10}  {}",
+                programs.len(),
+                cache.dir().display(),
+                total,
+                cache.max_size(),
+                "image id",
+                "size",
+                "last used"
+            );
+            for program in &programs {
+                text.push_str(&format!(
+                    "\n{:<64}  {:>10}  {}",
+                    program.image_id,
+                    program.size,
+                    program.last_used.format("%Y-%m-%d %H:%M:%S")
+                ));
+            }
+            text
+        })
+    }
+}
+
+/// Command to remove cached programs.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct CacheClear {
+    /// Only remove the program with this image ID. May be repeated.
+    #[clap(long)]
+    pub image_id: Vec<B256>,
+}
+
+impl CacheClear {
+    /// Run the [CacheClear] command.
+    pub fn run(&self, cache: &ProgramCache, global_config: &GlobalConfig) -> Result<()> {
+        let removed: Vec<String> = if self.image_id.is_empty() {
+            cache.clear()?.iter().map(|entry| entry.image_id.to_string()).collect()
+        } else {
+            let mut removed = vec![];
+            for image_id in &self.image_id {
+                let image_id = Digest::from_bytes(image_id.0);
+                if cache.remove(&image_id)? {
+                    removed.push(image_id.to_string());
+                }
+            }
+            removed
+        };
+        global_config.output_format().print(&removed, || {
+            format!("Removed {} programs from {}", removed.len(), cache.dir().display())
+        })
+    }
+}
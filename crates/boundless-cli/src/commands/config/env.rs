@@ -0,0 +1,228 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, ffi::OsStr};
+
+use anyhow::bail;
+use clap::{Arg, Args, Command};
+use serde::Serialize;
+
+use crate::{
+    completion::COMPLETION_STORE_ENV,
+    config::GlobalConfig,
+    keystore::{KEYSTORE_DIR_ENV, KEYSTORE_PASSWORD_ENV},
+};
+
+/// Variables read directly by the CLI rather than through a command-line flag, with the commands
+/// reading them and a description.
+const DIRECT_VARS: &[(&str, &str, &str)] = &[
+    ("LOG_REDACT", "", "Set to false to disable masking secrets in log output"),
+    ("RUST_LOG", "", "Log filter directives, overriding --log-level"),
+    ("RISC0_DEV_MODE", "", "Set to 1 to produce fake receipts instead of proving"),
+    (COMPLETION_STORE_ENV, "", "Path of the store of values offered for shell completion"),
+    (KEYSTORE_DIR_ENV, "", "Directory of the wallet keystores [default: ~/.boundless/keys]"),
+    (KEYSTORE_PASSWORD_ENV, "", "Password of the wallet keystores, instead of prompting for it"),
+    ("POSTGRES_USER", "proving benchmark", "User of the Bento task DB [default: worker]"),
+    ("POSTGRES_PASSWORD", "proving benchmark", "Password of the Bento task DB [default: password]"),
+    ("POSTGRES_DB", "proving benchmark", "Name of the Bento task DB [default: taskdb]"),
+    ("POSTGRES_HOST", "proving benchmark", "Host of the Bento task DB [default: 127.0.0.1]"),
+    ("POSTGRES_PORT", "proving benchmark", "Port of the Bento task DB [default: 5432]"),
+];
+
+/// Substrings of variable names whose values are redacted.
+const SECRET_MARKERS: &[&str] = &["KEY", "SECRET", "PASSWORD", "TOKEN", "JWT"];
+
+/// Command to list the environment variables read by the CLI.
+///
+/// Variables are collected from the flags of every command, so the list stays complete as
+/// commands are added. Values of variables that are set are validated with the parser of their
+/// flag, and secret values are redacted.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ConfigEnv {
+    /// Only list the variables that are set.
+    #[clap(long)]
+    pub set_only: bool,
+}
+
+/// An environment variable read by the CLI.
+#[derive(Clone, Debug, Serialize)]
+struct EnvVar {
+    name: String,
+    description: String,
+    /// Commands reading the variable. Empty if it is read by all commands.
+    commands: Vec<String>,
+    /// Value of the variable, redacted if secret. `None` if unset.
+    value: Option<String>,
+    /// Error from parsing the value, if invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl ConfigEnv {
+    /// Run the [ConfigEnv] command.
+    pub fn run(&self, global_config: &GlobalConfig, command: &Command) -> anyhow::Result<()> {
+        let vars: Vec<_> = collect_vars(command, |name| std::env::var(name).ok())
+            .into_values()
+            .filter(|var| !self.set_only || var.value.is_some())
+            .collect();
+        let invalid = vars.iter().filter(|var| var.error.is_some()).count();
+
+        global_config.output_format().print(&vars, || {
+            vars.iter().map(|var| var.to_string()).collect::<Vec<_>>().join("\n")
+        })?;
+        if invalid > 0 {
+            bail!("{invalid} environment variable(s) are invalid");
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for EnvVar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.value, &self.error) {
+            (Some(value), None) => write!(f, "✅ {}={value}", self.name)?,
+            (Some(value), Some(error)) => write!(f, "❌ {}={value}: {error}", self.name)?,
+            (None, _) => write!(f, "   {} (not set)", self.name)?,
+        }
+        if self.commands.is_empty() {
+            write!(f, "\n     {}", self.description)
+        } else {
+            write!(f, "\n     {} (used by: {})", self.description, self.commands.join(", "))
+        }
+    }
+}
+
+/// Collect the environment variables read by `command` and its subcommands, looking up their
+/// values with `lookup`.
+fn collect_vars(
+    command: &Command,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> BTreeMap<String, EnvVar> {
+    let mut vars = BTreeMap::new();
+    visit(command, &mut Vec::new(), &lookup, &mut vars);
+    for (name, commands, description) in DIRECT_VARS {
+        let value = lookup(name);
+        vars.entry(name.to_string()).or_insert_with(|| EnvVar {
+            name: name.to_string(),
+            description: description.to_string(),
+            commands: commands.split(',').filter(|c| !c.is_empty()).map(String::from).collect(),
+            value: value.map(|value| redact(name, false, value)),
+            error: None,
+        });
+    }
+    vars
+}
+
+fn visit(
+    command: &Command,
+    path: &mut Vec<String>,
+    lookup: &impl Fn(&str) -> Option<String>,
+    vars: &mut BTreeMap<String, EnvVar>,
+) {
+    for arg in command.get_arguments() {
+        let Some(name) = arg.get_env().map(|name| name.to_string_lossy().into_owned()) else {
+            continue;
+        };
+        let value = lookup(&name);
+        let error = value.as_ref().and_then(|value| validate(command, arg, value));
+        let var = vars.entry(name.clone()).or_insert_with(|| EnvVar {
+            name: name.clone(),
+            description: arg.get_help().map(|help| help.to_string()).unwrap_or_default(),
+            commands: Vec::new(),
+            value: value.map(|value| redact(&name, arg.is_hide_env_values_set(), value)),
+            error: None,
+        });
+        // Arguments of the top-level command are global, read by all commands.
+        if !path.is_empty() {
+            var.commands.push(path.join(" "));
+        }
+        var.error = var.error.take().or(error);
+    }
+    for subcommand in command.get_subcommands().filter(|subcommand| !subcommand.is_hide_set()) {
+        path.push(subcommand.get_name().to_string());
+        visit(subcommand, path, lookup, vars);
+        path.pop();
+    }
+}
+
+/// Parse `value` as clap would for `arg`, returning the error message if it is invalid.
+fn validate(command: &Command, arg: &Arg, value: &str) -> Option<String> {
+    let err = arg.get_value_parser().parse_ref(command, Some(arg), OsStr::new(value)).err()?;
+    let message = err.to_string();
+    let first_line = message.lines().next().unwrap_or_default();
+    Some(first_line.trim_start_matches("error: ").to_string())
+}
+
+fn redact(name: &str, secret: bool, value: String) -> String {
+    if secret || SECRET_MARKERS.iter().any(|marker| name.contains(marker)) {
+        "<redacted>".to_string()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use clap::{value_parser, CommandFactory, Parser, Subcommand};
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct TestArgs {
+        #[clap(long, env = "TEST_RPC_URL", global = true)]
+        rpc_url: Option<url::Url>,
+        #[clap(long, env = "TEST_PRIVATE_KEY", hide_env_values = true, global = true)]
+        private_key: Option<String>,
+        #[command(subcommand)]
+        command: TestCommands,
+    }
+
+    #[derive(Subcommand)]
+    enum TestCommands {
+        Submit {
+            /// Seconds to wait
+            #[clap(long, env = "TEST_TIMEOUT", value_parser = value_parser!(u64))]
+            timeout: Option<u64>,
+        },
+    }
+
+    #[test]
+    fn collects_and_validates_vars() {
+        let env = HashMap::from([
+            ("TEST_RPC_URL", "http://localhost:8545"),
+            ("TEST_PRIVATE_KEY", "0xdeadbeef"),
+            ("TEST_TIMEOUT", "soon"),
+        ]);
+        let vars =
+            collect_vars(&TestArgs::command(), |name| env.get(name).map(|value| value.to_string()));
+
+        let rpc_url = &vars["TEST_RPC_URL"];
+        assert!(rpc_url.commands.is_empty());
+        assert_eq!(rpc_url.value.as_deref(), Some("http://localhost:8545"));
+        assert!(rpc_url.error.is_none());
+
+        assert_eq!(vars["TEST_PRIVATE_KEY"].value.as_deref(), Some("<redacted>"));
+
+        let timeout = &vars["TEST_TIMEOUT"];
+        assert_eq!(timeout.commands, vec!["submit".to_string()]);
+        assert_eq!(timeout.description, "Seconds to wait");
+        assert!(timeout.error.is_some());
+
+        assert!(vars["POSTGRES_USER"].value.is_none());
+        assert_eq!(vars["POSTGRES_PASSWORD"].commands, vec!["proving benchmark".to_string()]);
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commands of the Boundless CLI for inspecting its configuration.
+
+mod env;
+
+pub use env::ConfigEnv;
+
+use clap::Subcommand;
+
+use crate::config::GlobalConfig;
+
+/// Commands for inspecting the configuration of the CLI.
+#[derive(Subcommand, Clone, Debug)]
+pub enum ConfigCommands {
+    /// List the environment variables read by the CLI and validate those that are set.
+    Env(ConfigEnv),
+}
+
+impl ConfigCommands {
+    /// Run the command.
+    ///
+    /// `command` is the definition of the CLI, from which the environment variables are collected.
+    pub fn run(&self, global_config: &GlobalConfig, command: &clap::Command) -> anyhow::Result<()> {
+        match self {
+            Self::Env(cmd) => cmd.run(global_config, command),
+        }
+    }
+}
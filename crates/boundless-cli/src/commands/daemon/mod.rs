@@ -0,0 +1,539 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Long-running daemon running background workers in one supervised process.
+
+mod workers;
+
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use alloy::primitives::{utils::parse_ether, Address, U256};
+use anyhow::{ensure, Context};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use clap::Args;
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::task::JoinError;
+use url::Url;
+
+use crate::{commands::ops::now, config::GlobalConfig};
+use workers::{DaemonContext, Worker};
+
+/// Delay before a worker that panicked is restarted.
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Maximum delay between polls of a worker that keeps failing.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// Command to run background workers, such as the deposit and lock monitors, in one process.
+///
+/// The workers to run are selected by the sections present in the TOML config file, and share a
+/// single client and provider. A failing poll is retried with backoff, and a worker that panics
+/// is restarted, so one worker does not take down the others.
+///
+/// ```toml
+/// [health]
+/// listen = "127.0.0.1:9090"
+///
+/// [deposit_monitor]
+/// min_balance = "0.1"
+/// top_up_to = "0.5"
+///
+/// [lock_monitor]
+/// ramp_up_percent = 80
+/// webhook_url = "https://hooks.example.com/boundless"
+///
+/// [fulfillment_watcher]
+///
+/// [slash_scan]
+/// dry_run = true
+/// ```
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct Daemon {
+    /// Path to the TOML config file selecting and configuring the workers.
+    #[clap(long, env = "BOUNDLESS_DAEMON_CONFIG")]
+    pub config: PathBuf,
+}
+
+/// Configuration of the daemon, read from a TOML file.
+///
+/// Each worker runs if and only if its section is present.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct DaemonConfig {
+    /// Serve health and metrics endpoints.
+    pub health: Option<HealthConfig>,
+    /// Keep the market balance of the wallet above a threshold.
+    pub deposit_monitor: Option<DepositMonitorConfig>,
+    /// Alert when requests are not locked or fulfilled in time.
+    pub lock_monitor: Option<LockMonitorConfig>,
+    /// Report the fulfillments of requests.
+    pub fulfillment_watcher: Option<FulfillmentWatcherConfig>,
+    /// Slash provers that did not fulfill the requests they locked.
+    pub slash_scan: Option<SlashScanConfig>,
+}
+
+/// Configuration of the health and metrics endpoints.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct HealthConfig {
+    /// Address to serve `/health` and `/metrics` on.
+    pub listen: SocketAddr,
+}
+
+/// Configuration of the deposit monitor.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct DepositMonitorConfig {
+    /// Interval in seconds between checks.
+    #[serde(default = "default_deposit_interval")]
+    pub interval: u64,
+    /// Market balance, in ether, below which a deposit is made.
+    #[serde(deserialize_with = "deserialize_ether")]
+    pub min_balance: U256,
+    /// Market balance, in ether, to top up to when below the minimum.
+    #[serde(deserialize_with = "deserialize_ether")]
+    pub top_up_to: U256,
+}
+
+/// Configuration of the lock monitor, as in `boundless ops lock-monitor`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct LockMonitorConfig {
+    /// Interval in seconds between checks.
+    #[serde(default = "default_event_interval")]
+    pub interval: u64,
+    /// Address of the requestor to monitor. Defaults to the address of the wallet.
+    pub address: Option<Address>,
+    /// Percentage of the ramp-up period after which an unlocked request raises an alert.
+    #[serde(default = "default_ramp_up_percent")]
+    pub ramp_up_percent: u8,
+    /// Number of blocks before the current block from which to discover submitted requests.
+    #[serde(default = "default_lookback_blocks")]
+    pub lookback_blocks: u64,
+    /// Order stream ID from which to discover requests submitted offchain.
+    pub order_stream_offset: Option<u64>,
+    /// URL to which each alert is sent as a JSON POST request.
+    pub webhook_url: Option<Url>,
+}
+
+/// Configuration of the fulfillment watcher.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct FulfillmentWatcherConfig {
+    /// Interval in seconds between checks.
+    #[serde(default = "default_event_interval")]
+    pub interval: u64,
+    /// Address of the requestor whose requests to watch. Defaults to the address of the wallet.
+    pub address: Option<Address>,
+    /// Number of blocks before the current block from which to report fulfillments.
+    #[serde(default)]
+    pub lookback_blocks: u64,
+    /// URL to which each fulfillment is sent as a JSON POST request.
+    pub webhook_url: Option<Url>,
+}
+
+/// Configuration of the slash scan.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct SlashScanConfig {
+    /// Interval in seconds between checks.
+    #[serde(default = "default_slash_interval")]
+    pub interval: u64,
+    /// Number of blocks before the current block from which to discover locked requests.
+    #[serde(default = "default_lookback_blocks")]
+    pub lookback_blocks: u64,
+    /// Log the requests that would be slashed, without sending transactions.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_deposit_interval() -> u64 {
+    60
+}
+
+fn default_event_interval() -> u64 {
+    12
+}
+
+fn default_slash_interval() -> u64 {
+    60
+}
+
+fn default_ramp_up_percent() -> u8 {
+    80
+}
+
+fn default_lookback_blocks() -> u64 {
+    1000
+}
+
+fn deserialize_ether<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_ether(&value).map_err(serde::de::Error::custom)
+}
+
+impl DaemonConfig {
+    /// Parse and validate the config from the contents of a TOML file.
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let config: Self = toml::from_str(contents)?;
+        ensure!(!config.workers().is_empty(), "no workers are configured");
+        if let Some(deposit) = &config.deposit_monitor {
+            ensure!(
+                deposit.top_up_to > deposit.min_balance,
+                "deposit_monitor.top_up_to must be greater than min_balance"
+            );
+        }
+        if let Some(lock) = &config.lock_monitor {
+            ensure!(
+                lock.ramp_up_percent <= 100,
+                "lock_monitor.ramp_up_percent must be at most 100"
+            );
+        }
+        ensure!(
+            config.workers().iter().all(|(_, interval)| *interval > 0),
+            "worker intervals must be positive"
+        );
+        Ok(config)
+    }
+
+    /// Names and poll intervals of the configured workers.
+    fn workers(&self) -> Vec<(&'static str, u64)> {
+        [
+            self.deposit_monitor.as_ref().map(|c| ("deposit_monitor", c.interval)),
+            self.lock_monitor.as_ref().map(|c| ("lock_monitor", c.interval)),
+            self.fulfillment_watcher.as_ref().map(|c| ("fulfillment_watcher", c.interval)),
+            self.slash_scan.as_ref().map(|c| ("slash_scan", c.interval)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Counters of a worker, reported by the health and metrics endpoints.
+#[derive(Debug)]
+pub(crate) struct WorkerStatus {
+    name: &'static str,
+    interval: u64,
+    started_at: u64,
+    polls: AtomicU64,
+    errors: AtomicU64,
+    events: AtomicU64,
+    restarts: AtomicU64,
+    last_success: AtomicU64,
+}
+
+/// Health of a worker, as served by `/health`.
+#[derive(Serialize)]
+struct WorkerHealth {
+    name: &'static str,
+    healthy: bool,
+    polls: u64,
+    errors: u64,
+    events: u64,
+    restarts: u64,
+    last_success: Option<u64>,
+}
+
+impl WorkerStatus {
+    fn new(name: &'static str, interval: u64, started_at: u64) -> Self {
+        Self {
+            name,
+            interval,
+            started_at,
+            polls: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            events: AtomicU64::new(0),
+            restarts: AtomicU64::new(0),
+            last_success: AtomicU64::new(0),
+        }
+    }
+
+    /// Record the outcome of a poll at time `now`.
+    fn record_poll(&self, success: bool, now: u64) {
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        if success {
+            self.last_success.store(now, Ordering::Relaxed);
+        } else {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record events handled by the worker, such as alerts raised or deposits made.
+    pub(crate) fn record_events(&self, count: u64) {
+        self.events.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Whether the worker succeeded recently at time `now`.
+    ///
+    /// A worker is unhealthy once three intervals, and at least a minute, passed since its last
+    /// successful poll, or since startup if it never succeeded.
+    fn health(&self, now: u64) -> WorkerHealth {
+        let last_success = self.last_success.load(Ordering::Relaxed);
+        let stale_after = (3 * self.interval).max(60);
+        WorkerHealth {
+            name: self.name,
+            healthy: now.saturating_sub(last_success.max(self.started_at)) <= stale_after,
+            polls: self.polls.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            events: self.events.load(Ordering::Relaxed),
+            restarts: self.restarts.load(Ordering::Relaxed),
+            last_success: (last_success > 0).then_some(last_success),
+        }
+    }
+}
+
+/// Render the counters of the workers in the Prometheus text format.
+fn render_metrics(statuses: &[Arc<WorkerStatus>], now: u64) -> String {
+    let metrics: [(&str, &str, &str, fn(&WorkerHealth) -> u64); 5] = [
+        ("polls_total", "counter", "Number of polls", |h| h.polls),
+        ("errors_total", "counter", "Number of failed polls", |h| h.errors),
+        ("events_total", "counter", "Number of alerts, deposits, fulfillments, or slashes", |h| {
+            h.events
+        }),
+        ("restarts_total", "counter", "Number of restarts after a panic", |h| h.restarts),
+        ("last_success_timestamp_seconds", "gauge", "Time of the last successful poll", |h| {
+            h.last_success.unwrap_or(0)
+        }),
+    ];
+    let healths: Vec<_> = statuses.iter().map(|status| status.health(now)).collect();
+    let mut out = String::new();
+    for (name, kind, help, value) in metrics {
+        out.push_str(&format!("# HELP boundless_daemon_{name} {help}\n"));
+        out.push_str(&format!("# TYPE boundless_daemon_{name} {kind}\n"));
+        for health in &healths {
+            out.push_str(&format!(
+                "boundless_daemon_{name}{{worker=\"{}\"}} {}\n",
+                health.name,
+                value(health)
+            ));
+        }
+    }
+    out
+}
+
+impl Daemon {
+    /// Run the [Daemon] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let contents = std::fs::read_to_string(&self.config)
+            .with_context(|| format!("Failed to read daemon config {}", self.config.display()))?;
+        let config = DaemonConfig::parse(&contents)
+            .with_context(|| format!("Invalid daemon config {}", self.config.display()))?;
+        let ctx = Arc::new(DaemonContext::new(global_config).await?);
+
+        let started_at = now();
+        let statuses: Vec<_> = config
+            .workers()
+            .into_iter()
+            .map(|(name, interval)| Arc::new(WorkerStatus::new(name, interval, started_at)))
+            .collect();
+        let mut tasks = tokio::task::JoinSet::new();
+        for status in &statuses {
+            // Check the configuration of each worker before starting any of them.
+            Worker::check(status.name, &config, &ctx)?;
+            tasks.spawn(supervise(status.clone(), config.clone(), ctx.clone()));
+        }
+        tracing::info!(
+            "Running workers: {}",
+            statuses.iter().map(|status| status.name).collect::<Vec<_>>().join(", ")
+        );
+
+        match &config.health {
+            Some(health) => {
+                let listener =
+                    tokio::net::TcpListener::bind(health.listen).await.with_context(|| {
+                        format!("Failed to bind health endpoint to {}", health.listen)
+                    })?;
+                tracing::info!("Serving health and metrics on {}", health.listen);
+                let app = Router::new()
+                    .route("/health", get(health_handler))
+                    .route("/metrics", get(metrics_handler))
+                    .with_state(statuses);
+                tokio::select! {
+                    result = axum::serve(listener, app) => result.context("health endpoint failed"),
+                    result = tasks.join_next() => Err(supervisor_stopped(result)),
+                }
+            }
+            None => Err(supervisor_stopped(tasks.join_next().await)),
+        }
+    }
+}
+
+/// Error for a supervisor task that stopped, which only happens if it panicked or was cancelled.
+fn supervisor_stopped(result: Option<Result<Infallible, JoinError>>) -> anyhow::Error {
+    match result {
+        Some(Ok(never)) => match never {},
+        Some(Err(err)) => anyhow::anyhow!("[B-CLI-DAEMON] worker supervisor stopped: {err}"),
+        None => anyhow::anyhow!("[B-CLI-DAEMON] no workers are running"),
+    }
+}
+
+/// Run the named worker, restarting it if it panics.
+async fn supervise(
+    status: Arc<WorkerStatus>,
+    config: DaemonConfig,
+    ctx: Arc<DaemonContext>,
+) -> Infallible {
+    loop {
+        let handle = tokio::spawn(run_worker(status.clone(), config.clone(), ctx.clone()));
+        match handle.await {
+            Ok(never) => match never {},
+            Err(err) => {
+                status.restarts.fetch_add(1, Ordering::Relaxed);
+                tracing::error!(
+                    "[B-CLI-DAEMON] Worker {} panicked, restarting in {:?}: {err}",
+                    status.name,
+                    RESTART_DELAY
+                );
+                tokio::time::sleep(RESTART_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Poll the named worker at its interval, backing off while it fails.
+async fn run_worker(
+    status: Arc<WorkerStatus>,
+    config: DaemonConfig,
+    ctx: Arc<DaemonContext>,
+) -> Infallible {
+    let interval = Duration::from_secs(status.interval);
+    let mut worker = None;
+    let mut failures = 0u32;
+    loop {
+        // The worker is created by the first poll, so that a failure to set it up is retried.
+        let result = if let Some(worker) = worker.as_mut() {
+            Worker::poll(worker, &ctx, &status).await
+        } else {
+            match Worker::new(status.name, &config, &ctx).await {
+                Ok(new) => {
+                    worker = Some(new);
+                    Ok(())
+                }
+                Err(err) => Err(err),
+            }
+        };
+        status.record_poll(result.is_ok(), now());
+        let delay = match result {
+            Ok(()) => {
+                failures = 0;
+                interval
+            }
+            Err(err) => {
+                failures = failures.saturating_add(1);
+                let delay =
+                    interval.saturating_mul(2u32.saturating_pow(failures.min(16))).min(MAX_BACKOFF);
+                tracing::warn!(
+                    "Worker {} failed {failures} time(s) in a row, retrying in {:?}: {err:?}",
+                    status.name,
+                    delay
+                );
+                delay
+            }
+        };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+async fn health_handler(State(statuses): State<Vec<Arc<WorkerStatus>>>) -> impl IntoResponse {
+    let now = now();
+    let healths: Vec<_> = statuses.iter().map(|status| status.health(now)).collect();
+    let code = if healths.iter().all(|health| health.healthy) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(healths))
+}
+
+async fn metrics_handler(State(statuses): State<Vec<Arc<WorkerStatus>>>) -> String {
+    render_metrics(&statuses, now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = DaemonConfig::parse(
+            r#"
+            [health]
+            listen = "127.0.0.1:9090"
+
+            [deposit_monitor]
+            min_balance = "0.1"
+            top_up_to = "0.5"
+
+            [slash_scan]
+            dry_run = true
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.workers(), vec![("deposit_monitor", 60), ("slash_scan", 60)]);
+        let deposit = config.deposit_monitor.unwrap();
+        assert_eq!(deposit.min_balance, parse_ether("0.1").unwrap());
+        assert_eq!(deposit.top_up_to, parse_ether("0.5").unwrap());
+        assert!(config.slash_scan.unwrap().dry_run);
+
+        assert!(DaemonConfig::parse("").is_err(), "no workers");
+        assert!(DaemonConfig::parse("[fulfillment_watcher]\ninterval = 0").is_err());
+        assert!(DaemonConfig::parse("[lock_monitor]\nunknown = 1").is_err());
+        assert!(DaemonConfig::parse("[deposit_monitor]\nmin_balance = \"1\"\ntop_up_to = \"0.5\"")
+            .is_err());
+    }
+
+    #[test]
+    fn worker_health() {
+        let status = WorkerStatus::new("lock_monitor", 12, 1000);
+        assert!(status.health(1060).healthy, "grace period after startup");
+        assert!(!status.health(1061).healthy);
+
+        status.record_poll(true, 1100);
+        status.record_poll(false, 1112);
+        let health = status.health(1160);
+        assert!(health.healthy);
+        assert_eq!((health.polls, health.errors, health.last_success), (2, 1, Some(1100)));
+        assert!(!status.health(1161).healthy);
+    }
+
+    #[test]
+    fn metrics_format() {
+        let status = Arc::new(WorkerStatus::new("slash_scan", 60, 0));
+        status.record_poll(true, 42);
+        status.record_events(3);
+        let metrics = render_metrics(&[status], 50);
+        assert!(metrics.contains("# TYPE boundless_daemon_polls_total counter\n"));
+        assert!(metrics.contains("boundless_daemon_events_total{worker=\"slash_scan\"} 3\n"));
+        assert!(metrics.contains(
+            "boundless_daemon_last_success_timestamp_seconds{worker=\"slash_scan\"} 42\n"
+        ));
+    }
+}
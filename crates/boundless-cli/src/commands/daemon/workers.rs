@@ -0,0 +1,398 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Workers run by the daemon.
+
+use std::collections::HashMap;
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{utils::format_ether, Address, B256, U256},
+    providers::{DynProvider, Provider},
+    rpc::types::Log,
+};
+use anyhow::{bail, Context};
+use boundless_market::{
+    contracts::{
+        boundless_market::{BoundlessMarketService, MarketError},
+        IBoundlessMarket::{RequestFulfilled, RequestLocked},
+        RequestId,
+    },
+    order_stream_client::OrderStreamClient,
+};
+use serde::Serialize;
+use url::Url;
+
+use super::{DaemonConfig, WorkerStatus};
+use crate::{
+    commands::ops::{query_events, send_webhook, LockMonitor},
+    config::GlobalConfig,
+};
+
+/// Client, provider, and wallet shared by the workers.
+pub(super) struct DaemonContext {
+    provider: DynProvider,
+    market: BoundlessMarketService<DynProvider>,
+    order_stream: Option<OrderStreamClient>,
+    /// Address of the wallet, if one is configured.
+    signer: Option<Address>,
+    http: reqwest::Client,
+}
+
+impl DaemonContext {
+    pub(super) async fn new(global_config: &GlobalConfig) -> anyhow::Result<Self> {
+        let http = reqwest::Client::new();
+        if global_config.private_key.is_some() {
+            let client = global_config.build_client_with_signer().await?;
+            Ok(Self {
+                provider: client.provider(),
+                signer: Some(client.caller()),
+                market: client.boundless_market,
+                order_stream: client.offchain_client,
+                http,
+            })
+        } else {
+            let client = global_config.build_client().await?;
+            Ok(Self {
+                provider: client.provider(),
+                signer: None,
+                market: client.boundless_market,
+                order_stream: client.offchain_client,
+                http,
+            })
+        }
+    }
+
+    fn market_address(&self) -> Address {
+        *self.market.instance().address()
+    }
+
+    fn require_signer(&self, worker: &str) -> anyhow::Result<Address> {
+        self.signer.with_context(|| {
+            format!("{worker} sends transactions; please set --private-key or --wallet")
+        })
+    }
+
+    fn address_or_signer(&self, address: Option<Address>, worker: &str) -> anyhow::Result<Address> {
+        address.or(self.signer).with_context(|| {
+            format!("{worker} needs an address; please set it or --private-key or --wallet")
+        })
+    }
+}
+
+/// A worker of the daemon, with its state between polls.
+pub(super) enum Worker {
+    Deposit(DepositMonitor),
+    Lock(LockWorker),
+    Fulfillment(FulfillmentWatcher),
+    SlashScan(SlashScan),
+}
+
+impl Worker {
+    /// Check that the named worker can run with the given context, without starting it.
+    pub(super) fn check(
+        name: &str,
+        config: &DaemonConfig,
+        ctx: &DaemonContext,
+    ) -> anyhow::Result<()> {
+        match name {
+            "deposit_monitor" | "slash_scan" => ctx.require_signer(name).map(|_| ()),
+            "lock_monitor" => {
+                let address = config.lock_monitor.as_ref().and_then(|c| c.address);
+                ctx.address_or_signer(address, name).map(|_| ())
+            }
+            "fulfillment_watcher" => {
+                let address = config.fulfillment_watcher.as_ref().and_then(|c| c.address);
+                ctx.address_or_signer(address, name).map(|_| ())
+            }
+            _ => bail!("unknown worker {name}"),
+        }
+    }
+
+    /// Create the named worker from its configuration.
+    pub(super) async fn new(
+        name: &str,
+        config: &DaemonConfig,
+        ctx: &DaemonContext,
+    ) -> anyhow::Result<Self> {
+        let start_block = |lookback: u64| async move {
+            anyhow::Ok(ctx.provider.get_block_number().await?.saturating_sub(lookback))
+        };
+        Ok(match name {
+            "deposit_monitor" => {
+                let config = config.deposit_monitor.as_ref().context("missing config")?;
+                Self::Deposit(DepositMonitor {
+                    address: ctx.require_signer(name)?,
+                    min_balance: config.min_balance,
+                    top_up_to: config.top_up_to,
+                })
+            }
+            "lock_monitor" => {
+                let config = config.lock_monitor.as_ref().context("missing config")?;
+                let monitor = LockMonitor::new(
+                    &ctx.provider,
+                    ctx.address_or_signer(config.address, name)?,
+                    config.ramp_up_percent,
+                    config.lookback_blocks,
+                    config.order_stream_offset,
+                )
+                .await?;
+                Self::Lock(LockWorker { monitor, webhook_url: config.webhook_url.clone() })
+            }
+            "fulfillment_watcher" => {
+                let config = config.fulfillment_watcher.as_ref().context("missing config")?;
+                Self::Fulfillment(FulfillmentWatcher {
+                    address: ctx.address_or_signer(config.address, name)?,
+                    next_block: start_block(config.lookback_blocks).await?,
+                    webhook_url: config.webhook_url.clone(),
+                })
+            }
+            "slash_scan" => {
+                let config = config.slash_scan.as_ref().context("missing config")?;
+                ctx.require_signer(name)?;
+                Self::SlashScan(SlashScan {
+                    next_block: start_block(config.lookback_blocks).await?,
+                    dry_run: config.dry_run,
+                    tracked: HashMap::new(),
+                })
+            }
+            _ => bail!("unknown worker {name}"),
+        })
+    }
+
+    /// Run one poll of the worker.
+    pub(super) async fn poll(
+        &mut self,
+        ctx: &DaemonContext,
+        status: &WorkerStatus,
+    ) -> anyhow::Result<()> {
+        match self {
+            Self::Deposit(worker) => worker.poll(ctx, status).await,
+            Self::Lock(worker) => worker.poll(ctx, status).await,
+            Self::Fulfillment(worker) => worker.poll(ctx, status).await,
+            Self::SlashScan(worker) => worker.poll(ctx, status).await,
+        }
+    }
+}
+
+/// Tops up the market balance of the wallet when it falls below a threshold.
+pub(super) struct DepositMonitor {
+    address: Address,
+    min_balance: U256,
+    top_up_to: U256,
+}
+
+impl DepositMonitor {
+    async fn poll(&mut self, ctx: &DaemonContext, status: &WorkerStatus) -> anyhow::Result<()> {
+        let balance = ctx.market.balance_of(self.address).await?;
+        if balance >= self.min_balance {
+            tracing::debug!("Market balance of {} is {} ETH", self.address, format_ether(balance));
+            return Ok(());
+        }
+        let amount = self.top_up_to - balance;
+        let wallet_balance = ctx.provider.get_balance(self.address).await?;
+        if wallet_balance < amount {
+            status.record_events(1);
+            bail!(
+                "[B-CLI-DAEMON] market balance of {} is {} ETH, below the minimum of {} ETH, but the wallet only holds {} ETH",
+                self.address,
+                format_ether(balance),
+                format_ether(self.min_balance),
+                format_ether(wallet_balance)
+            );
+        }
+        ctx.market.deposit(amount).await?;
+        status.record_events(1);
+        tracing::info!(
+            "Deposited {} ETH, topping up the market balance of {} to {} ETH",
+            format_ether(amount),
+            self.address,
+            format_ether(self.top_up_to)
+        );
+        Ok(())
+    }
+}
+
+/// Raises lock alerts, as `boundless ops lock-monitor` does.
+pub(super) struct LockWorker {
+    monitor: LockMonitor,
+    webhook_url: Option<Url>,
+}
+
+impl LockWorker {
+    async fn poll(&mut self, ctx: &DaemonContext, status: &WorkerStatus) -> anyhow::Result<()> {
+        let alerts = self
+            .monitor
+            .poll(&ctx.provider, ctx.market_address(), ctx.order_stream.as_ref())
+            .await?;
+        status.record_events(alerts.len() as u64);
+        for alert in &alerts {
+            tracing::warn!("[B-CLI-LOCK] {}", alert.message);
+            if let Some(url) = &self.webhook_url {
+                if let Err(err) = send_webhook(&ctx.http, url, alert).await {
+                    tracing::error!("Failed to send alert to webhook: {err:?}");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fulfillment of a watched request, as sent to the webhook.
+#[derive(Serialize)]
+struct FulfillmentNotice {
+    request_id: U256,
+    prover: Address,
+    tx_hash: Option<B256>,
+    block_number: Option<u64>,
+}
+
+/// Reports the fulfillments of the requests of an address.
+pub(super) struct FulfillmentWatcher {
+    address: Address,
+    next_block: u64,
+    webhook_url: Option<Url>,
+}
+
+impl FulfillmentWatcher {
+    async fn poll(&mut self, ctx: &DaemonContext, status: &WorkerStatus) -> anyhow::Result<()> {
+        let to = ctx.provider.get_block_number().await?;
+        if to < self.next_block {
+            return Ok(());
+        }
+        let fulfilled: Vec<(RequestFulfilled, Log)> =
+            query_events(&ctx.provider, ctx.market_address(), self.next_block, to).await?;
+        for (event, log) in fulfilled {
+            if RequestId::from_lossy(event.requestId).addr != self.address {
+                continue;
+            }
+            status.record_events(1);
+            tracing::info!(
+                "Request 0x{:x} fulfilled by {} in tx {:?}",
+                event.requestId,
+                event.prover,
+                log.transaction_hash
+            );
+            if let Some(url) = &self.webhook_url {
+                let notice = FulfillmentNotice {
+                    request_id: event.requestId,
+                    prover: event.prover,
+                    tx_hash: log.transaction_hash,
+                    block_number: log.block_number,
+                };
+                if let Err(err) = send_webhook(&ctx.http, url, &notice).await {
+                    tracing::error!("Failed to send fulfillment to webhook: {err:?}");
+                }
+            }
+        }
+        self.next_block = to + 1;
+        Ok(())
+    }
+}
+
+/// Deadlines of a locked request watched by the [SlashScan].
+struct LockedRequest {
+    lock_expires_at: u64,
+    expires_at: u64,
+}
+
+/// Slashes provers that locked a request and did not fulfill it before the lock expired.
+pub(super) struct SlashScan {
+    next_block: u64,
+    dry_run: bool,
+    tracked: HashMap<U256, LockedRequest>,
+}
+
+impl SlashScan {
+    async fn poll(&mut self, ctx: &DaemonContext, status: &WorkerStatus) -> anyhow::Result<()> {
+        // Deadlines are compared with the time of the latest block, as the market contract does.
+        let latest = ctx
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await?
+            .context("latest block not found")?;
+        let (to, now) = (latest.header.number, latest.header.timestamp);
+        if to >= self.next_block {
+            let locked: Vec<(RequestLocked, Log)> =
+                query_events(&ctx.provider, ctx.market_address(), self.next_block, to).await?;
+            for (event, _) in locked {
+                self.tracked.insert(
+                    event.requestId,
+                    LockedRequest {
+                        lock_expires_at: event.request.lock_expires_at(),
+                        expires_at: event.request.expires_at(),
+                    },
+                );
+            }
+            let fulfilled: Vec<(RequestFulfilled, Log)> =
+                query_events(&ctx.provider, ctx.market_address(), self.next_block, to).await?;
+            for (event, log) in fulfilled {
+                let Some(request) = self.tracked.get(&event.requestId) else {
+                    continue;
+                };
+                // Requests fulfilled after the lock expired can still be slashed.
+                if block_timestamp(&ctx.provider, &log).await? <= request.lock_expires_at {
+                    self.tracked.remove(&event.requestId);
+                }
+            }
+            self.next_block = to + 1;
+        }
+
+        let expired: Vec<U256> = self
+            .tracked
+            .iter()
+            .filter(|(_, request)| now > request.expires_at)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        for request_id in expired {
+            if self.dry_run {
+                tracing::info!("Request 0x{request_id:x} can be slashed (dry run)");
+                status.record_events(1);
+                self.tracked.remove(&request_id);
+                continue;
+            }
+            // Requests that fail to be slashed stay tracked, and are retried on the next poll.
+            match ctx.market.slash(request_id).await {
+                Ok(slashed) => {
+                    status.record_events(1);
+                    tracing::info!(
+                        "Slashed request 0x{request_id:x}: {} collateral burned, {} transferred",
+                        slashed.collateralBurned,
+                        slashed.collateralTransferred
+                    );
+                    self.tracked.remove(&request_id);
+                }
+                Err(MarketError::RequestIsSlashed(_)) => {
+                    tracing::debug!("Request 0x{request_id:x} is already slashed");
+                    self.tracked.remove(&request_id);
+                }
+                Err(err) => tracing::warn!("Failed to slash request 0x{request_id:x}: {err:?}"),
+            }
+        }
+        tracing::debug!("Watching {} locked requests", self.tracked.len());
+        Ok(())
+    }
+}
+
+async fn block_timestamp(provider: &DynProvider, log: &Log) -> anyhow::Result<u64> {
+    if let Some(timestamp) = log.block_timestamp {
+        return Ok(timestamp);
+    }
+    let number = log.block_number.context("log is missing its block number")?;
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Number(number))
+        .await?
+        .with_context(|| format!("block {number} not found"))?;
+    Ok(block.header.timestamp)
+}
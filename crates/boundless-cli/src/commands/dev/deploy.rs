@@ -0,0 +1,96 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::{
+    network::EthereumWallet,
+    primitives::{Address, B256},
+    providers::ProviderBuilder,
+};
+use boundless_market::deployments::{bootstrap, BootstrapParams, VerifierKind};
+use clap::Args;
+
+use crate::config::GlobalConfig;
+
+/// Command to deploy the Boundless Market and its supporting contracts to a chain.
+///
+/// The wallet given by the private key becomes the owner of the deployed contracts. The resulting
+/// addresses are printed as environment variables that configure the deployment for other commands.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct DevDeploy {
+    /// Image ID of the set builder guest.
+    #[clap(long)]
+    pub set_builder_image_id: B256,
+    /// URL the set builder guest can be downloaded from.
+    #[clap(long)]
+    pub set_builder_url: String,
+    /// Image ID of the assessor guest.
+    #[clap(long)]
+    pub assessor_image_id: B256,
+    /// URL the assessor guest can be downloaded from.
+    #[clap(long)]
+    pub assessor_url: String,
+    /// Verifier to register with the verifier router for Groth16 receipts.
+    #[clap(long, value_enum, default_value = "groth16")]
+    pub verifier: VerifierKind,
+    /// Address of an existing ERC-20 to use as the collateral token.
+    ///
+    /// If not set, a HitPoints token owned by the deployer is deployed.
+    #[clap(long)]
+    pub collateral_token: Option<Address>,
+    /// URL of the order stream service to include in the printed deployment.
+    #[clap(long)]
+    pub order_stream_url: Option<String>,
+}
+
+impl DevDeploy {
+    /// Run the [DevDeploy] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let signer = global_config.require_private_key()?;
+        let owner = signer.address();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_http(global_config.require_rpc_url()?);
+
+        tracing::info!("Deploying Boundless Market contracts owned by {owner}");
+        let params = BootstrapParams::new(
+            owner,
+            self.set_builder_image_id.0,
+            self.set_builder_url.clone(),
+            self.assessor_image_id.0,
+            self.assessor_url.clone(),
+        )
+        .with_verifier(self.verifier)
+        .with_collateral_token(self.collateral_token)
+        .with_order_stream_url(self.order_stream_url.clone());
+        let deployment = bootstrap(provider, params).await?;
+
+        println!("✅ Deployed Boundless Market");
+        if let Some(chain_id) = deployment.chain_id {
+            println!("CHAIN_ID={chain_id}");
+        }
+        println!("BOUNDLESS_MARKET_ADDRESS={}", deployment.boundless_market_address);
+        if let Some(address) = deployment.verifier_router_address {
+            println!("VERIFIER_ADDRESS={address}");
+        }
+        println!("SET_VERIFIER_ADDRESS={}", deployment.set_verifier_address);
+        if let Some(address) = deployment.collateral_token_address {
+            println!("COLLATERAL_TOKEN_ADDRESS={address}");
+        }
+        if let Some(url) = &deployment.order_stream_url {
+            println!("ORDER_STREAM_URL={url}");
+        }
+        Ok(())
+    }
+}
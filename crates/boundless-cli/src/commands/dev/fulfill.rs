@@ -0,0 +1,46 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use alloy::primitives::{B256, U256};
+use clap::Args;
+
+use crate::{config::GlobalConfig, dev::fake_fulfill};
+
+/// Command to fulfill a request with a fake receipt, without generating a proof.
+///
+/// Requires RISC0_DEV_MODE to be enabled and a deployment that accepts fake receipts, such as a
+/// local devnet.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct DevFulfill {
+    /// The proof request identifier.
+    pub request_id: U256,
+    /// The request digest.
+    #[clap(long)]
+    pub request_digest: Option<B256>,
+    /// The tx hash of the request submission.
+    #[clap(long)]
+    pub tx_hash: Option<B256>,
+}
+
+impl DevFulfill {
+    /// Run the [DevFulfill] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client_with_signer().await?;
+        tracing::info!("Fulfilling request 0x{:x} with a fake receipt", self.request_id);
+
+        fake_fulfill(&client, self.request_id, self.request_digest, self.tx_hash).await?;
+        tracing::info!("Successfully fulfilled request 0x{:x}", self.request_id);
+        Ok(())
+    }
+}
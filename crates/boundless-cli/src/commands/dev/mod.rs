@@ -0,0 +1,43 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Commands of the Boundless CLI for testing applications against a dev-mode deployment.
+
+mod deploy;
+mod fulfill;
+
+pub use deploy::DevDeploy;
+pub use fulfill::DevFulfill;
+
+use clap::Subcommand;
+
+use crate::config::GlobalConfig;
+
+/// Commands for testing applications against a dev-mode deployment.
+#[derive(Subcommand, Clone, Debug)]
+pub enum DevCommands {
+    /// Deploy the Boundless Market and its supporting contracts to a chain.
+    Deploy(DevDeploy),
+    /// Fulfill a request with a fake receipt, without generating a proof.
+    Fulfill(DevFulfill),
+}
+
+impl DevCommands {
+    /// Run the command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        match self {
+            Self::Deploy(cmd) => cmd.run(global_config).await,
+            Self::Fulfill(cmd) => cmd.run(global_config).await,
+        }
+    }
+}
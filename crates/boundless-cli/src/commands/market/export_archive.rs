@@ -0,0 +1,63 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use anyhow::{ensure, Context};
+use clap::Args;
+
+use crate::config::GlobalConfig;
+
+/// Command to download the offchain orders archived by the order stream.
+///
+/// The order stream archives orders once their request has expired or was fulfilled. Each line of
+/// the output file is a JSON object with the order, its submission and archival times, and the
+/// reason it was archived.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct MarketExportArchive {
+    /// Only download orders submitted at or after this UNIX timestamp.
+    #[clap(long)]
+    pub from: Option<u64>,
+    /// Only download orders submitted before this UNIX timestamp.
+    #[clap(long)]
+    pub to: Option<u64>,
+    /// File to write the archived orders to, as JSON lines.
+    #[clap(long, default_value = "order-archive.jsonl")]
+    pub output: PathBuf,
+}
+
+impl MarketExportArchive {
+    /// Run the [MarketExportArchive] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            ensure!(from < to, "--from must be before --to");
+        }
+        let client = global_config.build_client().await?;
+        let order_stream = client
+            .offchain_client
+            .as_ref()
+            .context("order stream URL is not configured for this deployment")?;
+
+        let file = File::create(&self.output)
+            .with_context(|| format!("failed to create {}", self.output.display()))?;
+        let orders = order_stream
+            .download_archive(self.from, self.to, BufWriter::new(file))
+            .await
+            .context("failed to download the order archive")?;
+
+        println!("✅ Downloaded {orders} archived orders to {}", self.output.display());
+        Ok(())
+    }
+}
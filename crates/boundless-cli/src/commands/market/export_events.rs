@@ -0,0 +1,470 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Seek, SeekFrom, Write},
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use anyhow::{bail, ensure, Context};
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use boundless_market::{contracts::IBoundlessMarket, log_query::ChunkedLogQuery};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::GlobalConfig;
+
+/// Name of the file in the output directory recording the progress of an export.
+const CHECKPOINT_FILE: &str = "export-checkpoint.json";
+
+/// Kind of market event that can be exported.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+    /// A request was submitted onchain.
+    RequestSubmitted,
+    /// A request was locked by a prover.
+    RequestLocked,
+    /// A request was fulfilled.
+    RequestFulfilled,
+    /// A proof was delivered for a request.
+    ProofDelivered,
+    /// A prover was slashed for failing to fulfill a locked request.
+    ProverSlashed,
+}
+
+impl EventKind {
+    /// All kinds of events, in the order they are exported.
+    pub const ALL: [EventKind; 5] = [
+        EventKind::RequestSubmitted,
+        EventKind::RequestLocked,
+        EventKind::RequestFulfilled,
+        EventKind::ProofDelivered,
+        EventKind::ProverSlashed,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            EventKind::RequestSubmitted => "request-submitted",
+            EventKind::RequestLocked => "request-locked",
+            EventKind::RequestFulfilled => "request-fulfilled",
+            EventKind::ProofDelivered => "proof-delivered",
+            EventKind::ProverSlashed => "prover-slashed",
+        }
+    }
+}
+
+/// File format of an export.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportFormat {
+    /// Comma-separated values, with event specific fields in a JSON encoded `data` column.
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Command to export decoded market events to files.
+///
+/// Events of each kind are written to `<kind>.<format>` in the output directory. Progress is
+/// recorded after each chunk of blocks, so an interrupted export over a large range can be
+/// continued with `--resume`.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct MarketExportEvents {
+    /// First block to export events from.
+    #[clap(long)]
+    pub from: u64,
+    /// Last block to export events from. Defaults to the latest block.
+    #[clap(long)]
+    pub to: Option<u64>,
+    /// Comma-separated kinds of events to export. Defaults to all kinds.
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub kinds: Vec<EventKind>,
+    /// Format of the exported files.
+    #[clap(long, value_enum, default_value = "jsonl")]
+    pub format: ExportFormat,
+    /// Directory to write the exported files to.
+    #[clap(long, default_value = ".")]
+    pub output_dir: PathBuf,
+    /// Number of blocks to query at once.
+    #[clap(long, default_value = "1000")]
+    pub chunk_size: u64,
+    /// Continue an interrupted export in the output directory.
+    #[clap(long)]
+    pub resume: bool,
+}
+
+/// Progress of an export, saved in the output directory.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ExportCheckpoint {
+    from: u64,
+    to: u64,
+    format: ExportFormat,
+    kinds: BTreeMap<EventKind, KindProgress>,
+}
+
+/// Progress of the export of a single kind of event.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct KindProgress {
+    /// Last block whose events have been written.
+    last_block: Option<u64>,
+    /// Length of the output file after the events up to `last_block` were written.
+    ///
+    /// On resume, the file is truncated to this length to drop any partially written chunk.
+    bytes: u64,
+}
+
+impl ExportCheckpoint {
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read checkpoint {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse checkpoint {}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let data = serde_json::to_vec_pretty(self)?;
+        // Use AtomicFile so that an interrupted write does not corrupt the checkpoint.
+        AtomicFile::new(path, OverwriteBehavior::AllowOverwrite)
+            .write(|f| f.write_all(&data))
+            .with_context(|| format!("failed to write checkpoint {}", path.display()))
+    }
+}
+
+/// A decoded event, as written to the export files.
+#[derive(Debug, Serialize)]
+struct EventRow<D> {
+    kind: EventKind,
+    block_number: Option<u64>,
+    block_timestamp: Option<u64>,
+    tx_hash: Option<B256>,
+    log_index: Option<u64>,
+    request_id: U256,
+    prover: Option<Address>,
+    data: D,
+}
+
+enum RowWriter {
+    Csv(csv::Writer<File>),
+    Jsonl(BufWriter<File>),
+}
+
+impl RowWriter {
+    /// Open the output file, truncating it to the given length and appending after it.
+    fn open(path: &Path, format: ExportFormat, len: u64) -> anyhow::Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        file.set_len(len)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(match format {
+            ExportFormat::Csv => {
+                Self::Csv(csv::WriterBuilder::new().has_headers(len == 0).from_writer(file))
+            }
+            ExportFormat::Jsonl => Self::Jsonl(BufWriter::new(file)),
+        })
+    }
+
+    fn write(&mut self, row: EventRow<serde_json::Value>) -> anyhow::Result<()> {
+        match self {
+            Self::Csv(writer) => {
+                let EventRow {
+                    kind,
+                    block_number,
+                    block_timestamp,
+                    tx_hash,
+                    log_index,
+                    request_id,
+                    prover,
+                    data,
+                } = row;
+                writer.serialize(EventRow {
+                    kind,
+                    block_number,
+                    block_timestamp,
+                    tx_hash,
+                    log_index,
+                    request_id,
+                    prover,
+                    data: data.to_string(),
+                })?;
+            }
+            Self::Jsonl(writer) => {
+                serde_json::to_writer(&mut *writer, &row)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flush buffered rows and return the length of the output file.
+    fn flush(&mut self) -> anyhow::Result<u64> {
+        let file = match self {
+            Self::Csv(writer) => {
+                writer.flush()?;
+                writer.get_ref()
+            }
+            Self::Jsonl(writer) => {
+                writer.flush()?;
+                writer.get_ref()
+            }
+        };
+        Ok(file.metadata()?.len())
+    }
+}
+
+impl MarketExportEvents {
+    /// Run the [MarketExportEvents] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let provider = client.provider();
+        let market = client.boundless_market.instance();
+
+        std::fs::create_dir_all(&self.output_dir).with_context(|| {
+            format!("failed to create output directory {}", self.output_dir.display())
+        })?;
+        let checkpoint_path = self.output_dir.join(CHECKPOINT_FILE);
+        let mut checkpoint = if self.resume {
+            let checkpoint = ExportCheckpoint::load(&checkpoint_path)?;
+            ensure!(
+                checkpoint.from == self.from
+                    && self.to.is_none_or(|to| to == checkpoint.to)
+                    && checkpoint.format == self.format,
+                "the export in {} covers blocks {}..={} as {:?}; the arguments must match to resume",
+                self.output_dir.display(),
+                checkpoint.from,
+                checkpoint.to,
+                checkpoint.format
+            );
+            checkpoint
+        } else {
+            if checkpoint_path.exists() {
+                bail!(
+                    "{} already contains an export; pass --resume to continue it",
+                    self.output_dir.display()
+                );
+            }
+            let to = match self.to {
+                Some(to) => to,
+                None => provider.get_block_number().await.context("failed to get block number")?,
+            };
+            ExportCheckpoint { from: self.from, to, format: self.format, kinds: BTreeMap::new() }
+        };
+        ensure!(checkpoint.from <= checkpoint.to, "--from must not be after --to");
+
+        let kinds =
+            if self.kinds.is_empty() { EventKind::ALL.to_vec() } else { self.kinds.clone() };
+        for kind in kinds {
+            let filter = match kind {
+                EventKind::RequestSubmitted => market.RequestSubmitted_filter().filter,
+                EventKind::RequestLocked => market.RequestLocked_filter().filter,
+                EventKind::RequestFulfilled => market.RequestFulfilled_filter().filter,
+                EventKind::ProofDelivered => market.ProofDelivered_filter().filter,
+                EventKind::ProverSlashed => market.ProverSlashed_filter().filter,
+            };
+            match kind {
+                EventKind::RequestSubmitted => {
+                    self.export::<IBoundlessMarket::RequestSubmitted, _>(
+                        &provider,
+                        filter,
+                        kind,
+                        &mut checkpoint,
+                        &checkpoint_path,
+                        |event| {
+                            let data = json!({
+                                "request": event.request,
+                                "client_signature": event.clientSignature,
+                            });
+                            (event.requestId, None, data)
+                        },
+                    )
+                    .await?
+                }
+                EventKind::RequestLocked => {
+                    self.export::<IBoundlessMarket::RequestLocked, _>(
+                        &provider,
+                        filter,
+                        kind,
+                        &mut checkpoint,
+                        &checkpoint_path,
+                        |event| {
+                            let data = json!({
+                                "request": event.request,
+                                "client_signature": event.clientSignature,
+                            });
+                            (event.requestId, Some(event.prover), data)
+                        },
+                    )
+                    .await?
+                }
+                EventKind::RequestFulfilled => {
+                    self.export::<IBoundlessMarket::RequestFulfilled, _>(
+                        &provider,
+                        filter,
+                        kind,
+                        &mut checkpoint,
+                        &checkpoint_path,
+                        |event| {
+                            let data = json!({ "request_digest": event.requestDigest });
+                            (event.requestId, Some(event.prover), data)
+                        },
+                    )
+                    .await?
+                }
+                EventKind::ProofDelivered => {
+                    self.export::<IBoundlessMarket::ProofDelivered, _>(
+                        &provider,
+                        filter,
+                        kind,
+                        &mut checkpoint,
+                        &checkpoint_path,
+                        |event| {
+                            let data = json!({ "fulfillment": event.fulfillment });
+                            (event.requestId, Some(event.prover), data)
+                        },
+                    )
+                    .await?
+                }
+                EventKind::ProverSlashed => {
+                    self.export::<IBoundlessMarket::ProverSlashed, _>(
+                        &provider,
+                        filter,
+                        kind,
+                        &mut checkpoint,
+                        &checkpoint_path,
+                        |event| {
+                            let data = json!({
+                                "collateral_burned": event.collateralBurned,
+                                "collateral_transferred": event.collateralTransferred,
+                                "collateral_recipient": event.collateralRecipient,
+                            });
+                            (event.requestId, None, data)
+                        },
+                    )
+                    .await?
+                }
+            }
+        }
+
+        println!(
+            "✅ Exported events from blocks {}..={} to {}",
+            checkpoint.from,
+            checkpoint.to,
+            self.output_dir.display()
+        );
+        Ok(())
+    }
+
+    /// Export all events of one kind, continuing from its recorded progress.
+    async fn export<E, P>(
+        &self,
+        provider: &P,
+        filter: Filter,
+        kind: EventKind,
+        checkpoint: &mut ExportCheckpoint,
+        checkpoint_path: &Path,
+        decode: impl Fn(E) -> (U256, Option<Address>, serde_json::Value),
+    ) -> anyhow::Result<()>
+    where
+        E: SolEvent,
+        P: Provider + Clone,
+    {
+        let progress = checkpoint.kinds.get(&kind).copied().unwrap_or_default();
+        let start = progress.last_block.map_or(checkpoint.from, |block| block + 1);
+        if start > checkpoint.to {
+            tracing::info!("Export of {} events is already complete", kind.name());
+            return Ok(());
+        }
+
+        let path =
+            self.output_dir.join(format!("{}.{}", kind.name(), checkpoint.format.extension()));
+        let mut writer = RowWriter::open(&path, checkpoint.format, progress.bytes)?;
+        let mut exported = 0;
+
+        let result = ChunkedLogQuery::new(self.chunk_size)
+            .scan::<E, _, anyhow::Error>(
+                provider.clone(),
+                filter,
+                start..=checkpoint.to,
+                |logs: Vec<(E, Log)>, chunk| {
+                    let result = (|| {
+                        for (event, log) in logs {
+                            let (request_id, prover, data) = decode(event);
+                            writer.write(EventRow {
+                                kind,
+                                block_number: log.block_number,
+                                block_timestamp: log.block_timestamp,
+                                tx_hash: log.transaction_hash,
+                                log_index: log.log_index,
+                                request_id,
+                                prover,
+                                data,
+                            })?;
+                        }
+                        let bytes = writer.flush()?;
+                        checkpoint.kinds.insert(
+                            kind,
+                            KindProgress { last_block: Some(*chunk.chunk.end()), bytes },
+                        );
+                        checkpoint.save(checkpoint_path)
+                    })();
+                    if let Err(err) = result {
+                        return ControlFlow::Break(err);
+                    }
+                    exported += chunk.logs;
+                    tracing::info!(
+                        "Exported {} {} events up to block {} ({:.1}%)",
+                        exported,
+                        kind.name(),
+                        chunk.chunk.end(),
+                        chunk.fraction() * 100.0
+                    );
+                    ControlFlow::Continue(())
+                },
+            )
+            .await
+            .with_context(|| format!("failed to query {} events", kind.name()))?;
+        if let Some(err) = result {
+            return Err(err.context(format!("failed to export {} events", kind.name())));
+        }
+
+        tracing::info!("Exported {} {} events to {}", exported, kind.name(), path.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commands of the Boundless CLI for inspecting the market.
+
+mod export_archive;
+mod export_events;
+mod top;
+
+pub use export_archive::MarketExportArchive;
+pub use export_events::{EventKind, ExportFormat, MarketExportEvents};
+pub use top::{
+    MarketTopProvers, MarketTopRequestors, ProverRanking, RankingArgs, RequestorRanking,
+};
+
+use clap::Subcommand;
+
+use crate::config::GlobalConfig;
+
+/// Commands for inspecting the market.
+#[derive(Subcommand, Clone, Debug)]
+pub enum MarketCommands {
+    /// Export decoded market events to files for offline analysis.
+    ExportEvents(MarketExportEvents),
+    /// Download the offchain orders archived by the order stream.
+    ExportArchive(MarketExportArchive),
+    /// Rank provers by fulfillments, locks, or slash rate over a block range.
+    TopProvers(MarketTopProvers),
+    /// Rank requestors by request volume or spend over a block range.
+    TopRequestors(MarketTopRequestors),
+}
+
+impl MarketCommands {
+    /// Run the command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        match self {
+            Self::ExportEvents(cmd) => cmd.run(global_config).await,
+            Self::ExportArchive(cmd) => cmd.run(global_config).await,
+            Self::TopProvers(cmd) => cmd.run(global_config).await,
+            Self::TopRequestors(cmd) => cmd.run(global_config).await,
+        }
+    }
+}
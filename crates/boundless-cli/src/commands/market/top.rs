@@ -0,0 +1,552 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::Write,
+};
+
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use anyhow::{ensure, Context};
+use boundless_market::{
+    contracts::{IBoundlessMarket, Offer, RequestId},
+    log_query::ChunkedLogQuery,
+};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+
+use crate::{config::GlobalConfig, format::OutputFormat};
+
+/// Block range and output options shared by the ranking commands.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RankingArgs {
+    /// First block to include in the ranking.
+    #[clap(long)]
+    pub from: u64,
+    /// Last block to include in the ranking. Defaults to the latest block.
+    #[clap(long)]
+    pub to: Option<u64>,
+    /// Number of entries to print.
+    #[clap(long, default_value = "10")]
+    pub limit: usize,
+    /// Number of blocks to query at once.
+    #[clap(long, default_value = "1000")]
+    pub chunk_size: u64,
+    /// Print the ranking as CSV instead of a table.
+    #[clap(long)]
+    pub csv: bool,
+}
+
+/// Statistic to rank provers by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProverRanking {
+    /// Number of requests fulfilled.
+    Fulfillments,
+    /// Number of requests locked.
+    Locks,
+    /// Fraction of the locks in the range that were slashed.
+    SlashRate,
+}
+
+/// Statistic to rank requestors by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RequestorRanking {
+    /// Number of requests seen onchain, either submitted or locked.
+    Requests,
+    /// Amount paid to provers.
+    Spend,
+}
+
+/// Command to rank provers by their activity over a block range.
+///
+/// Slashes are attributed to the prover that locked the request, so slashes of requests locked
+/// before the start of the range are not counted.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct MarketTopProvers {
+    /// Block range and output options.
+    #[clap(flatten)]
+    pub ranking: RankingArgs,
+    /// Statistic to rank provers by.
+    #[clap(long, value_enum, default_value = "fulfillments")]
+    pub by: ProverRanking,
+}
+
+/// Command to rank requestors by their activity over a block range.
+///
+/// Spend is the price of each request when it was locked. For requests fulfilled without a lock,
+/// it is the price when it was fulfilled, which is only known for requests submitted onchain
+/// within the range.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct MarketTopRequestors {
+    /// Block range and output options.
+    #[clap(flatten)]
+    pub ranking: RankingArgs,
+    /// Statistic to rank requestors by.
+    #[clap(long, value_enum, default_value = "requests")]
+    pub by: RequestorRanking,
+}
+
+/// Activity of a prover over the ranked block range.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+struct ProverStats {
+    prover: Address,
+    locks: u64,
+    fulfillments: u64,
+    slashes: u64,
+    slash_rate: f64,
+}
+
+/// Activity of a requestor over the ranked block range.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+struct RequestorStats {
+    requestor: Address,
+    requests: u64,
+    locked: u64,
+    fulfilled: u64,
+    spend_wei: String,
+    spend: String,
+}
+
+/// Accumulates prover statistics from market events.
+#[derive(Clone, Debug, Default)]
+struct ProverTally {
+    stats: BTreeMap<Address, ProverStats>,
+    lockers: HashMap<U256, Address>,
+}
+
+impl ProverTally {
+    fn entry(&mut self, prover: Address) -> &mut ProverStats {
+        self.stats.entry(prover).or_insert_with(|| ProverStats { prover, ..Default::default() })
+    }
+
+    fn lock(&mut self, request_id: U256, prover: Address) {
+        self.lockers.insert(request_id, prover);
+        self.entry(prover).locks += 1;
+    }
+
+    fn fulfill(&mut self, prover: Address) {
+        self.entry(prover).fulfillments += 1;
+    }
+
+    /// Record a slash, returning false if the request was not locked within the range.
+    fn slash(&mut self, request_id: U256) -> bool {
+        let Some(prover) = self.lockers.get(&request_id).copied() else {
+            return false;
+        };
+        self.entry(prover).slashes += 1;
+        true
+    }
+
+    fn ranked(self, by: ProverRanking, limit: usize) -> Vec<ProverStats> {
+        let mut stats: Vec<_> = self
+            .stats
+            .into_values()
+            .map(|mut stats| {
+                if stats.locks > 0 {
+                    stats.slash_rate = stats.slashes as f64 / stats.locks as f64;
+                }
+                stats
+            })
+            .collect();
+        // The sort is stable, so ties stay ordered by address.
+        match by {
+            ProverRanking::Fulfillments => {
+                stats.sort_by(|a, b| b.fulfillments.cmp(&a.fulfillments))
+            }
+            ProverRanking::Locks => stats.sort_by(|a, b| b.locks.cmp(&a.locks)),
+            ProverRanking::SlashRate => stats.sort_by(|a, b| b.slash_rate.total_cmp(&a.slash_rate)),
+        }
+        stats.truncate(limit);
+        stats
+    }
+}
+
+/// Activity of a single requestor, before formatting.
+#[derive(Clone, Debug, Default)]
+struct RequestorCounts {
+    requests: u64,
+    locked: u64,
+    fulfilled: u64,
+    spend: U256,
+}
+
+/// Accumulates requestor statistics from market events.
+#[derive(Clone, Debug, Default)]
+struct RequestorTally {
+    counts: BTreeMap<Address, RequestorCounts>,
+    seen: HashSet<U256>,
+    offers: HashMap<U256, Offer>,
+    locked: HashSet<U256>,
+}
+
+impl RequestorTally {
+    fn entry(&mut self, request_id: U256) -> &mut RequestorCounts {
+        let requestor = RequestId::from_lossy(request_id).addr;
+        let counts = self.counts.entry(requestor).or_default();
+        if self.seen.insert(request_id) {
+            counts.requests += 1;
+        }
+        counts
+    }
+
+    fn submit(&mut self, request_id: U256, offer: Offer) {
+        self.entry(request_id);
+        self.offers.insert(request_id, offer);
+    }
+
+    fn lock(&mut self, request_id: U256, price: U256) {
+        self.locked.insert(request_id);
+        let counts = self.entry(request_id);
+        counts.locked += 1;
+        counts.spend += price;
+    }
+
+    fn fulfill(&mut self, request_id: U256, timestamp: u64) -> anyhow::Result<()> {
+        let price = match self.offers.get(&request_id) {
+            Some(offer) if !self.locked.contains(&request_id) => Some(offer.price_at(timestamp)?),
+            _ => None,
+        };
+        let counts = self.entry(request_id);
+        counts.fulfilled += 1;
+        if let Some(price) = price {
+            counts.spend += price;
+        }
+        Ok(())
+    }
+
+    fn ranked(
+        self,
+        by: RequestorRanking,
+        limit: usize,
+        output: &OutputFormat,
+    ) -> Vec<RequestorStats> {
+        let mut counts: Vec<_> = self.counts.into_iter().collect();
+        match by {
+            RequestorRanking::Requests => counts.sort_by(|a, b| b.1.requests.cmp(&a.1.requests)),
+            RequestorRanking::Spend => counts.sort_by(|a, b| b.1.spend.cmp(&a.1.spend)),
+        }
+        counts
+            .into_iter()
+            .take(limit)
+            .map(|(requestor, counts)| RequestorStats {
+                requestor,
+                requests: counts.requests,
+                locked: counts.locked,
+                fulfilled: counts.fulfilled,
+                spend_wei: counts.spend.to_string(),
+                spend: output.amount(counts.spend).formatted,
+            })
+            .collect()
+    }
+}
+
+impl RankingArgs {
+    /// Resolve the block range to scan.
+    async fn range(&self, provider: &impl Provider) -> anyhow::Result<(u64, u64)> {
+        let to = match self.to {
+            Some(to) => to,
+            None => provider.get_block_number().await.context("failed to get block number")?,
+        };
+        ensure!(self.from <= to, "--from must not be after --to");
+        Ok((self.from, to))
+    }
+
+    /// Query all events of type `E` in the range.
+    async fn query<E, P>(
+        &self,
+        provider: &P,
+        filter: Filter,
+        (from, to): (u64, u64),
+    ) -> anyhow::Result<Vec<(E, Log)>>
+    where
+        E: SolEvent,
+        P: Provider + Clone,
+    {
+        ChunkedLogQuery::new(self.chunk_size)
+            .query::<E, _>(provider.clone(), filter, from..=to, |progress| {
+                tracing::info!(
+                    "Scanned {} events up to block {} ({:.1}%)",
+                    E::SIGNATURE,
+                    progress.chunk.end(),
+                    progress.fraction() * 100.0
+                );
+            })
+            .await
+            .with_context(|| format!("failed to query {} events", E::SIGNATURE))
+    }
+
+    /// Print the ranking as CSV, JSON, or a table.
+    fn print<T: Serialize>(
+        &self,
+        rows: &[T],
+        output: &OutputFormat,
+        table: impl FnOnce() -> String,
+    ) -> anyhow::Result<()> {
+        if self.csv {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            for row in rows {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+            return Ok(());
+        }
+        output.print(&rows, table)
+    }
+}
+
+/// Timestamp of the block containing `log`, fetched from the provider if the log does not
+/// include it.
+async fn block_timestamp(
+    provider: &impl Provider,
+    log: &Log,
+    cache: &mut HashMap<u64, u64>,
+) -> anyhow::Result<u64> {
+    if let Some(timestamp) = log.block_timestamp {
+        return Ok(timestamp);
+    }
+    let number = log.block_number.context("log is missing its block number")?;
+    if let Some(timestamp) = cache.get(&number) {
+        return Ok(*timestamp);
+    }
+    let block = provider
+        .get_block_by_number(number.into())
+        .await?
+        .with_context(|| format!("block {number} not found"))?;
+    cache.insert(number, block.header.timestamp);
+    Ok(block.header.timestamp)
+}
+
+impl MarketTopProvers {
+    /// Run the [MarketTopProvers] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let provider = client.provider();
+        let market = client.boundless_market.instance();
+        let range = self.ranking.range(&provider).await?;
+
+        let mut tally = ProverTally::default();
+        let locks = self
+            .ranking
+            .query::<IBoundlessMarket::RequestLocked, _>(
+                &provider,
+                market.RequestLocked_filter().filter,
+                range,
+            )
+            .await?;
+        for (event, _) in locks {
+            tally.lock(event.requestId, event.prover);
+        }
+        let fulfillments = self
+            .ranking
+            .query::<IBoundlessMarket::RequestFulfilled, _>(
+                &provider,
+                market.RequestFulfilled_filter().filter,
+                range,
+            )
+            .await?;
+        for (event, _) in fulfillments {
+            tally.fulfill(event.prover);
+        }
+        let slashes = self
+            .ranking
+            .query::<IBoundlessMarket::ProverSlashed, _>(
+                &provider,
+                market.ProverSlashed_filter().filter,
+                range,
+            )
+            .await?;
+        let unattributed =
+            slashes.into_iter().filter(|(event, _)| !tally.slash(event.requestId)).count();
+        if unattributed > 0 {
+            tracing::warn!(
+                "{unattributed} slashed requests were locked before block {}; they are not counted",
+                range.0
+            );
+        }
+
+        let rows = tally.ranked(self.by, self.ranking.limit);
+        self.ranking.print(&rows, &global_config.output_format(), || {
+            let mut table = format!(
+                "Top provers in blocks {}..={}\n{:>4}  {:<42}  {:>8}  {:>12}  {:>8}  {:>10}",
+                range.0,
+                range.1,
+                "rank",
+                "prover",
+                "locks",
+                "fulfillments",
+                "slashes",
+                "slash rate"
+            );
+            for (rank, stats) in rows.iter().enumerate() {
+                table.push_str(&format!(
+                    "\n{:>4}  {:<42}  {:>8}  {:>12}  {:>8}  {:>9.1}%",
+                    rank + 1,
+                    stats.prover.to_string(),
+                    stats.locks,
+                    stats.fulfillments,
+                    stats.slashes,
+                    stats.slash_rate * 100.0
+                ));
+            }
+            table
+        })
+    }
+}
+
+impl MarketTopRequestors {
+    /// Run the [MarketTopRequestors] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let provider = client.provider();
+        let market = client.boundless_market.instance();
+        let output = global_config.output_format();
+        let range = self.ranking.range(&provider).await?;
+        let mut timestamps = HashMap::new();
+
+        let mut tally = RequestorTally::default();
+        let submitted = self
+            .ranking
+            .query::<IBoundlessMarket::RequestSubmitted, _>(
+                &provider,
+                market.RequestSubmitted_filter().filter,
+                range,
+            )
+            .await?;
+        for (event, _) in submitted {
+            tally.submit(event.requestId, event.request.offer);
+        }
+        let locks = self
+            .ranking
+            .query::<IBoundlessMarket::RequestLocked, _>(
+                &provider,
+                market.RequestLocked_filter().filter,
+                range,
+            )
+            .await?;
+        for (event, log) in locks {
+            let timestamp = block_timestamp(&provider, &log, &mut timestamps).await?;
+            let price = event.request.offer.price_at(timestamp)?;
+            tally.lock(event.requestId, price);
+        }
+        let fulfillments = self
+            .ranking
+            .query::<IBoundlessMarket::RequestFulfilled, _>(
+                &provider,
+                market.RequestFulfilled_filter().filter,
+                range,
+            )
+            .await?;
+        for (event, log) in fulfillments {
+            let timestamp = block_timestamp(&provider, &log, &mut timestamps).await?;
+            tally.fulfill(event.requestId, timestamp)?;
+        }
+
+        let rows = tally.ranked(self.by, self.ranking.limit, &output);
+        self.ranking.print(&rows, &output, || {
+            let mut table = format!(
+                "Top requestors in blocks {}..={}\n{:>4}  {:<42}  {:>8}  {:>8}  {:>9}  {:>24}",
+                range.0, range.1, "rank", "requestor", "requests", "locked", "fulfilled", "spend"
+            );
+            for (rank, stats) in rows.iter().enumerate() {
+                table.push_str(&format!(
+                    "\n{:>4}  {:<42}  {:>8}  {:>8}  {:>9}  {:>24}",
+                    rank + 1,
+                    stats.requestor.to_string(),
+                    stats.requests,
+                    stats.locked,
+                    stats.fulfilled,
+                    stats.spend
+                ));
+            }
+            table
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provers_ranked_by_statistic() {
+        let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let requestor = Address::repeat_byte(9);
+        let mut tally = ProverTally::default();
+        for index in 0..3 {
+            tally.lock(RequestId::u256(requestor, index), alice);
+        }
+        tally.lock(RequestId::u256(requestor, 3), bob);
+        tally.fulfill(alice);
+        tally.fulfill(bob);
+        tally.fulfill(bob);
+        assert!(tally.slash(RequestId::u256(requestor, 3)));
+        // Requests locked before the range are not attributed.
+        assert!(!tally.slash(RequestId::u256(requestor, 4)));
+
+        let by_locks = tally.clone().ranked(ProverRanking::Locks, 10);
+        assert_eq!(by_locks.iter().map(|s| s.prover).collect::<Vec<_>>(), [alice, bob]);
+
+        let by_fulfillments = tally.clone().ranked(ProverRanking::Fulfillments, 10);
+        assert_eq!(by_fulfillments[0].prover, bob);
+        assert_eq!(by_fulfillments[0].fulfillments, 2);
+
+        let by_slash_rate = tally.ranked(ProverRanking::SlashRate, 1);
+        assert_eq!(by_slash_rate.len(), 1);
+        assert_eq!(by_slash_rate[0].prover, bob);
+        assert_eq!(by_slash_rate[0].slash_rate, 1.0);
+    }
+
+    #[test]
+    fn requestor_spend_counts_locks_and_unlocked_fulfillments() {
+        let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let offer = Offer {
+            minPrice: U256::from(100),
+            maxPrice: U256::from(100),
+            rampUpStart: 10,
+            rampUpPeriod: 0,
+            lockTimeout: 100,
+            timeout: 200,
+            ..Default::default()
+        };
+        let mut tally = RequestorTally::default();
+        // Alice submits two requests onchain; one is locked, the other fulfilled without a lock.
+        tally.submit(RequestId::u256(alice, 0), offer.clone());
+        tally.submit(RequestId::u256(alice, 1), offer);
+        tally.lock(RequestId::u256(alice, 0), U256::from(100));
+        tally.fulfill(RequestId::u256(alice, 0), 50).unwrap();
+        tally.fulfill(RequestId::u256(alice, 1), 50).unwrap();
+        // Bob's offchain request is only seen when it is locked.
+        tally.lock(RequestId::u256(bob, 0), U256::from(500));
+
+        let output = OutputFormat::default();
+        let by_requests = tally.clone().ranked(RequestorRanking::Requests, 10, &output);
+        assert_eq!(by_requests[0].requestor, alice);
+        assert_eq!(by_requests[0].requests, 2);
+        assert_eq!(by_requests[0].locked, 1);
+        assert_eq!(by_requests[0].fulfilled, 2);
+        assert_eq!(by_requests[0].spend_wei, "200");
+
+        let by_spend = tally.ranked(RequestorRanking::Spend, 10, &output);
+        assert_eq!(by_spend[0].requestor, bob);
+        assert_eq!(by_spend[0].requests, 1);
+        assert_eq!(by_spend[0].spend_wei, "500");
+    }
+}
@@ -17,5 +17,19 @@
 // TODO(victor): Move the main command groups (e.g. prove, request, account) to modules under this
 // one.
 
+pub mod account;
+pub mod cache;
+pub mod config;
+pub mod daemon;
+#[cfg(feature = "dev")]
+pub mod dev;
+pub mod market;
+pub mod ops;
 pub mod povw;
+pub mod proving;
+pub mod request;
+#[cfg(feature = "self-test")]
+pub mod self_test;
+pub mod toolchain;
+pub mod wallet;
 pub mod zkc;
@@ -0,0 +1,337 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::{
+    primitives::{utils::parse_units, B256, U256},
+    providers::Provider,
+    rpc::types::Log,
+};
+use anyhow::Context;
+use boundless_market::contracts::IBoundlessMarket::{
+    Deposit, ProverSlashed, RequestFulfilled, RequestLocked, RequestSubmitted,
+};
+use clap::Args;
+use serde::Serialize;
+
+use super::query_events;
+use crate::{
+    config::GlobalConfig,
+    format::{EthUnit, FormattedAmount},
+};
+
+/// Command to report the gas used by each type of market transaction on the configured chain.
+///
+/// Transactions are found from the market events emitted over the last --lookback-blocks blocks,
+/// and their gas usage is read from their receipts. The cost of each type is priced at the
+/// current gas price, or at --gas-price, so that chains can be compared before committing funds.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct OpsGasReport {
+    /// Number of blocks before the current block to search for transactions.
+    #[clap(long, default_value = "10000")]
+    pub lookback_blocks: u64,
+    /// Maximum number of transactions of each type whose receipts are fetched.
+    #[clap(long, default_value = "20")]
+    pub samples: usize,
+    /// Gas price in gwei to price the transactions at, instead of the current gas price.
+    #[clap(long, value_parser = parse_gwei)]
+    pub gas_price: Option<U256>,
+}
+
+fn parse_gwei(arg: &str) -> anyhow::Result<U256> {
+    Ok(parse_units(arg, "gwei")?.into())
+}
+
+/// Type of a market transaction, as identified by the events it emitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum TxKind {
+    Deposit,
+    Lock,
+    /// A fulfillment of a batch of requests, bucketed by the upper bound of the batch size.
+    Fulfill(usize),
+    Slash,
+}
+
+/// Upper bounds of the batch sizes that fulfillments are bucketed by.
+const FILL_BUCKETS: [usize; 3] = [1, 5, 10];
+
+impl TxKind {
+    fn all() -> impl Iterator<Item = TxKind> {
+        [TxKind::Deposit, TxKind::Lock]
+            .into_iter()
+            .chain(FILL_BUCKETS.into_iter().map(TxKind::Fulfill))
+            .chain([TxKind::Fulfill(usize::MAX), TxKind::Slash])
+    }
+
+    fn fulfill(fills: usize) -> Self {
+        TxKind::Fulfill(
+            FILL_BUCKETS.into_iter().find(|&bucket| fills <= bucket).unwrap_or(usize::MAX),
+        )
+    }
+
+    fn label(self) -> String {
+        match self {
+            TxKind::Deposit => "deposit".into(),
+            TxKind::Lock => "lock".into(),
+            TxKind::Fulfill(1) => "fulfill (1 fill)".into(),
+            TxKind::Fulfill(usize::MAX) => {
+                format!("fulfill ({}+ fills)", FILL_BUCKETS[FILL_BUCKETS.len() - 1] + 1)
+            }
+            TxKind::Fulfill(bucket) => {
+                let lower =
+                    FILL_BUCKETS.into_iter().take_while(|&b| b < bucket).last().unwrap_or(0);
+                format!("fulfill ({}-{bucket} fills)", lower + 1)
+            }
+            TxKind::Slash => "slash".into(),
+        }
+    }
+}
+
+/// Market events emitted by a single transaction.
+#[derive(Clone, Debug, Default)]
+struct TxEvents {
+    block_number: u64,
+    deposit: bool,
+    submitted: bool,
+    locked: bool,
+    fills: usize,
+    slashed: bool,
+}
+
+impl TxEvents {
+    /// Type of the transaction, if it is one of the reported types.
+    ///
+    /// A transaction that emitted several kinds of events is classified by the most expensive
+    /// of them. Deposits made along with a request submission are not counted as deposits.
+    fn kind(&self) -> Option<TxKind> {
+        if self.fills > 0 {
+            Some(TxKind::fulfill(self.fills))
+        } else if self.slashed {
+            Some(TxKind::Slash)
+        } else if self.locked {
+            Some(TxKind::Lock)
+        } else if self.deposit && !self.submitted {
+            Some(TxKind::Deposit)
+        } else {
+            None
+        }
+    }
+}
+
+/// Gas usage of one type of transaction, as printed with `--json`.
+#[derive(Serialize)]
+struct GasReportRow {
+    kind: String,
+    samples: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    median_gas: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_gas: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_gas: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_gwei: Option<FormattedAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost: Option<FormattedAmount>,
+}
+
+impl GasReportRow {
+    fn new(kind: TxKind, mut gas: Vec<u64>, gas_price: U256) -> Self {
+        gas.sort_unstable();
+        let median_gas = (!gas.is_empty()).then(|| gas[gas.len() / 2]);
+        let cost = median_gas.map(|gas| U256::from(gas) * gas_price);
+        Self {
+            kind: kind.label(),
+            samples: gas.len(),
+            median_gas,
+            min_gas: gas.first().copied(),
+            max_gas: gas.last().copied(),
+            cost_gwei: cost.map(|cost| amount(EthUnit::Gwei, cost)),
+            cost: cost.map(|cost| amount(EthUnit::Eth, cost)),
+        }
+    }
+}
+
+/// Gas usage of all types of transaction, as printed with `--json`.
+#[derive(Serialize)]
+struct GasReport {
+    from_block: u64,
+    to_block: u64,
+    gas_price: FormattedAmount,
+    transactions: Vec<GasReportRow>,
+}
+
+/// Events of the transaction that emitted `log`.
+fn tx_events<'a>(
+    txs: &'a mut HashMap<B256, TxEvents>,
+    log: &Log,
+) -> anyhow::Result<&'a mut TxEvents> {
+    let hash = log.transaction_hash.context("log is missing its transaction hash")?;
+    let events = txs.entry(hash).or_default();
+    events.block_number = log.block_number.unwrap_or_default();
+    Ok(events)
+}
+
+fn amount(unit: EthUnit, wei: U256) -> FormattedAmount {
+    FormattedAmount { wei: wei.to_string(), formatted: unit.format(wei) }
+}
+
+impl OpsGasReport {
+    /// Run the [OpsGasReport] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let provider = client.provider();
+        let market_address = client.deployment.boundless_market_address;
+        let to = provider.get_block_number().await?;
+        let from = to.saturating_sub(self.lookback_blocks);
+        tracing::info!("Searching blocks {from} to {to} for market transactions");
+
+        let mut txs: HashMap<B256, TxEvents> = HashMap::new();
+        for (_, log) in query_events::<Deposit>(&provider, market_address, from, to).await? {
+            tx_events(&mut txs, &log)?.deposit = true;
+        }
+        for (_, log) in
+            query_events::<RequestSubmitted>(&provider, market_address, from, to).await?
+        {
+            tx_events(&mut txs, &log)?.submitted = true;
+        }
+        for (_, log) in query_events::<RequestLocked>(&provider, market_address, from, to).await? {
+            tx_events(&mut txs, &log)?.locked = true;
+        }
+        for (_, log) in
+            query_events::<RequestFulfilled>(&provider, market_address, from, to).await?
+        {
+            tx_events(&mut txs, &log)?.fills += 1;
+        }
+        for (_, log) in query_events::<ProverSlashed>(&provider, market_address, from, to).await? {
+            tx_events(&mut txs, &log)?.slashed = true;
+        }
+
+        // Sample the most recent transactions of each type.
+        let mut txs: Vec<_> = txs.into_iter().collect();
+        txs.sort_by_key(|(_, events)| std::cmp::Reverse(events.block_number));
+        let mut gas: BTreeMap<TxKind, Vec<u64>> = BTreeMap::new();
+        for (hash, events) in txs {
+            let Some(kind) = events.kind() else {
+                continue;
+            };
+            let samples = gas.entry(kind).or_default();
+            if samples.len() >= self.samples {
+                continue;
+            }
+            let receipt = provider
+                .get_transaction_receipt(hash)
+                .await?
+                .with_context(|| format!("receipt of transaction {hash} not found"))?;
+            samples.push(receipt.gas_used);
+        }
+
+        let gas_price = match self.gas_price {
+            Some(gas_price) => gas_price,
+            None => U256::from(provider.get_gas_price().await?),
+        };
+        let report = GasReport {
+            from_block: from,
+            to_block: to,
+            gas_price: amount(EthUnit::Gwei, gas_price),
+            transactions: TxKind::all()
+                .map(|kind| {
+                    GasReportRow::new(kind, gas.remove(&kind).unwrap_or_default(), gas_price)
+                })
+                .collect(),
+        };
+        global_config.output_format().print(&report, || report.to_string())
+    }
+}
+
+impl std::fmt::Display for GasReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Gas used by market transactions in blocks {} to {}, priced at {}:",
+            self.from_block, self.to_block, self.gas_price
+        )?;
+        write!(
+            f,
+            "{:<22}  {:>7}  {:>10}  {:>10}  {:>10}  {:>24}  {:>24}",
+            "type", "samples", "median gas", "min gas", "max gas", "cost (gwei)", "cost (ETH)"
+        )?;
+        let dash = || "-".to_string();
+        for row in &self.transactions {
+            write!(
+                f,
+                "\n{:<22}  {:>7}  {:>10}  {:>10}  {:>10}  {:>24}  {:>24}",
+                row.kind,
+                row.samples,
+                row.median_gas.map_or_else(dash, |gas| gas.to_string()),
+                row.min_gas.map_or_else(dash, |gas| gas.to_string()),
+                row.max_gas.map_or_else(dash, |gas| gas.to_string()),
+                row.cost_gwei.as_ref().map_or_else(dash, ToString::to_string),
+                row.cost.as_ref().map_or_else(dash, ToString::to_string),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_transactions() {
+        let events = |f: fn(&mut TxEvents)| {
+            let mut events = TxEvents::default();
+            f(&mut events);
+            events.kind()
+        };
+        assert_eq!(events(|e| e.deposit = true), Some(TxKind::Deposit));
+        assert_eq!(events(|e| (e.deposit, e.submitted) = (true, true)), None);
+        assert_eq!(events(|e| e.locked = true), Some(TxKind::Lock));
+        assert_eq!(events(|e| (e.locked, e.fills) = (true, 1)), Some(TxKind::Fulfill(1)));
+        assert_eq!(events(|e| e.fills = 4), Some(TxKind::Fulfill(5)));
+        assert_eq!(events(|e| e.fills = 12), Some(TxKind::Fulfill(usize::MAX)));
+        assert_eq!(events(|e| e.slashed = true), Some(TxKind::Slash));
+
+        let labels: Vec<_> = TxKind::all().map(TxKind::label).collect();
+        assert_eq!(
+            labels,
+            [
+                "deposit",
+                "lock",
+                "fulfill (1 fill)",
+                "fulfill (2-5 fills)",
+                "fulfill (6-10 fills)",
+                "fulfill (11+ fills)",
+                "slash"
+            ]
+        );
+    }
+
+    #[test]
+    fn gas_report_row() {
+        let gas_price = U256::from(2_000_000_000u64);
+        let row = GasReportRow::new(TxKind::Lock, vec![300, 100, 200], gas_price);
+        assert_eq!(
+            (row.samples, row.median_gas, row.min_gas, row.max_gas),
+            (3, Some(200), Some(100), Some(300))
+        );
+        assert_eq!(row.cost_gwei.unwrap().wei, "400000000000");
+
+        let row = GasReportRow::new(TxKind::Slash, vec![], gas_price);
+        assert_eq!((row.samples, row.median_gas), (0, None));
+        assert!(row.cost.is_none());
+    }
+}
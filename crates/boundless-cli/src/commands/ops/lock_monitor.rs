@@ -0,0 +1,358 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    primitives::{Address, U256},
+    providers::{DynProvider, Provider},
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use anyhow::{bail, Context};
+use boundless_market::{
+    contracts::{
+        IBoundlessMarket::{RequestFulfilled, RequestLocked, RequestSubmitted},
+        ProofRequest,
+    },
+    log_query::query_events_chunked,
+    order_stream_client::OrderStreamClient,
+};
+use clap::Args;
+use serde::Serialize;
+use url::Url;
+
+use crate::config::GlobalConfig;
+
+/// Number of blocks queried at once when scanning for request events.
+const EVENT_CHUNK_SIZE: u64 = 1000;
+
+/// Command to watch the open requests of a requestor and alert when they are at risk.
+///
+/// An alert is raised when a request passes a given fraction of its ramp-up period without being
+/// locked, and when a locked request passes its lock deadline without being fulfilled. Requests
+/// submitted onchain are discovered from market events. Requests submitted offchain are discovered
+/// from the order stream, starting at --order-stream-offset.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct OpsLockMonitor {
+    /// Address of the requestor to monitor. Defaults to the address of the configured private key.
+    #[clap(long)]
+    pub address: Option<Address>,
+    /// Percentage of the ramp-up period after which an unlocked request raises an alert.
+    #[clap(long, default_value = "80", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub ramp_up_percent: u8,
+    /// Number of blocks before the current block from which to discover submitted requests.
+    #[clap(long, default_value = "1000")]
+    pub lookback_blocks: u64,
+    /// Order stream ID from which to discover requests submitted offchain.
+    ///
+    /// If not set, only requests submitted onchain are monitored until they are locked.
+    #[clap(long)]
+    pub order_stream_offset: Option<u64>,
+    /// Interval in seconds between checks.
+    #[clap(long, default_value = "12")]
+    pub interval: u64,
+    /// URL to which each alert is sent as a JSON POST request.
+    #[clap(long, env = "LOCK_MONITOR_WEBHOOK_URL")]
+    pub webhook_url: Option<Url>,
+    /// Exit with a non-zero status on the first alert.
+    #[clap(long)]
+    pub exit_on_alert: bool,
+}
+
+/// Kind of condition that raised a [LockAlert].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockAlertKind {
+    /// The request passed the configured fraction of its ramp-up period without being locked.
+    RampUpUnlocked,
+    /// The lock on the request expired before the request was fulfilled.
+    LockExpired,
+}
+
+/// Alert raised by the [OpsLockMonitor] for a single request.
+#[derive(Clone, Debug, Serialize)]
+pub struct LockAlert {
+    /// Kind of condition that raised the alert.
+    pub kind: LockAlertKind,
+    /// ID of the request.
+    pub request_id: U256,
+    /// Human readable description of the alert.
+    pub message: String,
+}
+
+/// State of a monitored request.
+#[derive(Clone, Debug)]
+struct Tracked {
+    request: ProofRequest,
+    locked: bool,
+    ramp_up_alerted: bool,
+    lock_expiry_alerted: bool,
+}
+
+impl Tracked {
+    fn new(request: ProofRequest) -> Self {
+        Self { request, locked: false, ramp_up_alerted: false, lock_expiry_alerted: false }
+    }
+
+    /// Alert raised by this request at time `now`, if any.
+    fn check(&mut self, now: u64, ramp_up_percent: u8) -> Option<LockAlert> {
+        let offer = &self.request.offer;
+        let request_id = self.request.id;
+        if !self.locked && !self.ramp_up_alerted {
+            let threshold =
+                offer.rampUpStart + offer.rampUpPeriod as u64 * ramp_up_percent as u64 / 100;
+            if now >= threshold {
+                self.ramp_up_alerted = true;
+                return Some(LockAlert {
+                    kind: LockAlertKind::RampUpUnlocked,
+                    request_id,
+                    message: format!(
+                        "request 0x{request_id:x} is not locked after {ramp_up_percent}% of its ramp-up period"
+                    ),
+                });
+            }
+        }
+        if self.locked && !self.lock_expiry_alerted && now > self.request.lock_expires_at() {
+            self.lock_expiry_alerted = true;
+            return Some(LockAlert {
+                kind: LockAlertKind::LockExpired,
+                request_id,
+                message: format!(
+                    "lock on request 0x{request_id:x} expired at {} without a fulfillment",
+                    self.request.lock_expires_at()
+                ),
+            });
+        }
+        None
+    }
+}
+
+impl OpsLockMonitor {
+    /// Run the [OpsLockMonitor] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let address = match self.address {
+            Some(address) => address,
+            None => global_config.require_private_key()?.address(),
+        };
+        let client = global_config.build_client().await?;
+        let provider = client.provider();
+        let market_address = client.deployment.boundless_market_address;
+        let http = reqwest::Client::new();
+
+        let mut monitor = LockMonitor::new(
+            &provider,
+            address,
+            self.ramp_up_percent,
+            self.lookback_blocks,
+            self.order_stream_offset,
+        )
+        .await?;
+
+        loop {
+            let alerts =
+                monitor.poll(&provider, market_address, client.offchain_client.as_ref()).await?;
+            for alert in &alerts {
+                tracing::warn!("[B-CLI-LOCK] {}", alert.message);
+                if let Some(url) = &self.webhook_url {
+                    if let Err(err) = send_webhook(&http, url, alert).await {
+                        tracing::error!("Failed to send alert to webhook: {err:?}");
+                    }
+                }
+            }
+            if self.exit_on_alert && !alerts.is_empty() {
+                bail!("{} request(s) raised an alert", alerts.len());
+            }
+            tokio::time::sleep(Duration::from_secs(self.interval)).await;
+        }
+    }
+}
+
+/// State of the lock monitor between polls, shared by [OpsLockMonitor] and the daemon.
+pub(crate) struct LockMonitor {
+    address: Address,
+    ramp_up_percent: u8,
+    tracked: HashMap<U256, Tracked>,
+    next_block: u64,
+    order_stream_offset: Option<u64>,
+}
+
+impl LockMonitor {
+    /// Monitor the requests of `address`, discovering them from `lookback_blocks` before the
+    /// current block, and from `order_stream_offset` in the order stream if set.
+    pub(crate) async fn new(
+        provider: &DynProvider,
+        address: Address,
+        ramp_up_percent: u8,
+        lookback_blocks: u64,
+        order_stream_offset: Option<u64>,
+    ) -> anyhow::Result<Self> {
+        let next_block = provider.get_block_number().await?.saturating_sub(lookback_blocks);
+        tracing::info!("Monitoring requests of {address} from block {next_block}");
+        Ok(Self {
+            address,
+            ramp_up_percent,
+            tracked: HashMap::new(),
+            next_block,
+            order_stream_offset,
+        })
+    }
+
+    /// Number of requests that are neither fulfilled nor expired, as of the last poll.
+    pub(crate) fn open_requests(&self) -> usize {
+        self.tracked.len()
+    }
+
+    /// Process the events and orders since the last poll, returning the alerts raised.
+    pub(crate) async fn poll(
+        &mut self,
+        provider: &DynProvider,
+        market_address: Address,
+        order_stream: Option<&OrderStreamClient>,
+    ) -> anyhow::Result<Vec<LockAlert>> {
+        let address = self.address;
+        let tracked = &mut self.tracked;
+        let next_block = self.next_block;
+        let to = provider.get_block_number().await?;
+        if to >= next_block {
+            let submitted: Vec<(RequestSubmitted, Log)> =
+                query_events(provider, market_address, next_block, to).await?;
+            for (event, _) in submitted {
+                if event.request.client_address() == address {
+                    tracked.entry(event.requestId).or_insert_with(|| Tracked::new(event.request));
+                }
+            }
+            let locked: Vec<(RequestLocked, Log)> =
+                query_events(provider, market_address, next_block, to).await?;
+            for (event, _) in locked {
+                if event.request.client_address() == address {
+                    tracked
+                        .entry(event.requestId)
+                        .or_insert_with(|| Tracked::new(event.request))
+                        .locked = true;
+                }
+            }
+            let fulfilled: Vec<(RequestFulfilled, Log)> =
+                query_events(provider, market_address, next_block, to).await?;
+            for (event, _) in fulfilled {
+                if tracked.remove(&event.requestId).is_some() {
+                    tracing::info!("Request 0x{:x} fulfilled", event.requestId);
+                }
+            }
+            self.next_block = to + 1;
+        }
+
+        if let (Some(offset), Some(order_stream)) =
+            (self.order_stream_offset.as_mut(), order_stream)
+        {
+            let orders = order_stream
+                .list_orders(*offset, 100)
+                .await
+                .context("failed to list orders from the order stream")?;
+            for data in orders {
+                *offset = (*offset).max(data.id as u64 + 1);
+                let request = data.order.request;
+                if request.client_address() == address {
+                    tracked.entry(request.id).or_insert_with(|| Tracked::new(request));
+                }
+            }
+        }
+
+        let now = now();
+        let mut alerts = Vec::new();
+        let ramp_up_percent = self.ramp_up_percent;
+        tracked.retain(|_, request| {
+            alerts.extend(request.check(now, ramp_up_percent));
+            now <= request.request.expires_at()
+        });
+        tracing::debug!("Monitoring {} open requests", tracked.len());
+        Ok(alerts)
+    }
+}
+
+/// Query the market events of type `E` in the given range of blocks.
+pub(crate) async fn query_events<E: SolEvent>(
+    provider: &DynProvider,
+    market_address: Address,
+    from: u64,
+    to: u64,
+) -> anyhow::Result<Vec<(E, Log)>> {
+    Ok(query_events_chunked(
+        provider.clone(),
+        Filter::new().address(market_address).event_signature(E::SIGNATURE_HASH),
+        from..=to,
+        EVENT_CHUNK_SIZE,
+        |_| {},
+    )
+    .await?)
+}
+
+pub(crate) async fn send_webhook(
+    http: &reqwest::Client,
+    url: &Url,
+    alert: &impl Serialize,
+) -> anyhow::Result<()> {
+    http.post(url.clone()).json(alert).send().await?.error_for_status()?;
+    Ok(())
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("system time before UNIX epoch").as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+    use boundless_market::{
+        contracts::{Offer, Predicate, RequestId, Requirements},
+        input::GuestEnv,
+    };
+    use risc0_zkvm::sha::Digest;
+
+    use super::*;
+
+    #[test]
+    fn test_lock_monitor_alerts() {
+        let request = ProofRequest::new(
+            RequestId::new(Address::ZERO, 1),
+            Requirements::new(Predicate::prefix_match(Digest::ZERO, Bytes::default())),
+            "http://image_uri.null",
+            GuestEnv::builder().build_inline().unwrap(),
+            Offer {
+                minPrice: U256::ZERO,
+                maxPrice: U256::ZERO,
+                rampUpStart: 1000,
+                rampUpPeriod: 100,
+                lockTimeout: 300,
+                timeout: 600,
+                lockCollateral: U256::ZERO,
+            },
+        );
+
+        let mut tracked = Tracked::new(request.clone());
+        assert!(tracked.check(1079, 80).is_none());
+        let alert = tracked.check(1080, 80).unwrap();
+        assert_eq!(alert.kind, LockAlertKind::RampUpUnlocked);
+        assert!(tracked.check(1090, 80).is_none(), "alert is only raised once");
+
+        let mut tracked = Tracked { locked: true, ..Tracked::new(request) };
+        assert!(tracked.check(1080, 80).is_none(), "locked requests do not raise ramp-up alerts");
+        assert!(tracked.check(1300, 80).is_none());
+        assert_eq!(tracked.check(1301, 80).unwrap().kind, LockAlertKind::LockExpired);
+        assert!(tracked.check(1302, 80).is_none());
+    }
+}
@@ -0,0 +1,26 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commands of the Boundless CLI for operating the market.
+
+mod gas_report;
+mod lock_monitor;
+mod verify_deployment;
+
+pub use gas_report::OpsGasReport;
+pub(crate) use lock_monitor::{now, query_events, send_webhook, LockMonitor};
+pub use lock_monitor::{LockAlert, LockAlertKind, OpsLockMonitor};
+pub use verify_deployment::{
+    observe_deployment, DeploymentManifest, ManifestCheck, OpsVerifyDeployment,
+};
@@ -0,0 +1,419 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fmt::Display, path::PathBuf};
+
+use alloy::{
+    network::Ethereum,
+    primitives::{b256, keccak256, Address, B256, U256},
+    providers::Provider,
+    sol,
+};
+use anyhow::{anyhow, Context};
+use boundless_market::{
+    selector::{set_builder_selector, SelectorRegistry},
+    Client, Deployment,
+};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::GlobalConfig,
+    exit_code::{ExitCode, WithExitCode},
+};
+
+/// Storage slot holding the implementation address of an ERC-1967 proxy.
+const ERC1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+sol! {
+    #[sol(rpc)]
+    interface IBoundlessMarketVerifier {
+        function VERIFIER() external view returns (address);
+    }
+}
+
+/// Command to audit the deployed contracts against expected bytecode hashes and image IDs.
+///
+/// Without a manifest, the deployment is checked for consistency onchain: code must be deployed
+/// at each configured contract, the BoundlessMarket must verify through the configured verifier
+/// router, and the router must route the set builder's selector to the configured set verifier.
+/// The chain ID and addresses are also compared against the deployment bundled with this release,
+/// if there is one for the connected chain. With a manifest, every value it specifies is compared
+/// instead.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct OpsVerifyDeployment {
+    /// Path to a JSON manifest of expected deployment values.
+    #[clap(long)]
+    pub manifest: Option<PathBuf>,
+    /// Write the values observed onchain to the given path as a JSON manifest.
+    ///
+    /// The written manifest can be passed to --manifest to detect later drift.
+    #[clap(long)]
+    pub write_manifest: Option<PathBuf>,
+}
+
+/// Expected values of a Boundless deployment.
+///
+/// Any field set to `None` is not checked.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct DeploymentManifest {
+    /// EIP-155 chain ID of the network.
+    pub chain_id: Option<u64>,
+    /// Address of the BoundlessMarket contract.
+    pub boundless_market_address: Option<Address>,
+    /// Keccak-256 hash of the code deployed at the BoundlessMarket address.
+    pub boundless_market_code_hash: Option<B256>,
+    /// Address of the BoundlessMarket implementation behind the proxy.
+    pub boundless_market_implementation: Option<Address>,
+    /// Keccak-256 hash of the BoundlessMarket implementation code.
+    pub boundless_market_implementation_code_hash: Option<B256>,
+    /// Address of the RiscZeroSetVerifier contract.
+    pub set_verifier_address: Option<Address>,
+    /// Keccak-256 hash of the code deployed at the RiscZeroSetVerifier address.
+    pub set_verifier_code_hash: Option<B256>,
+    /// Address of the RiscZeroVerifierRouter contract.
+    pub verifier_router_address: Option<Address>,
+    /// Keccak-256 hash of the code deployed at the RiscZeroVerifierRouter address.
+    pub verifier_router_code_hash: Option<B256>,
+    /// Image ID of the assessor guest configured in the BoundlessMarket.
+    pub assessor_image_id: Option<B256>,
+    /// Image ID of the set builder guest configured in the RiscZeroSetVerifier.
+    pub set_builder_image_id: Option<B256>,
+}
+
+impl DeploymentManifest {
+    /// Expected values derived from the [Deployment] bundled with this release for the chain.
+    pub fn bundled(chain_id: u64) -> Option<Self> {
+        let deployment = Deployment::from_chain_id(chain_id)?;
+        Some(Self {
+            chain_id: Some(chain_id),
+            boundless_market_address: Some(deployment.boundless_market_address),
+            set_verifier_address: Some(deployment.set_verifier_address),
+            verifier_router_address: deployment.verifier_router_address,
+            ..Default::default()
+        })
+    }
+
+    /// Load a manifest from a JSON file.
+    pub fn load(path: &PathBuf) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read manifest {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse manifest {}", path.display()))
+    }
+
+    /// Compare this manifest of expected values against the observed values.
+    ///
+    /// Returns one [ManifestCheck] for each expected value that is set.
+    pub fn compare(&self, observed: &DeploymentManifest) -> Vec<ManifestCheck> {
+        let mut checks = Vec::new();
+        fn check<T: PartialEq + Display>(
+            checks: &mut Vec<ManifestCheck>,
+            name: &'static str,
+            expected: &Option<T>,
+            observed: &Option<T>,
+        ) {
+            if let Some(expected) = expected {
+                checks.push(ManifestCheck {
+                    name,
+                    expected: expected.to_string(),
+                    observed: observed
+                        .as_ref()
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "<missing>".to_string()),
+                    ok: observed.as_ref() == Some(expected),
+                });
+            }
+        }
+        check(&mut checks, "chain ID", &self.chain_id, &observed.chain_id);
+        check(
+            &mut checks,
+            "BoundlessMarket address",
+            &self.boundless_market_address,
+            &observed.boundless_market_address,
+        );
+        check(
+            &mut checks,
+            "BoundlessMarket code hash",
+            &self.boundless_market_code_hash,
+            &observed.boundless_market_code_hash,
+        );
+        check(
+            &mut checks,
+            "BoundlessMarket implementation",
+            &self.boundless_market_implementation,
+            &observed.boundless_market_implementation,
+        );
+        check(
+            &mut checks,
+            "BoundlessMarket implementation code hash",
+            &self.boundless_market_implementation_code_hash,
+            &observed.boundless_market_implementation_code_hash,
+        );
+        check(
+            &mut checks,
+            "RiscZeroSetVerifier address",
+            &self.set_verifier_address,
+            &observed.set_verifier_address,
+        );
+        check(
+            &mut checks,
+            "RiscZeroSetVerifier code hash",
+            &self.set_verifier_code_hash,
+            &observed.set_verifier_code_hash,
+        );
+        check(
+            &mut checks,
+            "RiscZeroVerifierRouter address",
+            &self.verifier_router_address,
+            &observed.verifier_router_address,
+        );
+        check(
+            &mut checks,
+            "RiscZeroVerifierRouter code hash",
+            &self.verifier_router_code_hash,
+            &observed.verifier_router_code_hash,
+        );
+        check(
+            &mut checks,
+            "Assessor image ID",
+            &self.assessor_image_id,
+            &observed.assessor_image_id,
+        );
+        check(
+            &mut checks,
+            "Set builder image ID",
+            &self.set_builder_image_id,
+            &observed.set_builder_image_id,
+        );
+        checks
+    }
+}
+
+/// Result of comparing a single expected value against the observed deployment.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ManifestCheck {
+    /// Name of the checked value.
+    pub name: &'static str,
+    /// Expected value, as a string.
+    pub expected: String,
+    /// Observed value, as a string.
+    pub observed: String,
+    /// Whether the observed value matches the expected value.
+    pub ok: bool,
+}
+
+impl OpsVerifyDeployment {
+    /// Run the [OpsVerifyDeployment] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let observed = observe_deployment(&client).await?;
+
+        if let Some(path) = &self.write_manifest {
+            std::fs::write(path, serde_json::to_string_pretty(&observed)?)
+                .with_context(|| format!("failed to write manifest {}", path.display()))?;
+            tracing::info!("Wrote observed deployment manifest to {}", path.display());
+        }
+
+        let checks = match &self.manifest {
+            Some(path) => DeploymentManifest::load(path)?.compare(&observed),
+            None => {
+                let chain_id = observed.chain_id.context("chain ID not observed")?;
+                let mut checks = consistency_checks(&client, &observed).await?;
+                match DeploymentManifest::bundled(chain_id) {
+                    Some(bundled) => checks.extend(bundled.compare(&observed)),
+                    None => tracing::info!(
+                        "No bundled deployment for chain ID {chain_id}; checking consistency only"
+                    ),
+                }
+                checks
+            }
+        };
+        for check in &checks {
+            if check.ok {
+                println!("✅ {}: {}", check.name, check.observed);
+            } else {
+                println!(
+                    "❌ {}: expected {}, observed {}",
+                    check.name, check.expected, check.observed
+                );
+            }
+        }
+
+        let drift = checks.iter().filter(|check| !check.ok).count();
+        if drift > 0 {
+            return Err(anyhow!(
+                "deployment drift detected: {drift} of {} checks failed",
+                checks.len()
+            ))
+            .exit_code(ExitCode::Verification);
+        }
+        println!("\nDeployment matches the expected values ({} checks)", checks.len());
+        Ok(())
+    }
+}
+
+/// Collect the values of a deployment as observed onchain.
+pub async fn observe_deployment<P, St, R, Si>(
+    client: &Client<P, St, R, Si>,
+) -> anyhow::Result<DeploymentManifest>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    let provider = client.provider();
+    let deployment = &client.deployment;
+    let chain_id = provider.get_chain_id().await.context("failed to query chain ID")?;
+
+    let boundless_market_code_hash =
+        code_hash(&provider, deployment.boundless_market_address).await?;
+    let implementation = provider
+        .get_storage_at(
+            deployment.boundless_market_address,
+            U256::from_be_bytes(ERC1967_IMPLEMENTATION_SLOT.0),
+        )
+        .await
+        .context("failed to read BoundlessMarket implementation slot")?;
+    let boundless_market_implementation =
+        Some(Address::from_word(B256::from(implementation.to_be_bytes::<32>())))
+            .filter(|addr| !addr.is_zero());
+    let boundless_market_implementation_code_hash = match boundless_market_implementation {
+        Some(addr) => code_hash(&provider, addr).await?,
+        None => None,
+    };
+
+    let set_verifier_code_hash = code_hash(&provider, deployment.set_verifier_address).await?;
+    let verifier_router_code_hash = match deployment.verifier_router_address {
+        Some(addr) => code_hash(&provider, addr).await?,
+        None => None,
+    };
+
+    let assessor_image_id = match client.boundless_market.image_info().await {
+        Ok((image_id, _)) => Some(image_id),
+        Err(err) => {
+            tracing::warn!("Failed to query assessor image ID: {err}");
+            None
+        }
+    };
+    let set_builder_image_id = match client.set_verifier.image_info().await {
+        Ok((image_id, _)) => Some(image_id),
+        Err(err) => {
+            tracing::warn!("Failed to query set builder image ID: {err}");
+            None
+        }
+    };
+
+    Ok(DeploymentManifest {
+        chain_id: Some(chain_id),
+        boundless_market_address: Some(deployment.boundless_market_address),
+        boundless_market_code_hash,
+        boundless_market_implementation,
+        boundless_market_implementation_code_hash,
+        set_verifier_address: Some(deployment.set_verifier_address),
+        set_verifier_code_hash,
+        verifier_router_address: deployment.verifier_router_address,
+        verifier_router_code_hash,
+        assessor_image_id,
+        set_builder_image_id,
+    })
+}
+
+/// Check that the observed deployment is consistent onchain, independently of any expected values.
+pub async fn consistency_checks<P, St, R, Si>(
+    client: &Client<P, St, R, Si>,
+    observed: &DeploymentManifest,
+) -> anyhow::Result<Vec<ManifestCheck>>
+where
+    P: Provider<Ethereum> + Clone + 'static,
+{
+    fn deployed(name: &'static str, code_hash: &Option<B256>) -> ManifestCheck {
+        ManifestCheck {
+            name,
+            expected: "deployed code".to_string(),
+            observed: code_hash
+                .map(|hash| format!("code hash {hash}"))
+                .unwrap_or_else(|| "<missing>".to_string()),
+            ok: code_hash.is_some(),
+        }
+    }
+    fn address(name: &'static str, expected: Address, observed: Address) -> ManifestCheck {
+        ManifestCheck {
+            name,
+            expected: expected.to_string(),
+            observed: observed.to_string(),
+            ok: expected == observed,
+        }
+    }
+
+    let provider = client.provider();
+    let deployment = &client.deployment;
+    let mut checks = vec![
+        deployed("BoundlessMarket code", &observed.boundless_market_code_hash),
+        deployed("RiscZeroSetVerifier code", &observed.set_verifier_code_hash),
+    ];
+
+    let Some(router) = deployment.verifier_router_address else {
+        tracing::warn!("No verifier router configured; skipping verifier routing checks");
+        return Ok(checks);
+    };
+    checks.push(deployed("RiscZeroVerifierRouter code", &observed.verifier_router_code_hash));
+    if observed.boundless_market_code_hash.is_none() || observed.verifier_router_code_hash.is_none()
+    {
+        return Ok(checks);
+    }
+
+    let market_verifier =
+        IBoundlessMarketVerifier::new(deployment.boundless_market_address, &provider)
+            .VERIFIER()
+            .call()
+            .await
+            .context("failed to query the BoundlessMarket verifier")?;
+    checks.push(address("BoundlessMarket verifier", router, market_verifier));
+
+    match observed.set_builder_image_id {
+        Some(set_builder_image_id) => {
+            let selector = set_builder_selector(<[u8; 32]>::from(set_builder_image_id));
+            let routed = SelectorRegistry::new(router, provider.clone())
+                .lookup(selector)
+                .await
+                .context("failed to query the RiscZeroVerifierRouter")?
+                .map(|info| info.verifier)
+                .unwrap_or_default();
+            checks.push(address(
+                "RiscZeroVerifierRouter set verifier route",
+                deployment.set_verifier_address,
+                routed,
+            ));
+        }
+        None => checks.push(ManifestCheck {
+            name: "Set builder image ID",
+            expected: "image ID reported by the RiscZeroSetVerifier".to_string(),
+            observed: "<missing>".to_string(),
+            ok: false,
+        }),
+    }
+    Ok(checks)
+}
+
+/// Keccak-256 hash of the code at the given address, or `None` if no code is deployed there.
+async fn code_hash(provider: &impl Provider, address: Address) -> anyhow::Result<Option<B256>> {
+    let code = provider
+        .get_code_at(address)
+        .await
+        .with_context(|| format!("failed to get code at {address}"))?;
+    Ok((!code.is_empty()).then(|| keccak256(&code)))
+}
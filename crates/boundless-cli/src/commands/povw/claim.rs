@@ -14,17 +14,18 @@
 
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
+    ops::ControlFlow,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use alloy::{
-    contract::Event,
     primitives::{Address, U256},
     providers::{Provider, ProviderBuilder},
     rpc::types::{Filter, Log},
     sol_types::SolEvent,
 };
 use anyhow::{bail, ensure, Context};
+use boundless_market::log_query::{ChunkedLogQuery, ScanDirection};
 use boundless_povw::{
     deployments::Deployment,
     log_updater::IPovwAccounting::{self, EpochFinalized, IPovwAccountingInstance, WorkLogUpdated},
@@ -46,17 +47,18 @@ const HOUR: Duration = Duration::from_secs(60 * 60);
 #[non_exhaustive]
 #[derive(Args, Clone, Debug)]
 pub struct PovwClaim {
-    // TODO(povw): Support providing multiple log IDs.
-    /// Work log ID for the reward claim.
+    /// Work log ID for the reward claim. May be given multiple times to claim for several logs.
     ///
     /// State for submitted updates is retrieved from the chain using the ID. Note that initiating
     /// the claim can be done for any log ID and does not require authorization.
-    /// Work log ID for the reward claim.
+    #[arg(short, long, required = true)]
+    pub log_id: Vec<PovwLogId>,
+
+    /// Claim the rewards of all the given work logs with a single proof and transaction.
     ///
-    /// State for submitted updates is retrieved from the chain using the ID. Note that initiating
-    /// the claim can be done for any log ID and does not require authorization.
-    #[arg(short, long)]
-    pub log_id: PovwLogId,
+    /// Without this flag, a separate proof is generated and transaction sent for each log.
+    #[arg(long)]
+    pub batch: bool,
 
     // TODO: Deprecate and/or remove this when history support works without the Beacon API.
     /// URL for an Ethereum Beacon chain (i.e. consensus chain) API.
@@ -138,10 +140,113 @@ impl PovwClaim {
             IPovwAccounting::new(deployment.povw_accounting_address, provider.clone());
         let povw_mint = IPovwMint::new(deployment.povw_mint_address, provider.clone());
 
+        // Find the blocks with the events needed to claim for each log with unclaimed rewards.
+        let mut claims = Vec::new();
+        for &log_id in &self.log_id {
+            let event_block_numbers = self
+                .claimable_blocks(
+                    log_id,
+                    &povw_accounting,
+                    &povw_mint,
+                    &deployment,
+                    latest_block_number,
+                    lower_limit_block_number,
+                )
+                .await
+                .with_context(|| format!("Failed to find claimable rewards for log {log_id:x}"))?;
+            if let Some(event_block_numbers) = event_block_numbers {
+                claims.push((vec![log_id], event_block_numbers));
+            }
+        }
+        if claims.is_empty() {
+            tracing::info!("All rewards for submitted work log updates have been claimed");
+            return Ok(());
+        }
+        if self.batch && claims.len() > 1 {
+            tracing::info!("Batching the reward claims of {} work logs", claims.len());
+            let (log_ids, event_block_numbers) = claims.into_iter().fold(
+                (Vec::new(), BTreeSet::new()),
+                |(mut log_ids, mut event_block_numbers), (ids, blocks)| {
+                    log_ids.extend(ids);
+                    event_block_numbers.extend(blocks);
+                    (log_ids, event_block_numbers)
+                },
+            );
+            claims = vec![(log_ids, event_block_numbers)];
+        }
+
+        self.prover_config.configure_proving_backend_with_health_check().await?;
+        let mint_calculator_prover = MintCalculatorProver::builder()
+            .prover(default_prover())
+            .provider(provider.clone())
+            .beacon_api(self.beacon_api_url.clone())
+            .povw_accounting_address(deployment.povw_accounting_address)
+            .zkc_address(deployment.zkc_address)
+            .zkc_rewards_address(deployment.vezkc_address)
+            .chain_spec(chain_spec)
+            .prover_opts(ProverOpts::groth16())
+            .build()?;
+
+        for (log_ids, event_block_numbers) in claims {
+            tracing::info!("Building input data for Mint Calculator guest");
+            let mint_input = mint_calculator_prover
+                .build_input(event_block_numbers, log_ids)
+                .await
+                .context("Failed to build input for Mint Calculator Guest")?;
+
+            tracing::info!("Proving Mint Calculator guest");
+            let mint_prove_info = mint_calculator_prover
+                .prove_mint(&mint_input)
+                .await
+                .context("Failed to prove Mint Calculator guest")?;
+
+            tracing::info!("Sending reward claim transaction");
+            let tx_result = povw_mint
+                .mint_with_receipt(&mint_prove_info.receipt)
+                .context("Failed to construct reward claim transaction")?
+                .send()
+                .await
+                .context("Failed to send reward claim transaction")?;
+            let tx_hash = tx_result.tx_hash();
+            tracing::info!(%tx_hash, "Sent transaction for reward claim");
+
+            let timeout = global_config.tx_timeout.or(tx_result.timeout());
+            tracing::debug!(?timeout, %tx_hash, "Waiting for transaction receipt");
+            let tx_receipt = tx_result
+                .with_timeout(timeout)
+                .get_receipt()
+                .await
+                .context("Failed to receive receipt reward claim transaction")?;
+
+            ensure!(
+                tx_receipt.status(),
+                "Reward claim transaction failed: tx_hash = {}",
+                tx_receipt.transaction_hash
+            );
+        }
+
+        // TODO(povw): Display some info, like how much of a reward was created.
+        tracing::info!("Reward claim completed");
+        Ok(())
+    }
+
+    /// Find the blocks containing the events needed to claim the rewards of the given log.
+    ///
+    /// Returns `None` if all rewards for the submitted updates of the log have been claimed.
+    #[allow(clippy::too_many_arguments)]
+    async fn claimable_blocks<P: Provider>(
+        &self,
+        log_id: PovwLogId,
+        povw_accounting: &IPovwAccountingInstance<P>,
+        povw_mint: &IPovwMint::IPovwMintInstance<P>,
+        deployment: &Deployment,
+        latest_block_number: u64,
+        lower_limit_block_number: u64,
+    ) -> anyhow::Result<Option<BTreeSet<u64>>> {
         // Determine the commit range for which we can mint. This is the difference between the
         // recoreded work log commit on the accounting contract and on the mint contract.
         let initial_commit = Digest::from(
-            *povw_mint.workLogCommit(self.log_id.into()).call().await.with_context(|| {
+            *povw_mint.workLogCommit(log_id.into()).call().await.with_context(|| {
                 format!(
                     "Failed to call IPovwMint.workLogCommit on {}",
                     deployment.povw_mint_address
@@ -149,7 +254,7 @@ impl PovwClaim {
             })?,
         );
         let final_commit = Digest::from(
-            *povw_accounting.workLogCommit(self.log_id.into()).call().await.with_context(|| {
+            *povw_accounting.workLogCommit(log_id.into()).call().await.with_context(|| {
                 format!(
                     "Failed to call IPovwAccounting.workLogCommit on {}",
                     deployment.povw_accounting_address
@@ -159,15 +264,15 @@ impl PovwClaim {
         tracing::debug!(%initial_commit, %final_commit, "Commit range for mint");
 
         if initial_commit == final_commit {
-            tracing::info!("All rewards for submitted work log updates have been claimed");
-            return Ok(());
+            tracing::info!("All rewards for submitted updates of log {log_id:x} have been claimed");
+            return Ok(None);
         }
 
         // Search for the WorkLogUpdated events, and the the EpochFinalized events.
         tracing::info!("Searching for work log update events in the past {} days", self.days);
         let update_events = search_work_log_updated(
-            &povw_accounting,
-            self.log_id,
+            povw_accounting,
+            log_id,
             initial_commit,
             final_commit,
             latest_block_number,
@@ -177,7 +282,6 @@ impl PovwClaim {
         .await
         .context("Search for work log update events failed")?;
         tracing::info!("Found {} work log update events", update_events.len());
-
         // Check to see what the current pending epoch is on the PoVW accounting contract. Filter
         // out update events with an epoch that has not finalized (with a warning).
         let pending_epoch = povw_accounting
@@ -225,7 +329,7 @@ impl PovwClaim {
             tracing::info!("Searching for epoch finalization events, from epoch {first_epoch} to epoch {last_epoch}");
         }
         let epoch_events = search_epoch_finalized(
-            &povw_accounting,
+            povw_accounting,
             epochs,
             latest_block_number,
             lower_limit_block_number,
@@ -241,58 +345,7 @@ impl PovwClaim {
                 .map(|(_, block_number)| *block_number)
                 .chain(epoch_events.keys().copied()),
         );
-
-        self.prover_config.configure_proving_backend_with_health_check().await?;
-        let mint_calculator_prover = MintCalculatorProver::builder()
-            .prover(default_prover())
-            .provider(provider.clone())
-            .beacon_api(self.beacon_api_url.clone())
-            .povw_accounting_address(deployment.povw_accounting_address)
-            .zkc_address(deployment.zkc_address)
-            .zkc_rewards_address(deployment.vezkc_address)
-            .chain_spec(chain_spec)
-            .prover_opts(ProverOpts::groth16())
-            .build()?;
-
-        tracing::info!("Building input data for Mint Calculator guest");
-        let mint_input = mint_calculator_prover
-            .build_input(event_block_numbers, [self.log_id])
-            .await
-            .context("Failed to build input for Mint Calculator Guest")?;
-
-        tracing::info!("Proving Mint Calculator guest");
-        let mint_prove_info = mint_calculator_prover
-            .prove_mint(&mint_input)
-            .await
-            .context("Failed to prove Mint Calculator guest")?;
-
-        tracing::info!("Sending reward claim transaction");
-        let tx_result = povw_mint
-            .mint_with_receipt(&mint_prove_info.receipt)
-            .context("Failed to construct reward claim transaction")?
-            .send()
-            .await
-            .context("Failed to send reward claim transaction")?;
-        let tx_hash = tx_result.tx_hash();
-        tracing::info!(%tx_hash, "Sent transaction for reward claim");
-
-        let timeout = global_config.tx_timeout.or(tx_result.timeout());
-        tracing::debug!(?timeout, %tx_hash, "Waiting for transaction receipt");
-        let tx_receipt = tx_result
-            .with_timeout(timeout)
-            .get_receipt()
-            .await
-            .context("Failed to receive receipt reward claim transaction")?;
-
-        ensure!(
-            tx_receipt.status(),
-            "Reward claim transaction failed: tx_hash = {}",
-            tx_receipt.transaction_hash
-        );
-
-        // TODO(povw): Display some info, like how much of a reward was created.
-        tracing::info!("Reward claim completed");
-        Ok(())
+        Ok(Some(event_block_numbers))
     }
 }
 
@@ -476,39 +529,36 @@ async fn search_events<P: Provider + Clone, E: SolEvent>(
     chunk_size: u64,
     mut f: impl FnMut(&[(E, Log)]) -> anyhow::Result<bool>,
 ) -> anyhow::Result<()> {
-    let mut upper_block = upper_limit_block_number;
-    loop {
-        // The scan has reach block 0. This can only really happen in tests.
-        if upper_block == 0 {
-            tracing::warn!("Scan for events reached block 0");
-            break;
-        }
-        if upper_block < lower_limit_block_number {
-            bail!("Search reached lower limit block number {lower_limit_block_number}");
-        }
-
-        // Calculate the block range to query: from lower_block to upper_block. Range is
-        // inclusive of both lower and upper block.
-        let lower_block =
-            u64::max(upper_block.saturating_sub(chunk_size) + 1, lower_limit_block_number);
-
-        // Set up the event filter for the specified block range
-        tracing::debug!(range = ?(lower_block, upper_block), "Querying for events");
-        let query_logs = Event::new(
-            provider.clone(),
-            filter.clone().from_block(lower_block).to_block(upper_block),
+    // Scan backwards from the upper limit, until the predicate halts the search or returns an error.
+    let halted = ChunkedLogQuery::new(chunk_size)
+        .with_direction(ScanDirection::Backward)
+        .scan(
+            provider,
+            filter,
+            lower_limit_block_number..=upper_limit_block_number,
+            |query_logs: Vec<(E, Log)>, progress| {
+                tracing::debug!(range = ?progress.chunk, "Queried for events");
+                match f(&query_logs) {
+                    Ok(true) => ControlFlow::Continue(()),
+                    Ok(false) => ControlFlow::Break(Ok(())),
+                    Err(err) => ControlFlow::Break(Err(err)),
+                }
+            },
         )
-        .query()
         .await
-        .with_context(|| format!("Query for events in the range {lower_block} to {upper_block}"))?;
+        .with_context(|| {
+            format!(
+                "Query for events in the range {lower_limit_block_number} to {upper_limit_block_number}"
+            )
+        })?;
 
-        // Check the predicate to see if the search should continue.
-        if !f(&query_logs)? {
-            break;
+    match halted {
+        Some(result) => result,
+        // The scan has reach block 0. This can only really happen in tests.
+        None if lower_limit_block_number == 0 => {
+            tracing::warn!("Scan for events reached block 0");
+            Ok(())
         }
-
-        // Move the window down and continue.
-        upper_block = lower_block.saturating_sub(1);
+        None => bail!("Search reached lower limit block number {lower_limit_block_number}"),
     }
-    Ok(())
 }
@@ -0,0 +1,89 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::primitives::{utils::format_units, Address, B256, U256};
+use anyhow::{bail, Context};
+use clap::Args;
+
+use crate::config::GlobalConfig;
+
+/// Command to compute the collateral a prover needs to lock a request.
+///
+/// The required collateral is compared against the collateral the prover has deposited, so that a
+/// shortfall can be topped up before attempting the lock.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ProvingCollateralNeeded {
+    /// The proof request identifier.
+    #[clap(long)]
+    pub request_id: U256,
+    /// The request digest.
+    ///
+    /// If provided along with request-id, uses the request digest to find the request.
+    #[clap(long)]
+    pub request_digest: Option<B256>,
+    /// The tx hash of the request submission.
+    ///
+    /// If provided along with request-id, uses the transaction hash to find the request.
+    #[clap(long)]
+    pub tx_hash: Option<B256>,
+    /// Address of the prover to check the deposited collateral of.
+    ///
+    /// If not provided, defaults to the wallet address.
+    #[clap(long)]
+    pub prover: Option<Address>,
+}
+
+impl ProvingCollateralNeeded {
+    /// Run the [ProvingCollateralNeeded] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let prover = self.prover.unwrap_or(client.boundless_market.caller());
+        if prover == Address::ZERO {
+            bail!("No prover address specified. Please provide --prover or a private key.");
+        }
+
+        let (request, _signature) = client
+            .fetch_proof_request(self.request_id, self.tx_hash, self.request_digest)
+            .await
+            .context("failed to fetch request")?;
+        let requirement = client.boundless_market.collateral_requirement(&request, prover).await?;
+
+        let symbol = client.boundless_market.collateral_token_symbol().await?;
+        let decimals = client.boundless_market.collateral_token_decimals().await?;
+        let format = |amount: U256| -> anyhow::Result<String> {
+            Ok(format!(
+                "{} {symbol}",
+                format_units(amount, decimals).context("failed to format collateral amount")?
+            ))
+        };
+
+        println!("Request 0x{:x}", self.request_id);
+        println!("  Required collateral: {}", format(requirement.required)?);
+        println!("  Deposited by {prover}: {}", format(requirement.available)?);
+        if requirement.is_sufficient() {
+            println!("✅ Prover has enough collateral to lock this request");
+        } else {
+            println!("❌ Prover is short {} of collateral", format(requirement.shortfall())?);
+            println!(
+                "Deposit more with `boundless account deposit-collateral {}`",
+                format_units(requirement.shortfall(), decimals)?
+            );
+        }
+        if request.is_lock_expired() {
+            println!("Note: the lock deadline has passed, so this request can no longer be locked");
+        }
+        Ok(())
+    }
+}
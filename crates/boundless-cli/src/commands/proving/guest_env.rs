@@ -0,0 +1,139 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use anyhow::{bail, Context};
+use boundless_market::request_builder::ExecutorConfig;
+use clap::Args;
+use risc0_zkvm::{ExecutorEnv, ExecutorEnvBuilder, Receipt};
+
+/// Additions to the guest environment decoded from a request, for local execution.
+///
+/// Lets local execution reproduce a run whose guest reads more than its stdin, such as a
+/// composition guest that verifies the receipts of other programs.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug, Default)]
+pub struct GuestEnvArgs {
+    /// Environment variable to set in the guest, as KEY=VALUE. Can be repeated.
+    #[clap(long = "guest-env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub env_vars: Vec<(String, String)>,
+    /// Path to a file whose contents are written to the guest stdin as a length-prefixed frame,
+    /// after the input of the request. Can be repeated, and frames are written in order.
+    #[clap(long = "input-frame", value_name = "PATH")]
+    pub input_frames: Vec<PathBuf>,
+    /// File to expose to the guest on the given file descriptor, as FD=PATH. Can be repeated.
+    #[clap(long = "guest-fd", value_name = "FD=PATH", value_parser = parse_fd)]
+    pub fds: Vec<(u32, PathBuf)>,
+    /// Path to a bincode-encoded receipt to add as an assumption, for guests that verify other
+    /// receipts. Can be repeated.
+    #[clap(long = "assumption", value_name = "PATH")]
+    pub assumptions: Vec<PathBuf>,
+}
+
+impl GuestEnvArgs {
+    /// Build an [ExecutorEnv] with the given stdin, followed by the additions in these arguments.
+    pub fn executor_env(&self, stdin: &[u8]) -> anyhow::Result<ExecutorEnv<'static>> {
+        self.executor_env_builder(stdin)?.build()
+    }
+
+    /// Build an [ExecutorEnvBuilder] with the given stdin, followed by the additions in these
+    /// arguments, for callers that need to further configure the environment.
+    pub fn executor_env_builder(
+        &self,
+        stdin: &[u8],
+    ) -> anyhow::Result<ExecutorEnvBuilder<'static>> {
+        let mut builder = ExecutorEnv::builder();
+        builder.write_slice(stdin);
+        for path in &self.input_frames {
+            let frame = std::fs::read(path)
+                .with_context(|| format!("failed to read input frame {}", path.display()))?;
+            builder.write_frame(&frame);
+        }
+        for (key, value) in &self.env_vars {
+            builder.env_var(key, value);
+        }
+        for (fd, path) in &self.fds {
+            let file = File::open(path)
+                .with_context(|| format!("failed to open {} for fd {fd}", path.display()))?;
+            builder.read_fd(*fd, BufReader::new(file));
+        }
+        for path in &self.assumptions {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("failed to read assumption {}", path.display()))?;
+            let receipt = bincode::deserialize::<Receipt>(&bytes)
+                .with_context(|| format!("failed to decode assumption {}", path.display()))?;
+            builder.add_assumption(receipt);
+        }
+        Ok(builder)
+    }
+}
+
+/// Configuration of the executor for local execution.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug, Default)]
+pub struct ExecutorArgs {
+    /// Maximum size of a segment in local execution, as a power of two of cycles.
+    ///
+    /// Smaller segments lower the memory needed to execute large guests.
+    #[clap(long, value_name = "PO2")]
+    pub segment_limit_po2: Option<u32>,
+}
+
+impl ExecutorArgs {
+    /// Build an [ExecutorConfig] from these arguments, with the given cycle limit.
+    pub fn executor_config(&self, cycle_limit: Option<u64>) -> ExecutorConfig {
+        ExecutorConfig::default()
+            .with_cycle_limit(cycle_limit)
+            .with_segment_limit_po2(self.segment_limit_po2)
+    }
+}
+
+fn parse_env_var(s: &str) -> anyhow::Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        bail!("expected KEY=VALUE, got {s:?}");
+    };
+    if key.is_empty() {
+        bail!("environment variable name must not be empty");
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn parse_fd(s: &str) -> anyhow::Result<(u32, PathBuf)> {
+    let Some((fd, path)) = s.split_once('=') else {
+        bail!("expected FD=PATH, got {s:?}");
+    };
+    let fd: u32 = fd.parse().with_context(|| format!("invalid file descriptor {fd:?}"))?;
+    // Descriptors 0 to 3 are stdin, stdout, stderr, and the journal.
+    if fd <= 3 {
+        bail!("file descriptor {fd} is reserved by the zkVM");
+    }
+    Ok((fd, PathBuf::from(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args() {
+        assert_eq!(parse_env_var("RUST_LOG=a=b").unwrap(), ("RUST_LOG".into(), "a=b".into()));
+        assert_eq!(parse_env_var("EMPTY=").unwrap(), ("EMPTY".into(), String::new()));
+        parse_env_var("NO_VALUE").unwrap_err();
+        parse_env_var("=value").unwrap_err();
+
+        assert_eq!(parse_fd("10=input.bin").unwrap(), (10, PathBuf::from("input.bin")));
+        parse_fd("0=input.bin").unwrap_err();
+        parse_fd("x=input.bin").unwrap_err();
+    }
+}
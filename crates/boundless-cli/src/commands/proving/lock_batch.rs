@@ -0,0 +1,187 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::primitives::{utils::format_units, Bytes, U256};
+use anyhow::{bail, Context};
+use boundless_market::ProofRequest;
+use clap::Args;
+
+use crate::config::GlobalConfig;
+
+/// Command to lock several requests in one session.
+///
+/// The collateral needed by the whole batch is checked against the deposited collateral before
+/// any request is locked, so a shortfall is found upfront rather than partway through.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ProvingLockBatch {
+    /// Comma-separated IDs of the requests to lock, in the order to lock them.
+    #[clap(long, value_delimiter = ',', required = true)]
+    pub request_ids: Vec<U256>,
+    /// Deposit the collateral shortfall of the batch before locking.
+    #[clap(long, conflicts_with = "partial")]
+    pub top_up: bool,
+    /// If the deposited collateral does not cover the batch, lock the requests that fit in it,
+    /// in order, instead of aborting.
+    #[clap(long)]
+    pub partial: bool,
+}
+
+/// A request of the batch, fetched and ready to lock.
+struct LockCandidate {
+    request: ProofRequest,
+    signature: Bytes,
+    collateral: U256,
+}
+
+impl ProvingLockBatch {
+    /// Run the [ProvingLockBatch] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client_with_signer().await?;
+        let chain_id = client.boundless_market.get_chain_id().await?;
+        let symbol = client.boundless_market.collateral_token_symbol().await?;
+        let decimals = client.boundless_market.collateral_token_decimals().await?;
+        let format = |amount: U256| -> anyhow::Result<String> {
+            Ok(format!(
+                "{} {symbol}",
+                format_units(amount, decimals).context("failed to format collateral amount")?
+            ))
+        };
+
+        // Failures, by request ID, of requests that cannot be locked.
+        let mut failures: Vec<(U256, String)> = Vec::new();
+        let mut candidates = Vec::new();
+        for &request_id in &self.request_ids {
+            let (request, signature) =
+                match client.fetch_proof_request(request_id, None, None).await {
+                    Ok(fetched) => fetched,
+                    Err(err) => {
+                        failures.push((request_id, format!("failed to fetch request: {err}")));
+                        continue;
+                    }
+                };
+            if request.is_lock_expired() {
+                failures.push((request_id, "lock deadline has passed".to_string()));
+                continue;
+            }
+            if client.boundless_market.is_locked(request_id).await? {
+                failures.push((request_id, "request is already locked".to_string()));
+                continue;
+            }
+            // If the request is smart contract signed, the preflight of the lock request
+            // transaction will revert, since it includes the ERC1271 signature check.
+            if !request.is_smart_contract_signed() {
+                if let Err(err) = request.verify_signature(
+                    &signature,
+                    client.deployment.boundless_market_address,
+                    chain_id,
+                ) {
+                    failures.push((request_id, format!("invalid signature: {err}")));
+                    continue;
+                }
+            }
+            let collateral = request.offer.required_collateral();
+            candidates.push(LockCandidate { request, signature, collateral });
+        }
+
+        let required = candidates.iter().map(|c| c.collateral).fold(U256::ZERO, |a, b| a + b);
+        let prover = client.boundless_market.caller();
+        let mut available = client.boundless_market.balance_of_collateral(prover).await?;
+        println!("Batch of {} lockable requests", candidates.len());
+        println!("  Required collateral: {}", format(required)?);
+        println!("  Deposited by {prover}: {}", format(available)?);
+
+        let shortfall = required.saturating_sub(available);
+        if shortfall > U256::ZERO {
+            if self.top_up {
+                println!("Depositing the shortfall of {}", format(shortfall)?);
+                if client.deployment.collateral_token_supports_permit() {
+                    let signer = client.signer.as_ref().context("signer not set")?;
+                    client
+                        .boundless_market
+                        .deposit_collateral_with_permit(shortfall, signer)
+                        .await
+                        .context("failed to deposit collateral")?;
+                } else {
+                    if client.boundless_market.collateral_allowance(prover).await? < shortfall {
+                        client.boundless_market.approve_deposit_collateral(shortfall).await?;
+                    }
+                    client
+                        .boundless_market
+                        .deposit_collateral(shortfall)
+                        .await
+                        .context("failed to deposit collateral")?;
+                }
+                available += shortfall;
+            } else if self.partial {
+                println!(
+                    "Short {} of collateral; locking the requests that fit",
+                    format(shortfall)?
+                );
+                let mut budget = available;
+                candidates.retain(|candidate| {
+                    if candidate.collateral <= budget {
+                        budget -= candidate.collateral;
+                        true
+                    } else {
+                        failures.push((
+                            candidate.request.id,
+                            "skipped, not enough collateral".to_string(),
+                        ));
+                        false
+                    }
+                });
+            } else {
+                bail!(
+                    "Short {} of collateral to lock the batch; deposit more with `boundless account deposit-collateral {}`, or pass --top-up or --partial",
+                    format(shortfall)?,
+                    format_units(shortfall, decimals)?
+                );
+            }
+        }
+
+        let mut locked = 0;
+        for candidate in &candidates {
+            let request_id = candidate.request.id;
+            match client
+                .boundless_market
+                .lock_request(&candidate.request, candidate.signature.clone(), None)
+                .await
+            {
+                Ok(block) => {
+                    locked += 1;
+                    available = available.saturating_sub(candidate.collateral);
+                    println!(
+                        "✅ Locked request 0x{request_id:x} in block {block} ({} collateral)",
+                        format(candidate.collateral)?
+                    );
+                }
+                Err(err) => failures.push((request_id, format!("failed to lock: {err}"))),
+            }
+        }
+        for (request_id, reason) in &failures {
+            println!("❌ Request 0x{request_id:x}: {reason}");
+        }
+
+        println!(
+            "\nLocked {locked} of {} requests; {} collateral remaining",
+            self.request_ids.len(),
+            format(available)?
+        );
+        if !failures.is_empty() {
+            bail!("{} of {} requests were not locked", failures.len(), self.request_ids.len());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commands of the Boundless CLI for proving and verifying proofs.
+
+mod collateral_needed;
+mod guest_env;
+mod lock_batch;
+mod verify_bundle;
+mod verify_local;
+
+pub use collateral_needed::ProvingCollateralNeeded;
+pub use guest_env::{ExecutorArgs, GuestEnvArgs};
+pub use lock_batch::ProvingLockBatch;
+pub use verify_bundle::ProvingVerifyBundle;
+pub use verify_local::ProvingVerifyLocal;
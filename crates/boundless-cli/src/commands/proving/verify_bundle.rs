@@ -0,0 +1,144 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::path::PathBuf;
+
+use alloy::{primitives::B256, providers::Provider};
+use anyhow::{anyhow, bail, ensure, Context};
+use boundless_market::contracts::Predicate;
+use clap::Args;
+use risc0_ethereum_contracts::{
+    receipt::{decode_seal, Receipt as DecodedReceipt},
+    IRiscZeroVerifier,
+};
+use risc0_zkvm::{sha::Digestible, Digest, Journal};
+
+use crate::{
+    commands::request::ProofBundle,
+    config::GlobalConfig,
+    exit_code::{ExitCode, WithExitCode},
+};
+
+/// Command to verify a proof bundle exported with `boundless request export-proof`.
+///
+/// The request's predicate is checked against the bundled journal, and the seal is verified
+/// locally when possible. Set inclusion seals, and any seal when --onchain is given, are verified
+/// by calling the verifier router of the configured deployment on the configured RPC endpoint,
+/// which must be on the same chain as the bundle but need not be the endpoint it was exported
+/// from. The router address recorded in the bundle is not trusted, as the bundle may come from
+/// anyone.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ProvingVerifyBundle {
+    /// Path to the bundle.
+    pub bundle: PathBuf,
+    /// Verify the seal with the verifier router onchain, even if it can be verified locally.
+    #[clap(long)]
+    pub onchain: bool,
+}
+
+impl ProvingVerifyBundle {
+    /// Run the [ProvingVerifyBundle] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let bundle = ProofBundle::read(&self.bundle)?;
+        let manifest = &bundle.manifest;
+        ensure!(
+            bundle.request.id == manifest.request_id,
+            "bundled request 0x{:x} does not match the manifest request 0x{:x}",
+            bundle.request.id,
+            manifest.request_id
+        );
+
+        let predicate = Predicate::try_from(bundle.request.requirements.predicate.clone())?;
+        let claim_digest = predicate
+            .eval(&bundle.fulfillment_data())
+            .context("the bundled journal does not satisfy the request predicate")
+            .exit_code(ExitCode::Verification)?;
+        if B256::from(<[u8; 32]>::from(claim_digest)) != manifest.claim_digest {
+            return Err(anyhow!(
+                "claim digest {} does not match the manifest claim digest {}",
+                claim_digest,
+                manifest.claim_digest
+            ))
+            .exit_code(ExitCode::Verification);
+        }
+        println!("✅ Request predicate is satisfied");
+
+        let verified_locally = match (&manifest.image_id, &bundle.journal) {
+            (Some(image_id), Some(journal)) if !self.onchain => {
+                let image_id = Digest::from(<[u8; 32]>::from(*image_id));
+                match decode_seal(manifest.seal.clone(), image_id, journal.to_vec())
+                    .context("failed to decode seal")?
+                {
+                    DecodedReceipt::Base(receipt) => {
+                        receipt
+                            .verify(image_id)
+                            .context("local verification failed")
+                            .exit_code(ExitCode::Verification)?;
+                        true
+                    }
+                    DecodedReceipt::SetInclusion(_) => false,
+                }
+            }
+            _ => false,
+        };
+        if verified_locally {
+            println!("✅ Seal verified locally");
+        } else {
+            self.verify_onchain(&bundle, global_config).await?;
+            println!("✅ Seal verified by the verifier router on chain {}", manifest.chain_id);
+        }
+
+        println!("Proof of request 0x{:x} is valid", manifest.request_id);
+        Ok(())
+    }
+
+    async fn verify_onchain(
+        &self,
+        bundle: &ProofBundle,
+        global_config: &GlobalConfig,
+    ) -> anyhow::Result<()> {
+        let manifest = &bundle.manifest;
+        let client = global_config.build_client().await?;
+        let chain_id = client.provider().get_chain_id().await?;
+        if chain_id != manifest.chain_id {
+            bail!(
+                "the RPC endpoint is on chain {chain_id}, but the bundle is from chain {}",
+                manifest.chain_id
+            );
+        }
+        let verifier_address = client
+            .deployment
+            .verifier_router_address
+            .context("the configured deployment has no verifier router address")?;
+        if let Some(bundled) = manifest.verifier_router_address {
+            if bundled != verifier_address {
+                tracing::warn!(
+                    "The bundle names verifier router {bundled}, but the configured deployment uses {verifier_address}; verifying with the latter"
+                );
+            }
+        }
+
+        let verifier = IRiscZeroVerifier::new(verifier_address, client.provider());
+        verifier
+            .verifyIntegrity(IRiscZeroVerifier::Receipt {
+                seal: manifest.seal.clone(),
+                claimDigest: manifest.claim_digest,
+            })
+            .call()
+            .await
+            .map_err(|err| anyhow!("onchain verification failed: {err}"))
+            .exit_code(ExitCode::Verification)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use alloy::primitives::{Bytes, B256};
+use anyhow::{bail, Context};
+use clap::Args;
+use risc0_aggregation::merkle_path_root;
+use risc0_ethereum_contracts::receipt::{decode_seal, Receipt as DecodedReceipt};
+use risc0_zkvm::{sha::Digestible, Digest, Receipt};
+
+use crate::{
+    config::GlobalConfig,
+    exit_code::{ExitCode, WithExitCode},
+};
+
+/// Command to verify a proof locally, without calling a verifier contract.
+///
+/// Groth16 seals are checked against the verifying key bundled with the zkVM. Fake receipts, as
+/// produced in dev mode, only verify when `RISC0_DEV_MODE` is set. Set inclusion seals commit to
+/// a Merkle root that is only known onchain, so they cannot be fully verified offline.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ProvingVerifyLocal {
+    /// Image ID of the program the proof is for.
+    #[clap(long)]
+    pub image_id: B256,
+    /// Path to a bincode-encoded RISC Zero receipt.
+    #[clap(long, conflicts_with_all = ["seal", "journal"], required_unless_present = "seal")]
+    pub receipt: Option<PathBuf>,
+    /// Hex-encoded seal, as sent to the verifier contract, including the selector.
+    #[clap(long, requires = "journal")]
+    pub seal: Option<Bytes>,
+    /// Hex-encoded journal committed by the program.
+    #[clap(long, requires = "seal")]
+    pub journal: Option<Bytes>,
+}
+
+impl ProvingVerifyLocal {
+    /// Run the [ProvingVerifyLocal] command.
+    pub async fn run(&self, _global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let image_id = Digest::from(<[u8; 32]>::from(self.image_id));
+
+        let receipt = match (&self.receipt, &self.seal, &self.journal) {
+            (Some(path), _, _) => {
+                let bytes = std::fs::read(path)
+                    .with_context(|| format!("failed to read receipt {}", path.display()))?;
+                bincode::deserialize::<Receipt>(&bytes).context("failed to decode receipt")?
+            }
+            (None, Some(seal), Some(journal)) => {
+                match decode_seal(seal.clone(), image_id, journal.to_vec())
+                    .context("failed to decode seal")?
+                {
+                    DecodedReceipt::Base(receipt) => *receipt,
+                    DecodedReceipt::SetInclusion(receipt) => {
+                        let root = merkle_path_root(receipt.claim.digest(), &receipt.merkle_path);
+                        println!("Seal is a set inclusion proof for root 0x{}", hex::encode(root));
+                        bail!(
+                            "set inclusion seals cannot be verified offline; check that root 0x{} \
+                             is published in the RiscZeroSetVerifier",
+                            hex::encode(root)
+                        );
+                    }
+                }
+            }
+            _ => bail!("either --receipt or both --seal and --journal must be provided"),
+        };
+
+        match receipt.verify(image_id) {
+            Ok(()) => {
+                println!("✅ Proof verified for image ID {}", self.image_id);
+                println!("Journal: 0x{}", hex::encode(&receipt.journal.bytes));
+                Ok(())
+            }
+            Err(err) => {
+                println!("❌ Proof verification failed for image ID {}", self.image_id);
+                Err(err).context("local verification failed").exit_code(ExitCode::Verification)
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::time::Duration;
+
+use alloy::primitives::{Address, Bytes, B256, U256};
+use anyhow::Context;
+use boundless_market::contracts::{boundless_market::MarketError, FulfillmentData};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{completion, config::GlobalConfig};
+
+/// Command to wait until a request is fulfilled or expires.
+///
+/// Exits with a non-zero status if the request expires unfulfilled, or the timeout is reached.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestAwait {
+    /// The proof request identifier.
+    pub request_id: U256,
+    /// Timestamp at which the request expires.
+    ///
+    /// If not provided, the request is fetched from the order stream or the chain to find it.
+    #[clap(long)]
+    pub expires_at: Option<u64>,
+    /// Number of seconds between checks of the request status.
+    #[clap(long, default_value = "5")]
+    pub interval: u64,
+    /// Maximum number of seconds to wait. If not set, waits until the request expires.
+    #[clap(long)]
+    pub timeout: Option<u64>,
+    /// Number of blocks the fulfillment must be buried under before it is reported.
+    #[clap(long, default_value = "0")]
+    pub confirmations: u64,
+}
+
+/// Fulfillment of the awaited request, as printed with `--json`.
+#[derive(Serialize)]
+struct AwaitedFulfillment {
+    request_id: U256,
+    prover: Address,
+    block_number: u64,
+    tx_hash: B256,
+    fulfillment_data: FulfillmentData,
+    seal: Bytes,
+}
+
+impl RequestAwait {
+    /// Run the [RequestAwait] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let request_id = self.request_id;
+
+        let expires_at = match self.expires_at {
+            Some(expires_at) => expires_at,
+            None => {
+                let (request, _) = client
+                    .fetch_proof_request(request_id, None, None)
+                    .await
+                    .with_context(|| format!("Failed to fetch request 0x{request_id:x}"))?;
+                request.expires_at()
+            }
+        };
+        let output = global_config.output_format();
+        tracing::info!(
+            "Waiting for request 0x{request_id:x} to be fulfilled; it expires {}",
+            output.timestamp(expires_at)
+        );
+
+        let wait = client.boundless_market.wait_for_confirmed_fulfillment(
+            request_id,
+            Duration::from_secs(self.interval),
+            expires_at,
+            self.confirmations,
+        );
+        let result = match self.timeout {
+            Some(timeout) => {
+                tokio::time::timeout(Duration::from_secs(timeout), wait).await.with_context(
+                    || format!("Timed out after {timeout}s waiting for request 0x{request_id:x}"),
+                )?
+            }
+            None => wait.await,
+        };
+        let confirmed = match result {
+            Ok(confirmed) => confirmed,
+            Err(err @ MarketError::RequestHasExpired(_)) => {
+                return Err(err).context(format!(
+                    "Request 0x{request_id:x} expired unfulfilled {}",
+                    output.timestamp(expires_at)
+                ))
+            }
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to wait for request 0x{request_id:x}"))
+            }
+        };
+        completion::record(&[request_id], &[confirmed.prover]);
+
+        let fulfillment = AwaitedFulfillment {
+            request_id,
+            prover: confirmed.prover,
+            block_number: confirmed.block_number,
+            tx_hash: confirmed.tx_hash,
+            fulfillment_data: confirmed.fulfillment.data()?,
+            seal: confirmed.fulfillment.seal,
+        };
+        output.print(&fulfillment, || {
+            format!(
+                "✅ Request 0x{request_id:x} fulfilled by {} in block {} (tx {})\nFulfillment data: {}\nSeal: {}",
+                fulfillment.prover,
+                fulfillment.block_number,
+                fulfillment.tx_hash,
+                serde_json::to_string_pretty(&fulfillment.fulfillment_data).unwrap_or_default(),
+                fulfillment.seal
+            )
+        })
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::{collections::HashMap, fs::File, io::Read, path::PathBuf};
+
+use alloy::{
+    primitives::{Address, Bytes, B256, U256},
+    providers::Provider,
+};
+use anyhow::{bail, ensure, Context};
+use boundless_market::contracts::{FulfillmentData, ProofRequest};
+use clap::Args;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use risc0_zkvm::{sha::Digestible, Journal};
+use serde::{Deserialize, Serialize};
+
+use crate::config::GlobalConfig;
+
+/// Version of the proof bundle format written by this CLI.
+pub const PROOF_BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const REQUEST_FILE: &str = "request.yaml";
+const JOURNAL_FILE: &str = "journal.bin";
+const SEAL_FILE: &str = "seal.bin";
+const VERIFY_SCRIPT_FILE: &str = "verify.sh";
+
+/// Command to export the proof of a fulfilled request as a portable bundle.
+///
+/// The bundle is a gzipped tarball containing the request, journal, seal, and a manifest of the
+/// values needed to verify the proof, along with a script that verifies it using `cast`. It can be
+/// checked with `boundless proving verify-bundle` against any RPC endpoint of the same chain.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestExportProof {
+    /// The proof request identifier.
+    pub request_id: U256,
+    /// Path to write the bundle to.
+    #[clap(long, default_value = "bundle.tar.gz")]
+    pub out: PathBuf,
+}
+
+/// Values needed to verify the proof in a [ProofBundle].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[non_exhaustive]
+pub struct ProofBundleManifest {
+    /// Version of the bundle format.
+    pub version: u32,
+    /// EIP-155 chain ID of the network the request was fulfilled on.
+    pub chain_id: u64,
+    /// ID of the fulfilled request.
+    pub request_id: U256,
+    /// EIP-712 digest of the fulfilled request.
+    pub request_digest: B256,
+    /// Image ID of the program, if delivered with the fulfillment.
+    pub image_id: Option<B256>,
+    /// SHA-256 digest of the journal, if delivered with the fulfillment.
+    pub journal_digest: Option<B256>,
+    /// Digest of the receipt claim the seal proves.
+    pub claim_digest: B256,
+    /// Seal, as sent to the verifier contract, including the selector.
+    pub seal: Bytes,
+    /// Address of the BoundlessMarket contract.
+    pub boundless_market_address: Address,
+    /// Address of the RiscZeroSetVerifier contract.
+    pub set_verifier_address: Address,
+    /// Address of the RiscZeroVerifierRouter contract.
+    pub verifier_router_address: Option<Address>,
+}
+
+/// A portable bundle of a request and the proof that fulfilled it.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ProofBundle {
+    /// Values needed to verify the proof.
+    pub manifest: ProofBundleManifest,
+    /// The fulfilled request.
+    pub request: ProofRequest,
+    /// Journal of the proof, if delivered with the fulfillment.
+    pub journal: Option<Bytes>,
+}
+
+impl ProofBundle {
+    /// Write the bundle to a gzipped tarball at the given path.
+    pub fn write(&self, path: &PathBuf) -> anyhow::Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("failed to create bundle {}", path.display()))?;
+        let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+        append(&mut tar, MANIFEST_FILE, &serde_json::to_vec_pretty(&self.manifest)?, 0o644)?;
+        append(&mut tar, REQUEST_FILE, serde_yaml::to_string(&self.request)?.as_bytes(), 0o644)?;
+        append(&mut tar, SEAL_FILE, &self.manifest.seal, 0o644)?;
+        if let Some(journal) = &self.journal {
+            append(&mut tar, JOURNAL_FILE, journal, 0o644)?;
+        }
+        append(&mut tar, VERIFY_SCRIPT_FILE, self.verify_script().as_bytes(), 0o755)?;
+
+        tar.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    /// Read a bundle from a gzipped tarball at the given path.
+    pub fn read(path: &PathBuf) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open bundle {}", path.display()))?;
+        let mut archive = tar::Archive::new(GzDecoder::new(file));
+        let mut files = HashMap::new();
+        for entry in archive.entries().context("failed to read bundle")? {
+            let mut entry = entry.context("failed to read bundle entry")?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents)?;
+            files.insert(name, contents);
+        }
+
+        let manifest: ProofBundleManifest =
+            serde_json::from_slice(files.get(MANIFEST_FILE).context("bundle has no manifest")?)
+                .context("failed to parse bundle manifest")?;
+        ensure!(
+            manifest.version == PROOF_BUNDLE_VERSION,
+            "unsupported bundle version {}; expected {PROOF_BUNDLE_VERSION}",
+            manifest.version
+        );
+        let request =
+            serde_yaml::from_slice(files.get(REQUEST_FILE).context("bundle has no request")?)
+                .context("failed to parse bundle request")?;
+        let journal = files.remove(JOURNAL_FILE).map(Bytes::from);
+
+        Ok(Self { manifest, request, journal })
+    }
+
+    /// Fulfillment data of the proof, as delivered onchain.
+    pub fn fulfillment_data(&self) -> FulfillmentData {
+        match (&self.manifest.image_id, &self.journal) {
+            (Some(image_id), Some(journal)) => FulfillmentData::from_image_id_and_journal(
+                <[u8; 32]>::from(*image_id),
+                journal.clone(),
+            ),
+            _ => FulfillmentData::None,
+        }
+    }
+
+    /// Shell script that verifies the seal against the verifier router using `cast`.
+    fn verify_script(&self) -> String {
+        let manifest = &self.manifest;
+        let router = manifest
+            .verifier_router_address
+            .map(|address| address.to_string())
+            .unwrap_or_else(|| "${VERIFIER_ADDRESS:?set VERIFIER_ADDRESS}".to_string());
+        let call = match (&manifest.image_id, &manifest.journal_digest) {
+            (Some(image_id), Some(journal_digest)) => format!(
+                "cast call \"$VERIFIER\" 'verify(bytes,bytes32,bytes32)' \\\n    {} \\\n    {image_id} \\\n    {journal_digest} \\\n    --rpc-url \"$RPC_URL\"",
+                manifest.seal
+            ),
+            _ => format!(
+                "cast call \"$VERIFIER\" 'verifyIntegrity((bytes,bytes32))' \\\n    '({},{})' \\\n    --rpc-url \"$RPC_URL\"",
+                manifest.seal, manifest.claim_digest
+            ),
+        };
+        format!(
+            r#"#!/usr/bin/env bash
+# Verifies the proof of request {request_id:#x} on chain {chain_id} using Foundry's cast.
+# The call reverts if the proof is invalid. Requires RPC_URL to point at chain {chain_id}.
+set -euo pipefail
+
+: "${{RPC_URL:?set RPC_URL to an RPC endpoint of chain {chain_id}}}"
+[ "$(cast chain-id --rpc-url "$RPC_URL")" = "{chain_id}" ] || {{ echo "RPC_URL is not chain {chain_id}" >&2; exit 1; }}
+VERIFIER="{router}"
+
+{call}
+echo "Proof of request {request_id:#x} verified"
+"#,
+            request_id = manifest.request_id,
+            chain_id = manifest.chain_id,
+        )
+    }
+}
+
+impl RequestExportProof {
+    /// Run the [RequestExportProof] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let request_id = self.request_id;
+
+        let fulfillment =
+            client.boundless_market.get_request_fulfillment(request_id).await.with_context(
+                || format!("failed to fetch fulfillment of request 0x{request_id:x}"),
+            )?;
+        let (request, _) = client
+            .fetch_proof_request(request_id, None, Some(fulfillment.requestDigest))
+            .await
+            .with_context(|| format!("failed to fetch request 0x{request_id:x}"))?;
+
+        let (image_id, journal) = match fulfillment.data()? {
+            FulfillmentData::ImageIdAndJournal(image_id, journal) => {
+                (Some(B256::from(<[u8; 32]>::from(image_id))), Some(journal))
+            }
+            FulfillmentData::None => (None, None),
+        };
+        let journal_digest = journal
+            .as_ref()
+            .map(|journal| B256::from(<[u8; 32]>::from(Journal::new(journal.to_vec()).digest())));
+
+        let bundle = ProofBundle {
+            manifest: ProofBundleManifest {
+                version: PROOF_BUNDLE_VERSION,
+                chain_id: client.provider().get_chain_id().await?,
+                request_id,
+                request_digest: fulfillment.requestDigest,
+                image_id,
+                journal_digest,
+                claim_digest: fulfillment.claimDigest,
+                seal: fulfillment.seal,
+                boundless_market_address: client.deployment.boundless_market_address,
+                set_verifier_address: client.deployment.set_verifier_address,
+                verifier_router_address: client.deployment.verifier_router_address,
+            },
+            request,
+            journal,
+        };
+        if bundle.manifest.verifier_router_address.is_none() {
+            tracing::warn!("No verifier router address is configured; the bundled verification script will require VERIFIER_ADDRESS to be set");
+        }
+        bundle.write(&self.out)?;
+
+        println!("✅ Exported proof of request 0x{request_id:x} to {}", self.out.display());
+        Ok(())
+    }
+}
+
+fn append<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+    mode: u32,
+) -> anyhow::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(mode);
+    header.set_cksum();
+    tar.append_data(&mut header, path, data).with_context(|| format!("failed to write {path}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boundless_market::contracts::{Offer, Predicate, RequestId, RequestInput, Requirements};
+    use risc0_zkvm::sha::Digest;
+
+    #[test]
+    fn test_proof_bundle_roundtrip() {
+        let image_id = Digest::from([1u32; 8]);
+        let journal = Bytes::from(vec![0x41; 4]);
+        let request = ProofRequest::new(
+            RequestId::new(Address::repeat_byte(1), 0),
+            Requirements::new(Predicate::prefix_match(image_id, Bytes::default())),
+            "https://example.com/program",
+            RequestInput::builder().build_inline().unwrap(),
+            Offer::default(),
+        );
+        let bundle = ProofBundle {
+            manifest: ProofBundleManifest {
+                version: PROOF_BUNDLE_VERSION,
+                chain_id: 1,
+                request_id: request.id,
+                request_digest: B256::repeat_byte(2),
+                image_id: Some(B256::from(<[u8; 32]>::from(image_id))),
+                journal_digest: Some(B256::from(<[u8; 32]>::from(
+                    Journal::new(journal.to_vec()).digest(),
+                ))),
+                claim_digest: B256::repeat_byte(3),
+                seal: Bytes::from(vec![0xff; 36]),
+                boundless_market_address: Address::repeat_byte(4),
+                set_verifier_address: Address::repeat_byte(5),
+                verifier_router_address: None,
+            },
+            request: request.clone(),
+            journal: Some(journal.clone()),
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.tar.gz");
+        bundle.write(&path).unwrap();
+        let read = ProofBundle::read(&path).unwrap();
+
+        assert_eq!(read.manifest, bundle.manifest);
+        assert_eq!(read.request, request);
+        assert_eq!(read.journal, Some(journal.clone()));
+        assert_eq!(
+            read.fulfillment_data(),
+            FulfillmentData::from_image_id_and_journal(image_id, journal)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use anyhow::Context;
+use boundless_market::order_stream_client::RequestMetadata;
+use clap::Args;
+
+use crate::{
+    config::GlobalConfig,
+    table::{Cell, Style, Table},
+    tags::TagStore,
+};
+
+/// Number of orders fetched per page when filtering by tag.
+const TAG_PAGE_SIZE: u64 = 100;
+
+/// Command to list requests submitted to the order stream, with any attached metadata and local
+/// tags.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestList {
+    /// Order stream id of the first order to list.
+    #[clap(long, default_value = "0")]
+    pub offset: u64,
+    /// Maximum number of orders to list.
+    #[clap(long, default_value = "20")]
+    pub limit: u64,
+    /// Only list orders whose request has this local tag, see `request tag`.
+    ///
+    /// Orders are filtered before the limit is applied, paging through the order stream from
+    /// the offset until enough tagged orders are found.
+    #[clap(long)]
+    pub tag: Option<String>,
+}
+
+impl RequestList {
+    /// Run the [RequestList] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let order_stream = client
+            .offchain_client
+            .as_ref()
+            .context("order stream URL is not configured for this deployment")?;
+        let tags = TagStore::load_default();
+        let orders = match &self.tag {
+            None => order_stream.list_orders(self.offset, self.limit).await?,
+            Some(tag) => {
+                let mut tagged = Vec::new();
+                let mut offset = self.offset;
+                while (tagged.len() as u64) < self.limit {
+                    let page = order_stream.list_orders(offset, TAG_PAGE_SIZE).await?;
+                    let Some(last) = page.last() else {
+                        break;
+                    };
+                    offset = last.id as u64 + 1;
+                    tagged.extend(
+                        page.into_iter().filter(|data| tags.has_tag(data.order.request.id, tag)),
+                    );
+                }
+                tagged.truncate(self.limit as usize);
+                tagged
+            }
+        };
+        let output = global_config.output_format();
+        let mut table = Table::new(["id", "request id", "submitted", "app", "tags", "metadata"])
+            .align_right([0]);
+        for data in orders {
+            let metadata = data.order.metadata.map(|signed| signed.metadata).unwrap_or_default();
+            let fields = format_metadata(&metadata);
+            table.push([
+                data.id.to_string().into(),
+                format!("0x{:x}", data.order.request.id).into(),
+                output.timestamp(data.created_at.timestamp() as u64).to_string().into(),
+                or_placeholder(metadata.app_name),
+                or_placeholder(tags.get(data.order.request.id).map(|tags| tags.to_string())),
+                or_placeholder(fields),
+            ]);
+        }
+        if table.is_empty() {
+            if !output.quiet {
+                println!("No orders found");
+            }
+            return Ok(());
+        }
+        println!("{}", output.table(&table));
+        Ok(())
+    }
+}
+
+/// Format the trace ID and labels of the metadata as `key=value` pairs, if any.
+fn format_metadata(metadata: &RequestMetadata) -> Option<String> {
+    let mut fields: Vec<String> =
+        metadata.labels.iter().map(|(key, value)| format!("{key}={value}")).collect();
+    if let Some(trace_id) = &metadata.trace_id {
+        fields.insert(0, format!("trace_id={trace_id}"));
+    }
+    (!fields.is_empty()).then(|| fields.join(" "))
+}
+
+/// A cell with the value, or a dimmed `-` if there is none.
+fn or_placeholder(value: Option<String>) -> Cell {
+    value.map(Cell::from).unwrap_or_else(|| Cell::styled("-", Style::Dim))
+}
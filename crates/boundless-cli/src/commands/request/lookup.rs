@@ -0,0 +1,88 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use alloy::primitives::{Address, Bytes, B256, U256};
+use anyhow::Context;
+use boundless_market::contracts::{boundless_market::RequestSource, ProofRequest};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{completion, config::GlobalConfig};
+
+/// Command to find a proof request by its EIP-712 digest.
+///
+/// Digests appear in fulfillments, logs, and errors. The request is found by scanning the market
+/// events backwards from --to-block, or the latest block. Requests submitted offchain are only
+/// found once they are locked.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestLookup {
+    /// EIP-712 digest of the request.
+    #[clap(long)]
+    pub request_digest: B256,
+    /// Lowest block to search. Defaults to the range configured for event queries.
+    #[clap(long)]
+    pub from_block: Option<u64>,
+    /// Highest block to search. Defaults to the latest block.
+    #[clap(long)]
+    pub to_block: Option<u64>,
+}
+
+/// Request found by its digest, as printed with `--json`.
+#[derive(Serialize)]
+struct FoundRequest {
+    request_id: U256,
+    client: Address,
+    submitted_onchain: bool,
+    tx_hash: B256,
+    block_number: u64,
+    client_signature: Bytes,
+    request: ProofRequest,
+}
+
+impl RequestLookup {
+    /// Run the [RequestLookup] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+        let located = client
+            .boundless_market
+            .get_request_by_digest(self.request_digest, self.from_block, self.to_block)
+            .await
+            .with_context(|| {
+                format!("Failed to find request with digest {}", self.request_digest)
+            })?;
+        completion::record(&[located.request.id], &[]);
+
+        let found = FoundRequest {
+            request_id: located.request.id,
+            client: located.request.client_address(),
+            submitted_onchain: located.source == RequestSource::Submitted,
+            tx_hash: located.tx_hash,
+            block_number: located.block_number,
+            client_signature: located.client_signature,
+            request: located.request,
+        };
+        let request_yaml = serde_yaml::to_string(&found.request)?;
+        global_config.output_format().print(&found, || {
+            let tx = if found.submitted_onchain {
+                "Submitted in tx"
+            } else {
+                "Submitted offchain; locked in tx"
+            };
+            format!(
+                "Request 0x{:x} from {}\n{tx} {} (block {})\n{request_yaml}",
+                found.request_id, found.client, found.tx_hash, found.block_number
+            )
+        })
+    }
+}
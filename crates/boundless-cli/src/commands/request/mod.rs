@@ -0,0 +1,39 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commands of the Boundless CLI for working with proof requests.
+
+mod await_fulfillment;
+mod export_proof;
+mod list;
+mod lookup;
+mod price_curve;
+mod reclaim;
+mod request_ref;
+mod resubmit;
+mod simulate_callback;
+mod tag;
+
+pub use await_fulfillment::RequestAwait;
+pub use export_proof::{
+    ProofBundle, ProofBundleManifest, RequestExportProof, PROOF_BUNDLE_VERSION,
+};
+pub use list::RequestList;
+pub use lookup::RequestLookup;
+pub use price_curve::RequestPriceCurve;
+pub use reclaim::RequestReclaim;
+pub use request_ref::RequestRef;
+pub use resubmit::RequestResubmit;
+pub use simulate_callback::RequestSimulateCallback;
+pub use tag::{RequestTag, RequestTagList};
@@ -0,0 +1,145 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use alloy::primitives::{utils::parse_ether, U256};
+use anyhow::Context;
+use boundless_market::contracts::ProofRequest;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    config::GlobalConfig,
+    format::{FormattedAmount, FormattedDuration},
+};
+
+/// Width, in characters, of the bar drawn for the max price.
+const BAR_WIDTH: usize = 40;
+
+/// Command to print the price curve of the offer in a request.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestPriceCurve {
+    /// Path to a YAML file containing the request.
+    pub yaml_request: PathBuf,
+    /// Number of intervals to sample across the ramp-up period.
+    #[clap(long, default_value = "10")]
+    pub points: u32,
+    /// Print when the offer reaches this price, in ether.
+    #[clap(long, value_parser = parse_ether)]
+    pub target_price: Option<U256>,
+}
+
+/// A point on the price curve, as printed with `--json`.
+#[derive(Serialize)]
+struct PricePoint {
+    offset: FormattedDuration,
+    timestamp: u64,
+    price: FormattedAmount,
+}
+
+/// The price curve of an offer, as printed with `--json`.
+#[derive(Serialize)]
+struct PriceCurve {
+    points: Vec<PricePoint>,
+    lock_deadline: FormattedDuration,
+    request_deadline: FormattedDuration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_price: Option<FormattedAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_offset: Option<FormattedDuration>,
+}
+
+impl RequestPriceCurve {
+    /// Run the [RequestPriceCurve] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let output = global_config.output_format();
+        let file = File::open(&self.yaml_request)
+            .with_context(|| format!("Failed to open request file at {:?}", self.yaml_request))?;
+        let request: ProofRequest = serde_yaml::from_reader(BufReader::new(file))
+            .context("Failed to parse request from YAML")?;
+        let offer = &request.offer;
+
+        let points = offer.price_curve(self.points)?;
+        let target_offset =
+            self.target_price.and_then(|target_price| offer.time_to_reach(target_price));
+
+        if output.json {
+            let curve = PriceCurve {
+                points: points
+                    .iter()
+                    .map(|&(timestamp, price)| PricePoint {
+                        offset: output.seconds(timestamp - offer.rampUpStart),
+                        timestamp,
+                        price: output.amount(price),
+                    })
+                    .collect(),
+                lock_deadline: output.seconds(offer.lockTimeout.into()),
+                request_deadline: output.seconds(offer.timeout.into()),
+                target_price: self.target_price.map(|price| output.amount(price)),
+                target_offset: target_offset.map(|offset| output.seconds(offset)),
+            };
+            return output.print(&curve, String::new);
+        }
+
+        // A rampUpStart of 0 is filled in at submission time, so offsets are the useful output.
+        if offer.rampUpStart == 0 {
+            println!("Bidding start is not set; times are relative to the start of the ramp-up");
+        }
+        println!(
+            "{:>12}  {:>12}  {:>32}",
+            "offset",
+            "timestamp",
+            format!("price ({})", output.units.symbol())
+        );
+        for (timestamp, price) in points {
+            let bar_len = if offer.maxPrice.is_zero() {
+                0
+            } else {
+                (price * U256::from(BAR_WIDTH) / offer.maxPrice).to::<usize>()
+            };
+            println!(
+                "{:>12}  {:>12}  {:>32}  {}",
+                output.seconds(timestamp - offer.rampUpStart).to_string(),
+                timestamp,
+                output.amount(price).to_string(),
+                "#".repeat(bar_len)
+            );
+        }
+        println!(
+            "Lock deadline: +{}, request deadline: +{}",
+            output.seconds(offer.lockTimeout.into()),
+            output.seconds(offer.timeout.into())
+        );
+
+        if let Some(target_price) = self.target_price {
+            match target_offset {
+                Some(offset) => println!(
+                    "Price reaches {} at +{} (timestamp {})",
+                    output.amount(target_price),
+                    output.seconds(offset),
+                    offer.rampUpStart + offset
+                ),
+                None => println!(
+                    "Price never reaches {}; max price is {}",
+                    output.amount(target_price),
+                    output.amount(offer.maxPrice)
+                ),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::primitives::U256;
+use anyhow::Context;
+use boundless_market::contracts::boundless_market::ReclaimOutcome;
+use clap::Args;
+
+use crate::config::GlobalConfig;
+
+/// Command to reclaim the payment escrowed for a request that expired unfulfilled.
+///
+/// The payment is credited back to the client's market balance by slashing the prover that
+/// locked the request. It can then be withdrawn with `account withdraw`.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestReclaim {
+    /// The proof request identifier.
+    pub request_id: U256,
+}
+
+impl RequestReclaim {
+    /// Run the [RequestReclaim] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client_with_signer().await?;
+        let request_id = self.request_id;
+
+        let outcome = client
+            .boundless_market
+            .reclaim_expired(request_id)
+            .await
+            .with_context(|| format!("Failed to reclaim request 0x{request_id:x}"))?;
+        match outcome {
+            ReclaimOutcome::Slashed { tx_hash, refunded: true, .. } => println!(
+                "✅ Reclaimed the payment for request 0x{request_id:x} to the client's market balance (tx {tx_hash})"
+            ),
+            ReclaimOutcome::Slashed { tx_hash, refunded: false, .. } => println!(
+                "Request 0x{request_id:x} was fulfilled after its lock deadline, so the prover was slashed without a refund (tx {tx_hash})"
+            ),
+            ReclaimOutcome::AlreadySlashed => println!(
+                "Request 0x{request_id:x} was already slashed; any refund is already in the client's market balance"
+            ),
+            ReclaimOutcome::NotLocked => println!(
+                "Request 0x{request_id:x} was never locked, so no funds were escrowed for it"
+            ),
+            ReclaimOutcome::Fulfilled => {
+                println!("Request 0x{request_id:x} was fulfilled; there is nothing to reclaim")
+            }
+            _ => println!("Nothing to reclaim for request 0x{request_id:x}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::{fmt, str::FromStr};
+
+use alloy::{
+    primitives::{B256, U256},
+    providers::Provider,
+};
+use anyhow::{bail, Context};
+use boundless_market::contracts::boundless_market::BoundlessMarketService;
+use url::Url;
+
+/// Reference to a proof request, given either by its ID or by the transaction that submitted it.
+///
+/// Parses from a request ID, a transaction hash, or a block explorer URL for the submission
+/// transaction (e.g. `https://etherscan.io/tx/0x...`). Request IDs are at most 49 hex digits long,
+/// so a 64 digit hex value is always taken to be a transaction hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestRef {
+    /// A request ID.
+    Id(U256),
+    /// Hash of the transaction that submitted the request onchain.
+    Tx(B256),
+}
+
+impl RequestRef {
+    /// Resolve the request ID, along with the submission transaction hash if one was given.
+    ///
+    /// A transaction hash is resolved by decoding the request submitted in that transaction.
+    pub async fn resolve<P: Provider>(
+        &self,
+        market: &BoundlessMarketService<P>,
+    ) -> anyhow::Result<(U256, Option<B256>)> {
+        match self {
+            Self::Id(request_id) => Ok((*request_id, None)),
+            Self::Tx(tx_hash) => {
+                let (request, _) = market
+                    .get_submitted_request_by_tx(*tx_hash)
+                    .await
+                    .with_context(|| format!("Failed to find a request submitted in {tx_hash}"))?;
+                tracing::debug!("Resolved transaction {tx_hash} to request 0x{:x}", request.id);
+                Ok((request.id, Some(*tx_hash)))
+            }
+        }
+    }
+}
+
+impl From<U256> for RequestRef {
+    fn from(request_id: U256) -> Self {
+        Self::Id(request_id)
+    }
+}
+
+impl fmt::Display for RequestRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(request_id) => write!(f, "0x{request_id:x}"),
+            Self::Tx(tx_hash) => write!(f, "tx {tx_hash}"),
+        }
+    }
+}
+
+impl FromStr for RequestRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.contains("://") {
+            let url = Url::parse(s).context("Invalid URL")?;
+            let mut segments = url.path_segments().into_iter().flatten();
+            while let Some(segment) = segments.next() {
+                if segment == "tx" {
+                    let hash = segments.next().unwrap_or_default();
+                    return Ok(Self::Tx(
+                        hash.parse()
+                            .with_context(|| format!("Invalid transaction hash in URL: {hash}"))?,
+                    ));
+                }
+            }
+            bail!("URL does not point to a transaction; expected a path like /tx/0x...");
+        }
+        if s.strip_prefix("0x").is_some_and(|hex| hex.len() == 64) {
+            return Ok(Self::Tx(s.parse().context("Invalid transaction hash")?));
+        }
+        Ok(Self::Id(s.parse().context("Invalid request ID or transaction hash")?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_request_ref() {
+        let tx_hash = B256::repeat_byte(0xab);
+        assert_eq!("0x1f".parse::<RequestRef>().unwrap(), RequestRef::Id(U256::from(0x1f)));
+        assert_eq!("31".parse::<RequestRef>().unwrap(), RequestRef::Id(U256::from(31)));
+        assert_eq!(tx_hash.to_string().parse::<RequestRef>().unwrap(), RequestRef::Tx(tx_hash));
+        assert_eq!(
+            format!("https://sepolia.etherscan.io/tx/{tx_hash}").parse::<RequestRef>().unwrap(),
+            RequestRef::Tx(tx_hash)
+        );
+        assert_eq!(
+            format!("https://basescan.org/tx/{tx_hash}#eventlog").parse::<RequestRef>().unwrap(),
+            RequestRef::Tx(tx_hash)
+        );
+        assert!("https://etherscan.io/address/0x1234".parse::<RequestRef>().is_err());
+        assert!("https://etherscan.io/tx/0x1234".parse::<RequestRef>().is_err());
+        assert!("not-an-id".parse::<RequestRef>().is_err());
+    }
+}
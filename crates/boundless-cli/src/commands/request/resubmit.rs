@@ -0,0 +1,162 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use alloy::primitives::U256;
+use anyhow::{bail, Context};
+use boundless_market::contracts::{Offer, ProofRequest, RequestStatus};
+use clap::Args;
+
+use crate::{completion, config::GlobalConfig};
+
+/// Command to resubmit a request whose offer did not clear, with a fresh ID and a higher price.
+///
+/// The original request is not cancelled, as the market has no way to withdraw a submitted
+/// request. It stays valid until it expires, so it can still be locked or fulfilled unless it
+/// already expired.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestResubmit {
+    /// ID of the request to resubmit, fetched from the order stream or the chain.
+    #[clap(long, conflicts_with = "yaml_request", required_unless_present = "yaml_request")]
+    pub request_id: Option<U256>,
+    /// Path to a YAML file containing the request to resubmit.
+    #[clap(long)]
+    pub yaml_request: Option<PathBuf>,
+    /// Percentage by which to raise the min and max price of the offer.
+    #[clap(long, default_value = "10")]
+    pub price_bump: u32,
+    /// Number of seconds from now until bidding starts on the new request.
+    #[clap(long, default_value = "30")]
+    pub bidding_start_delay: u64,
+    /// Submit the new request offchain via the order stream.
+    #[clap(short, long)]
+    pub offchain: bool,
+    /// Resubmit even if the original request is locked by a prover.
+    #[clap(long)]
+    pub force: bool,
+}
+
+impl RequestResubmit {
+    /// Run the [RequestResubmit] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client_with_signer().await?;
+
+        let original = match (&self.request_id, &self.yaml_request) {
+            (Some(request_id), _) => {
+                let (request, _) = client
+                    .fetch_proof_request(*request_id, None, None)
+                    .await
+                    .with_context(|| format!("Failed to fetch request 0x{request_id:x}"))?;
+                request
+            }
+            (None, Some(path)) => {
+                let file = File::open(path)
+                    .with_context(|| format!("Failed to open request file at {path:?}"))?;
+                serde_yaml::from_reader(BufReader::new(file))
+                    .context("Failed to parse request from YAML")?
+            }
+            (None, None) => bail!("Either --request-id or --yaml-request must be provided"),
+        };
+
+        // A request from a YAML file may never have been submitted, leaving no status to check.
+        if original.offer.rampUpStart != 0 {
+            let status = client
+                .boundless_market
+                .get_status(original.id, Some(original.expires_at()))
+                .await?;
+            match status {
+                RequestStatus::Fulfilled => {
+                    bail!("Request 0x{:x} is already fulfilled", original.id)
+                }
+                RequestStatus::Locked if !self.force => bail!(
+                    "Request 0x{:x} is locked by a prover; use --force to resubmit anyway",
+                    original.id
+                ),
+                _ => tracing::debug!("Request 0x{:x} status: {:?}", original.id, status),
+            }
+        }
+
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+        let request_id = client.boundless_market.request_id_from_rand().await?;
+        let request =
+            resubmission(&original, request_id, now + self.bidding_start_delay, self.price_bump);
+
+        let (request_id, _) = if self.offchain {
+            client.submit_request_offchain(&request).await?
+        } else {
+            client.submit_request_onchain(&request).await?
+        };
+
+        println!("✅ Resubmitted request 0x{:x} as 0x{request_id:x}", original.id);
+        let output = global_config.output_format();
+        println!(
+            "   Price: {} - {} (was {} - {})",
+            output.amount(request.offer.minPrice),
+            output.amount(request.offer.maxPrice),
+            output.amount(original.offer.minPrice),
+            output.amount(original.offer.maxPrice)
+        );
+        println!("   Bidding starts {}", output.timestamp(request.offer.rampUpStart));
+        completion::record(&[request_id], &[]);
+        Ok(())
+    }
+}
+
+/// Clone `original` with a new ID and bidding start, raising its prices by `price_bump` percent.
+fn resubmission(
+    original: &ProofRequest,
+    request_id: U256,
+    bidding_start: u64,
+    price_bump: u32,
+) -> ProofRequest {
+    let bump = |price: U256| price * U256::from(100 + price_bump) / U256::from(100);
+    ProofRequest {
+        id: request_id,
+        offer: Offer {
+            minPrice: bump(original.offer.minPrice),
+            maxPrice: bump(original.offer.maxPrice),
+            rampUpStart: bidding_start,
+            ..original.offer.clone()
+        },
+        ..original.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resubmission_bumps_prices() {
+        let original = ProofRequest {
+            offer: Offer {
+                minPrice: U256::from(1000),
+                maxPrice: U256::from(2000),
+                rampUpStart: 10,
+                timeout: 300,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let request = resubmission(&original, U256::from(7), 100, 15);
+        assert_eq!(request.id, U256::from(7));
+        assert_eq!(request.offer.minPrice, U256::from(1150));
+        assert_eq!(request.offer.maxPrice, U256::from(2300));
+        assert_eq!(request.offer.rampUpStart, 100);
+        assert_eq!(request.offer.timeout, 300);
+        assert_eq!(request.requirements, original.requirements);
+    }
+}
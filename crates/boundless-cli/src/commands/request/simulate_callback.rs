@@ -0,0 +1,193 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use alloy::{
+    primitives::{Address, Bytes, B256, U256},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    sol,
+    sol_types::{decode_revert_reason, SolCall},
+    transports::{RpcError, TransportErrorKind},
+};
+use anyhow::{anyhow, bail, Context};
+use boundless_market::contracts::{FulfillmentData, Predicate, ProofRequest};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    config::GlobalConfig,
+    exit_code::{ExitCode, WithExitCode},
+};
+
+sol! {
+    interface IBoundlessMarketCallback {
+        function handleProof(bytes32 imageId, bytes calldata journal, bytes calldata seal) external;
+    }
+}
+
+/// Command to simulate the callback of a request, as the market would invoke it on fulfillment.
+///
+/// The callback is called with `eth_call` from the market address, with the gas limit of the
+/// request, so integrators can check their callback contracts before paying for a real proof.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestSimulateCallback {
+    /// ID of the request, fetched from the order stream or the chain.
+    #[clap(long, conflicts_with = "yaml_request", required_unless_present = "yaml_request")]
+    pub request_id: Option<U256>,
+    /// Path to a YAML file containing the request.
+    #[clap(long)]
+    pub yaml_request: Option<PathBuf>,
+    /// Path to the journal to deliver to the callback.
+    ///
+    /// Defaults to the journal of the fulfillment, if the request is fulfilled.
+    #[clap(long)]
+    pub journal_file: Option<PathBuf>,
+    /// Hex-encoded seal to deliver to the callback.
+    ///
+    /// Defaults to the seal of the fulfillment if the request is fulfilled, and empty otherwise.
+    /// Callbacks that verify the seal, such as those built on BoundlessMarketCallback, revert
+    /// on a seal that does not prove the journal.
+    #[clap(long)]
+    pub seal: Option<Bytes>,
+    /// Image ID to deliver to the callback.
+    ///
+    /// Defaults to the image ID of the request predicate. Required for claim digest predicates,
+    /// which do not include it.
+    #[clap(long)]
+    pub image_id: Option<B256>,
+}
+
+/// Outcome of a simulated callback, as printed with `--json`.
+#[derive(Serialize)]
+struct SimulatedCallback {
+    callback: Address,
+    gas_limit: u64,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revert_reason: Option<String>,
+}
+
+impl RequestSimulateCallback {
+    /// Run the [RequestSimulateCallback] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let client = global_config.build_client().await?;
+
+        let request: ProofRequest = match (&self.request_id, &self.yaml_request) {
+            (Some(request_id), _) => {
+                let (request, _) = client
+                    .fetch_proof_request(*request_id, None, None)
+                    .await
+                    .with_context(|| format!("Failed to fetch request 0x{request_id:x}"))?;
+                request
+            }
+            (None, Some(path)) => {
+                let file = File::open(path)
+                    .with_context(|| format!("Failed to open request file at {path:?}"))?;
+                serde_yaml::from_reader(BufReader::new(file))
+                    .context("Failed to parse request from YAML")?
+            }
+            (None, None) => bail!("Either --request-id or --yaml-request must be provided"),
+        };
+        let Some(callback) = request.requirements.callback.as_option() else {
+            bail!("Request 0x{:x} has no callback", request.id);
+        };
+        let predicate = Predicate::try_from(request.requirements.predicate.clone())?;
+
+        // Use the delivered proof of a fulfilled request, unless overridden.
+        let mut fulfillment = None;
+        if self.request_id.is_some() && client.boundless_market.is_fulfilled(request.id).await? {
+            let fill = client.boundless_market.get_request_fulfillment(request.id).await?;
+            if let FulfillmentData::ImageIdAndJournal(_, journal) = fill.data()? {
+                fulfillment = Some((journal, fill.seal));
+            }
+        }
+
+        let journal = match (&self.journal_file, &fulfillment) {
+            (Some(path), _) => Bytes::from(
+                std::fs::read(path)
+                    .with_context(|| format!("Failed to read journal at {path:?}"))?,
+            ),
+            (None, Some((journal, _))) => journal.clone(),
+            (None, None) => bail!("--journal-file is required unless the request is fulfilled"),
+        };
+        let seal =
+            self.seal.clone().or_else(|| fulfillment.map(|(_, seal)| seal)).unwrap_or_default();
+        let image_id = match (self.image_id, predicate.image_id()) {
+            (Some(image_id), _) => image_id,
+            (None, Some(image_id)) => B256::from(<[u8; 32]>::from(image_id)),
+            (None, None) => bail!("--image-id is required for claim digest predicates"),
+        };
+
+        let fulfillment_data =
+            FulfillmentData::from_image_id_and_journal(<[u8; 32]>::from(image_id), journal.clone());
+        if predicate.eval(&fulfillment_data).is_none() {
+            tracing::warn!(
+                "The journal does not satisfy the request predicate; the request could not be fulfilled with it"
+            );
+        }
+
+        let gas_limit = callback.gasLimit.to::<u64>();
+        let call = IBoundlessMarketCallback::handleProofCall { imageId: image_id, journal, seal };
+        let tx = TransactionRequest::default()
+            .from(client.deployment.boundless_market_address)
+            .to(callback.addr)
+            .input(call.abi_encode().into())
+            .gas_limit(gas_limit);
+
+        let provider = client.provider();
+        let outcome = match provider.call(tx.clone()).await {
+            Ok(_) => SimulatedCallback {
+                callback: callback.addr,
+                gas_limit,
+                success: true,
+                gas_used: Some(provider.estimate_gas(tx).await?),
+                revert_reason: None,
+            },
+            Err(err) => SimulatedCallback {
+                callback: callback.addr,
+                gas_limit,
+                success: false,
+                gas_used: None,
+                revert_reason: Some(revert_reason(&err)),
+            },
+        };
+
+        global_config.output_format().print(&outcome, || match &outcome.revert_reason {
+            None => format!(
+                "✅ Callback {} succeeded using {} of {gas_limit} gas",
+                outcome.callback,
+                outcome.gas_used.unwrap_or_default()
+            ),
+            Some(reason) => format!("❌ Callback {} reverted: {reason}", outcome.callback),
+        })?;
+        if !outcome.success {
+            return Err(anyhow!("Callback simulation of request 0x{:x} reverted", request.id))
+                .exit_code(ExitCode::Revert);
+        }
+        Ok(())
+    }
+}
+
+/// Decode the revert reason of a failed call, falling back to the error message.
+fn revert_reason(err: &RpcError<TransportErrorKind>) -> String {
+    err.as_error_resp()
+        .and_then(|payload| payload.as_revert_data())
+        .and_then(|data| decode_revert_reason(&data))
+        .unwrap_or_else(|| err.to_string())
+}
@@ -0,0 +1,131 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy::primitives::U256;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    config::GlobalConfig,
+    tags::{RequestTags, TagStore},
+};
+
+/// Command to attach local tags and a note to a request.
+///
+/// Tags and notes are kept in `~/.boundless/tags.json`, or `$BOUNDLESS_TAG_STORE`, and shown by
+/// `request list` and `request status`. Without any option, prints the tags of the request.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestTag {
+    /// The proof request identifier.
+    pub request_id: U256,
+    /// Tag to add to the request. May be repeated.
+    #[clap(long)]
+    pub tag: Vec<String>,
+    /// Tag to remove from the request. May be repeated.
+    #[clap(long)]
+    pub remove_tag: Vec<String>,
+    /// Note to attach to the request, replacing any previous note. An empty note removes it.
+    #[clap(long)]
+    pub note: Option<String>,
+    /// Remove all tags and the note of the request.
+    #[clap(long, conflicts_with_all = ["tag", "remove_tag", "note"])]
+    pub clear: bool,
+}
+
+impl RequestTag {
+    /// Run the [RequestTag] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let path = TagStore::require_default_path()?;
+        let modified = self.clear
+            || !self.tag.is_empty()
+            || !self.remove_tag.is_empty()
+            || self.note.is_some();
+        let store = if modified {
+            TagStore::update(&path, |store| {
+                let entry = store.entry(self.request_id);
+                if self.clear {
+                    *entry = RequestTags::default();
+                }
+                entry.tags.extend(self.tag.iter().cloned());
+                for tag in &self.remove_tag {
+                    entry.tags.remove(tag);
+                }
+                if let Some(note) = &self.note {
+                    entry.note = Some(note.clone()).filter(|note| !note.is_empty());
+                }
+                store.prune();
+                Ok(store.clone())
+            })?
+        } else {
+            TagStore::load(&path)?
+        };
+
+        let tags = store.get(self.request_id).cloned().unwrap_or_default();
+        let output = TaggedRequest { request_id: format!("0x{:x}", self.request_id), tags };
+        global_config.output_format().print(&output, || {
+            if output.tags.is_empty() {
+                format!("Request {} has no tags", output.request_id)
+            } else {
+                format!("Request {}: {}", output.request_id, output.tags)
+            }
+        })
+    }
+}
+
+/// Command to list the requests with local tags or notes.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct RequestTagList {
+    /// Only list requests with this tag.
+    #[clap(long)]
+    pub tag: Option<String>,
+}
+
+impl RequestTagList {
+    /// Run the [RequestTagList] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let store = TagStore::load(&TagStore::require_default_path()?)?;
+        let requests: Vec<TaggedRequest> = store
+            .requests
+            .into_iter()
+            .filter(|(_, tags)| self.tag.as_ref().is_none_or(|tag| tags.tags.contains(tag)))
+            .map(|(request_id, tags)| TaggedRequest { request_id, tags })
+            .collect();
+
+        global_config.output_format().print(&requests, || {
+            if requests.is_empty() {
+                return "No tagged requests found".to_string();
+            }
+            let mut text = format!("{:<66}  {:<24}  {}", "request id", "tags", "note");
+            for request in &requests {
+                let tags = request.tags.tags.iter().cloned().collect::<Vec<_>>().join(",");
+                text.push_str(&format!(
+                    "\n{:<66}  {:<24}  {}",
+                    request.request_id,
+                    tags,
+                    request.tags.note.as_deref().unwrap_or("-")
+                ));
+            }
+            text
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct TaggedRequest {
+    request_id: String,
+    #[serde(flatten)]
+    tags: RequestTags,
+}
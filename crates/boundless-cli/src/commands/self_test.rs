@@ -0,0 +1,352 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt,
+    future::Future,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use alloy::{
+    network::EthereumWallet, node_bindings::Anvil, providers::ProviderBuilder,
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{bail, ensure, Context, Result};
+use boundless_market::{
+    contracts::FulfillmentData,
+    deployments::{bootstrap, BootstrapParams, VerifierKind},
+    Client,
+};
+use clap::Args;
+use guest_assessor::{ASSESSOR_GUEST_ELF, ASSESSOR_GUEST_ID};
+use guest_set_builder::{SET_BUILDER_ELF, SET_BUILDER_ID};
+use guest_util::{ECHO_ELF, ECHO_ID};
+use risc0_ethereum_contracts::IRiscZeroVerifier;
+use risc0_zkvm::{
+    default_executor,
+    sha::{Digest, Digestible},
+    ExecutorEnv, Journal,
+};
+use serde::Serialize;
+use url::Url;
+
+use crate::{config::GlobalConfig, dev::fake_fulfill, is_dev_mode};
+
+/// Input of the request, which the echo guest commits to its journal unchanged.
+const INPUT: &[u8] = b"boundless self-test";
+
+/// Command to check the installation by running a request end-to-end against a local chain.
+///
+/// A local Anvil chain is started with the Boundless Market deployed behind a mock verifier, and
+/// the request is fulfilled with a fake receipt in dev mode, so no proof is generated and no
+/// funds or network access are needed. Requires `anvil` to be installed, and `RISC0_DEV_MODE=1`
+/// to be set.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct SelfTest {
+    /// Path to the anvil binary. Defaults to `anvil` on the PATH.
+    #[clap(long)]
+    pub anvil_path: Option<PathBuf>,
+}
+
+/// Stages of the self-test, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Stage {
+    Chain,
+    Deploy,
+    Execute,
+    Submit,
+    Fulfill,
+    Verify,
+}
+
+impl Stage {
+    const ALL: [Stage; 6] =
+        [Stage::Chain, Stage::Deploy, Stage::Execute, Stage::Submit, Stage::Fulfill, Stage::Verify];
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Chain => "chain",
+            Stage::Deploy => "deploy",
+            Stage::Execute => "execute",
+            Stage::Submit => "submit",
+            Stage::Fulfill => "fulfill",
+            Stage::Verify => "verify",
+        };
+        f.pad(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Fail,
+    Skipped,
+}
+
+/// Outcome of a stage, as printed with `--json`.
+#[derive(Debug, Serialize)]
+struct StageReport {
+    stage: Stage,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_secs: Option<f64>,
+}
+
+/// Outcomes of the stages run so far.
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+struct Report(Vec<StageReport>);
+
+impl Report {
+    /// Run a stage, recording whether it passed along with the detail it returns.
+    async fn stage<T>(
+        &mut self,
+        stage: Stage,
+        run: impl Future<Output = Result<(T, String)>>,
+    ) -> Result<T> {
+        let started = Instant::now();
+        let result = run.await;
+        let (status, detail) = match &result {
+            Ok((_, detail)) => (Status::Pass, detail.clone()),
+            Err(err) => (Status::Fail, format!("{err:#}")),
+        };
+        self.0.push(StageReport {
+            stage,
+            status,
+            detail: Some(detail),
+            elapsed_secs: Some(started.elapsed().as_secs_f64()),
+        });
+        result.map(|(value, _)| value).with_context(|| format!("{stage} stage failed"))
+    }
+
+    /// Mark the stages that did not run as skipped.
+    fn skip_remaining(&mut self) {
+        for stage in Stage::ALL {
+            if !self.0.iter().any(|report| report.stage == stage) {
+                self.0.push(StageReport {
+                    stage,
+                    status: Status::Skipped,
+                    detail: None,
+                    elapsed_secs: None,
+                });
+            }
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for report in &self.0 {
+            let symbol = match report.status {
+                Status::Pass => "✅",
+                Status::Fail => "❌",
+                Status::Skipped => "⏭️ ",
+            };
+            write!(f, "{symbol} {:<8}", report.stage)?;
+            match (&report.detail, report.elapsed_secs) {
+                (Some(detail), Some(secs)) => writeln!(f, " {detail} ({secs:.1}s)")?,
+                _ => writeln!(f, " skipped")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SelfTest {
+    /// Run the [SelfTest] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> Result<()> {
+        // Fetching the guests from file URLs and fulfilling with fake receipts require dev mode.
+        // It is read from the environment by the zkVM, so it cannot be enabled from here.
+        ensure!(
+            is_dev_mode(),
+            "self-test fulfills the request with a fake receipt, which requires RISC0_DEV_MODE=1"
+        );
+
+        let mut report = Report::default();
+        let result = self.run_stages(&mut report).await;
+        report.skip_remaining();
+        global_config.output_format().print(&report, || report.to_string())?;
+        result
+    }
+
+    async fn run_stages(&self, report: &mut Report) -> Result<()> {
+        let anvil = report
+            .stage(Stage::Chain, async {
+                let anvil = match &self.anvil_path {
+                    Some(path) => Anvil::at(path),
+                    None => Anvil::new(),
+                }
+                .try_spawn()
+                .context("failed to start anvil; is it installed?")?;
+                let detail = format!("started anvil at {}", anvil.endpoint());
+                Ok((anvil, detail))
+            })
+            .await?;
+        let signer = PrivateKeySigner::from(anvil.keys()[0].clone());
+
+        // The guests are written to files, as the market requires them to be fetchable by URL.
+        let programs = tempfile::tempdir()?;
+        let deployment = report
+            .stage(Stage::Deploy, async {
+                let set_builder_url =
+                    write_program(programs.path(), "set-builder.bin", SET_BUILDER_ELF)?;
+                let assessor_url =
+                    write_program(programs.path(), "assessor.bin", ASSESSOR_GUEST_ELF)?;
+                let provider = ProviderBuilder::new()
+                    .wallet(EthereumWallet::from(signer.clone()))
+                    .connect_http(anvil.endpoint_url());
+                let params = BootstrapParams::new(
+                    signer.address(),
+                    SET_BUILDER_ID,
+                    set_builder_url,
+                    ASSESSOR_GUEST_ID,
+                    assessor_url,
+                )
+                .with_verifier(VerifierKind::Mock);
+                let deployment = bootstrap(provider, params).await?;
+                let detail = format!(
+                    "deployed the market to {} with a mock verifier",
+                    deployment.boundless_market_address
+                );
+                Ok((deployment, detail))
+            })
+            .await?;
+
+        let cycles = report
+            .stage(Stage::Execute, async {
+                let env = ExecutorEnv::builder().write_slice(INPUT).build()?;
+                let session = default_executor().execute(env, ECHO_ELF)?;
+                ensure!(session.journal.bytes == INPUT, "journal does not match the input");
+                let cycles = session.segments.iter().map(|segment| 1 << segment.po2).sum::<u64>();
+                Ok((cycles, format!("executed the echo guest in {cycles} cycles")))
+            })
+            .await?;
+
+        let client = Client::builder()
+            .with_rpc_url(anvil.endpoint_url())
+            .with_deployment(deployment.clone())
+            .with_private_key(signer)
+            .build()
+            .await?;
+        let request_id = report
+            .stage(Stage::Submit, async {
+                let echo_url = write_program(programs.path(), "echo.bin", ECHO_ELF)?;
+                let request = client
+                    .new_request()
+                    .with_program(ECHO_ELF)
+                    .with_program_url(echo_url)?
+                    .with_stdin(INPUT)
+                    .with_cycles(cycles)
+                    .with_journal(Journal::new(INPUT.to_vec()));
+                let request = client.build_request(request).await?;
+                let (request_id, _) = client.submit_request_onchain(&request).await?;
+                Ok((request_id, format!("submitted request 0x{request_id:x} onchain")))
+            })
+            .await?;
+
+        report
+            .stage(Stage::Fulfill, async {
+                fake_fulfill(&client, request_id, None, None).await?;
+                Ok(((), "fulfilled the request with a fake receipt".to_string()))
+            })
+            .await?;
+
+        report
+            .stage(Stage::Verify, async {
+                let fulfillment =
+                    client.boundless_market.get_request_fulfillment(request_id).await?;
+                let FulfillmentData::ImageIdAndJournal(image_id, journal) = fulfillment.data()?
+                else {
+                    bail!("fulfillment does not include the image ID and journal");
+                };
+                ensure!(image_id == Digest::from(ECHO_ID), "fulfilled with unexpected image ID");
+                ensure!(&journal[..] == INPUT, "fulfilled journal does not match the input");
+                let router = deployment
+                    .verifier_router_address
+                    .context("deployment has no verifier router")?;
+                let verifier = IRiscZeroVerifier::new(router, client.provider());
+                let verify = |journal: &[u8]| {
+                    let journal_digest = Journal::new(journal.to_vec()).digest();
+                    verifier.verify(
+                        fulfillment.seal.clone(),
+                        <[u8; 32]>::from(image_id).into(),
+                        <[u8; 32]>::from(journal_digest).into(),
+                    )
+                };
+                verify(&journal).call().await.context("the verifier router rejected the seal")?;
+                // The mock verifier accepts fake receipts, but still checks that the seal commits
+                // to the claim, so it must reject the seal for any other journal.
+                ensure!(
+                    verify(b"tampered").call().await.is_err(),
+                    "the verifier router accepted the seal for another journal"
+                );
+                Ok((
+                    (),
+                    "verified the seal and journal with the verifier router and mock verifier"
+                        .to_string(),
+                ))
+            })
+            .await
+    }
+}
+
+/// Write a guest program to `dir`, returning its `file://` URL.
+fn write_program(dir: &Path, name: &str, program: &[u8]) -> Result<Url> {
+    let path = dir.join(name);
+    std::fs::write(&path, program)
+        .with_context(|| format!("failed to write program to {}", path.display()))?;
+    Url::from_file_path(&path).map_err(|_| anyhow::anyhow!("invalid program path {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut report = Report::default();
+        report.0.push(StageReport {
+            stage: Stage::Chain,
+            status: Status::Pass,
+            detail: Some("started anvil".to_string()),
+            elapsed_secs: Some(0.5),
+        });
+        report.0.push(StageReport {
+            stage: Stage::Deploy,
+            status: Status::Fail,
+            detail: Some("out of gas".to_string()),
+            elapsed_secs: Some(1.0),
+        });
+        report.skip_remaining();
+
+        let statuses: Vec<Status> = report.0.iter().map(|report| report.status).collect();
+        assert_eq!(statuses[..3], [Status::Pass, Status::Fail, Status::Skipped]);
+        assert_eq!(report.0.len(), Stage::ALL.len());
+
+        let text = report.to_string();
+        assert!(text.contains("❌ deploy   out of gas (1.0s)"), "{text}");
+        assert!(text.contains("verify   skipped"), "{text}");
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json[1]["status"], "fail");
+        assert_eq!(json[5]["stage"], "verify");
+    }
+}
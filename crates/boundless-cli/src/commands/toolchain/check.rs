@@ -0,0 +1,110 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use alloy::{primitives::FixedBytes, sol};
+use anyhow::bail;
+use boundless_market::contracts::Selector;
+use clap::Args;
+
+use super::{r0vm_version, ToolchainVersion};
+use crate::config::GlobalConfig;
+
+sol! {
+    #[sol(rpc)]
+    interface IRiscZeroVerifierRouter {
+        function getVerifier(bytes4 selector) external view returns (address);
+    }
+}
+
+/// Command to report the installed toolchain versions and check their compatibility.
+///
+/// The installed r0vm is compared against the version of the zkVM this CLI was built with. If an
+/// RPC URL is configured, the deployment's verifier router is also checked for a verifier of the
+/// Groth16 proofs this toolchain produces, so that mismatches are caught before a proof fails
+/// onchain verification.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ToolchainCheck {
+    /// Only check the local toolchain, without querying the deployment.
+    #[clap(long)]
+    pub offline: bool,
+}
+
+impl ToolchainCheck {
+    /// Run the [ToolchainCheck] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let cli = ToolchainVersion::cli();
+        println!("risc0-zkvm (CLI): {cli}");
+
+        let mut failures = 0;
+        match r0vm_version() {
+            Ok(installed) if installed.is_compatible_with(&cli) => {
+                println!("✅ r0vm: {installed}");
+            }
+            Ok(installed) => {
+                println!("❌ r0vm: {installed} is not compatible with risc0-zkvm {cli}");
+                failures += 1;
+            }
+            Err(err) => {
+                println!("❌ r0vm: {err}");
+                failures += 1;
+            }
+        }
+
+        if self.offline || global_config.rpc_url.is_none() {
+            if !self.offline {
+                println!("Skipping deployment checks; set --rpc-url or RPC_URL to enable them");
+            }
+        } else {
+            failures += check_deployment(global_config).await?;
+        }
+
+        if failures > 0 {
+            bail!(
+                "toolchain check failed; run `boundless toolchain install` to install a compatible r0vm"
+            );
+        }
+        println!("\nToolchain is compatible");
+        Ok(())
+    }
+}
+
+/// Check the deployment for verifiers of the proofs produced by this toolchain.
+///
+/// Returns the number of failed checks.
+async fn check_deployment(global_config: &GlobalConfig) -> anyhow::Result<usize> {
+    let client = global_config.build_client().await?;
+    let mut failures = 0;
+
+    let groth16 = Selector::groth16_latest();
+    match client.deployment.verifier_router_address {
+        Some(router) => {
+            let router = IRiscZeroVerifierRouter::new(router, client.provider());
+            let selector = FixedBytes::from(groth16 as u32);
+            match router.getVerifier(selector).call().await {
+                Ok(verifier) => println!("✅ Groth16 selector {selector}: verifier {verifier}"),
+                Err(err) => {
+                    println!("❌ Groth16 selector {selector} ({groth16:?}) is not routable: {err}");
+                    failures += 1;
+                }
+            }
+        }
+        None => println!("Skipping Groth16 selector check; deployment has no verifier router"),
+    }
+
+    match client.set_verifier.image_info().await {
+        Ok((image_id, _)) => println!("Set builder image ID: {image_id}"),
+        Err(err) => tracing::warn!("Failed to query set builder image ID: {err}"),
+    }
+    Ok(failures)
+}
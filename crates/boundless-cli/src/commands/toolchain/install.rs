@@ -0,0 +1,63 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use clap::Args;
+
+use super::{r0vm_version, ToolchainVersion};
+
+/// Command to install a version of r0vm with rzup and make it the default.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ToolchainInstall {
+    /// Version of r0vm to install.
+    ///
+    /// Defaults to the version of the zkVM this CLI was built with.
+    #[clap(long)]
+    pub version: Option<ToolchainVersion>,
+}
+
+impl ToolchainInstall {
+    /// Run the [ToolchainInstall] command.
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let version = self.version.unwrap_or_else(ToolchainVersion::cli).to_string();
+
+        if let Ok(installed) = r0vm_version() {
+            if installed.to_string() == version {
+                println!("r0vm {version} is already installed");
+                return Ok(());
+            }
+            println!("Switching r0vm from {installed} to {version}");
+        }
+
+        rzup(&["install", "r0vm", &version])?;
+        rzup(&["use", "r0vm", &version])?;
+
+        let installed = r0vm_version()?;
+        println!("r0vm {installed} is installed");
+        Ok(())
+    }
+}
+
+fn rzup(args: &[&str]) -> anyhow::Result<()> {
+    tracing::info!("Running rzup {}", args.join(" "));
+    let status = Command::new("rzup").args(args).status().context(
+        "failed to run rzup; install it with `curl -L https://risczero.com/install | bash`",
+    )?;
+    if !status.success() {
+        bail!("rzup {} failed with {status}", args.join(" "));
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Commands of the Boundless CLI for managing the RISC Zero toolchain used for execution and
+//! proving.
+
+mod check;
+mod install;
+
+pub use check::ToolchainCheck;
+pub use install::ToolchainInstall;
+
+use std::{fmt, process::Command, str::FromStr};
+
+use anyhow::{anyhow, bail, Context};
+use clap::Subcommand;
+
+use crate::config::GlobalConfig;
+
+/// Commands for managing the RISC Zero toolchain.
+#[derive(Subcommand, Clone, Debug)]
+pub enum ToolchainCommands {
+    /// Report the installed toolchain versions and check their compatibility.
+    Check(ToolchainCheck),
+    /// Install a version of r0vm with rzup and make it the default.
+    Install(ToolchainInstall),
+}
+
+impl ToolchainCommands {
+    /// Run the command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        match self {
+            Self::Check(cmd) => cmd.run(global_config).await,
+            Self::Install(cmd) => cmd.run().await,
+        }
+    }
+}
+
+/// Version of a RISC Zero toolchain component, e.g. `3.0.3`.
+///
+/// Pre-release and build metadata suffixes are ignored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ToolchainVersion {
+    /// Major version.
+    pub major: u64,
+    /// Minor version.
+    pub minor: u64,
+    /// Patch version.
+    pub patch: u64,
+}
+
+impl ToolchainVersion {
+    /// Version of `risc0-zkvm` this CLI was built with.
+    pub fn cli() -> Self {
+        risc0_zkvm::VERSION.parse().expect("risc0-zkvm version is a valid version")
+    }
+
+    /// Whether a component at this version can be used together with the given version.
+    ///
+    /// Releases of the zkVM are compatible within the same minor version.
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        self.major == other.major && self.minor == other.minor
+    }
+}
+
+impl FromStr for ToolchainVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_start_matches('v');
+        let core = s.split(['-', '+']).next().unwrap_or_default();
+        let mut parts = core.split('.').map(|part| part.parse::<u64>());
+        let (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            bail!("invalid version: {s}");
+        };
+        Ok(Self { major, minor, patch })
+    }
+}
+
+impl fmt::Display for ToolchainVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Parse the output of `r0vm --version`, e.g. `risc0-r0vm 3.0.3`.
+fn parse_version_output(output: &str) -> anyhow::Result<ToolchainVersion> {
+    let line = output.lines().next().unwrap_or_default();
+    line.split_whitespace()
+        .last()
+        .ok_or_else(|| anyhow!("empty version output"))?
+        .parse()
+        .with_context(|| format!("failed to parse version from {line:?}"))
+}
+
+/// Version of the `r0vm` binary on the `PATH`.
+pub fn r0vm_version() -> anyhow::Result<ToolchainVersion> {
+    let output = Command::new("r0vm").arg("--version").output().map_err(|_| {
+        anyhow!("r0vm is not installed or could not be executed. Please check instructions at https://dev.risczero.com/api/zkvm/install")
+    })?;
+    if !output.status.success() {
+        bail!("r0vm --version failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    parse_version_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Check that `r0vm` is installed, warning if its version does not match this CLI.
+pub fn r0vm_is_installed() -> anyhow::Result<()> {
+    let installed = r0vm_version()?;
+    let cli = ToolchainVersion::cli();
+    if !installed.is_compatible_with(&cli) {
+        tracing::warn!(
+            "r0vm {installed} may not be compatible with this CLI, which uses risc0-zkvm {cli}. Run `boundless toolchain install` to install a matching version"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_toolchain_version() {
+        let version = parse_version_output("risc0-r0vm 3.0.3\n").unwrap();
+        assert_eq!(version, ToolchainVersion { major: 3, minor: 0, patch: 3 });
+        assert_eq!("v2.1.0-rc.1".parse::<ToolchainVersion>().unwrap().to_string(), "2.1.0");
+        assert!("3.0".parse::<ToolchainVersion>().is_err());
+        assert!(parse_version_output("").is_err());
+
+        assert!(version.is_compatible_with(&"3.0.0".parse().unwrap()));
+        assert!(!version.is_compatible_with(&"3.1.0".parse().unwrap()));
+        assert!(!version.is_compatible_with(&"2.0.3".parse().unwrap()));
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Commands of the Boundless CLI for managing encrypted keystores of wallets.
+
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::{
+    config::GlobalConfig,
+    keystore::{read_password, KeyStore},
+};
+
+/// Commands for managing encrypted keystores, used by other commands with `--wallet <name>`.
+#[derive(Subcommand, Clone, Debug)]
+pub enum WalletCommands {
+    /// Generate a new wallet and store it in an encrypted keystore.
+    New(WalletNew),
+    /// Encrypt an existing private key into a keystore.
+    Import(WalletImport),
+    /// Decrypt a keystore and print its private key.
+    Export(WalletExport),
+    /// List the wallets in the keystore directory.
+    List(WalletList),
+}
+
+impl WalletCommands {
+    /// Run the command.
+    pub fn run(&self, global_config: &GlobalConfig) -> Result<()> {
+        match self {
+            Self::New(cmd) => cmd.run(global_config),
+            Self::Import(cmd) => cmd.run(global_config),
+            Self::Export(cmd) => cmd.run(global_config),
+            Self::List(cmd) => cmd.run(global_config),
+        }
+    }
+}
+
+/// A wallet in the keystore, as printed with `--json`.
+#[derive(Serialize)]
+struct WalletInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<Address>,
+    path: String,
+}
+
+/// Command to generate a new wallet and store it in an encrypted keystore.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct WalletNew {
+    /// Name of the wallet, used to select it with `--wallet`.
+    pub name: String,
+}
+
+impl WalletNew {
+    /// Run the [WalletNew] command.
+    pub fn run(&self, global_config: &GlobalConfig) -> Result<()> {
+        let signer = PrivateKeySigner::random();
+        add_wallet(global_config, &self.name, &signer)
+    }
+}
+
+/// Command to encrypt an existing private key into a keystore.
+///
+/// The key is taken from `--private-key` or the PRIVATE_KEY env var if set, and is otherwise
+/// prompted for without echo.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct WalletImport {
+    /// Name of the wallet, used to select it with `--wallet`.
+    pub name: String,
+}
+
+impl WalletImport {
+    /// Run the [WalletImport] command.
+    pub fn run(&self, global_config: &GlobalConfig) -> Result<()> {
+        let signer = match &global_config.private_key {
+            Some(signer) => signer.clone(),
+            None => rpassword::prompt_password("Private key: ")
+                .context("Failed to read private key")?
+                .trim()
+                .parse::<PrivateKeySigner>()
+                .context("Failed to parse private key")?,
+        };
+        add_wallet(global_config, &self.name, &signer)
+    }
+}
+
+/// Command to decrypt a keystore and print its private key.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct WalletExport {
+    /// Name of the wallet to export.
+    pub name: String,
+}
+
+impl WalletExport {
+    /// Run the [WalletExport] command.
+    pub fn run(&self, _global_config: &GlobalConfig) -> Result<()> {
+        let store = KeyStore::from_env()?;
+        let signer = store.load(&self.name, &read_password("Password: ", false)?)?;
+        eprintln!("⚠️  Anyone with this private key controls {}", signer.address());
+        println!("{}", hex::encode(signer.to_bytes()));
+        Ok(())
+    }
+}
+
+/// Command to list the wallets in the keystore directory.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct WalletList {}
+
+impl WalletList {
+    /// Run the [WalletList] command.
+    pub fn run(&self, global_config: &GlobalConfig) -> Result<()> {
+        let store = KeyStore::from_env()?;
+        let wallets = store
+            .list()?
+            .into_iter()
+            .map(|name| {
+                let path = store.path(&name)?;
+                Ok(WalletInfo {
+                    address: store.address(&name).ok().flatten(),
+                    path: path_string(&path),
+                    name,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        global_config.output_format().print(&wallets, || {
+            if wallets.is_empty() {
+                return format!("No wallets in {}", store.dir().display());
+            }
+            wallets
+                .iter()
+                .map(|wallet| match wallet.address {
+                    Some(address) => format!("{:<24} {address}", wallet.name),
+                    None => wallet.name.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    }
+}
+
+fn add_wallet(global_config: &GlobalConfig, name: &str, signer: &PrivateKeySigner) -> Result<()> {
+    let store = KeyStore::from_env()?;
+    // Check the name before prompting, so a bad name does not cost the user a password entry.
+    store.path(name)?;
+    let password = read_password("Password: ", true)?;
+    let path = store.add(name, signer, &password)?;
+
+    let wallet = WalletInfo {
+        name: name.to_string(),
+        address: Some(signer.address()),
+        path: path_string(&path),
+    };
+    global_config.output_format().print(&wallet, || {
+        format!(
+            "✅ Stored wallet {name} with address {} in {}\n   Use it with --wallet {name}",
+            signer.address(),
+            path.display()
+        )
+    })
+}
+
+fn path_string(path: &std::path::Path) -> String {
+    path.display().to_string()
+}
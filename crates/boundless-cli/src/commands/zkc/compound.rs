@@ -0,0 +1,357 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use alloy::{
+    primitives::{
+        utils::{format_ether, format_units, parse_units},
+        Address, B256, U256,
+    },
+    providers::{Provider, ProviderBuilder},
+    signers::{local::PrivateKeySigner, Signer},
+};
+use anyhow::{anyhow, bail, ensure, Context};
+use boundless_market::contracts::token::IERC20;
+use boundless_zkc::{
+    contracts::{extract_tx_log, IStaking, IStakingRewards},
+    deployments::Deployment,
+};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    commands::zkc::{claim_rewards, get_active_token_id, stake::stake_with_permit},
+    config::GlobalConfig,
+    format::FormattedAmount,
+};
+
+/// Gas assumed for the `addToStakeWithPermit` transaction.
+///
+/// It cannot be estimated before the rewards are claimed, as the account may not hold the
+/// tokens to stake yet.
+const ADD_TO_STAKE_GAS: u64 = 200_000;
+
+/// Deadline of the ERC20 permit authorizing the re-stake, in seconds.
+const PERMIT_DEADLINE: u64 = 3600;
+
+/// Command to claim pending staking rewards and add them to the stake.
+///
+/// Runs once by default, which suits a cron job. With `--interval`, keeps running and compounds
+/// every interval.
+///
+/// Rewards are not compounded if the account cannot pay the estimated gas of the claim and stake
+/// transactions, or if the gas price exceeds `--max-gas-price`. With `--zkc-price`, the rewards
+/// must also be worth a multiple of the gas cost. The amount staked is the increase of the ZKC
+/// balance of the account from the claim, so tokens already held are never staked.
+#[non_exhaustive]
+#[derive(Args, Clone, Debug)]
+pub struct ZkcCompound {
+    /// Minimum pending rewards to compound, in ZKC.
+    #[clap(long, default_value = "0")]
+    pub min_rewards: String,
+    /// Price of 1 ZKC in ETH, used to weigh the pending rewards against the gas cost.
+    ///
+    /// When set, rewards are only compounded if they are worth at least `--gas-multiple` times
+    /// the estimated gas cost of the claim and stake transactions.
+    #[clap(long)]
+    pub zkc_price: Option<String>,
+    /// Multiple of the estimated gas cost the pending rewards must be worth to be compounded.
+    #[clap(long, default_value_t = 10, requires = "zkc_price")]
+    pub gas_multiple: u64,
+    /// Maximum gas price to compound at, in gwei.
+    ///
+    /// Rewards are left pending while the gas price is higher, and compounded in a later round.
+    #[clap(long)]
+    pub max_gas_price: Option<String>,
+    /// Check and report whether rewards would be compounded, without sending transactions.
+    #[clap(long)]
+    pub dry_run: bool,
+    /// Keep running, compounding every interval, in seconds.
+    #[clap(long)]
+    pub interval: Option<u64>,
+    /// Configuration for the ZKC deployment to use.
+    #[clap(flatten, next_help_heading = "ZKC Deployment")]
+    pub deployment: Option<Deployment>,
+}
+
+/// Outcome of a compounding round, as printed with `--json`.
+#[derive(Serialize)]
+struct CompoundReport {
+    account: Address,
+    token_id: U256,
+    /// Pending rewards, in ZKC.
+    pending_rewards: String,
+    estimated_gas: u64,
+    /// Gas price, in gwei.
+    gas_price: String,
+    gas_cost: FormattedAmount,
+    compounded: bool,
+    dry_run: bool,
+    /// Why the rewards were not compounded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped_reason: Option<String>,
+    /// Claimed and re-staked rewards, in ZKC.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    staked: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_total_stake: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stake_tx: Option<B256>,
+}
+
+impl CompoundReport {
+    fn text(&self) -> String {
+        let mut text = format!(
+            "Pending rewards of {:#x}: {} ZKC; estimated gas cost: {}",
+            self.account, self.pending_rewards, self.gas_cost
+        );
+        match (&self.skipped_reason, &self.staked) {
+            (Some(reason), _) => text.push_str(&format!("\nNot compounding: {reason}")),
+            (None, _) if self.dry_run => text.push_str("\nDry run: rewards would be compounded"),
+            (None, Some(staked)) => text.push_str(&format!(
+                "\nCompounded {staked} ZKC into token {}, new total stake: {} ZKC",
+                self.token_id,
+                self.new_total_stake.as_deref().unwrap_or("unknown")
+            )),
+            (None, None) => {}
+        }
+        text
+    }
+}
+
+impl ZkcCompound {
+    /// Run the [ZkcCompound] command.
+    pub async fn run(&self, global_config: &GlobalConfig) -> anyhow::Result<()> {
+        let min_rewards: U256 = parse_units(&self.min_rewards, 18)
+            .map_err(|e| anyhow!("Failed to parse --min-rewards: {}", e))?
+            .into();
+        let zkc_price: Option<U256> = self
+            .zkc_price
+            .as_deref()
+            .map(|price| parse_units(price, 18).map(Into::into))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse --zkc-price: {}", e))?;
+        let max_gas_price: Option<U256> = self
+            .max_gas_price
+            .as_deref()
+            .map(|price| parse_units(price, "gwei").map(Into::into))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse --max-gas-price: {}", e))?;
+        let thresholds = Thresholds { min_rewards, zkc_price, max_gas_price };
+
+        let Some(interval) = self.interval else {
+            return self.compound(global_config, &thresholds).await;
+        };
+        ensure!(interval > 0, "--interval must be positive");
+        loop {
+            // Keep running through failed rounds, e.g. from a flaky RPC provider.
+            if let Err(err) = self.compound(global_config, &thresholds).await {
+                tracing::error!("Failed to compound rewards: {err:#}");
+            }
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    }
+
+    /// Run a single compounding round and print its report.
+    async fn compound(
+        &self,
+        global_config: &GlobalConfig,
+        thresholds: &Thresholds,
+    ) -> anyhow::Result<()> {
+        let rpc_url = global_config.require_rpc_url()?;
+        let tx_signer: PrivateKeySigner = global_config.require_private_key()?;
+        let account = tx_signer.address();
+
+        // Connect to the chain.
+        let provider = ProviderBuilder::new()
+            .wallet(tx_signer.clone())
+            .connect(rpc_url.as_str())
+            .await
+            .with_context(|| format!("failed to connect provider to {rpc_url}"))?;
+        let chain_id = provider.get_chain_id().await?;
+        let deployment = self.deployment.clone().or_else(|| Deployment::from_chain_id(chain_id))
+            .context("could not determine ZKC deployment from chain ID; please specify deployment explicitly")?;
+
+        let token_id =
+            get_active_token_id(provider.clone(), deployment.vezkc_address, account).await?;
+        if token_id.is_zero() {
+            bail!("No active stake position for account {account:#x}; stake ZKC first");
+        }
+
+        let staking_rewards = IStakingRewards::new(deployment.staking_rewards_address, &provider);
+        let current_epoch: u32 = staking_rewards.getCurrentEpoch().call().await?.try_into()?;
+        let epochs: Vec<U256> = (0..current_epoch).map(U256::from).collect();
+        let unclaimed =
+            staking_rewards.calculateUnclaimedRewards(account, epochs.clone()).call().await?;
+        let pending_rewards: U256 = unclaimed.iter().sum();
+        let unclaimed_epochs: Vec<U256> = epochs
+            .into_iter()
+            .zip(&unclaimed)
+            .filter(|(_, reward)| !reward.is_zero())
+            .map(|(epoch, _)| epoch)
+            .collect();
+
+        let claim_gas = if unclaimed_epochs.is_empty() {
+            0
+        } else {
+            staking_rewards
+                .claimRewards(unclaimed_epochs)
+                .estimate_gas()
+                .await
+                .context("Failed to estimate the gas of claimRewards")?
+        };
+        let estimated_gas = claim_gas + ADD_TO_STAKE_GAS;
+        let gas_price = provider.get_gas_price().await.context("Failed to get the gas price")?;
+        let gas_price = U256::from(gas_price);
+        let gas_cost = U256::from(estimated_gas) * gas_price;
+        let eth_balance = provider.get_balance(account).await?;
+        let skipped_reason = thresholds.skipped_reason(
+            pending_rewards,
+            gas_price,
+            gas_cost,
+            eth_balance,
+            self.gas_multiple,
+            global_config,
+        );
+
+        let mut report = CompoundReport {
+            account,
+            token_id,
+            pending_rewards: format_ether(pending_rewards),
+            estimated_gas,
+            gas_price: format_units(gas_price, "gwei")?,
+            gas_cost: global_config.output_format().amount(gas_cost),
+            compounded: false,
+            dry_run: self.dry_run,
+            skipped_reason,
+            staked: None,
+            new_total_stake: None,
+            stake_tx: None,
+        };
+
+        if report.skipped_reason.is_none() && !self.dry_run {
+            // Stake what the claim added to the balance of the account, rather than the claimed
+            // amount, which may be paid to another recipient.
+            let zkc = IERC20::new(deployment.zkc_address, &provider);
+            let balance_before = zkc.balanceOf(account).call().await?;
+            let claimed = claim_rewards(
+                provider.clone(),
+                deployment.staking_rewards_address,
+                account,
+                global_config,
+            )
+            .await?;
+            tracing::info!("Claimed rewards: {} ZKC", format_ether(claimed));
+            let received = zkc.balanceOf(account).call().await?.saturating_sub(balance_before);
+            if received != claimed {
+                tracing::warn!(
+                    "Claimed {} ZKC, but the balance of {account:#x} increased by {} ZKC",
+                    format_ether(claimed),
+                    format_ether(received)
+                );
+            }
+            if received.is_zero() {
+                report.skipped_reason =
+                    Some("claimed rewards were not received by the account".to_string());
+                return global_config.output_format().print(&report, || report.text());
+            }
+
+            let pending_tx = stake_with_permit(
+                provider.clone(),
+                deployment,
+                received,
+                &tx_signer,
+                PERMIT_DEADLINE,
+                true,
+            )
+            .await?;
+            let tx_hash = *pending_tx.tx_hash();
+            tracing::info!(%tx_hash, "Sent transaction for staking claimed rewards");
+
+            let timeout = global_config.tx_timeout.or(pending_tx.timeout());
+            let tx_receipt = pending_tx
+                .with_timeout(timeout)
+                .get_receipt()
+                .await
+                .context("Failed to receive receipt staking transaction")?;
+            ensure!(
+                tx_receipt.status(),
+                "Staking transaction failed: tx_hash = {}",
+                tx_receipt.transaction_hash
+            );
+            let log = extract_tx_log::<IStaking::StakeAdded>(&tx_receipt)
+                .map_err(|e| anyhow!("Failed to extract stake added log: {}", e))?;
+
+            report.compounded = true;
+            report.staked = Some(format_ether(log.data().addedAmount));
+            report.new_total_stake = Some(format_ether(log.data().newTotal));
+            report.stake_tx = Some(tx_hash);
+        }
+
+        global_config.output_format().print(&report, || report.text())
+    }
+}
+
+/// Conditions the pending rewards and gas must meet for the rewards to be compounded.
+struct Thresholds {
+    min_rewards: U256,
+    zkc_price: Option<U256>,
+    max_gas_price: Option<U256>,
+}
+
+impl Thresholds {
+    /// Why the pending rewards should not be compounded, if they should not.
+    fn skipped_reason(
+        &self,
+        pending_rewards: U256,
+        gas_price: U256,
+        gas_cost: U256,
+        eth_balance: U256,
+        gas_multiple: u64,
+        global_config: &GlobalConfig,
+    ) -> Option<String> {
+        let format = global_config.output_format();
+        if pending_rewards.is_zero() {
+            return Some("no pending rewards".to_string());
+        }
+        if pending_rewards < self.min_rewards {
+            return Some(format!(
+                "pending rewards are below {} ZKC",
+                format_ether(self.min_rewards)
+            ));
+        }
+        if let Some(max_gas_price) = self.max_gas_price.filter(|max| gas_price > *max) {
+            return Some(format!(
+                "gas price of {} gwei is above the maximum of {} gwei",
+                format_units(gas_price, "gwei").unwrap_or_default(),
+                format_units(max_gas_price, "gwei").unwrap_or_default()
+            ));
+        }
+        if eth_balance < gas_cost {
+            return Some(format!(
+                "balance of {} is less than the estimated gas cost",
+                format.amount(eth_balance)
+            ));
+        }
+        let price = self.zkc_price?;
+        let value = pending_rewards * price / U256::from(10).pow(U256::from(18));
+        (value < gas_cost * U256::from(gas_multiple)).then(|| {
+            format!(
+                "pending rewards are worth {}, less than {gas_multiple} times the gas cost",
+                format.amount(value)
+            )
+        })
+    }
+}
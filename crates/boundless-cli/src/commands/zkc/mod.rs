@@ -17,6 +17,7 @@
 mod balance_of;
 mod calculate_rewards;
 mod claim_rewards;
+mod compound;
 mod delegate_rewards;
 mod get_active_token_id;
 mod get_current_epoch;
@@ -29,6 +30,7 @@ mod unstake;
 pub use balance_of::{balance_of, ZkcBalance};
 pub use calculate_rewards::{calculate_rewards, ZkcCalculateRewards};
 pub use claim_rewards::{claim_rewards, ZkcClaimRewards};
+pub use compound::ZkcCompound;
 pub use delegate_rewards::ZkcDelegateRewards;
 pub use get_active_token_id::{get_active_token_id, ZkcGetActiveTokenId};
 pub use get_current_epoch::{get_current_epoch, ZkcGetCurrentEpoch};
@@ -65,6 +67,8 @@ pub enum ZKCCommands {
     CalculateRewards(ZkcCalculateRewards),
     /// Claim rewards.
     ClaimRewards(ZkcClaimRewards),
+    /// Claim pending rewards and add them to the stake, if they are worth the gas.
+    Compound(ZkcCompound),
     /// Get rewards delegates for a specified address.
     GetRewardsDelegates(ZkcGetRewardsDelegates),
 }
@@ -83,6 +87,7 @@ impl ZKCCommands {
             Self::Unstake(cmd) => cmd.run(global_config).await,
             Self::CalculateRewards(cmd) => cmd.run(global_config).await,
             Self::ClaimRewards(cmd) => cmd.run(global_config).await,
+            Self::Compound(cmd) => cmd.run(global_config).await,
             Self::GetRewardsDelegates(cmd) => cmd.run(global_config).await,
         }
     }
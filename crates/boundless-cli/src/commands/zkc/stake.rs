@@ -123,7 +123,7 @@ impl ZkcStake {
 
         let pending_tx = match self.no_permit {
             false => {
-                self.stake_with_permit(
+                stake_with_permit(
                     provider,
                     deployment,
                     parsed_amount,
@@ -233,52 +233,53 @@ impl ZkcStake {
             .maybe_decode_revert::<IStaking::IStakingErrors>()
             .context("Sending stake transaction failed")
     }
+}
 
-    async fn stake_with_permit(
-        &self,
-        provider: impl Provider + Clone,
-        deployment: Deployment,
-        value: U256,
-        signer: &impl Signer,
-        deadline: u64,
-        add: bool,
-    ) -> Result<PendingTransactionBuilder<Ethereum>, anyhow::Error> {
-        let contract = IERC20Permit::new(deployment.zkc_address, provider.clone());
-        let owner = signer.address();
-        let call = contract.nonces(owner);
-        // TODO(zkc): Map to proper error
-        let nonce = call.call().await.map_err(|e| anyhow::anyhow!("Failed to get nonce: {}", e))?;
+/// Stake `value` ZKC authorized by an ERC20 permit signed by `signer`, adding to the existing
+/// position of the signer if `add` is set.
+pub(super) async fn stake_with_permit(
+    provider: impl Provider + Clone,
+    deployment: Deployment,
+    value: U256,
+    signer: &impl Signer,
+    deadline: u64,
+    add: bool,
+) -> Result<PendingTransactionBuilder<Ethereum>, anyhow::Error> {
+    let contract = IERC20Permit::new(deployment.zkc_address, provider.clone());
+    let owner = signer.address();
+    let call = contract.nonces(owner);
+    // TODO(zkc): Map to proper error
+    let nonce = call.call().await.map_err(|e| anyhow::anyhow!("Failed to get nonce: {}", e))?;
 
-        // Compute the deadline for the permit using the latest block.
-        let latest_block = provider
-            .get_block(BlockId::latest())
-            .await
-            .context("Failed to check the current block timestamp")?
-            .context("Latest block response is empty")?;
-        let deadline = U256::from(deadline + latest_block.header.timestamp);
+    // Compute the deadline for the permit using the latest block.
+    let latest_block = provider
+        .get_block(BlockId::latest())
+        .await
+        .context("Failed to check the current block timestamp")?
+        .context("Latest block response is empty")?;
+    let deadline = U256::from(deadline + latest_block.header.timestamp);
 
-        // Build and sign a permit
-        let permit = Permit { owner, spender: deployment.vezkc_address, value, nonce, deadline };
-        tracing::debug!("Permit: {:?}", permit);
-        let domain_separator = contract.DOMAIN_SEPARATOR().call().await?;
-        let sig = permit.sign(signer, domain_separator).await?.as_bytes();
-        let r = B256::from_slice(&sig[..32]);
-        let s = B256::from_slice(&sig[32..64]);
-        let v: u8 = sig[64];
+    // Build and sign a permit
+    let permit = Permit { owner, spender: deployment.vezkc_address, value, nonce, deadline };
+    tracing::debug!("Permit: {:?}", permit);
+    let domain_separator = contract.DOMAIN_SEPARATOR().call().await?;
+    let sig = permit.sign(signer, domain_separator).await?.as_bytes();
+    let r = B256::from_slice(&sig[..32]);
+    let s = B256::from_slice(&sig[32..64]);
+    let v: u8 = sig[64];
 
-        let staking = IStaking::new(deployment.vezkc_address, provider);
-        let send_result = match add {
-            false => {
-                tracing::trace!("Calling stakeWithPermit({})", value);
-                staking.stakeWithPermit(value, deadline, v, r, s).send().await
-            }
-            true => {
-                tracing::trace!("Calling addToStakeWithPermit({})", value);
-                staking.addToStakeWithPermit(value, deadline, v, r, s).send().await
-            }
-        };
-        send_result
-            .maybe_decode_revert::<IStaking::IStakingErrors>()
-            .context("Sending stake with permit transaction failed")
-    }
+    let staking = IStaking::new(deployment.vezkc_address, provider);
+    let send_result = match add {
+        false => {
+            tracing::trace!("Calling stakeWithPermit({})", value);
+            staking.stakeWithPermit(value, deadline, v, r, s).send().await
+        }
+        true => {
+            tracing::trace!("Calling addToStakeWithPermit({})", value);
+            staking.addToStakeWithPermit(value, deadline, v, r, s).send().await
+        }
+    };
+    send_result
+        .maybe_decode_revert::<IStaking::IStakingErrors>()
+        .context("Sending stake with permit transaction failed")
 }
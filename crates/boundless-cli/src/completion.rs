@@ -0,0 +1,252 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dynamic shell completion of request IDs and addresses.
+//!
+//! The CLI records the request IDs and addresses it has seen in a small local store. The hidden
+//! `__complete` command reads this store, and the zsh and fish completion scripts call it to
+//! complete values that clap cannot know statically.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use alloy::primitives::{Address, U256};
+use anyhow::{Context, Result};
+use clap::{Command, ValueEnum};
+use clap_complete::aot::Shell;
+use serde::{Deserialize, Serialize};
+
+use crate::json_store;
+
+/// Environment variable overriding the path of the completion store.
+pub const COMPLETION_STORE_ENV: &str = "BOUNDLESS_COMPLETION_STORE";
+
+/// Maximum number of entries of each kind kept in the completion store.
+const MAX_ENTRIES: usize = 100;
+
+/// Kind of value to complete.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+    /// Recently seen request IDs.
+    RequestId,
+    /// Known addresses.
+    Address,
+}
+
+impl CompletionKind {
+    /// Shell function name used to complete this kind in the zsh completion script.
+    fn zsh_function(self) -> &'static str {
+        match self {
+            Self::RequestId => "_boundless_request_ids",
+            Self::Address => "_boundless_addresses",
+        }
+    }
+
+    /// Kind of value completed for the argument with the given ID, if any.
+    fn for_arg(id: &str) -> Option<Self> {
+        let id = id.to_lowercase().replace('-', "_");
+        if id.contains("request_id") {
+            Some(Self::RequestId)
+        } else if id.contains("address") {
+            Some(Self::Address)
+        } else {
+            None
+        }
+    }
+}
+
+/// Local store of recently seen request IDs and known addresses, most recent first.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CompletionStore {
+    /// Recently seen request IDs, as hex strings.
+    #[serde(default)]
+    pub request_ids: Vec<String>,
+    /// Known addresses, as checksummed hex strings.
+    #[serde(default)]
+    pub addresses: Vec<String>,
+}
+
+impl CompletionStore {
+    /// Default path of the store, `$BOUNDLESS_COMPLETION_STORE` or `~/.boundless/completions.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        json_store::default_path(COMPLETION_STORE_ENV, "completions.json")
+    }
+
+    /// Load the store from the given path, returning an empty store if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        json_store::load(path, "completion")
+    }
+
+    /// Save the store to the given path, creating its parent directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        json_store::save(self, path, "completion")
+    }
+
+    /// Record a request ID as the most recently seen.
+    pub fn add_request_id(&mut self, request_id: U256) {
+        push_front(&mut self.request_ids, format!("0x{request_id:x}"));
+    }
+
+    /// Record an address as the most recently seen.
+    pub fn add_address(&mut self, address: Address) {
+        push_front(&mut self.addresses, address.to_string());
+    }
+
+    /// Entries of the given kind starting with `prefix`, ignoring case.
+    pub fn complete(&self, kind: CompletionKind, prefix: &str) -> Vec<&str> {
+        let entries = match kind {
+            CompletionKind::RequestId => &self.request_ids,
+            CompletionKind::Address => &self.addresses,
+        };
+        let prefix = prefix.to_lowercase();
+        entries
+            .iter()
+            .filter(|entry| entry.to_lowercase().starts_with(&prefix))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+fn push_front(entries: &mut Vec<String>, entry: String) {
+    entries.retain(|existing| !existing.eq_ignore_ascii_case(&entry));
+    entries.insert(0, entry);
+    entries.truncate(MAX_ENTRIES);
+}
+
+/// Record request IDs and addresses in the default completion store.
+///
+/// Recording is best effort: failures are logged and otherwise ignored, so that they never fail
+/// the command that produced the values.
+pub fn record(request_ids: &[U256], addresses: &[Address]) {
+    let Some(path) = CompletionStore::default_path() else {
+        return;
+    };
+    let result = json_store::update(&path, "completion", |store: &mut CompletionStore| {
+        request_ids.iter().for_each(|id| store.add_request_id(*id));
+        addresses.iter().for_each(|addr| store.add_address(*addr));
+        Ok(())
+    });
+    if let Err(err) = result {
+        tracing::debug!("Failed to record values for shell completion: {err:?}");
+    }
+}
+
+/// Print shell completions for `cmd` to `out`.
+///
+/// For zsh and fish, the static completions generated by clap are extended to complete request
+/// IDs and addresses dynamically by calling `<bin_name> __complete`.
+pub fn generate(
+    shell: Shell,
+    cmd: &mut Command,
+    bin_name: &str,
+    out: &mut impl Write,
+) -> Result<()> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, cmd, bin_name, &mut script);
+    let script = String::from_utf8(script).context("generated completions are not UTF-8")?;
+    let script = match shell {
+        Shell::Zsh => zsh_dynamic(&script, bin_name),
+        Shell::Fish => fish_dynamic(&script, cmd, bin_name),
+        _ => script,
+    };
+    out.write_all(script.as_bytes())?;
+    Ok(())
+}
+
+fn zsh_dynamic(script: &str, bin_name: &str) -> String {
+    let mut out = String::with_capacity(script.len());
+    for line in script.lines() {
+        // Clap completes values without possible values or a hint with `_default`.
+        let kind = line
+            .trim_start()
+            .strip_prefix('\'')
+            .and_then(|spec| spec.split(['[', ' ']).next())
+            .and_then(|name| CompletionKind::for_arg(name.trim_start_matches([':', '-', '*'])));
+        match kind.filter(|_| line.contains(":_default'")) {
+            Some(kind) => {
+                out.push_str(&line.replace(":_default'", &format!(":{}'", kind.zsh_function())))
+            }
+            None => out.push_str(line),
+        }
+        out.push('\n');
+    }
+    for kind in [CompletionKind::RequestId, CompletionKind::Address] {
+        let value = kind.to_possible_value().expect("no skipped variants");
+        out.push_str(&format!(
+            "\n{}() {{\n    local -a values\n    values=(${{(f)\"$({bin_name} __complete {} \"$PREFIX\" 2>/dev/null)\"}})\n    compadd -a values\n}}\n",
+            kind.zsh_function(),
+            value.get_name(),
+        ));
+    }
+    out
+}
+
+fn fish_dynamic(script: &str, cmd: &Command, bin_name: &str) -> String {
+    let mut out = script.to_string();
+    let mut stack = vec![cmd];
+    while let Some(cmd) = stack.pop() {
+        stack.extend(cmd.get_subcommands());
+        for kind in [CompletionKind::RequestId, CompletionKind::Address] {
+            let takes_kind = cmd.get_arguments().any(|arg| {
+                CompletionKind::for_arg(arg.get_id().as_str()) == Some(kind)
+                    && arg.get_action().takes_values()
+            });
+            if !takes_kind || cmd.get_name() == bin_name {
+                continue;
+            }
+            let value = kind.to_possible_value().expect("no skipped variants");
+            out.push_str(&format!(
+                "complete -c {bin_name} -n \"__fish_seen_subcommand_from {}\" -f -a \"({bin_name} __complete {} (commandline -ct) 2>/dev/null)\"\n",
+                cmd.get_name(),
+                value.get_name(),
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_completes_most_recent_first() {
+        let mut store = CompletionStore::default();
+        store.add_request_id(U256::from(0xabc));
+        store.add_request_id(U256::from(0xdef));
+        store.add_request_id(U256::from(0xab1));
+        store.add_request_id(U256::from(0xabc));
+
+        assert_eq!(store.complete(CompletionKind::RequestId, "0xAB"), vec!["0xabc", "0xab1"]);
+        assert_eq!(store.complete(CompletionKind::RequestId, "").len(), 3);
+        assert!(store.complete(CompletionKind::Address, "").is_empty());
+    }
+
+    #[test]
+    fn store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("completions.json");
+        assert!(CompletionStore::load(&path).unwrap().request_ids.is_empty());
+
+        let mut store = CompletionStore::default();
+        store.add_address(Address::repeat_byte(0x11));
+        store.save(&path).unwrap();
+
+        let loaded = CompletionStore::load(&path).unwrap();
+        assert_eq!(loaded.complete(CompletionKind::Address, "0x1111").len(), 1);
+    }
+}
@@ -14,12 +14,11 @@
 
 //! Common configuration options for commands in the Boundless CLI.
 
-use std::{num::ParseIntError, time::Duration};
+use std::{io::IsTerminal, num::ParseIntError, time::Duration};
 
 use alloy::{providers::DynProvider, signers::local::PrivateKeySigner};
 use anyhow::{Context, Result};
 use clap::Args;
-use risc0_zkvm::ProverOpts;
 use tracing::level_filters::LevelFilter;
 use url::Url;
 
@@ -27,6 +26,14 @@ use boundless_market::{
     client::ClientBuilder, request_builder::StandardRequestBuilder, Client, Deployment, NotProvided,
 };
 
+use crate::{
+    cancellation,
+    exit_code::{ExitCode, WithExitCode},
+    format::{EthUnit, OutputFormat, TimeFormat},
+    keystore::{read_password, KeyStore},
+    prover_backend::ProverBackend,
+};
+
 /// Common configuration options for all commands
 #[derive(Args, Debug, Clone)]
 pub struct GlobalConfig {
@@ -38,14 +45,59 @@ pub struct GlobalConfig {
     #[clap(long, env = "PRIVATE_KEY", global = true, hide_env_values = true)]
     pub private_key: Option<PrivateKeySigner>,
 
+    /// Name of a wallet in the encrypted keystore to sign with, instead of --private-key
+    ///
+    /// Wallets are created with `boundless wallet new` or `boundless wallet import`. The password
+    /// is prompted for, or read from the BOUNDLESS_WALLET_PASSWORD env var.
+    #[clap(long, env = "BOUNDLESS_WALLET", global = true, conflicts_with = "private_key")]
+    pub wallet: Option<String>,
+
     /// Ethereum transaction timeout in seconds.
     #[clap(long, env = "TX_TIMEOUT", global = true, value_parser = |arg: &str| -> Result<Duration, ParseIntError> {Ok(Duration::from_secs(arg.parse()?))})]
     pub tx_timeout: Option<Duration>,
 
+    /// Timeout in seconds for the whole command, after which it is cancelled.
+    ///
+    /// Transactions already sent by the command are listed, as they may still land onchain.
+    #[clap(long, env = "BOUNDLESS_COMMAND_TIMEOUT", global = true, value_parser = |arg: &str| -> Result<Duration, ParseIntError> {Ok(Duration::from_secs(arg.parse()?))})]
+    pub command_timeout: Option<Duration>,
+
     /// Log level (error, warn, info, debug, trace)
     #[clap(long, env = "LOG_LEVEL", global = true, default_value = "info")]
     pub log_level: LevelFilter,
 
+    /// Unit to display ETH amounts in
+    #[clap(long, env = "BOUNDLESS_UNITS", global = true, value_enum, default_value = "eth")]
+    pub units: EthUnit,
+
+    /// How to display timestamps: in local time, in UTC, or relative to now
+    #[clap(
+        long,
+        env = "BOUNDLESS_TIME_FORMAT",
+        global = true,
+        value_enum,
+        default_value = "local"
+    )]
+    pub time_format: TimeFormat,
+
+    /// Print output as JSON, with raw values alongside formatted strings, for commands that
+    /// support it
+    #[clap(long, global = true)]
+    pub json: bool,
+
+    /// Do not color the output. Color is also disabled if the NO_COLOR env var is set, or if the
+    /// output is not a terminal
+    #[clap(long, global = true)]
+    pub no_color: bool,
+
+    /// Print only the essential output, e.g. tables without their header, for use in scripts
+    #[clap(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Print errors in full, including their backtrace if captured
+    #[clap(long, env = "BOUNDLESS_VERBOSE", global = true)]
+    pub verbose: bool,
+
     /// Configuration for the Boundless deployment to use.
     #[clap(flatten, next_help_heading = "Boundless Deployment")]
     pub deployment: Option<Deployment>,
@@ -61,13 +113,41 @@ impl GlobalConfig {
         self.rpc_url
             .clone()
             .context("Blockchain RPC URL not provided; please set --rpc-url or the RPC_URL env var")
+            .exit_code(ExitCode::Config)
+    }
+
+    /// Output options selected by [Self::units], [Self::time_format], [Self::json],
+    /// [Self::no_color], and [Self::quiet].
+    pub fn output_format(&self) -> OutputFormat {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        OutputFormat {
+            units: self.units,
+            time: self.time_format,
+            json: self.json,
+            color: !self.no_color && !no_color_env && std::io::stdout().is_terminal(),
+            quiet: self.quiet,
+        }
     }
 
     /// Access [Self::private_key] or return an error that can be shown to the user.
     pub fn require_private_key(&self) -> Result<PrivateKeySigner> {
-        self.private_key.clone().context(
-            "Private key not provided; please set --private-key or the PRIVATE_KEY env var",
-        )
+        self.private_key
+            .clone()
+            .context(
+                "Private key not provided; please set --private-key, --wallet, or the PRIVATE_KEY env var",
+            )
+            .exit_code(ExitCode::Config)
+    }
+
+    /// Decrypt the keystore of [Self::wallet], if set, into [Self::private_key].
+    pub fn unlock_wallet(&mut self) -> Result<()> {
+        let Some(name) = &self.wallet else {
+            return Ok(());
+        };
+        let password = read_password(&format!("Password for wallet {name}: "), false)?;
+        let keystore = KeyStore::from_env().exit_code(ExitCode::Config)?;
+        self.private_key = Some(keystore.load(name, &password).exit_code(ExitCode::Config)?);
+        Ok(())
     }
 
     /// Create a parially initialzed [ClientBuilder] from the options in this struct.
@@ -77,7 +157,8 @@ impl GlobalConfig {
         Ok(Client::builder()
             .with_rpc_url(self.require_rpc_url()?)
             .with_deployment(self.deployment.clone())
-            .with_timeout(self.tx_timeout))
+            .with_timeout(self.tx_timeout)
+            .with_sent_transactions(cancellation::sent_transactions()))
     }
 
     /// Create a parially initialzed [ClientBuilder] from the options in this struct.
@@ -159,57 +240,40 @@ pub struct ProverConfig {
 }
 
 impl ProverConfig {
-    /// Sets environment variables BONSAI_API_URL and BONSAI_API_KEY environmen variables that are
-    /// read by `default_prover()` when constructing the prover. Note that this is the only builtin
-    /// way to do this.
-    pub fn configure_proving_backend(&self) {
-        if self.use_default_prover {
-            tracing::info!(
-                "Using default prover behavior (respects RISC0_PROVER, RISC0_DEV_MODE, etc.)"
-            );
-            return;
-        }
+    /// Resolve the [ProverBackend] selected by these options.
+    pub fn backend(&self) -> Result<ProverBackend> {
+        ProverBackend::from_config(self)
+    }
 
-        tracing::info!("Using Bento prover at {}", self.bento_api_url);
-        std::env::set_var("BONSAI_API_URL", &self.bento_api_url);
-        if let Some(ref api_key) = self.bento_api_key {
-            std::env::set_var("BONSAI_API_KEY", api_key);
-        } else {
-            tracing::debug!("No API key provided. Setting BONSAI_API_KEY to empty string");
-            std::env::set_var("BONSAI_API_KEY", "");
-        }
+    /// Resolves the [ProverBackend] and sets the environment variables read by
+    /// `default_prover()` when constructing the prover (see [ProverBackend::configure]). Note that
+    /// this is the only builtin way to do this.
+    pub fn configure_proving_backend(&self) -> Result<ProverBackend> {
+        let backend = self.backend()?;
+        backend.configure();
+        Ok(backend)
     }
 
-    /// Sets environment variables to configure the prover (see [configure_proving_backend]) and
-    /// additionally runs a basic health check to make sure it can connect to Bento, if in use.
+    /// Configures the prover (see [Self::configure_proving_backend]) and additionally runs a basic
+    /// health check to make sure it can connect to Bento, if in use.
     ///
     /// This method is intended to give a slightly nicer error message if Bento is not running,
     /// expecially if they did not actually mean to use Bento.
-    pub async fn configure_proving_backend_with_health_check(&self) -> anyhow::Result<()> {
-        self.configure_proving_backend();
-
-        // No health check is implemented for default prover. If dev mode is set, then we are going
-        // to use the dev mode prover anyway, so don't run the health check.
-        if self.use_default_prover || self.skip_health_check || ProverOpts::default().dev_mode() {
-            return Ok(());
+    pub async fn configure_proving_backend_with_health_check(&self) -> Result<ProverBackend> {
+        let backend = self.configure_proving_backend()?;
+        if self.skip_health_check {
+            return Ok(backend);
         }
 
         // NOTE: If they are using the default, it is more likely they don't have Bento running.
         let using_default_url = self.bento_api_url == DEFAULT_BENTO_API_URL;
+        backend.health_check().await.with_context(|| match using_default_url {
+            true => {
+                "Bento health check failed; You can set --use-default-prover to use a local prover"
+            }
+            false => "Bento health check failed",
+        })?;
 
-        // Send a request to the /health endpoint.
-        let bento_url = Url::parse(&self.bento_api_url)
-            .with_context(|| format!("Failed to parse Bento API URL: {}", self.bento_api_url))?;
-        let health_check_url = bento_url.join("health")?;
-        reqwest::get(health_check_url.clone())
-            .await
-            .with_context(|| match using_default_url {
-                true => format!("Failed to send health check reqest to {health_check_url}; You can set --use-default-prover to use a local prover"),
-                false => format!("Failed to send health check reqest to {health_check_url}"),
-            })?
-            .error_for_status()
-            .context("Bento health check endpoint returned error status")?;
-
-        Ok(())
+        Ok(backend)
     }
 }
@@ -0,0 +1,100 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Helpers for testing applications end-to-end against a dev-mode deployment.
+//!
+//! With `RISC0_DEV_MODE` enabled, requests are fulfilled with fake receipts that a deployment
+//! using a mock verifier accepts. The request's program is executed to compute its journal, but
+//! no proof is generated, so fulfillment is instant and deterministic.
+
+use alloy::{
+    network::Ethereum,
+    primitives::{FixedBytes, B256, U256},
+    providers::Provider,
+};
+use anyhow::{bail, ensure, Context, Result};
+use boundless_market::{
+    contracts::{
+        boundless_market::{FulfillmentTx, UnlockedRequest},
+        Selector, UNSPECIFIED_SELECTOR,
+    },
+    storage::fetch_url,
+    Client,
+};
+
+use crate::{is_dev_mode, DefaultProver, OrderFulfilled};
+
+/// Fulfill the given request with a fake receipt.
+///
+/// The request is fetched from the order stream or onchain, using the optional digest and
+/// transaction hash to locate it, and is priced as part of fulfillment if it is not locked.
+///
+/// Requires `RISC0_DEV_MODE` to be enabled, and that the deployment routes the
+/// [Selector::FakeReceipt] selector to a mock verifier, as local test deployments do.
+pub async fn fake_fulfill<P, St, R, Si>(
+    client: &Client<P, St, R, Si>,
+    request_id: U256,
+    request_digest: Option<B256>,
+    tx_hash: Option<B256>,
+) -> Result<()>
+where
+    P: Provider<Ethereum> + 'static + Clone,
+{
+    ensure!(is_dev_mode(), "fake fulfillment requires RISC0_DEV_MODE to be enabled");
+
+    let (request, signature) =
+        client.fetch_proof_request(request_id, tx_hash, request_digest).await?;
+    let selector = request.requirements.selector;
+    if selector != UNSPECIFIED_SELECTOR
+        && selector != FixedBytes::from(Selector::FakeReceipt as u32)
+    {
+        bail!(
+            "request 0x{request_id:x} requires selector {selector}, which cannot be fulfilled with a fake receipt"
+        );
+    }
+
+    let (_, assessor_url) = client.boundless_market.image_info().await?;
+    let assessor_program = fetch_url(&assessor_url).await?;
+    let (_, set_builder_url) = client.set_verifier.image_info().await?;
+    let set_builder_program = fetch_url(&set_builder_url).await?;
+    let domain = client.boundless_market.eip712_domain().await?;
+    let prover = DefaultProver::new(
+        set_builder_program,
+        assessor_program,
+        client.boundless_market.caller(),
+        domain,
+    )?;
+
+    let unlocked_requests = match client.boundless_market.is_locked(request_id).await? {
+        true => vec![],
+        false => vec![UnlockedRequest::new(request.clone(), signature.clone())],
+    };
+
+    let (fills, root_receipt, assessor_receipt) = prover.fulfill(&[(request, signature)]).await?;
+    ensure!(!fills.is_empty(), "failed to execute request 0x{request_id:x}");
+    let order_fulfilled = OrderFulfilled::new(fills, root_receipt, assessor_receipt)?;
+
+    let fulfillment_tx = FulfillmentTx::new(order_fulfilled.fills, order_fulfilled.assessorReceipt)
+        .with_submit_root(
+            client.deployment.set_verifier_address,
+            order_fulfilled.root,
+            order_fulfilled.seal,
+        )
+        .with_unlocked_requests(unlocked_requests);
+    client
+        .boundless_market
+        .fulfill(fulfillment_tx)
+        .await
+        .with_context(|| format!("failed to fulfill request 0x{request_id:x}"))?;
+    Ok(())
+}
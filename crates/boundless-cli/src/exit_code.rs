@@ -0,0 +1,232 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Exit codes of the Boundless CLI, by class of failure.
+//!
+//! A failed command exits with the code of the first classified error in its chain, so that
+//! scripts can branch on the class of failure rather than on the message. Errors are either
+//! tagged explicitly with [WithExitCode::exit_code], or classified from their type, e.g. an RPC
+//! error response carrying revert data is an [ExitCode::Revert].
+
+use std::fmt;
+
+use alloy::{
+    contract::Error as ContractError,
+    providers::{PendingTransactionError, WatchTxError},
+    transports::{RpcError, TransportErrorKind},
+};
+use boundless_market::contracts::{
+    boundless_market::MarketError, token::IERC20::IERC20Errors,
+    IBoundlessMarket::IBoundlessMarketErrors, TxnErr,
+};
+
+/// Exit code of a failed command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExitCode {
+    /// A failure not covered by another code.
+    Failure = 1,
+    /// The command line arguments are invalid.
+    Usage = 2,
+    /// The configuration is missing or invalid, e.g. no RPC URL or private key is set.
+    Config = 3,
+    /// The RPC endpoint, or another service, could not be reached or returned an error.
+    Rpc = 4,
+    /// The account has insufficient funds or collateral.
+    InsufficientFunds = 5,
+    /// A transaction or call reverted.
+    Revert = 6,
+    /// A proof, journal, or deployment failed verification.
+    Verification = 7,
+    /// An operation timed out.
+    Timeout = 8,
+    /// The command was interrupted by Ctrl-C.
+    Interrupted = 130,
+}
+
+impl ExitCode {
+    /// Classify the error, defaulting to [ExitCode::Failure].
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.chain().find_map(classify).unwrap_or(ExitCode::Failure)
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        std::process::ExitCode::from(code as u8)
+    }
+}
+
+/// An error tagged with the [ExitCode] of its class.
+///
+/// It displays as the underlying error, so tagging does not change the message.
+#[derive(Debug)]
+pub struct ClassifiedError {
+    code: ExitCode,
+    source: anyhow::Error,
+}
+
+impl fmt::Display for ClassifiedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.source, f)
+    }
+}
+
+impl std::error::Error for ClassifiedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.source()
+    }
+}
+
+/// Extension trait to tag the error of a [Result] with an [ExitCode].
+pub trait WithExitCode<T> {
+    /// Tag the error, if any, with the given exit code.
+    fn exit_code(self, code: ExitCode) -> anyhow::Result<T>;
+}
+
+impl<T, E: Into<anyhow::Error>> WithExitCode<T> for Result<T, E> {
+    fn exit_code(self, code: ExitCode) -> anyhow::Result<T> {
+        self.map_err(|err| ClassifiedError { code, source: err.into() }.into())
+    }
+}
+
+fn classify(err: &(dyn std::error::Error + 'static)) -> Option<ExitCode> {
+    if let Some(err) = err.downcast_ref::<ClassifiedError>() {
+        return Some(err.code);
+    }
+    if err.is::<clap::Error>() {
+        return Some(ExitCode::Usage);
+    }
+    if let Some(err) = err.downcast_ref::<MarketError>() {
+        return match err {
+            MarketError::TimeoutReached(_) | MarketError::TxnConfirmationError(_) => {
+                Some(ExitCode::Timeout)
+            }
+            MarketError::LockRevert(_) | MarketError::SlashRevert(_) => Some(ExitCode::Revert),
+            MarketError::InsufficientBalance(_, _) => Some(ExitCode::InsufficientFunds),
+            _ => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<TxnErr>() {
+        return match err {
+            TxnErr::BoundlessMarketErr(IBoundlessMarketErrors::InsufficientBalance(_))
+            | TxnErr::ERC20Err(IERC20Errors::ERC20InsufficientBalance(_)) => {
+                Some(ExitCode::InsufficientFunds)
+            }
+            TxnErr::BoundlessMarketErr(_)
+            | TxnErr::SetVerifierErr(_)
+            | TxnErr::HitPointsErr(_)
+            | TxnErr::ERC20Err(_) => Some(ExitCode::Revert),
+            TxnErr::ContractErr(err) => classify_contract(err),
+            _ => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<ContractError>() {
+        return classify_contract(err);
+    }
+    if let Some(err) = err.downcast_ref::<RpcError<TransportErrorKind>>() {
+        return Some(classify_rpc(err));
+    }
+    if let Some(err) = err.downcast_ref::<PendingTransactionError>() {
+        return match err {
+            PendingTransactionError::TxWatcher(WatchTxError::Timeout) => Some(ExitCode::Timeout),
+            PendingTransactionError::TransportError(err) => Some(classify_rpc(err)),
+            _ => None,
+        };
+    }
+    if err.is::<tokio::time::error::Elapsed>() {
+        return Some(ExitCode::Timeout);
+    }
+    if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        return match err {
+            err if err.is_timeout() => Some(ExitCode::Timeout),
+            err if err.is_connect() || err.is_status() => Some(ExitCode::Rpc),
+            _ => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<std::io::Error>() {
+        return (err.kind() == std::io::ErrorKind::TimedOut).then_some(ExitCode::Timeout);
+    }
+    None
+}
+
+fn classify_contract(err: &ContractError) -> Option<ExitCode> {
+    match err {
+        ContractError::TransportError(err) => Some(classify_rpc(err)),
+        _ => None,
+    }
+}
+
+fn classify_rpc(err: &RpcError<TransportErrorKind>) -> ExitCode {
+    match err {
+        RpcError::ErrorResp(payload) => {
+            let message = payload.message.to_lowercase();
+            if message.contains("insufficient funds") {
+                ExitCode::InsufficientFunds
+            } else if payload.as_revert_data().is_some() || message.contains("revert") {
+                ExitCode::Revert
+            } else {
+                ExitCode::Rpc
+            }
+        }
+        _ => ExitCode::Rpc,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::rpc::json_rpc::ErrorPayload;
+    use anyhow::{anyhow, Context};
+
+    use super::*;
+
+    #[test]
+    fn explicit_code_wins_over_context() {
+        let err = Err::<(), _>(anyhow!("bad proof"))
+            .exit_code(ExitCode::Verification)
+            .context("failed to verify")
+            .unwrap_err();
+        assert_eq!(ExitCode::of(&err), ExitCode::Verification);
+        // Tagging does not change the message.
+        assert_eq!(format!("{err:#}"), "failed to verify: bad proof");
+    }
+
+    #[test]
+    fn classifies_rpc_errors() {
+        let revert = RpcError::<TransportErrorKind>::ErrorResp(ErrorPayload {
+            code: 3,
+            message: "execution reverted".into(),
+            data: None,
+        });
+        assert_eq!(ExitCode::of(&anyhow::Error::from(revert)), ExitCode::Revert);
+
+        let funds = RpcError::<TransportErrorKind>::ErrorResp(ErrorPayload {
+            code: -32000,
+            message: "insufficient funds for gas * price + value".into(),
+            data: None,
+        });
+        assert_eq!(ExitCode::of(&anyhow::Error::from(funds)), ExitCode::InsufficientFunds);
+
+        let unreachable = RpcError::<TransportErrorKind>::local_usage_str("connection refused");
+        let err = anyhow::Error::from(unreachable).context("Failed to build Boundless client");
+        assert_eq!(ExitCode::of(&err), ExitCode::Rpc);
+    }
+
+    #[test]
+    fn unclassified_is_failure() {
+        assert_eq!(ExitCode::of(&anyhow!("something went wrong")), ExitCode::Failure);
+        let timeout = MarketError::TimeoutReached(alloy::primitives::U256::ZERO);
+        assert_eq!(ExitCode::of(&anyhow::Error::from(timeout)), ExitCode::Timeout);
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Formatting of amounts, durations, and timestamps in command output.
+//!
+//! Commands render ETH amounts in the unit selected with `--units`, timestamps as selected with
+//! `--time-format`, and durations in a human-readable form. With `--json`, commands that support
+//! it print a JSON object instead, carrying both the raw values and their formatted strings.
+//! Listing commands print [Table]s, colored unless `--no-color` is set, and without their header
+//! with `--quiet`.
+
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{
+    utils::{format_ether, format_units},
+    U256,
+};
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::table::Table;
+
+/// Unit to display ETH amounts in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum EthUnit {
+    /// Wei, the smallest unit of ether.
+    Wei,
+    /// Gwei, 10^9 wei.
+    Gwei,
+    /// Ether, 10^18 wei.
+    #[default]
+    Eth,
+}
+
+impl EthUnit {
+    /// Symbol of the unit, as displayed after an amount.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Wei => "wei",
+            Self::Gwei => "gwei",
+            Self::Eth => "ETH",
+        }
+    }
+
+    /// Format an amount of wei in this unit, followed by the unit symbol.
+    pub fn format(self, wei: U256) -> String {
+        let value = match self {
+            Self::Wei => wei.to_string(),
+            Self::Gwei => format_units(wei, 9u8).expect("9 decimals is a valid unit"),
+            Self::Eth => format_ether(wei),
+        };
+        format!("{value} {}", self.symbol())
+    }
+}
+
+/// How to display timestamps.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TimeFormat {
+    /// Local time, with the offset from UTC.
+    #[default]
+    Local,
+    /// UTC time.
+    Utc,
+    /// Time relative to now, e.g. `in 12m` or `3h ago`.
+    Relative,
+}
+
+impl TimeFormat {
+    /// Format a UNIX timestamp, in seconds, relative to the current time if needed.
+    pub fn format(self, timestamp: u64) -> String {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        self.format_at(timestamp, now)
+    }
+
+    /// Format a UNIX timestamp, in seconds, as seen at time `now`.
+    pub fn format_at(self, timestamp: u64, now: u64) -> String {
+        let Some(time) = DateTime::<Utc>::from_timestamp(timestamp as i64, 0) else {
+            return timestamp.to_string();
+        };
+        match self {
+            Self::Local => time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            Self::Utc => time.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            Self::Relative => format_relative(timestamp, now),
+        }
+    }
+}
+
+/// Format a timestamp relative to `now`, in its largest unit, e.g. `in 12m` or `3h ago`.
+pub fn format_relative(timestamp: u64, now: u64) -> String {
+    let (secs, future) =
+        if timestamp >= now { (timestamp - now, true) } else { (now - timestamp, false) };
+    let amount = [(86_400, "d"), (3_600, "h"), (60, "m")]
+        .iter()
+        .find(|(unit, _)| secs >= *unit)
+        .map(|(unit, suffix)| format!("{}{suffix}", secs / unit))
+        .unwrap_or_else(|| format!("{secs}s"));
+    match (secs, future) {
+        (0, _) => "now".to_string(),
+        (_, true) => format!("in {amount}"),
+        (_, false) => format!("{amount} ago"),
+    }
+}
+
+/// Format a duration as days, hours, minutes, and seconds, e.g. `1h 5m 30s`.
+///
+/// Zero components are omitted, and sub-second precision is dropped.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return "0s".to_string();
+    }
+    let parts = [
+        (secs / 86_400, "d"),
+        (secs % 86_400 / 3_600, "h"),
+        (secs % 3_600 / 60, "m"),
+        (secs % 60, "s"),
+    ];
+    parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, suffix)| format!("{value}{suffix}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// An amount of wei, with its raw value and its formatted string.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FormattedAmount {
+    /// Amount in wei, as a decimal string.
+    pub wei: String,
+    /// Amount in the selected unit, followed by the unit symbol.
+    pub formatted: String,
+}
+
+impl fmt::Display for FormattedAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.formatted)
+    }
+}
+
+/// A duration, with its raw value in seconds and its human-readable string.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FormattedDuration {
+    /// Duration in seconds.
+    pub seconds: u64,
+    /// Human-readable duration, e.g. `1h 5m 30s`.
+    pub formatted: String,
+}
+
+impl fmt::Display for FormattedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.formatted)
+    }
+}
+
+/// A timestamp, with its raw value and its formatted string.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FormattedTimestamp {
+    /// UNIX timestamp, in seconds.
+    pub timestamp: u64,
+    /// Timestamp in the selected format.
+    pub formatted: String,
+}
+
+impl fmt::Display for FormattedTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.formatted)
+    }
+}
+
+/// Output options shared by all commands, selected with the global `--units`, `--time-format`,
+/// `--json`, `--no-color`, and `--quiet` flags.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutputFormat {
+    /// Unit to display ETH amounts in.
+    pub units: EthUnit,
+    /// How to display timestamps.
+    pub time: TimeFormat,
+    /// Print JSON objects instead of text, for commands that support it.
+    pub json: bool,
+    /// Color tables and highlighted values.
+    pub color: bool,
+    /// Print only the essential output, e.g. the rows of tables without their header.
+    pub quiet: bool,
+}
+
+impl OutputFormat {
+    /// Format an amount of wei.
+    pub fn amount(&self, wei: U256) -> FormattedAmount {
+        FormattedAmount { wei: wei.to_string(), formatted: self.units.format(wei) }
+    }
+
+    /// Format a UNIX timestamp, in seconds.
+    pub fn timestamp(&self, timestamp: u64) -> FormattedTimestamp {
+        FormattedTimestamp { timestamp, formatted: self.time.format(timestamp) }
+    }
+
+    /// Format a duration.
+    pub fn duration(&self, duration: Duration) -> FormattedDuration {
+        FormattedDuration { seconds: duration.as_secs(), formatted: format_duration(duration) }
+    }
+
+    /// Format a duration given in seconds.
+    pub fn seconds(&self, seconds: u64) -> FormattedDuration {
+        self.duration(Duration::from_secs(seconds))
+    }
+
+    /// Render a table with the color and quiet options.
+    pub fn table(&self, table: &Table) -> String {
+        table.render(self.color, self.quiet)
+    }
+
+    /// Print `value` as JSON if JSON output is enabled, or the text returned by `text` otherwise.
+    pub fn print<T: Serialize>(&self, value: &T, text: impl FnOnce() -> String) -> Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        } else {
+            println!("{}", text());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::utils::parse_ether;
+
+    use super::*;
+
+    #[test]
+    fn test_format_units() {
+        let amount = parse_ether("1.5").unwrap();
+        assert_eq!(EthUnit::Eth.format(amount), "1.500000000000000000 ETH");
+        assert_eq!(EthUnit::Gwei.format(amount), "1500000000.000000000 gwei");
+        assert_eq!(EthUnit::Wei.format(amount), "1500000000000000000 wei");
+
+        let output = OutputFormat { units: EthUnit::Gwei, json: true, ..Default::default() };
+        assert_eq!(
+            output.amount(U256::from(2_500_000_000u64)),
+            FormattedAmount {
+                wei: "2500000000".to_string(),
+                formatted: "2.500000000 gwei".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1s");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1m 30s");
+        assert_eq!(format_duration(Duration::from_secs(3_600)), "1h");
+        assert_eq!(format_duration(Duration::from_secs(93_784)), "1d 2h 3m 4s");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(TimeFormat::Utc.format_at(1_700_000_000, 0), "2023-11-14 22:13:20 UTC");
+        assert_eq!(TimeFormat::Relative.format_at(1_000, 1_000), "now");
+        assert_eq!(TimeFormat::Relative.format_at(1_045, 1_000), "in 45s");
+        assert_eq!(TimeFormat::Relative.format_at(1_000 + 12 * 60 + 30, 1_000), "in 12m");
+        assert_eq!(TimeFormat::Relative.format_at(20_000, 20_000 + 3 * 3_600), "3h ago");
+        assert_eq!(TimeFormat::Relative.format_at(0, 2 * 86_400 + 5), "2d ago");
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Remediation hints for common failures of the Boundless CLI.
+//!
+//! A failed command prints its error followed by the [Hint] for the first recognized failure in
+//! the error chain, e.g. a transaction the wallet cannot pay gas for, or a missing `r0vm`. With
+//! `--verbose`, the raw error is printed in full, including its backtrace if captured. With
+//! `--json`, the error is printed as a JSON [ErrorReport] instead, carrying the stable
+//! [ErrorCode] of the failure.
+
+use std::fmt;
+
+use boundless_market::{
+    contracts::{IBoundlessMarket::IBoundlessMarketErrors, TxnErr},
+    error_code::ErrorCode,
+};
+use serde::Serialize;
+
+use crate::exit_code::ExitCode;
+
+/// Base URL of the Boundless documentation.
+const DOCS_URL: &str = "https://docs.beboundless.xyz";
+
+/// A short, actionable hint for a class of failure, with a link to the relevant docs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Hint {
+    /// What to do about the failure.
+    pub message: &'static str,
+    /// Path of the relevant page of the docs.
+    pub docs: &'static str,
+}
+
+impl Hint {
+    /// The wallet cannot pay for the gas of a transaction.
+    pub const INSUFFICIENT_GAS: Hint = Hint {
+        message: "The wallet cannot pay for gas. Fund it with the chain's native token, or check its balance with `boundless account balance`.",
+        docs: "/developers/tooling/cli",
+    };
+    /// The RPC endpoint serves a different chain than the selected deployment.
+    pub const WRONG_CHAIN: Hint = Hint {
+        message: "The RPC URL is for a different chain than the deployment. Check --rpc-url, or select the deployment of its chain.",
+        docs: "/developers/tooling/cli",
+    };
+    /// A program or input has to be uploaded, but no storage provider is configured.
+    pub const MISSING_STORAGE_PROVIDER: Hint = Hint {
+        message: "No storage provider is configured to upload the program or input. Configure one (see --help), or pass an already uploaded --program-url.",
+        docs: "/developers/tooling/cli",
+    };
+    /// The seal of a fulfillment does not use the selector required by the request.
+    pub const SELECTOR_MISMATCH: Hint = Hint {
+        message: "The proof type does not match the selector required by the request, e.g. a Groth16 proof was requested. Prove with the requested proof type.",
+        docs: "/developers/smart-contracts/verifier-contracts",
+    };
+    /// `r0vm` is needed to execute or prove locally, but is not installed.
+    pub const R0VM_MISSING: Hint = Hint {
+        message: "r0vm is required to execute or prove locally. Install it with `boundless toolchain install`.",
+        docs: "/developers/quick-start",
+    };
+
+    /// Find the hint for the first recognized failure in the chain of the error, if any.
+    pub fn of(err: &anyhow::Error) -> Option<Self> {
+        err.chain().find_map(classify)
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hint: {}\n      see {DOCS_URL}{}", self.message, self.docs)
+    }
+}
+
+/// Format a failed command's error for the terminal, followed by its [Hint], if any.
+///
+/// The error is printed as its chain of messages, or in full if `verbose` is set.
+pub fn report(err: &anyhow::Error, verbose: bool) -> String {
+    let mut report = if verbose { format!("Error: {err:?}") } else { format!("Error: {err:#}") };
+    if let Some(hint) = Hint::of(err) {
+        report.push_str(&format!("\n\n{hint}"));
+    }
+    report
+}
+
+/// A failed command's error, as printed with `--json`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ErrorReport {
+    /// Stable string code of the first market SDK error in the chain, if any.
+    pub code: Option<ErrorCode>,
+    /// Stable numeric code of the first market SDK error in the chain, if any.
+    pub numeric_code: Option<u32>,
+    /// Exit code of the command, see [ExitCode].
+    pub exit_code: u8,
+    /// Chain of messages of the error. Not stable between releases.
+    pub message: String,
+    /// Hint for the failure, if any.
+    pub hint: Option<&'static str>,
+}
+
+impl ErrorReport {
+    /// Build the report of the error.
+    pub fn new(err: &anyhow::Error) -> Self {
+        let code = ErrorCode::of(err);
+        Self {
+            code,
+            numeric_code: code.map(ErrorCode::code),
+            exit_code: ExitCode::of(err) as u8,
+            message: format!("{err:#}"),
+            hint: Hint::of(err).map(|hint| hint.message),
+        }
+    }
+}
+
+/// Format a failed command's error as a JSON [ErrorReport].
+pub fn report_json(err: &anyhow::Error) -> String {
+    serde_json::json!({ "error": ErrorReport::new(err) }).to_string()
+}
+
+fn classify(err: &(dyn std::error::Error + 'static)) -> Option<Hint> {
+    if let Some(TxnErr::BoundlessMarketErr(IBoundlessMarketErrors::SelectorMismatch(_))) =
+        err.downcast_ref::<TxnErr>()
+    {
+        return Some(Hint::SELECTOR_MISMATCH);
+    }
+
+    // Most of these failures are only distinguishable by the message of the RPC error response,
+    // or of an error raised with `bail!`, so match on the message of each error in the chain.
+    let message = err.to_string().to_lowercase();
+    if message.contains("insufficient funds for gas")
+        || message.contains("intrinsic gas too low")
+        || message.contains("gas required exceeds allowance")
+    {
+        Some(Hint::INSUFFICIENT_GAS)
+    } else if message.contains("does not match chain_id")
+        || message.contains("unknown chain_id")
+        || message.contains("invalid chain id")
+    {
+        Some(Hint::WRONG_CHAIN)
+    } else if message.contains("storage provider is required")
+        || message.contains("storage provider is not provided")
+    {
+        Some(Hint::MISSING_STORAGE_PROVIDER)
+    } else if message.contains("selectormismatch") {
+        Some(Hint::SELECTOR_MISMATCH)
+    } else if message.contains("r0vm")
+        && (message.contains("not installed") || message.contains("not found"))
+    {
+        Some(Hint::R0VM_MISSING)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+    use alloy::{
+        primitives::FixedBytes,
+        rpc::json_rpc::ErrorPayload,
+        transports::{RpcError, TransportErrorKind},
+    };
+    use anyhow::{anyhow, Context};
+    use boundless_market::contracts::{
+        boundless_market::MarketError, IBoundlessMarket::SelectorMismatch,
+    };
+
+    use super::*;
+
+    #[test]
+    fn hints_common_failures() {
+        let gas = RpcError::<TransportErrorKind>::ErrorResp(ErrorPayload {
+            code: -32000,
+            message: "insufficient funds for gas * price + value".into(),
+            data: None,
+        });
+        let err = anyhow::Error::from(gas).context("Failed to deposit");
+        assert_eq!(Hint::of(&err), Some(Hint::INSUFFICIENT_GAS));
+
+        let selector = TxnErr::BoundlessMarketErr(IBoundlessMarketErrors::SelectorMismatch(
+            SelectorMismatch { required: FixedBytes([1; 4]), provided: FixedBytes([2; 4]) },
+        ));
+        assert_eq!(Hint::of(&anyhow::Error::from(selector)), Some(Hint::SELECTOR_MISMATCH));
+
+        let chain = anyhow!(
+            "provided deployment does not match chain_id reported by RPC provider: 1 != 8453"
+        );
+        assert_eq!(Hint::of(&chain), Some(Hint::WRONG_CHAIN));
+
+        let r0vm = anyhow!("r0vm is not installed or could not be executed");
+        assert_eq!(Hint::of(&r0vm.context("Failed to execute")), Some(Hint::R0VM_MISSING));
+
+        assert_eq!(Hint::of(&anyhow!("something went wrong")), None);
+    }
+
+    #[test]
+    fn report_appends_hint() {
+        let err = Err::<(), _>(anyhow!("A storage provider is required to upload programs."))
+            .context("Failed to submit request")
+            .unwrap_err();
+        assert_eq!(
+            report(&err, false),
+            format!(
+                "Error: Failed to submit request: A storage provider is required to upload programs.\n\n{}",
+                Hint::MISSING_STORAGE_PROVIDER
+            )
+        );
+        assert!(report(&anyhow!("something went wrong"), true).starts_with("Error: something"));
+    }
+
+    #[test]
+    fn json_report_has_stable_code() {
+        let err = anyhow::Error::from(MarketError::RequestHasExpired(U256::from(1)))
+            .context("Failed to lock request");
+        let report: serde_json::Value = serde_json::from_str(&report_json(&err)).unwrap();
+        assert_eq!(report["error"]["code"], "REQUEST_HAS_EXPIRED");
+        assert_eq!(report["error"]["numeric_code"], 1004);
+        assert_eq!(report["error"]["exit_code"], 1);
+        assert_eq!(report["error"]["message"], "Failed to lock request: Request has expired 0x1");
+
+        let report: serde_json::Value =
+            serde_json::from_str(&report_json(&anyhow!("something went wrong"))).unwrap();
+        assert!(report["error"]["code"].is_null());
+    }
+}
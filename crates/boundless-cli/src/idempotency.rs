@@ -0,0 +1,217 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local record of the requests submitted under an idempotency key.
+//!
+//! `request submit` reserves the key for the ID of the request before submitting it, and marks it
+//! as submitted afterwards, so that running the same command again returns the existing request
+//! instead of submitting a duplicate. Keys are scoped to the market and the submitting account,
+//! and are released once their request expires.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use alloy::primitives::{keccak256, Address, U256};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::json_store;
+
+/// Environment variable overriding the path of the idempotency store.
+pub const IDEMPOTENCY_STORE_ENV: &str = "BOUNDLESS_IDEMPOTENCY_STORE";
+
+/// Derive an idempotency key from the contents of a request file.
+pub fn key_from_contents(contents: &[u8]) -> String {
+    format!("content:{}", keccak256(contents))
+}
+
+/// A request submitted under an idempotency key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SubmittedRequest {
+    /// ID of the request, as a hex string.
+    pub request_id: String,
+    /// Time, in seconds since the UNIX epoch, at which the request expires.
+    pub expires_at: u64,
+    /// Whether the key is reserved for the request, but its submission was not confirmed.
+    ///
+    /// The submission may or may not have gone through, so the request must be looked up before
+    /// submitting it again.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pending: bool,
+}
+
+impl SubmittedRequest {
+    /// ID of the request.
+    pub fn request_id(&self) -> Result<U256> {
+        self.request_id.parse().with_context(|| {
+            format!("invalid request ID {} in the idempotency store", self.request_id)
+        })
+    }
+}
+
+/// Local store of the requests submitted under an idempotency key.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct IdempotencyStore {
+    /// Submitted requests, keyed by market address, account, and idempotency key.
+    #[serde(default)]
+    pub requests: BTreeMap<String, SubmittedRequest>,
+}
+
+impl IdempotencyStore {
+    /// Default path of the store, `$BOUNDLESS_IDEMPOTENCY_STORE` or
+    /// `~/.boundless/idempotency.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        json_store::default_path(IDEMPOTENCY_STORE_ENV, "idempotency.json")
+    }
+
+    /// Path of the store, or an error that can be shown to the user if there is none.
+    pub fn require_default_path() -> Result<PathBuf> {
+        Self::default_path().with_context(|| {
+            format!(
+                "HOME is not set; please set {IDEMPOTENCY_STORE_ENV} to the path of the idempotency store"
+            )
+        })
+    }
+
+    /// Load the store from the given path, returning an empty store if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        json_store::load(path, "idempotency")
+    }
+
+    /// Save the store to the given path, creating its parent directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        json_store::save(self, path, "idempotency")
+    }
+
+    /// Load the store from the given path, apply `f` to it, and save it, while holding its lock.
+    pub fn update<R>(path: &Path, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        json_store::update(path, "idempotency", f)
+    }
+
+    /// Request submitted under the given key, unless it expired before `now`.
+    pub fn get(
+        &self,
+        market: Address,
+        account: Address,
+        key: &str,
+        now: u64,
+    ) -> Option<&SubmittedRequest> {
+        self.requests
+            .get(&store_key(market, account, key))
+            .filter(|submitted| submitted.expires_at >= now)
+    }
+
+    /// Reserve the given key for a request about to be submitted, unless it is already in use.
+    ///
+    /// Returns the request the key is in use by, submitted or still pending, if any. Otherwise
+    /// records the request as pending and returns `None`.
+    pub fn reserve(
+        &mut self,
+        market: Address,
+        account: Address,
+        key: &str,
+        request_id: U256,
+        expires_at: u64,
+        now: u64,
+    ) -> Option<SubmittedRequest> {
+        self.prune(now);
+        if let Some(existing) = self.get(market, account, key, now) {
+            return Some(existing.clone());
+        }
+        let pending =
+            SubmittedRequest { request_id: format!("0x{request_id:x}"), expires_at, pending: true };
+        self.requests.insert(store_key(market, account, key), pending);
+        None
+    }
+
+    /// Record the request submitted under the given key.
+    pub fn insert(
+        &mut self,
+        market: Address,
+        account: Address,
+        key: &str,
+        request_id: U256,
+        expires_at: u64,
+    ) {
+        let submitted = SubmittedRequest {
+            request_id: format!("0x{request_id:x}"),
+            expires_at,
+            pending: false,
+        };
+        self.requests.insert(store_key(market, account, key), submitted);
+    }
+
+    /// Drop the requests that expired before `now`, releasing their keys.
+    pub fn prune(&mut self, now: u64) {
+        self.requests.retain(|_, submitted| submitted.expires_at >= now);
+    }
+}
+
+fn store_key(market: Address, account: Address, key: &str) -> String {
+    format!("{market:#x}/{account:#x}/{key}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("idempotency.json");
+        let (market, account) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let key = key_from_contents(b"request");
+        assert_eq!(key, key_from_contents(b"request"));
+        assert_ne!(key, key_from_contents(b"other request"));
+
+        let mut store = IdempotencyStore::load(&path).unwrap();
+        store.insert(market, account, &key, U256::from(0xabc), 100);
+        store.insert(market, account, "expired", U256::from(0xdef), 10);
+        store.prune(50);
+        store.save(&path).unwrap();
+
+        let loaded = IdempotencyStore::load(&path).unwrap();
+        assert_eq!(loaded.requests.len(), 1);
+        let submitted = loaded.get(market, account, &key, 50).unwrap();
+        assert_eq!(submitted.request_id().unwrap(), U256::from(0xabc));
+        // Keys are scoped to the account, and released once the request expires.
+        assert!(loaded.get(market, Address::ZERO, &key, 50).is_none());
+        assert!(loaded.get(market, account, &key, 101).is_none());
+    }
+
+    #[test]
+    fn repeated_key_is_suppressed() {
+        let (market, account) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut store = IdempotencyStore::default();
+
+        // The first submission reserves the key.
+        assert!(store.reserve(market, account, "key", U256::from(1), 100, 50).is_none());
+        // A retry finds the reservation, whether or not the submission went through.
+        let pending = store.reserve(market, account, "key", U256::from(2), 100, 60).unwrap();
+        assert!(pending.pending);
+        assert_eq!(pending.request_id().unwrap(), U256::from(1));
+        store.insert(market, account, "key", U256::from(1), 100);
+        let submitted = store.reserve(market, account, "key", U256::from(3), 100, 70).unwrap();
+        assert!(!submitted.pending);
+        assert_eq!(submitted.request_id().unwrap(), U256::from(1));
+
+        // The key is released once the request expires.
+        assert!(store.reserve(market, account, "key", U256::from(4), 200, 101).is_none());
+        assert_eq!(store.requests.len(), 1);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encoding of structured guest inputs given on the command line.
+
+use std::{fs::File, io::BufReader, path::Path};
+
+use alloy::dyn_abi::{DynSolType, DynSolValue};
+use anyhow::{bail, Context, Result};
+use boundless_market::{storage::StandardStorageProvider, GuestEnv};
+use indicatif::{ProgressBar, ProgressStyle};
+use url::Url;
+
+/// Input files at least this large are streamed to the storage provider, rather than read into
+/// memory.
+pub const STREAM_INPUT_MIN_BYTES: u64 = 32 * 1024 * 1024;
+
+/// ABI-encode `values` as the parameters described by the comma-separated type list `types`.
+///
+/// The type list uses Solidity syntax, e.g. `uint256,address,(bool,bytes32)[]`, and each value is
+/// parsed as the type at the same position. The result is encoded as function parameters, so a
+/// guest can decode it with `abi_decode_params` on the matching tuple type.
+pub fn abi_encode(types: &str, values: &[String]) -> Result<Vec<u8>> {
+    let DynSolType::Tuple(types) = DynSolType::parse(&format!("({types})"))
+        .with_context(|| format!("Failed to parse ABI type list '{types}'"))?
+    else {
+        unreachable!("a parenthesized type list always parses as a tuple");
+    };
+    if types.len() != values.len() {
+        bail!("ABI type list has {} types, but {} values were given", types.len(), values.len());
+    }
+    let values = types
+        .iter()
+        .zip(values)
+        .map(|(ty, value)| {
+            ty.coerce_str(value).with_context(|| format!("Failed to parse '{value}' as {ty}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DynSolValue::Tuple(values).abi_encode_params())
+}
+
+/// Validate a JSON document and return it in compact form.
+///
+/// The guest receives the document as a `String` written with `risc0_zkvm::serde`, which it can
+/// read with `env::read::<String>()` and deserialize into its own input type with `serde_json`.
+pub fn json_input(json: &str) -> Result<String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("Failed to parse input JSON")?;
+    Ok(value.to_string())
+}
+
+/// Upload the input in the given file as the stdin of the guest, showing a progress bar.
+///
+/// The input is encoded into a temporary file, from which the storage provider streams the upload,
+/// so that neither the input nor its encoding is held in memory.
+pub async fn upload_input_file(
+    storage_provider: &StandardStorageProvider,
+    path: &Path,
+) -> Result<Url> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open input file at {path:?}"))?;
+    let len = file.metadata()?.len();
+    let encoded = tempfile::NamedTempFile::new().context("Failed to create temporary file")?;
+
+    // Encoding touches every byte of the input, so it runs off the async runtime.
+    let encoded = tokio::task::spawn_blocking(move || {
+        GuestEnv::encode_stdin_stream(BufReader::new(file), len, encoded.as_file())?;
+        anyhow::Ok(encoded)
+    })
+    .await?
+    .with_context(|| format!("Failed to encode input file at {path:?}"))?;
+
+    let progress = ProgressBar::new(encoded.as_file().metadata()?.len())
+        .with_style(
+            ProgressStyle::with_template(
+                "{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+            )?
+            .progress_chars("=> "),
+        )
+        .with_message("Uploading input");
+    let url = storage_provider
+        .upload_input_file(encoded.path(), &|uploaded| progress.set_position(uploaded))
+        .await
+        .context("Failed to upload input")?;
+    progress.finish_and_clear();
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{address, Address, U256},
+        sol_types::SolValue,
+    };
+
+    use super::*;
+
+    #[test]
+    fn abi_encode_params() {
+        let encoded = abi_encode(
+            "uint256,address,string",
+            &["42".into(), "0x00000000000000000000000000000000000000ff".into(), "hi".into()],
+        )
+        .unwrap();
+        let expected = (
+            U256::from(42),
+            address!("0x00000000000000000000000000000000000000ff"),
+            String::from("hi"),
+        )
+            .abi_encode_params();
+        assert_eq!(encoded, expected);
+        assert_eq!(
+            <(U256, Address, String)>::abi_decode_params(&encoded).unwrap().0,
+            U256::from(42)
+        );
+    }
+
+    #[test]
+    fn abi_encode_rejects_bad_values() {
+        assert!(abi_encode("uint256,bool", &["1".into()]).is_err());
+        assert!(abi_encode("uint8", &["256".into()]).is_err());
+        assert!(abi_encode("uint257", &["1".into()]).is_err());
+    }
+
+    #[test]
+    fn json_input_is_compacted() {
+        assert_eq!(json_input(r#"{ "a": [1, 2] }"#).unwrap(), r#"{"a":[1,2]}"#);
+        assert!(json_input("{").is_err());
+    }
+}
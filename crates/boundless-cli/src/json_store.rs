@@ -0,0 +1,133 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Small JSON files kept under `~/.boundless` to record local state across runs.
+//!
+//! Writes are atomic, and [update] holds an exclusive lock on a sibling `.lock` file while it
+//! loads, modifies, and saves a store, so that concurrent runs do not overwrite each other.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Default path of a store, the value of the `env` variable or `~/.boundless/<file_name>`.
+pub fn default_path(env: &str, file_name: &str) -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(env) {
+        return Some(PathBuf::from(path));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".boundless").join(file_name))
+}
+
+/// Load the `name` store from the given path, returning an empty store if it does not exist.
+pub fn load<T: DeserializeOwned + Default>(path: &Path, name: &str) -> Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse {name} store {}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => {
+            Err(err).with_context(|| format!("failed to read {name} store {}", path.display()))
+        }
+    }
+}
+
+/// Save the `name` store to the given path, creating its parent directory if needed.
+pub fn save<T: Serialize>(store: &T, path: &Path, name: &str) -> Result<()> {
+    create_parent(path)?;
+    let data = serde_json::to_vec_pretty(store)?;
+    AtomicFile::new(path, OverwriteBehavior::AllowOverwrite)
+        .write(|f| f.write_all(&data))
+        .with_context(|| format!("failed to write {name} store {}", path.display()))?;
+    Ok(())
+}
+
+/// Load the `name` store, apply `f` to it, and save it, all while holding its lock.
+///
+/// The store is only saved if `f` succeeds.
+pub fn update<T, R>(path: &Path, name: &str, f: impl FnOnce(&mut T) -> Result<R>) -> Result<R>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let _lock = lock(path, name)?;
+    let mut store = load(path, name)?;
+    let result = f(&mut store)?;
+    save(&store, path, name)?;
+    Ok(result)
+}
+
+/// Take an exclusive lock on the store at `path`, released when the returned file is dropped.
+///
+/// The lock is taken on a sibling file, since saving replaces the store file itself.
+fn lock(path: &Path, name: &str) -> Result<File> {
+    create_parent(path)?;
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("failed to open lock of {name} store {}", path.display()))?;
+    file.lock().with_context(|| format!("failed to lock {name} store {}", path.display()))?;
+    Ok(file)
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("store.json");
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || {
+                    update(path, "test", |store: &mut BTreeMap<String, u32>| {
+                        store.insert(i.to_string(), i);
+                        Ok(())
+                    })
+                    .unwrap()
+                });
+            }
+        });
+
+        let store: BTreeMap<String, u32> = load(&path, "test").unwrap();
+        assert_eq!(store.len(), 8);
+        // A failed update leaves the store untouched.
+        let err = update(&path, "test", |store: &mut BTreeMap<String, u32>| -> Result<()> {
+            store.clear();
+            anyhow::bail!("failed")
+        });
+        assert!(err.is_err());
+        assert_eq!(load::<BTreeMap<String, u32>>(&path, "test").unwrap().len(), 8);
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Encrypted JSON keystores of wallets, referenced by name with `--wallet`.
+//!
+//! Keystores use the Web3 Secret Storage format, so they can also be used with other tools such
+//! as `cast`. They are stored in `~/.boundless/keys` by default, one file per wallet, named after
+//! the wallet.
+
+use std::path::{Path, PathBuf};
+
+use alloy::{primitives::Address, signers::local::PrivateKeySigner};
+use anyhow::{bail, ensure, Context, Result};
+use rand_core::OsRng;
+
+/// Environment variable overriding the directory of the keystores.
+pub const KEYSTORE_DIR_ENV: &str = "BOUNDLESS_KEYSTORE_DIR";
+
+/// Environment variable holding the password of the keystores, instead of prompting for it.
+pub const KEYSTORE_PASSWORD_ENV: &str = "BOUNDLESS_WALLET_PASSWORD";
+
+/// Directory of encrypted keystores, one per named wallet.
+#[derive(Clone, Debug)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Keystore in the given directory, which is created when the first wallet is added.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Keystore in `$BOUNDLESS_KEYSTORE_DIR` or `~/.boundless/keys`.
+    pub fn from_env() -> Result<Self> {
+        if let Some(dir) = std::env::var_os(KEYSTORE_DIR_ENV) {
+            return Ok(Self::new(dir));
+        }
+        let home = std::env::var_os("HOME").with_context(|| {
+            format!("HOME is not set; please set {KEYSTORE_DIR_ENV} to the keystore directory")
+        })?;
+        Ok(Self::new(PathBuf::from(home).join(".boundless").join("keys")))
+    }
+
+    /// Directory holding the keystores.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the keystore of the named wallet.
+    pub fn path(&self, name: &str) -> Result<PathBuf> {
+        validate_name(name)?;
+        Ok(self.dir.join(name))
+    }
+
+    /// Encrypt the key of `signer` with `password` and store it as the named wallet.
+    ///
+    /// Fails if a wallet with this name already exists.
+    pub fn add(&self, name: &str, signer: &PrivateKeySigner, password: &str) -> Result<PathBuf> {
+        let path = self.path(name)?;
+        ensure!(!path.exists(), "Wallet {name} already exists at {}", path.display());
+        create_private_dir(&self.dir)?;
+        PrivateKeySigner::encrypt_keystore(
+            &self.dir,
+            &mut OsRng,
+            signer.to_bytes(),
+            password,
+            Some(name),
+        )
+        .with_context(|| format!("Failed to write keystore {}", path.display()))?;
+        // Record the address in plain text, as geth does, so wallets can be listed without their
+        // passwords.
+        let mut json = read_json(&path)?;
+        json["address"] = hex::encode(signer.address()).into();
+        std::fs::write(&path, serde_json::to_vec(&json)?)
+            .with_context(|| format!("Failed to write keystore {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(path)
+    }
+
+    /// Decrypt the key of the named wallet with `password`.
+    pub fn load(&self, name: &str, password: &str) -> Result<PrivateKeySigner> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            bail!(
+                "Wallet {name} not found in {}; run `boundless wallet list` to see the available wallets",
+                self.dir.display()
+            );
+        }
+        PrivateKeySigner::decrypt_keystore(&path, password)
+            .with_context(|| format!("Failed to decrypt wallet {name}; is the password correct?"))
+    }
+
+    /// Address of the named wallet, if recorded in plain text in its keystore.
+    pub fn address(&self, name: &str) -> Result<Option<Address>> {
+        let json = read_json(&self.path(name)?)?;
+        Ok(json.get("address").and_then(|address| address.as_str()?.parse().ok()))
+    }
+
+    /// Names of the wallets in the keystore, in alphabetical order.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to read keystore {}", self.dir.display()))
+            }
+        };
+        let mut names = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if !entry.file_type()?.is_file() {
+                continue;
+            }
+            if let Some(name) = entry.file_name().to_str() {
+                if validate_name(name).is_ok() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+/// Check that a wallet name is safe to use as a file name.
+fn validate_name(name: &str) -> Result<()> {
+    ensure!(
+        !name.is_empty()
+            && !name.starts_with('.')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')),
+        "Invalid wallet name {name:?}; use letters, digits, '-', '_' and '.', not starting with '.'"
+    );
+    Ok(())
+}
+
+fn read_json(path: &Path) -> Result<serde_json::Value> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read keystore {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse keystore {}", path.display()))
+}
+
+fn create_private_dir(dir: &Path) -> Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir).with_context(|| format!("Failed to create directory {}", dir.display()))
+}
+
+/// Password of the keystores, from `$BOUNDLESS_WALLET_PASSWORD` or prompted for without echo.
+///
+/// With `confirm`, as when creating a wallet, a prompted password must be entered twice.
+pub fn read_password(prompt: &str, confirm: bool) -> Result<String> {
+    if let Ok(password) = std::env::var(KEYSTORE_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    let password = rpassword::prompt_password(prompt).context("Failed to read password")?;
+    if confirm {
+        let again =
+            rpassword::prompt_password("Confirm password: ").context("Failed to read password")?;
+        ensure!(password == again, "Passwords do not match");
+    }
+    Ok(password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_load_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KeyStore::new(dir.path().join("keys"));
+        assert!(store.list().unwrap().is_empty());
+
+        let signer = PrivateKeySigner::random();
+        let path = store.add("prover", &signer, "hunter2").unwrap();
+        assert_eq!(path, dir.path().join("keys").join("prover"));
+        store.add("customer", &PrivateKeySigner::random(), "hunter2").unwrap();
+        assert_eq!(store.list().unwrap(), vec!["customer", "prover"]);
+        assert_eq!(store.address("prover").unwrap(), Some(signer.address()));
+
+        let loaded = store.load("prover", "hunter2").unwrap();
+        assert_eq!(loaded.address(), signer.address());
+        assert!(store.load("prover", "wrong").is_err());
+        assert!(store.load("missing", "hunter2").is_err());
+        assert!(store.add("prover", &signer, "hunter2").is_err());
+    }
+
+    #[test]
+    fn rejects_unsafe_names() {
+        let store = KeyStore::new("/nonexistent");
+        for name in ["", ".", "..", "../key", "a/b", ".hidden", "key name"] {
+            assert!(store.path(name).is_err(), "{name:?} should be rejected");
+        }
+        assert!(store.path("my-wallet_1.json").is_ok());
+    }
+}
@@ -18,37 +18,54 @@
 
 // TODO(victor): Break up the code below into modules.
 
+pub mod cancellation;
 pub mod commands;
+pub mod completion;
 pub mod config;
+#[cfg(feature = "dev")]
+pub mod dev;
+pub mod exit_code;
+pub mod format;
+pub mod hint;
+pub mod idempotency;
+pub mod input;
+pub mod json_store;
+pub mod keystore;
+pub mod prover_backend;
+pub mod table;
+pub mod tags;
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
 
 use alloy::{
     primitives::{Address, Bytes},
-    sol_types::{SolStruct, SolValue},
+    providers::DynProvider,
 };
 use anyhow::{bail, Context, Result};
 use bonsai_sdk::non_blocking::Client as BonsaiClient;
 use boundless_assessor::{AssessorInput, Fulfillment};
 use chrono::{DateTime, Local};
-use risc0_aggregation::{
-    merkle_path, GuestState, SetInclusionReceipt, SetInclusionReceiptVerifierParameters,
-};
+use risc0_aggregation::GuestState;
 use risc0_ethereum_contracts::encode_seal;
-use risc0_zkvm::{
-    compute_image_id, default_prover,
-    sha::{Digest, Digestible},
-    ExecutorEnv, ProverOpts, Receipt, ReceiptClaim,
-};
+use risc0_zkvm::{compute_image_id, default_prover, sha::Digest, ExecutorEnv, ProverOpts, Receipt};
 
 use boundless_market::{
     contracts::{
-        AssessorJournal, AssessorReceipt, EIP712DomainSaltless,
-        Fulfillment as BoundlessFulfillment, FulfillmentData, PredicateType, RequestInputType,
+        AssessorReceipt, EIP712DomainSaltless, Fulfillment as BoundlessFulfillment, Predicate,
+        RequestInputType, UNSPECIFIED_SELECTOR,
+    },
+    fulfillment::{
+        assessor_receipt, build_fulfillment, fulfillment_data, receipt_claim, AggregationSet,
     },
     input::GuestEnv,
-    selector::{is_groth16_selector, SupportedSelectors},
-    storage::fetch_url,
+    selector::{is_groth16_selector, SelectorRegistry, SupportedSelectors},
+    storage::{fetch_url, ProgramCache},
     ProofRequest,
 };
+use prover_backend::ProverBackend;
 
 alloy::sol!(
     #[sol(all_derives)]
@@ -86,6 +103,22 @@ impl OrderFulfilled {
     }
 }
 
+/// Fetches the program at the given URL, returning its image ID along with the program.
+///
+/// If a [ProgramCache] is given, the program is read from it when `image_id` is cached, and
+/// stored in it otherwise.
+pub async fn fetch_program(
+    cache: Option<&ProgramCache>,
+    url: &str,
+    image_id: Option<Digest>,
+) -> Result<(Digest, Vec<u8>)> {
+    if let Some(cache) = cache {
+        return cache.fetch_program(url, image_id).await;
+    }
+    let program = fetch_url(url).await?;
+    Ok((compute_image_id(&program)?, program))
+}
+
 /// Converts a timestamp to a [DateTime] in the local timezone.
 pub fn convert_timestamp(timestamp: u64) -> DateTime<Local> {
     let t = DateTime::from_timestamp(timestamp as i64, 0).expect("invalid timestamp");
@@ -117,6 +150,9 @@ pub struct DefaultProver {
     address: Address,
     domain: EIP712DomainSaltless,
     supported_selectors: SupportedSelectors,
+    selector_registry: Option<SelectorRegistry<DynProvider>>,
+    backend: ProverBackend,
+    program_cache: Option<ProgramCache>,
 }
 
 impl DefaultProver {
@@ -137,9 +173,32 @@ impl DefaultProver {
             address,
             domain,
             supported_selectors,
+            selector_registry: None,
+            backend: ProverBackend::default(),
+            program_cache: None,
         })
     }
 
+    /// Set the [ProverBackend] in use, which is checked to support the selector of each order.
+    ///
+    /// This does not configure the backend; see [ProverBackend::configure].
+    pub fn with_backend(self, backend: ProverBackend) -> Self {
+        Self { backend, ..self }
+    }
+
+    /// Set the [SelectorRegistry] of the deployment's verifier router.
+    ///
+    /// Orders whose selector the router does not route are rejected before proving them, since
+    /// their fulfillment could not be verified.
+    pub fn with_selector_registry(self, selector_registry: SelectorRegistry<DynProvider>) -> Self {
+        Self { selector_registry: Some(selector_registry), ..self }
+    }
+
+    /// Set the [ProgramCache] used to look up the programs of orders before fetching them.
+    pub fn with_program_cache(self, program_cache: ProgramCache) -> Self {
+        Self { program_cache: Some(program_cache), ..self }
+    }
+
     // Proves the given [program] with the given [input] and [assumptions].
     // The [opts] parameter specifies the prover options.
     pub(crate) async fn prove(
@@ -183,17 +242,12 @@ impl DefaultProver {
     // Finalizes the set builder.
     pub(crate) async fn finalize(
         &self,
-        claims: Vec<ReceiptClaim>,
+        set: &AggregationSet,
         assumptions: Vec<Receipt>,
     ) -> Result<Receipt> {
-        let input = GuestState::initial(self.set_builder_image_id)
-            .into_input(claims, true)
-            .context("Failed to build set builder input")?;
-        let encoded_input = bytemuck::pod_collect_to_vec(&risc0_zkvm::serde::to_vec(&input)?);
-
         self.prove(
             self.set_builder_program.clone(),
-            encoded_input,
+            set.set_builder_input()?,
             assumptions,
             ProverOpts::groth16(),
         )
@@ -209,9 +263,104 @@ impl DefaultProver {
         let assessor_input =
             AssessorInput { domain: self.domain.clone(), fills, prover_address: self.address };
 
-        let stdin = GuestEnv::builder().write_frame(&assessor_input.encode()).stdin;
+        self.prove(
+            self.assessor_program.clone(),
+            assessor_input.to_stdin(),
+            receipts,
+            ProverOpts::succinct(),
+        )
+        .await
+    }
+
+    /// Fetches the program and input of each order, checking that each program has the image ID
+    /// required by its order.
+    ///
+    /// Artifacts are fetched concurrently, and a URL shared by several orders is only fetched
+    /// once. Programs are read from the program cache when one is set. Any failed fetch or image ID mismatch fails the whole batch before proving starts,
+    /// with every mismatch in the batch reported at once.
+    async fn fetch_artifacts(
+        &self,
+        orders: &[(ProofRequest, Bytes)],
+    ) -> Result<Vec<OrderArtifacts>> {
+        let mut input_urls = Vec::with_capacity(orders.len());
+        let mut required_image_ids = Vec::with_capacity(orders.len());
+        for (req, _) in orders {
+            let input_url = match req.input.inputType {
+                RequestInputType::Inline => None,
+                RequestInputType::Url => Some(
+                    std::str::from_utf8(&req.input.data)
+                        .with_context(|| {
+                            format!("input url of request 0x{:x} is not utf8", req.id)
+                        })?
+                        .to_string(),
+                ),
+                _ => bail!("Unsupported input type on request 0x{:x}", req.id),
+            };
+            input_urls.push(input_url);
+            let predicate = Predicate::try_from(req.requirements.predicate.clone())
+                .with_context(|| format!("invalid predicate on request 0x{:x}", req.id))?;
+            required_image_ids.push(predicate.image_id());
+        }
+
+        // The image ID required by the first order using a program URL is used to look up the
+        // program cache. Orders requiring another image ID from the same URL fail the check below.
+        let mut program_urls = BTreeMap::new();
+        for ((req, _), required) in orders.iter().zip(&required_image_ids) {
+            program_urls.entry(req.imageUrl.as_str()).or_insert(*required);
+        }
+        let input_urls_set: BTreeSet<&str> =
+            input_urls.iter().flatten().map(String::as_str).collect();
+        tracing::debug!(
+            "Fetching {} programs and {} inputs for {} orders",
+            program_urls.len(),
+            input_urls_set.len(),
+            orders.len()
+        );
+        let program_jobs = program_urls.into_iter().map(|(url, image_id)| async move {
+            let (image_id, program) = fetch_program(self.program_cache.as_ref(), url, image_id)
+                .await
+                .with_context(|| format!("failed to fetch program {url}"))?;
+            Ok::<_, anyhow::Error>((url, (image_id, Arc::new(program))))
+        });
+        let input_jobs = input_urls_set.into_iter().map(|url| async move {
+            let input = fetch_url(url).await.with_context(|| format!("failed to fetch {url}"))?;
+            Ok::<_, anyhow::Error>((url, input))
+        });
+        let (programs, inputs) = futures::future::join(
+            futures::future::join_all(program_jobs),
+            futures::future::join_all(input_jobs),
+        )
+        .await;
+        let programs: HashMap<&str, (Digest, Arc<Vec<u8>>)> =
+            programs.into_iter().collect::<Result<_>>()?;
+        let inputs: HashMap<&str, Vec<u8>> = inputs.into_iter().collect::<Result<_>>()?;
+
+        let mut mismatches = Vec::new();
+        let mut artifacts = Vec::with_capacity(orders.len());
+        for (((req, _), input_url), required) in
+            orders.iter().zip(&input_urls).zip(required_image_ids)
+        {
+            let (image_id, program) = programs[req.imageUrl.as_str()].clone();
+            if let Some(required) = required {
+                if required != image_id {
+                    mismatches.push(format!(
+                        "request 0x{:x} requires image ID {required}, but the program at {} has \
+                         image ID {image_id}",
+                        req.id, req.imageUrl
+                    ));
+                }
+            }
 
-        self.prove(self.assessor_program.clone(), stdin, receipts, ProverOpts::succinct()).await
+            let input = match input_url {
+                Some(url) => GuestEnv::decode(&inputs[url.as_str()])?.stdin,
+                None => GuestEnv::decode(&req.input.data)?.stdin,
+            };
+            artifacts.push(OrderArtifacts { program, image_id, input });
+        }
+        if !mismatches.is_empty() {
+            bail!("Image ID mismatch in the batch:\n  {}", mismatches.join("\n  "));
+        }
+        Ok(artifacts)
     }
 
     /// Fulfills a list of orders, returning the relevant data:
@@ -222,54 +371,45 @@ impl DefaultProver {
         &self,
         orders: &[(ProofRequest, Bytes)],
     ) -> Result<(Vec<BoundlessFulfillment>, Receipt, AssessorReceipt)> {
-        let orders_jobs = orders.iter().cloned().map(|(req, sig)| async move {
-            let order_program = fetch_url(&req.imageUrl).await?;
-            let order_input: Vec<u8> = match req.input.inputType {
-                RequestInputType::Inline => GuestEnv::decode(&req.input.data)?.stdin,
-                RequestInputType::Url => {
-                    GuestEnv::decode(
-                        &fetch_url(
-                            std::str::from_utf8(&req.input.data)
-                                .context("input url is not utf8")?,
-                        )
-                        .await?,
-                    )?
-                    .stdin
+        let artifacts = self.fetch_artifacts(orders).await?;
+        let orders_jobs =
+            orders.iter().cloned().zip(artifacts).map(|((req, sig), artifacts)| async move {
+                let selector = req.requirements.selector;
+                if !self.supported_selectors.is_supported(selector) {
+                    bail!("Unsupported selector {}", req.requirements.selector);
+                };
+                if let Some(registry) = self.selector_registry.as_ref() {
+                    if selector != UNSPECIFIED_SELECTOR
+                        && registry.lookup(selector).await?.is_none()
+                    {
+                        bail!("Selector {selector} is not routed by the verifier router");
+                    }
                 }
-                _ => bail!("Unsupported input type"),
-            };
+                self.backend.check_selector(selector)?;
 
-            let selector = req.requirements.selector;
-            if !self.supported_selectors.is_supported(selector) {
-                bail!("Unsupported selector {}", req.requirements.selector);
-            };
+                let order_receipt = self
+                    .prove(
+                        artifacts.program.as_ref().clone(),
+                        artifacts.input,
+                        vec![],
+                        ProverOpts::succinct(),
+                    )
+                    .await?;
 
-            let order_receipt = self
-                .prove(order_program.clone(), order_input.clone(), vec![], ProverOpts::succinct())
-                .await?;
+                let order_journal = order_receipt.journal.bytes.clone();
+                let order_image_id = artifacts.image_id;
+                let order_claim = receipt_claim(order_image_id, order_journal.clone());
 
-            let order_journal = order_receipt.journal.bytes.clone();
-            let order_image_id = compute_image_id(&order_program)?;
-            let order_claim = ReceiptClaim::ok(order_image_id, order_journal.clone());
-            let order_claim_digest = order_claim.digest();
+                let fulfillment_data = fulfillment_data(&req, order_image_id, order_journal)?;
+                let fill =
+                    Fulfillment { request: req.clone(), signature: sig.into(), fulfillment_data };
 
-            let fulfillment_data = match req.requirements.predicate.predicateType {
-                PredicateType::ClaimDigestMatch => FulfillmentData::None,
-                PredicateType::PrefixMatch | PredicateType::DigestMatch => {
-                    FulfillmentData::from_image_id_and_journal(order_image_id, order_journal)
-                }
-                _ => bail!("Invalid predicate type"),
-            };
-            let fill =
-                Fulfillment { request: req.clone(), signature: sig.into(), fulfillment_data };
-
-            Ok::<_, anyhow::Error>((order_receipt, order_claim, order_claim_digest, fill))
-        });
+                Ok::<_, anyhow::Error>((order_receipt, order_claim, fill))
+            });
 
         let results = futures::future::join_all(orders_jobs).await;
         let mut receipts = Vec::new();
-        let mut claims = Vec::new();
-        let mut claim_digests = Vec::new();
+        let mut set = AggregationSet::new(self.set_builder_image_id);
         let mut fills = Vec::new();
 
         for (i, result) in results.into_iter().enumerate() {
@@ -277,78 +417,50 @@ impl DefaultProver {
                 tracing::warn!("Failed to prove request 0x{:x}: {}", orders[i].0.id, e);
                 continue;
             }
-            let (receipt, claim, claim_digest, fill) = result?;
+            let (receipt, claim, fill) = result?;
             receipts.push(receipt);
-            claims.push(claim);
-            claim_digests.push(claim_digest);
+            set.push(claim);
             fills.push(fill);
         }
 
-        let assessor_receipt = self.assessor(fills.clone(), receipts.clone()).await?;
-        let assessor_journal = assessor_receipt.journal.bytes.clone();
+        let assessor_proof = self.assessor(fills.clone(), receipts.clone()).await?;
+        let assessor_journal = assessor_proof.journal.bytes.clone();
         let assessor_image_id = compute_image_id(&self.assessor_program)?;
-        let assessor_claim = ReceiptClaim::ok(assessor_image_id, assessor_journal.clone());
-        let assessor_receipt_journal: AssessorJournal =
-            AssessorJournal::abi_decode(&assessor_journal)?;
-
-        receipts.push(assessor_receipt);
-        claims.push(assessor_claim.clone());
-        claim_digests.push(assessor_claim.digest());
+        let assessor_index = set.push(receipt_claim(assessor_image_id, assessor_journal.clone()));
+        receipts.push(assessor_proof);
 
-        let root_receipt = self.finalize(claims.clone(), receipts.clone()).await?;
-
-        let verifier_parameters =
-            SetInclusionReceiptVerifierParameters { image_id: self.set_builder_image_id };
+        let root_receipt = self.finalize(&set, receipts.clone()).await?;
 
         let mut boundless_fills = Vec::new();
-
-        for i in 0..fills.len() {
-            let order_inclusion_receipt = SetInclusionReceipt::from_path_with_verifier_params(
-                claims[i].clone(),
-                merkle_path(&claim_digests, i),
-                verifier_parameters.digest(),
-            );
-            let (req, _sig) = &orders[i];
-            let order_seal = if is_groth16_selector(req.requirements.selector) {
+        for (i, fill) in fills.into_iter().enumerate() {
+            let order_seal = if is_groth16_selector(fill.request.requirements.selector) {
                 let receipt = self.compress(&receipts[i]).await?;
-                encode_seal(&receipt)?
+                encode_seal(&receipt)?.into()
             } else {
-                order_inclusion_receipt.abi_encode_seal()?
-            };
-
-            let (fulfillment_data_type, fulfillment_data) =
-                fills[i].fulfillment_data.fulfillment_type_and_data();
-            let claim_digest = fills[i].evaluate_requirements()?;
-
-            let fulfillment = BoundlessFulfillment {
-                claimDigest: <[u8; 32]>::from(claim_digest).into(),
-                fulfillmentData: fulfillment_data.into(),
-                fulfillmentDataType: fulfillment_data_type,
-                id: req.id,
-                requestDigest: req.eip712_signing_hash(&self.domain.alloy_struct()),
-                seal: order_seal.into(),
+                set.inclusion_seal(i)?
             };
-
-            boundless_fills.push(fulfillment);
+            boundless_fills.push(build_fulfillment(
+                &fill.request,
+                fill.fulfillment_data,
+                order_seal,
+                &self.domain,
+            )?);
         }
 
-        let assessor_inclusion_receipt = SetInclusionReceipt::from_path_with_verifier_params(
-            assessor_claim,
-            merkle_path(&claim_digests, claim_digests.len() - 1),
-            verifier_parameters.digest(),
-        );
-
-        let assessor_receipt = AssessorReceipt {
-            seal: assessor_inclusion_receipt.abi_encode_seal()?.into(),
-            prover: self.address,
-            selectors: assessor_receipt_journal.selectors,
-            callbacks: assessor_receipt_journal.callbacks,
-        };
+        let assessor_receipt =
+            assessor_receipt(&assessor_journal, set.inclusion_seal(assessor_index)?, self.address)?;
 
         Ok((boundless_fills, root_receipt, assessor_receipt))
     }
 }
 
+/// Program and input of an order, fetched before proving.
+struct OrderArtifacts {
+    program: Arc<Vec<u8>>,
+    image_id: Digest,
+    input: Vec<u8>,
+}
+
 async fn compress_with_bonsai(succinct_receipt: &Receipt) -> Result<Receipt> {
     let client = BonsaiClient::from_env(risc0_zkvm::VERSION)?;
     let encoded_receipt = bincode::serialize(succinct_receipt)?;
@@ -376,8 +488,8 @@ async fn compress_with_bonsai(succinct_receipt: &Receipt) -> Result<Receipt> {
     }
 }
 
-// Returns `true` if the dev mode environment variable is enabled.
-fn is_dev_mode() -> bool {
+/// Returns `true` if the dev mode environment variable is enabled.
+pub fn is_dev_mode() -> bool {
     std::env::var("RISC0_DEV_MODE")
         .ok()
         .map(|x| x.to_lowercase())
@@ -438,6 +550,43 @@ mod tests {
         prover.fulfill(&[(request, signature.as_bytes().into())]).await.unwrap();
     }
 
+    #[tokio::test]
+    #[ignore = "Requires RISC0_DEV_MODE=1"]
+    async fn test_fetch_artifacts_image_id_mismatch() {
+        let signer = PrivateKeySigner::random();
+        let (request, signature) = setup_proving_request_and_signature(&signer, None).await;
+        let mut mismatched = request.clone();
+        mismatched.id = RequestId::new(signer.address(), 1).into();
+        mismatched.requirements.predicate = Predicate::prefix_match(Digest::ZERO, vec![1]).into();
+
+        let domain = eip712_domain(Address::ZERO, 1);
+        let prover = DefaultProver::new(
+            SET_BUILDER_ELF.to_vec(),
+            ASSESSOR_GUEST_ELF.to_vec(),
+            Address::ZERO,
+            domain,
+        )
+        .expect("failed to create prover");
+
+        let artifacts = prover
+            .fetch_artifacts(&[(request.clone(), signature.as_bytes().into())])
+            .await
+            .unwrap();
+        assert_eq!(artifacts[0].image_id, Digest::from(ECHO_ID));
+        assert_eq!(artifacts[0].input, vec![1, 2, 3, 4]);
+
+        let err = prover
+            .fulfill(&[
+                (request, signature.as_bytes().into()),
+                (mismatched.clone(), signature.as_bytes().into()),
+            ])
+            .await
+            .unwrap_err();
+        let err = err.to_string();
+        assert!(err.contains(&format!("request 0x{:x}", mismatched.id)));
+        assert!(err.contains(&Digest::from(ECHO_ID).to_string()));
+    }
+
     #[tokio::test]
     #[ignore = "runs a proof; slow without RISC0_DEV_MODE=1"]
     async fn test_fulfill() {
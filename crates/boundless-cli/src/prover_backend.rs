@@ -0,0 +1,219 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Selection of the proving backend used to fulfill requests.
+//!
+//! [ProverBackend] is resolved from a [ProverConfig] in one place, so that the fulfill and
+//! benchmark commands, and library consumers of [DefaultProver](crate::DefaultProver), agree on
+//! which prover is in use and what it is capable of.
+
+use std::fmt;
+
+use alloy::primitives::FixedBytes;
+use anyhow::{bail, Context, Result};
+use bonsai_sdk::non_blocking::Client as BonsaiClient;
+use boundless_market::selector::is_groth16_selector;
+use risc0_zkvm::{default_prover, ProverOpts};
+use url::Url;
+
+use crate::{config::ProverConfig, is_dev_mode};
+
+/// Domain of the hosted Bonsai proving service.
+const BONSAI_DOMAIN: &str = "bonsai.xyz";
+
+/// Backend used to generate proofs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProverBackend {
+    /// The zkVM default prover, selected by `RISC0_PROVER`, `RISC0_DEV_MODE`, etc.
+    #[default]
+    Local,
+    /// The hosted Bonsai proving service.
+    Bonsai {
+        /// URL of the Bonsai API.
+        url: Url,
+        /// Bonsai API key.
+        api_key: String,
+    },
+    /// A Bento cluster, which exposes the Bonsai API.
+    Bento {
+        /// URL of the Bento API.
+        url: Url,
+        /// API key, if the cluster requires authentication.
+        api_key: Option<String>,
+    },
+}
+
+impl ProverBackend {
+    /// Resolve the backend selected by the given [ProverConfig].
+    ///
+    /// A Bento API URL on the Bonsai domain selects [ProverBackend::Bonsai], which requires an
+    /// API key.
+    pub fn from_config(config: &ProverConfig) -> Result<Self> {
+        if config.use_default_prover {
+            return Ok(Self::Local);
+        }
+        let url = Url::parse(&config.bento_api_url)
+            .with_context(|| format!("Failed to parse Bento API URL: {}", config.bento_api_url))?;
+        let is_bonsai = url.domain().is_some_and(|domain| {
+            domain == BONSAI_DOMAIN || domain.ends_with(&format!(".{BONSAI_DOMAIN}"))
+        });
+        if !is_bonsai {
+            return Ok(Self::Bento { url, api_key: config.bento_api_key.clone() });
+        }
+        let api_key =
+            config.bento_api_key.clone().context("An API key is required to prove with Bonsai")?;
+        Ok(Self::Bonsai { url, api_key })
+    }
+
+    /// URL and API key of the Bonsai API, if the backend exposes it.
+    fn bonsai_api(&self) -> Option<(&Url, Option<&str>)> {
+        match self {
+            Self::Local => None,
+            Self::Bonsai { url, api_key } => Some((url, Some(api_key))),
+            Self::Bento { url, api_key } => Some((url, api_key.as_deref())),
+        }
+    }
+
+    /// Sets the `BONSAI_API_URL` and `BONSAI_API_KEY` environment variables that are read by
+    /// `default_prover()` when constructing the prover.
+    ///
+    /// For [ProverBackend::Local], the environment is left untouched.
+    pub fn configure(&self) {
+        let Some((url, api_key)) = self.bonsai_api() else {
+            tracing::info!(
+                "Using default prover behavior (respects RISC0_PROVER, RISC0_DEV_MODE, etc.)"
+            );
+            return;
+        };
+
+        tracing::info!("Using {self}");
+        std::env::set_var("BONSAI_API_URL", url.as_str().trim_end_matches('/'));
+        if let Some(api_key) = api_key {
+            std::env::set_var("BONSAI_API_KEY", api_key);
+        } else {
+            tracing::debug!("No API key provided. Setting BONSAI_API_KEY to empty string");
+            std::env::set_var("BONSAI_API_KEY", "");
+        }
+    }
+
+    /// Returns a client for the Bonsai API of the backend.
+    ///
+    /// Returns an error for [ProverBackend::Local], which has no API.
+    pub fn bonsai_client(&self) -> Result<BonsaiClient> {
+        let Some((url, api_key)) = self.bonsai_api() else {
+            bail!("the default prover does not expose the Bonsai API; use Bento or Bonsai");
+        };
+        BonsaiClient::from_parts(
+            url.as_str().trim_end_matches('/').to_string(),
+            api_key.unwrap_or_default().to_string(),
+            risc0_zkvm::VERSION,
+        )
+        .context("Failed to create Bonsai client")
+    }
+
+    /// Returns `true` if the backend can compress receipts to Groth16.
+    ///
+    /// Bonsai and Bento always can. The local prover can in dev mode, where receipts are fake,
+    /// when `RISC0_PROVER` delegates to Bonsai, or on x86_64, where the zkVM runs the Groth16
+    /// prover in Docker.
+    pub fn supports_groth16(&self) -> bool {
+        match self {
+            Self::Bonsai { .. } | Self::Bento { .. } => true,
+            Self::Local => {
+                is_dev_mode()
+                    || default_prover().get_name() == "bonsai"
+                    || cfg!(target_arch = "x86_64")
+            }
+        }
+    }
+
+    /// Check that the backend can produce a seal for the given selector.
+    pub fn check_selector(&self, selector: FixedBytes<4>) -> Result<()> {
+        if is_groth16_selector(selector) && !self.supports_groth16() {
+            bail!("{self} cannot produce Groth16 proofs required by selector {selector}");
+        }
+        Ok(())
+    }
+
+    /// Run a basic health check against the backend, to give a clear error early if it cannot
+    /// be reached.
+    ///
+    /// No check is done for [ProverBackend::Local], or in dev mode.
+    pub async fn health_check(&self) -> Result<()> {
+        let Some((url, _)) = self.bonsai_api() else {
+            return Ok(());
+        };
+        if ProverOpts::default().dev_mode() {
+            return Ok(());
+        }
+        let health_check_url = url.join("health")?;
+        reqwest::get(health_check_url.clone())
+            .await
+            .with_context(|| format!("Failed to send health check reqest to {health_check_url}"))?
+            .error_for_status()
+            .with_context(|| format!("{self} health check endpoint returned error status"))?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for ProverBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "default prover"),
+            Self::Bonsai { url, .. } => write!(f, "Bonsai prover at {url}"),
+            Self::Bento { url, .. } => write!(f, "Bento prover at {url}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use risc0_ethereum_contracts::selector::Selector;
+
+    fn prover_config(url: &str, api_key: Option<&str>) -> ProverConfig {
+        ProverConfig {
+            bento_api_url: url.to_string(),
+            bento_api_key: api_key.map(str::to_string),
+            use_default_prover: false,
+            skip_health_check: false,
+        }
+    }
+
+    #[test]
+    fn resolves_backend() {
+        let config = ProverConfig { use_default_prover: true, ..prover_config("", None) };
+        assert_eq!(ProverBackend::from_config(&config).unwrap(), ProverBackend::Local);
+
+        let backend =
+            ProverBackend::from_config(&prover_config("http://localhost:8081", None)).unwrap();
+        assert!(matches!(backend, ProverBackend::Bento { api_key: None, .. }));
+
+        let backend =
+            ProverBackend::from_config(&prover_config("https://api.bonsai.xyz", Some("key")))
+                .unwrap();
+        assert!(matches!(backend, ProverBackend::Bonsai { .. }));
+
+        ProverBackend::from_config(&prover_config("https://api.bonsai.xyz", None)).unwrap_err();
+        ProverBackend::from_config(&prover_config("not a url", None)).unwrap_err();
+    }
+
+    #[test]
+    fn remote_backends_support_groth16() {
+        let backend =
+            ProverBackend::from_config(&prover_config("http://localhost:8081", None)).unwrap();
+        backend.check_selector(FixedBytes::from(Selector::groth16_latest() as u32)).unwrap();
+        backend.check_selector(FixedBytes::from(Selector::set_inclusion_latest() as u32)).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Aligned tables for the rows printed by listing commands.
+//!
+//! Columns are padded to their widest cell. When color is enabled, the header is bold and cells
+//! may be styled, e.g. errors in red; styling never changes the alignment. With `--quiet`, the
+//! header is omitted so that the rows can be piped into other tools.
+
+/// Horizontal alignment of a column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Align {
+    /// Pad on the right, for text.
+    #[default]
+    Left,
+    /// Pad on the left, for numbers and amounts.
+    Right,
+}
+
+/// Color or emphasis of a cell, shown if color is enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    /// Bold text.
+    Bold,
+    /// Dimmed text, for placeholders and secondary values.
+    Dim,
+    /// Green text, for healthy or successful values.
+    Green,
+    /// Yellow text, for values needing attention.
+    Yellow,
+    /// Red text, for errors.
+    Red,
+}
+
+impl Style {
+    fn ansi_code(self) -> &'static str {
+        match self {
+            Self::Bold => "1",
+            Self::Dim => "2",
+            Self::Green => "32",
+            Self::Yellow => "33",
+            Self::Red => "31",
+        }
+    }
+
+    /// Wrap `text` in the escape codes of the style.
+    pub fn paint(self, text: &str) -> String {
+        format!("\x1b[{}m{text}\x1b[0m", self.ansi_code())
+    }
+}
+
+/// A cell of a [Table], with an optional [Style].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cell {
+    text: String,
+    style: Option<Style>,
+}
+
+impl Cell {
+    /// A cell displaying `text` with the given style.
+    pub fn styled(text: impl Into<String>, style: Style) -> Self {
+        Self { text: text.into(), style: Some(style) }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Self { text, style: None }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        text.to_string().into()
+    }
+}
+
+/// Rows of cells, rendered in aligned columns under a header.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    /// A table with the given column headers, all left-aligned.
+    pub fn new<S: Into<String>>(headers: impl IntoIterator<Item = S>) -> Self {
+        let headers: Vec<String> = headers.into_iter().map(Into::into).collect();
+        let aligns = vec![Align::Left; headers.len()];
+        Self { headers, aligns, rows: Vec::new() }
+    }
+
+    /// Right-align the given columns.
+    pub fn align_right(mut self, columns: impl IntoIterator<Item = usize>) -> Self {
+        for column in columns {
+            self.aligns[column] = Align::Right;
+        }
+        self
+    }
+
+    /// Append a row. Missing cells are left empty, and extra cells are dropped.
+    pub fn push<C: Into<Cell>>(&mut self, row: impl IntoIterator<Item = C>) {
+        let mut row: Vec<Cell> = row.into_iter().map(Into::into).collect();
+        row.resize(self.headers.len(), Cell::default());
+        self.rows.push(row);
+    }
+
+    /// Whether the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render the table, styling it if `color` is set and omitting the header if `quiet` is set.
+    pub fn render(&self, color: bool, quiet: bool) -> String {
+        let mut widths = vec![0; self.headers.len()];
+        let header: Option<Vec<Cell>> = (!quiet).then(|| {
+            self.headers.iter().map(|header| Cell::styled(header.as_str(), Style::Bold)).collect()
+        });
+        for row in header.iter().chain(&self.rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.text.chars().count());
+            }
+        }
+
+        let lines: Vec<String> = header
+            .iter()
+            .chain(&self.rows)
+            .map(|row| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        let last = column + 1 == row.len();
+                        let padding = widths[column] - cell.text.chars().count();
+                        let text = match cell.style {
+                            Some(style) if color => style.paint(&cell.text),
+                            _ => cell.text.clone(),
+                        };
+                        match self.aligns[column] {
+                            Align::Left if last => text,
+                            Align::Left => format!("{text}{}", " ".repeat(padding)),
+                            Align::Right => format!("{}{text}", " ".repeat(padding)),
+                        }
+                    })
+                    .collect();
+                cells.join("  ").trim_end().to_string()
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(["chain", "balance", "status"]).align_right([1]);
+        table.push(["base", "1.5 ETH", "ok"]);
+        table.push([Cell::from("sepolia"), "10 ETH".into(), Cell::styled("error", Style::Red)]);
+        table
+    }
+
+    #[test]
+    fn renders_aligned_columns() {
+        assert_eq!(
+            table().render(false, false),
+            "chain    balance  status\n\
+             base     1.5 ETH  ok\n\
+             sepolia   10 ETH  error"
+        );
+        assert_eq!(table().render(false, true), "base     1.5 ETH  ok\nsepolia   10 ETH  error");
+    }
+
+    #[test]
+    fn color_does_not_change_alignment() {
+        let rendered = table().render(true, false);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "\x1b[1mchain\x1b[0m    \x1b[1mbalance\x1b[0m  \x1b[1mstatus\x1b[0m");
+        assert_eq!(lines[2], "sepolia   10 ETH  \x1b[31merror\x1b[0m");
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local tags and notes attached to request IDs.
+//!
+//! Tags and notes are never sent onchain or to the order stream. They are kept in a small local
+//! store so operators managing many requests can keep track of what each one is for.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use alloy::primitives::U256;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::json_store;
+
+/// Environment variable overriding the path of the tag store.
+pub const TAG_STORE_ENV: &str = "BOUNDLESS_TAG_STORE";
+
+/// Tags and note attached to a request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RequestTags {
+    /// Tags of the request.
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// Free-form note on the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl RequestTags {
+    /// Whether there are neither tags nor a note.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.is_none()
+    }
+}
+
+impl fmt::Display for RequestTags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tags = self.tags.iter().map(String::as_str).collect::<Vec<_>>().join(",");
+        match (&self.note, tags.is_empty()) {
+            (None, _) => write!(f, "{tags}"),
+            (Some(note), true) => write!(f, "\"{note}\""),
+            (Some(note), false) => write!(f, "{tags} \"{note}\""),
+        }
+    }
+}
+
+/// Local store of the tags and notes of requests, keyed by request ID.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TagStore {
+    /// Tags and note of each request, keyed by the request ID as a hex string.
+    #[serde(default)]
+    pub requests: BTreeMap<String, RequestTags>,
+}
+
+impl TagStore {
+    /// Default path of the store, `$BOUNDLESS_TAG_STORE` or `~/.boundless/tags.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        json_store::default_path(TAG_STORE_ENV, "tags.json")
+    }
+
+    /// Path of the store, or an error that can be shown to the user if there is none.
+    pub fn require_default_path() -> Result<PathBuf> {
+        Self::default_path().with_context(|| {
+            format!("HOME is not set; please set {TAG_STORE_ENV} to the path of the tag store")
+        })
+    }
+
+    /// Load the store from the given path, returning an empty store if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        json_store::load(path, "tag")
+    }
+
+    /// Load the store from its default path.
+    ///
+    /// Used to show tags alongside the output of other commands, so failures are logged and an
+    /// empty store is returned instead.
+    pub fn load_default() -> Self {
+        let Some(path) = Self::default_path() else {
+            return Self::default();
+        };
+        Self::load(&path).unwrap_or_else(|err| {
+            tracing::warn!("Failed to load request tags: {err:?}");
+            Self::default()
+        })
+    }
+
+    /// Save the store to the given path, creating its parent directory if needed.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        json_store::save(self, path, "tag")
+    }
+
+    /// Load the store from the given path, apply `f` to it, and save it, while holding its lock.
+    pub fn update<R>(path: &Path, f: impl FnOnce(&mut Self) -> Result<R>) -> Result<R> {
+        json_store::update(path, "tag", f)
+    }
+
+    /// Tags and note of the given request, if any.
+    pub fn get(&self, request_id: U256) -> Option<&RequestTags> {
+        self.requests.get(&key(request_id))
+    }
+
+    /// Tags and note of the given request, to be modified.
+    ///
+    /// Requests left without tags or a note are dropped from the store when it is saved.
+    pub fn entry(&mut self, request_id: U256) -> &mut RequestTags {
+        self.requests.entry(key(request_id)).or_default()
+    }
+
+    /// Drop the requests without tags or a note.
+    pub fn prune(&mut self) {
+        self.requests.retain(|_, tags| !tags.is_empty());
+    }
+
+    /// Whether the given request has the given tag.
+    pub fn has_tag(&self, request_id: U256, tag: &str) -> bool {
+        self.get(request_id).is_some_and(|tags| tags.tags.contains(tag))
+    }
+}
+
+fn key(request_id: U256) -> String {
+    format!("0x{request_id:x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("tags.json");
+        assert!(TagStore::load(&path).unwrap().requests.is_empty());
+
+        let mut store = TagStore::default();
+        let entry = store.entry(U256::from(0xabc));
+        entry.tags.insert("prod".to_string());
+        entry.note = Some("customer X".to_string());
+        store.entry(U256::from(0xdef));
+        store.prune();
+        store.save(&path).unwrap();
+
+        let loaded = TagStore::load(&path).unwrap();
+        assert_eq!(loaded.requests.len(), 1);
+        assert!(loaded.has_tag(U256::from(0xabc), "prod"));
+        assert!(!loaded.has_tag(U256::from(0xabc), "staging"));
+        assert_eq!(loaded.get(U256::from(0xabc)).unwrap().to_string(), "prod \"customer X\"");
+    }
+}
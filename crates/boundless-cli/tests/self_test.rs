@@ -0,0 +1,49 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Integration tests for the self-test command.
+
+#![cfg(feature = "self-test")]
+
+use assert_cmd::Command;
+use predicates::str::contains;
+
+#[test]
+fn test_self_test() -> anyhow::Result<()> {
+    let output = Command::cargo_bin("boundless")?
+        .args(["--json", "self-test"])
+        .env("RISC0_DEV_MODE", "1")
+        .env("NO_COLOR", "1")
+        .output()?;
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let stages = report.as_array().unwrap();
+    assert_eq!(stages.len(), 6);
+    for stage in stages {
+        assert_eq!(stage["status"], "pass", "{stage}");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_self_test_requires_dev_mode() -> anyhow::Result<()> {
+    Command::cargo_bin("boundless")?
+        .arg("self-test")
+        .env("RISC0_DEV_MODE", "0")
+        .assert()
+        .failure()
+        .stderr(contains("RISC0_DEV_MODE=1"));
+    Ok(())
+}
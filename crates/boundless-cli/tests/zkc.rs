@@ -16,6 +16,7 @@
 
 use alloy::{
     primitives::{utils::format_ether, U256},
+    providers::ext::AnvilApi,
     signers::local::PrivateKeySigner,
 };
 use assert_cmd::Command;
@@ -237,3 +238,65 @@ async fn test_get_current_epoch() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_compound() -> anyhow::Result<()> {
+    // Set up a local Anvil node with the required contracts
+    let ctx = test_ctx().await?;
+
+    // Use an Anvil-provided signer for transaction signing (with balance)
+    let user: PrivateKeySigner = ctx.anvil.lock().await.keys()[1].clone().into();
+    let user_private_key = format!("0x{}", hex::encode(user.to_bytes()));
+    let rpc_url = ctx.anvil.lock().await.endpoint_url();
+    let compound = |args: &[&str]| -> anyhow::Result<Command> {
+        let mut cmd = Command::cargo_bin("boundless")?;
+        cmd.args(["zkc", "compound"])
+            .args(args)
+            .env("ZKC_ADDRESS", format!("{:#x}", ctx.deployment.zkc_address))
+            .env("VEZKC_ADDRESS", format!("{:#x}", ctx.deployment.vezkc_address))
+            .env(
+                "STAKING_REWARDS_ADDRESS",
+                format!("{:#x}", ctx.deployment.staking_rewards_address),
+            )
+            .env("RPC_URL", rpc_url.as_str())
+            .env("PRIVATE_KEY", &user_private_key)
+            .env("NO_COLOR", "1")
+            .env("RUST_LOG", "boundless_cli=debug,info");
+        Ok(cmd)
+    };
+
+    // Fund the user and stake half of the tokens
+    let amount = U256::from(1_000_000_000);
+    let stake_amount = U256::from(500_000_000);
+    ctx.zkc.initialMint(vec![user.address()], vec![amount]).send().await?.watch().await?;
+    let mut cmd = Command::cargo_bin("boundless")?;
+    cmd.args(["zkc", "stake", "--amount", &format_ether(stake_amount)])
+        .env("ZKC_ADDRESS", format!("{:#x}", ctx.deployment.zkc_address))
+        .env("VEZKC_ADDRESS", format!("{:#x}", ctx.deployment.vezkc_address))
+        .env("STAKING_REWARDS_ADDRESS", format!("{:#x}", ctx.deployment.staking_rewards_address))
+        .env("RPC_URL", rpc_url.as_str())
+        .env("PRIVATE_KEY", &user_private_key)
+        .env("NO_COLOR", "1")
+        .write_stdin("yes\n")
+        .assert()
+        .success();
+
+    // No epoch has ended yet, so there is nothing to compound.
+    compound(&["--dry-run"])?.assert().success().stdout(contains("no pending rewards"));
+
+    // End the epoch in which the user staked, so that its rewards are pending.
+    let epoch_end = ctx.zkc.getCurrentEpochEndTime().call().await?;
+    ctx.provider.anvil_set_next_block_timestamp(epoch_end.to::<u64>() + 1).await?;
+    ctx.provider.anvil_mine(Some(1), None).await?;
+
+    // Rewards are left pending while the gas price is above the maximum.
+    compound(&["--max-gas-price", "0"])?.assert().success().stdout(contains("gas price of"));
+
+    compound(&[])?.assert().success().stdout(contains("Compounded"));
+
+    // Only the claimed rewards are staked, not the tokens the user already held.
+    let balance = ctx.zkc.balanceOf(user.address()).call().await?;
+    assert_eq!(balance, amount - stake_amount);
+
+    Ok(())
+}
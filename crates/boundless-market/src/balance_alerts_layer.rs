@@ -17,6 +17,8 @@ use alloy::primitives::{Address, U256};
 use alloy::providers::{PendingTransactionBuilder, Provider, ProviderLayer, RootProvider};
 use alloy::transports::TransportResult;
 
+use crate::event_bus::{AlertLevel, BalanceAsset, BusEvent, EventBus};
+
 /// Configuration for the BalanceAlertLayer
 #[derive(Debug, Clone, Default)]
 pub struct BalanceAlertConfig {
@@ -34,6 +36,7 @@ pub struct BalanceAlertConfig {
 #[derive(Debug, Clone, Default)]
 pub struct BalanceAlertLayer {
     config: BalanceAlertConfig,
+    event_bus: Option<EventBus>,
 }
 
 /// A ProviderLayer that can be added to an alloy Provider
@@ -62,7 +65,12 @@ pub struct BalanceAlertLayer {
 impl BalanceAlertLayer {
     /// Creates a new BalanceAlertLayer with the given configuration.
     pub fn new(config: BalanceAlertConfig) -> Self {
-        Self { config }
+        Self { config, event_bus: None }
+    }
+
+    /// Publishes the alerts on the given [EventBus], in addition to logging them.
+    pub fn with_event_bus(self, event_bus: impl Into<Option<EventBus>>) -> Self {
+        Self { event_bus: event_bus.into(), ..self }
     }
 }
 
@@ -73,7 +81,7 @@ where
     type Provider = BalanceAlertProvider<P>;
 
     fn layer(&self, inner: P) -> Self::Provider {
-        BalanceAlertProvider::new(inner, self.config.clone())
+        BalanceAlertProvider::new(inner, self.config.clone(), self.event_bus.clone())
     }
 }
 
@@ -83,6 +91,7 @@ where
 pub struct BalanceAlertProvider<P> {
     inner: P,
     config: BalanceAlertConfig,
+    event_bus: Option<EventBus>,
 }
 
 impl<P> BalanceAlertProvider<P>
@@ -90,8 +99,8 @@ where
     P: Provider,
 {
     #[allow(clippy::missing_const_for_fn)]
-    fn new(inner: P, config: BalanceAlertConfig) -> Self {
-        Self { inner, config }
+    fn new(inner: P, config: BalanceAlertConfig, event_bus: Option<EventBus>) -> Self {
+        Self { inner, config, event_bus }
     }
 }
 
@@ -118,20 +127,31 @@ where
         let res = self.inner.send_raw_transaction(encoded_tx).await;
         let balance = self.inner.get_balance(self.config.watch_address).await?;
 
-        if balance < self.config.error_threshold.unwrap_or(U256::ZERO) {
+        let level = if balance < self.config.error_threshold.unwrap_or(U256::ZERO) {
             tracing::error!(
                 "[B-BAL-ETH] balance of {} < error threshold: {}",
                 self.config.watch_address,
                 balance
             );
+            Some(AlertLevel::Error)
         } else if balance < self.config.warn_threshold.unwrap_or(U256::ZERO) {
             tracing::warn!(
                 "[B-BAL-ETH] balance of {} < warning threshold: {}",
                 self.config.watch_address,
                 balance
             );
+            Some(AlertLevel::Warn)
         } else {
             tracing::trace!("balance of {} is: {}", self.config.watch_address, balance);
+            None
+        };
+        if let (Some(level), Some(event_bus)) = (level, &self.event_bus) {
+            event_bus.publish(BusEvent::BalanceAlert {
+                address: self.config.watch_address,
+                asset: BalanceAsset::Eth,
+                balance,
+                level,
+            });
         }
         res
     }
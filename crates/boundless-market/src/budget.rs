@@ -0,0 +1,320 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Spend limits enforced by a [Client][crate::Client] when submitting requests.
+//!
+//! A [Budget] caps the max price of each request, and the sum of the max prices of the requests
+//! submitted in any 24 hour window and over the lifetime of the budget. The max price is what the
+//! requestor commits to pay if the request is fulfilled, so the budget guards against pricing
+//! bugs whether or not the requests end up being fulfilled. A request resubmitted with the same
+//! ID, e.g. for the next round of a [price curve][crate::contracts::price_curve], is only counted
+//! for the increase of its max price, since at most one of its submissions is paid for. Other
+//! costs, such as the gas of onchain submissions, can be counted with [Budget::charge].
+//!
+//! ```
+//! use alloy::primitives::utils::parse_ether;
+//! use boundless_market::budget::Budget;
+//!
+//! let budget = Budget::new()
+//!     .with_max_per_request(parse_ether("0.01").unwrap())
+//!     .with_max_per_day(parse_ether("0.5").unwrap());
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{utils::format_ether, U256};
+use thiserror::Error;
+
+/// Length in seconds of the window over which [Budget::with_max_per_day] applies.
+const DAY: u64 = 24 * 60 * 60;
+
+/// Error returned when submitting a request would exceed a [Budget].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BudgetError {
+    /// The max price of the request exceeds the per-request limit.
+    #[error("max price of {} ETH exceeds the per-request budget of {} ETH", format_ether(*.price), format_ether(*.limit))]
+    PerRequest {
+        /// Max price of the request, in wei.
+        price: U256,
+        /// Per-request limit, in wei.
+        limit: U256,
+    },
+    /// The request would take the spend of the last 24 hours over the daily limit.
+    #[error("max price of {} ETH exceeds the daily budget, with {} ETH of {} ETH spent in the last 24 hours", format_ether(*.price), format_ether(*.spent), format_ether(*.limit))]
+    PerDay {
+        /// Max price of the request, in wei.
+        price: U256,
+        /// Spend of the last 24 hours, in wei.
+        spent: U256,
+        /// Daily limit, in wei.
+        limit: U256,
+    },
+    /// The request would take the total spend over the total limit.
+    #[error("max price of {} ETH exceeds the total budget, with {} ETH of {} ETH spent", format_ether(*.price), format_ether(*.spent), format_ether(*.limit))]
+    Total {
+        /// Max price of the request, in wei.
+        price: U256,
+        /// Total spend, in wei.
+        spent: U256,
+        /// Total limit, in wei.
+        limit: U256,
+    },
+}
+
+#[derive(Debug, Default)]
+struct Spend {
+    total: U256,
+    /// Timestamp, request ID, and amount counted for the submissions and other charges of the
+    /// last 24 hours, oldest first. Charges have no request ID.
+    recent: VecDeque<(u64, Option<U256>, U256)>,
+    /// Amount counted for each request submitted in the last 24 hours, the highest max price it
+    /// was submitted with.
+    counted: HashMap<U256, U256>,
+}
+
+impl Spend {
+    fn prune(&mut self, now: u64) {
+        while self.recent.front().is_some_and(|&(timestamp, _, _)| timestamp + DAY <= now) {
+            self.recent.pop_front();
+        }
+        let recent = &self.recent;
+        self.counted.retain(|id, _| recent.iter().any(|(_, recent_id, _)| *recent_id == Some(*id)));
+    }
+
+    fn last_day(&self) -> U256 {
+        self.recent.iter().map(|&(_, _, amount)| amount).sum()
+    }
+}
+
+/// Limits on the max prices of the requests submitted by a [Client][crate::Client].
+///
+/// Spend is counted when a request is submitted, and refunded if the submission fails. All
+/// limits are in wei, and unset limits are not enforced.
+#[derive(Debug, Default)]
+pub struct Budget {
+    max_per_request: Option<U256>,
+    max_per_day: Option<U256>,
+    max_total: Option<U256>,
+    spend: Mutex<Spend>,
+}
+
+impl Budget {
+    /// Create a [Budget] with no limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum max price of a single request.
+    pub fn with_max_per_request(self, limit: impl Into<Option<U256>>) -> Self {
+        Self { max_per_request: limit.into(), ..self }
+    }
+
+    /// Set the maximum sum of the max prices of the requests submitted in any 24 hour window.
+    pub fn with_max_per_day(self, limit: impl Into<Option<U256>>) -> Self {
+        Self { max_per_day: limit.into(), ..self }
+    }
+
+    /// Set the maximum sum of the max prices of all requests submitted.
+    pub fn with_max_total(self, limit: impl Into<Option<U256>>) -> Self {
+        Self { max_total: limit.into(), ..self }
+    }
+
+    /// Sum of the max prices of all requests submitted, in wei.
+    pub fn spent_total(&self) -> U256 {
+        self.spend.lock().unwrap().total
+    }
+
+    /// Sum of the max prices of the requests submitted in the last 24 hours, in wei.
+    pub fn spent_last_day(&self) -> U256 {
+        let mut spend = self.spend.lock().unwrap();
+        spend.prune(now());
+        spend.last_day()
+    }
+
+    /// Count the request with the given ID and max price against the budget, or return the
+    /// limit it would exceed.
+    ///
+    /// If the request was already counted in the last 24 hours, only the increase of its max price
+    /// is counted. The limits are checked and the spend is counted under one lock, so concurrent
+    /// submissions cannot together exceed the limits.
+    pub fn reserve(&self, request_id: U256, price: U256) -> Result<(), BudgetError> {
+        self.reserve_at(request_id, price, now())
+    }
+
+    /// Refund the last submission of the request with the given ID counted by [Budget::reserve],
+    /// after it failed.
+    pub fn release(&self, request_id: U256) {
+        let mut spend = self.spend.lock().unwrap();
+        let Some(index) = spend.recent.iter().rposition(|&(_, id, _)| id == Some(request_id))
+        else {
+            return;
+        };
+        let (_, _, amount) = spend.recent.remove(index).expect("index is in bounds");
+        spend.total = spend.total.saturating_sub(amount);
+        if let Some(counted) = spend.counted.get_mut(&request_id) {
+            *counted = counted.saturating_sub(amount);
+        }
+    }
+
+    /// Count a cost other than the max price of a request, e.g. the gas paid to submit it onchain.
+    ///
+    /// Charges are not limited, since they are already paid, but count against the limits of
+    /// the requests submitted after them.
+    pub fn charge(&self, amount: U256) {
+        self.charge_at(amount, now())
+    }
+
+    fn charge_at(&self, amount: U256, now: u64) {
+        let mut spend = self.spend.lock().unwrap();
+        spend.prune(now);
+        spend.total += amount;
+        spend.recent.push_back((now, None, amount));
+    }
+
+    fn reserve_at(&self, request_id: U256, price: U256, now: u64) -> Result<(), BudgetError> {
+        if let Some(limit) = self.max_per_request {
+            if price > limit {
+                return Err(BudgetError::PerRequest { price, limit });
+            }
+        }
+
+        let mut spend = self.spend.lock().unwrap();
+        spend.prune(now);
+        let counted = spend.counted.get(&request_id).copied().unwrap_or_default();
+        let amount = price.saturating_sub(counted);
+        if let Some(limit) = self.max_per_day {
+            let spent = spend.last_day();
+            if spent.saturating_add(amount) > limit {
+                return Err(BudgetError::PerDay { price: amount, spent, limit });
+            }
+        }
+        if let Some(limit) = self.max_total {
+            if spend.total.saturating_add(amount) > limit {
+                return Err(BudgetError::Total { price: amount, spent: spend.total, limit });
+            }
+        }
+        spend.total += amount;
+        // Submissions counting nothing are recorded too, so that releasing them refunds nothing.
+        spend.recent.push_back((now, Some(request_id), amount));
+        spend.counted.insert(request_id, counted + amount);
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("system time before unix epoch").as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let budget = Budget::new()
+            .with_max_per_request(U256::from(50))
+            .with_max_per_day(U256::from(100))
+            .with_max_total(U256::from(150));
+
+        let id = U256::from;
+        assert_eq!(
+            budget.reserve_at(id(1), U256::from(51), 0),
+            Err(BudgetError::PerRequest { price: U256::from(51), limit: U256::from(50) })
+        );
+        budget.reserve_at(id(1), U256::from(50), 0).unwrap();
+        budget.reserve_at(id(2), U256::from(40), 10).unwrap();
+        assert_eq!(
+            budget.reserve_at(id(3), U256::from(20), 20),
+            Err(BudgetError::PerDay {
+                price: U256::from(20),
+                spent: U256::from(90),
+                limit: U256::from(100)
+            })
+        );
+
+        // The first request leaves the daily window, but the total limit still applies.
+        budget.reserve_at(id(4), U256::from(50), DAY).unwrap();
+        assert_eq!(
+            budget.reserve_at(id(5), U256::from(20), DAY + 20),
+            Err(BudgetError::Total {
+                price: U256::from(20),
+                spent: U256::from(140),
+                limit: U256::from(150)
+            })
+        );
+
+        // Refunding a failed submission frees up its spend.
+        budget.release(id(2));
+        assert_eq!(budget.spent_total(), U256::from(100));
+        budget.reserve_at(id(5), U256::from(20), DAY + 20).unwrap();
+    }
+
+    #[test]
+    fn resubmissions_count_the_increase() {
+        let budget = Budget::new().with_max_total(U256::from(100));
+        let id = U256::from(1);
+
+        // Rounds of a price curve resubmit the request with a rising max price.
+        budget.reserve_at(id, U256::from(40), 0).unwrap();
+        budget.reserve_at(id, U256::from(70), 10).unwrap();
+        assert_eq!(budget.spent_total(), U256::from(70));
+        budget.reserve_at(id, U256::from(70), 20).unwrap();
+        assert_eq!(budget.spent_total(), U256::from(70));
+
+        // A failed round only refunds what it counted.
+        budget.reserve_at(id, U256::from(90), 30).unwrap();
+        budget.release(id);
+        assert_eq!(budget.spent_total(), U256::from(70));
+        budget.release(id);
+        assert_eq!(budget.spent_total(), U256::from(70));
+        budget.reserve_at(U256::from(2), U256::from(30), 40).unwrap();
+        assert!(budget.reserve_at(U256::from(3), U256::from(1), 40).is_err());
+    }
+
+    #[test]
+    fn concurrent_reservations_stay_within_limits() {
+        let budget = Budget::new().with_max_per_day(U256::from(100));
+        let reserved: usize = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..32)
+                .map(|idx| {
+                    let budget = &budget;
+                    scope.spawn(move || budget.reserve_at(U256::from(idx), U256::from(10), 0))
+                })
+                .collect();
+            handles.into_iter().filter_map(|handle| handle.join().unwrap().ok()).count()
+        });
+        assert_eq!(reserved, 10);
+        assert_eq!(budget.spent_total(), U256::from(100));
+    }
+
+    #[test]
+    fn charges_count_against_later_requests() {
+        let budget = Budget::new().with_max_per_day(U256::from(100));
+        budget.reserve_at(U256::from(1), U256::from(60), 0).unwrap();
+        budget.charge_at(U256::from(30), 0);
+        assert_eq!(budget.spent_total(), U256::from(90));
+        assert!(budget.reserve_at(U256::from(2), U256::from(20), 10).is_err());
+
+        // Charges are not refunded with the submission they were paid for, and fall out of the
+        // daily window like submissions.
+        budget.release(U256::from(1));
+        assert_eq!(budget.spent_total(), U256::from(30));
+        budget.reserve_at(U256::from(2), U256::from(70), 10).unwrap();
+        budget.reserve_at(U256::from(3), U256::from(30), DAY).unwrap();
+    }
+}
@@ -12,39 +12,62 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{future::Future, str::FromStr, time::Duration};
+use std::{
+    borrow::Cow, collections::HashMap, future::Future, str::FromStr, sync::Arc, time::Duration,
+};
 
 use alloy::{
     network::{Ethereum, EthereumWallet, TxSigner},
     primitives::{Address, Bytes, U256},
     providers::{fillers::ChainIdFiller, DynProvider, Provider, ProviderBuilder},
+    rpc::client::RpcClient,
     signers::{
         local::{LocalSignerError, PrivateKeySigner},
         Signer,
     },
+    transports::{http::Http, layers::RetryBackoffLayer, utils::guess_local_url, BoxTransport},
 };
-use alloy_primitives::{Signature, B256};
+use alloy_primitives::{keccak256, Signature, B256};
 use anyhow::{anyhow, bail, Context, Result};
+use futures::{stream::FuturesUnordered, StreamExt};
 use risc0_aggregation::SetInclusionReceipt;
-use risc0_ethereum_contracts::set_verifier::SetVerifierService;
-use risc0_zkvm::{sha::Digest, ReceiptClaim};
+use risc0_ethereum_contracts::{set_verifier::SetVerifierService, IRiscZeroVerifier};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    Journal, ReceiptClaim,
+};
 use url::Url;
 
 use crate::{
     balance_alerts_layer::{BalanceAlertConfig, BalanceAlertLayer},
+    budget::{Budget, BudgetError},
     contracts::{
-        boundless_market::{BoundlessMarketService, MarketError},
-        Fulfillment, FulfillmentData, ProofRequest, RequestError,
+        boundless_market::{
+            BoundlessMarketService, ConfirmationPolicy, ConfirmedFulfillment, LocatedRequest,
+            MarketError, MarketTxType,
+        },
+        Fulfillment, FulfillmentData, Predicate, ProofRequest, RequestError,
     },
-    deployments::Deployment,
+    deployments::{ChainTiming, Deployment},
     dynamic_gas_filler::DynamicGasFiller,
+    event_bus::EventBus,
+    fault_injection::{FaultInjectionService, FaultInjector},
+    http_client::HttpClientConfig,
+    middleware::{ClientEvent, Middleware},
     nonce_layer::NonceProvider,
-    order_stream_client::OrderStreamClient,
+    order_stream_client::{
+        Order, OrderStreamClient, OrderStreamError, RequestMetadata, ValidateOrderRes,
+    },
+    prover_policy::{PolicyVerdict, ProverPolicy},
+    rate_limit_layer::{RateLimitLayer, RateLimitService, RateLimiter},
     request_builder::{
-        FinalizerConfigBuilder, OfferLayer, OfferLayerConfigBuilder, RequestBuilder,
-        RequestIdLayer, RequestIdLayerConfigBuilder, StandardRequestBuilder,
-        StandardRequestBuilderBuilderError, StorageLayer, StorageLayerConfigBuilder,
+        ExecutorConfig, FinalizerConfigBuilder, OfferLayer, OfferLayerConfigBuilder,
+        PreflightLayer, RequestBuilder, RequestIdLayer, RequestIdLayerConfigBuilder, RequestParams,
+        StandardRequestBuilder, StandardRequestBuilderBuilderError, StorageLayer,
+        StorageLayerConfigBuilder,
     },
+    selector::SelectorRegistry,
+    sent_tx_layer::{SentTransactions, SentTxLayer},
     storage::{
         StandardStorageProvider, StandardStorageProviderError, StorageProvider,
         StorageProviderConfig,
@@ -52,6 +75,12 @@ use crate::{
     util::NotProvided,
 };
 
+/// Default time [Client::prove] waits for a proof, from the upload of the program.
+pub const DEFAULT_PROVE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Interval between the checks for the fulfillment of the requests sent by [Client::prove].
+const PROVE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Builder for the [Client] with standard implementations for the required components.
 #[derive(Clone)]
 pub struct ClientBuilder<St = NotProvided, Si = NotProvided> {
@@ -60,7 +89,20 @@ pub struct ClientBuilder<St = NotProvided, Si = NotProvided> {
     signer: Option<Si>,
     storage_provider: Option<St>,
     tx_timeout: Option<std::time::Duration>,
+    confirmation_policies: Vec<(MarketTxType, ConfirmationPolicy)>,
     balance_alerts: Option<BalanceAlertConfig>,
+    sent_transactions: Option<SentTransactions>,
+    event_bus: Option<EventBus>,
+    http_client: Option<reqwest::Client>,
+    rate_limiter: Option<RateLimiter>,
+    rpc_retry: Option<RpcRetryConfig>,
+    rpc_faults: Option<FaultInjector>,
+    gas_filler: Option<GasFillerConfig>,
+    preset: Option<ClientPreset>,
+    prover_policy: Option<ProverPolicy>,
+    budget: Option<Arc<Budget>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    preflight_executor_config: ExecutorConfig,
     /// Configuration builder for [OfferLayer], part of [StandardRequestBuilder].
     pub offer_layer_config: OfferLayerConfigBuilder,
     /// Configuration builder for [StorageLayer], part of [StandardRequestBuilder].
@@ -79,7 +121,20 @@ impl<St, Si> Default for ClientBuilder<St, Si> {
             signer: None,
             storage_provider: None,
             tx_timeout: None,
+            confirmation_policies: Vec::new(),
             balance_alerts: None,
+            sent_transactions: None,
+            event_bus: None,
+            http_client: None,
+            rate_limiter: None,
+            rpc_retry: None,
+            rpc_faults: None,
+            gas_filler: None,
+            preset: None,
+            prover_policy: None,
+            budget: None,
+            middleware: Vec::new(),
+            preflight_executor_config: ExecutorConfig::default(),
             offer_layer_config: Default::default(),
             storage_layer_config: Default::default(),
             request_id_layer_config: Default::default(),
@@ -95,6 +150,142 @@ impl ClientBuilder {
     }
 }
 
+// Settings set explicitly take precedence over those of the preset.
+impl<St, Si> ClientBuilder<St, Si> {
+    fn resolved_tx_timeout(&self) -> Option<Duration> {
+        self.tx_timeout.or_else(|| self.preset.and_then(ClientPreset::tx_timeout))
+    }
+
+    fn resolved_rpc_retry(&self) -> Option<RpcRetryConfig> {
+        self.rpc_retry.or_else(|| self.preset.map(ClientPreset::rpc_retry))
+    }
+
+    fn resolved_gas_filler(&self) -> GasFillerConfig {
+        self.gas_filler.or_else(|| self.preset.map(ClientPreset::gas_filler)).unwrap_or_default()
+    }
+
+    fn resolved_balance_alerts(&self) -> Option<BalanceAlertConfig> {
+        self.balance_alerts.clone().or_else(|| self.preset.map(ClientPreset::balance_alerts))
+    }
+}
+
+/// Parameters of the [DynamicGasFiller] used by clients with a signer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct GasFillerConfig {
+    /// Factor by which to increase the estimated gas limit.
+    pub gas_limit_factor: f64,
+    /// Factor by which to increase the gas price for each pending transaction.
+    pub gas_increase_factor: f64,
+    /// Maximum gas price multiplier.
+    pub max_gas_multiplier: f64,
+}
+
+impl Default for GasFillerConfig {
+    fn default() -> Self {
+        Self { gas_limit_factor: 0.2, gas_increase_factor: 0.05, max_gas_multiplier: 2.0 }
+    }
+}
+
+impl GasFillerConfig {
+    /// Create a [DynamicGasFiller] with these parameters for the transactions of the given address.
+    pub fn filler(&self, address: Address) -> DynamicGasFiller {
+        DynamicGasFiller::new(
+            self.gas_limit_factor,
+            self.gas_increase_factor,
+            self.max_gas_multiplier,
+            address,
+        )
+    }
+}
+
+/// Defaults suited to the role of a client, applied by [ClientBuilder::prover_defaults] and
+/// [ClientBuilder::requestor_defaults].
+///
+/// Services that build their own provider, such as the broker, can use the defaults directly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClientPreset {
+    /// Defaults for a prover, whose locks and fulfillments are time-sensitive.
+    Prover,
+    /// Defaults for a requestor.
+    Requestor,
+}
+
+impl ClientPreset {
+    /// Transaction timeout, or `None` for the [recommended timeout of the
+    /// chain](ChainTiming::tx_timeout).
+    pub fn tx_timeout(self) -> Option<Duration> {
+        match self {
+            Self::Prover => Some(Duration::from_secs(30)),
+            Self::Requestor => None,
+        }
+    }
+
+    /// Retry policy for rate limited RPC requests.
+    pub fn rpc_retry(self) -> RpcRetryConfig {
+        match self {
+            Self::Prover => RpcRetryConfig::new(10, 1000, 100),
+            Self::Requestor => RpcRetryConfig::new(5, 1000, 100),
+        }
+    }
+
+    /// Pricing of the transactions sent by the client.
+    pub fn gas_filler(self) -> GasFillerConfig {
+        match self {
+            Self::Prover => GasFillerConfig {
+                gas_increase_factor: 0.1,
+                max_gas_multiplier: 3.0,
+                ..Default::default()
+            },
+            Self::Requestor => GasFillerConfig::default(),
+        }
+    }
+
+    /// Balance alerts of the signer. The watched address is left unset, as [Address::ZERO].
+    pub fn balance_alerts(self) -> BalanceAlertConfig {
+        match self {
+            Self::Prover => BalanceAlertConfig {
+                watch_address: Address::ZERO,
+                warn_threshold: Some(U256::from(100_000_000_000_000_000u128)),
+                error_threshold: Some(U256::from(50_000_000_000_000_000u128)),
+            },
+            Self::Requestor => BalanceAlertConfig {
+                watch_address: Address::ZERO,
+                warn_threshold: Some(U256::from(10_000_000_000_000_000u128)),
+                error_threshold: None,
+            },
+        }
+    }
+}
+
+/// Retry policy for RPC requests that are rate limited, as in the [RetryBackoffLayer] of alloy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RpcRetryConfig {
+    /// Maximum number of retries of a rate limited request.
+    pub max_retries: u32,
+    /// Initial backoff before retrying, in milliseconds.
+    pub initial_backoff_ms: u64,
+    /// Compute units per second allowed by the RPC provider.
+    pub compute_units_per_second: u64,
+}
+
+impl RpcRetryConfig {
+    /// Create a retry policy with the given parameters.
+    pub fn new(max_retries: u32, initial_backoff_ms: u64, compute_units_per_second: u64) -> Self {
+        Self { max_retries, initial_backoff_ms, compute_units_per_second }
+    }
+
+    fn layer(&self) -> RetryBackoffLayer {
+        RetryBackoffLayer::new(
+            self.max_retries,
+            self.initial_backoff_ms,
+            self.compute_units_per_second,
+        )
+    }
+}
+
 /// A utility trait used in the [ClientBuilder] to handle construction of the [alloy] [Provider].
 pub trait ClientProviderBuilder {
     /// Error returned by methods on this [ClientProviderBuilder].
@@ -120,29 +311,55 @@ where
         let rpc_url = rpc_url.as_ref();
         let provider = match self.signer.clone() {
             Some(signer) => {
-                let dynamic_gas_filler = DynamicGasFiller::new(
-                    0.2,  // 20% increase of gas limit
-                    0.05, // 5% increase of gas_price per pending transaction
-                    2.0,  // 2x max gas multiplier
-                    signer.address(),
-                );
+                let dynamic_gas_filler = self.resolved_gas_filler().filler(signer.address());
+                // Balance alerts set by a preset watch the signer, unless an address is given.
+                let mut balance_alerts = self.resolved_balance_alerts().unwrap_or_default();
+                if balance_alerts.watch_address == Address::ZERO {
+                    balance_alerts.watch_address = signer.address();
+                }
 
                 // Connect the RPC provider.
-                let base_provider = ProviderBuilder::new()
+                let builder = ProviderBuilder::new()
                     .disable_recommended_fillers()
                     .filler(ChainIdFiller::default())
                     .filler(dynamic_gas_filler)
-                    .layer(BalanceAlertLayer::new(self.balance_alerts.clone().unwrap_or_default()))
-                    .connect(rpc_url)
-                    .await
-                    .with_context(|| format!("failed to connect provider to {rpc_url}"))?;
+                    .layer(
+                        BalanceAlertLayer::new(balance_alerts)
+                            .with_event_bus(self.event_bus.clone()),
+                    )
+                    .layer(
+                        SentTxLayer::new(self.sent_transactions.clone().unwrap_or_default())
+                            .with_event_bus(self.event_bus.clone()),
+                    );
+                let base_provider = match shared_rpc_client(
+                    self.http_client.as_ref(),
+                    self.rate_limiter.as_ref(),
+                    self.resolved_rpc_retry().as_ref(),
+                    self.rpc_faults.as_ref(),
+                    rpc_url,
+                )? {
+                    Some(rpc_client) => builder.connect_client(rpc_client),
+                    None => builder
+                        .connect(rpc_url)
+                        .await
+                        .with_context(|| format!("failed to connect provider to {rpc_url}"))?,
+                };
                 NonceProvider::new(base_provider, EthereumWallet::from(signer)).erased()
             }
-            None => ProviderBuilder::new()
-                .connect(rpc_url)
-                .await
-                .with_context(|| format!("failed to connect provider to {rpc_url}"))?
-                .erased(),
+            None => match shared_rpc_client(
+                self.http_client.as_ref(),
+                self.rate_limiter.as_ref(),
+                self.resolved_rpc_retry().as_ref(),
+                self.rpc_faults.as_ref(),
+                rpc_url,
+            )? {
+                Some(rpc_client) => ProviderBuilder::new().connect_client(rpc_client).erased(),
+                None => ProviderBuilder::new()
+                    .connect(rpc_url)
+                    .await
+                    .with_context(|| format!("failed to connect provider to {rpc_url}"))?
+                    .erased(),
+            },
         };
         Ok(provider)
     }
@@ -157,11 +374,20 @@ impl<St> ClientProviderBuilder for ClientBuilder<St, NotProvided> {
 
     async fn build_provider(&self, rpc_url: impl AsRef<str>) -> Result<DynProvider, Self::Error> {
         let rpc_url = rpc_url.as_ref();
-        let provider = ProviderBuilder::new()
-            .connect(rpc_url)
-            .await
-            .with_context(|| format!("failed to connect provider to {rpc_url}"))?
-            .erased();
+        let provider = match shared_rpc_client(
+            self.http_client.as_ref(),
+            self.rate_limiter.as_ref(),
+            self.resolved_rpc_retry().as_ref(),
+            self.rpc_faults.as_ref(),
+            rpc_url,
+        )? {
+            Some(rpc_client) => ProviderBuilder::new().connect_client(rpc_client).erased(),
+            None => ProviderBuilder::new()
+                .connect(rpc_url)
+                .await
+                .with_context(|| format!("failed to connect provider to {rpc_url}"))?
+                .erased(),
+        };
         Ok(provider)
     }
 
@@ -170,6 +396,54 @@ impl<St> ClientProviderBuilder for ClientBuilder<St, NotProvided> {
     }
 }
 
+/// Build an [RpcClient] over the shared HTTP client, rate limiter, retry policy, and fault
+/// injector, if any is set and the URL uses HTTP(S).
+///
+/// Returns `None` when the provider should instead connect using the default transport for the
+/// URL (e.g. for WebSocket or IPC endpoints).
+fn shared_rpc_client(
+    http_client: Option<&reqwest::Client>,
+    rate_limiter: Option<&RateLimiter>,
+    rpc_retry: Option<&RpcRetryConfig>,
+    rpc_faults: Option<&FaultInjector>,
+    rpc_url: &str,
+) -> Result<Option<RpcClient>> {
+    if http_client.is_none()
+        && rate_limiter.is_none()
+        && rpc_retry.is_none()
+        && rpc_faults.is_none()
+    {
+        return Ok(None);
+    }
+    let url = Url::parse(rpc_url).with_context(|| format!("failed to parse RPC URL {rpc_url}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        if rate_limiter.is_some() || rpc_retry.is_some() || rpc_faults.is_some() {
+            tracing::warn!("Rate limiting, retries, and fault injection are only supported for HTTP RPC URLs; not applying them to {rpc_url}");
+        }
+        return Ok(None);
+    }
+    let http_client = http_client.cloned().unwrap_or_default();
+    let is_local = guess_local_url(&url);
+    let http = Http::with_client(http_client, url);
+    let transport = match rpc_faults {
+        Some(faults) => BoxTransport::new(FaultInjectionService::new(http, faults.clone())),
+        None => BoxTransport::new(http),
+    };
+    Ok(Some(match (rate_limiter, rpc_retry) {
+        (Some(limiter), Some(retry)) => RpcClient::builder()
+            .layer(retry.layer())
+            .layer(RateLimitLayer::new(limiter.clone()))
+            .transport(transport, is_local),
+        (None, Some(retry)) => {
+            RpcClient::builder().layer(retry.layer()).transport(transport, is_local)
+        }
+        (Some(limiter), None) => {
+            RpcClient::new(RateLimitService::new(transport, limiter.clone()), is_local)
+        }
+        (None, None) => RpcClient::new(transport, is_local),
+    }))
+}
+
 impl<St, Si> ClientBuilder<St, Si> {
     /// Build the client
     pub async fn build(
@@ -194,33 +468,53 @@ impl<St, Si> ClientBuilder<St, Si> {
         if deployment.chain_id.map(|id| id != chain_id).unwrap_or(false) {
             bail!("provided deployment does not match chain_id reported by RPC provider: {chain_id} != {}", deployment.chain_id.unwrap());
         }
+        // Timing defaults of the chain, for the parameters not set on the builder.
+        let timing = ChainTiming::for_chain_id(chain_id);
 
         // Build the contract instances.
         let boundless_market = BoundlessMarketService::new(
             deployment.boundless_market_address,
             provider.clone(),
             self.signer_address().unwrap_or(Address::ZERO),
-        );
+        )
+        .with_chain_id(chain_id)
+        .with_event_bus(self.event_bus.clone());
+        let boundless_market = self
+            .confirmation_policies
+            .iter()
+            .fold(boundless_market, |market, (tx_type, policy)| {
+                market.with_confirmation_policy(*tx_type, *policy)
+            });
         let set_verifier = SetVerifierService::new(
             deployment.set_verifier_address,
             provider.clone(),
             self.signer_address().unwrap_or(Address::ZERO),
         );
 
-        // Build the order stream client, if a URL was provided.
-        let offchain_client = deployment
-            .order_stream_url
-            .as_ref()
-            .map(|order_stream_url| {
-                let url = Url::parse(order_stream_url.as_ref())
-                    .context("failed to parse order_stream_url")?;
-                anyhow::Ok(OrderStreamClient::new(
+        // Build the order stream clients, if URLs were provided.
+        let order_stream_client = |order_stream_url: &str| {
+            let url = Url::parse(order_stream_url)
+                .with_context(|| format!("failed to parse order stream URL {order_stream_url}"))?;
+            anyhow::Ok(match &self.http_client {
+                Some(http_client) => OrderStreamClient::new_with_client(
+                    http_client.clone(),
                     url,
                     deployment.boundless_market_address,
                     chain_id,
-                ))
+                ),
+                None => OrderStreamClient::new(url, deployment.boundless_market_address, chain_id),
             })
-            .transpose()?;
+        };
+        let offchain_client =
+            deployment.order_stream_url.as_deref().map(order_stream_client).transpose()?;
+        let additional_offchain_clients = deployment
+            .additional_order_stream_urls
+            .iter()
+            .map(|url| order_stream_client(url))
+            .collect::<Result<Vec<_>, _>>()?;
+        if offchain_client.is_none() && !additional_offchain_clients.is_empty() {
+            bail!("additional order stream URLs require an order stream URL to be set");
+        }
 
         // Build the RequestBuilder.
         let request_builder = StandardRequestBuilder::builder()
@@ -228,7 +522,24 @@ impl<St, Si> ClientBuilder<St, Si> {
                 self.storage_provider.clone(),
                 self.storage_layer_config.build()?,
             ))
-            .offer_layer(OfferLayer::new(provider.clone(), self.offer_layer_config.build()?))
+            .preflight_layer(
+                PreflightLayer::default()
+                    .with_executor_config(self.preflight_executor_config)
+                    .with_http_client(self.http_client.clone())
+                    .with_rate_limiter(self.rate_limiter.clone()),
+            )
+            .offer_layer(
+                OfferLayer::new(
+                    provider.clone(),
+                    self.offer_layer_config.clone().chain_timing(&timing).build()?,
+                )
+                .with_selector_registry(deployment.verifier_router_address.map(
+                    |router| {
+                        SelectorRegistry::new(router, provider.clone())
+                            .with_set_verifier(deployment.set_verifier_address)
+                    },
+                )),
+            )
             .request_id_layer(RequestIdLayer::new(
                 boundless_market.clone(),
                 self.request_id_layer_config.build()?,
@@ -236,21 +547,27 @@ impl<St, Si> ClientBuilder<St, Si> {
             .finalizer(self.request_finalizer_config.build()?)
             .build()?;
 
-        let mut client = Client {
+        // Request events reach the event bus as the last middleware.
+        let mut middleware = self.middleware;
+        if let Some(event_bus) = self.event_bus {
+            middleware.push(Arc::new(event_bus));
+        }
+
+        let client = Client {
             boundless_market,
             set_verifier,
             storage_provider: self.storage_provider,
             offchain_client,
+            additional_offchain_clients,
             signer: self.signer,
             request_builder: Some(request_builder),
             deployment,
+            prover_policy: self.prover_policy,
+            budget: self.budget,
+            middleware,
         };
 
-        if let Some(timeout) = self.tx_timeout {
-            client = client.with_timeout(timeout);
-        }
-
-        Ok(client)
+        Ok(client.with_timeout(self.resolved_tx_timeout().unwrap_or(timing.tx_timeout)))
     }
 
     /// Set the [Deployment] of the Boundless Market that this client will use.
@@ -306,7 +623,20 @@ impl<St, Si> ClientBuilder<St, Si> {
             storage_provider: self.storage_provider,
             rpc_url: self.rpc_url,
             tx_timeout: self.tx_timeout,
+            confirmation_policies: self.confirmation_policies,
             balance_alerts: self.balance_alerts,
+            sent_transactions: self.sent_transactions,
+            event_bus: self.event_bus,
+            http_client: self.http_client,
+            rate_limiter: self.rate_limiter,
+            rpc_retry: self.rpc_retry,
+            rpc_faults: self.rpc_faults,
+            gas_filler: self.gas_filler,
+            preset: self.preset,
+            prover_policy: self.prover_policy,
+            budget: self.budget,
+            middleware: self.middleware,
+            preflight_executor_config: self.preflight_executor_config,
             offer_layer_config: self.offer_layer_config,
             storage_layer_config: self.storage_layer_config,
             request_id_layer_config: self.request_id_layer_config,
@@ -315,15 +645,179 @@ impl<St, Si> ClientBuilder<St, Si> {
     }
 
     /// Set the transaction timeout in seconds
+    ///
+    /// If `None`, the timeout of the [ClientPreset] is used if one is set, and otherwise the
+    /// [recommended timeout of the chain](ChainTiming::tx_timeout).
     pub fn with_timeout(self, tx_timeout: impl Into<Option<Duration>>) -> Self {
         Self { tx_timeout: tx_timeout.into(), ..self }
     }
 
+    /// Set the [ConfirmationPolicy] of the market transactions of the given type.
+    ///
+    /// Transactions of types without a policy wait for one confirmation, up to the transaction
+    /// timeout. See [BoundlessMarketService::with_confirmation_policy].
+    pub fn with_confirmation_policy(
+        mut self,
+        tx_type: MarketTxType,
+        policy: ConfirmationPolicy,
+    ) -> Self {
+        self.confirmation_policies.push((tx_type, policy));
+        self
+    }
+
+    /// Set the maximum number of cycles to execute a guest for in the request preflight.
+    ///
+    /// Requests whose guest runs past the limit fail to build with a
+    /// [PreflightError::CycleLimitExceeded][crate::request_builder::PreflightError], instead of
+    /// executing to completion locally.
+    pub fn with_preflight_cycle_limit(self, cycle_limit: impl Into<Option<u64>>) -> Self {
+        let preflight_executor_config =
+            self.preflight_executor_config.with_cycle_limit(cycle_limit);
+        Self { preflight_executor_config, ..self }
+    }
+
+    /// Set the configuration of the executor running guests in the request preflight.
+    ///
+    /// This replaces the cycle limit set with [Self::with_preflight_cycle_limit]. Lowering the
+    /// segment size lets large guests be preflighted on machines with less memory.
+    pub fn with_preflight_executor_config(self, executor_config: ExecutorConfig) -> Self {
+        Self { preflight_executor_config: executor_config, ..self }
+    }
+
     /// Set the balance alerts configuration
+    ///
+    /// If `None`, the balance alerts of the [ClientPreset] are used if one is set.
     pub fn with_balance_alerts(self, config: impl Into<Option<BalanceAlertConfig>>) -> Self {
         Self { balance_alerts: config.into(), ..self }
     }
 
+    /// Set the [SentTransactions] log in which the hashes of the transactions sent by the client
+    /// are recorded.
+    ///
+    /// This allows a caller that is interrupted, e.g. by a timeout, to report transactions that
+    /// may still land onchain.
+    pub fn with_sent_transactions(self, log: impl Into<Option<SentTransactions>>) -> Self {
+        Self { sent_transactions: log.into(), ..self }
+    }
+
+    /// Set the [EventBus] on which the client publishes its notable events: transactions sent and
+    /// confirmed, requests submitted, fulfilled, or failed, and balance alerts.
+    ///
+    /// Subscribe to the bus before building the client to receive all of its events.
+    pub fn with_event_bus(self, event_bus: impl Into<Option<EventBus>>) -> Self {
+        Self { event_bus: event_bus.into(), ..self }
+    }
+
+    /// Set the HTTP client shared by the RPC provider and the order stream client.
+    ///
+    /// Sharing a single [reqwest::Client] lets these components reuse pooled connections. Only
+    /// used for `http` and `https` RPC URLs. If `None`, each component creates its own client.
+    pub fn with_http_client(self, http_client: impl Into<Option<reqwest::Client>>) -> Self {
+        Self { http_client: http_client.into(), ..self }
+    }
+
+    /// Set the [RateLimiter] applied to requests made by the RPC provider.
+    ///
+    /// Only used for `http` and `https` RPC URLs. The limiter can be shared with other clients to
+    /// cap their combined request rate, e.g. to stay within the limits of a free RPC tier.
+    pub fn with_rate_limiter(self, rate_limiter: impl Into<Option<RateLimiter>>) -> Self {
+        Self { rate_limiter: rate_limiter.into(), ..self }
+    }
+
+    /// Set the [RpcRetryConfig] applied to requests made by the RPC provider.
+    ///
+    /// Only used for `http` and `https` RPC URLs. If `None`, the retry policy of the [ClientPreset]
+    /// is used if one is set, and otherwise rate limited requests are not retried.
+    pub fn with_rpc_retry(self, rpc_retry: impl Into<Option<RpcRetryConfig>>) -> Self {
+        Self { rpc_retry: rpc_retry.into(), ..self }
+    }
+
+    /// Set a [FaultInjector] injecting faults into the requests made by the RPC provider.
+    ///
+    /// Only used for `http` and `https` RPC URLs. Faults are injected below the retry policy, so
+    /// that injected errors exercise it. Meant for testing resilience to a degraded RPC endpoint.
+    pub fn with_rpc_fault_injection(self, rpc_faults: impl Into<Option<FaultInjector>>) -> Self {
+        Self { rpc_faults: rpc_faults.into(), ..self }
+    }
+
+    /// Set the [GasFillerConfig] used to price the transactions sent by a client with a signer.
+    pub fn with_gas_filler_config(self, gas_filler: GasFillerConfig) -> Self {
+        Self { gas_filler: Some(gas_filler), ..self }
+    }
+
+    /// Set the [ClientPreset] providing the defaults of the settings that are not set explicitly.
+    ///
+    /// Settings set explicitly take precedence over the preset, whether they are set before or
+    /// after it.
+    pub fn with_preset(self, preset: impl Into<Option<ClientPreset>>) -> Self {
+        Self { preset: preset.into(), ..self }
+    }
+
+    /// Configure the builder with defaults suited to a prover, see [ClientPreset::Prover].
+    ///
+    /// Locks and fulfillments are time-sensitive, so transactions are priced more aggressively
+    /// when others are pending, and confirmed with a shorter timeout. Rate limited RPC requests are
+    /// retried, and a warning is logged when the balance of the signer falls below 0.1 ETH, or an
+    /// error below 0.05 ETH. Each default can be overridden by calling its setter, before or after
+    /// this method.
+    ///
+    /// ```rust
+    /// # use boundless_market::Client;
+    /// use std::time::Duration;
+    ///
+    /// Client::builder().prover_defaults().with_timeout(Duration::from_secs(60));
+    /// ```
+    pub fn prover_defaults(self) -> Self {
+        self.with_preset(ClientPreset::Prover)
+    }
+
+    /// Configure the builder with defaults suited to a requestor, see [ClientPreset::Requestor].
+    ///
+    /// Transactions are priced with the standard [GasFillerConfig] and confirmed with the default
+    /// timeout. Rate limited RPC requests are retried, and a warning is logged when the balance of
+    /// the signer falls below 0.01 ETH. Each default can be overridden by calling its setter,
+    /// before or after this method.
+    pub fn requestor_defaults(self) -> Self {
+        self.with_preset(ClientPreset::Requestor)
+    }
+
+    /// Set the [ProverPolicy] used to check the provers that lock requests.
+    pub fn with_prover_policy(self, prover_policy: impl Into<Option<ProverPolicy>>) -> Self {
+        Self { prover_policy: prover_policy.into(), ..self }
+    }
+
+    /// Set the [Budget] limiting the max prices of the requests submitted by the built client.
+    pub fn with_budget(self, budget: impl Into<Option<Budget>>) -> Self {
+        Self { budget: budget.into().map(Arc::new), ..self }
+    }
+
+    /// Add a [Middleware] to the built client, run after the middleware already added.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Build a shared HTTP client from the given [HttpClientConfig] and use it for the RPC
+    /// provider and the order stream client.
+    ///
+    /// ```rust
+    /// # use boundless_market::client::ClientBuilder;
+    /// use boundless_market::http_client::HttpClientConfig;
+    /// use std::time::Duration;
+    ///
+    /// ClientBuilder::new()
+    ///     .with_http_client_config(
+    ///         &HttpClientConfig::default()
+    ///             .with_pool_max_idle_per_host(16)
+    ///             .with_connect_timeout(Duration::from_secs(5)),
+    ///     )
+    ///     .unwrap();
+    /// ```
+    pub fn with_http_client_config(self, config: &HttpClientConfig) -> Result<Self> {
+        let http_client = config.build().context("failed to build HTTP client")?;
+        Ok(self.with_http_client(http_client).with_rate_limiter(config.rate_limiter.clone()))
+    }
+
     /// Set the storage provider.
     ///
     /// The returned [ClientBuilder] will be generic over the provider [StorageProvider] type.
@@ -338,7 +832,20 @@ impl<St, Si> ClientBuilder<St, Si> {
             rpc_url: self.rpc_url,
             signer: self.signer,
             tx_timeout: self.tx_timeout,
+            confirmation_policies: self.confirmation_policies,
             balance_alerts: self.balance_alerts,
+            sent_transactions: self.sent_transactions,
+            event_bus: self.event_bus,
+            http_client: self.http_client,
+            rate_limiter: self.rate_limiter,
+            rpc_retry: self.rpc_retry,
+            rpc_faults: self.rpc_faults,
+            gas_filler: self.gas_filler,
+            preset: self.preset,
+            prover_policy: self.prover_policy,
+            budget: self.budget,
+            middleware: self.middleware,
+            preflight_executor_config: self.preflight_executor_config,
             request_finalizer_config: self.request_finalizer_config,
             request_id_layer_config: self.request_id_layer_config,
             storage_layer_config: self.storage_layer_config,
@@ -445,6 +952,8 @@ pub struct Client<
     ///
     /// If not provided, requests not only be sent onchain via a transaction.
     pub offchain_client: Option<OrderStreamClient>,
+    /// Additional [OrderStreamClient]s that offchain requests are also submitted to.
+    pub additional_offchain_clients: Vec<OrderStreamClient>,
     /// Alloy [Signer] for signing requests.
     ///
     /// If not provided, requests must be pre-signed handing them to this client.
@@ -455,6 +964,17 @@ pub struct Client<
     pub request_builder: Option<R>,
     /// Deployment of Boundless that this client is connected to.
     pub deployment: Deployment,
+    /// [ProverPolicy] used to check the provers that lock requests.
+    ///
+    /// If not provided, all provers are allowed.
+    pub prover_policy: Option<ProverPolicy>,
+    /// [Budget] limiting the max prices of the submitted requests, shared by clones of this
+    /// client.
+    ///
+    /// If not provided, spend is not limited.
+    pub budget: Option<Arc<Budget>>,
+    /// [Middleware] called on the requests submitted by this client, in order.
+    pub middleware: Vec<Arc<dyn Middleware>>,
 }
 
 /// Alias for a [Client] instantiated with the standard implementations provided by this crate.
@@ -478,6 +998,18 @@ pub enum ClientError {
     /// Request error
     #[error("RequestError {0}")]
     RequestError(#[from] RequestError),
+    /// Order stream error
+    ///
+    /// Use [OrderStreamError::is_rejection] and [OrderStreamError::is_unavailable] to tell an
+    /// invalid order from an order stream that is down.
+    #[error("Order stream error {0}")]
+    OrderStreamError(#[from] OrderStreamError),
+    /// An offchain submission was requested, but no order stream URL is configured.
+    #[error("Order stream client not available. Please provide an order stream URL")]
+    OrderStreamNotConfigured,
+    /// Submitting the request would exceed the [Budget] of the client.
+    #[error("Budget exceeded: {0}")]
+    BudgetExceeded(#[from] BudgetError),
     /// Error when trying to construct a [RequestBuilder].
     #[error("Error building RequestBuilder {0}")]
     BuilderError(#[from] StandardRequestBuilderBuilderError),
@@ -486,6 +1018,26 @@ pub enum ClientError {
     Error(#[from] anyhow::Error),
 }
 
+impl ClientError {
+    /// Whether an offchain submission failed because the order stream is down, rather than
+    /// because the order is invalid.
+    ///
+    /// Such a request may be retried later, or submitted onchain instead.
+    pub fn is_order_stream_unavailable(&self) -> bool {
+        matches!(self, ClientError::OrderStreamError(err) if err.is_unavailable())
+    }
+}
+
+/// Path by which a request is submitted, as reported by [Client::submit_request_auto].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub enum SubmissionPath {
+    /// The request was submitted offchain to the order stream.
+    Offchain,
+    /// The request was submitted in an onchain transaction.
+    Onchain,
+}
+
 impl Client<NotProvided, NotProvided, NotProvided, NotProvided> {
     /// Create a [ClientBuilder] to construct a [Client].
     pub fn builder() -> ClientBuilder {
@@ -510,6 +1062,7 @@ where
                 set_verifier_address: *set_verifier.instance().address(),
                 chain_id: None,
                 order_stream_url: None,
+                additional_order_stream_urls: Vec::new(),
                 collateral_token_address: None,
                 verifier_router_address: None,
             },
@@ -517,8 +1070,12 @@ where
             set_verifier,
             storage_provider: None,
             offchain_client: None,
+            additional_offchain_clients: Vec::new(),
             signer: None,
             request_builder: None,
+            prover_policy: None,
+            budget: None,
+            middleware: Vec::new(),
         }
     }
 }
@@ -581,6 +1138,100 @@ where
         }
     }
 
+    /// Add an offchain client that offchain requests are also submitted to.
+    ///
+    /// Requires an offchain client to be set with [Client::with_offchain_client] to submit to.
+    pub fn with_additional_offchain_client(mut self, offchain_client: OrderStreamClient) -> Self {
+        self.deployment
+            .additional_order_stream_urls
+            .push(offchain_client.base_url.to_string().into());
+        self.additional_offchain_clients.push(offchain_client);
+        self
+    }
+
+    /// Set the [ProverPolicy] used to check the provers that lock requests.
+    pub fn with_prover_policy(self, prover_policy: impl Into<Option<ProverPolicy>>) -> Self {
+        Self { prover_policy: prover_policy.into(), ..self }
+    }
+
+    /// Set the [Budget] limiting the max prices of the submitted requests.
+    pub fn with_budget(self, budget: impl Into<Option<Budget>>) -> Self {
+        Self { budget: budget.into().map(Arc::new), ..self }
+    }
+
+    /// Add a [Middleware], run after the middleware already added.
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    // Run the before_submit hook of each middleware on the request.
+    async fn middleware_before_submit(
+        &self,
+        request: &mut ProofRequest,
+        path: SubmissionPath,
+    ) -> Result<(), ClientError> {
+        for middleware in &self.middleware {
+            middleware.before_submit(request, path).await?;
+        }
+        Ok(())
+    }
+
+    // Count the request against the budget, if any.
+    fn reserve_budget(&self, request: &ProofRequest) -> Result<(), BudgetError> {
+        match &self.budget {
+            Some(budget) => budget.reserve(request.id, request.offer.maxPrice),
+            None => Ok(()),
+        }
+    }
+
+    // Refund a request counted against the budget whose submission failed.
+    fn release_budget(&self, request: &ProofRequest) {
+        if let Some(budget) = &self.budget {
+            budget.release(request.id);
+        }
+    }
+
+    // Notify each middleware of the event.
+    async fn notify_middleware(&self, event: ClientEvent<'_>) {
+        for middleware in &self.middleware {
+            middleware.on_event(&event).await;
+        }
+    }
+
+    /// Check that the order stream is up, returning the round-trip time of its health check.
+    ///
+    /// Only the primary order stream is checked, as it is the one whose failure fails offchain
+    /// submissions.
+    pub async fn order_stream_health(&self) -> Result<Duration, ClientError> {
+        let offchain_client =
+            self.offchain_client.as_ref().ok_or(ClientError::OrderStreamNotConfigured)?;
+        Ok(offchain_client.health_check().await?)
+    }
+
+    /// Check the prover that locked the given request against the [ProverPolicy] of this client.
+    ///
+    /// The market does not let requestors restrict which prover locks a request, so this check
+    /// lets requestors detect and report locks by disallowed provers. A warning is logged if the
+    /// prover is not allowed. Returns [PolicyVerdict::Allowed] if no policy is set.
+    pub async fn check_prover_policy(
+        &self,
+        request_id: impl Into<U256>,
+    ) -> Result<PolicyVerdict, ClientError> {
+        let request_id = request_id.into();
+        let Some(policy) = &self.prover_policy else {
+            return Ok(PolicyVerdict::Allowed);
+        };
+        let prover = self.boundless_market.get_locking_prover(request_id).await?;
+        let verdict = policy.check(prover);
+        if !verdict.is_allowed() {
+            tracing::warn!(
+                "Request 0x{request_id:x} was locked by a prover not allowed by the policy: {verdict:?}"
+            );
+        }
+        Ok(verdict)
+    }
+
     /// Set the transaction timeout
     pub fn with_timeout(self, tx_timeout: Duration) -> Self {
         Self {
@@ -610,8 +1261,12 @@ where
             set_verifier: self.set_verifier,
             storage_provider: self.storage_provider,
             offchain_client: self.offchain_client,
+            additional_offchain_clients: self.additional_offchain_clients,
             request_builder: self.request_builder,
             deployment: self.deployment,
+            prover_policy: self.prover_policy,
+            budget: self.budget,
+            middleware: self.middleware,
         }
     }
 
@@ -673,6 +1328,79 @@ where
         Ok(request)
     }
 
+    /// Build many proof requests concurrently, returning the result for each in order.
+    ///
+    /// At most `concurrency` requests are built at once. Program upload, input upload, and
+    /// preflight execution run concurrently across requests. Requests that share a program which
+    /// has not yet been uploaded reuse a single upload: the first request with each program is
+    /// built before the others, which then use its program URL.
+    ///
+    /// Requests built concurrently should not set their ID from the account nonce, as they may
+    /// be assigned the same ID. The default [RequestIdLayerMode::Rand] is unaffected.
+    ///
+    /// Requires a [RequestBuilder] to be provided.
+    ///
+    /// [RequestIdLayerMode::Rand]: crate::request_builder::RequestIdLayerMode::Rand
+    pub async fn build_requests(
+        &self,
+        params: impl IntoIterator<Item = RequestParams>,
+        concurrency: usize,
+    ) -> Vec<Result<ProofRequest, ClientError>>
+    where
+        R: RequestBuilder<RequestParams>,
+        R::Error: Into<anyhow::Error>,
+    {
+        let mut params: Vec<Option<RequestParams>> = params.into_iter().map(Some).collect();
+        let mut results: Vec<Option<Result<ProofRequest, ClientError>>> =
+            params.iter().map(|_| None).collect();
+
+        // Index of the first request with each program that needs to be uploaded.
+        let mut program_leaders = HashMap::<B256, usize>::new();
+        let mut followers = HashMap::<usize, usize>::new();
+        for (i, p) in params.iter().enumerate() {
+            let p = p.as_ref().expect("params are only taken when built");
+            if let (None, Some(program)) = (&p.program_url, &p.program) {
+                let leader = *program_leaders.entry(keccak256(program)).or_insert(i);
+                if leader != i {
+                    followers.insert(i, leader);
+                }
+            }
+        }
+
+        let build_all = |batch: Vec<(usize, RequestParams)>| {
+            futures::stream::iter(batch)
+                .map(move |(i, p)| async move { (i, self.build_request::<RequestParams>(p).await) })
+                .buffer_unordered(concurrency.max(1))
+                .collect::<Vec<_>>()
+        };
+
+        let first: Vec<_> = (0..params.len())
+            .filter(|i| !followers.contains_key(i))
+            .map(|i| (i, params[i].take().expect("params are only taken when built")))
+            .collect();
+        for (i, result) in build_all(first).await {
+            results[i] = Some(result);
+        }
+
+        let mut rest: Vec<_> = followers.into_iter().collect();
+        rest.sort_unstable();
+        let rest: Vec<_> = rest
+            .into_iter()
+            .map(|(i, leader)| {
+                let mut p = params[i].take().expect("params are only taken when built");
+                if let Some(Ok(request)) = &results[leader] {
+                    p.program_url = Url::parse(&request.imageUrl).ok();
+                }
+                (i, p)
+            })
+            .collect();
+        for (i, result) in build_all(rest).await {
+            results[i] = Some(result);
+        }
+
+        results.into_iter().map(|result| result.expect("every request is built")).collect()
+    }
+
     /// Build and submit a proof request by sending an onchain transaction.
     ///
     /// Requires a [Signer] to be provided to sign the request, and a [RequestBuilder] to be
@@ -704,6 +1432,22 @@ where
         self.submit_request_onchain_with_signer(request, signer).await
     }
 
+    /// Submit a proof request in an onchain transaction, paid for from the market balance only.
+    ///
+    /// No funds are sent with the transaction, so the wallet is never drawn on to cover the max
+    /// price. Fails with [MarketError::InsufficientBalance] if the market balance of the client
+    /// does not cover it. Requires a signer to be set to sign the request.
+    pub async fn submit_request_onchain_from_balance(
+        &self,
+        request: &ProofRequest,
+    ) -> Result<(U256, u64), ClientError>
+    where
+        Si: Signer,
+    {
+        let signer = self.signer.as_ref().context("signer not set")?;
+        self.submit_request_onchain_inner(request, signer, true).await
+    }
+
     /// Submit a proof request in a transaction.
     ///
     /// Accepts a signer to sign the request. Note that the transaction will be signed by the alloy
@@ -712,6 +1456,15 @@ where
         &self,
         request: &ProofRequest,
         signer: &impl Signer,
+    ) -> Result<(U256, u64), ClientError> {
+        self.submit_request_onchain_inner(request, signer, false).await
+    }
+
+    async fn submit_request_onchain_inner(
+        &self,
+        request: &ProofRequest,
+        signer: &impl Signer,
+        from_balance: bool,
     ) -> Result<(U256, u64), ClientError> {
         let mut request = request.clone();
 
@@ -722,10 +1475,31 @@ where
         if client_address != signer.address() {
             return Err(MarketError::AddressMismatch(client_address, signer.address()))?;
         };
+        self.middleware_before_submit(&mut request, SubmissionPath::Onchain).await?;
 
         request.validate()?;
+        self.reserve_budget(&request)?;
 
-        let request_id = self.boundless_market.submit_request(&request, signer).await?;
+        let result = if from_balance {
+            self.boundless_market.submit_request_from_balance(&request, signer).await
+        } else {
+            self.boundless_market.submit_request(&request, signer).await
+        };
+        let request_id = match result {
+            Ok(request_id) => request_id,
+            Err(err) => {
+                self.release_budget(&request);
+                let err = ClientError::from(err);
+                self.notify_middleware(ClientEvent::Failed { request_id: request.id, error: &err })
+                    .await;
+                return Err(err);
+            }
+        };
+        self.notify_middleware(ClientEvent::Submitted {
+            request: &request,
+            path: SubmissionPath::Onchain,
+        })
+        .await;
         Ok((request_id, request.expires_at()))
     }
 
@@ -739,9 +1513,18 @@ where
     ) -> Result<(U256, u64), ClientError> {
         let request = request.clone();
         request.validate()?;
+        self.reserve_budget(&request)?;
 
-        let request_id =
-            self.boundless_market.submit_request_with_signature(&request, signature).await?;
+        let request_id = self
+            .boundless_market
+            .submit_request_with_signature(&request, signature)
+            .await
+            .inspect_err(|_| self.release_budget(&request))?;
+        self.notify_middleware(ClientEvent::Submitted {
+            request: &request,
+            path: SubmissionPath::Onchain,
+        })
+        .await;
         Ok((request_id, request.expires_at()))
     }
 
@@ -764,6 +1547,13 @@ where
 
     /// Submit a proof request offchain via the order stream service.
     ///
+    /// The request is submitted with a single REST call, so no WebSocket connection to the order
+    /// stream is opened. Errors returned by the order stream are surfaced as
+    /// [ClientError::OrderStreamError], which can be inspected to decide whether to retry.
+    ///
+    /// With additional order streams in the [Deployment], the request is submitted to all of them
+    /// concurrently, and this returns as soon as the first accepts it.
+    ///
     /// Requires a signer to be set to sign the request.
     pub async fn submit_request_offchain(
         &self,
@@ -784,10 +1574,34 @@ where
         request: &ProofRequest,
         signer: &impl Signer,
     ) -> Result<(U256, u64), ClientError> {
-        let offchain_client = self
-            .offchain_client
-            .as_ref()
-            .context("Order stream client not available. Please provide an order stream URL")?;
+        self.submit_request_offchain_with_metadata_and_signer(request, None, signer).await
+    }
+
+    /// Submit a proof request offchain via the order stream service, attaching the given
+    /// [RequestMetadata].
+    ///
+    /// The metadata is signed along with the request, and is stored and returned with the order
+    /// by the order stream. Requires a signer to be set to sign the request.
+    pub async fn submit_request_offchain_with_metadata(
+        &self,
+        request: &ProofRequest,
+        metadata: RequestMetadata,
+    ) -> Result<(U256, u64), ClientError>
+    where
+        Si: Signer,
+    {
+        let signer = self.signer.as_ref().context("signer not set")?;
+        self.submit_request_offchain_with_metadata_and_signer(request, Some(metadata), signer).await
+    }
+
+    async fn submit_request_offchain_with_metadata_and_signer(
+        &self,
+        request: &ProofRequest,
+        metadata: Option<RequestMetadata>,
+        signer: &impl Signer,
+    ) -> Result<(U256, u64), ClientError> {
+        let offchain_client =
+            self.offchain_client.as_ref().ok_or(ClientError::OrderStreamNotConfigured)?;
         let mut request = request.clone();
 
         if request.id == U256::ZERO {
@@ -797,6 +1611,7 @@ where
         if client_address != signer.address() {
             return Err(MarketError::AddressMismatch(client_address, signer.address()))?;
         };
+        self.middleware_before_submit(&mut request, SubmissionPath::Offchain).await?;
         // Ensure address' balance is sufficient to cover the request
         let balance = self.boundless_market.balance_of(client_address).await?;
         if balance < U256::from(request.offer.maxPrice) {
@@ -806,28 +1621,300 @@ where
                 request.offer.maxPrice
             )));
         }
+        self.reserve_budget(&request)?;
 
-        let order = offchain_client.submit_request(&request, signer).await?;
+        // Every order stream serves the same deployment, so the order is signed once.
+        let result = match offchain_client.sign_order(&request, signer, metadata).await {
+            Ok(order) => self.submit_order_to_streams(order).await,
+            Err(err) => Err(err.into()),
+        };
+        let order = match result {
+            Ok(order) => order,
+            Err(err) => {
+                self.release_budget(&request);
+                self.notify_middleware(ClientEvent::Failed { request_id: request.id, error: &err })
+                    .await;
+                return Err(err);
+            }
+        };
+        self.notify_middleware(ClientEvent::Submitted {
+            request: &request,
+            path: SubmissionPath::Offchain,
+        })
+        .await;
 
         Ok((order.request.id, request.expires_at()))
     }
 
+    // Submit the order to all order streams concurrently, returning once the first accepts it.
+    // The submissions to the other order streams carry on in the background. The order is only
+    // refused if every order stream rejects it, with the error of the first to respond.
+    async fn submit_order_to_streams(&self, order: Order) -> Result<Order, ClientError> {
+        let order = Arc::new(order);
+        let log_failures = !self.additional_offchain_clients.is_empty();
+        let mut submissions = self
+            .offchain_client
+            .iter()
+            .chain(&self.additional_offchain_clients)
+            .map(|offchain_client| {
+                let (offchain_client, order) = (offchain_client.clone(), order.clone());
+                tokio::spawn(async move {
+                    let result = offchain_client.submit_order(&order).await;
+                    if let Err(err) = &result {
+                        if log_failures {
+                            tracing::warn!(
+                                "Failed to submit request 0x{:x} to order stream {}: {err}",
+                                order.request.id,
+                                offchain_client.base_url
+                            );
+                        }
+                    }
+                    result
+                })
+            })
+            .collect::<FuturesUnordered<_>>();
+        let mut error = None;
+        while let Some(joined) = submissions.next().await {
+            match joined {
+                Ok(Ok(_)) => return Ok(Arc::unwrap_or_clone(order)),
+                Ok(Err(err)) => {
+                    error.get_or_insert(err);
+                }
+                Err(err) => tracing::warn!("Order stream submission task failed: {err}"),
+            }
+        }
+        Err(error.map_or(ClientError::OrderStreamNotConfigured, ClientError::from))
+    }
+
+    /// Check that the order stream would accept a proof request, without submitting it.
+    ///
+    /// The request is signed and sent to the validation endpoint of the order stream, which runs
+    /// the checks of submission: the signature and deployment of the order, the expiry of the
+    /// request, whether it was already submitted or its ID used onchain, and whether the
+    /// requestor deposited enough to pay its max price. This lets requestors lint offchain
+    /// submissions, e.g. in CI, against the order stream they submit to in production.
+    ///
+    /// Unlike submission, the request is validated as given: middleware is not run, no budget is
+    /// reserved, and a request ID of zero is not replaced. Only the primary order stream is
+    /// checked. Requires a signer to be set to sign the request.
+    pub async fn validate_offchain_request(
+        &self,
+        request: &ProofRequest,
+    ) -> Result<ValidateOrderRes, ClientError>
+    where
+        Si: Signer,
+    {
+        let signer = self.signer.as_ref().context("signer not set")?;
+        let offchain_client =
+            self.offchain_client.as_ref().ok_or(ClientError::OrderStreamNotConfigured)?;
+        Ok(offchain_client.validate_request(request, signer, None).await?)
+    }
+
+    /// Submit a proof request offchain if possible, falling back to an onchain transaction.
+    ///
+    /// If an order stream URL is configured, the request is first submitted to the order stream.
+    /// If the order stream is unreachable or rejects the order, the request is submitted onchain
+    /// instead. Returns the request ID, its expiration time, and the [SubmissionPath] used.
+    ///
+    /// Requires a signer to be set to sign the request.
+    pub async fn submit_request_auto(
+        &self,
+        request: &ProofRequest,
+    ) -> Result<(U256, u64, SubmissionPath), ClientError>
+    where
+        Si: Signer,
+    {
+        let signer = self.signer.as_ref().context("signer not set")?;
+        let mut request = request.clone();
+        // Assign the ID up front, so that both paths submit the same request.
+        if request.id == U256::ZERO {
+            request.id = self.boundless_market.request_id_from_rand().await?;
+        };
+
+        if self.offchain_client.is_some() {
+            match self.submit_request_offchain_with_signer(&request, signer).await {
+                Ok((request_id, expires_at)) => {
+                    return Ok((request_id, expires_at, SubmissionPath::Offchain));
+                }
+                Err(ClientError::OrderStreamError(err)) => {
+                    tracing::warn!(
+                        "Failed to submit request 0x{:x} to the order stream, submitting onchain: {err}",
+                        request.id
+                    );
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (request_id, expires_at) =
+            self.submit_request_onchain_with_signer(&request, signer).await?;
+        Ok((request_id, expires_at, SubmissionPath::Onchain))
+    }
+
     /// Wait for a request to be fulfilled.
     ///
     /// The check interval is the time between each check for fulfillment.
     /// The timeout is the maximum time to wait for the request to be fulfilled.
     pub async fn wait_for_request_fulfillment(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
         check_interval: std::time::Duration,
         expires_at: u64,
     ) -> Result<Fulfillment, ClientError> {
-        Ok(self
+        let request_id = request_id.into();
+        let result = self
             .boundless_market
             .wait_for_request_fulfillment(request_id, check_interval, expires_at)
+            .await
+            .map_err(ClientError::from);
+        match &result {
+            Ok(fulfillment) => {
+                self.notify_middleware(ClientEvent::Fulfilled { request_id, fulfillment }).await
+            }
+            Err(error) => self.notify_middleware(ClientEvent::Failed { request_id, error }).await,
+        }
+        result
+    }
+
+    /// Wait for a request to be fulfilled, and for the fulfillment to be buried under
+    /// `confirmations` blocks.
+    ///
+    /// Returns the fulfillment along with the block and transaction that delivered it. If the
+    /// fulfillment is reorged out while waiting for confirmations, waiting resumes until it is
+    /// delivered again or the request expires.
+    pub async fn wait_for_confirmed_fulfillment(
+        &self,
+        request_id: impl Into<U256>,
+        check_interval: std::time::Duration,
+        expires_at: u64,
+        confirmations: u64,
+    ) -> Result<ConfirmedFulfillment, ClientError> {
+        let request_id = request_id.into();
+        Ok(self
+            .boundless_market
+            .wait_for_confirmed_fulfillment(request_id, check_interval, expires_at, confirmations)
             .await?)
     }
 
+    /// Prove the execution of a program on the given input, and return its journal.
+    ///
+    /// This is the simplest way to outsource a proof: the program and input are uploaded, and a
+    /// request with the default pricing is built, submitted offchain if possible, and waited for.
+    /// The seal of the fulfillment is verified onchain, with the verifier router of the deployment
+    /// or its set verifier, before the journal is returned.
+    ///
+    /// Fails if the proof is not delivered within [DEFAULT_PROVE_TIMEOUT]; see
+    /// [Client::prove_with_timeout]. Use [Client::new_request] and [Client::submit_onchain] to
+    /// control the request.
+    ///
+    /// Requires a signer, a storage provider, and a [RequestBuilder] to be provided.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use alloy::{primitives::Bytes, signers::local::PrivateKeySigner};
+    /// use boundless_market::{client::ClientBuilder, storage::StorageProviderConfig};
+    /// use url::Url;
+    ///
+    /// async fn prove(
+    ///     rpc_url: Url,
+    ///     signer: PrivateKeySigner,
+    ///     storage: StorageProviderConfig,
+    ///     program: &'static [u8],
+    ///     input: Vec<u8>,
+    /// ) -> Result<Bytes> {
+    ///     let client = ClientBuilder::new()
+    ///         .with_rpc_url(rpc_url)
+    ///         .with_private_key(signer)
+    ///         .with_storage_provider_config(&storage)?
+    ///         .build()
+    ///         .await?;
+    ///     let journal = client.prove(program, input).await?;
+    ///     Ok(journal)
+    /// }
+    /// ```
+    pub async fn prove(
+        &self,
+        program: impl Into<Cow<'static, [u8]>>,
+        input: impl Into<Vec<u8>>,
+    ) -> Result<Bytes, ClientError>
+    where
+        Si: Signer,
+        R: RequestBuilder<RequestParams>,
+        R::Error: Into<anyhow::Error>,
+    {
+        self.prove_with_timeout(program, input, DEFAULT_PROVE_TIMEOUT).await
+    }
+
+    /// Prove the execution of a program on the given input, and return its journal, failing if
+    /// the proof is not delivered within `timeout`.
+    ///
+    /// See [Client::prove].
+    pub async fn prove_with_timeout(
+        &self,
+        program: impl Into<Cow<'static, [u8]>>,
+        input: impl Into<Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<Bytes, ClientError>
+    where
+        Si: Signer,
+        R: RequestBuilder<RequestParams>,
+        R::Error: Into<anyhow::Error>,
+    {
+        let params = RequestParams::new().with_program(program).with_stdin(input);
+        tokio::time::timeout(timeout, self.prove_request(params))
+            .await
+            .map_err(|_| anyhow!("proof was not delivered within {} seconds", timeout.as_secs()))?
+    }
+
+    async fn prove_request(&self, params: RequestParams) -> Result<Bytes, ClientError>
+    where
+        Si: Signer,
+        R: RequestBuilder<RequestParams>,
+        R::Error: Into<anyhow::Error>,
+    {
+        let request = self.build_request(params).await?;
+        let image_id = Predicate::try_from(request.requirements.predicate.clone())
+            .context("failed to decode the request predicate")?
+            .image_id()
+            .context("request predicate has no image ID")?;
+        let (request_id, expires_at, _) = self.submit_request_auto(&request).await?;
+        tracing::info!("Submitted request 0x{request_id:x}, waiting for its proof");
+
+        let fulfillment =
+            self.wait_for_request_fulfillment(request_id, PROVE_CHECK_INTERVAL, expires_at).await?;
+        let journal = match fulfillment.data().context("failed to decode fulfillment data")? {
+            FulfillmentData::ImageIdAndJournal(fulfilled_image_id, journal) => {
+                if fulfilled_image_id != image_id {
+                    return Err(ClientError::Error(anyhow!(
+                        "request 0x{request_id:x} was fulfilled for image ID {fulfilled_image_id}, expected {image_id}"
+                    )));
+                }
+                journal
+            }
+            FulfillmentData::None => {
+                return Err(ClientError::Error(anyhow!(
+                    "request 0x{request_id:x} was fulfilled without a journal"
+                )))
+            }
+        };
+
+        let verifier_address =
+            self.deployment.verifier_router_address.unwrap_or(self.deployment.set_verifier_address);
+        let journal_digest = Journal::new(journal.to_vec()).digest();
+        IRiscZeroVerifier::new(verifier_address, self.provider())
+            .verify(
+                fulfillment.seal,
+                <[u8; 32]>::from(image_id).into(),
+                <[u8; 32]>::from(journal_digest).into(),
+            )
+            .call()
+            .await
+            .with_context(|| format!("failed to verify the seal of request 0x{request_id:x}"))?;
+        Ok(journal)
+    }
+
     /// Get the [SetInclusionReceipt] for a request.
     ///
     /// # Examples
@@ -847,9 +1934,10 @@ where
     /// ```
     pub async fn fetch_set_inclusion_receipt(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
         image_id: B256,
     ) -> Result<(Bytes, SetInclusionReceipt<ReceiptClaim>), ClientError> {
+        let request_id = request_id.into();
         // TODO(#646): This logic is only correct under the assumption there is a single set
         // verifier.
         let fulfillment = self.boundless_market.get_request_fulfillment(request_id).await?;
@@ -884,10 +1972,11 @@ where
     /// be returned.
     pub async fn fetch_proof_request(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
         tx_hash: Option<B256>,
         request_digest: Option<B256>,
     ) -> Result<(ProofRequest, Bytes), ClientError> {
+        let request_id = request_id.into();
         if let Some(ref order_stream_client) = self.offchain_client {
             tracing::debug!("Querying the order stream for request: 0x{request_id:x} using request_digest {request_digest:?}");
             match order_stream_client.fetch_order(request_id, request_digest).await {
@@ -921,4 +2010,53 @@ where
                 .map_err(Into::into),
         }
     }
+
+    /// Fetch the proof request with the given EIP-712 digest, along with the transaction that
+    /// carried it.
+    ///
+    /// Request digests appear in fulfillments and in logs. See
+    /// [BoundlessMarketService::get_request_by_digest] for how the request is found.
+    pub async fn fetch_request_by_digest(
+        &self,
+        request_digest: B256,
+    ) -> Result<LocatedRequest, ClientError> {
+        Ok(self.boundless_market.get_request_by_digest(request_digest, None, None).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_settings_override_the_preset_in_any_order() {
+        let timeout = Duration::from_secs(60);
+        let retry = RpcRetryConfig::new(1, 10, 10);
+        let before =
+            ClientBuilder::new().with_timeout(timeout).with_rpc_retry(retry).prover_defaults();
+        let after =
+            ClientBuilder::new().prover_defaults().with_timeout(timeout).with_rpc_retry(retry);
+        for builder in [before, after] {
+            assert_eq!(builder.resolved_tx_timeout(), Some(timeout));
+            assert_eq!(builder.resolved_rpc_retry(), Some(retry));
+            assert_eq!(builder.resolved_gas_filler(), ClientPreset::Prover.gas_filler());
+            assert_eq!(
+                builder.resolved_balance_alerts().unwrap().warn_threshold,
+                ClientPreset::Prover.balance_alerts().warn_threshold
+            );
+        }
+
+        // Switching presets keeps the explicit settings.
+        let builder =
+            ClientBuilder::new().prover_defaults().with_timeout(timeout).requestor_defaults();
+        assert_eq!(builder.resolved_tx_timeout(), Some(timeout));
+        assert_eq!(builder.resolved_rpc_retry(), Some(ClientPreset::Requestor.rpc_retry()));
+        assert_eq!(builder.resolved_gas_filler(), GasFillerConfig::default());
+
+        // Without a preset, nothing is set.
+        let builder = ClientBuilder::new();
+        assert_eq!(builder.resolved_tx_timeout(), None);
+        assert_eq!(builder.resolved_rpc_retry(), None);
+        assert!(builder.resolved_balance_alerts().is_none());
+    }
 }
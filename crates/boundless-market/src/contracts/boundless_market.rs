@@ -13,22 +13,24 @@
 // limitations under the License.
 
 use std::{
+    collections::HashMap,
     fmt::Debug,
+    ops::ControlFlow,
     sync::atomic::{AtomicU64, Ordering},
     time::Duration,
 };
 
 use alloy::{
     consensus::{BlockHeader, Transaction},
-    eips::BlockNumberOrTag,
-    network::Ethereum,
+    eips::{BlockId, BlockNumberOrTag},
+    network::{Ethereum, Network},
     primitives::{utils::format_ether, Address, Bytes, B256, U256},
     providers::{PendingTransactionBuilder, PendingTransactionError, Provider},
     rpc::types::{Log, TransactionReceipt},
     signers::Signer,
 };
 
-use alloy_sol_types::{SolCall, SolEvent};
+use alloy_sol_types::{SolCall, SolError, SolEvent, SolInterface};
 use anyhow::{anyhow, Context, Result};
 use risc0_ethereum_contracts::event_query::EventQueryConfig;
 use thiserror::Error;
@@ -36,14 +38,34 @@ use thiserror::Error;
 use crate::{
     contracts::token::{IERC20Permit, IHitPoints::IHitPointsErrors, Permit, IERC20},
     deployments::collateral_token_supports_permit,
+    event_bus::{AlertLevel, BalanceAsset, BusEvent, EventBus},
+    log_query::{ChunkedLogQuery, ScanDirection},
 };
 
 use super::{
-    eip712_domain, AssessorReceipt, EIP712DomainSaltless, Fulfillment,
-    IBoundlessMarket::{self, IBoundlessMarketInstance, ProofDelivered},
-    Offer, ProofRequest, RequestError, RequestId, RequestStatus, TxnErr, TXN_CONFIRM_TIMEOUT,
+    eip712_domain, eip712_domain_separator, AssessorReceipt, EIP712DomainSaltless, Fulfillment,
+    IBoundlessMarket::{self, IBoundlessMarketErrors, IBoundlessMarketInstance, ProofDelivered},
+    Offer, ProofRequest, RequestError, RequestId, RequestLock, RequestStatus, TxnErr,
+    TXN_CONFIRM_TIMEOUT,
 };
 
+alloy::sol! {
+    /// Getter of the public `requestLocks` mapping of the market, which is not part of
+    /// [IBoundlessMarket].
+    #[sol(rpc)]
+    interface IRequestLocks {
+        function requestLocks(uint256 id) external view returns (
+            address prover,
+            uint64 lockDeadline,
+            uint24 deadlineDelta,
+            uint8 requestLockFlags,
+            uint96 price,
+            uint96 collateral,
+            bytes32 requestDigest
+        );
+    }
+}
+
 /// Fraction of collateral the protocol gives to the prover who fills an order that was locked by another prover but expired
 /// This is determined by the constant SLASHING_BURN_BPS defined in the BoundlessMarket contract.
 /// The value is 4 because the slashing burn is 75% of the collateral, and we give the remaining 1/4 of that to the prover.
@@ -74,6 +96,10 @@ pub enum MarketError {
     #[error("Request is slashed 0x{0:x}")]
     RequestIsSlashed(U256),
 
+    /// Request has not expired yet.
+    #[error("Request 0x{0:x} does not expire until {1}")]
+    RequestNotExpired(U256, u64),
+
     /// Request malformed.
     #[error("Request error {0}")]
     RequestError(#[from] RequestError),
@@ -90,6 +116,10 @@ pub enum MarketError {
     #[error("Request not found in event logs 0x{0:x}")]
     RequestNotFound(U256),
 
+    /// No request with the given digest was found.
+    #[error("Request with digest {0} not found in event logs")]
+    RequestDigestNotFound(B256),
+
     /// Request already locked.
     #[error("Request already locked: 0x{0:x}")]
     RequestAlreadyLocked(U256),
@@ -113,6 +143,10 @@ pub enum MarketError {
     /// Timeout reached.
     #[error("Timeout: 0x{0:x}")]
     TimeoutReached(U256),
+
+    /// Market balance does not cover the max price of the request.
+    #[error("Market balance of {} ETH does not cover the max price of {} ETH", format_ether(*.0), format_ether(*.1))]
+    InsufficientBalance(U256, U256),
 }
 
 impl From<alloy::contract::Error> for MarketError {
@@ -123,8 +157,13 @@ impl From<alloy::contract::Error> for MarketError {
 }
 
 /// Proof market service.
-pub struct BoundlessMarketService<P> {
-    instance: IBoundlessMarketInstance<P, Ethereum>,
+///
+/// The service is generic over the alloy [Network] of the provider, defaulting to [Ethereum].
+/// Queries, deposits, and withdrawals are available on any network. Methods that inspect
+/// transaction receipts or blocks, such as locking, fulfilling, and slashing, require the
+/// [Ethereum] network types.
+pub struct BoundlessMarketService<P, N = Ethereum> {
+    instance: IBoundlessMarketInstance<P, N>,
     // Chain ID with caching to ensure we fetch it at most once.
     // Note: always access via get_chain_id()
     chain_id: AtomicU64,
@@ -133,6 +172,45 @@ pub struct BoundlessMarketService<P> {
     event_query_config: EventQueryConfig,
     balance_alert_config: StakeBalanceAlertConfig,
     receipt_query_config: ReceiptQueryConfig,
+    confirmation_policies: HashMap<MarketTxType, ConfirmationPolicy>,
+    event_bus: Option<EventBus>,
+}
+
+/// Type of a transaction sent by the [BoundlessMarketService], with its own [ConfirmationPolicy].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MarketTxType {
+    /// Deposits and withdrawals of funds or collateral, including collateral approvals.
+    Deposit,
+    /// Locks of requests.
+    Lock,
+    /// Fulfillments of requests, including the submission of their Merkle root.
+    ///
+    /// Its confirmations also apply to the fulfillments awaited by
+    /// [BoundlessMarketService::wait_for_request_fulfillment], whoever sent them.
+    Fulfill,
+    /// Slashes of expired requests.
+    Slash,
+}
+
+/// How long to wait for a transaction to confirm, and how deeply.
+///
+/// Latency-critical transactions, such as locks raced by competing provers, call for a short
+/// timeout, while slashes can wait for more confirmations before being considered final.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ConfirmationPolicy {
+    /// Number of blocks, including the one the transaction is included in, to wait for.
+    pub confirmations: u64,
+    /// Time to wait for the confirmations before failing.
+    pub timeout: Duration,
+}
+
+impl ConfirmationPolicy {
+    /// Creates a new policy, waiting up to `timeout` for `confirmations` blocks.
+    pub fn new(confirmations: u64, timeout: Duration) -> Self {
+        Self { confirmations, timeout }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -157,7 +235,7 @@ struct StakeBalanceAlertConfig {
     error_threshold: Option<U256>,
 }
 
-impl<P: Clone> Clone for BoundlessMarketService<P> {
+impl<P: Clone, N: Network> Clone for BoundlessMarketService<P, N> {
     fn clone(&self) -> Self {
         Self {
             instance: self.instance.clone(),
@@ -167,6 +245,8 @@ impl<P: Clone> Clone for BoundlessMarketService<P> {
             event_query_config: self.event_query_config.clone(),
             balance_alert_config: self.balance_alert_config.clone(),
             receipt_query_config: self.receipt_query_config.clone(),
+            confirmation_policies: self.confirmation_policies.clone(),
+            event_bus: self.event_bus.clone(),
         }
     }
 }
@@ -209,7 +289,11 @@ fn extract_tx_log<E: SolEvent + Debug + Clone>(
     }
 }
 
-impl<P: Provider> BoundlessMarketService<P> {
+impl<P, N> BoundlessMarketService<P, N>
+where
+    P: Provider<N>,
+    N: Network,
+{
     /// Creates a new Boundless market service.
     pub fn new(address: impl Into<Address>, provider: P, caller: impl Into<Address>) -> Self {
         let instance = IBoundlessMarket::new(address.into(), provider);
@@ -222,19 +306,82 @@ impl<P: Provider> BoundlessMarketService<P> {
             event_query_config: EventQueryConfig::default(),
             balance_alert_config: StakeBalanceAlertConfig::default(),
             receipt_query_config: ReceiptQueryConfig::default(),
+            confirmation_policies: HashMap::new(),
+            event_bus: None,
         }
     }
 
     /// Sets the transaction timeout.
+    ///
+    /// This applies to the transactions whose type has no policy set with
+    /// [Self::with_confirmation_policy].
     pub fn with_timeout(self, timeout: Duration) -> Self {
         Self { timeout, ..self }
     }
 
+    /// Sets the [ConfirmationPolicy] of the transactions of the given type.
+    ///
+    /// Transactions of types without a policy wait for one confirmation, up to the timeout set
+    /// with [Self::with_timeout].
+    pub fn with_confirmation_policy(
+        mut self,
+        tx_type: MarketTxType,
+        policy: ConfirmationPolicy,
+    ) -> Self {
+        self.confirmation_policies.insert(tx_type, policy);
+        self
+    }
+
+    /// Returns the [ConfirmationPolicy] of the transactions of the given type.
+    pub fn confirmation_policy(&self, tx_type: MarketTxType) -> ConfirmationPolicy {
+        self.confirmation_policies.get(&tx_type).copied().unwrap_or(self.default_policy())
+    }
+
+    fn default_policy(&self) -> ConfirmationPolicy {
+        ConfirmationPolicy::new(1, self.timeout)
+    }
+
+    // Configure the pending transaction to wait as required by the policy of its type, if any.
+    fn with_policy(
+        &self,
+        pending_tx: PendingTransactionBuilder<N>,
+        tx_type: Option<MarketTxType>,
+    ) -> (PendingTransactionBuilder<N>, ConfirmationPolicy) {
+        let policy = tx_type.map_or(self.default_policy(), |ty| self.confirmation_policy(ty));
+        let pending_tx = pending_tx
+            .with_required_confirmations(policy.confirmations)
+            .with_timeout(Some(policy.timeout));
+        (pending_tx, policy)
+    }
+
+    /// Sets the chain ID of the network the market is deployed on, e.g. from a [Deployment].
+    ///
+    /// This primes the cache used by [Self::get_chain_id], so that the chain ID and the
+    /// [EIP-712 domain](Self::eip712_domain) are available without an RPC call.
+    ///
+    /// [Deployment]: crate::Deployment
+    pub fn with_chain_id(self, chain_id: u64) -> Self {
+        Self { chain_id: AtomicU64::new(chain_id), ..self }
+    }
+
     /// Sets the event query configuration.
     pub fn with_event_query_config(self, config: EventQueryConfig) -> Self {
         Self { event_query_config: config, ..self }
     }
 
+    /// Sets the [EventBus] on which confirmed transactions and collateral balance alerts are
+    /// published.
+    pub fn with_event_bus(self, event_bus: impl Into<Option<EventBus>>) -> Self {
+        Self { event_bus: event_bus.into(), ..self }
+    }
+
+    // Publish the event on the event bus, if any.
+    fn publish(&self, event: BusEvent) {
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(event);
+        }
+    }
+
     /// Set collateral balance thresholds to warn or error alert on
     pub fn with_collateral_balance_alert(
         self,
@@ -262,8 +409,22 @@ impl<P: Provider> BoundlessMarketService<P> {
         self
     }
 
+    /// Sets the number of blocks that must be built on top of a fulfillment before
+    /// [BoundlessMarketService::wait_for_request_fulfillment] returns it.
+    ///
+    /// This sets the confirmations of the [MarketTxType::Fulfill] policy to `confirmations + 1`,
+    /// keeping its timeout.
+    #[deprecated(note = "use with_confirmation_policy with MarketTxType::Fulfill")]
+    pub fn with_fulfillment_confirmations(self, confirmations: u64) -> Self {
+        let timeout = self.confirmation_policy(MarketTxType::Fulfill).timeout;
+        self.with_confirmation_policy(
+            MarketTxType::Fulfill,
+            ConfirmationPolicy::new(confirmations + 1, timeout),
+        )
+    }
+
     /// Returns the market contract instance.
-    pub fn instance(&self) -> &IBoundlessMarketInstance<P, Ethereum> {
+    pub fn instance(&self) -> &IBoundlessMarketInstance<P, N> {
         &self.instance
     }
 
@@ -274,22 +435,32 @@ impl<P: Provider> BoundlessMarketService<P> {
 
     /// Get the EIP-712 domain associated with the market contract.
     ///
-    /// If not cached, this function will fetch the chain ID with an RPC call.
+    /// If the chain ID is not cached, or set with [Self::with_chain_id], this function will fetch
+    /// it with an RPC call.
     pub async fn eip712_domain(&self) -> Result<EIP712DomainSaltless, MarketError> {
         Ok(eip712_domain(*self.instance.address(), self.get_chain_id().await?))
     }
 
+    /// Get the separator of the EIP-712 domain associated with the market contract.
+    ///
+    /// Separators are cached per chain and market, see [eip712_domain_separator].
+    pub async fn eip712_domain_separator(&self) -> Result<B256, MarketError> {
+        Ok(eip712_domain_separator(*self.instance.address(), self.get_chain_id().await?))
+    }
+
     /// Deposit Ether into the market to pay for proof and/or lockin collateral.
     pub async fn deposit(&self, value: U256) -> Result<(), MarketError> {
         tracing::trace!("Calling deposit() value: {value}");
         let call = self.instance.deposit().value(value);
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting deposit tx {}", pending_tx.tx_hash());
-        let tx_hash = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_hash = self
+            .with_policy(pending_tx, Some(MarketTxType::Deposit))
+            .0
             .watch()
             .await
             .context("failed to confirm tx")?;
+        self.publish(BusEvent::TxConfirmed { tx_hash });
         tracing::debug!("Submitted deposit {}", tx_hash);
 
         Ok(())
@@ -301,11 +472,13 @@ impl<P: Provider> BoundlessMarketService<P> {
         let call = self.instance.withdraw(amount);
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting withdraw tx {}", pending_tx.tx_hash());
-        let tx_hash = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_hash = self
+            .with_policy(pending_tx, Some(MarketTxType::Deposit))
+            .0
             .watch()
             .await
             .context("failed to confirm tx")?;
+        self.publish(BusEvent::TxConfirmed { tx_hash });
         tracing::debug!("Submitted withdraw {}", tx_hash);
 
         Ok(())
@@ -313,13 +486,256 @@ impl<P: Provider> BoundlessMarketService<P> {
 
     /// Returns the balance, in Wei, of the given account.
     pub async fn balance_of(&self, account: impl Into<Address>) -> Result<U256, MarketError> {
+        self.balance_of_at(account, BlockId::latest()).await
+    }
+
+    /// Returns the balance, in Wei, of the given account as of the given block.
+    ///
+    /// Querying blocks older than the recent state requires an archive node.
+    pub async fn balance_of_at(
+        &self,
+        account: impl Into<Address>,
+        block: impl Into<BlockId>,
+    ) -> Result<U256, MarketError> {
         let account = account.into();
-        tracing::trace!("Calling balanceOf({account})");
-        let balance = self.instance.balanceOf(account).call().await?;
+        let block = block.into();
+        tracing::trace!("Calling balanceOf({account}) at block {block}");
+        let balance = self.instance.balanceOf(account).call().block(block).await?;
+
+        Ok(balance)
+    }
+
+    /// Checks if a request is locked in.
+    pub async fn is_locked(&self, request_id: impl Into<U256>) -> Result<bool, MarketError> {
+        let request_id = request_id.into();
+        self.is_locked_at(request_id, BlockId::latest()).await
+    }
+
+    /// Checks if a request was locked in as of the given block.
+    pub async fn is_locked_at(
+        &self,
+        request_id: impl Into<U256>,
+        block: impl Into<BlockId>,
+    ) -> Result<bool, MarketError> {
+        let request_id = request_id.into();
+        let block = block.into();
+        tracing::trace!("Calling requestIsLocked({:x}) at block {block}", request_id);
+        let res = self.instance.requestIsLocked(request_id).call().block(block).await?;
+
+        Ok(res)
+    }
+
+    /// Checks if a request is fulfilled.
+    pub async fn is_fulfilled(&self, request_id: impl Into<U256>) -> Result<bool, MarketError> {
+        let request_id = request_id.into();
+        self.is_fulfilled_at(request_id, BlockId::latest()).await
+    }
+
+    /// Checks if a request was fulfilled as of the given block.
+    pub async fn is_fulfilled_at(
+        &self,
+        request_id: impl Into<U256>,
+        block: impl Into<BlockId>,
+    ) -> Result<bool, MarketError> {
+        let request_id = request_id.into();
+        let block = block.into();
+        tracing::trace!("Calling requestIsFulfilled({:x}) at block {block}", request_id);
+        let res = self.instance.requestIsFulfilled(request_id).call().block(block).await?;
+
+        Ok(res)
+    }
+
+    /// Checks if a request is slashed.
+    pub async fn is_slashed(&self, request_id: impl Into<U256>) -> Result<bool, MarketError> {
+        let request_id = request_id.into();
+        self.is_slashed_at(request_id, BlockId::latest()).await
+    }
+
+    /// Checks if a request was slashed as of the given block.
+    pub async fn is_slashed_at(
+        &self,
+        request_id: impl Into<U256>,
+        block: impl Into<BlockId>,
+    ) -> Result<bool, MarketError> {
+        let request_id = request_id.into();
+        let block = block.into();
+        tracing::trace!("Calling requestIsSlashed({:x}) at block {block}", request_id);
+        let res = self.instance.requestIsSlashed(request_id).call().block(block).await?;
+
+        Ok(res)
+    }
+
+    /// Returns the lock of a request, as stored by the market.
+    ///
+    /// The lock is all zeros if the request was never locked. Once the request is fulfilled
+    /// during its lock or slashed, the price and collateral of the lock are cleared.
+    pub async fn get_request_lock(
+        &self,
+        request_id: impl Into<U256>,
+    ) -> Result<RequestLock, MarketError> {
+        let request_id = request_id.into();
+        tracing::trace!("Calling requestLocks({:x})", request_id);
+        let lock = IRequestLocks::new(*self.instance.address(), self.instance.provider())
+            .requestLocks(request_id)
+            .call()
+            .await?;
+        Ok(RequestLock {
+            prover: lock.prover,
+            lockDeadline: lock.lockDeadline,
+            deadlineDelta: lock.deadlineDelta,
+            requestLockFlags: lock.requestLockFlags,
+            price: lock.price,
+            collateral: lock.collateral,
+            requestDigest: lock.requestDigest,
+        })
+    }
+
+    /// Returns the image ID and URL of the assessor guest.
+    pub async fn image_info(&self) -> Result<(B256, String)> {
+        tracing::trace!("Calling imageInfo()");
+        let (image_id, image_url) =
+            self.instance.imageInfo().call().await.context("call failed")?.into();
+
+        Ok((image_id, image_url))
+    }
 
+    /// Get the chain ID.
+    ///
+    /// This function implements caching to save the chain ID after the first successful fetch.
+    pub async fn get_chain_id(&self) -> Result<u64, MarketError> {
+        let mut id = self.chain_id.load(Ordering::Relaxed);
+        if id != 0 {
+            return Ok(id);
+        }
+        id = self.instance.provider().get_chain_id().await.context("failed to get chain ID")?;
+        self.chain_id.store(id, Ordering::Relaxed);
+        Ok(id)
+    }
+
+    /// Returns the deposited balance, in HP, of the given account.
+    pub async fn balance_of_collateral(
+        &self,
+        account: impl Into<Address>,
+    ) -> Result<U256, MarketError> {
+        self.balance_of_collateral_at(account, BlockId::latest()).await
+    }
+
+    /// Returns the deposited balance, in HP, of the given account as of the given block.
+    pub async fn balance_of_collateral_at(
+        &self,
+        account: impl Into<Address>,
+        block: impl Into<BlockId>,
+    ) -> Result<U256, MarketError> {
+        let account = account.into();
+        let block = block.into();
+        tracing::trace!("Calling balanceOfCollateral({}) at block {block}", account);
+        let balance = self
+            .instance
+            .balanceOfCollateral(account)
+            .call()
+            .block(block)
+            .await
+            .context("call failed")?;
         Ok(balance)
     }
 
+    /// Returns the collateral the given prover needs to lock the request, compared to the
+    /// collateral it has deposited.
+    ///
+    /// Note that collateral for requests already locked by the prover has been deducted from the
+    /// deposited balance, so it is not counted as available.
+    pub async fn collateral_requirement(
+        &self,
+        request: &ProofRequest,
+        prover: impl Into<Address>,
+    ) -> Result<CollateralRequirement, MarketError> {
+        let available = self.balance_of_collateral(prover).await?;
+        Ok(CollateralRequirement { required: request.offer.required_collateral(), available })
+    }
+
+    /// Check the current collateral balance against the alert config
+    /// and log a warning or error or below the thresholds.
+    async fn check_collateral_balance(&self) -> Result<(), MarketError> {
+        let collateral_balance = self.balance_of_collateral(self.caller()).await?;
+        let level = if collateral_balance
+            < self.balance_alert_config.error_threshold.unwrap_or(U256::ZERO)
+        {
+            tracing::error!(
+                "[B-BAL-STK] collateral balance {} for {} < error threshold",
+                collateral_balance,
+                self.caller(),
+            );
+            Some(AlertLevel::Error)
+        } else if collateral_balance
+            < self.balance_alert_config.warn_threshold.unwrap_or(U256::ZERO)
+        {
+            tracing::warn!(
+                "[B-BAL-STK] collateral balance {} for {} < warning threshold",
+                collateral_balance,
+                self.caller(),
+            );
+            Some(AlertLevel::Warn)
+        } else {
+            tracing::trace!("collateral balance for {} is: {}", self.caller(), collateral_balance);
+            None
+        };
+        if let Some(level) = level {
+            self.publish(BusEvent::BalanceAlert {
+                address: self.caller(),
+                asset: BalanceAsset::Collateral,
+                balance: collateral_balance,
+                level,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the collateral token address used by the market.
+    pub async fn collateral_token_address(&self) -> Result<Address, MarketError> {
+        tracing::trace!("Calling COLLATERAL_TOKEN_CONTRACT()");
+        let address = self
+            .instance
+            .COLLATERAL_TOKEN_CONTRACT()
+            .call()
+            .await
+            .context("COLLATERAL_TOKEN_CONTRACT call failed")?
+            .0;
+        Ok(address.into())
+    }
+
+    /// Returns the collateral token's symbol.
+    pub async fn collateral_token_symbol(&self) -> Result<String, MarketError> {
+        let address = self.collateral_token_address().await?;
+        let contract = IERC20::new(address, self.instance.provider());
+        let symbol = contract.symbol().call().await.context("Failed to get token symbol")?;
+        Ok(symbol)
+    }
+
+    /// Returns the collateral token's decimals.
+    pub async fn collateral_token_decimals(&self) -> Result<u8, MarketError> {
+        let address = self.collateral_token_address().await?;
+        let contract = IERC20::new(address, self.instance.provider());
+        let decimals = contract.decimals().call().await.context("Failed to get token decimals")?;
+        Ok(decimals)
+    }
+
+    /// Returns the amount of collateral tokens the market is allowed to transfer on behalf of
+    /// `owner`.
+    pub async fn collateral_allowance(&self, owner: Address) -> Result<U256, MarketError> {
+        let address = self.collateral_token_address().await?;
+        let contract = IERC20::new(address, self.instance.provider());
+        let spender = *self.instance.address();
+        tracing::trace!("Calling allowance({:?}, {:?})", owner, spender);
+        let allowance = contract
+            .allowance(owner, spender)
+            .call()
+            .await
+            .context("Failed to get collateral token allowance")?;
+        Ok(allowance)
+    }
+}
+
+impl<P: Provider> BoundlessMarketService<P> {
     /// Submit a request such that it is publicly available for provers to evaluate and bid
     /// on. Includes the specified value, which will be deposited to the account of msg.sender.
     pub async fn submit_request_with_value(
@@ -351,7 +767,7 @@ impl<P: Provider> BoundlessMarketService<P> {
             request.id
         );
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let receipt = self.get_receipt_with_retry(pending_tx, None).await?;
 
         // Look for the logs for submitting the transaction.
         match extract_tx_log::<IBoundlessMarket::RequestSubmitted>(&receipt) {
@@ -377,7 +793,7 @@ impl<P: Provider> BoundlessMarketService<P> {
             request.id
         );
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let receipt = self.get_receipt_with_retry(pending_tx, None).await?;
 
         // Look for the logs for submitting the transaction.
         match extract_tx_log::<IBoundlessMarket::RequestSubmitted>(&receipt) {
@@ -386,6 +802,27 @@ impl<P: Provider> BoundlessMarketService<P> {
         }
     }
 
+    /// Submit a request such that it is publicly available for provers to evaluate and bid
+    /// on, paid for from the funds already deposited to the client account.
+    ///
+    /// Unlike [BoundlessMarketService::submit_request], no funds are sent with the transaction.
+    /// Returns [MarketError::InsufficientBalance] if the balance of the client account does not
+    /// cover the max price on the offer.
+    pub async fn submit_request_from_balance(
+        &self,
+        request: &ProofRequest,
+        signer: &impl Signer,
+    ) -> Result<U256, MarketError> {
+        let balance = self
+            .balance_of(signer.address())
+            .await
+            .context("failed to get whether the client balance can cover the offer max price")?;
+        if balance < request.offer.maxPrice {
+            return Err(MarketError::InsufficientBalance(balance, request.offer.maxPrice));
+        }
+        self.submit_request_with_value(request, signer, U256::ZERO).await
+    }
+
     /// Submit a request such that it is publicly available for provers to evaluate and bid
     /// on. Deposits funds to the client account if there are not enough to cover the max price on
     /// the offer.
@@ -450,7 +887,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         let tx_hash = *pending_tx.tx_hash();
         tracing::trace!("Broadcasting lock request tx {}", tx_hash);
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let receipt = self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Lock)).await?;
 
         if !receipt.status() {
             // TODO: Get + print revertReason
@@ -505,7 +942,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         let pending_tx = call.send().await.context("Failed to lock")?;
         tracing::trace!("Broadcasting lock request with signature tx {}", pending_tx.tx_hash());
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let receipt = self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Lock)).await?;
         if !receipt.status() {
             // TODO: Get + print revertReason
             return Err(MarketError::LockRevert(receipt.transaction_hash));
@@ -520,9 +957,25 @@ impl<P: Provider> BoundlessMarketService<P> {
         Ok(receipt.block_number.context("TXN Receipt missing block number")?)
     }
 
+    /// Estimate the gas used by [BoundlessMarketService::lock_request] for the given request.
+    ///
+    /// The estimate is made with `eth_estimateGas` against the latest state, using the same
+    /// calldata and sender as the lock transaction. An error is returned if the call would revert,
+    /// e.g. because the request is already locked or the caller has insufficient collateral.
+    pub async fn estimate_lock_gas(
+        &self,
+        request: &ProofRequest,
+        client_sig: impl Into<Bytes>,
+    ) -> Result<u64, MarketError> {
+        let call = self.instance.lockRequest(request.clone(), client_sig.into()).from(self.caller);
+        tracing::trace!("Estimating gas for lockRequest({:x})", request.id);
+        Ok(call.estimate_gas().await?)
+    }
+
     async fn get_receipt_with_retry(
         &self,
         pending_tx: PendingTransactionBuilder<Ethereum>,
+        tx_type: Option<MarketTxType>,
     ) -> Result<TransactionReceipt, MarketError> {
         let tx_hash = *pending_tx.tx_hash();
 
@@ -539,8 +992,12 @@ impl<P: Provider> BoundlessMarketService<P> {
             );
         }
 
-        match pending_tx.with_timeout(Some(self.timeout)).get_receipt().await {
-            Ok(receipt) => Ok(receipt),
+        let (pending_tx, policy) = self.with_policy(pending_tx, tx_type);
+        match pending_tx.get_receipt().await {
+            Ok(receipt) => {
+                self.publish(BusEvent::TxConfirmed { tx_hash });
+                Ok(receipt)
+            }
             Err(PendingTransactionError::TransportError(err)) if err.is_null_resp() => {
                 tracing::debug!("failed to query receipt of confirmed transaction, retrying");
                 // There is a race condition with some providers where a transaction will be
@@ -550,6 +1007,7 @@ impl<P: Provider> BoundlessMarketService<P> {
                     if let Ok(Some(receipt)) =
                         self.instance.provider().get_transaction_receipt(tx_hash).await
                     {
+                        self.publish(BusEvent::TxConfirmed { tx_hash });
                         return Ok(receipt);
                     }
 
@@ -566,7 +1024,7 @@ impl<P: Provider> BoundlessMarketService<P> {
             Err(e) => Err(MarketError::TxnConfirmationError(anyhow!(
                 "failed to confirm tx {:?} within timeout {:?}: {}",
                 tx_hash,
-                self.timeout,
+                policy.timeout,
                 e
             ))),
         }
@@ -576,18 +1034,40 @@ impl<P: Provider> BoundlessMarketService<P> {
     /// the associated prover collateral.
     pub async fn slash(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
     ) -> Result<IBoundlessMarket::ProverSlashed, MarketError> {
+        let request_id = request_id.into();
         if self.is_slashed(request_id).await? {
             return Err(MarketError::RequestIsSlashed(request_id));
         }
 
+        let pending_tx = self.send_slash(request_id).await?;
+        self.confirm_slash(pending_tx).await
+    }
+
+    /// Broadcast a transaction slashing the given request, without waiting for it to confirm.
+    ///
+    /// This allows the caller to record the transaction hash before waiting for the outcome with
+    /// [BoundlessMarketService::confirm_slash]. Unlike [BoundlessMarketService::slash], this does
+    /// not check whether the request is already slashed.
+    pub async fn send_slash(
+        &self,
+        request_id: impl Into<U256>,
+    ) -> Result<PendingTransactionBuilder<Ethereum>, MarketError> {
+        let request_id = request_id.into();
         tracing::trace!("Calling slash({:x?})", request_id);
         let call = self.instance.slash(request_id).from(self.caller);
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
+        Ok(pending_tx)
+    }
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+    /// Wait for a slash transaction sent by [BoundlessMarketService::send_slash] to confirm.
+    pub async fn confirm_slash(
+        &self,
+        pending_tx: PendingTransactionBuilder<Ethereum>,
+    ) -> Result<IBoundlessMarket::ProverSlashed, MarketError> {
+        let receipt = self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Slash)).await?;
 
         if !receipt.status() {
             return Err(MarketError::SlashRevert(receipt.transaction_hash));
@@ -599,8 +1079,66 @@ impl<P: Provider> BoundlessMarketService<P> {
         }
     }
 
+    /// Reclaim the funds escrowed for a request that expired unfulfilled.
+    ///
+    /// Payment is only escrowed once a request is locked, when the price is moved out of the
+    /// client's balance. If the request then expires unfulfilled, slashing the prover credits the
+    /// price back to the client's market balance, from which it can be withdrawn. This sends that
+    /// slash unless it was already sent, e.g. by a slasher. Requests that were never locked hold
+    /// no escrow, as their funds never left the client's balance.
+    ///
+    /// Returns [MarketError::RequestNotExpired] if the request is locked and not yet expired.
+    pub async fn reclaim_expired(
+        &self,
+        request_id: impl Into<U256>,
+    ) -> Result<ReclaimOutcome, MarketError> {
+        let request_id = request_id.into();
+        if self.is_slashed(request_id).await? {
+            return Ok(ReclaimOutcome::AlreadySlashed);
+        }
+        if !self.is_locked(request_id).await? {
+            return Ok(match self.is_fulfilled(request_id).await? {
+                true => ReclaimOutcome::Fulfilled,
+                false => ReclaimOutcome::NotLocked,
+            });
+        }
+
+        let deadline = self.instance.requestDeadline(request_id).call().await?;
+        if self.get_block_timestamp(BlockId::latest()).await? <= deadline {
+            return Err(MarketError::RequestNotExpired(request_id, deadline));
+        }
+        // Only requests fulfilled after the lock deadline can be slashed, and the client is not
+        // refunded for those, so check that the slash goes through before sending it.
+        if self.is_fulfilled(request_id).await?
+            && self.instance.slash(request_id).from(self.caller).call().await.is_err()
+        {
+            return Ok(ReclaimOutcome::Fulfilled);
+        }
+
+        let pending_tx = self.send_slash(request_id).await?;
+        let tx_hash = *pending_tx.tx_hash();
+        let event = self.confirm_slash(pending_tx).await?;
+        Ok(ReclaimOutcome::Slashed {
+            tx_hash,
+            refunded: event.collateralRecipient == *self.instance.address(),
+            event,
+        })
+    }
+
     /// Submits a `FulfillmentTx`.
     pub async fn fulfill(&self, tx: FulfillmentTx) -> Result<(), MarketError> {
+        self.fulfill_with_receipt(tx).await.map(|_| ())
+    }
+
+    /// Submits a `FulfillmentTx`, returning the receipt of the confirmed transaction.
+    ///
+    /// The receipt carries the gas used and the effective gas price, which can be passed to
+    /// [Telemetry::record_fulfillment](crate::telemetry::Telemetry::record_fulfillment) to
+    /// account for the cost of the fulfillment.
+    pub async fn fulfill_with_receipt(
+        &self,
+        tx: FulfillmentTx,
+    ) -> Result<TransactionReceipt, MarketError> {
         let FulfillmentTx { root, unlocked_requests, fulfillments, assessor_receipt, withdraw } =
             tx;
         let price = !unlocked_requests.is_empty();
@@ -653,26 +1191,156 @@ impl<P: Provider> BoundlessMarketService<P> {
         }
     }
 
-    /// Fulfill a batch of requests by delivering the proof for each application.
+    /// Submits a `FulfillmentTx`, returning the outcome of each fulfillment in the batch.
     ///
-    /// See [BoundlessMarketService::fulfill] for more details.
-    async fn _fulfill(
+    /// A batch fulfillment transaction succeeds even when some of its fulfillments do not result
+    /// in payment, e.g. because the request expired or is locked by another prover. The outcomes
+    /// are decoded from the events emitted by the market, so that such partial failures can be
+    /// handled per request.
+    pub async fn fulfill_with_outcomes(
         &self,
-        fulfillments: Vec<Fulfillment>,
-        assessor_fill: AssessorReceipt,
-    ) -> Result<(), MarketError> {
-        let fill_ids = fulfillments.iter().map(|fill| fill.id).collect::<Vec<_>>();
-        tracing::trace!("Calling fulfill({fulfillments:?}, {assessor_fill:?})");
-        let call = self.instance.fulfill(fulfillments, assessor_fill).from(self.caller);
-        tracing::trace!("Calldata: {:x}", call.calldata());
-        let pending_tx = call.send().await?;
-        tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
+        tx: FulfillmentTx,
+    ) -> Result<BatchFulfillment, MarketError> {
+        let receipt = self.fulfill_with_receipt(tx).await?;
+        let batch = BatchFulfillment::from_receipt(*self.instance.address(), receipt);
+        for (request_id, failure) in batch.failed() {
+            tracing::warn!("Fulfillment of request 0x{request_id:x} was not paid: {failure}");
+        }
+        Ok(batch)
+    }
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+    /// Estimate the gas used by [BoundlessMarketService::fulfill] for the given `FulfillmentTx`.
+    ///
+    /// The estimate is made with `eth_estimateGas` against the latest state, calling the same
+    /// market function with the same calldata and sender as [BoundlessMarketService::fulfill]
+    /// would. An error is returned if the call would revert.
+    pub async fn estimate_fulfill_gas(&self, tx: &FulfillmentTx) -> Result<u64, MarketError> {
+        let FulfillmentTx { root, unlocked_requests, fulfillments, assessor_receipt, withdraw } =
+            tx.clone();
+        let (requests, client_sigs): (Vec<_>, Vec<_>) =
+            unlocked_requests.into_iter().map(|ur| (ur.request, ur.client_sig)).unzip();
+        let price = !requests.is_empty();
+        let instance = &self.instance;
+        tracing::trace!("Estimating gas for fulfillment of {} requests", fulfillments.len());
+
+        let gas = match root {
+            None => match (price, withdraw) {
+                (false, false) => {
+                    instance
+                        .fulfill(fulfillments, assessor_receipt)
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+                (false, true) => {
+                    instance
+                        .fulfillAndWithdraw(fulfillments, assessor_receipt)
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+                (true, false) => {
+                    instance
+                        .priceAndFulfill(requests, client_sigs, fulfillments, assessor_receipt)
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+                (true, true) => {
+                    instance
+                        .priceAndFulfillAndWithdraw(
+                            requests,
+                            client_sigs,
+                            fulfillments,
+                            assessor_receipt,
+                        )
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+            },
+            Some(root) => match (price, withdraw) {
+                (false, false) => {
+                    instance
+                        .submitRootAndFulfill(
+                            root.verifier_address,
+                            root.root,
+                            root.seal,
+                            fulfillments,
+                            assessor_receipt,
+                        )
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+                (false, true) => {
+                    instance
+                        .submitRootAndFulfillAndWithdraw(
+                            root.verifier_address,
+                            root.root,
+                            root.seal,
+                            fulfillments,
+                            assessor_receipt,
+                        )
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+                (true, false) => {
+                    instance
+                        .submitRootAndPriceAndFulfill(
+                            root.verifier_address,
+                            root.root,
+                            root.seal,
+                            requests,
+                            client_sigs,
+                            fulfillments,
+                            assessor_receipt,
+                        )
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+                (true, true) => {
+                    instance
+                        .submitRootAndPriceAndFulfillAndWithdraw(
+                            root.verifier_address,
+                            root.root,
+                            root.seal,
+                            requests,
+                            client_sigs,
+                            fulfillments,
+                            assessor_receipt,
+                        )
+                        .from(self.caller)
+                        .estimate_gas()
+                        .await?
+                }
+            },
+        };
+        Ok(gas)
+    }
+
+    /// Fulfill a batch of requests by delivering the proof for each application.
+    ///
+    /// See [BoundlessMarketService::fulfill] for more details.
+    async fn _fulfill(
+        &self,
+        fulfillments: Vec<Fulfillment>,
+        assessor_fill: AssessorReceipt,
+    ) -> Result<TransactionReceipt, MarketError> {
+        let fill_ids = fulfillments.iter().map(|fill| fill.id).collect::<Vec<_>>();
+        tracing::trace!("Calling fulfill({fulfillments:?}, {assessor_fill:?})");
+        let call = self.instance.fulfill(fulfillments, assessor_fill).from(self.caller);
+        tracing::trace!("Calldata: {:x}", call.calldata());
+        let pending_tx = call.send().await?;
+        tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
+
+        let receipt = self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Fulfill)).await?;
 
         tracing::info!("Submitted proof for batch {:?}: {}", fill_ids, receipt.transaction_hash);
 
-        Ok(())
+        Ok(receipt)
     }
 
     /// Fulfill a batch of requests by delivering the proof for each application and withdraw from the prover balance.
@@ -682,7 +1350,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         &self,
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         let fill_ids = fulfillments.iter().map(|fill| fill.id).collect::<Vec<_>>();
         tracing::trace!("Calling fulfillAndWithdraw({fulfillments:?}, {assessor_fill:?})");
         let call = self.instance.fulfillAndWithdraw(fulfillments, assessor_fill).from(self.caller);
@@ -690,11 +1358,11 @@ impl<P: Provider> BoundlessMarketService<P> {
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
 
-        let receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let receipt = self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Fulfill)).await?;
 
         tracing::info!("Submitted proof for batch {:?}: {}", fill_ids, receipt.transaction_hash);
 
-        Ok(())
+        Ok(receipt)
     }
 
     /// Combined function to submit a new merkle root to the set-verifier and call `fulfill`.
@@ -704,7 +1372,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         root: Root,
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         tracing::trace!(
             "Calling submitRootAndFulfill({:?}, {:x}, {fulfillments:?}, {assessor_fill:?})",
             root.root,
@@ -723,11 +1391,12 @@ impl<P: Provider> BoundlessMarketService<P> {
         tracing::trace!("Calldata: {}", call.calldata());
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
-        let tx_receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let tx_receipt =
+            self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Fulfill)).await?;
 
         tracing::info!("Submitted merkle root and proof for batch {}", tx_receipt.transaction_hash);
 
-        Ok(())
+        Ok(tx_receipt)
     }
 
     /// Combined function to submit a new merkle root to the set-verifier and call `fulfillAndWithdraw`.
@@ -737,7 +1406,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         root: Root,
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         tracing::trace!("Calling submitRootAndFulfillAndWithdraw({:?}, {:x}, {fulfillments:?}, {assessor_fill:?})", root.root, root.seal);
         let call = self
             .instance
@@ -752,11 +1421,12 @@ impl<P: Provider> BoundlessMarketService<P> {
         tracing::trace!("Calldata: {}", call.calldata());
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
-        let tx_receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let tx_receipt =
+            self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Fulfill)).await?;
 
         tracing::info!("Submitted merkle root and proof for batch {}", tx_receipt.transaction_hash);
 
-        Ok(())
+        Ok(tx_receipt)
     }
 
     /// A combined call to `IBoundlessMarket.priceRequest` and `IBoundlessMarket.fulfill`.
@@ -768,7 +1438,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
         priority_gas: Option<u64>,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         tracing::trace!("Calling priceAndFulfill({fulfillments:?}, {assessor_fill:?})");
 
         let (requests, client_sigs): (Vec<_>, Vec<_>) =
@@ -795,11 +1465,12 @@ impl<P: Provider> BoundlessMarketService<P> {
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
 
-        let tx_receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let tx_receipt =
+            self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Fulfill)).await?;
 
         tracing::info!("Fulfilled proof for batch {}", tx_receipt.transaction_hash);
 
-        Ok(())
+        Ok(tx_receipt)
     }
 
     /// A combined call to `IBoundlessMarket.priceRequest` and `IBoundlessMarket.fulfillAndWithdraw`.
@@ -811,7 +1482,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
         priority_gas: Option<u64>,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         tracing::trace!("Calling priceAndFulfillAndWithdraw({fulfillments:?}, {assessor_fill:?})");
 
         let (requests, client_sigs): (Vec<_>, Vec<_>) =
@@ -838,11 +1509,12 @@ impl<P: Provider> BoundlessMarketService<P> {
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
 
-        let tx_receipt = self.get_receipt_with_retry(pending_tx).await?;
+        let tx_receipt =
+            self.get_receipt_with_retry(pending_tx, Some(MarketTxType::Fulfill)).await?;
 
         tracing::info!("Fulfilled proof for batch {}", tx_receipt.transaction_hash);
 
-        Ok(())
+        Ok(tx_receipt)
     }
 
     /// Combined function to submit a new merkle root to the set-verifier and call `priceAndfulfill`.
@@ -853,7 +1525,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         unlocked_requests: Vec<UnlockedRequest>,
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         let (requests, client_sigs): (Vec<_>, Vec<_>) =
             unlocked_requests.into_iter().map(|ur| (ur.request, ur.client_sig)).unzip();
         tracing::trace!("Calling submitRootAndPriceAndFulfill({:?}, {:x}, {:?}, {:?}, {fulfillments:?}, {assessor_fill:?})", root.root, root.seal, requests, client_sigs);
@@ -872,16 +1544,18 @@ impl<P: Provider> BoundlessMarketService<P> {
         tracing::trace!("Calldata: {}", call.calldata());
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
-        let tx_receipt = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_receipt = self
+            .with_policy(pending_tx, Some(MarketTxType::Fulfill))
+            .0
             .get_receipt()
             .await
             .context("failed to confirm tx")
             .map_err(MarketError::TxnConfirmationError)?;
+        self.publish(BusEvent::TxConfirmed { tx_hash: tx_receipt.transaction_hash });
 
         tracing::info!("Submitted merkle root and proof for batch {}", tx_receipt.transaction_hash);
 
-        Ok(())
+        Ok(tx_receipt)
     }
 
     /// Combined function to submit a new merkle root to the set-verifier and call `priceAndFulfillAndWithdraw`.
@@ -892,7 +1566,7 @@ impl<P: Provider> BoundlessMarketService<P> {
         unlocked_requests: Vec<UnlockedRequest>,
         fulfillments: Vec<Fulfillment>,
         assessor_fill: AssessorReceipt,
-    ) -> Result<(), MarketError> {
+    ) -> Result<TransactionReceipt, MarketError> {
         let (requests, client_sigs): (Vec<_>, Vec<_>) =
             unlocked_requests.into_iter().map(|ur| (ur.request, ur.client_sig)).unzip();
         tracing::trace!("Calling submitRootAndPriceAndFulfillAndWithdraw({:?}, {:x}, {:?}, {:?}, {fulfillments:?}, {assessor_fill:?})", root.root, root.seal, requests, client_sigs);
@@ -911,40 +1585,18 @@ impl<P: Provider> BoundlessMarketService<P> {
         tracing::trace!("Calldata: {}", call.calldata());
         let pending_tx = call.send().await?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
-        let tx_receipt = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_receipt = self
+            .with_policy(pending_tx, Some(MarketTxType::Fulfill))
+            .0
             .get_receipt()
             .await
             .context("failed to confirm tx")
             .map_err(MarketError::TxnConfirmationError)?;
+        self.publish(BusEvent::TxConfirmed { tx_hash: tx_receipt.transaction_hash });
 
         tracing::info!("Submitted merkle root and proof for batch {}", tx_receipt.transaction_hash);
 
-        Ok(())
-    }
-
-    /// Checks if a request is locked in.
-    pub async fn is_locked(&self, request_id: U256) -> Result<bool, MarketError> {
-        tracing::trace!("Calling requestIsLocked({:x})", request_id);
-        let res = self.instance.requestIsLocked(request_id).call().await?;
-
-        Ok(res)
-    }
-
-    /// Checks if a request is fulfilled.
-    pub async fn is_fulfilled(&self, request_id: U256) -> Result<bool, MarketError> {
-        tracing::trace!("Calling requestIsFulfilled({:x})", request_id);
-        let res = self.instance.requestIsFulfilled(request_id).call().await?;
-
-        Ok(res)
-    }
-
-    /// Checks if a request is slashed.
-    pub async fn is_slashed(&self, request_id: U256) -> Result<bool, MarketError> {
-        tracing::trace!("Calling requestIsSlashed({:x})", request_id);
-        let res = self.instance.requestIsSlashed(request_id).call().await?;
-
-        Ok(res)
+        Ok(tx_receipt)
     }
 
     /// Returns the [RequestStatus] of a request.
@@ -952,12 +1604,33 @@ impl<P: Provider> BoundlessMarketService<P> {
     /// The `expires_at` parameter is the time at which the request expires.
     pub async fn get_status(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
         expires_at: Option<u64>,
     ) -> Result<RequestStatus, MarketError> {
-        let timestamp = self.get_latest_block_timestamp().await?;
+        let request_id = request_id.into();
+        self.get_status_at(request_id, expires_at, BlockId::latest()).await
+    }
 
-        if self.is_fulfilled(request_id).await.context("Failed to check fulfillment status")? {
+    /// Returns the [RequestStatus] of a request as of the given block.
+    ///
+    /// The state of the market and the time used to check expiry are both those of the given
+    /// block, so this reconstructs the status that [Self::get_status] would have returned then.
+    /// The `expires_at` parameter is the time at which the request expires.
+    pub async fn get_status_at(
+        &self,
+        request_id: impl Into<U256>,
+        expires_at: Option<u64>,
+        block: impl Into<BlockId>,
+    ) -> Result<RequestStatus, MarketError> {
+        let request_id = request_id.into();
+        let block = block.into();
+        let timestamp = self.get_block_timestamp(block).await?;
+
+        if self
+            .is_fulfilled_at(request_id, block)
+            .await
+            .context("Failed to check fulfillment status")?
+        {
             return Ok(RequestStatus::Fulfilled);
         }
 
@@ -967,8 +1640,8 @@ impl<P: Provider> BoundlessMarketService<P> {
             }
         }
 
-        if self.is_locked(request_id).await.context("Failed to check locked status")? {
-            let deadline = self.instance.requestDeadline(request_id).call().await?;
+        if self.is_locked_at(request_id, block).await.context("Failed to check locked status")? {
+            let deadline = self.instance.requestDeadline(request_id).call().block(block).await?;
             if timestamp > deadline && deadline > 0 {
                 return Ok(RequestStatus::Expired);
             };
@@ -987,14 +1660,14 @@ impl<P: Provider> BoundlessMarketService<P> {
             .context("Failed to get latest block number")?)
     }
 
-    async fn get_latest_block_timestamp(&self) -> Result<u64, MarketError> {
+    async fn get_block_timestamp(&self, block: BlockId) -> Result<u64, MarketError> {
         let block = self
             .instance
             .provider()
-            .get_block_by_number(BlockNumberOrTag::Latest)
+            .get_block(block)
             .await
             .context("failed to get block")?
-            .context("failed to get block")?;
+            .with_context(|| format!("block {block} not found"))?;
         Ok(block.header.timestamp())
     }
 
@@ -1011,42 +1684,42 @@ impl<P: Provider> BoundlessMarketService<P> {
         lower_bound: Option<u64>,
         upper_bound: Option<u64>,
     ) -> Result<ProofDelivered, MarketError> {
-        let mut upper_block = upper_bound.unwrap_or(self.get_latest_block_number().await?);
+        Ok(self.query_fulfilled_event_with_log(request_id, lower_bound, upper_bound).await?.0)
+    }
+
+    /// Query the ProofDelivered event, along with the log that emitted it.
+    ///
+    /// See [Self::query_fulfilled_event] for the search range.
+    async fn query_fulfilled_event_with_log(
+        &self,
+        request_id: U256,
+        lower_bound: Option<u64>,
+        upper_bound: Option<u64>,
+    ) -> Result<(ProofDelivered, Log), MarketError> {
+        let upper_block = upper_bound.unwrap_or(self.get_latest_block_number().await?);
         let start_block = lower_bound.unwrap_or(upper_block.saturating_sub(
             self.event_query_config.block_range * self.event_query_config.max_iterations,
         ));
 
-        // Loop to progressively search through blocks
-        for _ in 0..self.event_query_config.max_iterations {
-            // If the current end block is less than or equal to the starting block, stop searching
-            if upper_block <= start_block {
-                break;
-            }
-
-            // Calculate the block range to query: from [lower_block] to [upper_block]
-            let lower_block = upper_block.saturating_sub(self.event_query_config.block_range);
-
-            // Set up the event filter for the specified block range
-            let mut event_filter = self.instance.ProofDelivered_filter();
-            event_filter.filter = event_filter
-                .filter
-                .topic1(request_id)
-                .from_block(lower_block)
-                .to_block(upper_block);
-
-            // Query the logs for the event
-            let logs = event_filter.query().await?;
-
-            if let Some((event, _)) = logs.first() {
-                return Ok(event.clone());
-            }
-
-            // Move the upper_block down for the next iteration
-            upper_block = lower_block.saturating_sub(1);
-        }
+        let mut event_filter = self.instance.ProofDelivered_filter();
+        event_filter.filter = event_filter.filter.topic1(request_id);
+
+        // Search backwards from the upper block, stopping at the first match.
+        let event = ChunkedLogQuery::new(self.event_query_config.block_range + 1)
+            .with_direction(ScanDirection::Backward)
+            .scan(
+                self.instance.provider(),
+                event_filter.filter,
+                start_block..=upper_block,
+                |logs: Vec<(ProofDelivered, Log)>, _| match logs.into_iter().next() {
+                    Some(event) => ControlFlow::Break(event),
+                    None => ControlFlow::Continue(()),
+                },
+            )
+            .await?;
 
-        // Return error if no logs are found after all iterations
-        Err(MarketError::ProofNotFound(request_id))
+        // Return error if no logs are found in the range
+        event.ok_or(MarketError::ProofNotFound(request_id))
     }
 
     /// Query the RequestSubmitted event based on request ID and block options.
@@ -1063,49 +1736,151 @@ impl<P: Provider> BoundlessMarketService<P> {
         lower_bound: Option<u64>,
         upper_bound: Option<u64>,
     ) -> Result<(ProofRequest, Bytes), MarketError> {
-        let mut upper_block = upper_bound.unwrap_or(self.get_latest_block_number().await?);
+        let upper_block = upper_bound.unwrap_or(self.get_latest_block_number().await?);
         let start_block = lower_bound.unwrap_or(upper_block.saturating_sub(
             self.event_query_config.block_range * self.event_query_config.max_iterations,
         ));
 
-        // Loop to progressively search through blocks
-        for _ in 0..self.event_query_config.max_iterations {
-            // If the current end block is less than or equal to the starting block, stop searching
-            if upper_block <= start_block {
-                break;
-            }
+        let mut event_filter = self.instance.RequestSubmitted_filter();
+        event_filter.filter = event_filter.filter.topic1(request_id);
+
+        // Search backwards from the upper block, stopping at the first match.
+        let event = ChunkedLogQuery::new(self.event_query_config.block_range + 1)
+            .with_direction(ScanDirection::Backward)
+            .scan(
+                self.instance.provider(),
+                event_filter.filter,
+                start_block..=upper_block,
+                |logs: Vec<(IBoundlessMarket::RequestSubmitted, Log)>, _| match logs
+                    .into_iter()
+                    .next()
+                {
+                    Some((event, _)) => ControlFlow::Break(event),
+                    None => ControlFlow::Continue(()),
+                },
+            )
+            .await?;
 
-            // Calculate the block range to query: from [lower_block] to [upper_block]
-            let lower_block = upper_block.saturating_sub(self.event_query_config.block_range);
+        // Return error if no logs are found in the range
+        let event = event.ok_or(MarketError::RequestNotFound(request_id))?;
+        Ok((event.request, event.clientSignature))
+    }
 
-            // Set up the event filter for the specified block range
-            let mut event_filter = self.instance.RequestSubmitted_filter();
-            event_filter.filter = event_filter
-                .filter
-                .topic1(request_id)
-                .from_block(lower_block)
-                .to_block(upper_block);
+    /// Returns the proof request whose EIP-712 digest is `request_digest`, along with the
+    /// transaction that carried it.
+    ///
+    /// Digests appear in fulfillments and logs, but are not indexed by the market events, so the
+    /// `RequestSubmitted` events are scanned backwards from `upper_bound`, or the latest block,
+    /// down to `lower_bound`, or the range configured by the [EventQueryConfig]. Requests
+    /// submitted offchain are only found once locked, by scanning the `RequestLocked` events.
+    pub async fn get_request_by_digest(
+        &self,
+        request_digest: B256,
+        lower_bound: Option<u64>,
+        upper_bound: Option<u64>,
+    ) -> Result<LocatedRequest, MarketError> {
+        let upper_block = upper_bound.unwrap_or(self.get_latest_block_number().await?);
+        let start_block = lower_bound.unwrap_or(upper_block.saturating_sub(
+            self.event_query_config.block_range * self.event_query_config.max_iterations,
+        ));
+        let separator = self.eip712_domain_separator().await?;
+        let located = |request: ProofRequest, client_signature: Bytes, source, log: Log| {
+            (request.signing_hash_with_separator(separator) == request_digest).then(|| {
+                LocatedRequest {
+                    request,
+                    client_signature,
+                    source,
+                    tx_hash: log.transaction_hash.unwrap_or_default(),
+                    block_number: log.block_number.unwrap_or_default(),
+                }
+            })
+        };
+        let query = ChunkedLogQuery::new(self.event_query_config.block_range + 1)
+            .with_direction(ScanDirection::Backward);
+
+        let submitted = query
+            .scan(
+                self.instance.provider(),
+                self.instance.RequestSubmitted_filter().filter,
+                start_block..=upper_block,
+                |logs: Vec<(IBoundlessMarket::RequestSubmitted, Log)>, _| match logs
+                    .into_iter()
+                    .rev()
+                    .find_map(|(event, log)| {
+                        located(event.request, event.clientSignature, RequestSource::Submitted, log)
+                    }) {
+                    Some(found) => ControlFlow::Break(found),
+                    None => ControlFlow::Continue(()),
+                },
+            )
+            .await?;
+        if let Some(found) = submitted {
+            return Ok(found);
+        }
 
-            // Query the logs for the event
-            let logs = event_filter.query().await?;
+        let locked = query
+            .scan(
+                self.instance.provider(),
+                self.instance.RequestLocked_filter().filter,
+                start_block..=upper_block,
+                |logs: Vec<(IBoundlessMarket::RequestLocked, Log)>, _| match logs
+                    .into_iter()
+                    .rev()
+                    .find_map(|(event, log)| {
+                        located(event.request, event.clientSignature, RequestSource::Locked, log)
+                    }) {
+                    Some(found) => ControlFlow::Break(found),
+                    None => ControlFlow::Continue(()),
+                },
+            )
+            .await?;
+        locked.ok_or(MarketError::RequestDigestNotFound(request_digest))
+    }
 
-            if let Some((event, _)) = logs.first() {
-                return Ok((event.request.clone(), event.clientSignature.clone()));
-            }
+    /// Returns the address of the prover that locked the request.
+    ///
+    /// The RequestLocked event is searched backwards from the latest block, over the range
+    /// configured by the [EventQueryConfig].
+    pub async fn get_locking_prover(
+        &self,
+        request_id: impl Into<U256>,
+    ) -> Result<Address, MarketError> {
+        let request_id = request_id.into();
+        let upper_block = self.get_latest_block_number().await?;
+        let start_block = upper_block.saturating_sub(
+            self.event_query_config.block_range * self.event_query_config.max_iterations,
+        );
 
-            // Move the upper_block down for the next iteration
-            upper_block = lower_block.saturating_sub(1);
-        }
+        let mut event_filter = self.instance.RequestLocked_filter();
+        event_filter.filter = event_filter.filter.topic1(request_id);
+
+        // Search backwards from the upper block, stopping at the first match.
+        let event = ChunkedLogQuery::new(self.event_query_config.block_range + 1)
+            .with_direction(ScanDirection::Backward)
+            .scan(
+                self.instance.provider(),
+                event_filter.filter,
+                start_block..=upper_block,
+                |logs: Vec<(IBoundlessMarket::RequestLocked, Log)>, _| match logs.into_iter().next()
+                {
+                    Some((event, _)) => ControlFlow::Break(event),
+                    None => ControlFlow::Continue(()),
+                },
+            )
+            .await?;
 
-        // Return error if no logs are found after all iterations
-        Err(MarketError::RequestNotFound(request_id))
+        let event = event.ok_or_else(|| {
+            MarketError::Error(anyhow!("lock of request 0x{request_id:x} not found in event logs"))
+        })?;
+        Ok(event.prover)
     }
 
     /// Returns fulfillment data and seal if the request is fulfilled.
     pub async fn get_request_fulfillment(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
     ) -> Result<Fulfillment, MarketError> {
+        let request_id = request_id.into();
         match self.get_status(request_id, None).await? {
             RequestStatus::Expired => Err(MarketError::RequestHasExpired(request_id)),
             RequestStatus::Fulfilled => {
@@ -1119,8 +1894,9 @@ impl<P: Provider> BoundlessMarketService<P> {
     /// Returns the prover address for a request that is fulfilled.
     pub async fn get_request_fulfillment_prover(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
     ) -> Result<Address, MarketError> {
+        let request_id = request_id.into();
         match self.get_status(request_id, None).await? {
             RequestStatus::Expired => Err(MarketError::RequestHasExpired(request_id)),
             RequestStatus::Fulfilled => {
@@ -1134,44 +1910,167 @@ impl<P: Provider> BoundlessMarketService<P> {
     /// Returns proof request and signature for a request submitted onchain.
     pub async fn get_submitted_request(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
         tx_hash: Option<B256>,
     ) -> Result<(ProofRequest, Bytes), MarketError> {
+        let request_id = request_id.into();
         if let Some(tx_hash) = tx_hash {
-            let tx_data = self
-                .instance
-                .provider()
-                .get_transaction_by_hash(tx_hash)
-                .await
-                .context("Failed to get transaction")?
-                .context("Transaction not found")?;
-            let inputs = tx_data.input();
-            // TODO: This should parse from events rather than calldata.
-            let calldata = IBoundlessMarket::submitRequestCall::abi_decode(inputs)
-                .context("Failed to decode input")?;
-            return Ok((calldata.request, calldata.clientSignature));
+            return self.submitted_request_in_tx(tx_hash, Some(request_id)).await;
         }
         self.query_request_submitted_event(request_id, None, None).await
     }
 
+    /// Returns the proof request and signature submitted onchain in the given transaction.
+    ///
+    /// The request is decoded from the `RequestSubmitted` event emitted by the market in the
+    /// transaction receipt, which also covers submissions made through another contract. If the
+    /// receipt has no such event, the transaction calldata is decoded as a direct call to
+    /// `submitRequest`.
+    ///
+    /// If the transaction submitted several requests, the first one is returned. Use
+    /// [Self::get_submitted_request] to select a request by ID.
+    pub async fn get_submitted_request_by_tx(
+        &self,
+        tx_hash: B256,
+    ) -> Result<(ProofRequest, Bytes), MarketError> {
+        self.submitted_request_in_tx(tx_hash, None).await
+    }
+
+    /// Returns the request submitted in the given transaction, matching `request_id` if given.
+    async fn submitted_request_in_tx(
+        &self,
+        tx_hash: B256,
+        request_id: Option<U256>,
+    ) -> Result<(ProofRequest, Bytes), MarketError> {
+        let provider = self.instance.provider();
+        let receipt = provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .context("Failed to get transaction receipt")?
+            .with_context(|| format!("Transaction {tx_hash} not found"))?;
+        let submitted: Vec<_> = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == *self.instance.address())
+            .filter_map(|log| log.log_decode::<IBoundlessMarket::RequestSubmitted>().ok())
+            .map(|event| event.inner.data)
+            .collect();
+        if !submitted.is_empty() {
+            return submitted
+                .into_iter()
+                .find(|data| request_id.is_none_or(|id| id == data.requestId))
+                .map(|data| (data.request, data.clientSignature))
+                .ok_or(MarketError::RequestNotFound(request_id.unwrap_or_default()));
+        }
+
+        let tx_data = provider
+            .get_transaction_by_hash(tx_hash)
+            .await
+            .context("Failed to get transaction")?
+            .with_context(|| format!("Transaction {tx_hash} not found"))?;
+        let calldata = IBoundlessMarket::submitRequestCall::abi_decode(tx_data.input())
+            .with_context(|| format!("Transaction {tx_hash} did not submit a request"))?;
+        if let Some(request_id) = request_id.filter(|id| *id != calldata.request.id) {
+            return Err(MarketError::RequestNotFound(request_id));
+        }
+        Ok((calldata.request, calldata.clientSignature))
+    }
+
     /// Returns the fulfillment data and seal if the request is fulfilled.
     ///
     /// This method will poll the status of the request until it is Fulfilled or Expired.
     /// Polling is done at intervals of `retry_interval` until the request is Fulfilled, Expired or
     /// the optional timeout is reached.
+    ///
+    /// The fulfillment is only returned once it has the confirmations of the
+    /// [MarketTxType::Fulfill] policy, that is once it is buried under one block less. See
+    /// [Self::wait_for_confirmed_fulfillment].
     pub async fn wait_for_request_fulfillment(
         &self,
-        request_id: U256,
+        request_id: impl Into<U256>,
         retry_interval: Duration,
         expires_at: u64,
     ) -> Result<Fulfillment, MarketError> {
+        let request_id = request_id.into();
+        let confirmed = self
+            .wait_for_confirmed_fulfillment(
+                request_id,
+                retry_interval,
+                expires_at,
+                self.confirmation_policy(MarketTxType::Fulfill).confirmations.saturating_sub(1),
+            )
+            .await?;
+        Ok(confirmed.fulfillment)
+    }
+
+    /// Wait for a request to be fulfilled, and for the fulfillment to be buried under
+    /// `confirmations` blocks.
+    ///
+    /// Once the fulfillment has enough confirmations, the block it was delivered in is checked
+    /// against the canonical chain. If it was reorged out, waiting resumes until the fulfillment
+    /// is delivered and confirmed again, or the request expires.
+    pub async fn wait_for_confirmed_fulfillment(
+        &self,
+        request_id: impl Into<U256>,
+        retry_interval: Duration,
+        expires_at: u64,
+        confirmations: u64,
+    ) -> Result<ConfirmedFulfillment, MarketError> {
+        let request_id = request_id.into();
         loop {
             let status = self.get_status(request_id, Some(expires_at)).await?;
             match status {
                 RequestStatus::Expired => return Err(MarketError::RequestHasExpired(request_id)),
                 RequestStatus::Fulfilled => {
-                    let event = self.query_fulfilled_event(request_id, None, None).await?;
-                    return Ok(event.fulfillment);
+                    let (event, log) =
+                        self.query_fulfilled_event_with_log(request_id, None, None).await?;
+                    let (Some(block_number), Some(block_hash), Some(tx_hash)) =
+                        (log.block_number, log.block_hash, log.transaction_hash)
+                    else {
+                        return Err(MarketError::Error(anyhow!(
+                            "fulfillment log of request 0x{request_id:x} is missing its block or transaction"
+                        )));
+                    };
+                    let depth = self.get_latest_block_number().await?.saturating_sub(block_number);
+                    let confirmed = ConfirmedFulfillment {
+                        fulfillment: event.fulfillment,
+                        prover: event.prover,
+                        block_number,
+                        block_hash,
+                        tx_hash,
+                        confirmations: depth,
+                    };
+                    if confirmations == 0 {
+                        return Ok(confirmed);
+                    }
+                    if depth >= confirmations {
+                        let canonical_hash = self
+                            .instance
+                            .provider()
+                            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                            .await
+                            .context("Failed to get fulfillment block")?
+                            .map(|block| block.header.hash);
+                        if canonical_hash == Some(block_hash) {
+                            return Ok(confirmed);
+                        }
+                        tracing::warn!(
+                            "Fulfillment of request {:x} in block {} was reorged out. Waiting for it to be delivered again",
+                            request_id,
+                            block_number
+                        );
+                    } else {
+                        tracing::info!(
+                            "Request {:x} fulfilled in block {} with {}/{} confirmations. Retrying in {:?}",
+                            request_id,
+                            block_number,
+                            depth,
+                            confirmations,
+                            retry_interval
+                        );
+                    }
+                    tokio::time::sleep(retry_interval).await;
                 }
                 _ => {
                     tracing::info!(
@@ -1240,28 +2139,6 @@ impl<P: Provider> BoundlessMarketService<P> {
         Ok(RequestId::u256(self.caller, index))
     }
 
-    /// Returns the image ID and URL of the assessor guest.
-    pub async fn image_info(&self) -> Result<(B256, String)> {
-        tracing::trace!("Calling imageInfo()");
-        let (image_id, image_url) =
-            self.instance.imageInfo().call().await.context("call failed")?.into();
-
-        Ok((image_id, image_url))
-    }
-
-    /// Get the chain ID.
-    ///
-    /// This function implements caching to save the chain ID after the first successful fetch.
-    pub async fn get_chain_id(&self) -> Result<u64, MarketError> {
-        let mut id = self.chain_id.load(Ordering::Relaxed);
-        if id != 0 {
-            return Ok(id);
-        }
-        id = self.instance.provider().get_chain_id().await.context("failed to get chain ID")?;
-        self.chain_id.store(id, Ordering::Relaxed);
-        Ok(id)
-    }
-
     /// Approves Boundless Market contract to deposit `value` amount of collateral on behalf of the caller.
     pub async fn approve_deposit_collateral(&self, value: U256) -> Result<()> {
         let spender = *self.instance.address();
@@ -1277,11 +2154,13 @@ impl<P: Provider> BoundlessMarketService<P> {
         let call = contract.approve(spender, value).from(self.caller);
         let pending_tx = call.send().await.map_err(IHitPointsErrors::decode_error)?;
         tracing::debug!("Broadcasting tx {}", pending_tx.tx_hash());
-        let tx_hash = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_hash = self
+            .with_policy(pending_tx, Some(MarketTxType::Deposit))
+            .0
             .watch()
             .await
             .context("failed to confirm tx")?;
+        self.publish(BusEvent::TxConfirmed { tx_hash });
 
         tracing::debug!(
             "Approved {} to spend {} of token 0x{:x}. Tx hash: {}",
@@ -1310,11 +2189,13 @@ impl<P: Provider> BoundlessMarketService<P> {
             self.instance.address(),
             pending_tx.tx_hash()
         );
-        let tx_hash = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_hash = self
+            .with_policy(pending_tx, Some(MarketTxType::Deposit))
+            .0
             .watch()
             .await
             .context("failed to confirm tx")?;
+        self.publish(BusEvent::TxConfirmed { tx_hash });
         tracing::debug!(
             "Submitted {} collateral deposit to market {:?}. Tx hash: {}",
             value,
@@ -1375,11 +2256,13 @@ impl<P: Provider> BoundlessMarketService<P> {
             self.instance.address(),
             pending_tx.tx_hash()
         );
-        let tx_hash = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_hash = self
+            .with_policy(pending_tx, Some(MarketTxType::Deposit))
+            .0
             .watch()
             .await
             .context("failed to confirm tx")?;
+        self.publish(BusEvent::TxConfirmed { tx_hash });
         tracing::debug!(
             "Submitted {} collateral deposit to market {:?}. Tx hash: {}",
             value,
@@ -1400,11 +2283,13 @@ impl<P: Provider> BoundlessMarketService<P> {
             self.instance.address(),
             pending_tx.tx_hash()
         );
-        let tx_hash = pending_tx
-            .with_timeout(Some(self.timeout))
+        let tx_hash = self
+            .with_policy(pending_tx, Some(MarketTxType::Deposit))
+            .0
             .watch()
             .await
             .context("failed to confirm tx")?;
+        self.publish(BusEvent::TxConfirmed { tx_hash });
         tracing::debug!(
             "Submitted {} collateral withdraw to market {:?}. Tx hash: {}",
             value,
@@ -1414,71 +2299,6 @@ impl<P: Provider> BoundlessMarketService<P> {
         self.check_collateral_balance().await?;
         Ok(())
     }
-
-    /// Returns the deposited balance, in HP, of the given account.
-    pub async fn balance_of_collateral(
-        &self,
-        account: impl Into<Address>,
-    ) -> Result<U256, MarketError> {
-        let account = account.into();
-        tracing::trace!("Calling balanceOfCollateral({})", account);
-        let balance =
-            self.instance.balanceOfCollateral(account).call().await.context("call failed")?;
-        Ok(balance)
-    }
-
-    /// Check the current collateral balance against the alert config
-    /// and log a warning or error or below the thresholds.
-    async fn check_collateral_balance(&self) -> Result<(), MarketError> {
-        let collateral_balance = self.balance_of_collateral(self.caller()).await?;
-        if collateral_balance < self.balance_alert_config.error_threshold.unwrap_or(U256::ZERO) {
-            tracing::error!(
-                "[B-BAL-STK] collateral balance {} for {} < error threshold",
-                collateral_balance,
-                self.caller(),
-            );
-        } else if collateral_balance
-            < self.balance_alert_config.warn_threshold.unwrap_or(U256::ZERO)
-        {
-            tracing::warn!(
-                "[B-BAL-STK] collateral balance {} for {} < warning threshold",
-                collateral_balance,
-                self.caller(),
-            );
-        } else {
-            tracing::trace!("collateral balance for {} is: {}", self.caller(), collateral_balance);
-        }
-        Ok(())
-    }
-
-    /// Returns the collateral token address used by the market.
-    pub async fn collateral_token_address(&self) -> Result<Address, MarketError> {
-        tracing::trace!("Calling COLLATERAL_TOKEN_CONTRACT()");
-        let address = self
-            .instance
-            .COLLATERAL_TOKEN_CONTRACT()
-            .call()
-            .await
-            .context("COLLATERAL_TOKEN_CONTRACT call failed")?
-            .0;
-        Ok(address.into())
-    }
-
-    /// Returns the collateral token's symbol.
-    pub async fn collateral_token_symbol(&self) -> Result<String, MarketError> {
-        let address = self.collateral_token_address().await?;
-        let contract = IERC20::new(address, self.instance.provider());
-        let symbol = contract.symbol().call().await.context("Failed to get token symbol")?;
-        Ok(symbol)
-    }
-
-    /// Returns the collateral token's decimals.
-    pub async fn collateral_token_decimals(&self) -> Result<u8, MarketError> {
-        let address = self.collateral_token_address().await?;
-        let contract = IERC20::new(address, self.instance.provider());
-        let decimals = contract.decimals().call().await.context("Failed to get token decimals")?;
-        Ok(decimals)
-    }
 }
 
 impl Offer {
@@ -1527,6 +2347,45 @@ impl Offer {
         }
     }
 
+    /// Calculates the number of seconds after the start of the ramp-up at which the price first
+    /// reaches the given price.
+    ///
+    /// Returns `None` if the price is above the max price, and so is never reached.
+    pub fn time_to_reach(&self, price: U256) -> Option<u64> {
+        if price > self.maxPrice {
+            return None;
+        }
+        if price <= self.minPrice || self.rampUpPeriod == 0 {
+            return Some(0);
+        }
+
+        let rise = self.maxPrice - self.minPrice;
+        let run = U256::from(self.rampUpPeriod);
+        ((price - self.minPrice) * run).div_ceil(rise).try_into().ok()
+    }
+
+    /// Samples the price of the offer at evenly spaced points across the ramp-up period.
+    ///
+    /// Returns `points + 1` pairs of UNIX timestamp and price, from the start of the ramp-up to the
+    /// time the max price is reached, followed by the lock deadline, at which the price drops
+    /// to zero.
+    pub fn price_curve(&self, points: u32) -> Result<Vec<(u64, U256)>, MarketError> {
+        let points = points.max(1) as u64;
+        let start = self.rampUpStart;
+        let period = self.rampUpPeriod as u64;
+
+        let mut curve = Vec::with_capacity(points as usize + 2);
+        for i in 0..=points {
+            let timestamp = start + (period * i).div_ceil(points);
+            curve.push((timestamp, self.price_at(timestamp)?));
+        }
+        let lock_deadline = self.lock_deadline();
+        if lock_deadline > start + period {
+            curve.push((lock_deadline + 1, self.price_at(lock_deadline + 1)?));
+        }
+        Ok(curve)
+    }
+
     /// UNIX timestamp after which the request is considered completely expired.
     pub fn deadline(&self) -> u64 {
         self.rampUpStart + (self.timeout as u64)
@@ -1543,6 +2402,16 @@ impl Offer {
         self.rampUpStart + (self.lockTimeout as u64)
     }
 
+    /// Returns the amount of collateral, in collateral token base units, that a prover must have
+    /// deposited to lock this offer.
+    ///
+    /// The market deducts exactly the `lockCollateral` of the offer from the collateral balance
+    /// of the prover at lock time, with no protocol multiplier applied. No collateral is required
+    /// to fulfill a request without locking it.
+    pub fn required_collateral(&self) -> U256 {
+        self.lockCollateral
+    }
+
     /// Returns the amount of collateral that the protocol awards to the prover who fills an order that
     /// was locked by another prover but not fulfilled by lock expiry.
     pub fn collateral_reward_if_locked_and_not_fulfilled(&self) -> U256 {
@@ -1554,6 +2423,234 @@ impl Offer {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Collateral required to lock a request, compared to the collateral deposited by a prover.
+pub struct CollateralRequirement {
+    /// Collateral deducted from the prover when it locks the request.
+    pub required: U256,
+    /// Collateral currently deposited by the prover and not held for other locks.
+    pub available: U256,
+}
+
+impl CollateralRequirement {
+    /// Whether the prover has deposited enough collateral to lock the request.
+    pub fn is_sufficient(&self) -> bool {
+        self.available >= self.required
+    }
+
+    /// Additional collateral the prover must deposit before it can lock the request.
+    pub fn shortfall(&self) -> U256 {
+        self.required.saturating_sub(self.available)
+    }
+}
+
+/// Market event a request was found in by [BoundlessMarketService::get_request_by_digest].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestSource {
+    /// The request was submitted onchain, in a `RequestSubmitted` event.
+    Submitted,
+    /// The request was submitted offchain, and found in the `RequestLocked` event of its lock.
+    Locked,
+}
+
+/// A proof request found onchain, with the transaction that carried it.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct LocatedRequest {
+    /// The proof request.
+    pub request: ProofRequest,
+    /// Signature of the client over the request.
+    pub client_signature: Bytes,
+    /// Event the request was found in.
+    pub source: RequestSource,
+    /// Hash of the transaction that submitted the request, or locked it if submitted offchain.
+    pub tx_hash: B256,
+    /// Number of the block of the transaction.
+    pub block_number: u64,
+}
+
+/// A fulfillment delivered onchain, with the block and transaction that delivered it.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ConfirmedFulfillment {
+    /// The delivered fulfillment, including the fulfillment data and seal.
+    pub fulfillment: Fulfillment,
+    /// Address of the prover that delivered the fulfillment.
+    pub prover: Address,
+    /// Number of the block the fulfillment was delivered in.
+    pub block_number: u64,
+    /// Hash of the block the fulfillment was delivered in.
+    pub block_hash: B256,
+    /// Hash of the transaction that delivered the fulfillment.
+    pub tx_hash: B256,
+    /// Number of blocks built on top of the fulfillment block when it was last checked.
+    pub confirmations: u64,
+}
+
+/// Reason a fulfillment in a batch did not result in payment to the prover.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FulfillmentFailure {
+    /// The request expired before the transaction was included, so the proof was not delivered.
+    Expired,
+    /// The proof was delivered, but the conditions for payment were not met, e.g. because the
+    /// request is locked by another prover or was already fulfilled.
+    PaymentRequirementsFailed {
+        /// Name and arguments of the market error, or its raw encoding if it could not be decoded.
+        reason: String,
+        /// ABI encoded market error, as emitted in the `PaymentRequirementsFailed` event.
+        error: Bytes,
+    },
+}
+
+impl std::fmt::Display for FulfillmentFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Expired => write!(f, "request expired"),
+            Self::PaymentRequirementsFailed { reason, .. } => {
+                write!(f, "payment requirements failed: {reason}")
+            }
+        }
+    }
+}
+
+/// Outcome of a single fulfillment in a batch fulfillment transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FulfillmentOutcome {
+    /// ID of the fulfilled request.
+    pub request_id: U256,
+    /// Whether this transaction was the first to fulfill the request.
+    pub newly_fulfilled: bool,
+    /// Whether the callback of the request reverted. A failed callback does not revert the
+    /// fulfillment.
+    pub callback_failed: bool,
+    /// Why the prover was not paid, or `None` if the proof was delivered and paid for.
+    pub failure: Option<FulfillmentFailure>,
+}
+
+/// Outcome of [BoundlessMarketService::reclaim_expired].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum ReclaimOutcome {
+    /// The prover that locked the request was slashed by this call.
+    Slashed {
+        /// Hash of the slash transaction.
+        tx_hash: B256,
+        /// Whether the price paid at lock time was credited back to the client's balance. This
+        /// is false if another prover fulfilled the request after the lock deadline.
+        refunded: bool,
+        /// The event emitted by the slash.
+        event: IBoundlessMarket::ProverSlashed,
+    },
+    /// The prover was already slashed, so any refund was already credited to the client.
+    AlreadySlashed,
+    /// The request was never locked, so no funds were escrowed for it.
+    NotLocked,
+    /// The request was fulfilled, and the prover paid.
+    Fulfilled,
+}
+
+/// Outcomes of the fulfillments in a confirmed batch fulfillment transaction.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct BatchFulfillment {
+    /// Receipt of the fulfillment transaction.
+    pub receipt: TransactionReceipt,
+    /// Outcome of each fulfillment, in the order they were processed by the market.
+    pub outcomes: Vec<FulfillmentOutcome>,
+}
+
+impl BatchFulfillment {
+    /// Decode the outcome of each fulfillment from the events emitted by the market at
+    /// `market_address` in the receipt.
+    pub fn from_receipt(market_address: Address, receipt: TransactionReceipt) -> Self {
+        let outcomes = fulfillment_outcomes(market_address, receipt.inner.logs());
+        Self { receipt, outcomes }
+    }
+
+    /// IDs of the requests whose proof was delivered and paid for.
+    pub fn fulfilled(&self) -> impl Iterator<Item = U256> + '_ {
+        self.outcomes.iter().filter(|outcome| outcome.failure.is_none()).map(|o| o.request_id)
+    }
+
+    /// IDs of the requests whose fulfillment was not paid for, with the reason.
+    pub fn failed(&self) -> impl Iterator<Item = (U256, &FulfillmentFailure)> + '_ {
+        self.outcomes
+            .iter()
+            .filter_map(|outcome| outcome.failure.as_ref().map(|f| (outcome.request_id, f)))
+    }
+
+    /// Whether every fulfillment in the batch was paid for.
+    pub fn is_complete(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.failure.is_none())
+    }
+}
+
+/// Decode the outcome of each fulfillment from the logs of a fulfillment transaction.
+///
+/// For each fulfillment, the market emits `PaymentRequirementsFailed` if the prover is not paid,
+/// followed by `ProofDelivered`, except for expired requests where no proof is delivered.
+/// `RequestFulfilled` and `CallbackFailed` are emitted around these events for the same request.
+fn fulfillment_outcomes(market_address: Address, logs: &[Log]) -> Vec<FulfillmentOutcome> {
+    let mut outcomes = Vec::new();
+    let mut newly_fulfilled = std::collections::HashSet::new();
+    let mut callback_failed = std::collections::HashSet::new();
+    let mut payment_error: Option<Bytes> = None;
+    for log in logs.iter().filter(|log| log.address() == market_address) {
+        let Some(topic) = log.topic0() else {
+            continue;
+        };
+        if *topic == IBoundlessMarket::RequestFulfilled::SIGNATURE_HASH {
+            if let Ok(event) = log.log_decode::<IBoundlessMarket::RequestFulfilled>() {
+                newly_fulfilled.insert(event.inner.data.requestId);
+            }
+        } else if *topic == IBoundlessMarket::CallbackFailed::SIGNATURE_HASH {
+            if let Ok(event) = log.log_decode::<IBoundlessMarket::CallbackFailed>() {
+                callback_failed.insert(event.inner.data.requestId);
+            }
+        } else if *topic == IBoundlessMarket::PaymentRequirementsFailed::SIGNATURE_HASH {
+            let Ok(event) = log.log_decode::<IBoundlessMarket::PaymentRequirementsFailed>() else {
+                continue;
+            };
+            let error = event.inner.data.error;
+            // Expired requests end the processing of the fulfillment without a proof. The market
+            // encodes the error with the request ID only, so it is matched on its selector rather
+            // than decoded as the declared RequestIsExpired(requestId, deadline).
+            match error.strip_prefix(&IBoundlessMarket::RequestIsExpired::SELECTOR) {
+                Some(args) if args.len() >= 32 => outcomes.push(FulfillmentOutcome {
+                    request_id: U256::from_be_slice(&args[..32]),
+                    newly_fulfilled: false,
+                    callback_failed: false,
+                    failure: Some(FulfillmentFailure::Expired),
+                }),
+                _ => payment_error = Some(error),
+            }
+        } else if *topic == ProofDelivered::SIGNATURE_HASH {
+            if let Ok(event) = log.log_decode::<ProofDelivered>() {
+                let failure = payment_error.take().map(|error| {
+                    let reason = match IBoundlessMarketErrors::abi_decode(&error) {
+                        Ok(err) => format!("{err:?}"),
+                        Err(_) => format!("unknown error {error}"),
+                    };
+                    FulfillmentFailure::PaymentRequirementsFailed { reason, error }
+                });
+                outcomes.push(FulfillmentOutcome {
+                    request_id: event.inner.data.requestId,
+                    newly_fulfilled: false,
+                    callback_failed: false,
+                    failure,
+                });
+            }
+        }
+    }
+    for outcome in &mut outcomes {
+        outcome.newly_fulfilled = newly_fulfilled.contains(&outcome.request_id);
+        outcome.callback_failed = callback_failed.contains(&outcome.request_id);
+    }
+    outcomes
+}
+
 #[derive(Debug, Clone)]
 /// Represents the parameters for submitting a Merkle Root.
 pub struct Root {
@@ -1648,8 +2745,21 @@ impl FulfillmentTx {
 
 #[cfg(test)]
 mod tests {
-    use crate::contracts::Offer;
-    use alloy::primitives::{utils::parse_ether, U256};
+    use super::{
+        fulfillment_outcomes, BoundlessMarketService, CollateralRequirement, ConfirmationPolicy,
+        FulfillmentFailure, MarketTxType,
+    };
+    use crate::contracts::{
+        eip712_domain, Fulfillment, FulfillmentDataType, IBoundlessMarket, Offer,
+    };
+    use alloy::{
+        network::AnyNetwork,
+        primitives::{utils::parse_ether, Address, Bytes, LogData, B256, U256},
+        providers::RootProvider,
+        rpc::types::Log,
+    };
+    use alloy_sol_types::{SolError, SolEvent};
+    use std::time::Duration;
     fn ether(value: &str) -> U256 {
         parse_ether(value).unwrap()
     }
@@ -1704,9 +2814,203 @@ mod tests {
         assert!(offer.time_at_price(ether("3")).is_err());
     }
 
+    #[test]
+    fn test_time_to_reach() {
+        let offer = &test_offer(100);
+
+        assert_eq!(offer.time_to_reach(ether("0.5")), Some(0));
+        assert_eq!(offer.time_to_reach(ether("1")), Some(0));
+        assert_eq!(offer.time_to_reach(ether("1.001")), Some(1));
+        assert_eq!(offer.time_to_reach(ether("1.5")), Some(50));
+        assert_eq!(offer.time_to_reach(ether("2")), Some(100));
+        assert_eq!(offer.time_to_reach(ether("2.01")), None);
+
+        // Consistent with price_at.
+        for price in ["1.01", "1.25", "1.75", "1.99"] {
+            let t = offer.time_to_reach(ether(price)).unwrap();
+            assert!(offer.price_at(offer.rampUpStart + t).unwrap() >= ether(price));
+            assert!(offer.price_at(offer.rampUpStart + t - 1).unwrap() < ether(price));
+        }
+    }
+
+    #[test]
+    fn test_price_curve() {
+        let offer = &test_offer(100);
+        let curve = offer.price_curve(4).unwrap();
+
+        assert_eq!(
+            curve[..5],
+            [
+                (100, ether("1")),
+                (125, ether("1.25")),
+                (150, ether("1.5")),
+                (175, ether("1.75")),
+                (200, ether("2")),
+            ]
+        );
+        // The final point is just after the lock deadline, where the price drops to zero.
+        assert_eq!(curve.last().unwrap(), &(offer.lock_deadline() + 1, U256::ZERO));
+    }
+
     #[test]
     fn test_collateral_reward_if_locked_and_not_fulfilled() {
         let offer = &test_offer(100);
         assert_eq!(offer.collateral_reward_if_locked_and_not_fulfilled(), ether("0.5"));
     }
+
+    #[test]
+    fn test_collateral_requirement() {
+        let offer = &test_offer(100);
+        assert_eq!(offer.required_collateral(), ether("1"));
+
+        let requirement = CollateralRequirement {
+            required: offer.required_collateral(),
+            available: ether("0.4"),
+        };
+        assert!(!requirement.is_sufficient());
+        assert_eq!(requirement.shortfall(), ether("0.6"));
+
+        let requirement =
+            CollateralRequirement { required: offer.required_collateral(), available: ether("3") };
+        assert!(requirement.is_sufficient());
+        assert_eq!(requirement.shortfall(), U256::ZERO);
+    }
+
+    #[test]
+    fn test_service_on_other_network() {
+        let provider =
+            RootProvider::<AnyNetwork>::new_http("http://localhost:8545".parse().unwrap());
+        let market =
+            BoundlessMarketService::<_, AnyNetwork>::new(Address::ZERO, provider, Address::ZERO);
+        assert_eq!(*market.instance().address(), Address::ZERO);
+        assert_eq!(market.caller(), Address::ZERO);
+    }
+
+    #[test]
+    fn test_confirmation_policy() {
+        let provider =
+            RootProvider::<AnyNetwork>::new_http("http://localhost:8545".parse().unwrap());
+        let slash_policy = ConfirmationPolicy::new(3, Duration::from_secs(600));
+        let market =
+            BoundlessMarketService::<_, AnyNetwork>::new(Address::ZERO, provider, Address::ZERO)
+                .with_timeout(Duration::from_secs(60))
+                .with_confirmation_policy(MarketTxType::Slash, slash_policy);
+        assert_eq!(market.confirmation_policy(MarketTxType::Slash), slash_policy);
+        // Types without a policy wait for a single confirmation, up to the service timeout.
+        assert_eq!(
+            market.confirmation_policy(MarketTxType::Lock),
+            ConfirmationPolicy::new(1, Duration::from_secs(60))
+        );
+
+        // The fulfillment confirmations are folded into the fulfill policy.
+        #[allow(deprecated)]
+        let market = market.with_fulfillment_confirmations(2);
+        assert_eq!(
+            market.confirmation_policy(MarketTxType::Fulfill),
+            ConfirmationPolicy::new(3, Duration::from_secs(60))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_eip712_domain_with_chain_id() {
+        // Nothing listens on this port, so any RPC call would fail.
+        let provider = RootProvider::<AnyNetwork>::new_http("http://127.0.0.1:1".parse().unwrap());
+        let address = Address::repeat_byte(7);
+        let market = BoundlessMarketService::<_, AnyNetwork>::new(address, provider, Address::ZERO)
+            .with_chain_id(8453);
+        assert_eq!(market.get_chain_id().await.unwrap(), 8453);
+        assert_eq!(market.eip712_domain().await.unwrap(), eip712_domain(address, 8453));
+    }
+
+    #[test]
+    fn test_fulfillment_outcomes() {
+        let market = Address::repeat_byte(1);
+        let prover = Address::repeat_byte(2);
+        let log = |address: Address, data: LogData| Log {
+            inner: alloy::primitives::Log { address, data },
+            ..Default::default()
+        };
+        let delivered = |id: u64| {
+            IBoundlessMarket::ProofDelivered {
+                requestId: U256::from(id),
+                prover,
+                fulfillment: Fulfillment {
+                    id: U256::from(id),
+                    requestDigest: B256::ZERO,
+                    claimDigest: B256::ZERO,
+                    fulfillmentDataType: FulfillmentDataType::None,
+                    fulfillmentData: Bytes::new(),
+                    seal: Bytes::new(),
+                },
+            }
+            .encode_log_data()
+        };
+        let payment_failed = |error: Vec<u8>| {
+            IBoundlessMarket::PaymentRequirementsFailed { error: error.into() }.encode_log_data()
+        };
+
+        let logs = vec![
+            // Request 1 is fulfilled and paid for, but its callback fails.
+            log(
+                market,
+                IBoundlessMarket::RequestFulfilled {
+                    requestId: U256::from(1),
+                    prover,
+                    requestDigest: B256::ZERO,
+                }
+                .encode_log_data(),
+            ),
+            log(market, delivered(1)),
+            log(
+                market,
+                IBoundlessMarket::CallbackFailed {
+                    requestId: U256::from(1),
+                    callback: Address::ZERO,
+                    error: Bytes::new(),
+                }
+                .encode_log_data(),
+            ),
+            // Request 2 is locked by another prover, so the proof is delivered without payment.
+            log(
+                market,
+                payment_failed(
+                    IBoundlessMarket::RequestIsLocked { requestId: U256::from(2) }.abi_encode(),
+                ),
+            ),
+            log(market, delivered(2)),
+            // Request 3 expired, so no proof is delivered. As in the market, the error is encoded
+            // with the request ID only.
+            log(
+                market,
+                payment_failed(
+                    [
+                        IBoundlessMarket::RequestIsExpired::SELECTOR.as_slice(),
+                        &U256::from(3).to_be_bytes::<32>(),
+                    ]
+                    .concat(),
+                ),
+            ),
+            // Events emitted by other contracts are ignored.
+            log(Address::repeat_byte(3), delivered(4)),
+        ];
+
+        let outcomes = fulfillment_outcomes(market, &logs);
+        assert_eq!(outcomes.len(), 3);
+
+        assert_eq!(outcomes[0].request_id, U256::from(1));
+        assert!(outcomes[0].newly_fulfilled);
+        assert!(outcomes[0].callback_failed);
+        assert_eq!(outcomes[0].failure, None);
+
+        assert_eq!(outcomes[1].request_id, U256::from(2));
+        assert!(!outcomes[1].newly_fulfilled);
+        assert!(matches!(
+            &outcomes[1].failure,
+            Some(FulfillmentFailure::PaymentRequirementsFailed { reason, .. })
+                if reason.contains("RequestIsLocked")
+        ));
+
+        assert_eq!(outcomes[2].request_id, U256::from(3));
+        assert_eq!(outcomes[2].failure, Some(FulfillmentFailure::Expired));
+    }
 }
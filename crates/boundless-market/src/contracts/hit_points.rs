@@ -24,20 +24,28 @@ use crate::contracts::{
 };
 
 use super::{IHitPoints::IHitPointsInstance, TXN_CONFIRM_TIMEOUT};
-use alloy::{network::Ethereum, primitives::Address, providers::Provider};
+use alloy::{
+    network::{Ethereum, Network},
+    primitives::Address,
+    providers::Provider,
+};
 use alloy_primitives::U256;
 use anyhow::{Context, Result};
 
 const DEFAULT_ALLOWANCE: u128 = 100000000000000000000;
 /// HitPointsService provides a high-level interface to the HitPoints contract.
 #[derive(Clone)]
-pub struct HitPointsService<P> {
-    instance: IHitPointsInstance<P, Ethereum>,
+pub struct HitPointsService<P, N = Ethereum> {
+    instance: IHitPointsInstance<P, N>,
     caller: Address,
     tx_timeout: Duration,
 }
 
-impl<P: Provider> HitPointsService<P> {
+impl<P, N> HitPointsService<P, N>
+where
+    P: Provider<N>,
+    N: Network,
+{
     /// Creates a new HitPointsService.
     pub fn new(address: Address, provider: P, caller: Address) -> Self {
         let instance = IHitPoints::new(address, provider);
@@ -46,7 +54,7 @@ impl<P: Provider> HitPointsService<P> {
     }
 
     /// Returns the underlying IHitPointsInstance.
-    pub fn instance(&self) -> &IHitPointsInstance<P, Ethereum> {
+    pub fn instance(&self) -> &IHitPointsInstance<P, N> {
         &self.instance
     }
 
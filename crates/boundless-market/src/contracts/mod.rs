@@ -105,7 +105,9 @@ pub mod token {
             error ERC20InvalidApprover(address approver);
             error ERC20InvalidSpender(address spender);
             function approve(address spender, uint256 value) external returns (bool);
+            function allowance(address owner, address spender) external view returns (uint256);
             function balanceOf(address account) external view returns (uint256);
+            function transfer(address to, uint256 value) external returns (bool);
             function symbol() external view returns (string memory);
             function decimals() external view returns (uint8);
         }
@@ -190,8 +192,14 @@ impl EIP712DomainSaltless {
 
 /// Structured represent of a request ID.
 ///
-/// This struct can be packed and unpacked from a U256 value.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// This struct can be packed and unpacked from a U256 value. It displays as the canonical hex
+/// form of the packed value, e.g. `0x7fa9385be102ac3eac297483dd6233d62b3e149600000064`, and
+/// parses from either that form, the decimal form, or `<address>:<index>`.
+///
+/// Methods of [BoundlessMarketService](boundless_market::BoundlessMarketService) and
+/// [Client](crate::Client) that take a request ID accept either a [RequestId] or its packed
+/// [U256] value.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub struct RequestId {
     /// Address of the wallet or contract authorizing the request.
@@ -218,6 +226,26 @@ impl RequestId {
         Self::new(addr, index).into()
     }
 
+    /// Address of the wallet or contract authorizing the request.
+    pub fn requestor(&self) -> Address {
+        self.addr
+    }
+
+    /// Index of the request, assigned by the requestor.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Whether the request is authorized by a smart contract, using ERC-1271.
+    pub fn is_smart_contract_signed(&self) -> bool {
+        self.smart_contract_signed
+    }
+
+    /// Pack this [RequestId] into a [U256].
+    pub fn to_u256(self) -> U256 {
+        self.into()
+    }
+
     /// Set the smart contract signed flag to true. This indicates that the signature associated
     /// with the request should be validated using ERC-1271's isValidSignature function.
     pub fn set_smart_contract_signed_flag(self) -> Self {
@@ -264,6 +292,38 @@ impl From<RequestId> for U256 {
     }
 }
 
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:x}", self.to_u256())
+    }
+}
+
+impl std::fmt::LowerHex for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::LowerHex::fmt(&self.to_u256(), f)
+    }
+}
+
+impl FromStr for RequestId {
+    type Err = RequestError;
+
+    /// Parse a [RequestId] from its hex or decimal packed form, or from `<address>:<index>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((addr, index)) = s.split_once(':') {
+            let addr = Address::from_str(addr).map_err(|_| RequestError::MalformedRequestId)?;
+            let index = index.parse().map_err(|_| RequestError::MalformedRequestId)?;
+            return Ok(Self::new(addr, index));
+        }
+        let value = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => U256::from_str_radix(hex, 16),
+            None => U256::from_str_radix(s, 10),
+        }
+        .map_err(|_| RequestError::MalformedRequestId)?;
+        Self::try_from(value)
+    }
+}
+
 #[non_exhaustive]
 #[derive(thiserror::Error, Debug)]
 /// Errors that can occur when creating a proof request.
@@ -400,6 +460,11 @@ impl ProofRequest {
         RequestId::from_lossy(self.id).addr
     }
 
+    /// Returns the structured [RequestId] of the request, ignoring unknown flags.
+    pub fn request_id(&self) -> RequestId {
+        RequestId::from_lossy(self.id)
+    }
+
     /// Returns the time, in seconds since the UNIX epoch, at which the request expires.
     pub fn expires_at(&self) -> u64 {
         self.offer.rampUpStart + self.offer.timeout as u64
@@ -515,10 +580,34 @@ impl ProofRequest {
         signature: &Bytes,
         contract_addr: Address,
         chain_id: u64,
+    ) -> Result<(), RequestError> {
+        self.verify_signature_with_separator(
+            signature,
+            eip712_domain_separator(contract_addr, chain_id),
+        )
+    }
+
+    /// Returns the EIP-712 signing hash for the request, given the separator of the domain.
+    ///
+    /// This is the same as [SolStruct::eip712_signing_hash], without hashing the domain.
+    pub fn signing_hash_with_separator(&self, domain_separator: FixedBytes<32>) -> FixedBytes<32> {
+        let mut hasher = Keccak256::new();
+        hasher.update([0x19, 0x01]);
+        hasher.update(domain_separator);
+        hasher.update(self.eip712_hash_struct());
+        hasher.finalize()
+    }
+
+    /// Verifies the request signature against the EIP-712 domain with the given separator.
+    ///
+    /// This is [Self::verify_signature] for callers that cache the domain separator.
+    pub fn verify_signature_with_separator(
+        &self,
+        signature: &Bytes,
+        domain_separator: FixedBytes<32>,
     ) -> Result<(), RequestError> {
         let sig = Signature::try_from(signature.as_ref())?;
-        let domain = eip712_domain(contract_addr, chain_id);
-        let hash = self.eip712_signing_hash(&domain.alloy_struct());
+        let hash = self.signing_hash_with_separator(domain_separator);
         let addr = sig.recover_address_from_prehash(&hash)?;
         if addr == self.client_address() {
             Ok(())
@@ -898,6 +987,31 @@ impl From<Url> for RequestInput {
     }
 }
 
+impl RequestLock {
+    /// Flag set once the prover that locked the request was paid for fulfilling it during the
+    /// lock.
+    pub const PROVER_PAID_DURING_LOCK_FLAG: u8 = 1 << 0;
+    /// Flag set once a prover was paid for fulfilling the request after the lock expired.
+    pub const PROVER_PAID_AFTER_LOCK_FLAG: u8 = 1 << 1;
+    /// Flag set once the prover that locked the request was slashed.
+    pub const SLASHED_FLAG: u8 = 1 << 2;
+
+    /// Whether the prover that locked the request fulfilled it during the lock, and was paid.
+    pub fn is_prover_paid_during_lock(&self) -> bool {
+        self.requestLockFlags & Self::PROVER_PAID_DURING_LOCK_FLAG != 0
+    }
+
+    /// Whether a prover fulfilled the request after the lock expired, and was paid.
+    pub fn is_prover_paid_after_lock(&self) -> bool {
+        self.requestLockFlags & Self::PROVER_PAID_AFTER_LOCK_FLAG != 0
+    }
+
+    /// Whether the prover that locked the request was slashed.
+    pub fn is_slashed(&self) -> bool {
+        self.requestLockFlags & Self::SLASHED_FLAG != 0
+    }
+}
+
 impl Offer {
     /// Sets the offer minimum price.
     pub fn with_min_price(self, min_price: U256) -> Self {
@@ -965,6 +1079,9 @@ pub mod boundless_market;
 #[cfg(not(target_os = "zkvm"))]
 /// The Hit Points module.
 pub mod hit_points;
+#[cfg(not(target_os = "zkvm"))]
+/// Price curves approximated by a schedule of offers.
+pub mod price_curve;
 
 #[cfg(not(target_os = "zkvm"))]
 #[derive(Error, Debug)]
@@ -1085,10 +1202,24 @@ pub fn eip712_domain(addr: Address, chain_id: u64) -> EIP712DomainSaltless {
     }
 }
 
+#[cfg(not(target_os = "zkvm"))]
+static DOMAIN_SEPARATORS: std::sync::LazyLock<dashmap::DashMap<(u64, Address), FixedBytes<32>>> =
+    std::sync::LazyLock::new(Default::default);
+
+#[cfg(not(target_os = "zkvm"))]
+/// The EIP-712 domain separator for the Boundless Market contract.
+///
+/// Separators are cached per chain and market address for the lifetime of the process.
+pub fn eip712_domain_separator(addr: Address, chain_id: u64) -> FixedBytes<32> {
+    *DOMAIN_SEPARATORS
+        .entry((chain_id, addr))
+        .or_insert_with(|| eip712_domain(addr, chain_id).alloy_struct().separator())
+}
+
 /// Constant to specify when no selector is specified.
 pub const UNSPECIFIED_SELECTOR: FixedBytes<4> = FixedBytes::<4>([0; 4]);
 
-#[cfg(feature = "test-utils")]
+#[cfg(feature = "deploy")]
 #[allow(missing_docs)]
 pub mod bytecode;
 
@@ -1202,4 +1333,36 @@ mod tests {
         assert_eq!(request_id1_u256, raw_id1);
         assert_eq!(request_id2_u256, raw_id2);
     }
+
+    #[test]
+    fn test_request_id_display_and_parse() {
+        let addr = Address::from_str("0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496").unwrap();
+        let request_id = RequestId::new(addr, 100);
+        let hex = "0x7fa9385be102ac3eac297483dd6233d62b3e149600000064";
+        assert_eq!(request_id.to_string(), hex);
+        assert_eq!(format!("{request_id:x}"), &hex[2..]);
+        assert_eq!(request_id.requestor(), addr);
+        assert_eq!(request_id.index(), 100);
+        assert!(!request_id.is_smart_contract_signed());
+
+        assert_eq!(RequestId::from_str(hex).unwrap(), request_id);
+        assert_eq!(
+            RequestId::from_str("3130239009558586413752262552917257075388277690201777635428")
+                .unwrap(),
+            request_id
+        );
+        assert_eq!(
+            RequestId::from_str("0x7FA9385bE102ac3EAc297483Dd6233D62b3e1496:100").unwrap(),
+            request_id
+        );
+
+        let signed = request_id.set_smart_contract_signed_flag();
+        assert_eq!(RequestId::from_str(&signed.to_string()).unwrap(), signed);
+
+        // Bits above the known flags are rejected.
+        let malformed = format!("0x{:x}", U256::from(1) << 200);
+        assert!(RequestId::from_str(&malformed).is_err());
+        assert!(RequestId::from_str("0xzz").is_err());
+        assert!(RequestId::from_str("0x01:abc").is_err());
+    }
 }
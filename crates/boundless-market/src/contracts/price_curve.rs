@@ -0,0 +1,209 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Price curves other than the linear ramp enforced by the market, approximated by a schedule of
+//! offers that the client submits in turn.
+
+use alloy_primitives::U256;
+use anyhow::anyhow;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::{boundless_market::MarketError, Offer};
+
+/// Steepness `k` of the exponential curve, whose price at fraction `x` of the ramp-up period is
+/// `min + (max - min) * (e^(k * x) - 1) / (e^k - 1)`.
+const EXPONENTIAL_STEEPNESS: f64 = 3.0;
+
+/// Resolution of the fraction of the price range reached at a given time.
+const FRACTION_SCALE: u64 = 1_000_000;
+
+/// Lowest max price of a round, since the market rejects offers with a max price of zero.
+const MIN_ROUND_PRICE: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+/// Shape of the price of a request over its ramp-up period.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum PriceCurve {
+    /// The price rises linearly from the min to the max price.
+    #[default]
+    Linear,
+    /// The price rises slowly at first and quickly towards the max price.
+    ///
+    /// This avoids paying close to the max price for large jobs that provers are slow to pick up.
+    Exponential,
+    /// The price rises in equal steps from the min to the max price, holding at each step.
+    Stepped,
+}
+
+impl PriceCurve {
+    /// Fraction of the price range, in units of [FRACTION_SCALE], reached at fraction `x` of the
+    /// ramp-up period.
+    fn fraction_at(self, x: f64, rounds: u32) -> u64 {
+        let fraction = match self {
+            PriceCurve::Linear => x,
+            PriceCurve::Exponential => {
+                (EXPONENTIAL_STEEPNESS * x).exp_m1() / EXPONENTIAL_STEEPNESS.exp_m1()
+            }
+            PriceCurve::Stepped if rounds <= 1 => 1.0,
+            PriceCurve::Stepped => (x * rounds as f64).floor() / (rounds - 1) as f64,
+        };
+        (fraction.clamp(0.0, 1.0) * FRACTION_SCALE as f64).round() as u64
+    }
+}
+
+impl Offer {
+    /// Approximate the given price curve with a schedule of offers, one for each of `rounds`
+    /// equal parts of the ramp-up period.
+    ///
+    /// The market only supports linear ramps, so the offer of each round ramps linearly between
+    /// the prices of the curve at the start and end of its part, or holds the price of its step
+    /// for a [PriceCurve::Stepped] curve. The client submits the request with the offer of the
+    /// first round, and resubmits it with the offer of each following round from its bidding
+    /// start, until the request is locked. Every round keeps the lock deadline and expiry of this
+    /// offer, and the request must be resubmitted with the same ID so that at most one round is
+    /// locked and paid for.
+    ///
+    /// A round whose price would be zero, such as the first step of a [PriceCurve::Stepped] curve
+    /// from the default min price of zero, offers a max price of 1 wei instead.
+    ///
+    /// A [PriceCurve::Linear] curve is the offer itself, in a single round.
+    pub fn schedule(&self, curve: PriceCurve, rounds: u32) -> Result<Vec<Offer>, MarketError> {
+        if curve == PriceCurve::Linear || rounds <= 1 || self.rampUpPeriod == 0 {
+            return Ok(vec![self.clone()]);
+        }
+        if self.rampUpPeriod >= self.lockTimeout {
+            return Err(MarketError::Error(anyhow!(
+                "ramp-up period of {}s must end before the lock timeout of {}s to schedule rounds",
+                self.rampUpPeriod,
+                self.lockTimeout
+            )));
+        }
+
+        let period = self.rampUpPeriod as u64;
+        let range = self.maxPrice.saturating_sub(self.minPrice);
+        let price_at = |offset: u64| {
+            let fraction = curve.fraction_at(offset as f64 / period as f64, rounds);
+            self.minPrice + range * U256::from(fraction) / U256::from(FRACTION_SCALE)
+        };
+
+        let rounds = rounds as u64;
+        Ok((0..rounds)
+            .map(|i| {
+                let start = period * i / rounds;
+                let end = period * (i + 1) / rounds;
+                let (min_price, max_price, ramp_up_period) = match curve {
+                    PriceCurve::Stepped => (price_at(start), price_at(start), 0),
+                    _ => (price_at(start), price_at(end), (end - start) as u32),
+                };
+                Offer {
+                    minPrice: min_price,
+                    maxPrice: max_price.max(MIN_ROUND_PRICE),
+                    rampUpStart: self.rampUpStart + start,
+                    rampUpPeriod: ramp_up_period,
+                    lockTimeout: self.lockTimeout - start as u32,
+                    timeout: self.timeout - start as u32,
+                    ..self.clone()
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request_builder::OfferLayerConfig;
+
+    fn offer() -> Offer {
+        Offer {
+            minPrice: U256::from(1_000),
+            maxPrice: U256::from(2_000),
+            rampUpStart: 100,
+            rampUpPeriod: 400,
+            lockTimeout: 1000,
+            timeout: 2000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_linear_schedule() {
+        assert_eq!(offer().schedule(PriceCurve::Linear, 4).unwrap(), vec![offer()]);
+        assert_eq!(offer().schedule(PriceCurve::Exponential, 1).unwrap(), vec![offer()]);
+    }
+
+    #[test]
+    fn test_exponential_schedule() {
+        let rounds = offer().schedule(PriceCurve::Exponential, 4).unwrap();
+        assert_eq!(rounds.len(), 4);
+        assert_eq!(rounds[0].minPrice, U256::from(1_000));
+        assert_eq!(rounds[3].maxPrice, U256::from(2_000));
+        for (round, next) in rounds.iter().zip(&rounds[1..]) {
+            // Rounds are contiguous, and rise faster as the ramp-up progresses.
+            assert_eq!(round.maxPrice, next.minPrice);
+            assert_eq!(round.rampUpStart + round.rampUpPeriod as u64, next.rampUpStart);
+            assert!(next.maxPrice - next.minPrice > round.maxPrice - round.minPrice);
+        }
+        // The curve stays below the linear ramp, and keeps the deadlines of the offer.
+        assert!(rounds[1].minPrice < offer().price_at(rounds[1].rampUpStart).unwrap());
+        for round in &rounds {
+            assert_eq!(round.lock_deadline(), offer().lock_deadline());
+            assert_eq!(round.deadline(), offer().deadline());
+        }
+    }
+
+    #[test]
+    fn test_stepped_schedule() {
+        let rounds = offer().schedule(PriceCurve::Stepped, 3).unwrap();
+        let prices: Vec<U256> = rounds.iter().map(|round| round.minPrice).collect();
+        assert_eq!(prices, [1_000, 1_500, 2_000].map(U256::from));
+        assert!(rounds.iter().all(|round| round.maxPrice == round.minPrice));
+        assert!(rounds.iter().all(|round| round.rampUpPeriod == 0));
+        assert_eq!(rounds[2].rampUpStart, 100 + 266);
+    }
+
+    #[test]
+    fn test_schedule_with_default_config() {
+        // The default offer starts from a min price of zero.
+        let config = OfferLayerConfig::default();
+        let offer = Offer {
+            minPrice: U256::ZERO,
+            maxPrice: U256::from(2_000),
+            rampUpStart: 100,
+            rampUpPeriod: config.ramp_up_period,
+            lockTimeout: config.lock_timeout,
+            timeout: config.timeout,
+            ..Default::default()
+        };
+        for curve in [PriceCurve::Exponential, PriceCurve::Stepped] {
+            let rounds = offer.schedule(curve, 4).unwrap();
+            assert_eq!(rounds.len(), 4);
+            assert_eq!(rounds[0].minPrice, U256::ZERO);
+            assert_eq!(rounds[3].maxPrice, U256::from(2_000));
+            for round in &rounds {
+                assert!(round.maxPrice > U256::ZERO, "{curve:?}: {round:?}");
+                assert!(round.maxPrice >= round.minPrice, "{curve:?}: {round:?}");
+                assert!(round.rampUpPeriod <= round.lockTimeout, "{curve:?}: {round:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_schedule_requires_ramp_up_before_lock_timeout() {
+        let offer = Offer { lockTimeout: 400, ..offer() };
+        assert!(offer.schedule(PriceCurve::Exponential, 4).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deployment of the Boundless Market contracts to a new chain.
+
+use std::borrow::Cow;
+
+use alloy::{
+    primitives::{Address, FixedBytes, B256},
+    providers::Provider,
+    sol_types::SolCall,
+};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use risc0_aggregation::SetInclusionReceiptVerifierParameters;
+use risc0_circuit_recursion::control_id::{ALLOWED_CONTROL_ROOT, BN254_IDENTITY_CONTROL_ID};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    Groth16ReceiptVerifierParameters,
+};
+
+use super::Deployment;
+use crate::contracts::{
+    bytecode::{
+        BoundlessMarket, ERC1967Proxy, HitPoints, RiscZeroGroth16Verifier, RiscZeroMockVerifier,
+        RiscZeroSetVerifier, RiscZeroVerifierRouter,
+    },
+    hit_points::HitPointsService,
+};
+
+/// Selector under which the [RiscZeroMockVerifier] is registered with the verifier router.
+const MOCK_VERIFIER_SELECTOR: [u8; 4] = [0xFF; 4];
+
+/// Verifier to register with the verifier router for Groth16 receipts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum VerifierKind {
+    /// A RiscZeroGroth16Verifier for the current release of the zkVM.
+    #[default]
+    Groth16,
+    /// A RiscZeroMockVerifier, which accepts fake receipts produced in dev mode.
+    ///
+    /// Only use this for local devnets and testing, as it does not check any proofs.
+    Mock,
+}
+
+/// Parameters for [bootstrap].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct BootstrapParams {
+    /// Owner of the deployed contracts, who is allowed to upgrade them and manage the router.
+    pub owner: Address,
+    /// Image ID of the set builder guest.
+    pub set_builder_image_id: B256,
+    /// URL the set builder guest can be downloaded from.
+    pub set_builder_url: String,
+    /// Image ID of the assessor guest.
+    pub assessor_image_id: B256,
+    /// URL the assessor guest can be downloaded from.
+    pub assessor_url: String,
+    /// Verifier to register with the verifier router for Groth16 receipts.
+    pub verifier: VerifierKind,
+    /// Address of an existing ERC-20 to use as the collateral token.
+    ///
+    /// If `None`, a HitPoints contract owned by [Self::owner] is deployed and used instead.
+    pub collateral_token: Option<Address>,
+    /// URL of the order stream service to set in the returned [Deployment].
+    pub order_stream_url: Option<String>,
+}
+
+impl BootstrapParams {
+    /// Create [BootstrapParams] with the given owner and guest programs.
+    pub fn new(
+        owner: Address,
+        set_builder_image_id: impl Into<Digest>,
+        set_builder_url: impl Into<String>,
+        assessor_image_id: impl Into<Digest>,
+        assessor_url: impl Into<String>,
+    ) -> Self {
+        Self {
+            owner,
+            set_builder_image_id: <[u8; 32]>::from(set_builder_image_id.into()).into(),
+            set_builder_url: set_builder_url.into(),
+            assessor_image_id: <[u8; 32]>::from(assessor_image_id.into()).into(),
+            assessor_url: assessor_url.into(),
+            verifier: VerifierKind::default(),
+            collateral_token: None,
+            order_stream_url: None,
+        }
+    }
+
+    /// Set the verifier to register with the verifier router for Groth16 receipts.
+    pub fn with_verifier(self, verifier: VerifierKind) -> Self {
+        Self { verifier, ..self }
+    }
+
+    /// Set an existing ERC-20 to use as the collateral token.
+    pub fn with_collateral_token(self, collateral_token: impl Into<Option<Address>>) -> Self {
+        Self { collateral_token: collateral_token.into(), ..self }
+    }
+
+    /// Set the URL of the order stream service to set in the returned [Deployment].
+    pub fn with_order_stream_url(self, order_stream_url: impl Into<Option<String>>) -> Self {
+        Self { order_stream_url: order_stream_url.into(), ..self }
+    }
+}
+
+/// Deploy the Boundless Market and its supporting contracts using the given provider.
+///
+/// This deploys a RiscZeroVerifierRouter, a Groth16 verifier (or a mock verifier) and a
+/// RiscZeroSetVerifier registered with the router, a collateral token unless one is given, and the
+/// BoundlessMarket behind an ERC-1967 proxy. The provider must have a wallet for
+/// [BootstrapParams::owner], which sends all deployment transactions.
+///
+/// ```rust,no_run
+/// # use alloy::{primitives::{Address, B256}, providers::ProviderBuilder};
+/// # async fn example(provider: impl alloy::providers::Provider + Clone) -> anyhow::Result<()> {
+/// use boundless_market::deployments::{bootstrap, BootstrapParams, VerifierKind};
+///
+/// let owner = Address::ZERO;
+/// let params = BootstrapParams::new(
+///     owner,
+///     B256::ZERO,
+///     "https://example.com/set-builder.bin",
+///     B256::ZERO,
+///     "https://example.com/assessor.bin",
+/// )
+/// .with_verifier(VerifierKind::Mock);
+/// let deployment = bootstrap(provider, params).await?;
+/// println!("BoundlessMarket deployed to {}", deployment.boundless_market_address);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn bootstrap<P: Provider + Clone>(
+    provider: P,
+    params: BootstrapParams,
+) -> Result<Deployment> {
+    let owner = params.owner;
+    let chain_id = provider.get_chain_id().await.context("failed to query chain ID")?;
+
+    let verifier_router = *RiscZeroVerifierRouter::deploy(&provider, owner)
+        .await
+        .context("failed to deploy RiscZeroVerifierRouter")?
+        .address();
+    tracing::info!("Deployed RiscZeroVerifierRouter to {verifier_router}");
+
+    let (groth16_verifier, groth16_selector) = match params.verifier {
+        VerifierKind::Mock => {
+            let verifier =
+                RiscZeroMockVerifier::deploy(&provider, FixedBytes(MOCK_VERIFIER_SELECTOR))
+                    .await
+                    .context("failed to deploy RiscZeroMockVerifier")?;
+            (*verifier.address(), MOCK_VERIFIER_SELECTOR)
+        }
+        VerifierKind::Groth16 => {
+            // Byte order in the contract is opposite that of Rust, because the EVM interprets the
+            // digest as a big-endian uint256.
+            let mut bn254_control_id = BN254_IDENTITY_CONTROL_ID;
+            bn254_control_id.as_mut_bytes().reverse();
+            let verifier = RiscZeroGroth16Verifier::deploy(
+                &provider,
+                <[u8; 32]>::from(ALLOWED_CONTROL_ROOT).into(),
+                <[u8; 32]>::from(bn254_control_id).into(),
+            )
+            .await
+            .context("failed to deploy RiscZeroGroth16Verifier")?;
+            (*verifier.address(), selector(Groth16ReceiptVerifierParameters::default().digest()))
+        }
+    };
+    tracing::info!("Deployed {:?} verifier to {groth16_verifier}", params.verifier);
+
+    let set_verifier = *RiscZeroSetVerifier::deploy(
+        &provider,
+        verifier_router,
+        params.set_builder_image_id,
+        params.set_builder_url,
+    )
+    .await
+    .context("failed to deploy RiscZeroSetVerifier")?
+    .address();
+    tracing::info!("Deployed RiscZeroSetVerifier to {set_verifier}");
+
+    let router = RiscZeroVerifierRouter::new(verifier_router, &provider);
+    router
+        .addVerifier(groth16_selector.into(), groth16_verifier)
+        .from(owner)
+        .send()
+        .await
+        .context("failed to send addVerifier transaction for the Groth16 verifier")?
+        .get_receipt()
+        .await
+        .context("failed to register the Groth16 verifier with the router")?;
+    let set_verifier_selector = selector(
+        SetInclusionReceiptVerifierParameters {
+            image_id: Digest::from(*params.set_builder_image_id),
+        }
+        .digest(),
+    );
+    router
+        .addVerifier(set_verifier_selector.into(), set_verifier)
+        .from(owner)
+        .send()
+        .await
+        .context("failed to send addVerifier transaction for the set verifier")?
+        .get_receipt()
+        .await
+        .context("failed to register the set verifier with the router")?;
+
+    let (collateral_token, deployed_hit_points) = match params.collateral_token {
+        Some(address) => (address, false),
+        None => {
+            let hit_points = *HitPoints::deploy(&provider, owner)
+                .await
+                .context("failed to deploy HitPoints")?
+                .address();
+            tracing::info!("Deployed HitPoints to {hit_points}");
+            (hit_points, true)
+        }
+    };
+
+    let market_implementation = *BoundlessMarket::deploy(
+        &provider,
+        verifier_router,
+        params.assessor_image_id,
+        B256::ZERO, // DEPRECATED_ASSESSOR_ID
+        0,          // DEPRECATED_ASSESSOR_DURATION
+        collateral_token,
+    )
+    .await
+    .context("failed to deploy BoundlessMarket implementation")?
+    .address();
+    let boundless_market = *ERC1967Proxy::deploy(
+        &provider,
+        market_implementation,
+        BoundlessMarket::initializeCall { initialOwner: owner, imageUrl: params.assessor_url }
+            .abi_encode()
+            .into(),
+    )
+    .await
+    .context("failed to deploy BoundlessMarket proxy")?
+    .address();
+    tracing::info!("Deployed BoundlessMarket to {boundless_market}");
+
+    if deployed_hit_points {
+        let hit_points = HitPointsService::new(collateral_token, &provider, owner);
+        hit_points.grant_minter_role(owner).await?;
+        hit_points.grant_authorized_transfer_role(boundless_market).await?;
+    }
+
+    Ok(Deployment {
+        chain_id: Some(chain_id),
+        boundless_market_address: boundless_market,
+        verifier_router_address: Some(verifier_router),
+        set_verifier_address: set_verifier,
+        collateral_token_address: Some(collateral_token),
+        order_stream_url: params.order_stream_url.map(Cow::Owned),
+        additional_order_stream_urls: Vec::new(),
+    })
+}
+
+/// Selector of a verifier, which is the first four bytes of its parameters digest.
+fn selector(parameters_digest: Digest) -> [u8; 4] {
+    parameters_digest.as_bytes()[..4].try_into().unwrap()
+}
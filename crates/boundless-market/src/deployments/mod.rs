@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::borrow::Cow;
+use std::{borrow::Cow, time::Duration};
 
 use alloy::primitives::{address, Address};
 use clap::Args;
@@ -20,6 +20,11 @@ use derive_builder::Builder;
 
 pub use alloy_chains::NamedChain;
 
+#[cfg(feature = "deploy")]
+mod bootstrap;
+#[cfg(feature = "deploy")]
+pub use bootstrap::{bootstrap, BootstrapParams, VerifierKind};
+
 /// Configuration for a deployment of the Boundless Market.
 // NOTE: See https://github.com/clap-rs/clap/issues/5092#issuecomment-1703980717 about clap usage.
 #[non_exhaustive]
@@ -73,6 +78,21 @@ pub struct Deployment {
     #[clap(long, env, long_help = "URL for the offchain order stream service")]
     #[builder(setter(into, strip_option), default)]
     pub order_stream_url: Option<Cow<'static, str>>,
+
+    /// URLs of additional [order stream services] that offchain requests are also submitted to.
+    ///
+    /// Submitting to several independently operated order streams keeps requests propagating to
+    /// provers when one of them is unavailable.
+    ///
+    /// [order stream services]: crate::order_stream_client
+    #[clap(
+        long,
+        env,
+        value_delimiter = ',',
+        long_help = "Comma-separated URLs of additional order stream services to submit offchain requests to"
+    )]
+    #[builder(setter(into), default)]
+    pub additional_order_stream_urls: Vec<Cow<'static, str>>,
 }
 
 impl Deployment {
@@ -102,6 +122,71 @@ impl Deployment {
     pub fn collateral_token_supports_permit(&self) -> bool {
         collateral_token_supports_permit(self.chain_id.unwrap())
     }
+
+    /// Returns the [ChainTiming] defaults of the chain of this deployment.
+    ///
+    /// Returns [ChainTiming::DEFAULT] if the [chain ID](Self::chain_id) is not set.
+    pub fn timing(&self) -> ChainTiming {
+        self.chain_id.map(ChainTiming::for_chain_id).unwrap_or(ChainTiming::DEFAULT)
+    }
+}
+
+/// Timing defaults of a chain, used where the user does not set them explicitly.
+///
+/// Values tuned for one chain are often wrong on another: a ramp-up period fitted to the 12
+/// second blocks of Sepolia is a hundred blocks on Base.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainTiming {
+    /// Average time between blocks.
+    pub block_time: Duration,
+    /// Recommended period, in seconds, for the price of an offer to ramp up from its min to its
+    /// max price.
+    pub ramp_up_period: u32,
+    /// Recommended number of blocks an event should be buried under before acting on it.
+    pub confirmations: u64,
+    /// Recommended time to wait for a transaction to be confirmed.
+    pub tx_timeout: Duration,
+}
+
+impl ChainTiming {
+    /// Timing for chains without known defaults, including local development chains.
+    pub const DEFAULT: ChainTiming = ChainTiming {
+        block_time: Duration::from_secs(12),
+        ramp_up_period: 60,
+        confirmations: 0,
+        tx_timeout: Duration::from_secs(45),
+    };
+
+    /// Timing of Ethereum and its testnets, with 12 second blocks.
+    pub const ETHEREUM: ChainTiming = ChainTiming {
+        block_time: Duration::from_secs(12),
+        ramp_up_period: 240,
+        confirmations: 2,
+        tx_timeout: Duration::from_secs(90),
+    };
+
+    /// Timing of Base and its testnets, with 2 second blocks.
+    pub const BASE: ChainTiming = ChainTiming {
+        block_time: Duration::from_secs(2),
+        ramp_up_period: 60,
+        confirmations: 10,
+        tx_timeout: Duration::from_secs(45),
+    };
+
+    /// Lookup the [ChainTiming] for a named chain, or [Self::DEFAULT] if it has none.
+    pub const fn for_chain(chain: NamedChain) -> ChainTiming {
+        match chain {
+            NamedChain::Mainnet | NamedChain::Sepolia => Self::ETHEREUM,
+            NamedChain::Base | NamedChain::BaseSepolia => Self::BASE,
+            _ => Self::DEFAULT,
+        }
+    }
+
+    /// Lookup the [ChainTiming] by chain ID, or [Self::DEFAULT] if it has none.
+    pub fn for_chain_id(chain_id: u64) -> ChainTiming {
+        NamedChain::try_from(chain_id).map(Self::for_chain).unwrap_or(Self::DEFAULT)
+    }
 }
 
 // TODO(#654): Ensure consistency with deployment.toml and with docs
@@ -113,6 +198,7 @@ pub const SEPOLIA: Deployment = Deployment {
     set_verifier_address: address!("0xcb9D14347b1e816831ECeE46EC199144F360B55c"),
     collateral_token_address: Some(address!("0xb4FC69A452D09D2662BD8C3B5BB756902260aE28")),
     order_stream_url: Some(Cow::Borrowed("https://eth-sepolia.boundless.network")),
+    additional_order_stream_urls: Vec::new(),
 };
 
 /// [Deployment] for the Base mainnet.
@@ -123,6 +209,7 @@ pub const BASE: Deployment = Deployment {
     set_verifier_address: address!("0x1Ab08498CfF17b9723ED67143A050c8E8c2e3104"),
     collateral_token_address: Some(address!("0xAA61bB7777bD01B684347961918f1E07fBbCe7CF")),
     order_stream_url: Some(Cow::Borrowed("https://base-mainnet.boundless.network")),
+    additional_order_stream_urls: Vec::new(),
 };
 
 /// [Deployment] for the Base Sepolia.
@@ -133,6 +220,7 @@ pub const BASE_SEPOLIA: Deployment = Deployment {
     set_verifier_address: address!("0x1Ab08498CfF17b9723ED67143A050c8E8c2e3104"),
     collateral_token_address: Some(address!("0x8d4dA4b7938471A919B08F941461b2ed1679d7bb")),
     order_stream_url: Some(Cow::Borrowed("https://base-sepolia.boundless.network")),
+    additional_order_stream_urls: Vec::new(),
 };
 
 /// Check if the collateral token supports permit.
@@ -0,0 +1,542 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Stable codes for the errors of the market SDK.
+//!
+//! Error messages are meant for humans, and change between releases. Each variant of
+//! [MarketError], [ClientError], [OrderStreamError], and [RequestError] instead maps to an
+//! [ErrorCode], with a numeric [code][ErrorCode::code] and a string [name][ErrorCode::as_str]
+//! that never change once released, so that integrators outside of Rust, e.g. reading the JSON
+//! output of the CLI, can branch on the failure.
+//!
+//! Codes are grouped by error type: 1xxx for [MarketError], 2xxx for [ClientError], 3xxx for
+//! [RequestError], and 4xxx for [OrderStreamError]. Reverts of the market contract decoded from a
+//! transaction error get the code of the matching error, e.g. `RequestIsLocked` is
+//! [ErrorCode::RequestIsLocked] and `InvalidSignature` is [ErrorCode::InvalidSignature]. New codes
+//! may be added, but existing codes are never renumbered or reused.
+//!
+//! ```
+//! use boundless_market::{contracts::boundless_market::MarketError, error_code::ErrorCode};
+//!
+//! let err = MarketError::RequestHasExpired(Default::default());
+//! assert_eq!(err.code(), ErrorCode::RequestHasExpired);
+//! assert_eq!(err.code().as_str(), "REQUEST_HAS_EXPIRED");
+//! assert_eq!(err.code().code(), 1004);
+//! ```
+//!
+//! [MarketError]: crate::contracts::boundless_market::MarketError
+//! [ClientError]: crate::client::ClientError
+//! [OrderStreamError]: crate::order_stream_client::OrderStreamError
+//! [RequestError]: crate::contracts::RequestError
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::ClientError,
+    contracts::{
+        boundless_market::MarketError, IBoundlessMarket::IBoundlessMarketErrors, RequestError,
+        TxnErr,
+    },
+    order_stream_client::OrderStreamError,
+};
+
+/// Stable code of an error of the market SDK.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[repr(u32)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// A market error not covered by another code.
+    MarketError = 1000,
+    /// A transaction failed, e.g. because it reverted.
+    TxnFailed = 1001,
+    /// A transaction was sent, but its confirmation failed.
+    TxnConfirmationFailed = 1002,
+    /// The request is not fulfilled.
+    RequestNotFulfilled = 1003,
+    /// The request has expired.
+    RequestHasExpired = 1004,
+    /// The prover of the request was slashed.
+    RequestIsSlashed = 1005,
+    /// The request has not expired yet.
+    RequestNotExpired = 1006,
+    /// The client address of the request does not match the signer.
+    AddressMismatch = 1007,
+    /// No proof of the request was found in the event logs.
+    ProofNotFound = 1008,
+    /// The request was not found in the event logs.
+    RequestNotFound = 1009,
+    /// No request with the given digest was found in the event logs.
+    RequestDigestNotFound = 1010,
+    /// The request is already locked.
+    RequestAlreadyLocked = 1011,
+    /// The lock transaction reverted, possibly because another prover locked the request first.
+    LockReverted = 1012,
+    /// The slash transaction reverted, possibly because the prover was already slashed.
+    SlashReverted = 1013,
+    /// An expected event was not emitted by a transaction.
+    LogNotEmitted = 1014,
+    /// Waiting for the request timed out.
+    Timeout = 1015,
+    /// The market balance does not cover the max price of the request.
+    InsufficientBalance = 1016,
+    /// The market reverted as the request is locked by another prover.
+    RequestIsLocked = 1017,
+    /// The market reverted as the request is neither locked nor priced.
+    RequestIsNotLockedOrPriced = 1018,
+    /// The market reverted as the request is not locked.
+    RequestIsNotLocked = 1019,
+    /// The market reverted as the request is already fulfilled.
+    RequestIsFulfilled = 1020,
+    /// The market reverted as the lock of the request has expired.
+    RequestLockIsExpired = 1021,
+    /// The market reverted as a token transfer failed.
+    TransferFailed = 1022,
+    /// The market reverted as the selector of the proof does not match the request.
+    SelectorMismatch = 1023,
+    /// The market reverted as the batch is larger than its limit.
+    BatchSizeExceedsLimit = 1024,
+    /// The market reverted as the callback of the request can not be fulfilled.
+    UnfulfillableCallback = 1025,
+
+    /// A client error not covered by another code.
+    ClientError = 2000,
+    /// The storage provider failed to upload the program or input.
+    StorageProviderFailed = 2001,
+    /// An offchain submission was requested, but no order stream is configured.
+    OrderStreamNotConfigured = 2002,
+    /// Submitting the request would exceed the budget of the client.
+    BudgetExceeded = 2003,
+    /// The request could not be built.
+    RequestBuildFailed = 2004,
+
+    /// The request is invalid, as reported by the market.
+    InvalidRequest = 3000,
+    /// The request ID is malformed.
+    MalformedRequestId = 3001,
+    /// The client address of the request ID is all zeroes.
+    ClientAddressIsZero = 3002,
+    /// The signature of the request is invalid.
+    InvalidSignature = 3003,
+    /// The image URL of the request is empty.
+    EmptyImageUrl = 3004,
+    /// The image URL of the request is malformed.
+    MalformedImageUrl = 3005,
+    /// The image ID of the request is zero.
+    ImageIdIsZero = 3006,
+    /// The timeout of the offer is zero.
+    OfferTimeoutIsZero = 3007,
+    /// The lock timeout of the offer is zero.
+    OfferLockTimeoutIsZero = 3008,
+    /// The ramp-up period of the offer is longer than its lock timeout.
+    OfferRampUpGreaterThanLockTimeout = 3009,
+    /// The lock timeout of the offer is longer than its timeout.
+    OfferLockTimeoutGreaterThanTimeout = 3010,
+    /// The timeout of the offer exceeds its lock timeout by 2^24 seconds or more.
+    OfferTimeoutRangeTooLarge = 3011,
+    /// The max price of the offer is zero.
+    OfferMaxPriceIsZero = 3012,
+    /// The max price of the offer is less than its min price.
+    OfferMaxPriceIsLessThanMin = 3013,
+    /// The ramp-up start of the offer is zero.
+    OfferRampUpStartIsZero = 3014,
+    /// The requirements are missing from the request.
+    MissingRequirements = 3015,
+    /// The image URL is missing from the request.
+    MissingImageUrl = 3016,
+    /// The input is missing from the request.
+    MissingInput = 3017,
+    /// The offer is missing from the request.
+    MissingOffer = 3018,
+    /// The request ID is missing from the request.
+    MissingRequestId = 3019,
+    /// The digest of the request does not match.
+    DigestMismatch = 3020,
+    /// The predicate of the request is invalid.
+    InvalidPredicate = 3021,
+
+    /// The order failed validation, or could not be signed, before it was sent.
+    InvalidOrder = 4000,
+    /// The order stream rejected the order as invalid.
+    OrderRejected = 4001,
+    /// The order stream already holds the order.
+    OrderReplayed = 4002,
+    /// The request expired before the order stream accepted it.
+    OrderExpired = 4003,
+    /// The order stream is rate limiting the client.
+    RateLimited = 4004,
+    /// The order stream failed to process the request.
+    OrderStreamServerError = 4005,
+    /// The order stream could not be reached.
+    OrderStreamUnavailable = 4006,
+    /// The order stream URL is invalid.
+    InvalidOrderStreamUrl = 4007,
+    /// The ID of the request was already used onchain.
+    RequestIdUsed = 4008,
+}
+
+impl ErrorCode {
+    /// Numeric code, e.g. for an FFI layer.
+    pub const fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// String code, as serialized in JSON.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::MarketError => "MARKET_ERROR",
+            Self::TxnFailed => "TXN_FAILED",
+            Self::TxnConfirmationFailed => "TXN_CONFIRMATION_FAILED",
+            Self::RequestNotFulfilled => "REQUEST_NOT_FULFILLED",
+            Self::RequestHasExpired => "REQUEST_HAS_EXPIRED",
+            Self::RequestIsSlashed => "REQUEST_IS_SLASHED",
+            Self::RequestNotExpired => "REQUEST_NOT_EXPIRED",
+            Self::AddressMismatch => "ADDRESS_MISMATCH",
+            Self::ProofNotFound => "PROOF_NOT_FOUND",
+            Self::RequestNotFound => "REQUEST_NOT_FOUND",
+            Self::RequestDigestNotFound => "REQUEST_DIGEST_NOT_FOUND",
+            Self::RequestAlreadyLocked => "REQUEST_ALREADY_LOCKED",
+            Self::LockReverted => "LOCK_REVERTED",
+            Self::SlashReverted => "SLASH_REVERTED",
+            Self::LogNotEmitted => "LOG_NOT_EMITTED",
+            Self::Timeout => "TIMEOUT",
+            Self::InsufficientBalance => "INSUFFICIENT_BALANCE",
+            Self::RequestIsLocked => "REQUEST_IS_LOCKED",
+            Self::RequestIsNotLockedOrPriced => "REQUEST_IS_NOT_LOCKED_OR_PRICED",
+            Self::RequestIsNotLocked => "REQUEST_IS_NOT_LOCKED",
+            Self::RequestIsFulfilled => "REQUEST_IS_FULFILLED",
+            Self::RequestLockIsExpired => "REQUEST_LOCK_IS_EXPIRED",
+            Self::TransferFailed => "TRANSFER_FAILED",
+            Self::SelectorMismatch => "SELECTOR_MISMATCH",
+            Self::BatchSizeExceedsLimit => "BATCH_SIZE_EXCEEDS_LIMIT",
+            Self::UnfulfillableCallback => "UNFULFILLABLE_CALLBACK",
+            Self::ClientError => "CLIENT_ERROR",
+            Self::StorageProviderFailed => "STORAGE_PROVIDER_FAILED",
+            Self::OrderStreamNotConfigured => "ORDER_STREAM_NOT_CONFIGURED",
+            Self::BudgetExceeded => "BUDGET_EXCEEDED",
+            Self::RequestBuildFailed => "REQUEST_BUILD_FAILED",
+            Self::InvalidRequest => "INVALID_REQUEST",
+            Self::MalformedRequestId => "MALFORMED_REQUEST_ID",
+            Self::ClientAddressIsZero => "CLIENT_ADDRESS_IS_ZERO",
+            Self::InvalidSignature => "INVALID_SIGNATURE",
+            Self::EmptyImageUrl => "EMPTY_IMAGE_URL",
+            Self::MalformedImageUrl => "MALFORMED_IMAGE_URL",
+            Self::ImageIdIsZero => "IMAGE_ID_IS_ZERO",
+            Self::OfferTimeoutIsZero => "OFFER_TIMEOUT_IS_ZERO",
+            Self::OfferLockTimeoutIsZero => "OFFER_LOCK_TIMEOUT_IS_ZERO",
+            Self::OfferRampUpGreaterThanLockTimeout => "OFFER_RAMP_UP_GREATER_THAN_LOCK_TIMEOUT",
+            Self::OfferLockTimeoutGreaterThanTimeout => "OFFER_LOCK_TIMEOUT_GREATER_THAN_TIMEOUT",
+            Self::OfferTimeoutRangeTooLarge => "OFFER_TIMEOUT_RANGE_TOO_LARGE",
+            Self::OfferMaxPriceIsZero => "OFFER_MAX_PRICE_IS_ZERO",
+            Self::OfferMaxPriceIsLessThanMin => "OFFER_MAX_PRICE_IS_LESS_THAN_MIN",
+            Self::OfferRampUpStartIsZero => "OFFER_RAMP_UP_START_IS_ZERO",
+            Self::MissingRequirements => "MISSING_REQUIREMENTS",
+            Self::MissingImageUrl => "MISSING_IMAGE_URL",
+            Self::MissingInput => "MISSING_INPUT",
+            Self::MissingOffer => "MISSING_OFFER",
+            Self::MissingRequestId => "MISSING_REQUEST_ID",
+            Self::DigestMismatch => "DIGEST_MISMATCH",
+            Self::InvalidPredicate => "INVALID_PREDICATE",
+            Self::InvalidOrder => "INVALID_ORDER",
+            Self::OrderRejected => "ORDER_REJECTED",
+            Self::OrderReplayed => "ORDER_REPLAYED",
+            Self::OrderExpired => "ORDER_EXPIRED",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::OrderStreamServerError => "ORDER_STREAM_SERVER_ERROR",
+            Self::OrderStreamUnavailable => "ORDER_STREAM_UNAVAILABLE",
+            Self::InvalidOrderStreamUrl => "INVALID_ORDER_STREAM_URL",
+            Self::RequestIdUsed => "REQUEST_ID_USED",
+        }
+    }
+
+    /// Code of the first error of the market SDK in the chain of the given error, if any.
+    pub fn of(err: &anyhow::Error) -> Option<Self> {
+        err.chain().find_map(|err| {
+            if let Some(err) = err.downcast_ref::<ClientError>() {
+                Some(err.code())
+            } else if let Some(err) = err.downcast_ref::<MarketError>() {
+                Some(err.code())
+            } else if let Some(err) = err.downcast_ref::<OrderStreamError>() {
+                Some(err.code())
+            } else {
+                err.downcast_ref::<RequestError>().map(RequestError::code)
+            }
+        })
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl MarketError {
+    /// Stable code of the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::TxnError(err) => err.code(),
+            Self::TxnConfirmationError(_) => ErrorCode::TxnConfirmationFailed,
+            Self::RequestNotFulfilled(_) => ErrorCode::RequestNotFulfilled,
+            Self::RequestHasExpired(_) => ErrorCode::RequestHasExpired,
+            Self::RequestIsSlashed(_) => ErrorCode::RequestIsSlashed,
+            Self::RequestNotExpired(_, _) => ErrorCode::RequestNotExpired,
+            Self::RequestError(err) => err.code(),
+            Self::AddressMismatch(_, _) => ErrorCode::AddressMismatch,
+            Self::ProofNotFound(_) => ErrorCode::ProofNotFound,
+            Self::RequestNotFound(_) => ErrorCode::RequestNotFound,
+            Self::RequestDigestNotFound(_) => ErrorCode::RequestDigestNotFound,
+            Self::RequestAlreadyLocked(_) => ErrorCode::RequestAlreadyLocked,
+            Self::LockRevert(_) => ErrorCode::LockReverted,
+            Self::SlashRevert(_) => ErrorCode::SlashReverted,
+            Self::LogNotEmitted(_, _) => ErrorCode::LogNotEmitted,
+            // Contract calls wrap their transaction error.
+            Self::Error(err) => {
+                err.downcast_ref::<TxnErr>().map_or(ErrorCode::MarketError, TxnErr::code)
+            }
+            Self::TimeoutReached(_) => ErrorCode::Timeout,
+            Self::InsufficientBalance(_, _) => ErrorCode::InsufficientBalance,
+        }
+    }
+}
+
+impl TxnErr {
+    /// Stable code of the error, that of the revert for decoded reverts of the market.
+    pub fn code(&self) -> ErrorCode {
+        let Self::BoundlessMarketErr(err) = self else {
+            return ErrorCode::TxnFailed;
+        };
+        match err {
+            IBoundlessMarketErrors::RequestIsLocked(_) => ErrorCode::RequestIsLocked,
+            IBoundlessMarketErrors::RequestIsNotLockedOrPriced(_) => {
+                ErrorCode::RequestIsNotLockedOrPriced
+            }
+            IBoundlessMarketErrors::RequestIsNotLocked(_) => ErrorCode::RequestIsNotLocked,
+            IBoundlessMarketErrors::RequestIsFulfilled(_) => ErrorCode::RequestIsFulfilled,
+            IBoundlessMarketErrors::RequestIsSlashed(_) => ErrorCode::RequestIsSlashed,
+            IBoundlessMarketErrors::RequestLockIsExpired(_) => ErrorCode::RequestLockIsExpired,
+            IBoundlessMarketErrors::RequestIsExpired(_) => ErrorCode::RequestHasExpired,
+            IBoundlessMarketErrors::RequestIsNotExpired(_) => ErrorCode::RequestNotExpired,
+            IBoundlessMarketErrors::InsufficientBalance(_) => ErrorCode::InsufficientBalance,
+            IBoundlessMarketErrors::InvalidSignature(_) => ErrorCode::InvalidSignature,
+            IBoundlessMarketErrors::InvalidRequest(_) => ErrorCode::InvalidRequest,
+            IBoundlessMarketErrors::TransferFailed(_) => ErrorCode::TransferFailed,
+            IBoundlessMarketErrors::SelectorMismatch(_) => ErrorCode::SelectorMismatch,
+            IBoundlessMarketErrors::BatchSizeExceedsLimit(_) => ErrorCode::BatchSizeExceedsLimit,
+            IBoundlessMarketErrors::UnfulfillableCallback(_) => ErrorCode::UnfulfillableCallback,
+            #[allow(unreachable_patterns)]
+            _ => ErrorCode::TxnFailed,
+        }
+    }
+}
+
+impl ClientError {
+    /// Stable code of the error, that of the wrapped error for market, request, and order stream
+    /// errors.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::StorageProviderError(_) => ErrorCode::StorageProviderFailed,
+            Self::MarketError(err) => err.code(),
+            Self::RequestError(err) => err.code(),
+            Self::OrderStreamError(err) => err.code(),
+            Self::OrderStreamNotConfigured => ErrorCode::OrderStreamNotConfigured,
+            Self::BudgetExceeded(_) => ErrorCode::BudgetExceeded,
+            Self::BuilderError(_) => ErrorCode::RequestBuildFailed,
+            Self::Error(_) => ErrorCode::ClientError,
+        }
+    }
+}
+
+impl OrderStreamError {
+    /// Stable code of the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidOrder(_) => ErrorCode::InvalidOrder,
+            Self::Rejected { .. } => ErrorCode::OrderRejected,
+            Self::Replayed { .. } => ErrorCode::OrderReplayed,
+            Self::RequestIdUsed { .. } => ErrorCode::RequestIdUsed,
+            Self::Expired { .. } => ErrorCode::OrderExpired,
+            Self::RateLimited { .. } => ErrorCode::RateLimited,
+            Self::Server { .. } => ErrorCode::OrderStreamServerError,
+            Self::Transport(_) => ErrorCode::OrderStreamUnavailable,
+            Self::InvalidUrl(_) => ErrorCode::InvalidOrderStreamUrl,
+        }
+    }
+}
+
+impl RequestError {
+    /// Stable code of the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::MalformedRequestId => ErrorCode::MalformedRequestId,
+            Self::ClientAddrIsZero => ErrorCode::ClientAddressIsZero,
+            Self::SignatureError(_) => ErrorCode::InvalidSignature,
+            Self::EmptyImageUrl => ErrorCode::EmptyImageUrl,
+            Self::MalformedImageUrl(_) => ErrorCode::MalformedImageUrl,
+            Self::ImageIdIsZero => ErrorCode::ImageIdIsZero,
+            Self::OfferTimeoutIsZero => ErrorCode::OfferTimeoutIsZero,
+            Self::OfferLockTimeoutIsZero => ErrorCode::OfferLockTimeoutIsZero,
+            Self::OfferRampUpGreaterThanLockTimeout => ErrorCode::OfferRampUpGreaterThanLockTimeout,
+            Self::OfferLockTimeoutGreaterThanTimeout => {
+                ErrorCode::OfferLockTimeoutGreaterThanTimeout
+            }
+            Self::OfferTimeoutRangeTooLarge => ErrorCode::OfferTimeoutRangeTooLarge,
+            Self::OfferMaxPriceIsZero => ErrorCode::OfferMaxPriceIsZero,
+            Self::OfferMaxPriceIsLessThanMin => ErrorCode::OfferMaxPriceIsLessThanMin,
+            Self::OfferRampUpStartIsZero => ErrorCode::OfferRampUpStartIsZero,
+            Self::MissingRequirements => ErrorCode::MissingRequirements,
+            Self::MissingImageUrl => ErrorCode::MissingImageUrl,
+            Self::MissingInput => ErrorCode::MissingInput,
+            Self::MissingOffer => ErrorCode::MissingOffer,
+            Self::MissingRequestId => ErrorCode::MissingRequestId,
+            Self::DigestMismatch => ErrorCode::DigestMismatch,
+            Self::PredicateError(_) => ErrorCode::InvalidPredicate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+    use anyhow::Context;
+
+    use super::*;
+    use crate::contracts::IBoundlessMarket;
+
+    #[test]
+    fn codes_are_stable() {
+        // Released codes and their strings must never change.
+        let codes = [
+            (ErrorCode::MarketError, 1000, "MARKET_ERROR"),
+            (ErrorCode::TxnFailed, 1001, "TXN_FAILED"),
+            (ErrorCode::TxnConfirmationFailed, 1002, "TXN_CONFIRMATION_FAILED"),
+            (ErrorCode::RequestNotFulfilled, 1003, "REQUEST_NOT_FULFILLED"),
+            (ErrorCode::RequestHasExpired, 1004, "REQUEST_HAS_EXPIRED"),
+            (ErrorCode::RequestIsSlashed, 1005, "REQUEST_IS_SLASHED"),
+            (ErrorCode::RequestNotExpired, 1006, "REQUEST_NOT_EXPIRED"),
+            (ErrorCode::AddressMismatch, 1007, "ADDRESS_MISMATCH"),
+            (ErrorCode::ProofNotFound, 1008, "PROOF_NOT_FOUND"),
+            (ErrorCode::RequestNotFound, 1009, "REQUEST_NOT_FOUND"),
+            (ErrorCode::RequestDigestNotFound, 1010, "REQUEST_DIGEST_NOT_FOUND"),
+            (ErrorCode::RequestAlreadyLocked, 1011, "REQUEST_ALREADY_LOCKED"),
+            (ErrorCode::LockReverted, 1012, "LOCK_REVERTED"),
+            (ErrorCode::SlashReverted, 1013, "SLASH_REVERTED"),
+            (ErrorCode::LogNotEmitted, 1014, "LOG_NOT_EMITTED"),
+            (ErrorCode::Timeout, 1015, "TIMEOUT"),
+            (ErrorCode::InsufficientBalance, 1016, "INSUFFICIENT_BALANCE"),
+            (ErrorCode::RequestIsLocked, 1017, "REQUEST_IS_LOCKED"),
+            (ErrorCode::RequestIsNotLockedOrPriced, 1018, "REQUEST_IS_NOT_LOCKED_OR_PRICED"),
+            (ErrorCode::RequestIsNotLocked, 1019, "REQUEST_IS_NOT_LOCKED"),
+            (ErrorCode::RequestIsFulfilled, 1020, "REQUEST_IS_FULFILLED"),
+            (ErrorCode::RequestLockIsExpired, 1021, "REQUEST_LOCK_IS_EXPIRED"),
+            (ErrorCode::TransferFailed, 1022, "TRANSFER_FAILED"),
+            (ErrorCode::SelectorMismatch, 1023, "SELECTOR_MISMATCH"),
+            (ErrorCode::BatchSizeExceedsLimit, 1024, "BATCH_SIZE_EXCEEDS_LIMIT"),
+            (ErrorCode::UnfulfillableCallback, 1025, "UNFULFILLABLE_CALLBACK"),
+            (ErrorCode::ClientError, 2000, "CLIENT_ERROR"),
+            (ErrorCode::StorageProviderFailed, 2001, "STORAGE_PROVIDER_FAILED"),
+            (ErrorCode::OrderStreamNotConfigured, 2002, "ORDER_STREAM_NOT_CONFIGURED"),
+            (ErrorCode::BudgetExceeded, 2003, "BUDGET_EXCEEDED"),
+            (ErrorCode::RequestBuildFailed, 2004, "REQUEST_BUILD_FAILED"),
+            (ErrorCode::InvalidRequest, 3000, "INVALID_REQUEST"),
+            (ErrorCode::MalformedRequestId, 3001, "MALFORMED_REQUEST_ID"),
+            (ErrorCode::ClientAddressIsZero, 3002, "CLIENT_ADDRESS_IS_ZERO"),
+            (ErrorCode::InvalidSignature, 3003, "INVALID_SIGNATURE"),
+            (ErrorCode::EmptyImageUrl, 3004, "EMPTY_IMAGE_URL"),
+            (ErrorCode::MalformedImageUrl, 3005, "MALFORMED_IMAGE_URL"),
+            (ErrorCode::ImageIdIsZero, 3006, "IMAGE_ID_IS_ZERO"),
+            (ErrorCode::OfferTimeoutIsZero, 3007, "OFFER_TIMEOUT_IS_ZERO"),
+            (ErrorCode::OfferLockTimeoutIsZero, 3008, "OFFER_LOCK_TIMEOUT_IS_ZERO"),
+            (
+                ErrorCode::OfferRampUpGreaterThanLockTimeout,
+                3009,
+                "OFFER_RAMP_UP_GREATER_THAN_LOCK_TIMEOUT",
+            ),
+            (
+                ErrorCode::OfferLockTimeoutGreaterThanTimeout,
+                3010,
+                "OFFER_LOCK_TIMEOUT_GREATER_THAN_TIMEOUT",
+            ),
+            (ErrorCode::OfferTimeoutRangeTooLarge, 3011, "OFFER_TIMEOUT_RANGE_TOO_LARGE"),
+            (ErrorCode::OfferMaxPriceIsZero, 3012, "OFFER_MAX_PRICE_IS_ZERO"),
+            (ErrorCode::OfferMaxPriceIsLessThanMin, 3013, "OFFER_MAX_PRICE_IS_LESS_THAN_MIN"),
+            (ErrorCode::OfferRampUpStartIsZero, 3014, "OFFER_RAMP_UP_START_IS_ZERO"),
+            (ErrorCode::MissingRequirements, 3015, "MISSING_REQUIREMENTS"),
+            (ErrorCode::MissingImageUrl, 3016, "MISSING_IMAGE_URL"),
+            (ErrorCode::MissingInput, 3017, "MISSING_INPUT"),
+            (ErrorCode::MissingOffer, 3018, "MISSING_OFFER"),
+            (ErrorCode::MissingRequestId, 3019, "MISSING_REQUEST_ID"),
+            (ErrorCode::DigestMismatch, 3020, "DIGEST_MISMATCH"),
+            (ErrorCode::InvalidPredicate, 3021, "INVALID_PREDICATE"),
+            (ErrorCode::InvalidOrder, 4000, "INVALID_ORDER"),
+            (ErrorCode::OrderRejected, 4001, "ORDER_REJECTED"),
+            (ErrorCode::OrderReplayed, 4002, "ORDER_REPLAYED"),
+            (ErrorCode::OrderExpired, 4003, "ORDER_EXPIRED"),
+            (ErrorCode::RateLimited, 4004, "RATE_LIMITED"),
+            (ErrorCode::OrderStreamServerError, 4005, "ORDER_STREAM_SERVER_ERROR"),
+            (ErrorCode::OrderStreamUnavailable, 4006, "ORDER_STREAM_UNAVAILABLE"),
+            (ErrorCode::InvalidOrderStreamUrl, 4007, "INVALID_ORDER_STREAM_URL"),
+            (ErrorCode::RequestIdUsed, 4008, "REQUEST_ID_USED"),
+        ];
+        for (code, numeric, string) in codes {
+            assert_eq!(code.code(), numeric, "{code:?}");
+            assert_eq!(code.as_str(), string, "{code:?}");
+            // The string code is the one serialized.
+            assert_eq!(serde_json::to_value(code).unwrap(), string);
+        }
+    }
+
+    #[test]
+    fn request_errors_have_distinct_codes() {
+        assert_eq!(RequestError::MalformedRequestId.code(), ErrorCode::MalformedRequestId);
+        assert_eq!(RequestError::OfferMaxPriceIsZero.code(), ErrorCode::OfferMaxPriceIsZero);
+        assert_eq!(RequestError::MissingInput.code(), ErrorCode::MissingInput);
+        assert_eq!(
+            ClientError::from(RequestError::DigestMismatch).code(),
+            ErrorCode::DigestMismatch
+        );
+    }
+
+    #[test]
+    fn market_reverts_have_their_own_codes() {
+        let revert = IBoundlessMarketErrors::RequestIsLocked(IBoundlessMarket::RequestIsLocked {
+            requestId: U256::from(1),
+        });
+        let err = MarketError::from(TxnErr::BoundlessMarketErr(revert));
+        assert_eq!(err.code(), ErrorCode::RequestIsLocked);
+
+        // Contract calls wrap the transaction error in an anyhow error.
+        let revert =
+            IBoundlessMarketErrors::InsufficientBalance(IBoundlessMarket::InsufficientBalance {
+                account: Default::default(),
+            });
+        let err = MarketError::Error(TxnErr::BoundlessMarketErr(revert).into());
+        assert_eq!(err.code(), ErrorCode::InsufficientBalance);
+
+        let err = MarketError::from(TxnErr::BytesDecode);
+        assert_eq!(err.code(), ErrorCode::TxnFailed);
+    }
+
+    #[test]
+    fn code_of_error_chain() {
+        let err = ClientError::from(MarketError::RequestAlreadyLocked(U256::from(1)));
+        assert_eq!(err.code(), ErrorCode::RequestAlreadyLocked);
+
+        let err = Err::<(), _>(err).context("failed to lock").unwrap_err();
+        assert_eq!(ErrorCode::of(&err), Some(ErrorCode::RequestAlreadyLocked));
+        assert_eq!(ErrorCode::of(&anyhow::anyhow!("something went wrong")), None);
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-process bus of the notable events of a [Client].
+//!
+//! An [EventBus] set on the [ClientBuilder] with [ClientBuilder::with_event_bus] receives the
+//! transactions sent and confirmed by the client, the submissions, fulfillments, and failures of
+//! its requests, and the balance alerts of its account. Any number of consumers, e.g. metrics, a
+//! UI, and logging, can [subscribe][EventBus::subscribe] to it, each receiving every [BusEvent]
+//! published after it subscribed.
+//!
+//! Events are published on a [broadcast] channel of bounded capacity, so publishing never blocks
+//! the client. A consumer that falls behind by more than the capacity misses the oldest events,
+//! and is told how many with [broadcast::error::RecvError::Lagged].
+//!
+//! ```no_run
+//! # async fn example(rpc_url: url::Url) -> anyhow::Result<()> {
+//! use boundless_market::{event_bus::{BusEvent, EventBus}, Client};
+//!
+//! let events = EventBus::default();
+//! let mut receiver = events.subscribe();
+//! let client = Client::builder().with_rpc_url(rpc_url).with_event_bus(events).build().await?;
+//!
+//! tokio::spawn(async move {
+//!     while let Ok(event) = receiver.recv().await {
+//!         if let BusEvent::TxSent { tx_hash } = event {
+//!             println!("sent {tx_hash}");
+//!         }
+//!     }
+//! });
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [Client]: crate::Client
+//! [ClientBuilder]: crate::client::ClientBuilder
+//! [ClientBuilder::with_event_bus]: crate::client::ClientBuilder::with_event_bus
+
+use alloy::primitives::{Address, B256, U256};
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use crate::{
+    client::SubmissionPath,
+    middleware::{ClientEvent, Middleware},
+};
+
+/// Default number of events an [EventBus] buffers for its slowest subscriber.
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 256;
+
+/// Asset whose balance triggered a [BusEvent::BalanceAlert].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BalanceAsset {
+    /// ETH held by the account, used to pay for gas.
+    Eth,
+    /// Collateral deposited by the account in the market.
+    Collateral,
+}
+
+/// Severity of a [BusEvent::BalanceAlert].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AlertLevel {
+    /// The balance is below the warning threshold.
+    Warn,
+    /// The balance is below the error threshold.
+    Error,
+}
+
+/// Notable event of a [Client][crate::Client], published on an [EventBus].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BusEvent {
+    /// A transaction was broadcast.
+    TxSent {
+        /// Hash of the transaction.
+        tx_hash: B256,
+    },
+    /// A transaction sent by the market service was confirmed.
+    TxConfirmed {
+        /// Hash of the transaction.
+        tx_hash: B256,
+    },
+    /// A request was submitted.
+    RequestSubmitted {
+        /// ID of the request.
+        request_id: U256,
+        /// Whether the request was submitted offchain or onchain.
+        path: SubmissionPath,
+        /// Max price of the request, in wei.
+        max_price: U256,
+    },
+    /// A request was fulfilled.
+    RequestFulfilled {
+        /// ID of the request.
+        request_id: U256,
+    },
+    /// Submitting a request, or waiting for its fulfillment, failed.
+    RequestFailed {
+        /// ID of the request.
+        request_id: U256,
+        /// The error returned to the caller.
+        error: String,
+    },
+    /// The balance of the account fell below a configured threshold.
+    BalanceAlert {
+        /// Address of the account.
+        address: Address,
+        /// Asset whose balance is low.
+        asset: BalanceAsset,
+        /// Balance of the account, in wei or base units of the collateral token.
+        balance: U256,
+        /// Threshold the balance fell below.
+        level: AlertLevel,
+    },
+}
+
+/// Broadcast channel of the [BusEvent]s of a client.
+///
+/// Clones publish to, and subscribe from, the same channel.
+#[derive(Clone, Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<BusEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// Creates a bus buffering up to `capacity` events for its slowest subscriber.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Subscribes to the events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Number of current subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Publishes an event to the current subscribers. Events published while there are none are
+    /// dropped.
+    pub fn publish(&self, event: BusEvent) {
+        // Sending only fails if there are no subscribers, which is not an error for the client.
+        let _ = self.sender.send(event);
+    }
+}
+
+/// Publishes the request events of the client, which reach the bus through its middleware.
+#[async_trait]
+impl Middleware for EventBus {
+    async fn on_event(&self, event: &ClientEvent<'_>) {
+        let event = match event {
+            ClientEvent::Submitted { request, path } => BusEvent::RequestSubmitted {
+                request_id: request.id,
+                path: *path,
+                max_price: request.offer.maxPrice,
+            },
+            ClientEvent::Fulfilled { request_id, .. } => {
+                BusEvent::RequestFulfilled { request_id: *request_id }
+            }
+            ClientEvent::Failed { request_id, error } => {
+                BusEvent::RequestFailed { request_id: *request_id, error: error.to_string() }
+            }
+        };
+        self.publish(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::{Offer, ProofRequest};
+
+    #[tokio::test]
+    async fn subscribers_receive_events() {
+        let bus = EventBus::new(4);
+        // Events published before anyone subscribes are dropped.
+        bus.publish(BusEvent::TxSent { tx_hash: B256::ZERO });
+
+        let (mut first, mut second) = (bus.subscribe(), bus.subscribe());
+        assert_eq!(bus.subscriber_count(), 2);
+        let request = ProofRequest {
+            id: U256::from(7),
+            offer: Offer { maxPrice: U256::from(10), ..Default::default() },
+            ..Default::default()
+        };
+        bus.on_event(&ClientEvent::Submitted { request: &request, path: SubmissionPath::Onchain })
+            .await;
+
+        let expected = BusEvent::RequestSubmitted {
+            request_id: U256::from(7),
+            path: SubmissionPath::Onchain,
+            max_price: U256::from(10),
+        };
+        assert_eq!(first.recv().await.unwrap(), expected);
+        assert_eq!(second.recv().await.unwrap(), expected);
+        assert!(first.try_recv().is_err());
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt,
+    future::Future,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{TransportError, TransportErrorKind, TransportFut},
+};
+use anyhow::{anyhow, bail, ensure};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tower::{Layer, Service};
+
+/// Maximum delay of [FaultRates] that set a delay rate without a maximum delay.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// HTTP status of injected errors, which RPC retry policies treat as a transient failure.
+const INJECTED_ERROR_STATUS: u16 = 503;
+
+/// Probabilities of the faults injected into each call by a [FaultInjector].
+///
+/// Parsed from a comma-separated list of `key=value` pairs, e.g.
+/// `error=0.05,drop=0.01,delay=0.2,max_delay=3`:
+/// * `error`: fraction of calls that fail without being sent.
+/// * `drop`: fraction of calls that are sent, but whose response is lost.
+/// * `delay`: fraction of calls that are sent after a random delay.
+/// * `max_delay`: maximum delay in seconds, 5 by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct FaultRates {
+    /// Fraction of calls that fail without being sent.
+    pub error: f64,
+    /// Fraction of calls that are sent, but whose response is lost.
+    pub drop: f64,
+    /// Fraction of calls that are sent after a random delay of up to `max_delay`.
+    pub delay: f64,
+    /// Maximum delay of delayed calls.
+    pub max_delay: Duration,
+}
+
+impl FaultRates {
+    /// Creates fault rates, checking that they are valid probabilities that sum to at most 1.
+    pub fn new(error: f64, drop: f64, delay: f64, max_delay: Duration) -> anyhow::Result<Self> {
+        for (name, rate) in [("error", error), ("drop", drop), ("delay", delay)] {
+            ensure!((0.0..=1.0).contains(&rate), "{name} rate must be between 0 and 1");
+        }
+        ensure!(error + drop + delay <= 1.0, "fault rates must sum to at most 1");
+        Ok(Self { error, drop, delay, max_delay })
+    }
+}
+
+impl FromStr for FaultRates {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut error, mut drop, mut delay, mut max_delay) = (0.0, 0.0, 0.0, DEFAULT_MAX_DELAY);
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) =
+                pair.split_once('=').ok_or_else(|| anyhow!("expected key=value, got {pair}"))?;
+            let value: f64 =
+                value.trim().parse().map_err(|_| anyhow!("invalid value for {key}: {value}"))?;
+            match key.trim() {
+                "error" => error = value,
+                "drop" => drop = value,
+                "delay" => delay = value,
+                "max_delay" => {
+                    max_delay = Duration::try_from_secs_f64(value)
+                        .map_err(|_| anyhow!("invalid max_delay: {value}"))?
+                }
+                key => bail!("unknown fault {key}; expected error, drop, delay, or max_delay"),
+            }
+        }
+        Self::new(error, drop, delay, max_delay)
+    }
+}
+
+impl fmt::Display for FaultRates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error={},drop={},delay={},max_delay={}",
+            self.error,
+            self.drop,
+            self.delay,
+            self.max_delay.as_secs_f64()
+        )
+    }
+}
+
+/// A fault drawn by a [FaultInjector] for a call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Fault {
+    /// Fail the call without sending it.
+    Error,
+    /// Send the call, but lose its response.
+    Drop,
+    /// Send the call after the given delay.
+    Delay(Duration),
+}
+
+/// Injects random faults into calls, to check how a service copes with degraded infrastructure.
+///
+/// Clones share the same random generator. Use [FaultInjectionLayer] to inject faults into an
+/// RPC transport, and [FaultInjector::inject] for other calls, such as storage uploads.
+#[derive(Clone, Debug)]
+pub struct FaultInjector {
+    name: Arc<str>,
+    rates: FaultRates,
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl FaultInjector {
+    /// Creates an injector with the given rates, with a name used in the logs and errors of the
+    /// injected faults.
+    pub fn new(name: impl Into<Arc<str>>, rates: FaultRates) -> Self {
+        Self::with_rng(name, rates, StdRng::from_os_rng())
+    }
+
+    /// Creates an injector drawing faults from a generator seeded with `seed`.
+    pub fn with_seed(name: impl Into<Arc<str>>, rates: FaultRates, seed: u64) -> Self {
+        Self::with_rng(name, rates, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(name: impl Into<Arc<str>>, rates: FaultRates, rng: StdRng) -> Self {
+        Self { name: name.into(), rates, rng: Arc::new(Mutex::new(rng)) }
+    }
+
+    /// The rates of the injected faults.
+    pub fn rates(&self) -> FaultRates {
+        self.rates
+    }
+
+    /// Draws the fault to inject into the next call, if any.
+    pub fn next_fault(&self) -> Option<Fault> {
+        let FaultRates { error, drop, delay, max_delay } = self.rates;
+        let mut rng = self.rng.lock().unwrap();
+        let draw: f64 = rng.random();
+        let fault = if draw < error {
+            Fault::Error
+        } else if draw < error + drop {
+            Fault::Drop
+        } else if draw < error + drop + delay {
+            Fault::Delay(max_delay.mul_f64(rng.random()))
+        } else {
+            return None;
+        };
+        tracing::warn!("Injecting {fault:?} into {} call", self.name);
+        Some(fault)
+    }
+
+    /// Runs the given call, injecting a fault drawn for it.
+    pub async fn inject<T>(
+        &self,
+        call: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let fault = self.next_fault();
+        match fault {
+            Some(Fault::Error) => bail!("injected {} error", self.name),
+            Some(Fault::Delay(delay)) => tokio::time::sleep(delay).await,
+            _ => {}
+        }
+        let result = call.await;
+        if fault == Some(Fault::Drop) {
+            bail!("injected {} error: response dropped", self.name);
+        }
+        result
+    }
+}
+
+/// A [tower] [Layer] that injects the faults of a [FaultInjector] into an RPC transport.
+///
+/// Injected errors are returned as HTTP 503 responses, which the retry policy of the client
+/// retries. Dropped responses are returned as custom transport errors, after the request was sent.
+#[derive(Clone, Debug)]
+pub struct FaultInjectionLayer {
+    injector: FaultInjector,
+}
+
+impl FaultInjectionLayer {
+    /// Creates a new layer injecting the faults of the given injector.
+    pub fn new(injector: FaultInjector) -> Self {
+        Self { injector }
+    }
+}
+
+impl<S> Layer<S> for FaultInjectionLayer {
+    type Service = FaultInjectionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FaultInjectionService { inner, injector: self.injector.clone() }
+    }
+}
+
+/// A transport service that injects the faults of a [FaultInjector] into each request.
+#[derive(Clone, Debug)]
+pub struct FaultInjectionService<S> {
+    inner: S,
+    injector: FaultInjector,
+}
+
+impl<S> FaultInjectionService<S> {
+    /// Creates a new service injecting the faults of the given injector.
+    pub fn new(inner: S, injector: FaultInjector) -> Self {
+        Self { inner, injector }
+    }
+}
+
+impl<S> Service<RequestPacket> for FaultInjectionService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let fault = self.injector.next_fault();
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            match fault {
+                Some(Fault::Error) => {
+                    return Err(TransportErrorKind::http_error(
+                        INJECTED_ERROR_STATUS,
+                        "injected fault".to_string(),
+                    ))
+                }
+                Some(Fault::Delay(delay)) => tokio::time::sleep(delay).await,
+                _ => {}
+            }
+            let response = inner.call(request).await;
+            if fault == Some(Fault::Drop) {
+                return Err(TransportErrorKind::custom_str("injected fault: response dropped"));
+            }
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fault_rates() {
+        let rates: FaultRates = "error=0.1, drop=0.05,delay=0.5,max_delay=2".parse().unwrap();
+        assert_eq!(rates, FaultRates::new(0.1, 0.05, 0.5, Duration::from_secs(2)).unwrap());
+        assert_eq!(rates.to_string().parse::<FaultRates>().unwrap(), rates);
+        assert_eq!("delay=0.5".parse::<FaultRates>().unwrap().max_delay, DEFAULT_MAX_DELAY);
+
+        "error=1.5".parse::<FaultRates>().unwrap_err();
+        "error=0.6,drop=0.6".parse::<FaultRates>().unwrap_err();
+        "timeout=0.1".parse::<FaultRates>().unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn inject_faults() {
+        let always = |error, drop| {
+            FaultInjector::with_seed(
+                "test",
+                FaultRates::new(error, drop, 0.0, Duration::ZERO).unwrap(),
+                0,
+            )
+        };
+        let never = always(0.0, 0.0);
+        assert_eq!(never.next_fault(), None);
+        assert_eq!(never.inject(async { Ok(1) }).await.unwrap(), 1);
+
+        // Injected errors fail before the call is made, while dropped responses fail after.
+        let mut called = false;
+        always(1.0, 0.0)
+            .inject(async {
+                called = true;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+        assert!(!called);
+        always(0.0, 1.0)
+            .inject(async {
+                called = true;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+        assert!(called);
+    }
+}
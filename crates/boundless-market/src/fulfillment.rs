@@ -0,0 +1,253 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Low-level construction of fulfillments from proven receipts.
+//!
+//! A prover fulfills a batch of requests by proving each request, proving the assessor over the
+//! batch, and aggregating the claims of all of these receipts into a set whose root is proven by
+//! the set builder. The functions in this module compute the claims, the set builder input, and
+//! the [Fulfillment] and [AssessorReceipt] structs submitted to the market, so that alternative
+//! prover implementations can build valid fulfillments with any proving backend.
+//!
+//! ```rust,no_run
+//! # use alloy::primitives::{Address, Bytes};
+//! # use risc0_zkvm::{sha::Digest, Receipt};
+//! # use boundless_market::contracts::{EIP712DomainSaltless, ProofRequest};
+//! # fn prove_set_builder(input: Vec<u8>) -> Receipt { unimplemented!() }
+//! # fn example(
+//! #     request: ProofRequest,
+//! #     order_image_id: Digest,
+//! #     order_journal: Vec<u8>,
+//! #     assessor_image_id: Digest,
+//! #     assessor_journal: Vec<u8>,
+//! #     set_builder_image_id: Digest,
+//! #     domain: EIP712DomainSaltless,
+//! #     prover: Address,
+//! # ) -> anyhow::Result<()> {
+//! use boundless_market::fulfillment::{
+//!     assessor_receipt, build_fulfillment, fulfillment_data, receipt_claim, AggregationSet,
+//! };
+//!
+//! let mut set = AggregationSet::new(set_builder_image_id);
+//! let order_index = set.push(receipt_claim(order_image_id, order_journal.clone()));
+//! let assessor_index = set.push(receipt_claim(assessor_image_id, assessor_journal.clone()));
+//!
+//! // Prove the set builder with this input, with the order and assessor receipts as assumptions.
+//! let root_receipt = prove_set_builder(set.set_builder_input()?);
+//!
+//! let data = fulfillment_data(&request, order_image_id, order_journal)?;
+//! let fill = build_fulfillment(&request, data, set.inclusion_seal(order_index)?, &domain)?;
+//! let assessor =
+//!     assessor_receipt(&assessor_journal, set.inclusion_seal(assessor_index)?, prover)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use alloy::{
+    primitives::{Address, Bytes},
+    sol_types::{SolStruct, SolValue},
+};
+use anyhow::{bail, Context, Result};
+use risc0_aggregation::{
+    merkle_path, merkle_root, GuestState, SetInclusionReceipt,
+    SetInclusionReceiptVerifierParameters,
+};
+use risc0_zkvm::{
+    sha::{Digest, Digestible},
+    ReceiptClaim,
+};
+
+use crate::contracts::{
+    AssessorJournal, AssessorReceipt, EIP712DomainSaltless, Fulfillment, FulfillmentData,
+    Predicate, PredicateType, ProofRequest,
+};
+
+/// Claim of a receipt for a successful execution of `image_id` committing to `journal`.
+///
+/// This is the claim added to the [AggregationSet] for each proven request, and for the assessor.
+pub fn receipt_claim(image_id: impl Into<Digest>, journal: impl Into<Vec<u8>>) -> ReceiptClaim {
+    ReceiptClaim::ok(image_id.into(), journal.into())
+}
+
+/// The [FulfillmentData] to deliver for a request, given the image ID and journal of its receipt.
+///
+/// Requests with a claim digest predicate are fulfilled without delivering the journal.
+pub fn fulfillment_data(
+    request: &ProofRequest,
+    image_id: impl Into<Digest>,
+    journal: impl Into<Bytes>,
+) -> Result<FulfillmentData> {
+    match request.requirements.predicate.predicateType {
+        PredicateType::ClaimDigestMatch => Ok(FulfillmentData::None),
+        PredicateType::PrefixMatch | PredicateType::DigestMatch => {
+            Ok(FulfillmentData::from_image_id_and_journal(image_id, journal))
+        }
+        predicate_type => bail!("unsupported predicate type {predicate_type:?}"),
+    }
+}
+
+/// Set of receipt claims aggregated by the set builder guest.
+///
+/// Each claim is proven by its inclusion in the set, whose root is verified onchain by the
+/// RiscZeroSetVerifier configured with the same set builder image ID.
+#[derive(Clone, Debug)]
+pub struct AggregationSet {
+    set_builder_image_id: Digest,
+    claims: Vec<ReceiptClaim>,
+    digests: Vec<Digest>,
+}
+
+impl AggregationSet {
+    /// Create an empty set, to be aggregated by the set builder with the given image ID.
+    pub fn new(set_builder_image_id: impl Into<Digest>) -> Self {
+        Self { set_builder_image_id: set_builder_image_id.into(), claims: vec![], digests: vec![] }
+    }
+
+    /// Add a claim to the set, returning its index.
+    pub fn push(&mut self, claim: ReceiptClaim) -> usize {
+        self.digests.push(claim.digest());
+        self.claims.push(claim);
+        self.claims.len() - 1
+    }
+
+    /// Claims in the set, in the order they were added.
+    pub fn claims(&self) -> &[ReceiptClaim] {
+        &self.claims
+    }
+
+    /// Root of the Merkle tree over the claim digests, committed by the set builder.
+    pub fn root(&self) -> Result<Digest> {
+        if self.digests.is_empty() {
+            bail!("aggregation set is empty");
+        }
+        Ok(merkle_root(&self.digests))
+    }
+
+    /// Encoded input of the set builder guest, which proves the root of this set.
+    ///
+    /// The receipts of all claims in the set must be added as assumptions when proving.
+    pub fn set_builder_input(&self) -> Result<Vec<u8>> {
+        let input = GuestState::initial(self.set_builder_image_id)
+            .into_input(self.claims.clone(), true)
+            .context("failed to build set builder input")?;
+        Ok(bytemuck::pod_collect_to_vec(&risc0_zkvm::serde::to_vec(&input)?))
+    }
+
+    /// Receipt proving the inclusion of the claim at `index` in the set.
+    pub fn inclusion_receipt(&self, index: usize) -> Result<SetInclusionReceipt<ReceiptClaim>> {
+        let claim = self.claims.get(index).context("claim index out of bounds")?;
+        let verifier_parameters =
+            SetInclusionReceiptVerifierParameters { image_id: self.set_builder_image_id };
+        Ok(SetInclusionReceipt::from_path_with_verifier_params(
+            claim.clone(),
+            merkle_path(&self.digests, index),
+            verifier_parameters.digest(),
+        ))
+    }
+
+    /// Seal proving the inclusion of the claim at `index`, as verified by the RiscZeroSetVerifier.
+    pub fn inclusion_seal(&self, index: usize) -> Result<Bytes> {
+        Ok(self.inclusion_receipt(index)?.abi_encode_seal()?.into())
+    }
+}
+
+/// Build the [Fulfillment] of a request, evaluating its requirements on the delivered data.
+///
+/// The `seal` is either the [AggregationSet::inclusion_seal] of the request's claim, or the
+/// encoded seal of a stand-alone receipt for requests that require one, such as Groth16.
+pub fn build_fulfillment(
+    request: &ProofRequest,
+    fulfillment_data: FulfillmentData,
+    seal: impl Into<Bytes>,
+    domain: &EIP712DomainSaltless,
+) -> Result<Fulfillment> {
+    let predicate = Predicate::try_from(request.requirements.predicate.clone())?;
+    let claim_digest = predicate
+        .eval(&fulfillment_data)
+        .with_context(|| format!("requirements of request 0x{:x} are not met", request.id))?;
+    let (fulfillment_data_type, fulfillment_data) = fulfillment_data.fulfillment_type_and_data();
+    Ok(Fulfillment {
+        id: request.id,
+        requestDigest: request.eip712_signing_hash(&domain.alloy_struct()),
+        claimDigest: <[u8; 32]>::from(claim_digest).into(),
+        fulfillmentDataType: fulfillment_data_type,
+        fulfillmentData: fulfillment_data.into(),
+        seal: seal.into(),
+    })
+}
+
+/// Build the [AssessorReceipt] from the journal of the assessor receipt and its inclusion seal.
+pub fn assessor_receipt(
+    assessor_journal: &[u8],
+    inclusion_seal: impl Into<Bytes>,
+    prover: Address,
+) -> Result<AssessorReceipt> {
+    let journal = AssessorJournal::abi_decode(assessor_journal)
+        .context("failed to decode assessor journal")?;
+    Ok(AssessorReceipt {
+        seal: inclusion_seal.into(),
+        prover,
+        selectors: journal.selectors,
+        callbacks: journal.callbacks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::*;
+    use crate::contracts::{eip712_domain, Offer, RequestId, RequestInput, Requirements};
+
+    fn request(predicate: Predicate) -> ProofRequest {
+        ProofRequest::new(
+            RequestId::new(Address::ZERO, 1),
+            Requirements::new(predicate),
+            "https://dev.null",
+            RequestInput::builder().build_inline().unwrap(),
+            Offer {
+                minPrice: U256::from(0),
+                maxPrice: U256::from(1),
+                rampUpStart: 0,
+                timeout: 1000,
+                rampUpPeriod: 1,
+                lockTimeout: 1000,
+                lockCollateral: U256::from(0),
+            },
+        )
+    }
+
+    #[test]
+    fn test_build_fulfillment() {
+        let image_id = Digest::from([1u32; 8]);
+        let journal = b"journal".to_vec();
+        let request = request(Predicate::prefix_match(image_id, Bytes::from_static(b"jour")));
+        let domain = eip712_domain(Address::ZERO, 1);
+
+        let mut set = AggregationSet::new(Digest::from([2u32; 8]));
+        let index = set.push(receipt_claim(image_id, journal.clone()));
+        assert_eq!(set.root().unwrap(), set.claims()[0].digest());
+
+        let data = fulfillment_data(&request, image_id, journal.clone()).unwrap();
+        let fill =
+            build_fulfillment(&request, data, set.inclusion_seal(index).unwrap(), &domain).unwrap();
+        assert_eq!(fill.id, request.id);
+        assert_eq!(fill.requestDigest, request.eip712_signing_hash(&domain.alloy_struct()));
+        assert_eq!(fill.claimDigest, <[u8; 32]>::from(receipt_claim(image_id, journal).digest()));
+
+        // A journal that does not match the predicate does not fulfill the request.
+        let data = fulfillment_data(&request, image_id, b"other".to_vec()).unwrap();
+        assert!(build_fulfillment(&request, data, Bytes::new(), &domain).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use alloy::{
+    rpc::client::RpcClient,
+    transports::{http::Http, utils::guess_local_url},
+};
+use url::Url;
+
+use crate::rate_limit_layer::{RateLimitService, RateLimiter};
+
+/// Tuning options for the HTTP client shared by the RPC transport, storage fetches, and the
+/// order stream client.
+///
+/// Building a single [reqwest::Client] from this config and passing it to each component lets
+/// them share one connection pool, which avoids reconnecting on every request in long-running
+/// services.
+///
+/// ```rust
+/// # use boundless_market::http_client::HttpClientConfig;
+/// use std::time::Duration;
+///
+/// let client = HttpClientConfig::default()
+///     .with_pool_max_idle_per_host(32)
+///     .with_timeout(Duration::from_secs(30))
+///     .with_gzip(true)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct HttpClientConfig {
+    /// Maximum number of idle connections kept open per host.
+    ///
+    /// If `None`, the [reqwest] default is used.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Duration after which an idle pooled connection is closed.
+    ///
+    /// If `None`, the [reqwest] default is used.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Use HTTP/2 without negotiating it first.
+    ///
+    /// Only enable this if every endpoint the client talks to is known to support HTTP/2.
+    pub http2_prior_knowledge: bool,
+    /// Interval for HTTP/2 keep-alive pings, keeping pooled HTTP/2 connections warm.
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// Timeout for establishing a connection.
+    pub connect_timeout: Option<Duration>,
+    /// Total timeout for a request, from connecting until the response body is read.
+    pub timeout: Option<Duration>,
+    /// TCP keep-alive interval for open connections.
+    pub tcp_keepalive: Option<Duration>,
+    /// Request gzip-compressed responses and transparently decompress them.
+    pub gzip: bool,
+    /// Rate limiter shared by the RPC provider and storage fetches made through this client.
+    ///
+    /// Not applied by [Self::build], as [reqwest] has no hook for it. Components built from this
+    /// config, like the [crate::Client], apply it to their requests.
+    pub rate_limiter: Option<RateLimiter>,
+}
+
+impl HttpClientConfig {
+    /// Set the maximum number of idle connections kept open per host.
+    pub fn with_pool_max_idle_per_host(self, max: usize) -> Self {
+        Self { pool_max_idle_per_host: Some(max), ..self }
+    }
+
+    /// Set the duration after which an idle pooled connection is closed.
+    pub fn with_pool_idle_timeout(self, timeout: impl Into<Option<Duration>>) -> Self {
+        Self { pool_idle_timeout: timeout.into(), ..self }
+    }
+
+    /// Set whether to use HTTP/2 without negotiating it first.
+    pub fn with_http2_prior_knowledge(self, enabled: bool) -> Self {
+        Self { http2_prior_knowledge: enabled, ..self }
+    }
+
+    /// Set the interval for HTTP/2 keep-alive pings.
+    pub fn with_http2_keep_alive_interval(self, interval: impl Into<Option<Duration>>) -> Self {
+        Self { http2_keep_alive_interval: interval.into(), ..self }
+    }
+
+    /// Set the timeout for establishing a connection.
+    pub fn with_connect_timeout(self, timeout: impl Into<Option<Duration>>) -> Self {
+        Self { connect_timeout: timeout.into(), ..self }
+    }
+
+    /// Set the total timeout for a request.
+    pub fn with_timeout(self, timeout: impl Into<Option<Duration>>) -> Self {
+        Self { timeout: timeout.into(), ..self }
+    }
+
+    /// Set the TCP keep-alive interval.
+    pub fn with_tcp_keepalive(self, interval: impl Into<Option<Duration>>) -> Self {
+        Self { tcp_keepalive: interval.into(), ..self }
+    }
+
+    /// Set whether to request gzip-compressed responses.
+    pub fn with_gzip(self, enabled: bool) -> Self {
+        Self { gzip: enabled, ..self }
+    }
+
+    /// Limit requests to `requests_per_second` on average, with bursts of up to `burst`.
+    pub fn with_rate_limit(self, requests_per_second: f64, burst: u32) -> Self {
+        self.with_rate_limiter(RateLimiter::new(requests_per_second, burst))
+    }
+
+    /// Set the [RateLimiter], which may be shared with other clients.
+    pub fn with_rate_limiter(self, rate_limiter: impl Into<Option<RateLimiter>>) -> Self {
+        Self { rate_limiter: rate_limiter.into(), ..self }
+    }
+
+    /// Create an [RpcClient] for the given URL using `client`, rate limited if a [RateLimiter]
+    /// is set.
+    pub fn rpc_client(&self, client: reqwest::Client, rpc_url: Url) -> RpcClient {
+        match &self.rate_limiter {
+            Some(limiter) => rate_limited_rpc_client_with_http(client, rpc_url, limiter.clone()),
+            None => rpc_client_with_http(client, rpc_url),
+        }
+    }
+
+    /// Build a [reqwest::Client] from this configuration.
+    pub fn build(&self) -> Result<reqwest::Client, reqwest::Error> {
+        let mut builder = reqwest::Client::builder()
+            .gzip(self.gzip)
+            .tcp_keepalive(self.tcp_keepalive)
+            .http2_keep_alive_interval(self.http2_keep_alive_interval);
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build()
+    }
+}
+
+/// Create an [RpcClient] that sends requests over HTTP using the given [reqwest::Client].
+///
+/// The returned client can be passed to [alloy::providers::ProviderBuilder::connect_client] so
+/// that the provider reuses the connection pool of `client`.
+pub fn rpc_client_with_http(client: reqwest::Client, rpc_url: Url) -> RpcClient {
+    let is_local = guess_local_url(&rpc_url);
+    RpcClient::new(Http::with_client(client, rpc_url), is_local)
+}
+
+/// Create an [RpcClient] that sends requests over HTTP using the given [reqwest::Client],
+/// waiting for the given [RateLimiter] before each request.
+pub fn rate_limited_rpc_client_with_http(
+    client: reqwest::Client,
+    rpc_url: Url,
+    limiter: RateLimiter,
+) -> RpcClient {
+    let is_local = guess_local_url(&rpc_url);
+    RpcClient::new(RateLimitService::new(Http::with_client(client, rpc_url), limiter), is_local)
+}
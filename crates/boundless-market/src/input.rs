@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::{BufWriter, Read, Write};
+
 use bytemuck::Pod;
 use risc0_zkvm::serde::to_vec;
 use risc0_zkvm::ExecutorEnv;
@@ -69,6 +71,12 @@ pub enum Error {
     /// Encoded input buffer is empty, which is an invalid encoding.
     #[error("Cannot decode empty buffer as input")]
     EmptyEncodedInput,
+    /// IO error while streaming the input
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Input is too large to encode
+    #[error("Input of {0} bytes is too large to encode")]
+    InputTooLarge(u64),
 }
 
 /// Structured input used by the Boundless prover to execute the guest for the proof request.
@@ -120,6 +128,57 @@ impl GuestEnv {
     pub fn from_stdin(bytes: impl Into<Vec<u8>>) -> Self {
         GuestEnv { stdin: bytes.into() }
     }
+
+    /// Encode a [GuestEnv] whose `stdin` is the `len` bytes read from `reader`, writing the
+    /// encoding to `writer`.
+    ///
+    /// The output is identical to [GuestEnv::encode], but the input is streamed rather than held
+    /// in memory, so that inputs of hundreds of MB can be encoded.
+    pub fn encode_stdin_stream(
+        reader: impl Read,
+        len: u64,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        let len = u32::try_from(len).map_err(|_| Error::InputTooLarge(len))?;
+        let mut writer = BufWriter::new(writer);
+
+        // MessagePack map with the single field `stdin`, as written by `rmp_serde::to_vec_named`.
+        writer.write_all(&[Version::V1.into(), 0x81, 0xa5])?;
+        writer.write_all(b"stdin")?;
+        // Array of `len` integers, with the header in its most compact form.
+        match len {
+            0..16 => writer.write_all(&[0x90 | len as u8])?,
+            16..=0xffff => {
+                writer.write_all(&[0xdc])?;
+                writer.write_all(&(len as u16).to_be_bytes())?;
+            }
+            _ => {
+                writer.write_all(&[0xdd])?;
+                writer.write_all(&len.to_be_bytes())?;
+            }
+        }
+
+        let mut buf = [0u8; 64 * 1024];
+        let mut reader = reader.take(len.into());
+        let mut remaining = len;
+        while remaining > 0 {
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            for &byte in &buf[..n] {
+                // Bytes below 0x80 are positive fixints, the rest are prefixed by the uint8 marker.
+                if byte < 0x80 {
+                    writer.write_all(&[byte])?;
+                } else {
+                    writer.write_all(&[0xcc, byte])?;
+                }
+            }
+            remaining -= n as u32;
+        }
+        writer.flush()?;
+        Ok(())
+    }
 }
 
 impl TryFrom<GuestEnv> for ExecutorEnv<'_> {
@@ -272,4 +331,19 @@ mod tests {
         assert_eq!(env, decoded_env);
         Ok(())
     }
+
+    #[test]
+    fn test_encode_stdin_stream() -> Result<(), Error> {
+        // Cover each form of the array header, and bytes on either side of the fixint range.
+        for len in [0usize, 15, 16, 0xffff, 0x10000] {
+            let stdin: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+            let mut encoded = Vec::new();
+            GuestEnv::encode_stdin_stream(stdin.as_slice(), len as u64, &mut encoded)?;
+            assert_eq!(encoded, GuestEnv::from_stdin(stdin).encode()?, "length {len}");
+        }
+
+        let err = GuestEnv::encode_stdin_stream(&[1u8, 2][..], 3, Vec::new()).unwrap_err();
+        assert!(matches!(err, Error::Io(_)), "{err:?}");
+        Ok(())
+    }
 }
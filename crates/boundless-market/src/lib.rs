@@ -30,6 +30,10 @@ pub use alloy;
 #[cfg(not(target_os = "zkvm"))]
 pub mod balance_alerts_layer;
 
+/// Spend limits on the requests submitted by a [Client].
+#[cfg(not(target_os = "zkvm"))]
+pub mod budget;
+
 /// Client module for interacting with the Boundless Market API.
 #[cfg(not(target_os = "zkvm"))]
 pub mod client;
@@ -51,12 +55,44 @@ pub mod deployments;
 #[cfg(not(target_os = "zkvm"))]
 pub use deployments::Deployment;
 
+/// Stable codes for the errors of the market SDK, for integrators outside of Rust.
+#[cfg(not(target_os = "zkvm"))]
+pub mod error_code;
+
+/// In-process broadcast bus of the notable events of a [Client].
+#[cfg(not(target_os = "zkvm"))]
+pub mod event_bus;
+
+/// Injection of random faults into RPC and storage calls, for resilience testing.
+#[cfg(not(target_os = "zkvm"))]
+pub mod fault_injection;
+
+/// Construction of fulfillments and assessor receipts from proven receipts.
+#[cfg(not(target_os = "zkvm"))]
+pub mod fulfillment;
+
+/// Configuration of the HTTP client shared by the RPC transport, storage, and order stream.
+#[cfg(not(target_os = "zkvm"))]
+pub mod http_client;
+
 /// Input module for serializing input.
 #[cfg(not(target_os = "zkvm"))]
 pub mod input;
 #[cfg(not(target_os = "zkvm"))]
 pub use input::{GuestEnv, GuestEnvBuilder};
 
+/// Utilities for querying event logs over large block ranges.
+#[cfg(not(target_os = "zkvm"))]
+pub mod log_query;
+
+/// Redaction of sensitive values from log output.
+#[cfg(not(target_os = "zkvm"))]
+pub mod log_redaction;
+
+/// Middleware observing and modifying the requests sent by a [Client].
+#[cfg(not(target_os = "zkvm"))]
+pub mod middleware;
+
 /// Order stream client module for submitting requests off-chain.
 #[cfg(not(target_os = "zkvm"))]
 pub mod order_stream_client;
@@ -67,6 +103,14 @@ pub mod nonce_layer;
 #[cfg(not(target_os = "zkvm"))]
 pub use order_stream_client::OrderStreamClient;
 
+/// Policies describing which provers a requestor accepts, based on per-prover statistics.
+#[cfg(not(target_os = "zkvm"))]
+pub mod prover_policy;
+
+/// Token-bucket rate limiting of outbound RPC and storage requests.
+#[cfg(not(target_os = "zkvm"))]
+pub mod rate_limit_layer;
+
 /// Module providing functionality to build requests.
 #[cfg(not(target_os = "zkvm"))]
 pub mod request_builder;
@@ -75,12 +119,38 @@ pub mod request_builder;
 #[cfg(not(target_os = "zkvm"))]
 pub mod selector;
 
+/// Classification of fulfillment seals and estimates of their verification gas.
+#[cfg(not(target_os = "zkvm"))]
+pub mod seal;
+
+/// A ProviderLayer module recording the hashes of broadcast transactions.
+#[cfg(not(target_os = "zkvm"))]
+pub mod sent_tx_layer;
+
+/// Batched verification of proof request signatures.
+#[cfg(not(target_os = "zkvm"))]
+pub mod signature_verifier;
+#[cfg(not(target_os = "zkvm"))]
+pub use signature_verifier::SignatureVerifier;
+
+/// A ProviderLayer module for detecting RPC nodes that lag behind the chain.
+#[cfg(not(target_os = "zkvm"))]
+pub mod stale_rpc_layer;
+
 /// Storage module for interacting with the storage provider.
 #[cfg(not(target_os = "zkvm"))]
 pub mod storage;
 #[cfg(not(target_os = "zkvm"))]
 pub use storage::{StandardStorageProvider, StorageProvider, StorageProviderConfig};
 
+/// Durable queue of signed requests, submitted as connectivity allows.
+#[cfg(not(target_os = "zkvm"))]
+pub mod submit_queue;
+
+/// Pluggable telemetry for the economics of fulfilled requests.
+#[cfg(not(target_os = "zkvm"))]
+pub mod telemetry;
+
 /// Utility functions and types used elsewhere.
 pub(crate) mod util;
 pub use util::NotProvided;
@@ -0,0 +1,293 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    ops::{ControlFlow, RangeInclusive},
+    time::Duration,
+};
+
+use alloy::{
+    contract::Error as ContractErr,
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+
+/// Progress of a chunked log query, reported after each chunk is queried.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ChunkProgress {
+    /// Block range of the completed chunk, inclusive of both ends.
+    pub chunk: RangeInclusive<u64>,
+    /// Number of logs found in the completed chunk.
+    pub logs: usize,
+    /// Number of blocks scanned so far, including the completed chunk.
+    pub blocks_scanned: u64,
+    /// Total number of blocks in the range being scanned.
+    pub total_blocks: u64,
+}
+
+impl ChunkProgress {
+    /// Fraction of the range scanned so far, between 0.0 and 1.0.
+    pub fn fraction(&self) -> f64 {
+        if self.total_blocks == 0 {
+            return 1.0;
+        }
+        self.blocks_scanned as f64 / self.total_blocks as f64
+    }
+}
+
+/// Order in which a [ChunkedLogQuery] walks the block range.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScanDirection {
+    /// Scan from the lowest block to the highest.
+    #[default]
+    Forward,
+    /// Scan from the highest block to the lowest. Useful to find the most recent event.
+    Backward,
+}
+
+/// Query for logs over a block range, split into chunks to stay within provider limits.
+///
+/// When a chunk query fails, the chunk size is halved and the query retried, up to
+/// [Self::max_retries] consecutive times. This handles providers that reject ranges larger than
+/// some undocumented limit, as well as transient errors.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ChunkedLogQuery {
+    /// Maximum number of blocks to query in a single `eth_getLogs` request.
+    pub chunk_size: u64,
+    /// Maximum number of consecutive retries for a failed chunk.
+    pub max_retries: u32,
+    /// Delay between retries of a failed chunk.
+    pub retry_delay: Duration,
+    /// Order in which the range is scanned.
+    pub direction: ScanDirection,
+}
+
+impl ChunkedLogQuery {
+    /// Create a new [ChunkedLogQuery] with the given chunk size, scanning forward.
+    pub fn new(chunk_size: u64) -> Self {
+        Self {
+            chunk_size: chunk_size.max(1),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+            direction: ScanDirection::Forward,
+        }
+    }
+
+    /// Set the maximum number of consecutive retries for a failed chunk.
+    pub fn with_max_retries(self, max_retries: u32) -> Self {
+        Self { max_retries, ..self }
+    }
+
+    /// Set the delay between retries of a failed chunk.
+    pub fn with_retry_delay(self, retry_delay: Duration) -> Self {
+        Self { retry_delay, ..self }
+    }
+
+    /// Set the order in which the range is scanned.
+    pub fn with_direction(self, direction: ScanDirection) -> Self {
+        Self { direction, ..self }
+    }
+
+    /// Scan the given block range for events matching the filter, calling `visit` with the
+    /// decoded events of each chunk.
+    ///
+    /// The scan stops early if `visit` returns [ControlFlow::Break], in which case the break value
+    /// is returned. Returns `None` if the whole range was scanned.
+    pub async fn scan<E, P, B>(
+        &self,
+        provider: P,
+        filter: Filter,
+        range: RangeInclusive<u64>,
+        mut visit: impl FnMut(Vec<(E, Log)>, &ChunkProgress) -> ControlFlow<B>,
+    ) -> Result<Option<B>, ContractErr>
+    where
+        E: SolEvent,
+        P: Provider + Clone,
+    {
+        let mut decode_err = None;
+        let result = self
+            .scan_logs(provider, filter, range, |logs, progress| {
+                let events: Result<Vec<(E, Log)>, _> = logs
+                    .into_iter()
+                    .map(|log| log.log_decode::<E>().map(|decoded| (decoded.inner.data, log)))
+                    .collect();
+                match events {
+                    Ok(events) => visit(events, progress).map_break(Some),
+                    Err(err) => {
+                        decode_err = Some(ContractErr::AbiError(err.into()));
+                        ControlFlow::Break(None)
+                    }
+                }
+            })
+            .await?;
+        match decode_err {
+            Some(err) => Err(err),
+            None => Ok(result.flatten()),
+        }
+    }
+
+    /// Scan the given block range for logs matching the filter, calling `visit` with the raw logs
+    /// of each chunk.
+    ///
+    /// Unlike [Self::scan], logs are not decoded, which lets callers match events whose type is
+    /// only known at runtime. The scan stops early if `visit` returns [ControlFlow::Break].
+    pub async fn scan_logs<P, B>(
+        &self,
+        provider: P,
+        filter: Filter,
+        range: RangeInclusive<u64>,
+        mut visit: impl FnMut(Vec<Log>, &ChunkProgress) -> ControlFlow<B>,
+    ) -> Result<Option<B>, ContractErr>
+    where
+        P: Provider + Clone,
+    {
+        let (mut low, mut high) = (*range.start(), *range.end());
+        if low > high {
+            return Ok(None);
+        }
+        let total_blocks = high - low + 1;
+        let mut chunk_size = self.chunk_size.max(1);
+        let mut blocks_scanned = 0;
+
+        loop {
+            let mut attempt = 0;
+            let (chunk, logs) = loop {
+                let chunk = match self.direction {
+                    ScanDirection::Forward => low..=low.saturating_add(chunk_size - 1).min(high),
+                    ScanDirection::Backward => high.saturating_sub(chunk_size - 1).max(low)..=high,
+                };
+                tracing::trace!(range = ?chunk, "Querying for events");
+                let chunk_filter = filter.clone().from_block(*chunk.start()).to_block(*chunk.end());
+                match provider.get_logs(&chunk_filter).await {
+                    Ok(logs) => break (chunk, logs),
+                    Err(err) if attempt < self.max_retries => {
+                        attempt += 1;
+                        chunk_size = (chunk_size / 2).max(1);
+                        tracing::warn!(
+                            "Failed to query events in blocks {} to {}: {err}; retrying with chunk size {chunk_size}",
+                            chunk.start(),
+                            chunk.end()
+                        );
+                        tokio::time::sleep(self.retry_delay).await;
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            };
+
+            blocks_scanned += chunk.end() - chunk.start() + 1;
+            let progress = ChunkProgress {
+                chunk: chunk.clone(),
+                logs: logs.len(),
+                blocks_scanned,
+                total_blocks,
+            };
+            if let ControlFlow::Break(value) = visit(logs, &progress) {
+                return Ok(Some(value));
+            }
+
+            match self.direction {
+                ScanDirection::Forward if *chunk.end() < high => low = chunk.end() + 1,
+                ScanDirection::Backward if *chunk.start() > low => high = chunk.start() - 1,
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    /// Query the given block range for all events matching the filter.
+    ///
+    /// `progress` is called after each chunk. Events are returned in the order the chunks were
+    /// scanned, and in block order within each chunk.
+    pub async fn query<E, P>(
+        &self,
+        provider: P,
+        filter: Filter,
+        range: RangeInclusive<u64>,
+        mut progress: impl FnMut(&ChunkProgress),
+    ) -> Result<Vec<(E, Log)>, ContractErr>
+    where
+        E: SolEvent,
+        P: Provider + Clone,
+    {
+        let mut events = Vec::new();
+        self.scan(provider, filter, range, |logs, chunk_progress| {
+            events.extend(logs);
+            progress(chunk_progress);
+            ControlFlow::<()>::Continue(())
+        })
+        .await?;
+        Ok(events)
+    }
+
+    /// Query the given block range for all logs matching the filter, without decoding them.
+    ///
+    /// See [Self::scan_logs].
+    pub async fn query_logs<P>(
+        &self,
+        provider: P,
+        filter: Filter,
+        range: RangeInclusive<u64>,
+        mut progress: impl FnMut(&ChunkProgress),
+    ) -> Result<Vec<Log>, ContractErr>
+    where
+        P: Provider + Clone,
+    {
+        let mut all_logs = Vec::new();
+        self.scan_logs(provider, filter, range, |logs, chunk_progress| {
+            all_logs.extend(logs);
+            progress(chunk_progress);
+            ControlFlow::<()>::Continue(())
+        })
+        .await?;
+        Ok(all_logs)
+    }
+}
+
+/// Query the given block range for all events matching the filter, in chunks of at most
+/// `chunk_size` blocks, calling `progress` after each chunk.
+///
+/// See [ChunkedLogQuery] for control over retries and scan direction.
+///
+/// ```no_run
+/// # use alloy::providers::ProviderBuilder;
+/// use boundless_market::{
+///     contracts::IBoundlessMarket::RequestLocked, log_query::query_events_chunked,
+/// };
+/// use alloy::{rpc::types::Filter, sol_types::SolEvent};
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// # let provider = ProviderBuilder::new().connect_http("http://localhost:8545".parse()?);
+/// let filter = Filter::new().event_signature(RequestLocked::SIGNATURE_HASH);
+/// let events = query_events_chunked::<RequestLocked, _>(&provider, filter, 0..=10_000, 1000, |p| {
+///     tracing::info!("Scanned {:.0}% of blocks", p.fraction() * 100.0);
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn query_events_chunked<E, P>(
+    provider: P,
+    filter: Filter,
+    range: RangeInclusive<u64>,
+    chunk_size: u64,
+    progress: impl FnMut(&ChunkProgress),
+) -> Result<Vec<(E, Log)>, ContractErr>
+where
+    E: SolEvent,
+    P: Provider + Clone,
+{
+    ChunkedLogQuery::new(chunk_size).query(provider, filter, range, progress).await
+}
@@ -0,0 +1,283 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Redaction of sensitive values from log output.
+//!
+//! Wrap the writer of a [tracing_subscriber::fmt] layer with [Redactor::make_writer] to mask
+//! secrets before they are written:
+//!
+//! ```rust,no_run
+//! use boundless_market::log_redaction::Redactor;
+//!
+//! let redactor = Redactor::from_env().with_secret("my-api-key");
+//! tracing_subscriber::fmt().with_writer(redactor.make_writer(std::io::stdout)).init();
+//! ```
+
+use std::{borrow::Cow, io, sync::Arc};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Text written in place of a redacted value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Environment variables whose values are treated as secrets by [Redactor::from_env].
+pub const SECRET_ENV_VARS: &[&str] = &[
+    "PRIVATE_KEY",
+    "POVW_PRIVATE_KEY",
+    "BONSAI_API_KEY",
+    "PINATA_JWT",
+    "S3_ACCESS_KEY",
+    "S3_SECRET_KEY",
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_SESSION_TOKEN",
+];
+
+/// Environment variable that disables redaction when set to `false` or `0`.
+pub const LOG_REDACT_ENV_VAR: &str = "LOG_REDACT";
+
+/// URL query parameters carrying credentials, such as the signature of a presigned URL.
+///
+/// Matched case-insensitively.
+const SECRET_QUERY_PARAMS: &[&str] = &[
+    "x-amz-signature",
+    "x-amz-credential",
+    "x-amz-security-token",
+    "signature",
+    "sig",
+    "token",
+    "access_token",
+    "api_key",
+    "apikey",
+];
+
+/// Keys under which raw input payloads appear in debug and JSON output of a request.
+const INPUT_DATA_KEYS: &[&str] = &["data: 0x", "\"data\":\"0x", "\"data\": \"0x"];
+
+/// Minimum length of a secret for it to be redacted, to avoid masking common short strings.
+const MIN_SECRET_LEN: usize = 8;
+
+/// Masks private keys, API keys, presigned URL credentials, and raw input payloads in log output.
+#[derive(Clone, Debug)]
+pub struct Redactor {
+    secrets: Arc<Vec<String>>,
+    enabled: bool,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self { secrets: Arc::new(Vec::new()), enabled: true }
+    }
+}
+
+impl Redactor {
+    /// Create a [Redactor] that masks the values of the [SECRET_ENV_VARS] set in the environment.
+    ///
+    /// Redaction is disabled if the [LOG_REDACT_ENV_VAR] environment variable is `false` or `0`.
+    pub fn from_env() -> Self {
+        let enabled = !matches!(
+            std::env::var(LOG_REDACT_ENV_VAR).as_deref().map(str::to_ascii_lowercase).as_deref(),
+            Ok("false" | "0")
+        );
+        SECRET_ENV_VARS
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .fold(Self::default(), |redactor, secret| redactor.with_secret(secret))
+            .with_enabled(enabled)
+    }
+
+    /// Add a secret value to mask wherever it appears.
+    ///
+    /// Hex values are matched with or without a `0x` prefix, and in any case.
+    pub fn with_secret(self, secret: impl Into<String>) -> Self {
+        let secret = secret.into();
+        let secret = secret.trim();
+        let secret = secret.strip_prefix("0x").unwrap_or(secret);
+        if secret.len() < MIN_SECRET_LEN {
+            return self;
+        }
+        let mut secrets = Vec::clone(&self.secrets);
+        secrets.push(secret.to_string());
+        if secret.bytes().all(|b| b.is_ascii_hexdigit()) {
+            secrets.push(secret.to_ascii_lowercase());
+            secrets.push(secret.to_ascii_uppercase());
+        }
+        secrets.dedup();
+        Self { secrets: Arc::new(secrets), ..self }
+    }
+
+    /// Set whether redaction is enabled. Disabling it can be useful for local debugging.
+    pub fn with_enabled(self, enabled: bool) -> Self {
+        Self { enabled, ..self }
+    }
+
+    /// Mask the sensitive values in the given text.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if !self.enabled {
+            return Cow::Borrowed(text);
+        }
+        let mut text = Cow::Borrowed(text);
+        for secret in self.secrets.iter() {
+            if text.contains(secret.as_str()) {
+                text = Cow::Owned(text.replace(secret.as_str(), REDACTED));
+            }
+        }
+        if let Some(redacted) = redact_query_params(&text) {
+            text = Cow::Owned(redacted);
+        }
+        if let Some(redacted) = redact_input_data(&text) {
+            text = Cow::Owned(redacted);
+        }
+        text
+    }
+
+    /// Wrap a [MakeWriter] so that everything written through it is redacted.
+    pub fn make_writer<M>(&self, make_writer: M) -> RedactingMakeWriter<M> {
+        RedactingMakeWriter { redactor: self.clone(), make_writer }
+    }
+}
+
+/// Mask the values of [SECRET_QUERY_PARAMS] in URLs, returning `None` if there are none.
+fn redact_query_params(text: &str) -> Option<String> {
+    let lower = text.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    for (i, c) in lower.char_indices() {
+        if c != '?' && c != '&' {
+            continue;
+        }
+        let rest = &lower[i + 1..];
+        let Some(param) = SECRET_QUERY_PARAMS
+            .iter()
+            .find(|param| rest.starts_with(*param) && rest[param.len()..].starts_with('='))
+        else {
+            continue;
+        };
+        let start = i + 1 + param.len() + 1;
+        let end = text[start..]
+            .find(|c: char| c == '&' || c == '"' || c == '\'' || c == ')' || c.is_whitespace())
+            .map_or(text.len(), |len| start + len);
+        if end > start {
+            ranges.push(start..end);
+        }
+    }
+    replace_ranges(text, ranges, |_| REDACTED.to_string())
+}
+
+/// Mask raw input payloads following one of the [INPUT_DATA_KEYS], returning `None` if there
+/// are none.
+fn redact_input_data(text: &str) -> Option<String> {
+    let mut ranges = Vec::new();
+    for key in INPUT_DATA_KEYS {
+        for (i, _) in text.match_indices(key) {
+            let start = i + key.len();
+            let len = text[start..].bytes().take_while(u8::is_ascii_hexdigit).count();
+            if len > 0 {
+                ranges.push(start..start + len);
+            }
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    replace_ranges(text, ranges, |hex| format!("{REDACTED} {} bytes", hex.len() / 2))
+}
+
+/// Replace each of the sorted, non-overlapping byte ranges of `text`.
+fn replace_ranges(
+    text: &str,
+    ranges: Vec<std::ops::Range<usize>>,
+    replacement: impl Fn(&str) -> String,
+) -> Option<String> {
+    if ranges.is_empty() {
+        return None;
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in ranges {
+        out.push_str(&text[last..range.start]);
+        out.push_str(&replacement(&text[range.clone()]));
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    Some(out)
+}
+
+/// A [MakeWriter] that redacts everything written through the writers it makes.
+///
+/// Created with [Redactor::make_writer].
+#[derive(Clone, Debug)]
+pub struct RedactingMakeWriter<M> {
+    redactor: Redactor,
+    make_writer: M,
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter { redactor: self.redactor.clone(), inner: self.make_writer.make_writer() }
+    }
+}
+
+/// An [io::Write] that redacts the text written to it before passing it to the inner writer.
+///
+/// Each call to `write` is redacted on its own, which matches how [tracing_subscriber::fmt]
+/// writes a whole formatted event at once.
+#[derive(Debug)]
+pub struct RedactingWriter<W> {
+    redactor: Redactor,
+    inner: W,
+}
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        self.inner.write_all(self.redactor.redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_sensitive_values() {
+        let key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+        let redactor = Redactor::default().with_secret(format!("0x{key}")).with_secret("short");
+
+        assert_eq!(redactor.redact(&format!("key=0x{key}")), "key=0x[REDACTED]");
+        assert_eq!(redactor.redact(&key.to_uppercase()), REDACTED);
+        assert_eq!(redactor.redact("short"), "short", "short secrets are not redacted");
+
+        assert_eq!(
+            redactor.redact(
+                "fetching https://bucket.s3.amazonaws.com/input?X-Amz-Credential=AKIA%2F&X-Amz-Expires=300&X-Amz-Signature=abc123 done"
+            ),
+            "fetching https://bucket.s3.amazonaws.com/input?X-Amz-Credential=[REDACTED]&X-Amz-Expires=300&X-Amz-Signature=[REDACTED] done"
+        );
+
+        assert_eq!(
+            redactor.redact("RequestInput { inputType: Inline, data: 0x0102030405 }"),
+            "RequestInput { inputType: Inline, data: 0x[REDACTED] 5 bytes }"
+        );
+        assert_eq!(
+            redactor.redact(r#"{"data":"0xdeadbeef"}"#),
+            r#"{"data":"0x[REDACTED] 4 bytes"}"#
+        );
+
+        let disabled = redactor.with_enabled(false);
+        assert_eq!(disabled.redact(&format!("0x{key}")), format!("0x{key}"));
+    }
+}
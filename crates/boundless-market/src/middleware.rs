@@ -0,0 +1,198 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Middleware observing and modifying the requests sent by a [Client].
+//!
+//! A [Middleware] registered on a [Client] is called before each request is signed and submitted,
+//! where it may modify the request or abort its submission, and is notified of each
+//! [ClientEvent]: submissions, fulfillments, and failures. Middleware run in the order they were
+//! added. Built-in middleware are provided for logging ([LoggingMiddleware]) and metrics
+//! ([MetricsMiddleware]). Spend limits are enforced by the [Budget] of the client instead.
+//!
+//! ```no_run
+//! # fn example(client: boundless_market::Client) {
+//! use boundless_market::middleware::{LoggingMiddleware, MetricsMiddleware};
+//!
+//! let client = client
+//!     .with_middleware(LoggingMiddleware)
+//!     .with_middleware(MetricsMiddleware::default());
+//! # }
+//! ```
+//!
+//! [Client]: crate::Client
+//! [Budget]: crate::budget::Budget
+
+use std::sync::Mutex;
+
+use alloy::primitives::{utils::format_ether, U256};
+use async_trait::async_trait;
+
+use crate::{
+    client::{ClientError, SubmissionPath},
+    contracts::{Fulfillment, ProofRequest},
+};
+
+/// Event observed by a [Middleware].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ClientEvent<'a> {
+    /// A request was submitted.
+    Submitted {
+        /// The submitted request.
+        request: &'a ProofRequest,
+        /// Whether the request was submitted offchain or onchain.
+        path: SubmissionPath,
+    },
+    /// A request was fulfilled.
+    Fulfilled {
+        /// ID of the fulfilled request.
+        request_id: U256,
+        /// The fulfillment of the request.
+        fulfillment: &'a Fulfillment,
+    },
+    /// Submitting a request, or waiting for its fulfillment, failed.
+    Failed {
+        /// ID of the request.
+        request_id: U256,
+        /// The error returned to the caller.
+        error: &'a ClientError,
+    },
+}
+
+/// Cross-cutting behavior added to a [Client][crate::Client].
+///
+/// Both methods have a default implementation doing nothing, so implementations only need to
+/// provide the ones they use.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Called before a request is signed and submitted through the given path.
+    ///
+    /// The request may be modified. Returning an error aborts the submission, and the error is
+    /// returned to the caller.
+    async fn before_submit(
+        &self,
+        request: &mut ProofRequest,
+        path: SubmissionPath,
+    ) -> anyhow::Result<()> {
+        let _ = (request, path);
+        Ok(())
+    }
+
+    /// Called when the client observes an event.
+    async fn on_event(&self, event: &ClientEvent<'_>) {
+        let _ = event;
+    }
+}
+
+/// [Middleware] logging every [ClientEvent].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoggingMiddleware;
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn on_event(&self, event: &ClientEvent<'_>) {
+        match event {
+            ClientEvent::Submitted { request, path } => tracing::info!(
+                "Submitted request 0x{:x} {:?} with max price {} ETH",
+                request.id,
+                path,
+                format_ether(request.offer.maxPrice)
+            ),
+            ClientEvent::Fulfilled { request_id, .. } => {
+                tracing::info!("Request 0x{request_id:x} was fulfilled")
+            }
+            ClientEvent::Failed { request_id, error } => {
+                tracing::warn!("Request 0x{request_id:x} failed: {error}")
+            }
+        }
+    }
+}
+
+/// Counters collected by a [MetricsMiddleware].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ClientMetrics {
+    /// Number of requests submitted offchain.
+    pub submitted_offchain: u64,
+    /// Number of requests submitted onchain.
+    pub submitted_onchain: u64,
+    /// Number of requests observed to be fulfilled.
+    pub fulfilled: u64,
+    /// Number of failed submissions or waits for fulfillment.
+    pub failed: u64,
+    /// Sum of the max prices of the submitted requests, in wei.
+    pub committed: U256,
+}
+
+/// [Middleware] counting submissions, fulfillments, and failures.
+#[derive(Debug, Default)]
+pub struct MetricsMiddleware {
+    metrics: Mutex<ClientMetrics>,
+}
+
+impl MetricsMiddleware {
+    /// Current values of the counters.
+    pub fn metrics(&self) -> ClientMetrics {
+        *self.metrics.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn on_event(&self, event: &ClientEvent<'_>) {
+        let mut metrics = self.metrics.lock().unwrap();
+        match event {
+            ClientEvent::Submitted { request, path } => {
+                match path {
+                    SubmissionPath::Offchain => metrics.submitted_offchain += 1,
+                    SubmissionPath::Onchain => metrics.submitted_onchain += 1,
+                }
+                metrics.committed += request.offer.maxPrice;
+            }
+            ClientEvent::Fulfilled { .. } => metrics.fulfilled += 1,
+            ClientEvent::Failed { .. } => metrics.failed += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::Offer;
+
+    fn request(max_price: u64) -> ProofRequest {
+        ProofRequest {
+            offer: Offer { maxPrice: U256::from(max_price), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics() {
+        let middleware = MetricsMiddleware::default();
+        let request = request(10);
+        let error = ClientError::Error(anyhow::anyhow!("failed"));
+        for path in [SubmissionPath::Offchain, SubmissionPath::Onchain, SubmissionPath::Onchain] {
+            middleware.on_event(&ClientEvent::Submitted { request: &request, path }).await;
+        }
+        middleware.on_event(&ClientEvent::Failed { request_id: request.id, error: &error }).await;
+
+        let metrics = middleware.metrics();
+        assert_eq!(metrics.submitted_offchain, 1);
+        assert_eq!(metrics.submitted_onchain, 2);
+        assert_eq!(metrics.failed, 1);
+        assert_eq!(metrics.fulfilled, 0);
+        assert_eq!(metrics.committed, U256::from(30));
+    }
+}
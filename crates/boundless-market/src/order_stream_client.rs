@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use alloy::{
-    primitives::{Address, Signature, U256},
+    primitives::{keccak256, Address, Signature, U256},
     signers::{Error as SignerErr, Signer},
 };
 use alloy_primitives::B256;
@@ -23,9 +23,18 @@ use async_stream::stream;
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, Stream, StreamExt};
 use reqwest::Url;
+use reqwest::{
+    header::{HeaderValue, RETRY_AFTER},
+    StatusCode,
+};
 use serde::{Deserialize, Serialize};
 use siwe::Message as SiweMsg;
-use std::pin::Pin;
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    pin::Pin,
+    time::Duration,
+};
 use thiserror::Error;
 use time::OffsetDateTime;
 use tokio::net::TcpStream;
@@ -39,14 +48,130 @@ use crate::contracts::{eip712_domain, ProofRequest, RequestError};
 
 /// Order stream submission API path.
 pub const ORDER_SUBMISSION_PATH: &str = "/api/v1/submit_order";
+/// Order stream validation API path.
+pub const ORDER_VALIDATION_PATH: &str = "/api/v1/validate_order";
 /// Order stream order list API path.
 pub const ORDER_LIST_PATH: &str = "/api/v1/orders";
 /// Order stream nonce API path.
 pub const AUTH_GET_NONCE: &str = "/api/v1/nonce/";
 /// Order stream health check API path.
 pub const HEALTH_CHECK: &str = "/api/v1/health";
+/// Order stream archive export API path.
+pub const ORDER_ARCHIVE_PATH: &str = "/api/v1/archive";
+/// Maximum number of archived orders exported per request.
+pub const ARCHIVE_PAGE_SIZE: u64 = 1000;
+/// Number of consecutive rate limited responses after which an archive download fails.
+const ARCHIVE_MAX_RATE_LIMITED: u32 = 10;
 /// Order stream websocket path.
 pub const ORDER_WS_PATH: &str = "/ws/v1/orders";
+/// Header with which a websocket client requests an [OrderEncoding] for the orders it receives.
+pub const ORDER_ENCODING_HEADER: &str = "X-Order-Encoding";
+
+/// Upper bound on the size of a decompressed order frame, guarding against decompression bombs.
+const MAX_DECOMPRESSED_ORDER_LEN: u64 = 16 * 1024 * 1024;
+
+/// Encoding of the orders sent over the order stream websocket.
+///
+/// JSON text frames are the default and are understood by every client. The binary encodings
+/// send MessagePack, optionally deflate-compressed, in binary frames whose first byte identifies
+/// the encoding. They cut the bandwidth used by provers subscribed to high-volume streams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OrderEncoding {
+    /// JSON in text frames.
+    #[default]
+    Json,
+    /// MessagePack in binary frames.
+    MsgPack,
+    /// Deflate-compressed MessagePack in binary frames.
+    MsgPackDeflate,
+}
+
+/// An order encoded for a websocket frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodedOrder {
+    /// Payload of a text frame.
+    Text(String),
+    /// Payload of a binary frame.
+    Binary(Vec<u8>),
+}
+
+impl OrderEncoding {
+    /// Name of the encoding, as sent in the [ORDER_ENCODING_HEADER] header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MsgPack => "msgpack",
+            Self::MsgPackDeflate => "msgpack-deflate",
+        }
+    }
+
+    /// Tag prefixed to binary frames in this encoding.
+    fn tag(&self) -> Option<u8> {
+        match self {
+            Self::Json => None,
+            Self::MsgPack => Some(1),
+            Self::MsgPackDeflate => Some(2),
+        }
+    }
+
+    /// Encode an order for a websocket frame.
+    pub fn encode(&self, order: &impl Serialize) -> Result<EncodedOrder> {
+        let Some(tag) = self.tag() else {
+            return Ok(EncodedOrder::Text(serde_json::to_string(order)?));
+        };
+        let packed = rmp_serde::to_vec_named(order).context("failed to encode order")?;
+        let mut frame = vec![tag];
+        match self {
+            Self::MsgPackDeflate => {
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(&mut frame, flate2::Compression::default());
+                encoder.write_all(&packed)?;
+                encoder.finish()?;
+            }
+            _ => frame.extend_from_slice(&packed),
+        }
+        Ok(EncodedOrder::Binary(frame))
+    }
+
+    /// Decode an order from the payload of a binary frame, in any of the binary encodings.
+    pub fn decode_binary(frame: &[u8]) -> Result<OrderData> {
+        let (&tag, payload) = frame.split_first().context("empty order frame")?;
+        match tag {
+            1 => Ok(rmp_serde::from_slice(payload).context("failed to decode order")?),
+            2 => {
+                let mut packed = Vec::new();
+                flate2::read::DeflateDecoder::new(payload)
+                    .take(MAX_DECOMPRESSED_ORDER_LEN)
+                    .read_to_end(&mut packed)
+                    .context("failed to decompress order")?;
+                Ok(rmp_serde::from_slice(&packed).context("failed to decode order")?)
+            }
+            _ => anyhow::bail!("unknown order frame encoding: {tag}"),
+        }
+    }
+}
+
+impl std::fmt::Display for OrderEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for OrderEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "msgpack" => Ok(Self::MsgPack),
+            "msgpack-deflate" => Ok(Self::MsgPackDeflate),
+            other => anyhow::bail!(
+                "unknown order encoding {other:?}; expected json, msgpack or msgpack-deflate"
+            ),
+        }
+    }
+}
 
 /// Error body for API responses
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
@@ -78,6 +203,247 @@ pub enum OrderError {
     #[error("request error: {0}")]
     /// Request error.
     RequestError(#[from] RequestError),
+    #[error("invalid metadata: {0}")]
+    /// Invalid request metadata.
+    InvalidMetadata(String),
+}
+
+/// Error returned when submitting to, or querying, the order stream server.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum OrderStreamError {
+    /// The order failed validation, or could not be signed, before it was sent.
+    #[error("invalid order: {0}")]
+    InvalidOrder(#[from] OrderError),
+    /// The server rejected the request as invalid (HTTP 4xx).
+    #[error("order stream rejected the request ({status}): {msg}")]
+    Rejected {
+        /// HTTP status code of the response.
+        status: u16,
+        /// Error message returned by the server.
+        msg: String,
+    },
+    /// The order stream already holds an order with the same request digest, e.g. because the
+    /// same signed request was submitted twice (HTTP 409).
+    #[error("order was already submitted to the order stream: {msg}")]
+    Replayed {
+        /// Error message returned by the server.
+        msg: String,
+    },
+    /// The ID of the request was already used onchain, by a locked or fulfilled request
+    /// (HTTP 409).
+    #[error("request ID was already used onchain: {msg}")]
+    RequestIdUsed {
+        /// Error message returned by the server.
+        msg: String,
+    },
+    /// The request expired before the order stream accepted it.
+    #[error("request has expired: {msg}")]
+    Expired {
+        /// Error message describing the expired request.
+        msg: String,
+    },
+    /// The server is rate limiting this client (HTTP 429).
+    #[error("order stream rate limited the request, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay requested by the server in the `Retry-After` header, if any.
+        retry_after: Option<Duration>,
+    },
+    /// The server failed to process the request (HTTP 5xx). The request may be retried.
+    #[error("order stream server error ({status}): {msg}")]
+    Server {
+        /// HTTP status code of the response.
+        status: u16,
+        /// Error message returned by the server.
+        msg: String,
+    },
+    /// The request could not be sent, or the response could not be read.
+    #[error("order stream transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+    /// The order stream URL could not be joined with the API path.
+    #[error("invalid order stream URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+}
+
+impl OrderStreamError {
+    /// Whether the failed request may succeed if retried later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Server { .. } => true,
+            Self::Transport(err) => err.is_timeout() || err.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// Whether the order was refused as invalid, by this client or by the server.
+    ///
+    /// Submitting the same order again, to this or another order stream, fails the same way.
+    pub fn is_rejection(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidOrder(_)
+                | Self::Rejected { .. }
+                | Self::Replayed { .. }
+                | Self::RequestIdUsed { .. }
+                | Self::Expired { .. }
+        )
+    }
+
+    /// Whether the order stream could not be reached or failed to process the request, saying
+    /// nothing about the order itself.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, Self::Transport(_) | Self::Server { .. } | Self::RateLimited { .. })
+    }
+
+    /// Map an unsuccessful HTTP response to an error.
+    ///
+    /// The order stream returns an [ErrMsg] as the body of error responses. If the body is not an
+    /// [ErrMsg], e.g. when it was produced by a proxy, the raw body is used as the message.
+    /// Replayed and expired orders, and used request IDs, are recognized by the [ErrMsg] type.
+    fn from_response_parts(
+        status: StatusCode,
+        retry_after: Option<&HeaderValue>,
+        body: &str,
+    ) -> Self {
+        let (r#type, msg) = match serde_json::from_str::<ErrMsg>(body) {
+            Ok(err) => (Some(err.r#type), err.msg),
+            Err(_) => (None, body.trim().to_string()),
+        };
+        match r#type.as_deref() {
+            Some("DuplicateOrder") => return Self::Replayed { msg },
+            Some("RequestIdUsed") => return Self::RequestIdUsed { msg },
+            Some("ExpiredOrder") => return Self::Expired { msg },
+            _ => {}
+        }
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = retry_after
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Self::RateLimited { retry_after };
+        }
+        if status.is_server_error() {
+            Self::Server { status: status.as_u16(), msg }
+        } else {
+            Self::Rejected { status: status.as_u16(), msg }
+        }
+    }
+
+    async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        let retry_after = response.headers().get(RETRY_AFTER).cloned();
+        let body = response.text().await.unwrap_or_default();
+        Self::from_response_parts(status, retry_after.as_ref(), &body)
+    }
+}
+
+/// Reject an order whose request has expired, as the order stream would not accept it.
+fn check_not_expired(order: &Order) -> Result<(), OrderStreamError> {
+    if order.request.is_expired() {
+        return Err(OrderStreamError::Expired {
+            msg: format!(
+                "request 0x{:x} expired at {}",
+                order.request.id,
+                order.request.expires_at()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Maximum size, in bytes, of the JSON encoding of [RequestMetadata] accepted with an order.
+pub const MAX_METADATA_LEN: usize = 1024;
+
+/// Auxiliary metadata attached to a request submitted to the order stream.
+///
+/// Metadata is not part of the [ProofRequest] and is never sent onchain. It is opaque to the
+/// market, and is stored and returned with the order so that provers and analytics can attribute
+/// demand, e.g. by application.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RequestMetadata {
+    /// Name of the application that submitted the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_name: Option<String>,
+    /// Trace ID, used to correlate the request with the requestor's own logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// Free-form labels.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+}
+
+impl RequestMetadata {
+    /// Set the name of the application submitting the request.
+    pub fn with_app_name(self, app_name: impl Into<String>) -> Self {
+        Self { app_name: Some(app_name.into()), ..self }
+    }
+
+    /// Set the trace ID of the request.
+    pub fn with_trace_id(self, trace_id: impl Into<String>) -> Self {
+        Self { trace_id: Some(trace_id.into()), ..self }
+    }
+
+    /// Add a label to the metadata.
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Hash signed by the request client to attach this metadata to the request with the given
+    /// EIP-712 digest.
+    ///
+    /// Binding the signature to the request digest prevents the metadata from being replayed on
+    /// other requests.
+    pub fn signing_hash(&self, request_digest: B256) -> Result<B256, OrderError> {
+        let encoded = serde_json::to_vec(self)
+            .map_err(|e| OrderError::InvalidMetadata(format!("failed to encode: {e}")))?;
+        if encoded.len() > MAX_METADATA_LEN {
+            return Err(OrderError::InvalidMetadata(format!(
+                "encoded metadata is {} bytes, the maximum is {MAX_METADATA_LEN}",
+                encoded.len()
+            )));
+        }
+        Ok(keccak256([request_digest.as_slice(), &encoded].concat()))
+    }
+
+    /// Sign this metadata for the request with the given EIP-712 digest.
+    pub async fn sign(
+        self,
+        request_digest: B256,
+        signer: &impl Signer,
+    ) -> Result<SignedRequestMetadata, OrderError> {
+        let hash = self.signing_hash(request_digest)?;
+        let signature = signer.sign_hash(&hash).await.map_err(OrderError::InvalidSignature)?;
+        Ok(SignedRequestMetadata { metadata: self, signature })
+    }
+}
+
+/// [RequestMetadata] signed by the client of the request it is attached to.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, PartialEq)]
+pub struct SignedRequestMetadata {
+    /// The metadata.
+    pub metadata: RequestMetadata,
+    /// Signature by the request client over [RequestMetadata::signing_hash].
+    #[schema(value_type = Object)]
+    pub signature: Signature,
+}
+
+impl SignedRequestMetadata {
+    /// Verify that the metadata was signed by `client` for the request with the given digest.
+    pub fn verify(&self, request_digest: B256, client: Address) -> Result<(), OrderError> {
+        let hash = self.metadata.signing_hash(request_digest)?;
+        let signer = self
+            .signature
+            .recover_address_from_prehash(&hash)
+            .map_err(|e| OrderError::InvalidMetadata(format!("invalid signature: {e}")))?;
+        if signer != client {
+            return Err(OrderError::InvalidMetadata(format!(
+                "signed by {signer}, expected the request client {client}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 /// Order struct, containing a ProofRequest and its Signature
@@ -95,6 +461,9 @@ pub struct Order {
     // TODO: This should not be Signature. It should be Bytes or Vec<u8>.
     #[schema(value_type = Object)]
     pub signature: Signature,
+    /// Optional metadata attached to the order by the request client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<SignedRequestMetadata>,
 }
 
 /// Order data + order-stream id
@@ -109,6 +478,43 @@ pub struct OrderData {
     pub created_at: DateTime<Utc>,
 }
 
+/// Reason an order was moved to the order stream archive.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveReason {
+    /// The request expired.
+    Expired,
+    /// The request was fulfilled onchain.
+    Fulfilled,
+}
+
+impl ArchiveReason {
+    /// Name of the reason, as stored and exported by the order stream.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArchiveReason::Expired => "expired",
+            ArchiveReason::Fulfilled => "fulfilled",
+        }
+    }
+}
+
+/// An archived order, as exported by the order stream, one per line.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct ArchivedOrderData {
+    /// Order stream id
+    pub id: i64,
+    /// Order data
+    pub order: Order,
+    /// Time the order was submitted
+    #[schema(value_type = Option<String>)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Time the order was archived
+    #[schema(value_type = String)]
+    pub archived_at: DateTime<Utc>,
+    /// Reason the order was archived
+    pub reason: ArchiveReason,
+}
+
 /// Nonce object for authentication to order-stream websocket
 #[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
 pub struct Nonce {
@@ -126,10 +532,26 @@ pub struct SubmitOrderRes {
     pub request_id: U256,
 }
 
+/// Response for validating an order without submitting it
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone)]
+pub struct ValidateOrderRes {
+    /// Request ID validated
+    #[schema(value_type = Object)]
+    pub request_id: U256,
+    /// Digest of the validated request
+    #[schema(value_type = Object)]
+    pub request_digest: B256,
+}
+
 impl Order {
     /// Create a new Order
     pub fn new(request: ProofRequest, request_digest: B256, signature: Signature) -> Self {
-        Self { request, request_digest, signature }
+        Self { request, request_digest, signature, metadata: None }
+    }
+
+    /// Attach signed metadata to the order.
+    pub fn with_metadata(self, metadata: SignedRequestMetadata) -> Self {
+        Self { metadata: Some(metadata), ..self }
     }
 
     /// Validate the Order
@@ -145,6 +567,9 @@ impl Order {
             market_address,
             chain_id,
         )?;
+        if let Some(metadata) = &self.metadata {
+            metadata.verify(self.request_digest, self.request.client_address())?;
+        }
         Ok(())
     }
 }
@@ -212,46 +637,132 @@ pub struct OrderStreamClient {
 impl OrderStreamClient {
     /// Create a new client
     pub fn new(base_url: Url, boundless_market_address: Address, chain_id: u64) -> Self {
-        Self { client: reqwest::Client::new(), base_url, boundless_market_address, chain_id }
+        Self::new_with_client(reqwest::Client::new(), base_url, boundless_market_address, chain_id)
+    }
+
+    /// Create a new client that sends requests using the given [reqwest::Client].
+    ///
+    /// This allows the order stream client to share a connection pool with other components.
+    pub fn new_with_client(
+        client: reqwest::Client,
+        base_url: Url,
+        boundless_market_address: Address,
+        chain_id: u64,
+    ) -> Self {
+        Self { client, base_url, boundless_market_address, chain_id }
     }
 
     /// Submit a proof request to the order stream server
+    ///
+    /// Submission uses a single REST call, and does not require a WebSocket connection.
     pub async fn submit_request(
         &self,
         request: &ProofRequest,
         signer: &impl Signer,
-    ) -> Result<Order> {
-        let url = self.base_url.join(ORDER_SUBMISSION_PATH)?;
-        let signature =
-            request.sign_request(signer, self.boundless_market_address, self.chain_id).await?;
-        let domain = eip712_domain(self.boundless_market_address, self.chain_id);
-        let request_digest = request.eip712_signing_hash(&domain.alloy_struct());
-        let order = Order { request: request.clone(), request_digest, signature };
-        order.validate(self.boundless_market_address, self.chain_id)?;
-        let order_json = serde_json::to_value(&order)?;
-        let response = self
-            .client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .json(&order_json)
-            .send()
-            .await?;
+    ) -> Result<Order, OrderStreamError> {
+        self.submit_request_with_metadata(request, signer, None).await
+    }
 
-        // Check for any errors in the response
-        if let Err(err) = response.error_for_status_ref() {
-            let error_message = match response.json::<serde_json::Value>().await {
-                Ok(json_body) => {
-                    json_body["msg"].as_str().unwrap_or("Unknown server error").to_string()
-                }
-                Err(_) => "Failed to read server error message".to_string(),
-            };
+    /// Submit a proof request to the order stream server, attaching the given metadata.
+    ///
+    /// The metadata is signed with `signer`, which must be the client of the request.
+    pub async fn submit_request_with_metadata(
+        &self,
+        request: &ProofRequest,
+        signer: &impl Signer,
+        metadata: impl Into<Option<RequestMetadata>>,
+    ) -> Result<Order, OrderStreamError> {
+        let order = self.sign_order(request, signer, metadata.into()).await?;
+        self.submit_order(&order).await?;
+        Ok(order)
+    }
 
-            return Err(anyhow::Error::new(err).context(error_message));
+    /// Sign a proof request with `signer` and run the checks of submission on the order stream
+    /// server, without submitting it.
+    ///
+    /// See [Self::validate_order].
+    pub async fn validate_request(
+        &self,
+        request: &ProofRequest,
+        signer: &impl Signer,
+        metadata: impl Into<Option<RequestMetadata>>,
+    ) -> Result<ValidateOrderRes, OrderStreamError> {
+        let order = self.sign_order(request, signer, metadata.into()).await?;
+        self.validate_order(&order).await
+    }
+
+    /// Run the checks of submission on the order stream server, without submitting the order.
+    ///
+    /// The server checks the signature of the order against its own deployment, the expiry of
+    /// the request, that the order was not already submitted, that the request ID was not used
+    /// onchain, and that the requestor has deposited enough to pay the max price of the request.
+    /// The first failed check is returned as the error submission would return, e.g.
+    /// [OrderStreamError::Replayed] for a duplicate order.
+    ///
+    /// Unlike [Self::submit_order], the order is not validated locally first, so that a mismatch
+    /// between the deployment of the client and that of the server is reported by the server.
+    pub async fn validate_order(
+        &self,
+        order: &Order,
+    ) -> Result<ValidateOrderRes, OrderStreamError> {
+        let url = self.base_url.join(ORDER_VALIDATION_PATH)?;
+        let response = self.client.post(url).json(order).send().await?;
+        if !response.status().is_success() {
+            return Err(OrderStreamError::from_response(response).await);
         }
+        Ok(response.json().await?)
+    }
 
+    pub(crate) async fn sign_order(
+        &self,
+        request: &ProofRequest,
+        signer: &impl Signer,
+        metadata: Option<RequestMetadata>,
+    ) -> Result<Order, OrderStreamError> {
+        let signature = request
+            .sign_request(signer, self.boundless_market_address, self.chain_id)
+            .await
+            .map_err(OrderError::from)?;
+        let domain = eip712_domain(self.boundless_market_address, self.chain_id);
+        let request_digest = request.eip712_signing_hash(&domain.alloy_struct());
+        let mut order = Order::new(request.clone(), request_digest, signature);
+        if let Some(metadata) = metadata {
+            order = order.with_metadata(metadata.sign(request_digest, signer).await?);
+        }
         Ok(order)
     }
 
+    /// Submit an order that was already signed, e.g. by another service, to the order stream
+    /// server.
+    ///
+    /// The order is validated before it is sent, and an expired request is rejected with
+    /// [OrderStreamError::Expired] without contacting the server. If the server already holds the
+    /// order, [OrderStreamError::Replayed] is returned.
+    pub async fn submit_order(&self, order: &Order) -> Result<SubmitOrderRes, OrderStreamError> {
+        order.validate(self.boundless_market_address, self.chain_id)?;
+        check_not_expired(order)?;
+        let url = self.base_url.join(ORDER_SUBMISSION_PATH)?;
+        let response = self.client.post(url).json(order).send().await?;
+        if !response.status().is_success() {
+            return Err(OrderStreamError::from_response(response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Check that the order stream server is up and able to serve requests.
+    ///
+    /// Returns the round-trip time of the health check. A server that is up but unhealthy, e.g.
+    /// because it lost its database connection, returns [OrderStreamError::Server].
+    pub async fn health_check(&self) -> Result<Duration, OrderStreamError> {
+        let url = self.base_url.join(HEALTH_CHECK)?;
+        let started = std::time::Instant::now();
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(OrderStreamError::from_response(response).await);
+        }
+        Ok(started.elapsed())
+    }
+
     /// Fetch an order from the order stream server.
     ///
     /// If multiple orders are found, the `request_digest` must be provided to select the correct order.
@@ -292,16 +803,120 @@ impl OrderStreamClient {
         }
     }
 
-    /// Get the nonce from the order stream service for websocket auth
-    pub async fn get_nonce(&self, address: Address) -> Result<Nonce> {
+    /// List orders from the order stream server, in the order they were submitted.
+    ///
+    /// Returns at most `limit` orders, starting at order-stream id `offset`.
+    pub async fn list_orders(&self, offset: u64, limit: u64) -> Result<Vec<OrderData>> {
+        let mut url = self.base_url.join(ORDER_LIST_PATH)?;
+        url.query_pairs_mut()
+            .append_pair("offset", &offset.to_string())
+            .append_pair("limit", &limit.to_string());
+        let response = self.client.get(url).send().await?;
+
+        if !response.status().is_success() {
+            let error_message = match response.json::<serde_json::Value>().await {
+                Ok(json_body) => {
+                    json_body["msg"].as_str().unwrap_or("Unknown server error").to_string()
+                }
+                Err(_) => "Failed to read server error message".to_string(),
+            };
+
+            return Err(anyhow::Error::msg(error_message));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// List orders from the order stream server, from the most recently submitted.
+    ///
+    /// Returns at most `limit` orders, starting at order-stream id `offset` and going back. An
+    /// offset of 0 starts at the newest order.
+    pub async fn list_recent_orders(&self, offset: u64, limit: u64) -> Result<Vec<OrderData>> {
+        let mut url = self.base_url.join(ORDER_LIST_PATH)?;
+        url.query_pairs_mut()
+            .append_pair("offset", &offset.to_string())
+            .append_pair("limit", &limit.to_string())
+            .append_pair("desc", "true");
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(OrderStreamError::from_response(response).await.into());
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Download the orders archived by the order stream server, as JSON lines.
+    ///
+    /// Only orders submitted within `[from, to)`, given as UNIX timestamps, are downloaded. Each
+    /// line written to `out` is an [ArchivedOrderData]. The archive is downloaded in pages of up to
+    /// [ARCHIVE_PAGE_SIZE] orders, waiting whenever the server rate limits the client. Returns the
+    /// number of orders written.
+    pub async fn download_archive(
+        &self,
+        from: Option<u64>,
+        to: Option<u64>,
+        mut out: impl std::io::Write,
+    ) -> Result<u64> {
+        let mut orders = 0;
+        let mut after = 0;
+        let mut rate_limited = 0;
+        loop {
+            let mut url = self.base_url.join(ORDER_ARCHIVE_PATH)?;
+            if let Some(from) = from {
+                url.query_pairs_mut().append_pair("from", &from.to_string());
+            }
+            if let Some(to) = to {
+                url.query_pairs_mut().append_pair("to", &to.to_string());
+            }
+            url.query_pairs_mut()
+                .append_pair("after", &after.to_string())
+                .append_pair("limit", &ARCHIVE_PAGE_SIZE.to_string());
+            let response = self.client.get(url).send().await?;
+            if !response.status().is_success() {
+                match OrderStreamError::from_response(response).await {
+                    OrderStreamError::RateLimited { retry_after }
+                        if rate_limited < ARCHIVE_MAX_RATE_LIMITED =>
+                    {
+                        rate_limited += 1;
+                        tokio::time::sleep(retry_after.unwrap_or(Duration::from_secs(1))).await;
+                        continue;
+                    }
+                    err => return Err(err.into()),
+                }
+            }
+            rate_limited = 0;
+
+            let page = response.bytes().await?;
+            let lines: Vec<&[u8]> =
+                page.split(|&byte| byte == b'\n').filter(|line| !line.is_empty()).collect();
+            for line in &lines {
+                out.write_all(line)?;
+                out.write_all(b"\n")?;
+            }
+            orders += lines.len() as u64;
+            match lines.last() {
+                Some(last) if lines.len() as u64 == ARCHIVE_PAGE_SIZE => {
+                    let last: ArchivedOrderData = serde_json::from_slice(last)
+                        .context("failed to parse an archived order")?;
+                    after = last.id;
+                }
+                _ => break,
+            }
+        }
+        out.flush()?;
+        Ok(orders)
+    }
+
+    /// Get the current nonce of `address` from the order stream service, used for websocket auth.
+    ///
+    /// The server registers the address if it has not seen it before.
+    pub async fn get_nonce(&self, address: Address) -> Result<Nonce, OrderStreamError> {
         let url = self.base_url.join(AUTH_GET_NONCE)?.join(&address.to_string())?;
         let res = self.client.get(url).send().await?;
         if !res.status().is_success() {
-            anyhow::bail!("Http error {} fetching nonce", res.status())
+            return Err(OrderStreamError::from_response(res).await);
         }
-        let nonce = res.json().await?;
-
-        Ok(nonce)
+        Ok(res.json().await?)
     }
 
     /// Return a WebSocket stream connected to the order stream server
@@ -313,6 +928,19 @@ impl OrderStreamClient {
     pub async fn connect_async(
         &self,
         signer: &impl Signer,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        self.connect_async_with_encoding(signer, OrderEncoding::Json).await
+    }
+
+    /// Return a WebSocket stream connected to the order stream server, requesting that orders
+    /// are sent in the given [OrderEncoding].
+    ///
+    /// Servers that do not support binary encodings ignore the request and send JSON, which
+    /// [order_stream] handles as well.
+    pub async fn connect_async_with_encoding(
+        &self,
+        signer: &impl Signer,
+        encoding: OrderEncoding,
     ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>> {
         let nonce = self
             .get_nonce(signer.address())
@@ -341,6 +969,12 @@ impl OrderStreamClient {
         request
             .headers_mut()
             .insert("X-Auth-Data", auth_json.parse().context("failed to parse auth message")?);
+        if encoding != OrderEncoding::Json {
+            request.headers_mut().insert(
+                ORDER_ENCODING_HEADER,
+                encoding.as_str().parse().context("failed to parse order encoding")?,
+            );
+        }
 
         // Connect to the WebSocket server and return the socket
         let (socket, _) = match connect_async(request).await {
@@ -425,6 +1059,15 @@ pub fn order_stream(
                                 }
                             }
                         }
+                        Some(Ok(tungstenite::Message::Binary(frame))) => {
+                            match OrderEncoding::decode_binary(&frame) {
+                                Ok(order) => yield order,
+                                Err(err) => {
+                                    tracing::warn!("Failed to parse order: {:?}", err);
+                                    continue;
+                                }
+                            }
+                        }
                         // Reply to Ping's inline
                         Some(Ok(tungstenite::Message::Ping(data))) => {
                             tracing::trace!("Responding to ping");
@@ -450,7 +1093,7 @@ pub fn order_stream(
                             break;
                         }
                         Some(Ok(other)) => {
-                            tracing::debug!("Ignoring non-text message: {:?}", other);
+                            tracing::debug!("Ignoring non-order message: {:?}", other);
                             continue;
                         }
                         Some(Err(err)) => {
@@ -484,10 +1127,98 @@ pub fn order_stream(
     })
 }
 
+/// Blocking client for the order stream REST API.
+///
+/// Intended for constrained environments, such as serverless functions, that cannot run an async
+/// runtime or hold a WebSocket connection open. The blocking client must not be used from within
+/// an async runtime.
+#[cfg(feature = "blocking")]
+pub mod blocking {
+    use alloy::{primitives::Address, signers::SignerSync};
+    use alloy_sol_types::SolStruct;
+    use reqwest::{blocking::Response, header::RETRY_AFTER, Url};
+
+    use super::{
+        check_not_expired, Nonce, Order, OrderError, OrderStreamError, SubmitOrderRes,
+        AUTH_GET_NONCE, ORDER_SUBMISSION_PATH,
+    };
+    use crate::contracts::{eip712_domain, ProofRequest};
+
+    /// Blocking counterpart of [super::OrderStreamClient], supporting submission only.
+    #[derive(Clone, Debug)]
+    pub struct OrderStreamClient {
+        /// HTTP client
+        pub client: reqwest::blocking::Client,
+        /// Base URL of the order stream server
+        pub base_url: Url,
+        /// Address of the market contract
+        pub boundless_market_address: Address,
+        /// Chain ID of the network
+        pub chain_id: u64,
+    }
+
+    impl OrderStreamClient {
+        /// Create a new client
+        pub fn new(base_url: Url, boundless_market_address: Address, chain_id: u64) -> Self {
+            Self {
+                client: reqwest::blocking::Client::new(),
+                base_url,
+                boundless_market_address,
+                chain_id,
+            }
+        }
+
+        /// Sign a proof request with `signer` and submit it to the order stream server.
+        pub fn submit_request(
+            &self,
+            request: &ProofRequest,
+            signer: &impl SignerSync,
+        ) -> Result<Order, OrderStreamError> {
+            let domain = eip712_domain(self.boundless_market_address, self.chain_id);
+            let request_digest = request.eip712_signing_hash(&domain.alloy_struct());
+            let signature =
+                signer.sign_hash_sync(&request_digest).map_err(OrderError::InvalidSignature)?;
+            let order = Order::new(request.clone(), request_digest, signature);
+            self.submit_order(&order)?;
+            Ok(order)
+        }
+
+        /// Submit an order that was already signed to the order stream server.
+        ///
+        /// The order is validated before it is sent.
+        pub fn submit_order(&self, order: &Order) -> Result<SubmitOrderRes, OrderStreamError> {
+            order.validate(self.boundless_market_address, self.chain_id)?;
+            check_not_expired(order)?;
+            let url = self.base_url.join(ORDER_SUBMISSION_PATH)?;
+            let response = self.client.post(url).json(order).send()?;
+            Ok(check_response(response)?.json()?)
+        }
+
+        /// Get the nonce from the order stream service for the given address.
+        pub fn get_nonce(&self, address: Address) -> Result<Nonce, OrderStreamError> {
+            let url = self.base_url.join(AUTH_GET_NONCE)?.join(&address.to_string())?;
+            let response = self.client.get(url).send()?;
+            Ok(check_response(response)?.json()?)
+        }
+    }
+
+    fn check_response(response: Response) -> Result<Response, OrderStreamError> {
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let retry_after = response.headers().get(RETRY_AFTER).cloned();
+        let body = response.text().unwrap_or_default();
+        Err(OrderStreamError::from_response_parts(status, retry_after.as_ref(), &body))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy::signers::local::LocalSigner;
+    use crate::contracts::{Offer, Predicate, RequestId, RequestInput, Requirements};
+    use alloy::{primitives::Bytes, signers::local::LocalSigner};
+    use risc0_zkvm::Digest;
 
     #[tokio::test]
     async fn auth_msg_verify() {
@@ -517,4 +1248,176 @@ mod tests {
         let auth_msg = AuthMsg::new(nonce.clone(), &origin, &signer).await.unwrap();
         auth_msg.verify("localhost:8585", "BAD_NONCE").await.unwrap();
     }
+
+    #[tokio::test]
+    async fn metadata_sign_verify() {
+        let signer = LocalSigner::random();
+        let digest = B256::repeat_byte(1);
+        let metadata = RequestMetadata::default().with_app_name("app").with_label("env", "test");
+        let signed = metadata.clone().sign(digest, &signer).await.unwrap();
+        signed.verify(digest, signer.address()).unwrap();
+
+        // The signature is bound to the request digest and the signer.
+        assert!(signed.verify(B256::repeat_byte(2), signer.address()).is_err());
+        assert!(signed.verify(digest, Address::ZERO).is_err());
+
+        let mut tampered = signed.clone();
+        tampered.metadata = metadata.with_trace_id("trace");
+        assert!(tampered.verify(digest, signer.address()).is_err());
+    }
+
+    #[tokio::test]
+    async fn metadata_too_large() {
+        let signer = LocalSigner::random();
+        let metadata = RequestMetadata::default().with_label("big", "x".repeat(MAX_METADATA_LEN));
+        let err = metadata.sign(B256::ZERO, &signer).await.unwrap_err();
+        assert!(matches!(err, OrderError::InvalidMetadata(_)));
+    }
+
+    #[test]
+    fn order_stream_error_from_response() {
+        let err = OrderStreamError::from_response_parts(
+            StatusCode::BAD_REQUEST,
+            None,
+            r#"{"type":"InvalidOrder","msg":"bad signature"}"#,
+        );
+        assert!(
+            matches!(&err, OrderStreamError::Rejected { status: 400, msg } if msg == "bad signature")
+        );
+        assert!(!err.is_retryable());
+
+        let retry_after = HeaderValue::from_static("12");
+        let err = OrderStreamError::from_response_parts(
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(&retry_after),
+            "slow down",
+        );
+        assert!(matches!(
+            err,
+            OrderStreamError::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(12)
+        ));
+
+        let err =
+            OrderStreamError::from_response_parts(StatusCode::BAD_GATEWAY, None, "upstream down\n");
+        assert!(
+            matches!(&err, OrderStreamError::Server { status: 502, msg } if msg == "upstream down")
+        );
+        assert!(err.is_retryable());
+
+        let err = OrderStreamError::from_response_parts(
+            StatusCode::CONFLICT,
+            None,
+            r#"{"type":"DuplicateOrder","msg":"already submitted"}"#,
+        );
+        assert!(matches!(&err, OrderStreamError::Replayed { msg } if msg == "already submitted"));
+        assert!(!err.is_retryable());
+
+        // Other conflicts, e.g. from a proxy, are not taken for a replayed order.
+        let err = OrderStreamError::from_response_parts(StatusCode::CONFLICT, None, "conflict");
+        assert!(
+            matches!(&err, OrderStreamError::Rejected { status: 409, msg } if msg == "conflict")
+        );
+
+        let err = OrderStreamError::from_response_parts(
+            StatusCode::CONFLICT,
+            None,
+            r#"{"type":"RequestIdUsed","msg":"request 0x1 was already locked or fulfilled onchain"}"#,
+        );
+        assert!(matches!(err, OrderStreamError::RequestIdUsed { .. }));
+        assert!(err.is_rejection());
+        assert!(!err.is_retryable());
+
+        let err = OrderStreamError::from_response_parts(
+            StatusCode::BAD_REQUEST,
+            None,
+            r#"{"type":"ExpiredOrder","msg":"request 0x1 has expired"}"#,
+        );
+        assert!(matches!(err, OrderStreamError::Expired { .. }));
+        assert!(err.is_rejection());
+        assert!(!err.is_unavailable());
+    }
+
+    #[tokio::test]
+    async fn health_check_unreachable() {
+        let client = OrderStreamClient::new(
+            "http://127.0.0.1:1".parse().unwrap(),
+            Address::ZERO,
+            Default::default(),
+        );
+        let err = client.health_check().await.unwrap_err();
+        assert!(err.is_unavailable(), "{err:?}");
+        assert!(!err.is_rejection());
+    }
+
+    #[tokio::test]
+    async fn order_encoding_roundtrip() {
+        let signer = LocalSigner::random();
+        let request = ProofRequest {
+            id: RequestId::u256(signer.address(), 1),
+            requirements: Requirements::new(Predicate::prefix_match(
+                Digest::ZERO,
+                Bytes::from(vec![7u8; 64]),
+            )),
+            imageUrl: "https://example.com/program".to_string(),
+            ..Default::default()
+        };
+        let digest = B256::repeat_byte(3);
+        let signature = request.sign_request(&signer, Address::ZERO, 1).await.unwrap();
+        let metadata = RequestMetadata::default().with_app_name("app");
+        let order = OrderData {
+            id: 42,
+            order: Order::new(request, digest, signature)
+                .with_metadata(metadata.sign(digest, &signer).await.unwrap()),
+            created_at: Utc::now(),
+        };
+
+        let EncodedOrder::Text(json) = OrderEncoding::Json.encode(&order).unwrap() else {
+            panic!("JSON orders must be sent as text");
+        };
+        for encoding in [OrderEncoding::MsgPack, OrderEncoding::MsgPackDeflate] {
+            let EncodedOrder::Binary(frame) = encoding.encode(&order).unwrap() else {
+                panic!("{encoding} orders must be sent as binary");
+            };
+            let decoded = OrderEncoding::decode_binary(&frame).unwrap();
+            assert_eq!(decoded.id, order.id);
+            assert_eq!(decoded.order, order.order);
+            assert_eq!(decoded.created_at, order.created_at);
+            assert!(frame.len() < json.len(), "{encoding} is not smaller than JSON");
+            assert_eq!(encoding.as_str().parse::<OrderEncoding>().unwrap(), encoding);
+        }
+        assert!(OrderEncoding::decode_binary(&[9, 1, 2]).is_err());
+        assert!("xml".parse::<OrderEncoding>().is_err());
+    }
+
+    #[tokio::test]
+    async fn submit_expired_order() {
+        let signer = LocalSigner::random();
+        let request = ProofRequest {
+            id: RequestId::u256(signer.address(), 1),
+            requirements: Requirements::new(Predicate::prefix_match(
+                Digest::ZERO,
+                Bytes::default(),
+            )),
+            imageUrl: "https://dev.null".to_string(),
+            input: RequestInput::builder().build_inline().unwrap(),
+            offer: Offer {
+                minPrice: U256::from(0),
+                maxPrice: U256::from(1),
+                rampUpStart: 1,
+                timeout: 100,
+                rampUpPeriod: 1,
+                lockTimeout: 100,
+                lockCollateral: U256::from(0),
+            },
+        };
+        let signature = request.sign_request(&signer, Address::ZERO, 1).await.unwrap();
+        let request_digest = request.signing_hash(Address::ZERO, 1).unwrap();
+        let order = Order::new(request, request_digest, signature);
+
+        // Nothing listens on this URL; the order is rejected before it is sent.
+        let client =
+            OrderStreamClient::new("http://127.0.0.1:1".parse().unwrap(), Address::ZERO, 1);
+        let err = client.submit_order(&order).await.unwrap_err();
+        assert!(matches!(err, OrderStreamError::Expired { .. }), "unexpected error: {err:?}");
+    }
 }
@@ -0,0 +1,263 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+};
+
+use alloy::{
+    network::Ethereum,
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::Log,
+};
+
+use crate::{
+    contracts::{
+        boundless_market::{BoundlessMarketService, MarketError},
+        IBoundlessMarket::{ProverSlashed, RequestFulfilled, RequestLocked},
+    },
+    log_query::query_events_chunked,
+};
+
+/// Statistics of a prover on the market, collected from market events.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProverStats {
+    /// Number of requests locked by the prover.
+    pub locked: u64,
+    /// Number of requests fulfilled by the prover.
+    pub fulfilled: u64,
+    /// Number of requests locked by the prover for which it was slashed.
+    pub slashed: u64,
+}
+
+impl ProverStats {
+    /// Fraction of locked requests for which the prover was not slashed.
+    ///
+    /// Returns `None` if the prover has not locked any request.
+    pub fn score(&self) -> Option<f64> {
+        (self.locked > 0)
+            .then(|| self.locked.saturating_sub(self.slashed) as f64 / self.locked as f64)
+    }
+}
+
+/// Result of checking a prover against a [ProverPolicy].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PolicyVerdict {
+    /// The prover is allowed by the policy.
+    Allowed,
+    /// The prover is on the deny-list.
+    Denied(Address),
+    /// An allow-list is set and the prover is not on it.
+    NotAllowListed(Address),
+    /// The score of the prover is below the minimum score.
+    LowScore {
+        /// Address of the prover.
+        prover: Address,
+        /// Score of the prover, see [ProverStats::score].
+        score: f64,
+    },
+}
+
+impl PolicyVerdict {
+    /// Whether the prover is allowed by the policy.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, PolicyVerdict::Allowed)
+    }
+}
+
+/// Policy describing which provers a requestor accepts for its requests.
+///
+/// The market does not let requestors restrict which prover locks or fulfills a request, so the
+/// policy is not enforced onchain. Instead, attach it to a [Client][crate::Client] with
+/// [ClientBuilder::with_prover_policy][crate::client::ClientBuilder::with_prover_policy] and use
+/// [Client::check_prover_policy][crate::Client::check_prover_policy] to detect and report when a
+/// disallowed prover locks a request.
+///
+/// ```rust
+/// # use boundless_market::prover_policy::ProverPolicy;
+/// use alloy::primitives::Address;
+///
+/// let policy = ProverPolicy::default()
+///     .with_denied([Address::repeat_byte(0x01)])
+///     .with_min_score(0.9)
+///     .with_min_locked(10);
+/// assert!(!policy.check(Address::repeat_byte(0x01)).is_allowed());
+/// assert!(policy.check(Address::repeat_byte(0x02)).is_allowed());
+/// ```
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ProverPolicy {
+    /// If set, only provers on this list are allowed.
+    pub allowed: Option<HashSet<Address>>,
+    /// Provers that are never allowed.
+    pub denied: HashSet<Address>,
+    /// Minimum [ProverStats::score] for a prover to be allowed.
+    pub min_score: Option<f64>,
+    /// Number of locked requests a prover needs before [Self::min_score] is applied to it.
+    pub min_locked: u64,
+    /// Statistics of the provers on the market, used to compute their scores.
+    pub stats: HashMap<Address, ProverStats>,
+}
+
+impl ProverPolicy {
+    /// Only allow the given provers.
+    pub fn with_allowed(self, provers: impl IntoIterator<Item = Address>) -> Self {
+        Self { allowed: Some(provers.into_iter().collect()), ..self }
+    }
+
+    /// Never allow the given provers.
+    pub fn with_denied(self, provers: impl IntoIterator<Item = Address>) -> Self {
+        Self { denied: provers.into_iter().collect(), ..self }
+    }
+
+    /// Set the minimum score for a prover to be allowed.
+    pub fn with_min_score(self, min_score: impl Into<Option<f64>>) -> Self {
+        Self { min_score: min_score.into(), ..self }
+    }
+
+    /// Set the number of locked requests a prover needs before the minimum score is applied.
+    pub fn with_min_locked(self, min_locked: u64) -> Self {
+        Self { min_locked, ..self }
+    }
+
+    /// Set the statistics of the provers on the market.
+    pub fn with_stats(self, stats: HashMap<Address, ProverStats>) -> Self {
+        Self { stats, ..self }
+    }
+
+    /// Check whether the given prover is allowed by this policy.
+    pub fn check(&self, prover: Address) -> PolicyVerdict {
+        if self.denied.contains(&prover) {
+            return PolicyVerdict::Denied(prover);
+        }
+        if self.allowed.as_ref().is_some_and(|allowed| !allowed.contains(&prover)) {
+            return PolicyVerdict::NotAllowListed(prover);
+        }
+        if let (Some(min_score), Some(stats)) = (self.min_score, self.stats.get(&prover)) {
+            if stats.locked >= self.min_locked {
+                let score = stats.score().unwrap_or(1.0);
+                if score < min_score {
+                    return PolicyVerdict::LowScore { prover, score };
+                }
+            }
+        }
+        PolicyVerdict::Allowed
+    }
+
+    /// Refresh the statistics of the provers from the market events in the given block range.
+    pub async fn refresh_stats<P>(
+        &mut self,
+        market: &BoundlessMarketService<P>,
+        range: RangeInclusive<u64>,
+        chunk_size: u64,
+    ) -> Result<(), MarketError>
+    where
+        P: Provider<Ethereum> + 'static + Clone,
+    {
+        self.stats = collect_prover_stats(market, range, chunk_size).await?;
+        Ok(())
+    }
+}
+
+/// Collect the [ProverStats] of every prover active on the market in the given block range.
+///
+/// Slashes are attributed to the prover that locked the request, so only slashes of requests
+/// locked within the range are counted.
+pub async fn collect_prover_stats<P>(
+    market: &BoundlessMarketService<P>,
+    range: RangeInclusive<u64>,
+    chunk_size: u64,
+) -> Result<HashMap<Address, ProverStats>, MarketError>
+where
+    P: Provider<Ethereum> + 'static + Clone,
+{
+    let instance = market.instance();
+    let provider = instance.provider();
+
+    let mut stats: HashMap<Address, ProverStats> = HashMap::new();
+    let mut lockers: HashMap<U256, Address> = HashMap::new();
+
+    let locked: Vec<(RequestLocked, Log)> = query_events_chunked(
+        provider,
+        instance.RequestLocked_filter().filter,
+        range.clone(),
+        chunk_size,
+        |_| {},
+    )
+    .await?;
+    for (event, _) in locked {
+        stats.entry(event.prover).or_default().locked += 1;
+        lockers.insert(event.requestId, event.prover);
+    }
+
+    let fulfilled: Vec<(RequestFulfilled, Log)> = query_events_chunked(
+        provider,
+        instance.RequestFulfilled_filter().filter,
+        range.clone(),
+        chunk_size,
+        |_| {},
+    )
+    .await?;
+    for (event, _) in fulfilled {
+        stats.entry(event.prover).or_default().fulfilled += 1;
+    }
+
+    let slashed: Vec<(ProverSlashed, Log)> = query_events_chunked(
+        provider,
+        instance.ProverSlashed_filter().filter,
+        range,
+        chunk_size,
+        |_| {},
+    )
+    .await?;
+    for (event, _) in slashed {
+        if let Some(prover) = lockers.get(&event.requestId) {
+            stats.entry(*prover).or_default().slashed += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_check() {
+        let good = Address::repeat_byte(1);
+        let bad = Address::repeat_byte(2);
+        let new = Address::repeat_byte(3);
+        let stats = HashMap::from([
+            (good, ProverStats { locked: 10, fulfilled: 10, slashed: 0 }),
+            (bad, ProverStats { locked: 10, fulfilled: 5, slashed: 5 }),
+            (new, ProverStats { locked: 1, fulfilled: 0, slashed: 1 }),
+        ]);
+        let policy =
+            ProverPolicy::default().with_min_score(0.9).with_min_locked(5).with_stats(stats);
+
+        assert_eq!(policy.check(good), PolicyVerdict::Allowed);
+        assert_eq!(policy.check(bad), PolicyVerdict::LowScore { prover: bad, score: 0.5 });
+        // Provers with too few locks are not scored.
+        assert_eq!(policy.check(new), PolicyVerdict::Allowed);
+
+        let policy = policy.with_allowed([good, bad]).with_denied([good]);
+        assert_eq!(policy.check(good), PolicyVerdict::Denied(good));
+        assert_eq!(policy.check(new), PolicyVerdict::NotAllowListed(new));
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{TransportError, TransportFut},
+};
+use tower::{Layer, Service};
+
+/// A token-bucket rate limiter that can be shared by several clients.
+///
+/// The bucket holds up to `burst` tokens and is refilled at `requests_per_second`. Each request
+/// takes one token, waiting for the bucket to refill if it is empty. Clones share the same bucket,
+/// so a single limiter can cap the combined request rate of the RPC provider, event queries, and
+/// storage fetches of a service pointed at a rate-limited endpoint.
+///
+/// ```rust
+/// # use boundless_market::rate_limit_layer::RateLimiter;
+/// # async fn example() {
+/// let limiter = RateLimiter::new(10.0, 5);
+/// limiter.acquire().await;
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+    requests_per_second: f64,
+    burst: f64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a new limiter allowing `requests_per_second` on average, with bursts of up to
+    /// `burst` requests. The bucket starts full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is not a positive finite number or `burst` is zero. Use
+    /// [parse_requests_per_second] to validate user input.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second.is_finite() && requests_per_second > 0.0,
+            "requests_per_second must be positive"
+        );
+        assert!(burst > 0, "burst must be at least 1");
+        let burst = burst as f64;
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket { tokens: burst, updated_at: Instant::now() })),
+            requests_per_second,
+            burst,
+        }
+    }
+
+    /// Average number of requests allowed per second.
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    /// Takes a token if one is available, returning how long to wait for one otherwise.
+    fn try_take(&self) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+        bucket.updated_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.requests_per_second))
+        }
+    }
+
+    /// Takes a token if one is available without waiting, returning whether one was taken.
+    pub fn try_acquire(&self) -> bool {
+        self.try_take().is_ok()
+    }
+
+    /// Waits until a token is available and takes it.
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_take() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Parse a rate in requests per second, e.g. from a command line flag, rejecting the values that
+/// [RateLimiter::new] does not accept.
+pub fn parse_requests_per_second(value: &str) -> Result<f64, String> {
+    let requests_per_second: f64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number of requests per second: {value}"))?;
+    if !(requests_per_second.is_finite() && requests_per_second > 0.0) {
+        return Err(format!("requests per second must be a positive number, got {value}"));
+    }
+    Ok(requests_per_second)
+}
+
+/// A [tower] [Layer] that rate limits the requests sent over an RPC transport.
+///
+/// Each request packet takes one token from the [RateLimiter], so a batch of calls counts as a
+/// single request.
+///
+/// # Examples
+/// ```no_run
+/// # use alloy::{providers::ProviderBuilder, rpc::client::ClientBuilder};
+/// # use boundless_market::rate_limit_layer::{RateLimitLayer, RateLimiter};
+/// let client = ClientBuilder::default()
+///     .layer(RateLimitLayer::new(RateLimiter::new(25.0, 10)))
+///     .http("http://localhost:8545".parse()?);
+/// let provider = ProviderBuilder::new().connect_client(client);
+/// # anyhow::Ok(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+}
+
+impl RateLimitLayer {
+    /// Creates a new layer that takes a token from the given limiter for every request.
+    pub fn new(limiter: RateLimiter) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService { inner, limiter: self.limiter.clone() }
+    }
+}
+
+/// A transport service that waits for a [RateLimiter] before sending each request.
+#[derive(Clone, Debug)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: RateLimiter,
+}
+
+impl<S> RateLimitService<S> {
+    /// Creates a new service that takes a token from the given limiter for every request.
+    pub fn new(inner: S, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+impl<S> Service<RequestPacket> for RateLimitService<S>
+where
+    S: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let limiter = self.limiter.clone();
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            limiter.acquire().await;
+            inner.call(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        node_bindings::Anvil,
+        providers::{Provider, ProviderBuilder},
+        rpc::client::ClientBuilder,
+    };
+
+    #[test]
+    fn test_rate_limiter_burst() {
+        let limiter = RateLimiter::new(1.0, 3);
+        let shared = limiter.clone();
+        assert!(limiter.try_acquire());
+        assert!(shared.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!shared.try_acquire(), "the bucket is shared between clones");
+    }
+
+    #[test]
+    fn test_parse_requests_per_second() {
+        assert_eq!(parse_requests_per_second("2.5"), Ok(2.5));
+        for invalid in ["0", "-1", "NaN", "inf", "fast"] {
+            assert!(parse_requests_per_second(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_layer() -> anyhow::Result<()> {
+        let anvil = Anvil::new().spawn();
+        let limiter = RateLimiter::new(20.0, 1);
+        let client = ClientBuilder::default()
+            .layer(RateLimitLayer::new(limiter.clone()))
+            .http(anvil.endpoint_url());
+        let provider = ProviderBuilder::new().disable_recommended_fillers().connect_client(client);
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            provider.get_block_number().await?;
+        }
+        // The first request uses the initial token, and each of the others waits for a refill.
+        assert!(start.elapsed() >= Duration::from_millis(190), "elapsed {:?}", start.elapsed());
+        assert!(!limiter.try_acquire());
+        Ok(())
+    }
+}
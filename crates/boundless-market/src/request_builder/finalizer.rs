@@ -19,10 +19,96 @@ use crate::{
     },
     util::now_timestamp,
 };
+use alloy::primitives::U256;
 use anyhow::{bail, Context};
 use derive_builder::Builder;
 use url::Url;
 
+/// A mistake in the offer of a request, found when finalizing it.
+///
+/// Many requests that silently expire without being locked have one of these. Issues for which
+/// [OfferIssue::is_warning] is true are logged, and the others fail the request build.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OfferIssue {
+    /// The timeout is shorter than the lock timeout.
+    #[error("timeout of {timeout}s is shorter than the lock timeout of {lock_timeout}s")]
+    TimeoutBeforeLockTimeout {
+        /// Timeout of the offer, in seconds.
+        timeout: u32,
+        /// Lock timeout of the offer, in seconds.
+        lock_timeout: u32,
+    },
+    /// The ramp-up period is longer than the lock timeout, so the price never reaches the max
+    /// price while the request can be locked.
+    #[error(
+        "ramp-up period of {ramp_up_period}s is longer than the lock timeout of {lock_timeout}s"
+    )]
+    RampUpLongerThanLockTimeout {
+        /// Ramp-up period of the offer, in seconds.
+        ramp_up_period: u32,
+        /// Lock timeout of the offer, in seconds.
+        lock_timeout: u32,
+    },
+    /// The ramp-up period is as long as the lock timeout, so the price only reaches the max price
+    /// as the lock expires.
+    #[error("ramp-up period of {0}s ends when the lock expires; provers may not lock the request at the max price")]
+    RampUpEndsAtLockExpiry(u32),
+    /// The timeout is equal to the lock timeout, leaving no time for other provers to fulfill the
+    /// request if the locking prover does not.
+    #[error("timeout equals the lock timeout of {0}s; no other prover can fulfill the request if the lock expires")]
+    NoTimeAfterLockExpiry(u32),
+    /// Bidding starts further in the past than the configured tolerance.
+    #[error("bidding starts at {bidding_start}, {age}s in the past, more than the tolerance of {tolerance}s")]
+    BiddingStartInPast {
+        /// Start of bidding, as a UNIX timestamp.
+        bidding_start: u64,
+        /// Number of seconds between the start of bidding and now.
+        age: u64,
+        /// Configured tolerance, in seconds.
+        tolerance: u64,
+    },
+    /// The max price per million cycles is below the configured minimum.
+    #[error("max price of {price_per_mcycle} per mcycle is below the minimum of {min}")]
+    PricePerMCycleTooLow {
+        /// Max price of the offer per million cycles.
+        price_per_mcycle: U256,
+        /// Configured minimum.
+        min: U256,
+    },
+    /// The max price per million cycles is above the configured maximum.
+    #[error("max price of {price_per_mcycle} per mcycle is above the maximum of {max}")]
+    PricePerMCycleTooHigh {
+        /// Max price of the offer per million cycles.
+        price_per_mcycle: U256,
+        /// Configured maximum.
+        max: U256,
+    },
+}
+
+impl OfferIssue {
+    /// Whether the issue is a warning, which does not fail the request build.
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            Self::RampUpEndsAtLockExpiry(_)
+                | Self::NoTimeAfterLockExpiry(_)
+                | Self::BiddingStartInPast { .. }
+        )
+    }
+}
+
+/// Error returned when the offer of a built request has issues that fail the build.
+///
+/// Holds every [OfferIssue] found that is not a warning.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("built request has an invalid offer: {}", display_issues(.0))]
+pub struct InvalidOffer(pub Vec<OfferIssue>);
+
+fn display_issues(issues: &[OfferIssue]) -> String {
+    issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
 #[non_exhaustive]
 #[derive(Debug, Clone, Builder)]
 /// Configuration for the [Finalizer] layer.
@@ -32,6 +118,24 @@ pub struct FinalizerConfig {
     /// If true, the request's expiration time will be checked against the system clock.
     #[builder(default = true)]
     pub check_expiration: bool,
+
+    /// Maximum number of seconds bidding may start in the past.
+    ///
+    /// Only checked along with the expiration. Defaults to 5 minutes.
+    #[builder(setter(into), default = "Some(300)")]
+    pub bidding_start_tolerance: Option<u64>,
+
+    /// Minimum max price per million cycles of the request, in wei.
+    ///
+    /// Only checked if the cycle count of the request is known, e.g. from preflight.
+    #[builder(setter(into, strip_option), default)]
+    pub min_price_per_mcycle: Option<U256>,
+
+    /// Maximum max price per million cycles of the request, in wei.
+    ///
+    /// Only checked if the cycle count of the request is known, e.g. from preflight.
+    #[builder(setter(into, strip_option), default)]
+    pub max_price_per_mcycle: Option<U256>,
 }
 
 /// The final layer in the request building pipeline.
@@ -66,6 +170,55 @@ impl FinalizerConfig {
     }
 }
 
+impl Finalizer {
+    /// Check the offer of a request for common mistakes, at the given time.
+    ///
+    /// The price bounds are only checked if the cycle count of the request is given.
+    pub fn check_offer(&self, offer: &Offer, cycles: Option<u64>, now: u64) -> Vec<OfferIssue> {
+        let mut issues = vec![];
+        if offer.timeout < offer.lockTimeout {
+            issues.push(OfferIssue::TimeoutBeforeLockTimeout {
+                timeout: offer.timeout,
+                lock_timeout: offer.lockTimeout,
+            });
+        } else if offer.timeout == offer.lockTimeout {
+            issues.push(OfferIssue::NoTimeAfterLockExpiry(offer.lockTimeout));
+        }
+        if offer.rampUpPeriod > offer.lockTimeout {
+            issues.push(OfferIssue::RampUpLongerThanLockTimeout {
+                ramp_up_period: offer.rampUpPeriod,
+                lock_timeout: offer.lockTimeout,
+            });
+        } else if offer.rampUpPeriod > 0 && offer.rampUpPeriod == offer.lockTimeout {
+            issues.push(OfferIssue::RampUpEndsAtLockExpiry(offer.rampUpPeriod));
+        }
+        if let Some(tolerance) = self.config.bidding_start_tolerance {
+            let age = now.saturating_sub(offer.rampUpStart);
+            if self.config.check_expiration && age > tolerance {
+                issues.push(OfferIssue::BiddingStartInPast {
+                    bidding_start: offer.rampUpStart,
+                    age,
+                    tolerance,
+                });
+            }
+        }
+        if let Some(cycles) = cycles.filter(|cycles| *cycles > 0) {
+            let price_per_mcycle = offer.maxPrice * U256::from(1_000_000) / U256::from(cycles);
+            if let Some(min) = self.config.min_price_per_mcycle {
+                if price_per_mcycle < min {
+                    issues.push(OfferIssue::PricePerMCycleTooLow { price_per_mcycle, min });
+                }
+            }
+            if let Some(max) = self.config.max_price_per_mcycle {
+                if price_per_mcycle > max {
+                    issues.push(OfferIssue::PricePerMCycleTooHigh { price_per_mcycle, max });
+                }
+            }
+        }
+        issues
+    }
+}
+
 impl Layer<(Url, RequestInput, Requirements, Offer, RequestId)> for Finalizer {
     type Output = ProofRequest;
     type Error = anyhow::Error;
@@ -129,6 +282,17 @@ impl Adapt<Finalizer> for RequestParams {
             .context("failed to build request: offer is incomplete")?;
         let request_id = self.require_request_id().context("failed to build request")?.clone();
 
+        let (warnings, errors): (Vec<_>, Vec<_>) = layer
+            .check_offer(&offer, self.cycles, now_timestamp())
+            .into_iter()
+            .partition(OfferIssue::is_warning);
+        for issue in warnings {
+            tracing::warn!("Request offer: {issue}");
+        }
+        if !errors.is_empty() {
+            return Err(InvalidOffer(errors).into());
+        }
+
         // If enough data is provided, check that the known journal and image match the predicate.
         let predicate = Predicate::try_from(requirements.predicate.clone())?;
         let eval = match (&self.journal, self.image_id) {
@@ -37,7 +37,9 @@ use crate::{
 mod preflight_layer;
 mod storage_layer;
 
-pub use preflight_layer::PreflightLayer;
+pub use preflight_layer::{
+    execute_with_cycle_limit, ExecutorConfig, PreflightError, PreflightLayer,
+};
 pub use storage_layer::{StorageLayer, StorageLayerConfig, StorageLayerConfigBuilder};
 mod requirements_layer;
 pub use requirements_layer::{RequirementParams, RequirementsLayer};
@@ -47,10 +49,11 @@ pub use request_id_layer::{
 };
 mod offer_layer;
 pub use offer_layer::{
-    OfferLayer, OfferLayerConfig, OfferLayerConfigBuilder, OfferParams, OfferParamsBuilder,
+    MarketStats, MarketStatsBuilder, OfferLayer, OfferLayerConfig, OfferLayerConfigBuilder,
+    OfferParams, OfferParamsBuilder,
 };
 mod finalizer;
-pub use finalizer::{Finalizer, FinalizerConfig, FinalizerConfigBuilder};
+pub use finalizer::{Finalizer, FinalizerConfig, FinalizerConfigBuilder, InvalidOffer, OfferIssue};
 
 /// A trait for building proof requests, used by the [Client][crate::Client].
 ///
@@ -637,19 +640,22 @@ mod tests {
     use url::Url;
 
     use super::{
-        Layer, OfferLayer, OfferLayerConfig, OfferParams, PreflightLayer, RequestBuilder,
+        ExecutorConfig, Finalizer, FinalizerConfig, InvalidOffer, Layer, MarketStats, OfferIssue,
+        OfferLayer, OfferLayerConfig, OfferParams, PreflightError, PreflightLayer, RequestBuilder,
         RequestId, RequestIdLayer, RequestIdLayerConfig, RequestIdLayerMode, RequestParams,
         RequirementsLayer, StandardRequestBuilder, StorageLayer, StorageLayerConfig,
     };
 
     use crate::{
         contracts::{
-            boundless_market::BoundlessMarketService, FulfillmentData, Predicate, RequestInput,
-            RequestInputType, Requirements,
+            boundless_market::BoundlessMarketService, FulfillmentData, Offer, Predicate,
+            RequestInput, RequestInputType, Requirements,
         },
+        deployments::{ChainTiming, NamedChain},
         input::GuestEnv,
+        rate_limit_layer::RateLimiter,
         storage::{fetch_url, MockStorageProvider, StorageProvider},
-        util::NotProvided,
+        util::{now_timestamp, NotProvided},
         StandardStorageProvider,
     };
     use alloy_primitives::U256;
@@ -826,6 +832,63 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[traced_test]
+    async fn test_preflight_layer_cycle_limit() -> anyhow::Result<()> {
+        let storage = MockStorageProvider::start();
+        let program_url = storage.upload_program(ECHO_ELF).await?;
+        let env = GuestEnv::from_stdin(b"hello_zkvm".to_vec());
+        let input = RequestInput::inline(env.encode()?);
+
+        let layer = PreflightLayer::default().with_cycle_limit(Some(100));
+        let err = layer.process((&program_url, &input)).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PreflightError>(),
+            Some(PreflightError::CycleLimitExceeded(100))
+        ));
+
+        let layer = PreflightLayer::default().with_cycle_limit(Some(1 << 30));
+        let session = layer.process((&program_url, &input)).await?;
+        assert!(session.exit_code.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_preflight_layer_segment_limit() -> anyhow::Result<()> {
+        let storage = MockStorageProvider::start();
+        let program_url = storage.upload_program(ECHO_ELF).await?;
+        let env = GuestEnv::from_stdin(b"hello_zkvm".to_vec());
+        let input = RequestInput::inline(env.encode()?);
+
+        let config = ExecutorConfig::default().with_segment_limit_po2(14);
+        let layer = PreflightLayer::default().with_executor_config(config);
+        let session = layer.process((&program_url, &input)).await?;
+        assert!(session.exit_code.is_ok());
+        assert!(session.segments.iter().all(|segment| segment.po2 <= 14));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_preflight_layer_rate_limit() -> anyhow::Result<()> {
+        let storage = MockStorageProvider::start();
+        let program_url = storage.upload_program(ECHO_ELF).await?;
+        let input_url =
+            storage.upload_input(&GuestEnv::from_stdin(b"hello".to_vec()).encode()?).await?;
+        let input = RequestInput::url(input_url.to_string());
+
+        // Fetching the program and the input takes a token each.
+        let limiter = RateLimiter::new(1.0, 3);
+        let layer = PreflightLayer::default()
+            .with_http_client(reqwest::Client::new())
+            .with_rate_limiter(limiter.clone());
+        layer.process((&program_url, &input)).await?;
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+        Ok(())
+    }
+
     #[tokio::test]
     #[traced_test]
     async fn test_requirements_layer() -> anyhow::Result<()> {
@@ -951,6 +1014,89 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[traced_test]
+    async fn test_offer_layer_gas_inclusive_pricing() -> anyhow::Result<()> {
+        let anvil = Anvil::new().spawn();
+        let test_ctx = create_test_ctx(&anvil).await?;
+        let provider = test_ctx.customer_provider.clone();
+        let image_id = compute_image_id(ECHO_ELF).unwrap();
+        let predicate = Predicate::digest_match(image_id, Journal::new(b"hello".to_vec()).digest());
+        let requirements = Requirements::new(predicate);
+        let request_id = RequestId::new(test_ctx.customer_signer.address(), 0);
+        let offer_params: OfferParams =
+            OfferParams::builder().min_price(U256::from(1u64)).max_price(U256::from(5u64)).into();
+
+        // The gas cost is added to the prices set in the params.
+        let layer = OfferLayer::new(
+            provider.clone(),
+            OfferLayerConfig::builder().gas_inclusive_pricing(true).build()?,
+        );
+        let adjustment = layer.gas_adjustment(&requirements, &request_id).await?;
+        assert!(adjustment > U256::ZERO);
+        let offer = layer.process((&requirements, &request_id, Some(0u64), &offer_params)).await?;
+        assert_eq!(offer.minPrice, U256::from(1u64) + adjustment);
+        assert_eq!(offer.maxPrice, U256::from(5u64) + adjustment);
+
+        // The added gas cost is capped.
+        let layer = OfferLayer::new(
+            provider,
+            OfferLayerConfig::builder()
+                .gas_inclusive_pricing(true)
+                .max_gas_adjustment(U256::from(100u64))
+                .build()?,
+        );
+        let offer = layer.process((&requirements, &request_id, Some(0u64), &offer_params)).await?;
+        assert_eq!(offer.minPrice, U256::from(101u64));
+        assert_eq!(offer.maxPrice, U256::from(105u64));
+        Ok(())
+    }
+
+    #[test]
+    fn offer_params_from_deadline() {
+        let stats = MarketStats::builder()
+            .proving_rate(1 << 20)
+            .execution_rate(1 << 20)
+            .lock_latency(60)
+            .min_bidding_start_delay(30)
+            .build()
+            .unwrap();
+        let now = now_timestamp();
+        let cycles = 100 << 20;
+
+        let params = OfferParams::from_deadline(cycles, now + 1000, &stats).unwrap();
+        let bidding_start = params.bidding_start.unwrap();
+        // Execution takes 100s, longer than the minimum bidding start delay.
+        assert!(bidding_start >= now + 100 && bidding_start <= now + 101);
+        assert_eq!(bidding_start + params.timeout.unwrap() as u64, now + 1000);
+        // Other provers have the proving time left after the lock expires.
+        assert_eq!(params.timeout.unwrap() - params.lock_timeout.unwrap(), 100);
+        assert_eq!(params.ramp_up_period, Some(60));
+        assert!(params.min_price.is_none() && params.max_price.is_none());
+
+        // Not enough time for two proving attempts.
+        OfferParams::from_deadline(cycles, now + 250, &stats).unwrap_err();
+        OfferParams::from_deadline(cycles, now, &stats).unwrap_err();
+
+        // The bidding start is exact given the current time.
+        let params = OfferParams::from_deadline_at(cycles, 2000, &stats, 900).unwrap();
+        assert_eq!(params.bidding_start, Some(1000));
+        assert_eq!(params.timeout, Some(1000));
+    }
+
+    #[test]
+    fn offer_layer_config_chain_timing() {
+        let timing = ChainTiming::for_chain(NamedChain::Sepolia);
+        let config = OfferLayerConfig::builder().chain_timing(&timing).build().unwrap();
+        assert_eq!(config.ramp_up_period, timing.ramp_up_period);
+
+        // Values set explicitly are kept.
+        let config =
+            OfferLayerConfig::builder().ramp_up_period(30).chain_timing(&timing).build().unwrap();
+        assert_eq!(config.ramp_up_period, 30);
+        assert_eq!(ChainTiming::for_chain_id(31337), ChainTiming::DEFAULT);
+    }
+
     #[test]
     fn request_params_with_program_url_infallible() {
         // When passing a parsed URL, with_program_url should be infallible.
@@ -988,6 +1134,70 @@ mod tests {
         assert_eq!(input.data.as_ref(), "https://fileserver.example/input2.bin".as_bytes());
     }
 
+    #[test]
+    fn test_finalizer_check_offer() {
+        let finalizer = Finalizer::from(
+            FinalizerConfig::builder()
+                .min_price_per_mcycle(U256::from(10))
+                .max_price_per_mcycle(U256::from(1000))
+                .build()
+                .unwrap(),
+        );
+        let now = 10_000;
+        let offer = Offer {
+            minPrice: U256::ZERO,
+            maxPrice: U256::from(100),
+            rampUpStart: now - 10,
+            rampUpPeriod: 60,
+            lockTimeout: 600,
+            timeout: 1200,
+            lockCollateral: U256::ZERO,
+        };
+        assert!(finalizer.check_offer(&offer, Some(1_000_000), now).is_empty());
+
+        let issues = finalizer.check_offer(
+            &Offer { rampUpPeriod: 600, timeout: 600, ..offer.clone() },
+            None,
+            now,
+        );
+        assert!(issues.iter().all(OfferIssue::is_warning));
+        assert_eq!(issues.len(), 2);
+
+        // Bidding starting in the past is only a warning, as the price is still ramping up.
+        let issues =
+            finalizer.check_offer(&Offer { rampUpStart: now - 1000, ..offer.clone() }, None, now);
+        assert!(issues.iter().all(OfferIssue::is_warning));
+        assert_eq!(issues.len(), 1);
+
+        let issues = finalizer.check_offer(
+            &Offer { rampUpStart: now - 1000, timeout: 300, ..offer.clone() },
+            Some(20_000_000),
+            now,
+        );
+        assert_eq!(
+            issues,
+            vec![
+                OfferIssue::TimeoutBeforeLockTimeout { timeout: 300, lock_timeout: 600 },
+                OfferIssue::BiddingStartInPast {
+                    bidding_start: now - 1000,
+                    age: 1000,
+                    tolerance: 300
+                },
+                OfferIssue::PricePerMCycleTooLow {
+                    price_per_mcycle: U256::from(5),
+                    min: U256::from(10)
+                },
+            ]
+        );
+        let errors: Vec<_> = issues.into_iter().filter(|issue| !issue.is_warning()).collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            InvalidOffer(errors).to_string(),
+            "built request has an invalid offer: timeout of 300s is shorter than the lock timeout \
+             of 600s; max price of 5 per mcycle is below the minimum of 10"
+        );
+    }
+
     #[allow(dead_code)]
     trait AssertSend: Send {}
 
@@ -15,18 +15,19 @@
 use super::{Adapt, Layer, MissingFieldError, RequestParams};
 use crate::{
     contracts::{Offer, RequestId, Requirements},
-    selector::{ProofType, SupportedSelectors},
+    deployments::ChainTiming,
+    selector::{ProofType, SelectorRegistry, SupportedSelectors},
     util::now_timestamp,
 };
 use alloy::{
     network::Ethereum,
     primitives::{
         utils::{format_units, Unit},
-        U256,
+        FixedBytes, U256,
     },
     providers::Provider,
 };
-use anyhow::{ensure, Context};
+use anyhow::{bail, ensure, Context};
 use clap::Args;
 use derive_builder::Builder;
 
@@ -50,6 +51,9 @@ pub struct OfferLayerConfig {
     pub bidding_start_delay: u64,
 
     /// Duration in seconds for the price to ramp up from min to max.
+    ///
+    /// When built by the [Client][crate::Client], defaults to the
+    /// [recommendation for the chain][crate::deployments::ChainTiming::ramp_up_period].
     #[builder(default = "60")]
     pub ramp_up_period: u32,
 
@@ -85,6 +89,18 @@ pub struct OfferLayerConfig {
     /// Supported proof types and their corresponding selectors.
     #[builder(setter(into), default)]
     pub supported_selectors: SupportedSelectors,
+
+    /// Whether to add the estimated gas cost of the request to both its min and max price.
+    ///
+    /// The gas cost is estimated with [OfferLayer::estimate_gas_cost_upper_bound] at the gas
+    /// price when the request is built, plus 10%. This applies to prices set in the
+    /// [OfferParams] as well, so that provers are not underpaid on chains where gas is expensive.
+    #[builder(default = "false")]
+    pub gas_inclusive_pricing: bool,
+
+    /// Maximum amount, in wei, added to the prices with [Self::gas_inclusive_pricing].
+    #[builder(setter(strip_option, into), default)]
+    pub max_gas_adjustment: Option<U256>,
 }
 
 #[non_exhaustive]
@@ -100,6 +116,11 @@ pub struct OfferLayer<P> {
 
     /// Configuration for offer generation.
     pub config: OfferLayerConfig,
+
+    /// Registry used to classify selectors missing from the
+    /// [supported selectors][OfferLayerConfig::supported_selectors], such as those of verifiers
+    /// newer than this crate.
+    pub selector_registry: Option<SelectorRegistry<P>>,
 }
 
 impl OfferLayerConfig {
@@ -112,6 +133,14 @@ impl OfferLayerConfig {
     }
 }
 
+impl OfferLayerConfigBuilder {
+    /// Use the defaults of the given chain timing for the fields that are not set.
+    pub fn chain_timing(&mut self, timing: &ChainTiming) -> &mut Self {
+        self.ramp_up_period.get_or_insert(timing.ramp_up_period);
+        self
+    }
+}
+
 impl Default for OfferLayerConfig {
     fn default() -> Self {
         Self::builder().build().expect("implementation error in Default for OfferLayerConfig")
@@ -120,7 +149,7 @@ impl Default for OfferLayerConfig {
 
 impl<P: Clone> From<P> for OfferLayer<P> {
     fn from(provider: P) -> Self {
-        OfferLayer { provider, config: Default::default() }
+        OfferLayer { provider, config: Default::default(), selector_registry: None }
     }
 }
 
@@ -219,6 +248,109 @@ impl OfferParams {
     pub fn builder() -> OfferParamsBuilder {
         Default::default()
     }
+
+    /// Derive the timing of an offer from a target completion deadline.
+    ///
+    /// Given the cycle count of the request, a UNIX timestamp by which the proof should be
+    /// delivered, and [MarketStats] describing observed prover behavior, this sets:
+    ///
+    /// * `bidding_start` after the time provers need to execute the request.
+    /// * `timeout` such that the request expires at the deadline.
+    /// * `lock_timeout` such that, if the locking prover fails to deliver, other provers still
+    ///   have the time needed to prove the request before the deadline.
+    /// * `ramp_up_period` to the observed lock latency, so that the price reaches its maximum
+    ///   around the time requests are typically locked.
+    ///
+    /// Pricing and collateral are left unset. Returns an error if the deadline is too soon to
+    /// leave time for two proving attempts.
+    ///
+    /// ```rust
+    /// # use boundless_market::request_builder::{MarketStats, OfferParams};
+    /// # use std::time::{SystemTime, UNIX_EPOCH};
+    /// let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    /// let stats = MarketStats::default();
+    /// let params = OfferParams::from_deadline(1 << 20, now + 3600, &stats).unwrap();
+    /// assert_eq!(params.ramp_up_period, Some(stats.lock_latency));
+    /// ```
+    pub fn from_deadline(cycles: u64, deadline: u64, stats: &MarketStats) -> anyhow::Result<Self> {
+        Self::from_deadline_at(cycles, deadline, stats, now_timestamp())
+    }
+
+    /// Derive the timing of an offer from a target completion deadline, as of the UNIX timestamp
+    /// `now`.
+    ///
+    /// See [Self::from_deadline].
+    pub fn from_deadline_at(
+        cycles: u64,
+        deadline: u64,
+        stats: &MarketStats,
+        now: u64,
+    ) -> anyhow::Result<Self> {
+        let bidding_start = now + stats.bidding_start_delay(cycles);
+        let proving_time = stats.proving_time(cycles);
+        let timeout = deadline.checked_sub(bidding_start).with_context(|| {
+            format!("deadline {deadline} is before the start of bidding at {bidding_start}")
+        })?;
+        ensure!(
+            timeout >= 2 * proving_time,
+            "deadline {deadline} is too soon: {timeout}s from the start of bidding is less than \
+            twice the {proving_time}s proving time"
+        );
+        let lock_timeout: u32 =
+            (timeout - proving_time).try_into().context("lock timeout too large")?;
+        Ok(Self {
+            bidding_start: Some(bidding_start),
+            ramp_up_period: Some(stats.lock_latency.min(lock_timeout)),
+            lock_timeout: Some(lock_timeout),
+            timeout: Some(timeout.try_into().context("timeout too large")?),
+            ..Default::default()
+        })
+    }
+}
+
+/// Observed prover behavior on the market, used by [OfferParams::from_deadline].
+#[non_exhaustive]
+#[derive(Clone, Debug, Builder)]
+pub struct MarketStats {
+    /// Rate at which provers prove requests, in cycles per second.
+    #[builder(default = "52_429")] // ~20 seconds per million cycles
+    pub proving_rate: u64,
+
+    /// Rate at which provers execute requests before bidding, in cycles per second.
+    #[builder(default = "2_000_000")]
+    pub execution_rate: u64,
+
+    /// Time in seconds from the start of bidding until requests are typically locked.
+    #[builder(default = "240")]
+    pub lock_latency: u32,
+
+    /// Minimum time in seconds between creating a request and the start of bidding.
+    #[builder(default = "30")]
+    pub min_bidding_start_delay: u64,
+}
+
+impl MarketStats {
+    /// Creates a new builder for constructing [MarketStats].
+    pub fn builder() -> MarketStatsBuilder {
+        Default::default()
+    }
+
+    /// Time in seconds for a prover to prove a request with the given cycle count.
+    pub fn proving_time(&self, cycles: u64) -> u64 {
+        cycles.div_ceil(self.proving_rate.max(1))
+    }
+
+    /// Delay in seconds from now until bidding should start on a request with the given cycle
+    /// count, leaving provers time to execute it.
+    pub fn bidding_start_delay(&self, cycles: u64) -> u64 {
+        cycles.div_ceil(self.execution_rate.max(1)).max(self.min_bidding_start_delay)
+    }
+}
+
+impl Default for MarketStats {
+    fn default() -> Self {
+        Self::builder().build().expect("implementation error in Default for MarketStats")
+    }
 }
 
 impl<P> OfferLayer<P>
@@ -230,7 +362,15 @@ where
     /// The provider is used to fetch current gas prices for estimating transaction costs,
     /// which are factored into the offer pricing.
     pub fn new(provider: P, config: OfferLayerConfig) -> Self {
-        Self { provider, config }
+        Self { provider, config, selector_registry: None }
+    }
+
+    /// Classify selectors missing from the supported selectors with the given [SelectorRegistry].
+    pub fn with_selector_registry(
+        self,
+        selector_registry: impl Into<Option<SelectorRegistry<P>>>,
+    ) -> Self {
+        Self { selector_registry: selector_registry.into(), ..self }
     }
 
     /// Estimates the maximum gas usage for a proof request.
@@ -244,6 +384,20 @@ where
         &self,
         requirements: &Requirements,
         request_id: &RequestId,
+    ) -> anyhow::Result<u64> {
+        let proof_type = self
+            .config
+            .supported_selectors
+            .proof_type(requirements.selector)
+            .context("cannot estimate gas usage for request with unsupported selector")?;
+        self.gas_usage(requirements, request_id, proof_type)
+    }
+
+    fn gas_usage(
+        &self,
+        requirements: &Requirements,
+        request_id: &RequestId,
+        proof_type: ProofType,
     ) -> anyhow::Result<u64> {
         let mut gas_usage_estimate =
             self.config.lock_gas_estimate + self.config.fulfill_gas_estimate;
@@ -254,18 +408,37 @@ where
             gas_usage_estimate +=
                 u64::try_from(callback.gasLimit).context("callback gas limit too large for u64")?;
         }
-
-        let proof_type = self
-            .config
-            .supported_selectors
-            .proof_type(requirements.selector)
-            .context("cannot estimate gas usage for request with unsupported selector")?;
         if let ProofType::Groth16 = proof_type {
             gas_usage_estimate += self.config.groth16_verify_gas_estimate;
         };
         Ok(gas_usage_estimate)
     }
 
+    // Proof type of the given selector, from the supported selectors or the selector registry.
+    async fn proof_type(&self, selector: FixedBytes<4>) -> anyhow::Result<ProofType> {
+        if let Some(proof_type) = self.config.supported_selectors.proof_type(selector) {
+            return Ok(proof_type);
+        }
+        if let Some(registry) = &self.selector_registry {
+            if let Some(info) = registry.lookup(selector).await? {
+                return Ok(info.proof_type);
+            }
+        }
+        bail!("cannot estimate gas usage for request with unsupported selector {selector}")
+    }
+
+    // Estimated gas cost of the request, classifying its selector with the registry if needed.
+    async fn gas_cost(
+        &self,
+        requirements: &Requirements,
+        request_id: &RequestId,
+        gas_price: u128,
+    ) -> anyhow::Result<U256> {
+        let proof_type = self.proof_type(requirements.selector).await?;
+        let gas_usage_estimate = self.gas_usage(requirements, request_id, proof_type)?;
+        Ok(U256::from(gas_price * (gas_usage_estimate as u128)))
+    }
+
     /// Estimates the maximum gas cost for a proof request.
     ///
     /// This calculates the cost in wei based on the estimated gas usage and
@@ -284,6 +457,31 @@ where
         let gas_cost_estimate = gas_price * (gas_usage_estimate as u128);
         Ok(U256::from(gas_cost_estimate))
     }
+
+    /// Amount added to the prices of a request with [OfferLayerConfig::gas_inclusive_pricing].
+    ///
+    /// This is the estimated gas cost of the request at the current gas price plus 10%, capped
+    /// at [OfferLayerConfig::max_gas_adjustment].
+    pub async fn gas_adjustment(
+        &self,
+        requirements: &Requirements,
+        request_id: &RequestId,
+    ) -> anyhow::Result<U256> {
+        let gas_price: u128 = self.provider.get_gas_price().await?;
+        let gas_cost_estimate = self.gas_cost(requirements, request_id, gas_price).await?;
+        let adjustment = gas_cost_estimate + (gas_cost_estimate / U256::from(10));
+        Ok(match self.config.max_gas_adjustment {
+            Some(cap) if adjustment > cap => {
+                tracing::warn!(
+                    "Gas cost estimate of {} ether exceeds the cap of {} ether on price adjustments",
+                    format_units(adjustment, "ether")?,
+                    format_units(cap, "ether")?,
+                );
+                cap
+            }
+            _ => adjustment,
+        })
+    }
 }
 
 impl<P> Layer<(&Requirements, &RequestId, Option<u64>, &OfferParams)> for OfferLayer<P>
@@ -302,6 +500,17 @@ where
             &OfferParams,
         ),
     ) -> Result<Self::Output, Self::Error> {
+        let gas_adjustment = if self.config.gas_inclusive_pricing {
+            let adjustment = self.gas_adjustment(requirements, request_id).await?;
+            tracing::debug!(
+                "Adding {} ether of gas costs to the min and max price",
+                format_units(adjustment, "ether")?
+            );
+            Some(adjustment)
+        } else {
+            None
+        };
+
         let min_price = if params.min_price.is_none() {
             match cycle_count {
                 Some(cycle_count) => self.config.min_price_per_cycle * U256::from(cycle_count),
@@ -316,15 +525,21 @@ where
         } else {
             params.min_price.unwrap()
         };
+        let min_price = min_price + gas_adjustment.unwrap_or_default();
 
-        let max_price = if params.max_price.is_none() {
+        let max_price = if let Some(max_price) = params.max_price {
+            max_price + gas_adjustment.unwrap_or_default()
+        } else if let Some(gas_adjustment) = gas_adjustment {
+            let cycle_count =
+                cycle_count.context("cycle count required to set max price in OfferLayer")?;
+            self.config.max_price_per_cycle * U256::from(cycle_count) + gas_adjustment
+        } else {
             let cycle_count =
                 cycle_count.context("cycle count required to set max price in OfferLayer")?;
             let max_price_cycle = self.config.max_price_per_cycle * U256::from(cycle_count);
 
             let gas_price: u128 = self.provider.get_gas_price().await?;
-            let gas_cost_estimate =
-                self.estimate_gas_cost_upper_bound(requirements, request_id, gas_price)?;
+            let gas_cost_estimate = self.gas_cost(requirements, request_id, gas_price).await?;
 
             // Add the gas price plus 10% to the max_price.
             let max_price =
@@ -336,8 +551,6 @@ where
                 format_units(gas_cost_estimate, "ether")?,
             );
             max_price
-        } else {
-            params.max_price.unwrap()
         };
 
         let bidding_start = params
@@ -15,11 +15,84 @@
 use super::{Adapt, Layer, RequestParams};
 use crate::contracts::{RequestInput, RequestInputType};
 use crate::input::GuestEnv;
-use crate::storage::fetch_url;
+use crate::rate_limit_layer::RateLimiter;
+use crate::storage::{fetch_url, fetch_url_with_client, fetch_url_with_rate_limit};
 use anyhow::{bail, ensure, Context};
-use risc0_zkvm::{default_executor, sha::Digestible, SessionInfo};
+use risc0_zkvm::{default_executor, sha::Digestible, ExecutorEnv, ExecutorEnvBuilder, SessionInfo};
 use url::Url;
 
+/// Error returned by preflight execution.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum PreflightError {
+    /// Execution was aborted because the guest ran past the cycle limit.
+    #[error("guest requires at least {0} cycles, exceeding the preflight cycle limit")]
+    CycleLimitExceeded(u64),
+}
+
+/// Configuration of the executor used to run guests locally, e.g. in preflight.
+///
+/// The defaults match [default_executor] with a default [ExecutorEnv]. Large guests can be run on
+/// constrained machines by lowering the segment size, which bounds the memory used per segment.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExecutorConfig {
+    /// Maximum number of cycles to execute the guest for, or `None` for no limit.
+    pub cycle_limit: Option<u64>,
+    /// Maximum size of a segment, as a power of two of cycles, or `None` for the zkVM default.
+    pub segment_limit_po2: Option<u32>,
+}
+
+impl ExecutorConfig {
+    /// Set the maximum number of cycles to execute the guest for.
+    pub fn with_cycle_limit(self, cycle_limit: impl Into<Option<u64>>) -> Self {
+        Self { cycle_limit: cycle_limit.into(), ..self }
+    }
+
+    /// Set the maximum size of a segment, as a power of two of cycles.
+    pub fn with_segment_limit_po2(self, segment_limit_po2: impl Into<Option<u32>>) -> Self {
+        Self { segment_limit_po2: segment_limit_po2.into(), ..self }
+    }
+
+    /// Execute the program with the environment from the given builder, configured with these
+    /// settings.
+    ///
+    /// When the cycle limit is exceeded, the returned error is a
+    /// [PreflightError::CycleLimitExceeded]. This protects callers from guests that would
+    /// otherwise execute for hours.
+    pub fn execute(
+        &self,
+        env: &mut ExecutorEnvBuilder<'_>,
+        program: &[u8],
+    ) -> anyhow::Result<SessionInfo> {
+        env.session_limit(self.cycle_limit);
+        if let Some(po2) = self.segment_limit_po2 {
+            env.segment_limit_po2(po2);
+        }
+        let env = env.build()?;
+        default_executor().execute(env, program).map_err(|err| match self.cycle_limit {
+            // The executor does not expose a typed error, and may be running in r0vm, so the
+            // session limit error is only recognizable by its message.
+            Some(limit) if format!("{err:#}").to_lowercase().contains("session limit exceeded") => {
+                PreflightError::CycleLimitExceeded(limit).into()
+            }
+            _ => err,
+        })
+    }
+}
+
+/// Execute the program with the environment from the given builder, aborting once the guest runs
+/// more than `cycle_limit` cycles, if set.
+///
+/// This is [ExecutorConfig::execute] with only a cycle limit set.
+pub fn execute_with_cycle_limit(
+    env: &mut ExecutorEnvBuilder<'_>,
+    program: &[u8],
+    cycle_limit: Option<u64>,
+) -> anyhow::Result<SessionInfo> {
+    ExecutorConfig::default().with_cycle_limit(cycle_limit).execute(env, program)
+}
+
 /// A layer that performs preflight execution of the guest program.
 ///
 /// This layer runs the program with the provided input to compute:
@@ -31,12 +104,53 @@ use url::Url;
 /// verification configuration based on actual execution results.
 ///
 /// Each time this layer is invoked, it created a new [Executor][risc0_zkvm::Executor] with
-/// [default_executor].
+/// [default_executor], configured with its [ExecutorConfig]. If a cycle limit is set, execution is
+/// aborted once the guest exceeds it, failing with [PreflightError::CycleLimitExceeded].
+///
+/// The program and input are fetched with the HTTP client and rate limiter of the layer, if set.
 #[non_exhaustive]
 #[derive(Clone, Default)]
-pub struct PreflightLayer {}
+pub struct PreflightLayer {
+    /// Configuration of the executor running the guest.
+    pub executor_config: ExecutorConfig,
+    http_client: Option<reqwest::Client>,
+    rate_limiter: Option<RateLimiter>,
+}
 
 impl PreflightLayer {
+    /// Set the maximum number of cycles to execute the guest for.
+    ///
+    /// Requests whose guest runs past the limit fail preflight instead of executing to completion.
+    pub fn with_cycle_limit(self, cycle_limit: Option<u64>) -> Self {
+        Self { executor_config: self.executor_config.with_cycle_limit(cycle_limit), ..self }
+    }
+
+    /// Set the configuration of the executor running the guest.
+    pub fn with_executor_config(self, executor_config: ExecutorConfig) -> Self {
+        Self { executor_config, ..self }
+    }
+
+    /// Set the HTTP client used to fetch the program and input.
+    pub fn with_http_client(self, http_client: impl Into<Option<reqwest::Client>>) -> Self {
+        Self { http_client: http_client.into(), ..self }
+    }
+
+    /// Set the [RateLimiter] applied to the fetches of the program and input.
+    pub fn with_rate_limiter(self, rate_limiter: impl Into<Option<RateLimiter>>) -> Self {
+        Self { rate_limiter: rate_limiter.into(), ..self }
+    }
+
+    async fn fetch(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        match (&self.http_client, &self.rate_limiter) {
+            (client, Some(limiter)) => {
+                let client = client.clone().unwrap_or_default();
+                fetch_url_with_rate_limit(&client, limiter, url).await
+            }
+            (Some(client), None) => fetch_url_with_client(client, url).await,
+            (None, None) => fetch_url(url).await,
+        }
+    }
+
     async fn fetch_env(&self, input: &RequestInput) -> anyhow::Result<GuestEnv> {
         let env = match input.inputType {
             RequestInputType::Inline => GuestEnv::decode(&input.data)?,
@@ -44,7 +158,7 @@ impl PreflightLayer {
                 let input_url =
                     std::str::from_utf8(&input.data).context("Input URL is not valid UTF-8")?;
                 tracing::info!("Fetching input from {}", input_url);
-                GuestEnv::decode(&fetch_url(&input_url).await?)?
+                GuestEnv::decode(&self.fetch(input_url).await?)?
             }
             _ => bail!("Unsupported input type"),
         };
@@ -60,10 +174,9 @@ impl Layer<(&Url, &RequestInput)> for PreflightLayer {
         &self,
         (program_url, input): (&Url, &RequestInput),
     ) -> anyhow::Result<Self::Output> {
-        let program = fetch_url(program_url).await?;
+        let program = self.fetch(program_url.as_str()).await?;
         let env = self.fetch_env(input).await?;
-        let session_info = default_executor().execute(env.try_into()?, &program)?;
-        Ok(session_info)
+        self.executor_config.execute(ExecutorEnv::builder().write_slice(&env.stdin), &program)
     }
 }
 
@@ -124,6 +124,17 @@ impl RequirementParams {
     }
 }
 
+impl RequirementParamsBuilder {
+    /// Require the receipt claim with the given digest, rather than a journal from the program.
+    ///
+    /// This is a convenience method to set a [Predicate::ClaimDigestMatch] as the predicate. It
+    /// suits composition-style workflows, where the requestor needs a receipt for a specific
+    /// claim, and the journal does not need to be delivered.
+    pub fn claim_digest(&mut self, claim_digest: impl Into<Digest>) -> &mut Self {
+        self.predicate(Predicate::claim_digest_match(claim_digest))
+    }
+}
+
 impl RequirementsLayer {
     /// Creates a new builder for constructing a [RequirementsLayer].
     ///
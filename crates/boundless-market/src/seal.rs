@@ -0,0 +1,253 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Introspection of fulfillment seals.
+//!
+//! Classifies a seal by its selector and contents, and estimates the gas it costs to verify
+//! onchain. Requestors whose callbacks verify the seal can use the estimates to choose a proof
+//! type, e.g. requiring a Groth16 proof only when set inclusion would cost more.
+//!
+//! Estimates are approximate: they cover the calldata of the seal and the work of the verifier,
+//! but not the rest of the transaction, and the gas of a verifier varies with its version.
+
+use std::fmt;
+
+use alloy::sol_types::SolValue;
+use alloy_primitives::FixedBytes;
+use risc0_ethereum_contracts::selector::{Selector, SelectorType};
+
+use crate::selector::ProofType;
+
+/// Estimated gas to verify a Groth16 proof, excluding calldata.
+pub const GROTH16_VERIFY_GAS: u64 = 250_000;
+
+/// Estimated gas to verify the inclusion of a claim in a set with a cached root, excluding the
+/// Merkle path and calldata.
+pub const SET_INCLUSION_VERIFY_GAS: u64 = 15_000;
+
+/// Estimated gas to hash each node of the Merkle path of a set inclusion seal.
+pub const MERKLE_NODE_GAS: u64 = 500;
+
+/// Estimated gas to verify a fake receipt with a mock verifier, excluding calldata.
+pub const FAKE_VERIFY_GAS: u64 = 5_000;
+
+/// Size, in bytes, of the ABI-encoded Groth16 proof that follows the selector of a Groth16 seal.
+const GROTH16_PROOF_SIZE: usize = 256;
+
+alloy::sol! {
+    /// Seal of the set verifier, following its selector.
+    struct SetInclusionSeal {
+        bytes32[] path;
+        bytes rootSeal;
+    }
+}
+
+/// Error type for seal introspection.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum SealError {
+    /// The seal is too short to hold a selector.
+    #[error("seal of {0} bytes is too short to hold a selector")]
+    MissingSelector(usize),
+    /// The seal does not match the format of its selector.
+    #[error("malformed {kind} seal: {reason}")]
+    Malformed {
+        /// Kind of seal indicated by the selector.
+        kind: &'static str,
+        /// Why the seal does not match it.
+        reason: String,
+    },
+}
+
+/// Kind of a seal, as indicated by its selector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SealKind {
+    /// A Groth16 proof.
+    Groth16,
+    /// Proof of inclusion in a set of claims.
+    SetInclusion {
+        /// Number of nodes in the Merkle path from the claim to the root of the set.
+        path_length: usize,
+        /// Whether the seal carries a proof of the root, verified unless the root is already
+        /// cached by the set verifier.
+        has_root_seal: bool,
+    },
+    /// A fake receipt, only accepted by mock verifiers in dev mode.
+    Fake,
+    /// A selector unknown to this crate, e.g. of a verifier released after it.
+    Unknown,
+}
+
+impl SealKind {
+    /// Type of proof the seal satisfies.
+    pub fn proof_type(&self) -> ProofType {
+        match self {
+            Self::Groth16 => ProofType::Groth16,
+            Self::SetInclusion { .. } => ProofType::Inclusion,
+            Self::Fake | Self::Unknown => ProofType::Any,
+        }
+    }
+}
+
+impl fmt::Display for SealKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Groth16 => write!(f, "groth16"),
+            Self::SetInclusion { path_length, has_root_seal } => {
+                write!(f, "set inclusion (path length {path_length}")?;
+                if *has_root_seal {
+                    write!(f, ", with root seal")?;
+                }
+                write!(f, ")")
+            }
+            Self::Fake => write!(f, "fake"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Description of a seal, with an estimate of the gas to verify it onchain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SealInfo {
+    /// Selector of the verifier of the seal.
+    pub selector: FixedBytes<4>,
+    /// Kind of the seal.
+    pub kind: SealKind,
+    /// Size of the seal, in bytes.
+    pub size: usize,
+    /// Gas to send the seal as calldata.
+    pub calldata_gas: u64,
+}
+
+impl SealInfo {
+    /// Classify the given seal by its selector, checking that it matches the expected format.
+    pub fn from_seal(seal: &[u8]) -> Result<Self, SealError> {
+        let Some((selector, proof)) = seal.split_first_chunk::<4>() else {
+            return Err(SealError::MissingSelector(seal.len()));
+        };
+        let selector = FixedBytes::from(*selector);
+        let kind = match Selector::from_bytes(selector.into()).map(|s| s.get_type()) {
+            Some(SelectorType::Groth16) => {
+                if proof.len() != GROTH16_PROOF_SIZE {
+                    return Err(SealError::Malformed {
+                        kind: "groth16",
+                        reason: format!(
+                            "expected {GROTH16_PROOF_SIZE} bytes after the selector, got {}",
+                            proof.len()
+                        ),
+                    });
+                }
+                SealKind::Groth16
+            }
+            Some(SelectorType::SetVerifier) => {
+                let decoded = SetInclusionSeal::abi_decode(proof).map_err(|err| {
+                    SealError::Malformed { kind: "set inclusion", reason: err.to_string() }
+                })?;
+                SealKind::SetInclusion {
+                    path_length: decoded.path.len(),
+                    has_root_seal: !decoded.rootSeal.is_empty(),
+                }
+            }
+            Some(SelectorType::FakeReceipt) => SealKind::Fake,
+            _ => SealKind::Unknown,
+        };
+        Ok(Self { selector, kind, size: seal.len(), calldata_gas: calldata_gas(seal) })
+    }
+
+    /// Estimated gas to verify the seal onchain, including its calldata, or `None` if its kind
+    /// is unknown.
+    ///
+    /// Set inclusion seals are estimated with the proof of their root verified if they carry one,
+    /// which is the worst case.
+    pub fn estimated_gas(&self) -> Option<u64> {
+        let verify = match &self.kind {
+            SealKind::Groth16 => GROTH16_VERIFY_GAS,
+            SealKind::SetInclusion { path_length, has_root_seal } => {
+                let root = if *has_root_seal { GROTH16_VERIFY_GAS } else { 0 };
+                SET_INCLUSION_VERIFY_GAS + MERKLE_NODE_GAS * *path_length as u64 + root
+            }
+            SealKind::Fake => FAKE_VERIFY_GAS,
+            SealKind::Unknown => return None,
+        };
+        Some(verify + self.calldata_gas)
+    }
+}
+
+impl fmt::Display for SealInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} seal, {} bytes", self.kind, self.size)?;
+        if let Some(gas) = self.estimated_gas() {
+            write!(f, ", ~{gas} gas to verify")?;
+        }
+        Ok(())
+    }
+}
+
+/// Gas to send the given bytes as calldata: 4 per zero byte, and 16 per non-zero byte.
+pub fn calldata_gas(data: &[u8]) -> u64 {
+    data.iter().map(|&byte| if byte == 0 { 4 } else { 16 }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use super::*;
+
+    fn seal(selector: Selector, proof: &[u8]) -> Vec<u8> {
+        [&(selector as u32).to_be_bytes()[..], proof].concat()
+    }
+
+    #[test]
+    fn test_groth16_seal() {
+        let info = SealInfo::from_seal(&seal(Selector::groth16_latest(), &[1; 256])).unwrap();
+        assert_eq!(info.kind, SealKind::Groth16);
+        assert_eq!(info.size, 260);
+        assert_eq!(info.estimated_gas(), Some(GROTH16_VERIFY_GAS + 260 * 16));
+
+        SealInfo::from_seal(&seal(Selector::groth16_latest(), &[1; 100])).unwrap_err();
+    }
+
+    #[test]
+    fn test_set_inclusion_seal() {
+        let proof =
+            SetInclusionSeal { path: vec![B256::repeat_byte(1); 3], rootSeal: vec![].into() };
+        let info =
+            SealInfo::from_seal(&seal(Selector::set_inclusion_latest(), &proof.abi_encode()))
+                .unwrap();
+        assert_eq!(info.kind, SealKind::SetInclusion { path_length: 3, has_root_seal: false });
+        assert_eq!(info.kind.proof_type(), ProofType::Inclusion);
+        assert_eq!(
+            info.estimated_gas(),
+            Some(SET_INCLUSION_VERIFY_GAS + 3 * MERKLE_NODE_GAS + info.calldata_gas)
+        );
+
+        SealInfo::from_seal(&seal(Selector::set_inclusion_latest(), &[1; 10])).unwrap_err();
+    }
+
+    #[test]
+    fn test_other_seals() {
+        let info = SealInfo::from_seal(&seal(Selector::FakeReceipt, &[0; 32])).unwrap();
+        assert_eq!(info.kind, SealKind::Fake);
+        assert_eq!(info.calldata_gas, 4 * 16 + 32 * 4);
+
+        let info = SealInfo::from_seal(&[0xab, 0xcd, 0xef, 0x01, 0, 0]).unwrap();
+        assert_eq!(info.kind, SealKind::Unknown);
+        assert_eq!(info.estimated_gas(), None);
+
+        SealInfo::from_seal(&[0; 3]).unwrap_err();
+    }
+}
@@ -14,9 +14,13 @@
 
 //! Selector utility functions.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
-use alloy_primitives::FixedBytes;
+use alloy::providers::Provider;
+use alloy_primitives::{Address, FixedBytes};
 use clap::ValueEnum;
 use risc0_aggregation::SetInclusionReceiptVerifierParameters;
 use risc0_ethereum_contracts::selector::{Selector, SelectorType};
@@ -100,18 +104,254 @@ impl SupportedSelectors {
     /// The selector is calculated by constructing the [SetInclusionReceiptVerifierParameters]
     /// using the given image ID. The resulting selector has [ProofType::Inclusion].
     pub fn with_set_builder_image_id(&self, set_builder_image_id: impl Into<Digest>) -> Self {
-        let verifier_params =
-            SetInclusionReceiptVerifierParameters { image_id: set_builder_image_id.into() }
-                .digest();
-        let set_builder_selector: FixedBytes<4> =
-            verifier_params.as_bytes()[0..4].try_into().unwrap();
         let mut selectors = self.selectors.clone();
-        selectors.insert(set_builder_selector, ProofType::Inclusion);
+        selectors.insert(set_builder_selector(set_builder_image_id), ProofType::Inclusion);
 
         Self { selectors }
     }
 }
 
+/// Calculate the selector of the set verifier for the given set builder image ID.
+pub fn set_builder_selector(set_builder_image_id: impl Into<Digest>) -> FixedBytes<4> {
+    let verifier_params =
+        SetInclusionReceiptVerifierParameters { image_id: set_builder_image_id.into() }.digest();
+    verifier_params.as_bytes()[0..4].try_into().unwrap()
+}
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface IVerifierRouter {
+        function getVerifier(bytes4 selector) external view returns (address);
+    }
+
+    /// Getters implemented by some of the verifiers a router routes to.
+    #[sol(rpc)]
+    interface IVerifierInfo {
+        function VERSION() external view returns (string memory);
+        function imageInfo() external view returns (bytes32, string memory);
+    }
+}
+
+/// Error type for the [SelectorRegistry].
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum SelectorRegistryError {
+    /// The verifier router or a verifier could not be queried.
+    #[error("failed to query verifier: {0}")]
+    Query(#[from] alloy::contract::Error),
+}
+
+/// A verifier routed to by a verifier router.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VerifierInfo {
+    /// Selector the verifier is routed to by.
+    pub selector: FixedBytes<4>,
+    /// Address of the verifier.
+    pub verifier: Address,
+    /// Type of the proofs the verifier accepts. Mock verifiers and verifiers of unrecognized
+    /// proofs have [ProofType::Any].
+    pub proof_type: ProofType,
+    /// Version reported by the verifier, if it reports one.
+    pub version: Option<String>,
+}
+
+/// Registry of the verifiers routed to by a verifier router, resolved by querying the router.
+///
+/// Unlike [SupportedSelectors], which lists the selectors known when this crate was built, the
+/// registry resolves any selector the router routes, including those of verifier versions added
+/// after this crate's release. Selectors unknown to this crate are classified by the interface of
+/// their verifier: set verifiers report their image info, and Groth16 verifiers their version.
+/// Lookups, including of unroutable selectors, are cached for the lifetime of the registry.
+///
+/// The router cannot list the selectors it routes, so [Self::latest] and
+/// [Self::supported_selectors] consider the latest selectors known to this crate, the selector of
+/// the deployment's set verifier if given with [Self::with_set_verifier], the selectors added with
+/// [Self::with_candidates], and every routable selector looked up so far.
+#[derive(Clone)]
+pub struct SelectorRegistry<P> {
+    router: IVerifierRouter::IVerifierRouterInstance<P>,
+    candidates: Vec<FixedBytes<4>>,
+    set_verifier: Option<Address>,
+    cache: Arc<Mutex<HashMap<FixedBytes<4>, Option<VerifierInfo>>>>,
+}
+
+impl<P: Provider + Clone> SelectorRegistry<P> {
+    /// Create a registry of the verifiers routed to by the router at the given address.
+    pub fn new(router_address: Address, provider: P) -> Self {
+        let mut candidates = vec![
+            FixedBytes::from(Selector::groth16_latest() as u32),
+            FixedBytes::from(Selector::set_inclusion_latest() as u32),
+        ];
+        if is_dev_mode() {
+            candidates.push(FixedBytes::from(Selector::FakeReceipt as u32));
+        }
+        Self {
+            router: IVerifierRouter::new(router_address, provider),
+            candidates,
+            set_verifier: None,
+            cache: Default::default(),
+        }
+    }
+
+    /// Consider the selector of the given set verifier, derived from the image ID of the set
+    /// builder it reports, which may be newer than the latest known to this crate.
+    pub fn with_set_verifier(self, set_verifier: Address) -> Self {
+        Self { set_verifier: Some(set_verifier), ..self }
+    }
+
+    /// Add selectors to consider in [Self::latest], beyond the latest ones known to this crate.
+    ///
+    /// This allows selecting verifier versions released after this crate, given their selector.
+    pub fn with_candidates(mut self, selectors: impl IntoIterator<Item = FixedBytes<4>>) -> Self {
+        for selector in selectors {
+            if !self.candidates.contains(&selector) {
+                self.candidates.push(selector);
+            }
+        }
+        self
+    }
+
+    /// Look up the verifier routed to by the given selector.
+    ///
+    /// Returns `None` if the router does not route the selector, e.g. because it was never added
+    /// or was removed.
+    pub async fn lookup(
+        &self,
+        selector: FixedBytes<4>,
+    ) -> Result<Option<VerifierInfo>, SelectorRegistryError> {
+        if let Some(info) = self.cache.lock().unwrap().get(&selector) {
+            return Ok(info.clone());
+        }
+
+        let info = match self.router.getVerifier(selector).call().await {
+            Ok(verifier) => Some(self.describe(selector, verifier).await?),
+            Err(err) if call_failed(&err) => None,
+            Err(err) => return Err(err.into()),
+        };
+        self.cache.lock().unwrap().insert(selector, info.clone());
+        Ok(info)
+    }
+
+    /// Find the routable verifier of the given proof type with the highest version among the
+    /// candidate selectors.
+    ///
+    /// Verifiers that report no version rank below those that do. Returns `None` if none of the
+    /// candidates of the proof type are routable.
+    pub async fn latest(
+        &self,
+        proof_type: ProofType,
+    ) -> Result<Option<VerifierInfo>, SelectorRegistryError> {
+        let mut latest: Option<VerifierInfo> = None;
+        for selector in self.candidates().await? {
+            let Some(info) = self.lookup(selector).await? else {
+                continue;
+            };
+            if info.proof_type != proof_type {
+                continue;
+            }
+            let newer = latest.as_ref().is_none_or(|latest| {
+                parse_version(info.version.as_deref()) > parse_version(latest.version.as_deref())
+            });
+            if newer {
+                latest = Some(info);
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Build the [SupportedSelectors] of the routable candidate selectors.
+    pub async fn supported_selectors(&self) -> Result<SupportedSelectors, SelectorRegistryError> {
+        let mut supported =
+            SupportedSelectors::new().with_selector(UNSPECIFIED_SELECTOR, ProofType::Any);
+        for selector in self.candidates().await? {
+            if let Some(info) = self.lookup(selector).await? {
+                supported.add_selector(selector, info.proof_type);
+            }
+        }
+        Ok(supported)
+    }
+
+    // Selectors considered by [Self::latest] and [Self::supported_selectors].
+    async fn candidates(&self) -> Result<Vec<FixedBytes<4>>, SelectorRegistryError> {
+        let mut candidates = self.candidates.clone();
+        if let Some(set_verifier) = self.set_verifier {
+            let instance = IVerifierInfo::new(set_verifier, self.router.provider().clone());
+            let image_id = instance.imageInfo().call().await?._0;
+            candidates.push(set_builder_selector(<[u8; 32]>::from(image_id)));
+        }
+        let routable: Vec<FixedBytes<4>> = self
+            .cache
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(selector, info)| info.as_ref().map(|_| *selector))
+            .collect();
+        candidates.extend(routable);
+
+        let mut unique = Vec::with_capacity(candidates.len());
+        for selector in candidates {
+            if !unique.contains(&selector) {
+                unique.push(selector);
+            }
+        }
+        Ok(unique)
+    }
+
+    async fn describe(
+        &self,
+        selector: FixedBytes<4>,
+        verifier: Address,
+    ) -> Result<VerifierInfo, SelectorRegistryError> {
+        let instance = IVerifierInfo::new(verifier, self.router.provider().clone());
+        let version = match instance.VERSION().call().await {
+            Ok(version) => Some(version),
+            Err(err) if call_failed(&err) => None,
+            Err(err) => return Err(err.into()),
+        };
+        let proof_type = match Selector::from_bytes(selector.into()) {
+            Some(known) => proof_type_of(known.get_type()),
+            None => match instance.imageInfo().call().await {
+                Ok(_) => ProofType::Inclusion,
+                Err(err) if call_failed(&err) => match version {
+                    Some(_) => ProofType::Groth16,
+                    None => ProofType::Any,
+                },
+                Err(err) => return Err(err.into()),
+            },
+        };
+        Ok(VerifierInfo { selector, verifier, proof_type, version })
+    }
+}
+
+// Whether a call failed in the node, e.g. because it reverted, or returned no data because the
+// function does not exist, rather than failing to reach the node or returning undecodable data.
+fn call_failed(err: &alloy::contract::Error) -> bool {
+    match err {
+        alloy::contract::Error::TransportError(err) => err.as_error_resp().is_some(),
+        alloy::contract::Error::ZeroData(..) => true,
+        _ => false,
+    }
+}
+
+fn proof_type_of(selector_type: SelectorType) -> ProofType {
+    if selector_type == SelectorType::Groth16 {
+        ProofType::Groth16
+    } else if selector_type == SelectorType::SetVerifier {
+        ProofType::Inclusion
+    } else {
+        ProofType::Any
+    }
+}
+
+// Parse a version such as `3.0.0` into its numeric components, for ordering. Missing versions
+// order first, and non-numeric components are ignored.
+fn parse_version(version: Option<&str>) -> Option<Vec<u64>> {
+    version.map(|version| {
+        version.trim_start_matches('v').split('.').filter_map(|part| part.parse().ok()).collect()
+    })
+}
+
 /// Check if a selector is a groth16 selector.
 pub fn is_groth16_selector(selector: FixedBytes<4>) -> bool {
     let sel = Selector::from_bytes(selector.into());
@@ -126,7 +366,14 @@ pub fn is_groth16_selector(selector: FixedBytes<4>) -> bool {
 
 #[cfg(test)]
 mod tests {
+    use alloy::node_bindings::Anvil;
+    use boundless_test_utils::market::create_test_ctx;
+
     use super::*;
+    use crate::{
+        contracts::{Predicate, RequestId, Requirements},
+        request_builder::{Layer, OfferLayer, OfferParams},
+    };
 
     #[test]
     fn test_supported_selectors() {
@@ -138,6 +385,57 @@ mod tests {
         assert!(!supported_selectors.is_supported(selector));
     }
 
+    #[test]
+    fn test_parse_version() {
+        assert!(parse_version(Some("3.0.0")) > parse_version(Some("2.2.0")));
+        assert!(parse_version(Some("v3.1")) > parse_version(Some("3.0.9")));
+        assert!(parse_version(Some("1.0.0")) > parse_version(None));
+    }
+
+    #[test]
+    fn test_proof_type_of() {
+        assert_eq!(proof_type_of(Selector::groth16_latest().get_type()), ProofType::Groth16);
+        assert_eq!(
+            proof_type_of(Selector::set_inclusion_latest().get_type()),
+            ProofType::Inclusion
+        );
+        assert_eq!(proof_type_of(Selector::FakeReceipt.get_type()), ProofType::Any);
+    }
+
+    #[tokio::test]
+    async fn test_registry_lookup() {
+        let anvil = Anvil::new().spawn();
+        let ctx = create_test_ctx(&anvil).await.unwrap();
+        let router = ctx.deployment.verifier_router_address.unwrap();
+        let registry = SelectorRegistry::new(router, ctx.customer_provider.clone())
+            .with_set_verifier(ctx.deployment.set_verifier_address);
+
+        // The selector of the deployment's set verifier is found through the set verifier, even
+        // though its set builder is not the latest known to this crate.
+        let (image_id, _) = ctx.set_verifier.image_info().await.unwrap();
+        let set_selector = set_builder_selector(<[u8; 32]>::from(image_id));
+        let latest = registry.latest(ProofType::Inclusion).await.unwrap().unwrap();
+        assert_eq!(latest.selector, set_selector);
+        assert_eq!(latest.verifier, ctx.deployment.set_verifier_address);
+        assert_eq!(registry.lookup(set_selector).await.unwrap(), Some(latest));
+
+        // Selectors the router does not route are looked up as missing, rather than as errors.
+        let unroutable = FixedBytes::from([1, 2, 3, 4]);
+        assert_eq!(registry.lookup(unroutable).await.unwrap(), None);
+
+        // The offer layer classifies selectors unknown to its config with the registry.
+        let request_id = RequestId::new(ctx.customer_signer.address(), 0);
+        let params = OfferParams::default();
+        let mut requirements = Requirements::new(Predicate::prefix_match(Digest::ZERO, Vec::new()));
+        requirements.selector = set_selector;
+        let layer = OfferLayer::from(ctx.customer_provider.clone());
+        assert!(layer.process((&requirements, &request_id, Some(0), &params)).await.is_err());
+        let layer = layer.with_selector_registry(registry);
+        layer.process((&requirements, &request_id, Some(0), &params)).await.unwrap();
+        requirements.selector = unroutable;
+        assert!(layer.process((&requirements, &request_id, Some(0), &params)).await.is_err());
+    }
+
     #[test]
     fn test_is_groth16_selector() {
         let selector = FixedBytes::from(Selector::groth16_latest() as u32);
@@ -0,0 +1,174 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::{Arc, Mutex};
+
+use alloy::network::Ethereum;
+use alloy::primitives::{keccak256, B256};
+use alloy::providers::{PendingTransactionBuilder, Provider, ProviderLayer, RootProvider};
+use alloy::transports::{RpcError, TransportResult};
+
+use crate::event_bus::{BusEvent, EventBus};
+
+/// Shared log of the hashes of transactions broadcast through a [SentTxProvider].
+///
+/// A transaction is recorded before it is sent, so that an interrupted caller can still report
+/// the hash of a transaction that may land onchain. It is dropped again only if the node rejected
+/// it.
+#[derive(Clone, Debug, Default)]
+pub struct SentTransactions {
+    hashes: Arc<Mutex<Vec<B256>>>,
+}
+
+impl SentTransactions {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hashes of the transactions sent so far, in the order they were sent.
+    pub fn hashes(&self) -> Vec<B256> {
+        self.hashes.lock().unwrap().clone()
+    }
+
+    fn record(&self, hash: B256) {
+        self.hashes.lock().unwrap().push(hash);
+    }
+
+    fn remove(&self, hash: B256) {
+        self.hashes.lock().unwrap().retain(|sent| *sent != hash);
+    }
+}
+
+/// A layer that can be added to an alloy Provider to record the hashes of the transactions it
+/// broadcasts in a [SentTransactions] log.
+///
+/// # Examples
+/// ```no_run
+/// # use alloy::providers::ProviderBuilder;
+/// # use boundless_market::sent_tx_layer::{SentTransactions, SentTxLayer};
+/// let sent = SentTransactions::new();
+/// let provider = ProviderBuilder::new().layer(SentTxLayer::new(sent.clone()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SentTxLayer {
+    log: SentTransactions,
+    event_bus: Option<EventBus>,
+}
+
+impl SentTxLayer {
+    /// Creates a new SentTxLayer recording into the given log.
+    pub fn new(log: SentTransactions) -> Self {
+        Self { log, event_bus: None }
+    }
+
+    /// Publishes the hashes of the transactions accepted by the node on the given [EventBus].
+    pub fn with_event_bus(self, event_bus: impl Into<Option<EventBus>>) -> Self {
+        Self { event_bus: event_bus.into(), ..self }
+    }
+}
+
+impl<P> ProviderLayer<P> for SentTxLayer
+where
+    P: Provider,
+{
+    type Provider = SentTxProvider<P>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        SentTxProvider { inner, log: self.log.clone(), event_bus: self.event_bus.clone() }
+    }
+}
+
+/// A provider that records the hashes of the transactions it broadcasts.
+#[derive(Clone, Debug)]
+pub struct SentTxProvider<P> {
+    inner: P,
+    log: SentTransactions,
+    event_bus: Option<EventBus>,
+}
+
+#[async_trait::async_trait]
+impl<P> Provider for SentTxProvider<P>
+where
+    P: Provider,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider {
+        self.inner.root()
+    }
+
+    /// Broadcasts a raw transaction RLP bytes to the network.
+    ///
+    /// This override records the hash of the transaction before sending it, and publishes it on
+    /// the event bus, if any, once the node accepted it.
+    async fn send_raw_transaction(
+        &self,
+        encoded_tx: &[u8],
+    ) -> TransportResult<PendingTransactionBuilder<Ethereum>> {
+        let hash = keccak256(encoded_tx);
+        self.log.record(hash);
+        let res = self.inner.send_raw_transaction(encoded_tx).await;
+        match &res {
+            Err(RpcError::ErrorResp(_)) => self.log.remove(hash),
+            Ok(_) => {
+                if let Some(event_bus) = &self.event_bus {
+                    event_bus.publish(BusEvent::TxSent { tx_hash: hash });
+                }
+            }
+            Err(_) => {}
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        network::{EthereumWallet, TransactionBuilder},
+        node_bindings::Anvil,
+        primitives::{utils::parse_ether, Address},
+        providers::ProviderBuilder,
+        rpc::{client::RpcClient, types::TransactionRequest},
+        signers::local::LocalSigner,
+    };
+
+    #[tokio::test]
+    async fn test_sent_tx_layer() -> anyhow::Result<()> {
+        let anvil = Anvil::default().args(["--balance", "10"]).spawn();
+        let wallet = EthereumWallet::from(LocalSigner::from(anvil.keys()[0].clone()));
+        let client = RpcClient::builder().http(anvil.endpoint_url());
+
+        let sent = SentTransactions::new();
+        let event_bus = EventBus::default();
+        let mut events = event_bus.subscribe();
+        let provider = ProviderBuilder::new()
+            .layer(SentTxLayer::new(sent.clone()).with_event_bus(event_bus))
+            .wallet(wallet)
+            .connect_client(client);
+
+        let tx = TransactionRequest::default().with_to(Address::ZERO).with_value(parse_ether("1")?);
+        let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+        assert_eq!(sent.hashes(), vec![receipt.transaction_hash]);
+        assert_eq!(events.try_recv()?, BusEvent::TxSent { tx_hash: receipt.transaction_hash });
+
+        // A transaction that fails before it is accepted by the node is not kept in the log.
+        let tx =
+            TransactionRequest::default().with_to(Address::ZERO).with_value(parse_ether("100")?);
+        assert!(provider.send_transaction(tx).await.is_err());
+        assert_eq!(sent.hashes(), vec![receipt.transaction_hash]);
+        assert!(events.try_recv().is_err());
+
+        Ok(())
+    }
+}
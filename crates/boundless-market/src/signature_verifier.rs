@@ -0,0 +1,174 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Batched verification of the EIP-712 signatures on proof requests.
+//!
+//! Consumers such as the CLI `fulfill` command and provers validating the order stream check
+//! many signatures at once. [SignatureVerifier] uses the EIP-712 domain separators cached per
+//! market and chain, and spreads the signature recoveries across blocking tasks.
+
+use std::{num::NonZeroUsize, thread};
+
+use alloy::primitives::{Address, Bytes, B256};
+
+use crate::contracts::{eip712_domain_separator, ProofRequest, RequestError};
+
+/// Batches smaller than this are verified on the calling thread.
+const MIN_PARALLEL_BATCH: usize = 16;
+
+/// Outcome of a successful signature check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The signature was produced by the client address in the request ID.
+    Valid,
+    /// The request is signed by a smart contract (ERC-1271), which can only be checked onchain.
+    SmartContractSigned,
+}
+
+/// Verifier of proof request signatures.
+///
+/// The EIP-712 domain separator of each market is cached by [eip712_domain_separator].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct SignatureVerifier {}
+
+impl SignatureVerifier {
+    /// Create a verifier.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the EIP-712 domain separator of the market at `market_address` on `chain_id`.
+    pub fn domain_separator(&self, market_address: Address, chain_id: u64) -> B256 {
+        eip712_domain_separator(market_address, chain_id)
+    }
+
+    /// Verify the signature of a single request.
+    pub fn verify(
+        &self,
+        request: &ProofRequest,
+        signature: &Bytes,
+        market_address: Address,
+        chain_id: u64,
+    ) -> Result<SignatureStatus, RequestError> {
+        let separator = self.domain_separator(market_address, chain_id);
+        verify_with_separator(request, signature, separator)
+    }
+
+    /// Verify the signatures of a batch of requests, all submitted to the market at
+    /// `market_address` on `chain_id`.
+    ///
+    /// Returns one result per request, in the order given. Large batches are verified in
+    /// parallel on blocking tasks, so as not to stall the async runtime.
+    pub async fn verify_batch(
+        &self,
+        requests: &[(ProofRequest, Bytes)],
+        market_address: Address,
+        chain_id: u64,
+    ) -> Vec<Result<SignatureStatus, RequestError>> {
+        let separator = self.domain_separator(market_address, chain_id);
+        let threads = thread::available_parallelism().map(NonZeroUsize::get).unwrap_or(1);
+        if requests.len() < MIN_PARALLEL_BATCH || threads == 1 {
+            return verify_chunk(requests, separator);
+        }
+        let chunk_size = requests.len().div_ceil(threads);
+        let tasks = requests.chunks(chunk_size).map(|chunk| {
+            let chunk = chunk.to_vec();
+            tokio::task::spawn_blocking(move || verify_chunk(&chunk, separator))
+        });
+        futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .flat_map(|result| result.expect("signature verification task panicked"))
+            .collect()
+    }
+}
+
+fn verify_chunk(
+    requests: &[(ProofRequest, Bytes)],
+    separator: B256,
+) -> Vec<Result<SignatureStatus, RequestError>> {
+    requests
+        .iter()
+        .map(|(request, signature)| verify_with_separator(request, signature, separator))
+        .collect()
+}
+
+fn verify_with_separator(
+    request: &ProofRequest,
+    signature: &Bytes,
+    separator: B256,
+) -> Result<SignatureStatus, RequestError> {
+    if request.is_smart_contract_signed() {
+        return Ok(SignatureStatus::SmartContractSigned);
+    }
+    request.verify_signature_with_separator(signature, separator)?;
+    Ok(SignatureStatus::Valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::local::PrivateKeySigner;
+
+    use super::*;
+    use crate::contracts::{eip712_domain, RequestId};
+
+    async fn signed_requests(
+        count: u32,
+        market: Address,
+        chain_id: u64,
+    ) -> Vec<(ProofRequest, Bytes)> {
+        let signer = PrivateKeySigner::random();
+        let mut requests = Vec::new();
+        for index in 0..count {
+            let request =
+                ProofRequest { id: RequestId::u256(signer.address(), index), ..Default::default() };
+            let signature = request.sign_request(&signer, market, chain_id).await.unwrap();
+            requests.push((request, signature.as_bytes().into()));
+        }
+        requests
+    }
+
+    #[tokio::test]
+    async fn verify_batch_matches_single_verification() {
+        let market = Address::repeat_byte(0x42);
+        let mut requests = signed_requests(40, market, 1).await;
+        // Corrupt one signature and sign another for the wrong chain.
+        requests[3].1 = Bytes::from(vec![1u8; 65]);
+        let (request, signature) = signed_requests(1, market, 2).await.remove(0);
+        requests[25] = (request, signature);
+
+        let verifier = SignatureVerifier::new();
+        let results = verifier.verify_batch(&requests, market, 1).await;
+        assert_eq!(results.len(), requests.len());
+        for (i, ((request, signature), result)) in requests.iter().zip(&results).enumerate() {
+            assert_eq!(
+                result.is_ok(),
+                request.verify_signature(signature, market, 1).is_ok(),
+                "mismatch at {i}"
+            );
+            assert_eq!(result.is_err(), i == 3 || i == 25, "unexpected result at {i}");
+        }
+    }
+
+    #[test]
+    fn domain_separator_is_cached_per_chain() {
+        let verifier = SignatureVerifier::new();
+        let market = Address::repeat_byte(0x42);
+        let mainnet = verifier.domain_separator(market, 1);
+        assert_eq!(mainnet, eip712_domain(market, 1).alloy_struct().separator());
+        assert_ne!(mainnet, verifier.domain_separator(market, 8453));
+        assert_ne!(mainnet, verifier.domain_separator(Address::repeat_byte(0x43), 1));
+        assert_eq!(verifier.domain_separator(market, 1), mainnet);
+    }
+}
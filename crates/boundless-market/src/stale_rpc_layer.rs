@@ -0,0 +1,334 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use alloy::{
+    network::Ethereum,
+    providers::{DynProvider, Provider, ProviderLayer, RootProvider},
+    transports::{RpcError, TransportError, TransportErrorKind, TransportResult},
+};
+use alloy_chains::NamedChain;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+use crate::deployments::ChainTiming;
+
+/// Number of expected block intervals after which a head block is considered stale.
+const STALE_BLOCK_INTERVALS: u32 = 10;
+
+/// Minimum default head age, so chains with very short block times tolerate brief hiccups.
+const MIN_MAX_HEAD_AGE: Duration = Duration::from_secs(60);
+
+/// Error returned when the RPC node appears to be lagging behind the chain.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StaleRpcError {
+    /// The head block reported by the node is older than the configured maximum age.
+    #[error("stale RPC: head block {head} is {age:?} old, more than the maximum of {max_age:?}")]
+    HeadTooOld {
+        /// Head block number reported by the node.
+        head: u64,
+        /// Age of the head block, by wall-clock time.
+        age: Duration,
+        /// Maximum accepted age.
+        max_age: Duration,
+    },
+    /// The head block reported by the node is behind that of the reference endpoint.
+    #[error("stale RPC: head block {head} is {lag} blocks behind the reference endpoint")]
+    BehindReference {
+        /// Head block number reported by the node.
+        head: u64,
+        /// Number of blocks the node is behind the reference endpoint.
+        lag: u64,
+    },
+}
+
+impl StaleRpcError {
+    /// Returns the [StaleRpcError] wrapped by the given transport error, if any.
+    ///
+    /// Errors from the [StaleRpcProvider] are returned through the [Provider] methods as custom
+    /// transport errors.
+    pub fn from_transport_error(err: &TransportError) -> Option<&Self> {
+        match err {
+            RpcError::Transport(TransportErrorKind::Custom(err)) => err.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Default maximum age of the head block for the given chain.
+///
+/// Returns `None` for local development chains, which only produce blocks on demand.
+pub fn default_max_head_age(chain_id: u64) -> Option<Duration> {
+    if let Ok(NamedChain::AnvilHardhat | NamedChain::Dev) = NamedChain::try_from(chain_id) {
+        return None;
+    }
+    let block_time = ChainTiming::for_chain_id(chain_id).block_time;
+    Some((block_time * STALE_BLOCK_INTERVALS).max(MIN_MAX_HEAD_AGE))
+}
+
+/// Configuration for the [StaleRpcLayer].
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct StaleRpcConfig {
+    /// Whether to check the age of the head block against wall-clock time.
+    pub check_head_age: bool,
+    /// Maximum age of the head block. If `None`, [default_max_head_age] for the chain is used.
+    pub max_head_age: Option<Duration>,
+    /// Second endpoint to compare the head block against.
+    pub reference: Option<DynProvider>,
+    /// Maximum number of blocks the node may be behind the reference endpoint.
+    pub max_block_lag: u64,
+}
+
+impl std::fmt::Debug for StaleRpcConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaleRpcConfig")
+            .field("check_head_age", &self.check_head_age)
+            .field("max_head_age", &self.max_head_age)
+            .field("reference", &self.reference.is_some())
+            .field("max_block_lag", &self.max_block_lag)
+            .finish()
+    }
+}
+
+impl Default for StaleRpcConfig {
+    fn default() -> Self {
+        Self { check_head_age: true, max_head_age: None, reference: None, max_block_lag: 10 }
+    }
+}
+
+impl StaleRpcConfig {
+    /// Set whether to check the age of the head block.
+    pub fn with_check_head_age(self, check_head_age: bool) -> Self {
+        Self { check_head_age, ..self }
+    }
+
+    /// Set the maximum age of the head block, overriding the default for the chain.
+    pub fn with_max_head_age(self, max_head_age: Duration) -> Self {
+        Self { max_head_age: Some(max_head_age), ..self }
+    }
+
+    /// Set a second endpoint to compare the head block against.
+    pub fn with_reference(self, reference: DynProvider) -> Self {
+        Self { reference: Some(reference), ..self }
+    }
+
+    /// Set the maximum number of blocks the node may be behind the reference endpoint.
+    pub fn with_max_block_lag(self, max_block_lag: u64) -> Self {
+        Self { max_block_lag, ..self }
+    }
+}
+
+/// Checks whether the head block reported by an RPC node is fresh.
+///
+/// The timestamp of the head block is checked against wall-clock time, and optionally the head
+/// block number against a second endpoint. A lagging node is reported as a [StaleRpcError],
+/// wrapped in a custom transport error.
+#[derive(Clone, Debug, Default)]
+pub struct StaleRpcGuard {
+    config: StaleRpcConfig,
+    // Maximum head age resolved for the chain of the node, or None if not checked.
+    max_head_age: OnceCell<Option<Duration>>,
+}
+
+impl StaleRpcGuard {
+    /// Creates a new guard with the given configuration.
+    pub fn new(config: StaleRpcConfig) -> Self {
+        Self { config, max_head_age: OnceCell::new() }
+    }
+
+    async fn max_head_age(&self, provider: &impl Provider) -> TransportResult<Option<Duration>> {
+        if !self.config.check_head_age {
+            return Ok(None);
+        }
+        if let Some(max_head_age) = self.config.max_head_age {
+            return Ok(Some(max_head_age));
+        }
+        self.max_head_age
+            .get_or_try_init(|| async {
+                Ok::<_, TransportError>(default_max_head_age(provider.get_chain_id().await?))
+            })
+            .await
+            .copied()
+    }
+
+    /// Check that the given head block reported by the provider is fresh.
+    pub async fn check(&self, provider: &impl Provider, head: u64) -> TransportResult<()> {
+        if let Some(max_age) = self.max_head_age(provider).await? {
+            let block = provider.get_block_by_number(head.into()).await?.ok_or_else(|| {
+                TransportErrorKind::custom_str(&format!("head block {head} not found"))
+            })?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let age = Duration::from_secs(now.saturating_sub(block.header.timestamp));
+            if age > max_age {
+                return Err(TransportErrorKind::custom(StaleRpcError::HeadTooOld {
+                    head,
+                    age,
+                    max_age,
+                }));
+            }
+        }
+
+        if let Some(reference) = &self.config.reference {
+            let reference_head = reference.get_block_number().await?;
+            let lag = reference_head.saturating_sub(head);
+            if lag > self.config.max_block_lag {
+                return Err(TransportErrorKind::custom(StaleRpcError::BehindReference {
+                    head,
+                    lag,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the head block number from the provider, checking that it is fresh.
+    pub async fn block_number(&self, provider: &impl Provider) -> TransportResult<u64> {
+        let head = provider.get_block_number().await?;
+        self.check(provider, head).await?;
+        Ok(head)
+    }
+}
+
+/// A [ProviderLayer] that detects when the RPC node is lagging behind the chain.
+///
+/// Each call to [Provider::get_block_number] is checked by a [StaleRpcGuard]. If the node is
+/// lagging, a [StaleRpcError] is returned instead of the block number, so services that poll the
+/// head refuse to act on stale data.
+///
+/// Fillers added to a [alloy::providers::ProviderBuilder] wrap its layers, and do not forward
+/// [Provider::get_block_number] to them. When using fillers, use a [StaleRpcGuard] directly.
+///
+/// # Examples
+/// ```no_run
+/// # use std::time::Duration;
+/// # use alloy::providers::ProviderBuilder;
+/// # use boundless_market::stale_rpc_layer::{StaleRpcConfig, StaleRpcLayer};
+/// let provider = ProviderBuilder::new()
+///     .disable_recommended_fillers()
+///     .layer(StaleRpcLayer::new(
+///         StaleRpcConfig::default().with_max_head_age(Duration::from_secs(120)),
+///     ))
+///     .connect_http("http://localhost:8545".parse()?);
+/// # anyhow::Ok(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StaleRpcLayer {
+    config: StaleRpcConfig,
+}
+
+impl StaleRpcLayer {
+    /// Creates a new StaleRpcLayer with the given configuration.
+    pub fn new(config: StaleRpcConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<P> ProviderLayer<P> for StaleRpcLayer
+where
+    P: Provider,
+{
+    type Provider = StaleRpcProvider<P>;
+
+    fn layer(&self, inner: P) -> Self::Provider {
+        StaleRpcProvider { inner, guard: StaleRpcGuard::new(self.config.clone()) }
+    }
+}
+
+/// A provider that returns a [StaleRpcError] from [Provider::get_block_number] when the node is
+/// lagging behind the chain.
+#[derive(Clone, Debug)]
+pub struct StaleRpcProvider<P> {
+    inner: P,
+    guard: StaleRpcGuard,
+}
+
+#[async_trait::async_trait]
+impl<P> Provider for StaleRpcProvider<P>
+where
+    P: Provider,
+{
+    #[inline(always)]
+    fn root(&self) -> &RootProvider<Ethereum> {
+        self.inner.root()
+    }
+
+    /// Gets the latest block number, checking that the node is not lagging behind the chain.
+    async fn get_block_number(&self) -> TransportResult<u64> {
+        self.guard.block_number(&self.inner).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{node_bindings::Anvil, primitives::U256, providers::ProviderBuilder};
+
+    #[test]
+    fn test_default_max_head_age() {
+        assert_eq!(default_max_head_age(1), Some(Duration::from_secs(120)));
+        assert_eq!(default_max_head_age(8453), Some(Duration::from_secs(60)));
+        assert_eq!(default_max_head_age(31337), None);
+    }
+
+    #[tokio::test]
+    async fn test_stale_rpc_layer() -> anyhow::Result<()> {
+        let anvil = Anvil::new().spawn();
+        let reference = Anvil::new().spawn();
+        let reference_provider =
+            ProviderBuilder::new().connect_http(reference.endpoint_url()).erased();
+        reference_provider
+            .raw_request::<_, serde_json::Value>(
+                "anvil_mine".into(),
+                (U256::from(20), None::<U256>),
+            )
+            .await?;
+
+        // Anvil only mines on demand, so its genesis block ages with wall-clock time.
+        let provider = ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .layer(StaleRpcLayer::new(StaleRpcConfig::default()))
+            .connect_http(anvil.endpoint_url());
+        assert_eq!(provider.get_block_number().await?, 0);
+
+        let provider = ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .layer(StaleRpcLayer::new(StaleRpcConfig::default().with_max_head_age(Duration::ZERO)))
+            .connect_http(anvil.endpoint_url());
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let err = provider.get_block_number().await.unwrap_err();
+        assert!(matches!(
+            StaleRpcError::from_transport_error(&err),
+            Some(StaleRpcError::HeadTooOld { head: 0, .. })
+        ));
+
+        let provider = ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .layer(StaleRpcLayer::new(
+                StaleRpcConfig::default()
+                    .with_check_head_age(false)
+                    .with_reference(reference_provider),
+            ))
+            .connect_http(anvil.endpoint_url());
+        let err = provider.get_block_number().await.unwrap_err();
+        assert_eq!(
+            StaleRpcError::from_transport_error(&err),
+            Some(&StaleRpcError::BehindReference { head: 0, lag: 20 })
+        );
+
+        Ok(())
+    }
+}
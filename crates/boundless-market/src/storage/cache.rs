@@ -0,0 +1,277 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A persistent local cache of fetched programs, keyed by image ID.
+
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use hex::FromHex;
+use risc0_zkvm::{compute_image_id, sha::Digest};
+
+use super::fetch_url;
+
+/// Environment variable overriding the directory of the program cache.
+pub const PROGRAM_CACHE_DIR_ENV: &str = "BOUNDLESS_PROGRAM_CACHE_DIR";
+
+/// Default maximum total size of the program cache, in bytes.
+pub const DEFAULT_PROGRAM_CACHE_SIZE: u64 = 1 << 30;
+
+const PROGRAM_EXTENSION: &str = "bin";
+
+/// A cached program.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ProgramCacheEntry {
+    /// Image ID of the program.
+    pub image_id: Digest,
+    /// Size of the program, in bytes.
+    pub size: u64,
+    /// Time the program was last read from or written to the cache.
+    pub last_used: SystemTime,
+}
+
+/// An on-disk cache of programs, keyed by their image ID.
+///
+/// Programs are only stored under the image ID computed from their contents, so a cache hit
+/// always returns the program with the requested image ID. The cache is bounded in size: once a
+/// write pushes it over its maximum size, the least recently used programs are evicted.
+#[derive(Clone, Debug)]
+pub struct ProgramCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl ProgramCache {
+    /// Creates a cache in the given directory, bounded to `max_size` bytes.
+    ///
+    /// The directory is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>, max_size: u64) -> Self {
+        Self { dir: dir.into(), max_size }
+    }
+
+    /// Default directory of the cache.
+    ///
+    /// This is `$BOUNDLESS_PROGRAM_CACHE_DIR` if set, and otherwise `boundless/programs` under
+    /// `$XDG_CACHE_HOME` or `~/.cache`. Returns `None` if none of these variables are set.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Some(dir) = std::env::var_os(PROGRAM_CACHE_DIR_ENV) {
+            return Some(PathBuf::from(dir));
+        }
+        let cache_home = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(cache_home.join("boundless").join("programs"))
+    }
+
+    /// Creates a cache in the [default directory][Self::default_dir], bounded to
+    /// [DEFAULT_PROGRAM_CACHE_SIZE].
+    pub fn from_default_dir() -> Option<Self> {
+        Self::default_dir().map(|dir| Self::new(dir, DEFAULT_PROGRAM_CACHE_SIZE))
+    }
+
+    /// Directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Maximum total size of the cache, in bytes.
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    fn path(&self, image_id: &Digest) -> PathBuf {
+        self.dir.join(format!("{image_id}.{PROGRAM_EXTENSION}"))
+    }
+
+    /// Returns the program with the given image ID, if it is cached.
+    pub fn get(&self, image_id: &Digest) -> Result<Option<Vec<u8>>> {
+        let path = self.path(image_id);
+        let program = match fs::read(&path) {
+            Ok(program) => program,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            }
+        };
+        // Refresh the modification time, which orders the entries for eviction.
+        let touched =
+            File::options().write(true).open(&path).and_then(|f| f.set_modified(SystemTime::now()));
+        if let Err(err) = touched {
+            tracing::debug!("Failed to update the last use of {}: {err}", path.display());
+        }
+        Ok(Some(program))
+    }
+
+    /// Stores a program, returning its image ID.
+    ///
+    /// Evicts the least recently used programs if the cache grows over its maximum size.
+    pub fn insert(&self, program: &[u8]) -> Result<Digest> {
+        let image_id = compute_image_id(program)?;
+        if program.len() as u64 > self.max_size {
+            tracing::debug!("Program {image_id} is larger than the program cache; not caching it");
+            return Ok(image_id);
+        }
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create directory {}", self.dir.display()))?;
+        // Write to a temporary file first, so concurrent readers never see a partial program.
+        let mut file = tempfile::NamedTempFile::new_in(&self.dir)?;
+        file.write_all(program)?;
+        file.persist(self.path(&image_id))?;
+        self.evict()?;
+        Ok(image_id)
+    }
+
+    /// Fetches the program at the given URL, unless a program with `image_id` is cached.
+    ///
+    /// Returns the image ID of the program along with the program. The image ID may differ from
+    /// `image_id` if the URL serves a different program; the caller is responsible for checking
+    /// it. Failing to write to the cache is logged rather than returned, since the program was
+    /// still fetched.
+    pub async fn fetch_program(
+        &self,
+        url: &str,
+        image_id: Option<Digest>,
+    ) -> Result<(Digest, Vec<u8>)> {
+        if let Some(image_id) = image_id {
+            match self.get(&image_id) {
+                Ok(Some(program)) => {
+                    tracing::debug!("Using cached program {image_id}");
+                    return Ok((image_id, program));
+                }
+                Ok(None) => {}
+                Err(err) => tracing::warn!("Failed to read cached program {image_id}: {err:#}"),
+            }
+        }
+        let program = fetch_url(url).await?;
+        let image_id = match self.insert(&program) {
+            Ok(image_id) => image_id,
+            Err(err) => {
+                tracing::warn!("Failed to cache program fetched from {url}: {err:#}");
+                compute_image_id(&program)?
+            }
+        };
+        Ok((image_id, program))
+    }
+
+    /// Lists the cached programs, most recently used first.
+    pub fn entries(&self) -> Result<Vec<ProgramCacheEntry>> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("failed to read directory {}", self.dir.display()))
+            }
+        };
+        let mut entries = vec![];
+        for dir_entry in dir {
+            let path = dir_entry?.path();
+            if path.extension().is_none_or(|ext| ext != PROGRAM_EXTENSION) {
+                continue;
+            }
+            let Some(image_id) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| Digest::from_hex(stem).ok())
+            else {
+                continue;
+            };
+            // Entries may be evicted concurrently by another process.
+            let metadata = match fs::metadata(&path) {
+                Ok(metadata) => metadata,
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            entries.push(ProgramCacheEntry {
+                image_id,
+                size: metadata.len(),
+                last_used: metadata.modified()?,
+            });
+        }
+        entries.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+        Ok(entries)
+    }
+
+    /// Removes the program with the given image ID, returning whether it was cached.
+    pub fn remove(&self, image_id: &Digest) -> Result<bool> {
+        match fs::remove_file(self.path(image_id)) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Removes all cached programs, returning the removed entries.
+    pub fn clear(&self) -> Result<Vec<ProgramCacheEntry>> {
+        let entries = self.entries()?;
+        for entry in &entries {
+            self.remove(&entry.image_id)?;
+        }
+        Ok(entries)
+    }
+
+    /// Removes the least recently used programs until the cache fits in its maximum size.
+    fn evict(&self) -> Result<()> {
+        let entries = self.entries()?;
+        let mut size = 0;
+        for entry in entries {
+            size += entry.size;
+            if size > self.max_size {
+                tracing::debug!("Evicting program {} from the program cache", entry.image_id);
+                self.remove(&entry.image_id)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boundless_test_utils::guests::{ECHO_ELF, ECHO_ID, IDENTITY_ELF, IDENTITY_ID};
+
+    #[test]
+    fn insert_get_evict() {
+        let dir = tempfile::tempdir().unwrap();
+        // Room for either program, but not both.
+        let max_size = (ECHO_ELF.len() + IDENTITY_ELF.len() - 1) as u64;
+        let cache = ProgramCache::new(dir.path().join("programs"), max_size);
+        assert!(cache.entries().unwrap().is_empty());
+        assert!(cache.get(&Digest::from(ECHO_ID)).unwrap().is_none());
+
+        assert_eq!(cache.insert(ECHO_ELF).unwrap(), Digest::from(ECHO_ID));
+        assert_eq!(cache.get(&Digest::from(ECHO_ID)).unwrap().unwrap(), ECHO_ELF);
+        File::options()
+            .write(true)
+            .open(cache.path(&Digest::from(ECHO_ID)))
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+
+        // Inserting another program pushes the cache over its size, evicting the older one.
+        cache.insert(IDENTITY_ELF).unwrap();
+        let entries = cache.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].image_id, Digest::from(IDENTITY_ID));
+
+        assert_eq!(cache.clear().unwrap().len(), 1);
+        assert!(cache.entries().unwrap().is_empty());
+    }
+}
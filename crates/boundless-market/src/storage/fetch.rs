@@ -14,18 +14,34 @@
 
 //! An implementation of URL fetching that supports the common URL types seen on Boundless.
 
-use crate::util::is_dev_mode;
+use std::sync::LazyLock;
+
+use crate::{rate_limit_layer::RateLimiter, util::is_dev_mode};
 use anyhow::{bail, ensure};
 use url::Url;
 
+/// HTTP client used by [fetch_url], shared so that repeated fetches reuse pooled connections.
+static DEFAULT_HTTP_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
 /// Fetches the content of a URL.
 /// Supported URL schemes are `http`, `https`, and `file`.
 pub async fn fetch_url(url_str: impl AsRef<str>) -> anyhow::Result<Vec<u8>> {
+    fetch_url_with_client(&DEFAULT_HTTP_CLIENT, url_str).await
+}
+
+/// Fetches the content of a URL, using the given [reqwest::Client] for HTTP requests.
+///
+/// Supported URL schemes are `http`, `https`, and `file`. See
+/// [HttpClientConfig][crate::http_client::HttpClientConfig] for building a tuned client.
+pub async fn fetch_url_with_client(
+    client: &reqwest::Client,
+    url_str: impl AsRef<str>,
+) -> anyhow::Result<Vec<u8>> {
     tracing::debug!("Fetching URL: {}", url_str.as_ref());
     let url = Url::parse(url_str.as_ref())?;
 
     match url.scheme() {
-        "http" | "https" => fetch_http(&url).await,
+        "http" | "https" => fetch_http(client, &url).await,
         "file" => {
             ensure!(is_dev_mode(), "file fetch is only enabled when RISC0_DEV_MODE is enabled");
             fetch_file(&url).await
@@ -34,8 +50,20 @@ pub async fn fetch_url(url_str: impl AsRef<str>) -> anyhow::Result<Vec<u8>> {
     }
 }
 
-async fn fetch_http(url: &Url) -> anyhow::Result<Vec<u8>> {
-    let response = reqwest::get(url.as_str()).await?;
+/// Fetches the content of a URL, waiting for the given [RateLimiter] before any HTTP request.
+pub async fn fetch_url_with_rate_limit(
+    client: &reqwest::Client,
+    limiter: &RateLimiter,
+    url_str: impl AsRef<str>,
+) -> anyhow::Result<Vec<u8>> {
+    if url_str.as_ref().starts_with("http") {
+        limiter.acquire().await;
+    }
+    fetch_url_with_client(client, url_str).await
+}
+
+async fn fetch_http(client: &reqwest::Client, url: &Url) -> anyhow::Result<Vec<u8>> {
+    let response = client.get(url.as_str()).send().await?;
     let status = response.status();
     if !status.is_success() {
         bail!("HTTP request failed with status: {}", status);
@@ -14,7 +14,12 @@
 
 //! Provider implementation for storing programs and inputs locally as temporary files.
 
-use std::{fmt::Debug, path::PathBuf, result::Result::Ok, sync::Arc};
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+    result::Result::Ok,
+    sync::Arc,
+};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -79,6 +84,24 @@ impl TempFileStorageProvider {
             .map_err(|()| anyhow!("failed to convert file path to URL: {:?}", file_path))?;
         Ok(file_url)
     }
+
+    /// Copy the input in the file at the given path, without reading it into memory.
+    ///
+    /// `progress` is called with the size of the file once it is copied.
+    pub async fn upload_input_file(
+        &self,
+        path: &Path,
+        progress: &(dyn Fn(u64) + Send + Sync),
+    ) -> Result<Url, TempFileStorageProviderError> {
+        let digest = super::sha256_file(path).await?;
+        let file_path = self.temp_dir.path().join(format!("{}.input", hex::encode(digest)));
+        let len = tokio::fs::copy(path, &file_path).await?;
+        progress(len);
+
+        let file_url = Url::from_file_path(&file_path)
+            .map_err(|()| anyhow!("failed to convert file path to URL: {:?}", file_path))?;
+        Ok(file_url)
+    }
 }
 
 #[async_trait]
@@ -117,4 +140,17 @@ mod tests {
         println!("Program URL: {program_url}");
         println!("Input URL: {input_url}");
     }
+
+    #[tokio::test]
+    async fn test_upload_input_file() {
+        let provider = TempFileStorageProvider::new().unwrap();
+        let input_data = b"test input data";
+        let input_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(input_file.path(), input_data).unwrap();
+
+        // The file is stored under the same name as the same input uploaded from memory.
+        let file_url = provider.upload_input_file(input_file.path(), &|_| {}).await.unwrap();
+        assert_eq!(file_url, provider.upload_input(input_data).await.unwrap());
+        assert_eq!(std::fs::read(file_url.to_file_path().unwrap()).unwrap(), input_data);
+    }
 }
@@ -15,21 +15,33 @@
 //! Provider implementations for uploading image and input files such that they are publicly
 //! accessible to provers.
 
-use std::{fmt::Debug, ops::Deref, path::PathBuf, result::Result::Ok, sync::Arc};
+use std::{
+    fmt::Debug,
+    ops::Deref,
+    path::{Path, PathBuf},
+    result::Result::Ok,
+    sync::Arc,
+};
 
 use crate::util::is_dev_mode;
 use async_trait::async_trait;
 use clap::{builder::ArgPredicate, Args, ValueEnum};
 use derive_builder::Builder;
 use reqwest::Url;
+use sha2::{Digest as _, Sha256};
+use tokio::io::AsyncReadExt;
 
+mod cache;
 mod fetch;
 mod file;
 mod mock;
 mod pinata;
 mod s3;
 
-pub use fetch::fetch_url;
+pub use cache::{
+    ProgramCache, ProgramCacheEntry, DEFAULT_PROGRAM_CACHE_SIZE, PROGRAM_CACHE_DIR_ENV,
+};
+pub use fetch::{fetch_url, fetch_url_with_client, fetch_url_with_rate_limit};
 pub use file::{TempFileStorageProvider, TempFileStorageProviderError};
 pub use mock::{MockStorageError, MockStorageProvider};
 pub use pinata::{PinataStorageProvider, PinataStorageProviderError};
@@ -112,6 +124,9 @@ pub enum StandardStorageProviderError {
     #[cfg(feature = "test-utils")]
     #[error("mock storage provider error")]
     Mock(#[from] MockStorageError),
+    /// Error type for IO errors while reading an input file.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
     /// Error type for an invalid storage provider.
     #[error("Invalid storage provider: {0}")]
     InvalidProvider(String),
@@ -253,6 +268,21 @@ impl StorageProvider for StandardStorageProvider {
     }
 }
 
+/// Compute the SHA-256 digest of the file at the given path, reading it in chunks.
+pub(crate) async fn sha256_file(path: &Path) -> std::io::Result<[u8; 32]> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().into())
+}
+
 /// Creates a storage provider based on the environment variables.
 ///
 /// If the environment variable `RISC0_DEV_MODE` is set, a temporary file storage provider is used.
@@ -303,6 +333,29 @@ pub fn storage_provider_from_config(
 }
 
 impl StandardStorageProvider {
+    /// Upload the input for use in a proof request from the file at the given path.
+    ///
+    /// S3 and the temporary file provider stream the file, uploading it to S3 in parts that are
+    /// retried on failure, so that the file is never read into memory. Other providers read the
+    /// file and call [StorageProvider::upload_input]. `progress` is called with the number of
+    /// bytes uploaded so far.
+    pub async fn upload_input_file(
+        &self,
+        path: &Path,
+        progress: &(dyn Fn(u64) + Send + Sync),
+    ) -> Result<Url, StandardStorageProviderError> {
+        Ok(match self {
+            Self::S3(provider) => provider.upload_input_file(path, progress).await?,
+            Self::File(provider) => provider.upload_input_file(path, progress).await?,
+            _ => {
+                let input = tokio::fs::read(path).await?;
+                let url = self.upload_input(&input).await?;
+                progress(input.len() as u64);
+                url
+            }
+        })
+    }
+
     /// Creates a storage provider based on the environment variables.
     ///
     /// See [storage_provider_from_env()].
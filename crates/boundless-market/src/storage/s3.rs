@@ -14,23 +14,30 @@
 
 //! Provider implementation for uploading programs and inputs to AWS S3.
 
-use std::{env::VarError, fmt::Debug, result::Result::Ok, time::Duration};
+use std::{env::VarError, fmt::Debug, path::Path, result::Result::Ok, time::Duration};
 
 use async_trait::async_trait;
 use aws_sdk_s3::{
     config::{Builder, Credentials, Region},
     presigning::{PresigningConfig, PresigningConfigError},
     primitives::ByteStream,
-    types::CreateBucketConfiguration,
+    types::{CompletedMultipartUpload, CompletedPart, CreateBucketConfiguration},
     Error as S3Error,
 };
 use reqwest::Url;
 use sha2::{Digest as _, Sha256};
-use tokio::sync::OnceCell;
+use tokio::{fs::File, io::AsyncReadExt, sync::OnceCell};
 use url::ParseError;
 
 use super::{StorageProvider, StorageProviderConfig};
 
+/// Size of the parts of a multipart upload. S3 requires every part but the last to be at least
+/// 5 MiB. Files no larger than one part are uploaded in a single request.
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Number of attempts made to upload each part of a multipart upload.
+const PART_ATTEMPTS: u32 = 3;
+
 #[derive(Clone, Debug)]
 /// Storage provider that uploads programs and inputs to S3.
 pub struct S3StorageProvider {
@@ -50,6 +57,10 @@ pub enum S3StorageProviderError {
     #[error("AWS S3 error: {0}")]
     S3Error(#[from] Box<S3Error>),
 
+    /// Error type for IO errors while reading an input file.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// Error type for S3 presigning errors.
     #[error("S3 presigning error: {0}")]
     PresigningConfigError(#[from] PresigningConfigError),
@@ -147,6 +158,130 @@ impl S3StorageProvider {
         Ok(Self::from_parts(access_key, secret_key, bucket, url, region, presigned))
     }
 
+    /// Upload the input in the file at the given path, without reading it into memory.
+    ///
+    /// Files larger than one part are uploaded with a multipart upload, retrying each part that
+    /// fails. `progress` is called with the number of bytes uploaded after each part.
+    pub async fn upload_input_file(
+        &self,
+        path: &Path,
+        progress: &(dyn Fn(u64) + Send + Sync),
+    ) -> Result<Url, S3StorageProviderError> {
+        let digest = super::sha256_file(path).await?;
+        let key = format!("input/{}", hex::encode(digest));
+
+        let mut file = File::open(path).await?;
+        let len = file.metadata().await?.len();
+        if len <= PART_SIZE as u64 {
+            let mut input = Vec::with_capacity(len as usize);
+            file.read_to_end(&mut input).await?;
+            let url = self.upload(input, &key).await?;
+            progress(len);
+            return Ok(url);
+        }
+
+        self.ensure_bucket_init().await?;
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.s3_bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|e| Box::new(S3Error::from(e.into_service_error())))?;
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| anyhow::anyhow!("S3 did not return an upload ID for {key}"))?;
+
+        let parts = match self.upload_parts(&mut file, &key, upload_id, progress).await {
+            Ok(parts) => parts,
+            Err(err) => {
+                // Abort the upload so that S3 does not keep the uploaded parts around.
+                if let Err(abort_err) = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(&self.s3_bucket)
+                    .key(&key)
+                    .upload_id(upload_id)
+                    .send()
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to abort multipart upload of {key}: {}",
+                        S3Error::from(abort_err.into_service_error())
+                    );
+                }
+                return Err(err);
+            }
+        };
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.s3_bucket)
+            .key(&key)
+            .upload_id(upload_id)
+            .multipart_upload(CompletedMultipartUpload::builder().set_parts(Some(parts)).build())
+            .send()
+            .await
+            .map_err(|e| Box::new(S3Error::from(e.into_service_error())))?;
+
+        self.object_url(&key).await
+    }
+
+    async fn upload_parts(
+        &self,
+        file: &mut File,
+        key: &str,
+        upload_id: &str,
+        progress: &(dyn Fn(u64) + Send + Sync),
+    ) -> Result<Vec<CompletedPart>, S3StorageProviderError> {
+        let mut parts = Vec::new();
+        let mut uploaded = 0u64;
+        let mut buf = vec![0u8; PART_SIZE];
+        for part_number in 1.. {
+            let n = read_part(file, &mut buf).await?;
+            if n == 0 {
+                break;
+            }
+
+            let mut attempt = 1;
+            let output = loop {
+                let res = self
+                    .client
+                    .upload_part()
+                    .bucket(&self.s3_bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .part_number(part_number)
+                    .body(ByteStream::from(buf[..n].to_vec()))
+                    .send()
+                    .await
+                    .map_err(|e| S3Error::from(e.into_service_error()));
+                match res {
+                    Ok(output) => break output,
+                    Err(err) if attempt < PART_ATTEMPTS => {
+                        tracing::warn!(
+                            "Failed to upload part {part_number} of {key} (attempt {attempt}/{PART_ATTEMPTS}): {err}"
+                        );
+                        tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+                        attempt += 1;
+                    }
+                    Err(err) => return Err(Box::new(err).into()),
+                }
+            };
+
+            parts.push(
+                CompletedPart::builder()
+                    .set_e_tag(output.e_tag().map(str::to_string))
+                    .part_number(part_number)
+                    .build(),
+            );
+            uploaded += n as u64;
+            progress(uploaded);
+        }
+        Ok(parts)
+    }
+
     async fn upload(
         &self,
         data: impl AsRef<[u8]>,
@@ -165,6 +300,10 @@ impl S3StorageProvider {
             .await
             .map_err(|e| Box::new(S3Error::from(e.into_service_error())))?;
 
+        self.object_url(key).await
+    }
+
+    async fn object_url(&self, key: &str) -> Result<Url, S3StorageProviderError> {
         if !self.presigned {
             return Ok(Url::parse(&format!("s3://{}/{}", self.s3_bucket, key)).unwrap());
         }
@@ -211,6 +350,19 @@ impl S3StorageProvider {
     }
 }
 
+/// Read from the file until the buffer is full or the file ends, returning the bytes read.
+async fn read_part(file: &mut File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = file.read(&mut buf[filled..]).await?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
 #[async_trait]
 impl StorageProvider for S3StorageProvider {
     type Error = S3StorageProviderError;
@@ -0,0 +1,531 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A durable queue of signed requests, submitted as connectivity allows.
+//!
+//! Requestors running with intermittent network access can [enqueue](SubmitQueue::enqueue)
+//! signed [Order]s while offline. The queue persists them to a JSON file, and
+//! [SubmitQueue::process] pushes them to the order stream or the chain once the network is
+//! reachable. Submissions that fail because the network is unreachable are retried with
+//! exponential backoff until the request expires; orders rejected by the order stream or the
+//! chain are marked [failed](SubmissionStatus::Failed).
+//!
+//! ```rust,no_run
+//! # use boundless_market::Client;
+//! # use boundless_market::order_stream_client::Order;
+//! use boundless_market::submit_queue::{SubmitQueue, SubmitTarget};
+//! # async fn example(client: Client, order: Order) -> anyhow::Result<()> {
+//!
+//! let mut queue = SubmitQueue::open("submit-queue.json")?;
+//! let request_id = queue.enqueue(order, SubmitTarget::Auto)?;
+//!
+//! // Later, e.g. on a timer or when connectivity is restored.
+//! queue.process(&client).await?;
+//! println!("{:?}", queue.get(request_id).map(|entry| &entry.status));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use alloy::{
+    contract::Error as ContractErr, network::Ethereum, primitives::U256, providers::Provider,
+    transports::TransportError,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::{Client, SubmissionPath},
+    contracts::{boundless_market::MarketError, RequestError, TxnErr},
+    order_stream_client::Order,
+    util::now_timestamp,
+};
+
+/// Default delay before the first retry of a failed submission.
+pub const DEFAULT_RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+/// Default maximum delay between retries of a failed submission.
+pub const DEFAULT_RETRY_MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// Errors returned by the [SubmitQueue].
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum SubmitQueueError {
+    /// The queue file could not be read or written.
+    #[error("submit queue IO error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The queue file could not be decoded.
+    #[error("failed to decode submit queue: {0}")]
+    Decode(#[from] serde_json::Error),
+    /// The enqueued request is invalid.
+    #[error("invalid request: {0}")]
+    InvalidRequest(#[from] RequestError),
+    /// A different order with the same request ID is already queued.
+    #[error("a different order for request 0x{0:x} is already queued")]
+    Conflict(U256),
+}
+
+/// Where a queued order is submitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SubmitTarget {
+    /// Submit to the order stream only.
+    Offchain,
+    /// Submit in an onchain transaction only.
+    Onchain,
+    /// Submit to the order stream, falling back to an onchain transaction if the order stream is
+    /// not configured or rejects the order. See [Client::submit_request_auto].
+    Auto,
+}
+
+/// Submission status of a queued order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SubmissionStatus {
+    /// The order has not been submitted yet, or its last attempt failed and will be retried.
+    Pending,
+    /// The order was submitted by the given path.
+    Submitted(SubmissionPath),
+    /// The request expired before it could be submitted.
+    Expired,
+    /// The order was rejected with an error that retrying will not resolve.
+    Failed(String),
+}
+
+impl SubmissionStatus {
+    /// Whether the queue is done with the order, successfully or not.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, Self::Pending)
+    }
+}
+
+/// An order held by the [SubmitQueue], with its submission status.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct QueuedOrder {
+    /// The signed order.
+    pub order: Order,
+    /// Where the order is submitted.
+    pub target: SubmitTarget,
+    /// Submission status of the order.
+    pub status: SubmissionStatus,
+    /// Number of submission attempts made so far.
+    pub attempts: u32,
+    /// Error of the last failed attempt, if any.
+    pub last_error: Option<String>,
+    /// Time, in seconds since the UNIX epoch, the order was enqueued.
+    pub enqueued_at: u64,
+    /// Time, in seconds since the UNIX epoch, before which the order is not attempted again.
+    pub next_attempt_at: u64,
+}
+
+/// Counts of the outcomes of a call to [SubmitQueue::process].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProcessReport {
+    /// Orders submitted in this call.
+    pub submitted: usize,
+    /// Orders whose submission failed and will be retried.
+    pub retrying: usize,
+    /// Orders that failed permanently in this call.
+    pub failed: usize,
+    /// Orders that expired before they could be submitted.
+    pub expired: usize,
+    /// Orders still pending after this call, including those waiting for their backoff.
+    pub pending: usize,
+}
+
+/// A durable queue of signed orders, persisted to a JSON file.
+///
+/// Every change to the queue is written to disk before the method making it returns, so orders
+/// survive restarts of the process. The file is replaced atomically, so a crash while writing
+/// leaves the previous state intact.
+#[derive(Debug)]
+pub struct SubmitQueue {
+    path: PathBuf,
+    orders: BTreeMap<U256, QueuedOrder>,
+    retry_base_delay: Duration,
+    retry_max_delay: Duration,
+}
+
+impl SubmitQueue {
+    /// Open the queue persisted at `path`, creating an empty queue if the file does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SubmitQueueError> {
+        let path = path.as_ref().to_path_buf();
+        let orders = match std::fs::read(&path) {
+            Ok(contents) => serde_json::from_slice::<Vec<QueuedOrder>>(&contents)?
+                .into_iter()
+                .map(|entry| (entry.order.request.id, entry))
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self {
+            path,
+            orders,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            retry_max_delay: DEFAULT_RETRY_MAX_DELAY,
+        })
+    }
+
+    /// Set the delay before the first retry of a failed submission, and the maximum delay the
+    /// exponential backoff grows to.
+    pub fn with_retry_delay(self, base: Duration, max: Duration) -> Self {
+        Self { retry_base_delay: base, retry_max_delay: max, ..self }
+    }
+
+    /// Path of the file the queue is persisted to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Add a signed order to the queue, returning its request ID.
+    ///
+    /// Enqueuing the same order again is a no-op. Enqueuing a different order with the ID of a
+    /// queued order returns [SubmitQueueError::Conflict].
+    pub fn enqueue(
+        &mut self,
+        order: Order,
+        target: SubmitTarget,
+    ) -> Result<U256, SubmitQueueError> {
+        order.request.validate()?;
+        let request_id = order.request.id;
+        if let Some(existing) = self.orders.get(&request_id) {
+            if existing.order.request_digest != order.request_digest {
+                return Err(SubmitQueueError::Conflict(request_id));
+            }
+            return Ok(request_id);
+        }
+
+        let now = now_timestamp();
+        self.orders.insert(
+            request_id,
+            QueuedOrder {
+                order,
+                target,
+                status: SubmissionStatus::Pending,
+                attempts: 0,
+                last_error: None,
+                enqueued_at: now,
+                next_attempt_at: now,
+            },
+        );
+        self.persist()?;
+        Ok(request_id)
+    }
+
+    /// Get the queued order with the given request ID.
+    pub fn get(&self, request_id: U256) -> Option<&QueuedOrder> {
+        self.orders.get(&request_id)
+    }
+
+    /// Iterate over all queued orders, ordered by request ID.
+    pub fn iter(&self) -> impl Iterator<Item = &QueuedOrder> {
+        self.orders.values()
+    }
+
+    /// Number of orders that are still pending submission.
+    pub fn pending(&self) -> usize {
+        self.orders.values().filter(|entry| !entry.status.is_final()).count()
+    }
+
+    /// Remove the order with the given request ID from the queue.
+    pub fn remove(&mut self, request_id: U256) -> Result<Option<QueuedOrder>, SubmitQueueError> {
+        let removed = self.orders.remove(&request_id);
+        if removed.is_some() {
+            self.persist()?;
+        }
+        Ok(removed)
+    }
+
+    /// Remove all orders with a final status, returning how many were removed.
+    pub fn prune(&mut self) -> Result<usize, SubmitQueueError> {
+        let before = self.orders.len();
+        self.orders.retain(|_, entry| !entry.status.is_final());
+        let removed = before - self.orders.len();
+        if removed > 0 {
+            self.persist()?;
+        }
+        Ok(removed)
+    }
+
+    /// Attempt to submit every pending order whose backoff has elapsed.
+    ///
+    /// Failures to submit an order are recorded on the order rather than returned. Only errors
+    /// persisting the queue are returned.
+    pub async fn process<P, St, R, Si>(
+        &mut self,
+        client: &Client<P, St, R, Si>,
+    ) -> Result<ProcessReport, SubmitQueueError>
+    where
+        P: Provider<Ethereum> + 'static + Clone,
+    {
+        let now = now_timestamp();
+        let mut report = ProcessReport::default();
+        let mut changed = false;
+
+        for (request_id, entry) in self.orders.iter_mut() {
+            if entry.status.is_final() {
+                continue;
+            }
+            if entry.order.request.expires_at() <= now {
+                tracing::warn!("Queued request 0x{request_id:x} expired before submission");
+                entry.status = SubmissionStatus::Expired;
+                report.expired += 1;
+                changed = true;
+                continue;
+            }
+            if entry.next_attempt_at > now {
+                report.pending += 1;
+                continue;
+            }
+
+            entry.attempts += 1;
+            changed = true;
+            match submit(client, &entry.order, entry.target).await {
+                Ok(path) => {
+                    tracing::info!("Submitted queued request 0x{request_id:x} {path:?}");
+                    entry.status = SubmissionStatus::Submitted(path);
+                    entry.last_error = None;
+                    report.submitted += 1;
+                }
+                Err(SubmitError::Permanent(err)) => {
+                    tracing::error!("Queued request 0x{request_id:x} was rejected: {err}");
+                    entry.status = SubmissionStatus::Failed(err.clone());
+                    entry.last_error = Some(err);
+                    report.failed += 1;
+                }
+                Err(SubmitError::Retryable(err)) => {
+                    let delay =
+                        retry_delay(self.retry_base_delay, self.retry_max_delay, entry.attempts);
+                    tracing::warn!(
+                        "Failed to submit queued request 0x{request_id:x} (attempt {}), retrying in {delay:?}: {err}",
+                        entry.attempts
+                    );
+                    entry.next_attempt_at = now + delay.as_secs();
+                    entry.last_error = Some(err);
+                    report.retrying += 1;
+                    report.pending += 1;
+                }
+            }
+        }
+
+        if changed {
+            self.persist()?;
+        }
+        Ok(report)
+    }
+
+    /// Process the queue every `interval` until no orders are pending.
+    pub async fn run<P, St, R, Si>(
+        &mut self,
+        client: &Client<P, St, R, Si>,
+        interval: Duration,
+    ) -> Result<(), SubmitQueueError>
+    where
+        P: Provider<Ethereum> + 'static + Clone,
+    {
+        loop {
+            let report = self.process(client).await?;
+            if report.pending == 0 {
+                return Ok(());
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    fn persist(&self) -> Result<(), SubmitQueueError> {
+        let orders: Vec<&QueuedOrder> = self.orders.values().collect();
+        let contents = serde_json::to_vec_pretty(&orders)?;
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, contents)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+enum SubmitError {
+    Retryable(String),
+    Permanent(String),
+}
+
+async fn submit<P, St, R, Si>(
+    client: &Client<P, St, R, Si>,
+    order: &Order,
+    target: SubmitTarget,
+) -> Result<SubmissionPath, SubmitError>
+where
+    P: Provider<Ethereum> + 'static + Clone,
+{
+    let offchain = match (target, client.offchain_client.as_ref()) {
+        (SubmitTarget::Onchain, _) | (SubmitTarget::Auto, None) => None,
+        (SubmitTarget::Offchain, None) => {
+            return Err(SubmitError::Permanent(
+                "order stream client not available; provide an order stream URL".to_string(),
+            ))
+        }
+        (_, Some(offchain_client)) => Some(offchain_client),
+    };
+
+    if let Some(offchain_client) = offchain {
+        match offchain_client.submit_order(order).await {
+            Ok(_) => return Ok(SubmissionPath::Offchain),
+            Err(err) if err.is_retryable() => {
+                return Err(SubmitError::Retryable(err.to_string()));
+            }
+            Err(err) if target == SubmitTarget::Offchain => {
+                return Err(SubmitError::Permanent(err.to_string()));
+            }
+            Err(err) => tracing::warn!(
+                "Order stream rejected request 0x{:x}, submitting onchain: {err}",
+                order.request.id
+            ),
+        }
+    }
+
+    client
+        .boundless_market
+        .submit_request_with_signature(&order.request, order.signature.as_bytes().to_vec())
+        .await
+        .map(|_| SubmissionPath::Onchain)
+        .map_err(|err| match is_retryable(&err) {
+            true => SubmitError::Retryable(err.to_string()),
+            false => SubmitError::Permanent(err.to_string()),
+        })
+}
+
+/// Whether a failed onchain submission may succeed if retried later.
+///
+/// Failures to reach the RPC node, rate limiting, and timeouts waiting for the transaction to
+/// confirm are retried until the request expires. Errors returned by the node when broadcasting,
+/// e.g. a revert found while estimating gas, and transactions that reverted onchain, are not.
+fn is_retryable(err: &MarketError) -> bool {
+    match err {
+        MarketError::TxnConfirmationError(_) => true,
+        MarketError::Error(err) => match err.downcast_ref::<TxnErr>() {
+            Some(TxnErr::ContractErr(ContractErr::TransportError(err))) => match err {
+                TransportError::ErrorResp(resp) => resp.is_retry_err(),
+                TransportError::Transport(_) | TransportError::NullResp => true,
+                _ => false,
+            },
+            // Error responses without revert data, including rate limiting by the node.
+            Some(TxnErr::MissingData(code, _)) => matches!(code, 429 | -32005),
+            Some(_) => false,
+            // Errors not raised by the contract call, e.g. failing to fetch the receipt of a
+            // confirmed transaction.
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+/// Delay before the given attempt is retried, doubling from `base` up to `max`.
+fn retry_delay(base: Duration, max: Duration, attempts: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempts.saturating_sub(1))).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, Bytes},
+        signers::local::PrivateKeySigner,
+        sol_types::SolStruct,
+    };
+    use risc0_zkvm::sha::Digest;
+
+    use super::*;
+    use crate::{
+        contracts::{eip712_domain, Offer, Predicate, RequestId, RequestInput, Requirements},
+        ProofRequest,
+    };
+
+    async fn signed_order(index: u32) -> Order {
+        let signer = PrivateKeySigner::random();
+        let market_address = Address::ZERO;
+        let request = ProofRequest {
+            id: RequestId::u256(signer.address(), index),
+            requirements: Requirements::new(Predicate::prefix_match(
+                Digest::ZERO,
+                Bytes::default(),
+            )),
+            imageUrl: "https://dev.null".to_string(),
+            input: RequestInput::builder().build_inline().unwrap(),
+            offer: Offer {
+                minPrice: U256::from(0),
+                maxPrice: U256::from(1),
+                rampUpStart: now_timestamp(),
+                timeout: 1000,
+                rampUpPeriod: 1,
+                lockTimeout: 1000,
+                lockCollateral: U256::from(0),
+            },
+        };
+        let signature = request.sign_request(&signer, market_address, 1).await.unwrap();
+        let request_digest =
+            request.eip712_signing_hash(&eip712_domain(market_address, 1).alloy_struct());
+        Order::new(request, request_digest, signature)
+    }
+
+    #[tokio::test]
+    async fn test_queue_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.json");
+
+        let mut queue = SubmitQueue::open(&path).unwrap();
+        let order = signed_order(1).await;
+        let request_id = queue.enqueue(order.clone(), SubmitTarget::Auto).unwrap();
+        // Enqueuing the same order again is a no-op.
+        assert_eq!(queue.enqueue(order.clone(), SubmitTarget::Auto).unwrap(), request_id);
+        assert_eq!(queue.pending(), 1);
+
+        let queue = SubmitQueue::open(&path).unwrap();
+        let entry = queue.get(request_id).unwrap();
+        assert_eq!(entry.order, order);
+        assert_eq!(entry.target, SubmitTarget::Auto);
+        assert_eq!(entry.status, SubmissionStatus::Pending);
+        assert_eq!(entry.attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut queue = SubmitQueue::open(dir.path().join("queue.json")).unwrap();
+
+        let order = signed_order(1).await;
+        queue.enqueue(order.clone(), SubmitTarget::Onchain).unwrap();
+
+        let mut other = order.clone();
+        other.request.offer.maxPrice = U256::from(2);
+        other.request_digest =
+            other.request.eip712_signing_hash(&eip712_domain(Address::ZERO, 1).alloy_struct());
+        assert!(matches!(
+            queue.enqueue(other, SubmitTarget::Onchain),
+            Err(SubmitQueueError::Conflict(id)) if id == order.request.id
+        ));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let base = Duration::from_secs(5);
+        let max = Duration::from_secs(60);
+        assert_eq!(retry_delay(base, max, 1), Duration::from_secs(5));
+        assert_eq!(retry_delay(base, max, 2), Duration::from_secs(10));
+        assert_eq!(retry_delay(base, max, 4), Duration::from_secs(40));
+        assert_eq!(retry_delay(base, max, 5), max);
+        assert_eq!(retry_delay(base, max, 100), max);
+    }
+}
@@ -0,0 +1,447 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Pluggable telemetry for prover economics.
+//!
+//! A [FulfillmentRecord] captures the realized price, cycles, proving time, and gas cost of one
+//! fulfilled request. Records are handed to a [Telemetry] hub, which forwards them to every
+//! registered [EconomicsSink]. Sinks are provided for CSV files ([CsvSink]), Prometheus
+//! ([PrometheusSink]) and, with the `postgres` feature, Postgres (`PostgresSink`).
+//!
+//! ```no_run
+//! # async fn example(
+//! #     market: boundless_market::contracts::boundless_market::BoundlessMarketService<
+//! #         impl alloy::providers::Provider,
+//! #     >,
+//! #     tx: boundless_market::contracts::boundless_market::FulfillmentTx,
+//! # ) -> anyhow::Result<()> {
+//! use alloy::primitives::U256;
+//! use boundless_market::telemetry::{CsvSink, FulfillmentRecord, Telemetry};
+//!
+//! let telemetry = Telemetry::default().with_sink(CsvSink::new("fulfillments.csv"));
+//! let request_id = tx.fulfillments[0].id;
+//! let receipt = market.fulfill_with_receipt(tx).await?;
+//! telemetry
+//!     .record_fulfillment(&receipt, vec![FulfillmentRecord::new(request_id, U256::from(1000))])
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    fmt::Write as _,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    primitives::{I256, U256},
+    rpc::types::TransactionReceipt,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+/// Economics of a single fulfilled request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct FulfillmentRecord {
+    /// ID of the fulfilled request.
+    pub request_id: U256,
+    /// Unix timestamp, in seconds, at which the fulfillment was recorded.
+    pub fulfilled_at: u64,
+    /// Price paid to the prover for the request, in wei.
+    pub price: U256,
+    /// Number of cycles used to prove the request, if known.
+    pub cycles: Option<u64>,
+    /// Wall-clock time spent proving the request, if known.
+    pub proving_time: Option<Duration>,
+    /// Gas used by the fulfillment, attributed to this request.
+    pub gas_used: u64,
+    /// Cost of the gas used, in wei.
+    pub gas_cost: U256,
+}
+
+impl FulfillmentRecord {
+    /// Creates a record of a request fulfilled for the given price, in wei.
+    pub fn new(request_id: U256, price: U256) -> Self {
+        Self {
+            request_id,
+            fulfilled_at: now(),
+            price,
+            cycles: None,
+            proving_time: None,
+            gas_used: 0,
+            gas_cost: U256::ZERO,
+        }
+    }
+
+    /// Sets the number of cycles used to prove the request.
+    pub fn with_cycles(self, cycles: u64) -> Self {
+        Self { cycles: Some(cycles), ..self }
+    }
+
+    /// Sets the time spent proving the request.
+    pub fn with_proving_time(self, proving_time: Duration) -> Self {
+        Self { proving_time: Some(proving_time), ..self }
+    }
+
+    /// Sets the gas used by the fulfillment and its cost, in wei.
+    pub fn with_gas(self, gas_used: u64, gas_cost: U256) -> Self {
+        Self { gas_used, gas_cost, ..self }
+    }
+
+    /// Sets the time at which the request was fulfilled.
+    pub fn with_fulfilled_at(self, fulfilled_at: u64) -> Self {
+        Self { fulfilled_at, ..self }
+    }
+
+    /// Price minus the gas cost, in wei. Negative if the fulfillment was made at a loss.
+    pub fn profit(&self) -> I256 {
+        I256::from_raw(self.price).wrapping_sub(I256::from_raw(self.gas_cost))
+    }
+}
+
+/// A destination for [FulfillmentRecord]s.
+#[async_trait]
+pub trait EconomicsSink: Send + Sync {
+    /// Records a fulfilled request.
+    async fn record(&self, record: &FulfillmentRecord) -> Result<()>;
+}
+
+/// Hub forwarding [FulfillmentRecord]s to a set of [EconomicsSink]s.
+///
+/// Failures of a sink are logged and do not affect the other sinks, so that telemetry can never
+/// disrupt fulfillment.
+#[derive(Clone, Default)]
+pub struct Telemetry {
+    sinks: Vec<Arc<dyn EconomicsSink>>,
+}
+
+impl Telemetry {
+    /// Adds a sink to which records are forwarded.
+    pub fn with_sink(mut self, sink: impl EconomicsSink + 'static) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Returns `true` if no sink is registered.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Forwards the given records to every sink.
+    pub async fn record(&self, records: &[FulfillmentRecord]) {
+        for record in records {
+            for sink in &self.sinks {
+                if let Err(err) = sink.record(record).await {
+                    tracing::warn!(
+                        "Failed to record economics of request 0x{:x}: {err:?}",
+                        record.request_id
+                    );
+                }
+            }
+        }
+    }
+
+    /// Records the requests fulfilled by the transaction with the given receipt.
+    ///
+    /// The gas used by the transaction is split evenly across the records, with any remainder
+    /// attributed to the first one.
+    pub async fn record_fulfillment(
+        &self,
+        receipt: &TransactionReceipt,
+        records: Vec<FulfillmentRecord>,
+    ) {
+        let records = split_gas(receipt.gas_used, receipt.effective_gas_price, records);
+        self.record(&records).await;
+    }
+}
+
+/// Split `gas_used` at `gas_price` evenly across `records`.
+fn split_gas(
+    gas_used: u64,
+    gas_price: u128,
+    records: Vec<FulfillmentRecord>,
+) -> Vec<FulfillmentRecord> {
+    let count = records.len() as u64;
+    if count == 0 {
+        return records;
+    }
+    let share = gas_used / count;
+    let remainder = gas_used % count;
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            let gas = if i == 0 { share + remainder } else { share };
+            record.with_gas(gas, U256::from(gas) * U256::from(gas_price))
+        })
+        .collect()
+}
+
+const CSV_HEADER: &str =
+    "request_id,fulfilled_at,price_wei,cycles,proving_time_ms,gas_used,gas_cost_wei,profit_wei";
+
+/// Sink appending records to a CSV file, writing a header if the file is new or empty.
+pub struct CsvSink {
+    path: PathBuf,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl CsvSink {
+    /// Creates a sink appending to the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), lock: tokio::sync::Mutex::new(()) }
+    }
+}
+
+fn csv_line(record: &FulfillmentRecord) -> String {
+    format!(
+        "0x{:x},{},{},{},{},{},{},{}\n",
+        record.request_id,
+        record.fulfilled_at,
+        record.price,
+        record.cycles.map(|cycles| cycles.to_string()).unwrap_or_default(),
+        record.proving_time.map(|time| time.as_millis().to_string()).unwrap_or_default(),
+        record.gas_used,
+        record.gas_cost,
+        record.profit()
+    )
+}
+
+#[async_trait]
+impl EconomicsSink for CsvSink {
+    async fn record(&self, record: &FulfillmentRecord) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .with_context(|| format!("failed to open {}", self.path.display()))?;
+        let mut contents = String::new();
+        if file.metadata().await?.len() == 0 {
+            contents.push_str(CSV_HEADER);
+            contents.push('\n');
+        }
+        contents.push_str(&csv_line(record));
+        file.write_all(contents.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct PrometheusTotals {
+    fulfilled: u64,
+    revenue: U256,
+    gas_used: u64,
+    gas_cost: U256,
+    cycles: u64,
+    proving_time: Duration,
+}
+
+/// Sink accumulating totals exposed in the Prometheus text format.
+///
+/// The sink does not serve the metrics itself; [PrometheusSink::render] returns the exposition
+/// text, to be served by the application's metrics endpoint. Clones share the same totals.
+#[derive(Clone, Default)]
+pub struct PrometheusSink {
+    totals: Arc<Mutex<PrometheusTotals>>,
+}
+
+impl PrometheusSink {
+    /// Renders the accumulated totals in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let totals = self.totals.lock().unwrap();
+        let metrics = [
+            (
+                "boundless_prover_fulfilled_requests_total",
+                "Number of requests fulfilled.",
+                totals.fulfilled.to_string(),
+            ),
+            (
+                "boundless_prover_revenue_wei_total",
+                "Total price paid for fulfilled requests, in wei.",
+                totals.revenue.to_string(),
+            ),
+            (
+                "boundless_prover_gas_used_total",
+                "Total gas used by fulfillments.",
+                totals.gas_used.to_string(),
+            ),
+            (
+                "boundless_prover_gas_cost_wei_total",
+                "Total cost of the gas used by fulfillments, in wei.",
+                totals.gas_cost.to_string(),
+            ),
+            (
+                "boundless_prover_cycles_total",
+                "Total cycles proven for fulfilled requests.",
+                totals.cycles.to_string(),
+            ),
+            (
+                "boundless_prover_proving_seconds_total",
+                "Total time spent proving fulfilled requests, in seconds.",
+                totals.proving_time.as_secs_f64().to_string(),
+            ),
+        ];
+        let mut out = String::new();
+        for (name, help, value) in metrics {
+            writeln!(out, "# HELP {name} {help}").unwrap();
+            writeln!(out, "# TYPE {name} counter").unwrap();
+            writeln!(out, "{name} {value}").unwrap();
+        }
+        out
+    }
+}
+
+#[async_trait]
+impl EconomicsSink for PrometheusSink {
+    async fn record(&self, record: &FulfillmentRecord) -> Result<()> {
+        let mut totals = self.totals.lock().unwrap();
+        totals.fulfilled += 1;
+        totals.revenue += record.price;
+        totals.gas_used += record.gas_used;
+        totals.gas_cost += record.gas_cost;
+        totals.cycles += record.cycles.unwrap_or_default();
+        totals.proving_time += record.proving_time.unwrap_or_default();
+        Ok(())
+    }
+}
+
+/// Sink inserting records into a `fulfillment_economics` table in Postgres.
+#[cfg(feature = "postgres")]
+pub struct PostgresSink {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresSink {
+    /// Connects to the database at `url`, creating the table if it does not exist.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let pool = sqlx::PgPool::connect(url).await.context("failed to connect to Postgres")?;
+        Self::from_pool(pool).await
+    }
+
+    /// Creates a sink from an existing pool, creating the table if it does not exist.
+    pub async fn from_pool(pool: sqlx::PgPool) -> Result<Self> {
+        // Amounts in wei do not fit in a BIGINT, so they are stored as NUMERIC.
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS fulfillment_economics (
+                request_id TEXT NOT NULL,
+                fulfilled_at BIGINT NOT NULL,
+                price_wei NUMERIC NOT NULL,
+                cycles BIGINT,
+                proving_time_ms BIGINT,
+                gas_used BIGINT NOT NULL,
+                gas_cost_wei NUMERIC NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .context("failed to create fulfillment_economics table")?;
+        Ok(Self { pool })
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl EconomicsSink for PostgresSink {
+    async fn record(&self, record: &FulfillmentRecord) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO fulfillment_economics
+                (request_id, fulfilled_at, price_wei, cycles, proving_time_ms, gas_used, gas_cost_wei)
+                VALUES ($1, $2, $3::NUMERIC, $4, $5, $6, $7::NUMERIC)",
+        )
+        .bind(format!("0x{:x}", record.request_id))
+        .bind(record.fulfilled_at as i64)
+        .bind(record.price.to_string())
+        .bind(record.cycles.map(|cycles| cycles as i64))
+        .bind(record.proving_time.map(|time| time.as_millis() as i64))
+        .bind(record.gas_used as i64)
+        .bind(record.gas_cost.to_string())
+        .execute(&self.pool)
+        .await
+        .context("failed to insert fulfillment record")?;
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_gas_assigns_remainder_to_first() {
+        let records = vec![
+            FulfillmentRecord::new(U256::from(1), U256::from(100)),
+            FulfillmentRecord::new(U256::from(2), U256::from(100)),
+        ];
+        let records = split_gas(101, 2, records);
+        assert_eq!(records[0].gas_used, 51);
+        assert_eq!(records[1].gas_used, 50);
+        assert_eq!(records[0].gas_cost, U256::from(102));
+        assert_eq!(records[1].profit(), I256::ZERO);
+    }
+
+    #[test]
+    fn profit_can_be_negative() {
+        let record =
+            FulfillmentRecord::new(U256::from(1), U256::from(10)).with_gas(21_000, U256::from(25));
+        assert_eq!(record.profit(), I256::try_from(-15i64).unwrap());
+    }
+
+    #[tokio::test]
+    async fn csv_sink_writes_header_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("economics.csv");
+        let telemetry = Telemetry::default().with_sink(CsvSink::new(&path));
+        let record = FulfillmentRecord::new(U256::from(0xa), U256::from(100))
+            .with_fulfilled_at(7)
+            .with_cycles(1 << 20)
+            .with_proving_time(Duration::from_millis(1500))
+            .with_gas(10, U256::from(40));
+        telemetry.record(&[record.clone(), record]).await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "0xa,7,100,1048576,1500,10,40,60");
+    }
+
+    #[tokio::test]
+    async fn prometheus_sink_accumulates_totals() {
+        let sink = PrometheusSink::default();
+        let telemetry = Telemetry::default().with_sink(sink.clone());
+        telemetry
+            .record(&[
+                FulfillmentRecord::new(U256::from(1), U256::from(100)).with_cycles(10),
+                FulfillmentRecord::new(U256::from(2), U256::from(50)).with_cycles(5),
+            ])
+            .await;
+
+        let rendered = sink.render();
+        assert!(rendered.contains("boundless_prover_fulfilled_requests_total 2\n"));
+        assert!(rendered.contains("boundless_prover_revenue_wei_total 150\n"));
+        assert!(rendered.contains("boundless_prover_cycles_total 15\n"));
+    }
+}
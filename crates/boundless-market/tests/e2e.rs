@@ -12,26 +12,42 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{ops::ControlFlow, time::Duration};
+
 use alloy::{
     node_bindings::Anvil,
-    primitives::{aliases::U160, utils::parse_ether, Address, U256},
-    providers::Provider,
+    primitives::{aliases::U160, utils::parse_ether, Address, B256, U256},
+    providers::{ext::AnvilApi, Provider},
+    rpc::types::Log,
+    signers::local::PrivateKeySigner,
     sol_types::eip712_domain,
 };
 use alloy_primitives::Bytes;
 use boundless_market::{
+    budget::{Budget, BudgetError},
+    client::{ClientError, SubmissionPath},
     contracts::{
-        boundless_market::{FulfillmentTx, UnlockedRequest},
+        boundless_market::{
+            ConfirmationPolicy, FulfillmentTx, MarketError, MarketTxType, ReclaimOutcome,
+            RequestSource, UnlockedRequest,
+        },
         hit_points::default_allowance,
-        AssessorReceipt, FulfillmentData, FulfillmentDataType, Offer, Predicate, ProofRequest,
-        RequestId, RequestStatus, Requirements,
+        AssessorReceipt, FulfillmentData, FulfillmentDataType, IBoundlessMarket, Offer, Predicate,
+        ProofRequest, RequestId, RequestStatus, Requirements,
     },
     input::GuestEnv,
+    log_query::{query_events_chunked, ChunkedLogQuery, ScanDirection},
+    order_stream_client::{Order, ORDER_SUBMISSION_PATH},
+    request_builder::RequestParams,
+    storage::StorageProviderConfig,
+    submit_queue::{SubmissionStatus, SubmitQueue, SubmitTarget},
+    Client,
 };
 use boundless_test_utils::{
-    guests::ECHO_ID,
+    guests::{ECHO_ELF, ECHO_ID},
     market::{create_test_ctx, mock_singleton, TestCtx},
 };
+use httpmock::{Method::POST, MockServer};
 use risc0_zkvm::{
     sha::{Digest, Digestible},
     ReceiptClaim,
@@ -77,6 +93,8 @@ async fn test_deposit_withdraw() {
         parse_ether("2").unwrap()
     );
 
+    let deposit_block = ctx.prover_provider.get_block_number().await.unwrap();
+
     // Withdraw prover balances
     ctx.prover_market.withdraw(parse_ether("2").unwrap()).await.unwrap();
     assert_eq!(
@@ -84,6 +102,19 @@ async fn test_deposit_withdraw() {
         U256::ZERO
     );
 
+    // The balance as of the deposit is still available
+    assert_eq!(
+        ctx.prover_market.balance_of_at(ctx.prover_signer.address(), deposit_block).await.unwrap(),
+        parse_ether("2").unwrap()
+    );
+    assert_eq!(
+        ctx.prover_market
+            .balance_of_at(ctx.prover_signer.address(), deposit_block - 1)
+            .await
+            .unwrap(),
+        U256::ZERO
+    );
+
     // Withdraw when balance is zero
     assert!(ctx.prover_market.withdraw(parse_ether("2").unwrap()).await.is_err());
 }
@@ -156,6 +187,391 @@ async fn test_submit_request() {
 
     let (log, _) = logs.first().unwrap();
     assert!(log.requestId == request_id);
+
+    // the request can be found by its digest
+    let domain = ctx.customer_market.eip712_domain().await.unwrap();
+    let digest = request.eip712_signing_hash(&domain.alloy_struct());
+    let located = ctx.customer_market.get_request_by_digest(digest, None, None).await.unwrap();
+    assert_eq!(located.request.id, request_id);
+    assert_eq!(located.source, RequestSource::Submitted);
+    assert_eq!(located.tx_hash, logs.first().unwrap().1.transaction_hash.unwrap());
+    assert!(matches!(
+        ctx.customer_market.get_request_by_digest(B256::ZERO, None, None).await,
+        Err(MarketError::RequestDigestNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_get_submitted_request_by_tx() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+
+    let request = new_request(1, &ctx).await;
+    let request_id =
+        ctx.customer_market.submit_request(&request, &ctx.customer_signer).await.unwrap();
+    let logs = ctx.customer_market.instance().RequestSubmitted_filter().query().await.unwrap();
+    let tx_hash = logs.first().unwrap().1.transaction_hash.unwrap();
+
+    let (submitted, _) = ctx.customer_market.get_submitted_request_by_tx(tx_hash).await.unwrap();
+    assert_eq!(submitted.id, request_id);
+    let (submitted, _) =
+        ctx.customer_market.get_submitted_request(request_id, Some(tx_hash)).await.unwrap();
+    assert_eq!(submitted.id, request_id);
+
+    // A request ID that was not submitted in the transaction is not found.
+    let other_id = RequestId::new(ctx.customer_signer.address(), 2);
+    assert!(matches!(
+        ctx.customer_market.get_submitted_request(other_id, Some(tx_hash)).await,
+        Err(MarketError::RequestNotFound(id)) if id == U256::from(other_id)
+    ));
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_build_requests() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(ctx.deployment.clone())
+        .with_storage_provider_config(&StorageProviderConfig::dev_mode())
+        .unwrap()
+        .with_private_key(ctx.customer_signer.clone())
+        .build()
+        .await
+        .unwrap();
+
+    let mut params: Vec<RequestParams> = (0..4u8)
+        .map(|i| client.new_request().with_program(ECHO_ELF).with_stdin(vec![i; 8]))
+        .collect();
+    // A request without a program cannot be built, but does not affect the others.
+    params.insert(2, client.new_request().with_stdin(b"no program"));
+
+    let results = client.build_requests(params, 2).await;
+    assert_eq!(results.len(), 5);
+    assert!(results[2].is_err());
+
+    let requests: Vec<_> = results.into_iter().filter_map(Result::ok).collect();
+    assert_eq!(requests.len(), 4);
+    // The program is uploaded once and shared by all the requests.
+    assert!(requests.iter().all(|request| request.imageUrl == requests[0].imageUrl));
+    for (i, request) in requests.iter().enumerate() {
+        let env = GuestEnv::decode(&request.input.data).unwrap();
+        assert_eq!(env.stdin, vec![i as u8; 8]);
+    }
+}
+
+#[tokio::test]
+async fn test_client_confirmation_policy() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let lock_policy = ConfirmationPolicy::new(1, Duration::from_secs(5));
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(ctx.deployment.clone())
+        .with_confirmation_policy(MarketTxType::Lock, lock_policy)
+        .with_timeout(Duration::from_secs(90))
+        .with_private_key(ctx.customer_signer.clone())
+        .build()
+        .await
+        .unwrap();
+
+    // The policy survives the signer being set, and the timeout applied once built.
+    assert_eq!(client.boundless_market.confirmation_policy(MarketTxType::Lock), lock_policy);
+    assert_eq!(
+        client.boundless_market.confirmation_policy(MarketTxType::Slash),
+        ConfirmationPolicy::new(1, Duration::from_secs(90))
+    );
+    client.boundless_market.deposit(U256::from(1)).await.unwrap();
+}
+
+async fn queued_order<P: Provider>(request: ProofRequest, ctx: &TestCtx<P>) -> Order {
+    let market_address = ctx.deployment.boundless_market_address;
+    let chain_id = ctx.customer_provider.get_chain_id().await.unwrap();
+    let signature =
+        request.sign_request(&ctx.customer_signer, market_address, chain_id).await.unwrap();
+    let request_digest = request.signing_hash(market_address, chain_id).unwrap();
+    Order::new(request, request_digest, signature)
+}
+
+#[tokio::test]
+async fn test_submit_queue_process() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let mut deployment = ctx.deployment.clone();
+    deployment.order_stream_url = None;
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment.clone())
+        .with_private_key(ctx.customer_signer.clone())
+        .build()
+        .await
+        .unwrap();
+    // A client whose account has no ether, so that broadcasting its transactions fails.
+    let unfunded_client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment)
+        .with_private_key(PrivateKeySigner::random())
+        .build()
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut queue = SubmitQueue::open(dir.path().join("queue.json")).unwrap();
+    let onchain = queue
+        .enqueue(queued_order(new_request(1, &ctx).await, &ctx).await, SubmitTarget::Onchain)
+        .unwrap();
+    let offchain = queue
+        .enqueue(queued_order(new_request(2, &ctx).await, &ctx).await, SubmitTarget::Offchain)
+        .unwrap();
+    let mut expired_request = new_request(3, &ctx).await;
+    expired_request.offer.rampUpStart = now_timestamp() - 200;
+    let expired =
+        queue.enqueue(queued_order(expired_request, &ctx).await, SubmitTarget::Auto).unwrap();
+
+    let report = queue.process(&client).await.unwrap();
+    assert_eq!((report.submitted, report.failed, report.expired, report.pending), (1, 1, 1, 0));
+    assert_eq!(
+        queue.get(onchain).unwrap().status,
+        SubmissionStatus::Submitted(SubmissionPath::Onchain)
+    );
+    assert!(matches!(queue.get(offchain).unwrap().status, SubmissionStatus::Failed(_)));
+    assert_eq!(queue.get(expired).unwrap().status, SubmissionStatus::Expired);
+    let (submitted, _) = ctx.customer_market.get_submitted_request(onchain, None).await.unwrap();
+    assert_eq!(submitted.id, onchain);
+
+    // Errors broadcasting the transaction are not retried.
+    let rejected = queue
+        .enqueue(queued_order(new_request(4, &ctx).await, &ctx).await, SubmitTarget::Onchain)
+        .unwrap();
+    let report = queue.process(&unfunded_client).await.unwrap();
+    assert_eq!((report.failed, report.retrying, report.pending), (1, 0, 0));
+    let entry = queue.get(rejected).unwrap();
+    assert!(matches!(entry.status, SubmissionStatus::Failed(_)));
+    assert_eq!(entry.attempts, 1);
+
+    // Orders with a final status are not attempted again.
+    queue.run(&client, Duration::from_secs(1)).await.unwrap();
+    assert_eq!(queue.get(rejected).unwrap().attempts, 1);
+}
+
+#[tokio::test]
+async fn test_submit_queue_run_retries_until_expired() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let mut deployment = ctx.deployment.clone();
+    deployment.order_stream_url = None;
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment)
+        .with_private_key(ctx.customer_signer.clone())
+        .build()
+        .await
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut queue = SubmitQueue::open(dir.path().join("queue.json"))
+        .unwrap()
+        .with_retry_delay(Duration::from_secs(1), Duration::from_secs(1));
+    let mut request = new_request(1, &ctx).await;
+    request.offer.timeout = 3;
+    request.offer.lockTimeout = 3;
+    let request_id =
+        queue.enqueue(queued_order(request, &ctx).await, SubmitTarget::Onchain).unwrap();
+
+    // With the RPC node unreachable, the submission is retried until the request expires.
+    drop(anvil);
+    tokio::time::timeout(Duration::from_secs(30), queue.run(&client, Duration::from_secs(1)))
+        .await
+        .unwrap()
+        .unwrap();
+    let entry = queue.get(request_id).unwrap();
+    assert_eq!(entry.status, SubmissionStatus::Expired);
+    assert!(entry.attempts > 1, "attempts: {}", entry.attempts);
+    assert!(entry.last_error.is_some());
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_submit_request_auto_fallback() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    // Point the order stream at a port with nothing listening, so offchain submission fails.
+    let mut deployment = ctx.deployment.clone();
+    deployment.order_stream_url = Some("http://127.0.0.1:1".into());
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment)
+        .with_private_key(ctx.customer_signer.clone())
+        .build()
+        .await
+        .unwrap();
+    client.boundless_market.deposit(parse_ether("0.1").unwrap()).await.unwrap();
+
+    let request = new_request(1, &ctx).await;
+    let (request_id, _, path) = client.submit_request_auto(&request).await.unwrap();
+    assert_eq!(request_id, request.id);
+    assert_eq!(path, SubmissionPath::Onchain);
+    assert!(logs_contain("submitting onchain"));
+
+    let (submitted, _) = ctx.customer_market.get_submitted_request(request_id, None).await.unwrap();
+    assert_eq!(submitted.id, request.id);
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_submit_request_offchain_fan_out() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let (primary, additional) = (MockServer::start_async().await, MockServer::start_async().await);
+    let mut deployment = ctx.deployment.clone();
+    deployment.order_stream_url = Some(primary.base_url().into());
+    deployment.additional_order_stream_urls = vec![additional.base_url().into()];
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment)
+        .with_private_key(ctx.customer_signer.clone())
+        .build()
+        .await
+        .unwrap();
+    client.boundless_market.deposit(parse_ether("0.1").unwrap()).await.unwrap();
+
+    // The primary order stream is slow to respond, so the submission returns on the ack of the
+    // additional one.
+    let request = new_request(1, &ctx).await;
+    let accepted = serde_json::json!({ "status": "success", "request_id": request.id });
+    let slow = primary
+        .mock_async(|when, then| {
+            when.method(POST).path(ORDER_SUBMISSION_PATH);
+            then.status(200).json_body(accepted.clone()).delay(Duration::from_secs(30));
+        })
+        .await;
+    let fast = additional
+        .mock_async(|when, then| {
+            when.method(POST).path(ORDER_SUBMISSION_PATH);
+            then.status(200).json_body(accepted.clone());
+        })
+        .await;
+    let started = std::time::Instant::now();
+    let (request_id, _) = client.submit_request_offchain(&request).await.unwrap();
+    assert_eq!(request_id, request.id);
+    assert!(started.elapsed() < Duration::from_secs(30));
+    fast.assert_async().await;
+    slow.delete_async().await;
+    fast.delete_async().await;
+
+    // The request is refused once every order stream rejects it.
+    for server in [&primary, &additional] {
+        server
+            .mock_async(|when, then| {
+                when.method(POST).path(ORDER_SUBMISSION_PATH);
+                then.status(503).body("unavailable");
+            })
+            .await;
+    }
+    let err = client.submit_request_offchain(&new_request(2, &ctx).await).await.unwrap_err();
+    assert!(matches!(err, ClientError::OrderStreamError(_)), "{err:?}");
+    assert!(logs_contain("Failed to submit request"));
+}
+
+#[tokio::test]
+async fn test_submit_request_budget() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let mut deployment = ctx.deployment.clone();
+    deployment.order_stream_url = None;
+    let max_price = new_request(1, &ctx).await.offer.maxPrice;
+    let budget = || Budget::new().with_max_total(max_price + max_price / U256::from(2));
+    let client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment.clone())
+        .with_private_key(ctx.customer_signer.clone())
+        .with_budget(budget())
+        .build()
+        .await
+        .unwrap();
+    let client_budget = client.budget.clone().unwrap();
+
+    client.submit_request_onchain(&new_request(1, &ctx).await).await.unwrap();
+    assert_eq!(client_budget.spent_total(), max_price);
+
+    // A request over the remaining budget is refused without being submitted.
+    let refused = new_request(2, &ctx).await;
+    let err = client.submit_request_onchain(&refused).await.unwrap_err();
+    assert!(
+        matches!(err, ClientError::BudgetExceeded(BudgetError::Total { .. })),
+        "unexpected error: {err:?}"
+    );
+    assert!(ctx.customer_market.get_submitted_request(refused.id, None).await.is_err());
+
+    // A submission that fails is refunded.
+    let signer = PrivateKeySigner::random();
+    let unfunded_client = Client::builder()
+        .with_rpc_url(anvil.endpoint_url())
+        .with_deployment(deployment)
+        .with_private_key(signer.clone())
+        .with_budget(budget())
+        .build()
+        .await
+        .unwrap();
+    let mut request = new_request(1, &ctx).await;
+    request.id = RequestId::new(signer.address(), 1).into();
+    unfunded_client.submit_request_onchain(&request).await.unwrap_err();
+    assert_eq!(unfunded_client.budget.as_ref().unwrap().spent_total(), U256::ZERO);
+}
+
+#[tokio::test]
+async fn test_query_events_chunked() {
+    // Setup anvil
+    let anvil = Anvil::new().spawn();
+
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+
+    let mut request_ids = Vec::new();
+    for idx in 1..=3 {
+        let request = new_request(idx, &ctx).await;
+        request_ids.push(
+            ctx.customer_market.submit_request(&request, &ctx.customer_signer).await.unwrap(),
+        );
+    }
+
+    // Query with a chunk size of one block, so that each submission is in its own chunk.
+    let latest_block = ctx.customer_market.instance().provider().get_block_number().await.unwrap();
+    let filter = ctx.customer_market.instance().RequestSubmitted_filter().filter;
+    let mut chunks = 0;
+    let mut last_progress = None;
+    let events: Vec<(IBoundlessMarket::RequestSubmitted, Log)> = query_events_chunked(
+        ctx.customer_market.instance().provider(),
+        filter.clone(),
+        0..=latest_block,
+        1,
+        |progress| {
+            chunks += 1;
+            last_progress = Some(progress.clone());
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(chunks, latest_block + 1);
+    assert_eq!(last_progress.unwrap().fraction(), 1.0);
+    assert_eq!(events.iter().map(|(event, _)| event.requestId).collect::<Vec<_>>(), request_ids);
+
+    // Scanning backwards stops at the most recent event.
+    let most_recent = ChunkedLogQuery::new(1)
+        .with_direction(ScanDirection::Backward)
+        .scan(
+            ctx.customer_market.instance().provider(),
+            filter,
+            0..=latest_block,
+            |logs: Vec<(IBoundlessMarket::RequestSubmitted, Log)>, _| match logs.first() {
+                Some((event, _)) => ControlFlow::Break(event.requestId),
+                None => ControlFlow::Continue(()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(most_recent, request_ids.last().copied());
 }
 
 #[tokio::test]
@@ -191,8 +607,15 @@ async fn test_e2e() {
     ctx.prover_market.deposit_collateral_with_permit(deposit, &ctx.prover_signer).await.unwrap();
 
     // Lock the request
+    let lock_gas =
+        ctx.prover_market.estimate_lock_gas(request, customer_sig.clone()).await.unwrap();
+    assert!(lock_gas > 0);
+    let submit_block = ctx.customer_provider.get_block_number().await.unwrap();
     ctx.prover_market.lock_request(request, customer_sig, None).await.unwrap();
+    let lock_block = ctx.customer_provider.get_block_number().await.unwrap();
     assert!(ctx.customer_market.is_locked(request_id).await.unwrap());
+    // The typed request ID is accepted wherever a request ID is taken.
+    assert!(ctx.customer_market.is_locked(RequestId::from_lossy(request_id)).await.unwrap());
     assert!(
         ctx.customer_market.get_status(request_id, Some(expires_at)).await.unwrap()
             == RequestStatus::Locked
@@ -216,12 +639,26 @@ async fn test_e2e() {
         callbacks: vec![],
     };
     // fulfill the request
-    ctx.prover_market
-        .fulfill(FulfillmentTx::new(vec![fulfillment.clone()], assessor_fill.clone()))
-        .await
-        .unwrap();
+    let fulfillment_tx = FulfillmentTx::new(vec![fulfillment.clone()], assessor_fill.clone());
+    let fulfill_gas = ctx.prover_market.estimate_fulfill_gas(&fulfillment_tx).await.unwrap();
+    assert!(fulfill_gas > 0);
+    ctx.prover_market.fulfill(fulfillment_tx).await.unwrap();
     assert!(ctx.customer_market.is_fulfilled(request_id).await.unwrap());
 
+    // the status at earlier blocks is reconstructed from the state at those blocks
+    assert_eq!(
+        ctx.customer_market
+            .get_status_at(request_id, Some(expires_at), submit_block)
+            .await
+            .unwrap(),
+        RequestStatus::Unknown
+    );
+    assert_eq!(
+        ctx.customer_market.get_status_at(request_id, Some(expires_at), lock_block).await.unwrap(),
+        RequestStatus::Locked
+    );
+    assert!(!ctx.customer_market.is_fulfilled_at(request_id, lock_block).await.unwrap());
+
     // retrieve fulfillment data data and seal from the fulfilled request
     let fulfillment_result = ctx.customer_market.get_request_fulfillment(request_id).await.unwrap();
     let expected_fulfillment_data = FulfillmentData::decode_with_type(
@@ -232,6 +669,28 @@ async fn test_e2e() {
     let fulfillment_data = fulfillment_result.data().unwrap();
     assert_eq!(fulfillment_data, expected_fulfillment_data);
     assert_eq!(fulfillment_result.seal, fulfillment.seal);
+
+    // the fulfillment is returned with the block and transaction that delivered it
+    let interval = Duration::from_millis(100);
+    let confirmed = ctx
+        .customer_market
+        .wait_for_confirmed_fulfillment(request_id, interval, expires_at, 0)
+        .await
+        .unwrap();
+    assert_eq!(confirmed.fulfillment.seal, fulfillment.seal);
+    assert_eq!(confirmed.prover, ctx.prover_signer.address());
+    assert_eq!(confirmed.confirmations, 0);
+
+    // mine a block on top of the fulfillment, so that it has one confirmation
+    ctx.customer_market.deposit(U256::from(1)).await.unwrap();
+    let reconfirmed = ctx
+        .customer_market
+        .wait_for_confirmed_fulfillment(request_id, interval, expires_at, 1)
+        .await
+        .unwrap();
+    assert_eq!(reconfirmed.block_hash, confirmed.block_hash);
+    assert_eq!(reconfirmed.tx_hash, confirmed.tx_hash);
+    assert_eq!(reconfirmed.confirmations, 1);
 }
 
 #[tokio::test]
@@ -491,6 +950,48 @@ async fn test_e2e_no_payment() {
     //assert_eq!(seal, fulfillment.seal);
 }
 
+#[tokio::test]
+async fn test_reclaim_expired() {
+    // Setup anvil
+    let anvil = Anvil::new().spawn();
+
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+
+    // A request that was never locked holds no escrow.
+    let unlocked = new_request(1, &ctx).await;
+    ctx.customer_market.submit_request(&unlocked, &ctx.customer_signer).await.unwrap();
+    let outcome = ctx.customer_market.reclaim_expired(unlocked.id).await.unwrap();
+    assert!(matches!(outcome, ReclaimOutcome::NotLocked));
+
+    let request = new_request(2, &ctx).await;
+    let request_id =
+        ctx.customer_market.submit_request(&request, &ctx.customer_signer).await.unwrap();
+    let logs = ctx.customer_market.instance().RequestSubmitted_filter().query().await.unwrap();
+    let (event, _) = logs.into_iter().find(|(event, _)| event.request.id == request_id).unwrap();
+
+    let deposit = default_allowance();
+    ctx.prover_market.deposit_collateral_with_permit(deposit, &ctx.prover_signer).await.unwrap();
+    ctx.prover_market.lock_request(&event.request, event.clientSignature, None).await.unwrap();
+
+    let err = ctx.customer_market.reclaim_expired(request_id).await.unwrap_err();
+    assert!(matches!(err, MarketError::RequestNotExpired(id, _) if id == request_id), "{err:?}");
+
+    // Let the request expire unfulfilled.
+    ctx.customer_provider.anvil_increase_time(request.offer.timeout as u64 + 1).await.unwrap();
+    ctx.customer_provider.anvil_mine(Some(1), None).await.unwrap();
+
+    let balance_before =
+        ctx.customer_market.balance_of(ctx.customer_signer.address()).await.unwrap();
+    let outcome = ctx.customer_market.reclaim_expired(request_id).await.unwrap();
+    assert!(matches!(outcome, ReclaimOutcome::Slashed { refunded: true, .. }), "{outcome:?}");
+    let balance_after =
+        ctx.customer_market.balance_of(ctx.customer_signer.address()).await.unwrap();
+    assert!(balance_after > balance_before);
+
+    let outcome = ctx.customer_market.reclaim_expired(request_id).await.unwrap();
+    assert!(matches!(outcome, ReclaimOutcome::AlreadySlashed));
+}
+
 #[tokio::test]
 #[traced_test]
 async fn test_e2e_claim_digest_no_fulfillment_data() {
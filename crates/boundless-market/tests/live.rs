@@ -0,0 +1,253 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! End-to-end test of a live Boundless deployment.
+//!
+//! Submits a small request for the echo guest from the given account, waits for a prover on the
+//! network to lock and fulfill it, and verifies the delivered proof against the verifier router.
+//! A pass/fail report of each stage is printed, and written as JSON if a path is given.
+//!
+//! The test is only built with the `live-test` feature, and spends real funds. It is configured
+//! with the following environment variables:
+//! - `BOUNDLESS_LIVE_RPC_URL`: RPC URL of the chain. The deployment is selected by its chain ID.
+//! - `BOUNDLESS_LIVE_PRIVATE_KEY`: key of the requestor account, funded with a small amount.
+//! - `BOUNDLESS_LIVE_PROGRAM_URL`: URL of an uploaded echo guest. If not set, the guest is
+//!   uploaded with the storage provider configured in the environment.
+//! - `BOUNDLESS_LIVE_MAX_PRICE`: max price of the request, in ether. Defaults to 0.001.
+//! - `BOUNDLESS_LIVE_REPORT`: path to write the JSON report to.
+//!
+//! ```sh
+//! cargo test -p boundless-market --features live-test --test live -- --nocapture
+//! ```
+
+use std::{
+    env,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    primitives::{utils::parse_ether, U256},
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{bail, Context, Result};
+use boundless_market::{
+    budget::Budget,
+    contracts::RequestStatus,
+    request_builder::OfferParams,
+    storage::{storage_provider_from_env, StandardStorageProvider},
+    Client,
+};
+use boundless_test_utils::guests::ECHO_ELF;
+use risc0_ethereum_contracts::IRiscZeroVerifier;
+use risc0_zkvm::sha::Digestible;
+use url::Url;
+
+/// Interval between checks of the request status.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+struct LiveConfig {
+    rpc_url: Url,
+    private_key: PrivateKeySigner,
+    program_url: Option<Url>,
+    max_price: U256,
+    report_path: Option<String>,
+}
+
+impl LiveConfig {
+    fn from_env() -> Result<Self> {
+        let var = |name: &str| env::var(name).with_context(|| format!("{name} must be set"));
+        Ok(Self {
+            rpc_url: var("BOUNDLESS_LIVE_RPC_URL")?.parse()?,
+            private_key: var("BOUNDLESS_LIVE_PRIVATE_KEY")?.parse()?,
+            program_url: env::var("BOUNDLESS_LIVE_PROGRAM_URL")
+                .ok()
+                .map(|url| url.parse())
+                .transpose()?,
+            max_price: parse_ether(
+                &env::var("BOUNDLESS_LIVE_MAX_PRICE").unwrap_or_else(|_| "0.001".into()),
+            )?,
+            report_path: env::var("BOUNDLESS_LIVE_REPORT").ok(),
+        })
+    }
+}
+
+/// Outcome of one stage of the cycle.
+struct Stage {
+    name: &'static str,
+    passed: bool,
+    elapsed: Duration,
+    detail: String,
+}
+
+/// Pass/fail report of the stages run so far.
+#[derive(Default)]
+struct Report {
+    stages: Vec<Stage>,
+}
+
+impl Report {
+    /// Record the outcome of a stage started at `start`, returning its value if it passed.
+    fn record<T>(
+        &mut self,
+        name: &'static str,
+        start: Instant,
+        result: Result<T>,
+        detail: impl FnOnce(&T) -> String,
+    ) -> Option<T> {
+        let (passed, detail, value) = match result {
+            Ok(value) => (true, detail(&value), Some(value)),
+            Err(err) => (false, format!("{err:#}"), None),
+        };
+        self.stages.push(Stage { name, passed, elapsed: start.elapsed(), detail });
+        value
+    }
+
+    fn passed(&self) -> bool {
+        self.stages.iter().all(|stage| stage.passed)
+    }
+
+    fn print(&self) {
+        println!("{:<8}  {:<6}  {:>8}  detail", "stage", "result", "time");
+        for stage in &self.stages {
+            println!(
+                "{:<8}  {:<6}  {:>7}s  {}",
+                stage.name,
+                if stage.passed { "PASS" } else { "FAIL" },
+                stage.elapsed.as_secs(),
+                stage.detail
+            );
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "passed": self.passed(),
+            "stages": self.stages.iter().map(|stage| serde_json::json!({
+                "name": stage.name,
+                "passed": stage.passed,
+                "seconds": stage.elapsed.as_secs_f64(),
+                "detail": stage.detail,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn live_request_cycle() {
+    let config = LiveConfig::from_env().expect("invalid live test configuration");
+    let mut report = Report::default();
+    run(&config, &mut report).await;
+
+    report.print();
+    if let Some(path) = &config.report_path {
+        let json = serde_json::to_string_pretty(&report.to_json()).unwrap();
+        std::fs::write(path, json).expect("failed to write the report");
+    }
+    assert!(report.passed(), "live request cycle failed");
+}
+
+/// Run the stages of the cycle in order, stopping at the first failure.
+async fn run(config: &LiveConfig, report: &mut Report) {
+    let start = Instant::now();
+    let storage_provider: Result<Option<StandardStorageProvider>> = config
+        .program_url
+        .is_none()
+        .then(storage_provider_from_env)
+        .transpose()
+        .map_err(Into::into);
+    let client = async {
+        anyhow::Ok(
+            Client::builder()
+                .with_rpc_url(config.rpc_url.clone())
+                .with_private_key(config.private_key.clone())
+                .with_storage_provider(storage_provider?)
+                .with_budget(Budget::new().with_max_total(config.max_price))
+                .build()
+                .await?,
+        )
+    }
+    .await;
+    let Some(client) = report.record("connect", start, client, |client| {
+        format!("market {}", client.deployment.boundless_market_address)
+    }) else {
+        return;
+    };
+
+    let start = Instant::now();
+    let submitted = async {
+        let stdin = format!("boundless live test {:016x}", rand::random::<u64>());
+        let request = match &config.program_url {
+            Some(url) => client.new_request().with_program_url(url.clone())?,
+            None => client.new_request().with_program(ECHO_ELF),
+        }
+        .with_stdin(stdin.into_bytes())
+        .with_offer(OfferParams::builder().max_price(config.max_price));
+        anyhow::Ok(client.submit_onchain(request).await?)
+    }
+    .await;
+    let Some((request_id, expires_at)) =
+        report.record("submit", start, submitted, |(id, _)| format!("request 0x{id:x}"))
+    else {
+        return;
+    };
+
+    // A request may be fulfilled without ever being locked, if a prover fulfills it directly.
+    let start = Instant::now();
+    let locked = async {
+        loop {
+            match client.boundless_market.get_status(request_id, Some(expires_at)).await? {
+                status @ (RequestStatus::Locked | RequestStatus::Fulfilled) => return Ok(status),
+                RequestStatus::Expired => bail!("request expired before it was locked"),
+                RequestStatus::Unknown => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+    .await;
+    if report.record("lock", start, locked, |status| format!("{status:?}")).is_none() {
+        return;
+    }
+
+    let start = Instant::now();
+    let fulfilled = client
+        .wait_for_confirmed_fulfillment(request_id, POLL_INTERVAL, expires_at, 0)
+        .await
+        .map_err(Into::into);
+    let Some(fulfilled) = report.record("fulfill", start, fulfilled, |fulfilled| {
+        format!("prover {} in tx {}", fulfilled.prover, fulfilled.tx_hash)
+    }) else {
+        return;
+    };
+
+    let start = Instant::now();
+    let verified = async {
+        let data = fulfilled.fulfillment.data()?;
+        let image_id = data.image_id().context("fulfillment has no image ID")?;
+        let journal = data.journal().context("fulfillment has no journal")?;
+        let router = client
+            .deployment
+            .verifier_router_address
+            .context("deployment has no verifier router")?;
+        IRiscZeroVerifier::new(router, client.provider())
+            .verify(
+                fulfilled.fulfillment.seal.clone(),
+                <[u8; 32]>::from(image_id).into(),
+                <[u8; 32]>::from(journal.digest()).into(),
+            )
+            .call()
+            .await
+            .context("seal was rejected by the verifier router")?;
+        anyhow::Ok(router)
+    }
+    .await;
+    report.record("verify", start, verified, |router| format!("verified by router {router}"));
+}
@@ -21,8 +21,8 @@ use alloy::{
 use anyhow::{Context, Result};
 use boundless_market::{
     balance_alerts_layer::{BalanceAlertConfig, BalanceAlertLayer},
+    client::ClientPreset,
     contracts::boundless_market::BoundlessMarketService,
-    dynamic_gas_filler::DynamicGasFiller,
     nonce_layer::NonceProvider,
 };
 use broker::{Args, Broker, Config, CustomRetryPolicy};
@@ -57,26 +57,26 @@ async fn main() -> Result<()> {
         CustomRetryPolicy,
     );
     let client = RpcClient::builder().layer(retry_layer).http(args.rpc_url.clone());
+    // Balance thresholds not set in the config fall back to the prover defaults of the client.
+    let preset_alerts = ClientPreset::Prover.balance_alerts();
     let balance_alerts_layer = BalanceAlertLayer::new(BalanceAlertConfig {
         watch_address: wallet.default_signer().address(),
         warn_threshold: config
             .market
             .balance_warn_threshold
             .map(|s| parse_ether(&s))
-            .transpose()?,
+            .transpose()?
+            .or(preset_alerts.warn_threshold),
         error_threshold: config
             .market
             .balance_error_threshold
             .map(|s| parse_ether(&s))
-            .transpose()?,
+            .transpose()?
+            .or(preset_alerts.error_threshold),
     });
 
-    let dynamic_gas_filler = DynamicGasFiller::new(
-        0.2,  // 20% increase of gas limit
-        0.05, // 5% increase of gas_price per pending transaction
-        2.0,  // 2x max gas multiplier
-        wallet.default_signer().address(),
-    );
+    let dynamic_gas_filler =
+        ClientPreset::Prover.gas_filler().filler(wallet.default_signer().address());
 
     let base_provider = ProviderBuilder::new()
         .disable_recommended_fillers()
@@ -218,11 +218,13 @@ pub struct MarketConf {
     pub additional_proof_cycles: u64,
     /// Optional balance warning threshold (in native token)
     ///
-    /// If the submitter balance drops below this the broker will issue warning logs
+    /// If the submitter balance drops below this the broker will issue warning logs. Defaults to
+    /// the warning threshold of the prover client defaults, 0.1 ETH.
     pub balance_warn_threshold: Option<String>,
     /// Optional balance error threshold (in native token)
     ///
-    /// If the submitter balance drops below this the broker will issue error logs
+    /// If the submitter balance drops below this the broker will issue error logs. Defaults to
+    /// the error threshold of the prover client defaults, 0.05 ETH.
     pub balance_error_threshold: Option<String>,
     /// Optional collateral balance warning threshold (in collateral tokens)
     ///
@@ -407,6 +409,16 @@ pub struct BatcherConfig {
     pub block_deadline_buffer_secs: u64,
     /// Timeout, in seconds for transaction confirmations
     pub txn_timeout: Option<u64>,
+    /// Timeout, in seconds, for the confirmation of lock transactions
+    ///
+    /// Locks are raced by competing provers, so a timeout shorter than `txn_timeout` gives up
+    /// sooner on a lock that is stuck. Defaults to `txn_timeout`.
+    pub lock_txn_timeout: Option<u64>,
+    /// Number of blocks, including the one it is included in, to wait for before a fulfillment
+    /// transaction is considered confirmed
+    ///
+    /// Defaults to 1.
+    pub fulfill_txn_confirmations: Option<u64>,
     /// Polling time, in milliseconds
     ///
     /// The time between polls for new orders to aggregate and how often to check for batch finalize
@@ -435,6 +447,8 @@ impl Default for BatcherConfig {
             batch_max_fees: None,
             block_deadline_buffer_secs: 120,
             txn_timeout: None,
+            lock_txn_timeout: None,
+            fulfill_txn_confirmations: None,
             batch_poll_time_ms: Some(1000),
             single_txn_fulfill: false,
             withdraw: false,
@@ -669,6 +683,8 @@ batch_max_time = 300
 batch_size = 3
 block_deadline_buffer_secs = 120
 txn_timeout = 45
+lock_txn_timeout = 10
+fulfill_txn_confirmations = 2
 batch_poll_time_ms = 1200
 single_txn_fulfill = true
 withdraw = true"#;
@@ -714,6 +730,7 @@ error = ?"#;
         assert_eq!(config.batcher.batch_max_fees, Some("0.1".into()));
         assert_eq!(config.batcher.block_deadline_buffer_secs, 120);
         assert_eq!(config.batcher.txn_timeout, None);
+        assert_eq!(config.batcher.lock_txn_timeout, None);
         assert_eq!(config.batcher.batch_poll_time_ms, None);
     }
 
@@ -771,6 +788,8 @@ error = ?"#;
             assert_eq!(config.prover.proof_retry_sleep_ms, 500);
             assert!(config.prover.bonsai_r0_zkvm_ver.is_none());
             assert_eq!(config.batcher.txn_timeout, Some(45));
+            assert_eq!(config.batcher.lock_txn_timeout, Some(10));
+            assert_eq!(config.batcher.fulfill_txn_confirmations, Some(2));
             assert_eq!(config.batcher.batch_poll_time_ms, Some(1200));
             assert_eq!(config.batcher.min_batch_size, Some(3));
             assert!(config.batcher.single_txn_fulfill);
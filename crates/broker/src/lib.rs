@@ -27,6 +27,7 @@ use alloy::{
 };
 use anyhow::{Context, Result};
 use boundless_market::{
+    client::ClientPreset,
     contracts::{boundless_market::BoundlessMarketService, ProofRequest},
     order_stream_client::OrderStreamClient,
     selector::is_groth16_selector,
@@ -122,19 +123,19 @@ pub struct Args {
     /// RPC HTTP retry rate limit max retry
     ///
     /// From the `RetryBackoffLayer` of Alloy
-    #[clap(long, default_value_t = 10)]
+    #[clap(long, default_value_t = ClientPreset::Prover.rpc_retry().max_retries)]
     pub rpc_retry_max: u32,
 
     /// RPC HTTP retry backoff (in ms)
     ///
     /// From the `RetryBackoffLayer` of Alloy
-    #[clap(long, default_value_t = 1000)]
+    #[clap(long, default_value_t = ClientPreset::Prover.rpc_retry().initial_backoff_ms)]
     pub rpc_retry_backoff: u64,
 
     /// RPC HTTP retry compute-unit per second
     ///
     /// From the `RetryBackoffLayer` of Alloy
-    #[clap(long, default_value_t = 100)]
+    #[clap(long, default_value_t = ClientPreset::Prover.rpc_retry().compute_units_per_second)]
     pub rpc_retry_cu: u64,
 
     /// Log JSON
@@ -33,7 +33,7 @@ use alloy::{
 };
 use anyhow::{Context, Result};
 use boundless_market::contracts::{
-    boundless_market::{BoundlessMarketService, MarketError},
+    boundless_market::{BoundlessMarketService, ConfirmationPolicy, MarketError, MarketTxType},
     IBoundlessMarket::IBoundlessMarketErrors,
     RequestStatus, TxnErr,
 };
@@ -175,9 +175,9 @@ where
         collateral_token_decimals: u8,
         rpc_retry_config: RpcRetryConfig,
     ) -> Result<Self> {
-        let txn_timeout_opt = {
+        let (txn_timeout_opt, lock_txn_timeout_opt) = {
             let config = config.lock_all().context("Failed to read config")?;
-            config.batcher.txn_timeout
+            (config.batcher.txn_timeout, config.batcher.lock_txn_timeout)
         };
 
         let mut market = BoundlessMarketService::new(
@@ -188,6 +188,12 @@ where
         if let Some(txn_timeout) = txn_timeout_opt {
             market = market.with_timeout(Duration::from_secs(txn_timeout));
         }
+        if let Some(lock_txn_timeout) = lock_txn_timeout_opt {
+            market = market.with_confirmation_policy(
+                MarketTxType::Lock,
+                ConfirmationPolicy::new(1, Duration::from_secs(lock_txn_timeout)),
+            );
+        }
         {
             let config = config.lock_all()?;
 
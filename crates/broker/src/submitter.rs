@@ -26,7 +26,10 @@ use alloy::{
 use anyhow::{anyhow, Context, Result};
 use boundless_market::{
     contracts::{
-        boundless_market::{BoundlessMarketService, FulfillmentTx, MarketError, UnlockedRequest},
+        boundless_market::{
+            BoundlessMarketService, ConfirmationPolicy, FulfillmentTx, MarketError, MarketTxType,
+            UnlockedRequest,
+        },
         encode_seal, AssessorJournal, AssessorReceipt, Fulfillment,
         FulfillmentDataImageIdAndJournal, FulfillmentDataType, PredicateType,
     },
@@ -120,9 +123,9 @@ where
         market_addr: Address,
         set_builder_img_id: Digest,
     ) -> Result<Self> {
-        let txn_timeout_opt = {
+        let (txn_timeout_opt, fulfill_confirmations_opt) = {
             let config = config.lock_all().context("Failed to read config")?;
-            config.batcher.txn_timeout
+            (config.batcher.txn_timeout, config.batcher.fulfill_txn_confirmations)
         };
 
         let mut market = BoundlessMarketService::new(
@@ -134,6 +137,13 @@ where
             tracing::debug!("Setting market timeout to {}", txn_timeout);
             market = market.with_timeout(Duration::from_secs(txn_timeout));
         }
+        if let Some(confirmations) = fulfill_confirmations_opt {
+            let timeout = market.confirmation_policy(MarketTxType::Fulfill).timeout;
+            market = market.with_confirmation_policy(
+                MarketTxType::Fulfill,
+                ConfirmationPolicy::new(confirmations, timeout),
+            );
+        }
 
         let mut set_verifier = SetVerifierService::new(
             set_verifier_addr,
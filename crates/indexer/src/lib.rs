@@ -14,9 +14,13 @@
 
 use std::{cmp::min, collections::HashMap, sync::Arc};
 
-use ::boundless_market::contracts::{
-    boundless_market::{BoundlessMarketService, MarketError},
-    EIP712DomainSaltless,
+use ::boundless_market::{
+    contracts::{
+        boundless_market::{BoundlessMarketService, MarketError},
+        EIP712DomainSaltless,
+    },
+    http_client::rate_limited_rpc_client_with_http,
+    rate_limit_layer::RateLimiter,
 };
 use alloy::{
     eips::BlockNumberOrTag,
@@ -81,6 +85,8 @@ pub struct IndexerService<P> {
 pub struct IndexerServiceConfig {
     pub interval: Duration,
     pub retries: u32,
+    /// Limit on the rate of requests sent to the RPC endpoint.
+    pub rate_limiter: Option<RateLimiter>,
 }
 
 impl IndexerService<ProviderWallet> {
@@ -92,10 +98,16 @@ impl IndexerService<ProviderWallet> {
         config: IndexerServiceConfig,
     ) -> Result<Self, ServiceError> {
         let caller = private_key.address();
-        let provider = ProviderBuilder::new()
-            .disable_recommended_fillers()
-            .filler(ChainIdFiller::default())
-            .connect_http(rpc_url);
+        let builder =
+            ProviderBuilder::new().disable_recommended_fillers().filler(ChainIdFiller::default());
+        let provider = match config.rate_limiter.clone() {
+            Some(limiter) => builder.connect_client(rate_limited_rpc_client_with_http(
+                Default::default(),
+                rpc_url,
+                limiter,
+            )),
+            None => builder.connect_http(rpc_url),
+        };
         let boundless_market =
             BoundlessMarketService::new(boundless_market_address, provider.clone(), caller);
         let db: DbObj = Arc::new(AnyDb::new(db_conn).await?);
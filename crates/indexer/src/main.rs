@@ -17,6 +17,7 @@ use std::time::Duration;
 use alloy::{primitives::Address, signers::local::PrivateKeySigner};
 use anyhow::{bail, Result};
 use boundless_indexer::{IndexerService, IndexerServiceConfig};
+use boundless_market::rate_limit_layer::{parse_requests_per_second, RateLimiter};
 use clap::Parser;
 use url::Url;
 
@@ -42,6 +43,14 @@ struct MainArgs {
     /// Number of retries before quitting after an error.
     #[clap(long, default_value = "10")]
     retries: u32,
+    /// Maximum average number of requests per second to send to the RPC endpoint.
+    ///
+    /// Useful for staying within the limits of rate-limited RPC tiers. Unlimited if not set.
+    #[clap(long, env, value_parser = parse_requests_per_second)]
+    rpc_rate_limit: Option<f64>,
+    /// Maximum number of requests to send to the RPC endpoint in a burst, with --rpc-rate-limit.
+    #[clap(long, default_value = "10", value_parser = clap::value_parser!(u32).range(1..))]
+    rpc_rate_limit_burst: u32,
     /// Whether to log in JSON format.
     #[clap(long, env, default_value_t = false)]
     log_json: bool,
@@ -73,6 +82,9 @@ async fn main() -> Result<()> {
         IndexerServiceConfig {
             interval: Duration::from_secs(args.interval),
             retries: args.retries,
+            rate_limiter: args
+                .rpc_rate_limit
+                .map(|rps| RateLimiter::new(rps, args.rpc_rate_limit_burst)),
         },
     )
     .await?;
@@ -0,0 +1,180 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulation of an application consuming proofs through the callback of each request.
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{Address, B256, U256};
+use anyhow::{Context, Result};
+use boundless_market::{
+    client::Client,
+    contracts::{bytecode::MockCallback, ProofRequest},
+};
+
+alloy::sol! {
+    #[sol(rpc)]
+    interface ICallbackCounter {
+        function getCallCount() external view returns (uint256);
+    }
+}
+
+/// Error returned when the consumer did not receive the callbacks of fulfilled requests.
+#[derive(Debug, PartialEq)]
+pub(crate) struct CallbackMismatch {
+    expected: U256,
+    observed: U256,
+}
+
+impl std::fmt::Display for CallbackMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "consumer call count is {}, expected at least {} after the fulfilled requests",
+            self.observed, self.expected
+        )
+    }
+}
+
+/// Tracks requests with a callback to a consumer contract, and checks that the consumer's state
+/// advances by one call for each fulfilled request.
+///
+/// The consumer must expose `getCallCount()`, like the `MockCallback` contract.
+pub(crate) struct CallbackSim {
+    consumer: Address,
+    /// Call count of the consumer when the simulation started.
+    baseline: U256,
+    /// Expiry timestamps of the tracked requests that are not yet fulfilled.
+    pending: HashMap<U256, u64>,
+    /// Number of tracked requests that were fulfilled.
+    fulfilled: u64,
+}
+
+impl CallbackSim {
+    /// Start a simulation against an existing consumer contract.
+    pub(crate) async fn new(client: &Client, consumer: Address) -> Result<Self> {
+        let baseline = call_count(client, consumer).await?;
+        tracing::info!("Callback consumer {consumer} starts with call count {baseline}");
+        Ok(Self { consumer, baseline, pending: HashMap::new(), fulfilled: 0 })
+    }
+
+    /// Deploy a `MockCallback` consumer accepting proofs of the given image ID, and start a
+    /// simulation against it.
+    pub(crate) async fn deploy(client: &Client, image_id: B256) -> Result<Self> {
+        let verifier = client
+            .deployment
+            .verifier_router_address
+            .context("deploying a callback consumer requires the verifier router address")?;
+        let consumer = MockCallback::deploy(
+            client.provider(),
+            verifier,
+            client.deployment.boundless_market_address,
+            image_id,
+            U256::ZERO,
+        )
+        .await
+        .context("failed to deploy MockCallback consumer")?;
+        tracing::info!("Deployed callback consumer to {}", consumer.address());
+        Self::new(client, *consumer.address()).await
+    }
+
+    /// Address of the consumer contract, to set as the callback of generated requests.
+    pub(crate) fn consumer(&self) -> Address {
+        self.consumer
+    }
+
+    /// Whether no tracked request is still waiting to be fulfilled or to expire.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Start tracking a request that was just submitted.
+    pub(crate) fn track(&mut self, request: &ProofRequest) {
+        self.pending.insert(request.id, request.expires_at());
+    }
+
+    /// Check for fulfilled requests, and verify the consumer received their callbacks.
+    pub(crate) async fn update(&mut self, client: &Client) -> Result<Result<(), CallbackMismatch>> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut resolved = Vec::new();
+        for (&request_id, &expires_at) in &self.pending {
+            if client.boundless_market.is_fulfilled(request_id).await? {
+                tracing::debug!("Request 0x{request_id:x} fulfilled, expecting a callback");
+                resolved.push((request_id, true));
+            } else if now > expires_at {
+                tracing::debug!("Request 0x{request_id:x} expired, no callback expected");
+                resolved.push((request_id, false));
+            }
+        }
+        for (request_id, fulfilled) in resolved {
+            self.pending.remove(&request_id);
+            self.fulfilled += fulfilled as u64;
+        }
+
+        let observed = call_count(client, self.consumer).await?;
+        Ok(self.evaluate(observed))
+    }
+
+    /// Compare the observed call count against the number of fulfilled requests.
+    ///
+    /// Other requests may call the same consumer, so only a shortfall is a mismatch.
+    fn evaluate(&self, observed: U256) -> Result<(), CallbackMismatch> {
+        let expected = self.baseline + U256::from(self.fulfilled);
+        tracing::info!(
+            "Callback consumer {}: {} fulfilled requests, call count {observed} (expected at least {expected})",
+            self.consumer,
+            self.fulfilled
+        );
+        if observed < expected {
+            return Err(CallbackMismatch { expected, observed });
+        }
+        Ok(())
+    }
+}
+
+async fn call_count(client: &Client, consumer: Address) -> Result<U256> {
+    ICallbackCounter::new(consumer, client.provider())
+        .getCallCount()
+        .call()
+        .await
+        .with_context(|| format!("failed to query call count of consumer {consumer}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let mut sim = CallbackSim {
+            consumer: Address::ZERO,
+            baseline: U256::from(3),
+            pending: HashMap::new(),
+            fulfilled: 2,
+        };
+        assert!(sim.evaluate(U256::from(5)).is_ok());
+        // Callbacks of requests from other clients also advance the count.
+        assert!(sim.evaluate(U256::from(7)).is_ok());
+        assert_eq!(
+            sim.evaluate(U256::from(4)),
+            Err(CallbackMismatch { expected: U256::from(5), observed: U256::from(4) })
+        );
+
+        sim.fulfilled = 0;
+        assert!(sim.evaluate(U256::from(3)).is_ok());
+    }
+}
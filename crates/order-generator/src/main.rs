@@ -12,28 +12,65 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{path::PathBuf, time::Duration};
+use std::{
+    future::Future,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use alloy::{
     network::EthereumWallet,
     primitives::{
         utils::{format_units, parse_ether},
-        U256,
+        Address, B256, U256,
     },
+    providers::Provider,
     signers::local::PrivateKeySigner,
 };
 use anyhow::{Context, Result};
+#[cfg(feature = "self-fulfill")]
+use boundless_cli::config::ProverConfig;
 use boundless_market::{
-    balance_alerts_layer::BalanceAlertConfig, client::Client, deployments::Deployment,
-    input::GuestEnv, request_builder::OfferParams, storage::fetch_url,
+    balance_alerts_layer::BalanceAlertConfig,
+    budget::Budget,
+    client::{Client, ClientError},
+    contracts::ProofRequest,
+    deployments::Deployment,
+    fault_injection::{FaultInjector, FaultRates},
+    input::GuestEnv,
+    log_redaction::Redactor,
+    request_builder::{MarketStats, OfferParams, PreflightError, RequirementParams},
+    storage::fetch_url,
     storage::StorageProviderConfig,
 };
-use clap::Parser;
-use rand::Rng;
-use risc0_zkvm::Journal;
+use callback_sim::CallbackSim;
+use clap::{CommandFactory, FromArgMatches, Parser};
+use monitor::{MarketMonitor, Observation};
+use presets::Preset;
+use profile::LoadProfile;
+use proof_check::{ExpectedJournal, ProofChecker};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use reclaim::Reclaimer;
+use report::{ReportFormat, RunReport};
+use risc0_zkvm::{compute_image_id, Journal};
+#[cfg(feature = "self-fulfill")]
+use self_fulfill::SelfFulfiller;
+use slo::{SloConfig, SloTracker, SloViolation};
+use tokio::sync::Mutex;
 use tracing_subscriber::fmt::format::FmtSpan;
 use url::Url;
 
+mod callback_sim;
+mod monitor;
+mod presets;
+mod profile;
+mod proof_check;
+mod reclaim;
+mod report;
+#[cfg(feature = "self-fulfill")]
+mod self_fulfill;
+mod slo;
+
 /// Arguments of the order generator.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
@@ -42,8 +79,8 @@ struct MainArgs {
     #[clap(short, long, env)]
     rpc_url: Url,
     /// Private key used to sign and submit requests.
-    #[clap(long, env)]
-    private_key: PrivateKeySigner,
+    #[clap(long, env, required_unless_present = "monitor")]
+    private_key: Option<PrivateKeySigner>,
     /// Transaction timeout in seconds.
     #[clap(long, default_value = "45")]
     tx_timeout: u64,
@@ -60,6 +97,13 @@ struct MainArgs {
     /// If unspecified, the loop will run indefinitely.
     #[clap(short, long)]
     count: Option<u64>,
+    /// Schedule of submission rates to follow instead of a constant interval.
+    ///
+    /// For example, `ramp:10m:1rps-10rps,hold:5m:10rps,ramp:10m:10rps-1rps` warms up to 10
+    /// requests per second, holds that peak, and cools down. Rates take an `rps` or `rpm` suffix.
+    /// The generator stops at the end of the schedule.
+    #[clap(long, env, conflicts_with = "interval")]
+    profile: Option<LoadProfile>,
     /// Minimum price per mcycle in ether.
     #[clap(long = "min", value_parser = parse_ether, default_value = "0.001")]
     min_price_per_mcycle: U256,
@@ -69,8 +113,10 @@ struct MainArgs {
     /// Lockin stake amount in ether.
     #[clap(short, long, default_value = "0")]
     lock_collateral_raw: U256,
-    /// Number of seconds, from the current time, before the auction period starts.
-    /// If not provided, will be calculated based on cycle count assuming 5 MHz prove rate.
+    /// Minimum number of seconds, from the current time, before the auction period starts.
+    ///
+    /// The delay is extended to leave provers time to execute the request at the configured
+    /// execution rate. If not provided, defaults to 30 seconds.
     #[clap(long)]
     bidding_start_delay: Option<u64>,
     /// Ramp-up period in seconds.
@@ -108,6 +154,12 @@ struct MainArgs {
     /// The maximum cycle count to drive the loop.
     #[clap(long, env = "CYCLE_COUNT_MAX", conflicts_with_all = ["input", "program"])]
     input_max_mcycles: Option<u64>,
+    /// Preflight each request, aborting once the guest runs past this many cycles.
+    ///
+    /// By default, requests are not preflighted, as the cycle count and journal of the loop guest
+    /// are known in advance. Requests whose guest runs past the limit are not submitted.
+    #[clap(long)]
+    preflight_cycle_limit: Option<u64>,
     /// Balance threshold at which to log a warning.
     #[clap(long, value_parser = parse_ether, default_value = "1")]
     warn_balance_below: Option<U256>,
@@ -123,33 +175,225 @@ struct MainArgs {
     #[clap(long, default_value = "false")]
     submit_offchain: bool,
 
+    /// Preset bundle of interval, pricing, cycle distribution, and timeouts.
+    ///
+    /// Options given explicitly take precedence over the values set by the preset.
+    #[clap(long, env, value_enum)]
+    preset: Option<Preset>,
+
+    /// Latency SLO: seconds from submission within which requests should be locked.
+    ///
+    /// Requests fulfilled without being locked count as locked when they are fulfilled.
+    #[clap(long, help_heading = "Latency SLO")]
+    slo_lock_within: Option<u64>,
+    /// Latency SLO: seconds from submission within which requests should be fulfilled.
+    #[clap(long, help_heading = "Latency SLO")]
+    slo_fulfill_within: Option<u64>,
+    /// Fraction of requests that must meet each latency objective.
+    #[clap(long, default_value = "0.95", help_heading = "Latency SLO")]
+    slo_target: f64,
+    /// Number of most recent requests with a known outcome over which the SLO is evaluated.
+    #[clap(long, default_value = "20", help_heading = "Latency SLO")]
+    slo_window: usize,
+    /// Minimum number of requests with a known outcome before the SLO is evaluated.
+    #[clap(long, default_value = "5", help_heading = "Latency SLO")]
+    slo_min_samples: usize,
+    /// Exit with a non-zero status when the SLO is violated, instead of only logging an alert.
+    #[clap(long, help_heading = "Latency SLO")]
+    slo_exit_on_violation: bool,
+
+    /// Request Groth16 proofs instead of accepting any proof type.
+    #[clap(long)]
+    groth16: bool,
+    /// Verify each delivered proof against the requested proof type and the onchain verifiers.
+    ///
+    /// Seals with the wrong selector, or that fail verification (including set-inclusion proofs
+    /// whose path does not lead to a verified root) are reported as errors.
+    #[clap(long)]
+    verify_proofs: bool,
+    /// Verify that each delivered journal matches the input and random nonce of its request.
+    ///
+    /// Every generated request commits to a distinct nonce, so a mismatch means the proof is for
+    /// another request or program, and is reported as a critical error.
+    #[clap(long)]
+    verify_journals: bool,
+
+    /// Address of a consumer contract to set as the callback of each request.
+    ///
+    /// The consumer must expose `getCallCount()`, like the MockCallback contract. Its call count
+    /// is checked to advance by one for each fulfilled request.
+    #[clap(long, conflicts_with = "deploy_callback", help_heading = "Callback Simulation")]
+    callback_address: Option<Address>,
+    /// Deploy a MockCallback consumer for the program and set it as the callback of each request.
+    #[clap(long, help_heading = "Callback Simulation")]
+    deploy_callback: bool,
+    /// Gas limit for the callback of each request.
+    #[clap(long, default_value = "100000", help_heading = "Callback Simulation")]
+    callback_gas_limit: u64,
+
+    /// Maximum amount of ETH to spend per day, counting the max price of each offer and the gas
+    /// paid for onchain submissions.
+    ///
+    /// Submission is paused with an alert while the next request would exceed the budget over
+    /// the last 24 hours, and resumes once earlier spending falls out of that window.
+    #[clap(long, env, value_parser = parse_ether)]
+    max_spend_eth_per_day: Option<U256>,
+
+    /// Seed of the random generator of cycle counts and journal nonces.
+    ///
+    /// If unspecified, a random seed is used. The seed is logged at startup either way, so a run
+    /// can be reproduced by passing it back. Request IDs stay random, as they must be unique
+    /// onchain.
+    #[clap(long, env = "ORDER_GENERATOR_SEED")]
+    seed: Option<u64>,
+
+    /// Reclaim the payment of requests that were locked but expired unfulfilled.
+    ///
+    /// The prover of each such request is slashed once it expires, unless already slashed, which
+    /// credits the payment back to the generator's market balance. Failed attempts are retried
+    /// with a backoff, up to 5 times per request.
+    #[clap(long, env)]
+    reclaim_expired: bool,
+
+    /// Path to write a summary report of the run to, once --count requests are submitted or the
+    /// generator is stopped with Ctrl-C or SIGTERM.
+    ///
+    /// The report covers submissions, fulfillment rates, latency percentiles, spend, and errors.
+    /// With --count, it is written once every submitted request is fulfilled or expired.
+    #[clap(long, help_heading = "Report")]
+    report: Option<PathBuf>,
+    /// Format of the report. Defaults to HTML for paths ending in .html, and markdown otherwise.
+    #[clap(long, value_enum, help_heading = "Report")]
+    report_format: Option<ReportFormat>,
+
+    /// Submit no requests, and instead monitor the requests of other requestors on the market.
+    ///
+    /// The fulfillment rate and latency of the monitored requests are logged every --interval
+    /// seconds, and the latency SLO and report options apply to them, with latencies measured
+    /// from the bidding start of each request. Requests submitted offchain are only seen once
+    /// locked. Requests are dropped from the statistics a day after they expire.
+    #[clap(long, help_heading = "Monitor")]
+    monitor: bool,
+    /// Only monitor the requests of these requestors. If unspecified, all requests are monitored.
+    #[clap(long, value_delimiter = ',', requires = "monitor", help_heading = "Monitor")]
+    monitor_requestors: Vec<Address>,
+    /// Block from which to monitor requests. If unspecified, monitoring starts at the latest block.
+    #[clap(long, requires = "monitor", help_heading = "Monitor")]
+    monitor_from_block: Option<u64>,
+
+    /// Inject faults into the RPC requests of the generator, e.g.
+    /// `error=0.05,drop=0.01,delay=0.2,max_delay=3`.
+    ///
+    /// `error` is the fraction of requests failing with a retryable HTTP 503 without being sent,
+    /// `drop` the fraction sent but whose response is lost, and `delay` the fraction sent after a
+    /// random delay of up to `max_delay` seconds. Meant for game days, to check that monitoring
+    /// and retries cope with a degraded endpoint.
+    #[clap(long, env, help_heading = "Chaos")]
+    chaos_rpc: Option<FaultRates>,
+    /// Inject faults into the program upload and fetch of the generator, in the format of
+    /// --chaos-rpc.
+    #[clap(long, env, help_heading = "Chaos")]
+    chaos_storage: Option<FaultRates>,
+
+    /// Prove and fulfill the requests that are still unlocked this many seconds after their
+    /// bidding start, with the prover backend configured below.
+    ///
+    /// Keeps the latency statistics meaningful on test networks where no other provers are
+    /// active. Self-fulfilled requests are reported apart from those fulfilled by provers.
+    #[cfg(feature = "self-fulfill")]
+    #[clap(long, env, help_heading = "Self-Fulfillment")]
+    self_fulfill_after: Option<u64>,
+    /// Prover backend used to fulfill requests with --self-fulfill-after.
+    #[cfg(feature = "self-fulfill")]
+    #[clap(flatten, next_help_heading = "Self-Fulfillment")]
+    prover_config: ProverConfig,
+
     /// Storage provider to use.
     #[clap(flatten, next_help_heading = "Storage Provider")]
     storage_config: StorageProviderConfig,
 }
 
+/// Number of attempts at the storage calls of the generator before giving up.
+const STORAGE_ATTEMPTS: u32 = 3;
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(Redactor::from_env().make_writer(std::io::stdout))
         .with_ansi(false)
         .with_span_events(FmtSpan::CLOSE)
         .json()
         .init();
 
-    let args = MainArgs::parse();
+    let matches = MainArgs::command().get_matches();
+    let mut args = MainArgs::from_arg_matches(&matches)?;
+    if let Some(preset) = args.preset {
+        preset.apply(&mut args, &matches);
+    }
+
+    // The report also collects the statistics logged in monitor-only mode.
+    let report = (args.report.is_some() || args.monitor).then(|| {
+        let mut report = RunReport::new(slo::now());
+        if args.monitor {
+            report.set_monitor();
+        }
+        Mutex::new(report)
+    });
 
     // NOTE: Using a separate `run` function to facilitate testing below.
-    let result = run(&args).await;
+    let task = async {
+        match &report {
+            Some(report) if args.monitor => monitor(&args, report).await,
+            _ => run(&args, report.as_ref()).await,
+        }
+    };
+    let result = tokio::select! {
+        result = task => result,
+        _ = shutdown_signal() => {
+            tracing::info!("Received shutdown signal, stopping");
+            Ok(())
+        }
+    };
+    if let (Some(path), Some(report)) = (&args.report, report) {
+        let format = args.report_format.unwrap_or_else(|| ReportFormat::for_path(path));
+        match report.into_inner().write(path, format, slo::now()) {
+            Ok(()) => tracing::info!("Wrote report to {}", path.display()),
+            Err(e) => tracing::error!("Failed to write report: {e:?}"),
+        }
+    }
     if let Err(e) = result {
         tracing::error!("FATAL: {:?}", e);
+        // A violated SLO is reported through the exit status, so the generator can be used as
+        // a monitoring probe.
+        if e.is::<SloViolation>() {
+            return Err(e);
+        }
     }
 
     Ok(())
 }
 
-async fn run(args: &MainArgs) -> Result<()> {
-    let wallet = EthereumWallet::from(args.private_key.clone());
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("failed to install Ctrl+C handler");
+    };
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+async fn run(args: &MainArgs, report: Option<&Mutex<RunReport>>) -> Result<()> {
+    let private_key =
+        args.private_key.clone().context("--private-key is required to submit requests")?;
+    let wallet = EthereumWallet::from(private_key.clone());
     let balance_alerts = BalanceAlertConfig {
         watch_address: wallet.default_signer().address(),
         warn_threshold: args.warn_balance_below,
@@ -157,12 +401,16 @@ async fn run(args: &MainArgs) -> Result<()> {
     };
 
     let client = Client::builder()
+        .requestor_defaults()
         .with_rpc_url(args.rpc_url.clone())
         .with_storage_provider_config(&args.storage_config)?
         .with_deployment(args.deployment.clone())
-        .with_private_key(args.private_key.clone())
+        .with_private_key(private_key)
         .with_balance_alerts(balance_alerts)
         .with_timeout(Some(Duration::from_secs(args.tx_timeout)))
+        .with_rpc_fault_injection(rpc_faults(args))
+        .with_budget(args.max_spend_eth_per_day.map(|limit| Budget::new().with_max_per_day(limit)))
+        .with_preflight_cycle_limit(args.preflight_cycle_limit)
         .config_offer_layer(|config| {
             config
                 .min_price_per_cycle(args.min_price_per_mcycle >> 20)
@@ -176,11 +424,18 @@ async fn run(args: &MainArgs) -> Result<()> {
         .ipfs_gateway_url
         .clone()
         .unwrap_or(Url::parse("https://gateway.pinata.cloud").unwrap());
+    let storage_faults = args.chaos_storage.map(|rates| {
+        tracing::warn!("Injecting faults into storage calls: {rates}");
+        FaultInjector::new("storage", rates)
+    });
     // Ensure we have both a program and a program URL.
     let program = args.program.as_ref().map(std::fs::read).transpose()?;
     let program_url = match program {
         Some(ref program) => {
-            let program_url = client.upload_program(program).await?;
+            let program_url = storage_call(storage_faults.as_ref(), || async {
+                Ok(client.upload_program(program).await?)
+            })
+            .await?;
             tracing::info!("Uploaded program to {}", program_url);
             program_url
         }
@@ -192,10 +447,52 @@ async fn run(args: &MainArgs) -> Result<()> {
         }
     };
     let program = match program {
-        None => fetch_url(&program_url).await.context("failed to fetch order generator program")?,
+        None => storage_call(storage_faults.as_ref(), || fetch_url(&program_url))
+            .await
+            .context("failed to fetch order generator program")?,
         Some(program) => program,
     };
 
+    let mut slo = slo_tracker(args);
+
+    let mut proof_checker =
+        (args.verify_proofs || args.verify_journals).then(|| ProofChecker::new(args.verify_proofs));
+
+    let mut callback_sim = match args.callback_address {
+        Some(consumer) => Some(CallbackSim::new(&client, consumer).await?),
+        None if args.deploy_callback => {
+            let image_id = B256::from(<[u8; 32]>::from(compute_image_id(&program)?));
+            Some(CallbackSim::deploy(&client, image_id).await?)
+        }
+        None => None,
+    };
+    let callback = callback_sim.as_ref().map(CallbackSim::consumer);
+
+    let mut budget_paused = false;
+
+    let mut reclaimer = args.reclaim_expired.then(Reclaimer::default);
+
+    #[cfg(feature = "self-fulfill")]
+    let mut self_fulfiller = match args.self_fulfill_after {
+        Some(after) => Some(SelfFulfiller::new(&client, &args.prover_config, after).await?),
+        None => None,
+    };
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+    tracing::info!(seed, "Generating requests with seed {seed}; pass --seed {seed} to reproduce");
+    let mut rng = StdRng::seed_from_u64(seed);
+    if let Some(report) = report {
+        let mut report = report.lock().await;
+        report.set_seed(seed);
+        update_report(&client, &mut report).await;
+    }
+
+    let started = Instant::now();
+    if let Some(profile) = &args.profile {
+        tracing::info!("Following load profile {profile} for {}s", profile.duration().as_secs());
+    }
+    let mut phase = None;
+
     let mut i = 0u64;
     loop {
         if let Some(count) = args.count {
@@ -203,98 +500,443 @@ async fn run(args: &MainArgs) -> Result<()> {
                 break;
             }
         }
-        if let Err(e) = handle_request(args, &client, &program, &program_url).await {
-            tracing::error!("Request failed: {e:?}");
+        let iteration_started = Instant::now();
+        let delay = match &args.profile {
+            Some(profile) => match profile.rate_at(started.elapsed()) {
+                Some((index, rate)) => {
+                    if phase.replace(index) != Some(index) {
+                        tracing::info!("Entering phase {} of the load profile", index + 1);
+                    }
+                    tracing::debug!("Submitting at {rate:.3} requests per second");
+                    Duration::from_secs_f64(1.0 / rate)
+                }
+                None => {
+                    tracing::info!("Load profile finished after {i} requests");
+                    break;
+                }
+            },
+            None => Duration::from_secs(args.interval),
+        };
+        let inputs = generate_inputs(args, &mut rng);
+        match handle_request(args, &client, &program, &program_url, callback, inputs).await {
+            Ok((request, journal)) => {
+                let request_id = request.id;
+                if std::mem::take(&mut budget_paused) {
+                    tracing::info!("Spending is back within the daily budget, resumed submission");
+                }
+                if let Some(slo) = slo.as_mut() {
+                    if let Err(e) = slo.track(&client, request_id).await {
+                        tracing::warn!("Failed to track request 0x{request_id:x} for SLO: {e:?}");
+                    }
+                }
+                if let Some(checker) = proof_checker.as_mut() {
+                    if let Err(e) = checker.track(&client, &request, journal).await {
+                        tracing::warn!(
+                            "Failed to track request 0x{request_id:x} for proof checks: {e:?}"
+                        );
+                    }
+                }
+                if let Some(sim) = callback_sim.as_mut() {
+                    sim.track(&request);
+                }
+                if let Some(reclaimer) = reclaimer.as_mut() {
+                    reclaimer.track(&request);
+                }
+                #[cfg(feature = "self-fulfill")]
+                if let Some(fulfiller) = self_fulfiller.as_mut() {
+                    if let Err(e) = fulfiller.track(&client, &request).await {
+                        tracing::warn!(
+                            "Failed to track request 0x{request_id:x} for self-fulfillment: {e:?}"
+                        );
+                    }
+                }
+                if let Some(report) = report {
+                    report.lock().await.record_submitted(
+                        &request,
+                        !args.submit_offchain,
+                        slo::now(),
+                    );
+                }
+            }
+            Err(e) if exceeds_preflight_cycle_limit(&e) => {
+                tracing::warn!("Skipping request past the preflight cycle limit: {e:#}");
+                if let Some(report) = report {
+                    report.lock().await.record_error(&e);
+                }
+            }
+            Err(e) => match e.downcast_ref::<ClientError>() {
+                Some(ClientError::BudgetExceeded(exceeded)) => {
+                    if !std::mem::replace(&mut budget_paused, true) {
+                        tracing::error!("[B-OG-BUDGET] {exceeded}, pausing submission");
+                    } else {
+                        tracing::debug!("Submission paused: {exceeded}");
+                    }
+                    if let Some(report) = report {
+                        report.lock().await.record_budget_refusal();
+                    }
+                    // Requests refused by the budget do not count towards --count.
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+                _ => {
+                    tracing::error!("Request failed: {e:?}");
+                    if let Some(report) = report {
+                        report.lock().await.record_error(&e);
+                    }
+                }
+            },
         }
         i += 1;
+        if let Some(slo) = slo.as_mut() {
+            check_slo(args, &client, slo).await?;
+        }
+        if let Some(checker) = proof_checker.as_mut() {
+            check_proofs(&client, checker).await;
+        }
+        if let Some(sim) = callback_sim.as_mut() {
+            check_callbacks(&client, sim).await;
+        }
+        if let Some(reclaimer) = reclaimer.as_mut() {
+            reclaim_expired(&client, reclaimer).await;
+        }
+        #[cfg(feature = "self-fulfill")]
+        if let Some(fulfiller) = self_fulfiller.as_mut() {
+            self_fulfill(&client, fulfiller, report).await;
+        }
+        if let Some(report) = report {
+            update_report(&client, &mut *report.lock().await).await;
+        }
+        // Under a load profile, the delay is measured from the start of the submission, so that
+        // the time spent submitting does not lower the rate.
+        match args.profile {
+            Some(_) => tokio::time::sleep(delay.saturating_sub(iteration_started.elapsed())).await,
+            None => tokio::time::sleep(delay).await,
+        }
+    }
+
+    // Wait for the outcome of the outstanding requests before the final evaluation.
+    if let Some(slo) = slo.as_mut() {
+        while !slo.is_idle() {
+            check_slo(args, &client, slo).await?;
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+        check_slo(args, &client, slo).await?;
+    }
+    if let Some(checker) = proof_checker.as_mut() {
+        while !checker.is_idle() {
+            check_proofs(&client, checker).await;
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    }
+    if let Some(sim) = callback_sim.as_mut() {
+        while !sim.is_idle() {
+            check_callbacks(&client, sim).await;
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    }
+    #[cfg(feature = "self-fulfill")]
+    if let Some(fulfiller) = self_fulfiller.as_mut() {
+        while !fulfiller.is_idle() {
+            self_fulfill(&client, fulfiller, report).await;
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    }
+    if let Some(reclaimer) = reclaimer.as_mut() {
+        while !reclaimer.is_idle() {
+            reclaim_expired(&client, reclaimer).await;
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    }
+    if let Some(report) = report {
+        loop {
+            let mut report = report.lock().await;
+            update_report(&client, &mut report).await;
+            if report.is_idle(slo::now()) {
+                break;
+            }
+            drop(report);
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Track the requests on the market without submitting any, logging their fulfillment rate and
+/// latency every interval, and evaluating the latency SLO over them if configured.
+async fn monitor(args: &MainArgs, report: &Mutex<RunReport>) -> Result<()> {
+    let client = Client::builder()
+        .with_rpc_url(args.rpc_url.clone())
+        .with_storage_provider_config(&args.storage_config)?
+        .with_deployment(args.deployment.clone())
+        .with_signer(None::<PrivateKeySigner>)
+        .with_timeout(Some(Duration::from_secs(args.tx_timeout)))
+        .with_rpc_fault_injection(rpc_faults(args))
+        .build()
+        .await?;
+
+    let mut slo = slo_tracker(args);
+    let mut monitor = MarketMonitor::new(args.monitor_requestors.clone(), args.monitor_from_block);
+    if args.monitor_requestors.is_empty() {
+        tracing::info!("Monitoring the requests of all requestors");
+    } else {
+        tracing::info!("Monitoring the requests of {:?}", args.monitor_requestors);
+    }
+
+    loop {
+        match monitor.update(&client, slo::now()).await {
+            Ok(observations) => {
+                let mut report = report.lock().await;
+                for observation in observations {
+                    match observation {
+                        Observation::Request { request, onchain } => {
+                            tracing::debug!("Monitoring request 0x{:x}", request.id);
+                            // The submission time of requests submitted offchain is not known
+                            // onchain, so latencies are measured from the bidding start.
+                            let since = request.offer.rampUpStart;
+                            if let Some(slo) = slo.as_mut() {
+                                slo.track_since(request.id, since);
+                            }
+                            report.record_submitted(&request, onchain, since);
+                        }
+                        Observation::Locked { request_id, at } => {
+                            if let Some(slo) = slo.as_mut() {
+                                slo.record_locked(request_id, at);
+                            }
+                            report.record_locked(request_id, at);
+                        }
+                        Observation::Fulfilled { request_id, at } => {
+                            if let Some(slo) = slo.as_mut() {
+                                slo.record_fulfilled(request_id, at);
+                            }
+                            report.record_fulfilled(request_id, at);
+                        }
+                    }
+                }
+                let now = slo::now();
+                report.prune_expired(now.saturating_sub(monitor::REPORT_RETENTION));
+                report.log_summary(now);
+            }
+            Err(e) => tracing::warn!("Failed to query market activity: {e:?}"),
+        }
+        if let Some(slo) = slo.as_mut() {
+            slo.refresh();
+            evaluate_slo(args, slo)?;
+        }
         tokio::time::sleep(Duration::from_secs(args.interval)).await;
     }
+}
+
+/// Injector of the faults into RPC requests configured by --chaos-rpc, if any.
+fn rpc_faults(args: &MainArgs) -> Option<FaultInjector> {
+    args.chaos_rpc.map(|rates| {
+        tracing::warn!("Injecting faults into RPC requests: {rates}");
+        FaultInjector::new("RPC", rates)
+    })
+}
+
+/// Run a storage call, injecting the configured faults, and retrying it with backoff on failure.
+async fn storage_call<T, Fut>(
+    faults: Option<&FaultInjector>,
+    mut call: impl FnMut() -> Fut,
+) -> Result<T>
+where
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        let result = match faults {
+            Some(faults) => faults.inject(call()).await,
+            None => call().await,
+        };
+        match result {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < STORAGE_ATTEMPTS => {
+                tracing::warn!(
+                    "Storage call failed on attempt {attempt}/{STORAGE_ATTEMPTS}, retrying: {e:?}"
+                );
+                tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Tracker of the latency SLO, if any objective is configured.
+fn slo_tracker(args: &MainArgs) -> Option<SloTracker> {
+    (args.slo_lock_within.is_some() || args.slo_fulfill_within.is_some()).then(|| {
+        SloTracker::new(SloConfig {
+            lock_within: args.slo_lock_within,
+            fulfill_within: args.slo_fulfill_within,
+            target: args.slo_target,
+            window: args.slo_window,
+            min_samples: args.slo_min_samples,
+        })
+    })
+}
 
+/// Update and evaluate the latency SLO, alerting on violation.
+///
+/// Returns an error on violation if the generator is configured to exit on SLO violation.
+async fn check_slo(args: &MainArgs, client: &Client, slo: &mut SloTracker) -> Result<()> {
+    if let Err(e) = slo.update(client).await {
+        tracing::warn!("Failed to update SLO status: {e:?}");
+        return Ok(());
+    }
+    evaluate_slo(args, slo)
+}
+
+/// Evaluate the latency SLO over the outcomes recorded so far, alerting on violation.
+fn evaluate_slo(args: &MainArgs, slo: &SloTracker) -> Result<()> {
+    if let Err(violation) = slo.evaluate() {
+        tracing::error!("[B-OG-SLO] {violation}");
+        if args.slo_exit_on_violation {
+            return Err(violation.into());
+        }
+    }
     Ok(())
 }
 
-async fn handle_request(
-    args: &MainArgs,
+/// Verify the proofs delivered since the last check.
+async fn check_proofs(client: &Client, checker: &mut ProofChecker) {
+    if let Err(e) = checker.update(client).await {
+        tracing::warn!("Failed to check delivered proofs: {e:?}");
+    }
+}
+
+/// Check that the callback consumer received the callbacks of the requests fulfilled so far.
+async fn check_callbacks(client: &Client, sim: &mut CallbackSim) {
+    match sim.update(client).await {
+        Ok(Ok(())) => {}
+        Ok(Err(mismatch)) => tracing::error!("[B-OG-CALLBACK] {mismatch}"),
+        Err(e) => tracing::warn!("Failed to check callback consumer: {e:?}"),
+    }
+}
+
+/// Record the outcomes of the submitted requests since the last update in the report.
+async fn update_report(client: &Client, report: &mut RunReport) {
+    if let Err(e) = report.update(client).await {
+        tracing::warn!("Failed to update the run report: {e:?}");
+    }
+}
+
+/// Reclaim the payment of the tracked requests that expired since the last check.
+async fn reclaim_expired(client: &Client, reclaimer: &mut Reclaimer) {
+    if let Err(e) = reclaimer.update(client).await {
+        tracing::warn!("Failed to reclaim expired requests: {e:?}");
+    }
+}
+
+/// Fulfill the tracked requests that no prover locked in time, recording them in the report.
+#[cfg(feature = "self-fulfill")]
+async fn self_fulfill(
     client: &Client,
-    program: &[u8],
-    program_url: &url::Url,
-) -> Result<()> {
-    let mut rng = rand::rng();
+    fulfiller: &mut SelfFulfiller,
+    report: Option<&Mutex<RunReport>>,
+) {
+    match fulfiller.update(client).await {
+        Ok(fulfilled) => {
+            if let Some(report) = report {
+                let mut report = report.lock().await;
+                for request_id in fulfilled {
+                    report.record_self_fulfilled(request_id);
+                }
+            }
+        }
+        Err(e) => tracing::warn!("Failed to self-fulfill requests: {e:?}"),
+    }
+}
+
+/// Generate the cycle count and nonce of the next request from the seeded `rng`.
+///
+/// Both values are always drawn, so that the sequence of nonces does not depend on `--input`.
+fn generate_inputs(args: &MainArgs, rng: &mut impl Rng) -> ExpectedJournal {
     let nonce: u64 = rng.random();
+    let max = args.input_max_mcycles.unwrap_or(1000);
+    let random_input: u64 = rng.random_range(1..=max) << 20;
     let input = match args.input {
         Some(input) => input,
         None => {
-            // Generate a random input.
-            let max = args.input_max_mcycles.unwrap_or(1000);
-            let input: u64 = rand::rng().random_range(1..=max) << 20;
-            tracing::debug!("Generated random cycle count: {}", input);
-            input
+            tracing::debug!("Generated random cycle count: {}", random_input);
+            random_input
         }
     };
+    ExpectedJournal { input, nonce }
+}
+
+/// Whether building the request failed because its guest ran past the preflight cycle limit.
+fn exceeds_preflight_cycle_limit(err: &anyhow::Error) -> bool {
+    err.chain().any(|err| {
+        matches!(err.downcast_ref::<PreflightError>(), Some(PreflightError::CycleLimitExceeded(_)))
+    })
+}
+
+async fn handle_request(
+    args: &MainArgs,
+    client: &Client,
+    program: &[u8],
+    program_url: &url::Url,
+    callback: Option<Address>,
+    expected_journal: ExpectedJournal,
+) -> Result<(ProofRequest, ExpectedJournal)> {
+    let ExpectedJournal { input, nonce } = expected_journal;
     let env = GuestEnv::builder().write(&(input as u64))?.write(&nonce)?.build_env();
 
-    // add 1 minute for each 1M cycles to the original timeout
     // Use the input directly as the estimated cycle count, since we are using a loop program.
     let m_cycles = input >> 20;
-    let seconds_for_mcycles = args.seconds_per_mcycle.checked_mul(m_cycles as u32).unwrap();
-    let ramp_up_seconds_for_mcycles =
-        args.ramp_up_seconds_per_mcycle.checked_mul(m_cycles as u32).unwrap();
-    let ramp_up = args.ramp_up + ramp_up_seconds_for_mcycles;
-    let lock_timeout = args.lock_timeout + seconds_for_mcycles;
-    tracing::debug!(
-        "m_cycles: {}, seconds_for_mcycles: {}, ramp_up [{} + {}]: {}, lock_timeout [{} + {}]: {}",
-        m_cycles,
-        seconds_for_mcycles,
-        args.ramp_up,
-        ramp_up_seconds_for_mcycles,
-        ramp_up,
-        args.lock_timeout,
-        seconds_for_mcycles,
-        lock_timeout
-    );
-    // Give equal time for provers that are fulfilling after lock expiry to prove.
-    let timeout: u32 = args.timeout + lock_timeout + seconds_for_mcycles;
+    let stats = MarketStats::builder()
+        .proving_rate((1u64 << 20).checked_div(args.seconds_per_mcycle as u64).unwrap_or(u64::MAX))
+        .execution_rate(args.exec_rate_khz.saturating_mul(1000))
+        .lock_latency(args.ramp_up + args.ramp_up_seconds_per_mcycle * m_cycles as u32)
+        .min_bidding_start_delay(args.bidding_start_delay.unwrap_or(30))
+        .build()?;
 
-    // Provide journal and cycles in order to skip preflighting, allowing us to send requests faster.
-    let journal = Journal::new([input.to_le_bytes(), nonce.to_le_bytes()].concat());
-
-    // Calculate bidding_start timestamp
+    // Extend the lock timeout by the time needed to prove the request, and give equal time for
+    // provers that are fulfilling after lock expiry to prove.
     let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
-
-    let bidding_start = if let Some(delay) = args.bidding_start_delay {
-        // Use provided delay
-        now + delay
-    } else {
-        // Calculate delay based on execution time using configured execution rate
-        // mcycles * 1000 = kcycles, then divide by exec_rate_khz to get seconds
-        let exec_time_seconds = (m_cycles.saturating_mul(1000)).div_ceil(args.exec_rate_khz);
-        let delay = std::cmp::max(30, exec_time_seconds);
-
-        tracing::debug!(
-            "Calculated bidding_start_delay: {} seconds (based on {} mcycles at {} kHz exec rate)",
-            delay,
-            m_cycles,
-            args.exec_rate_khz
-        );
-
-        now + delay
+    let proving_time = stats.proving_time(input);
+    let lock_timeout = args.lock_timeout as u64 + proving_time;
+    let timeout = args.timeout as u64 + lock_timeout + proving_time;
+    let deadline = now + stats.bidding_start_delay(input) + timeout;
+    let lock_timeout: u32 = lock_timeout.try_into().context("lock timeout too large")?;
+    let offer = OfferParams {
+        ramp_up_period: Some(stats.lock_latency.min(lock_timeout)),
+        lock_timeout: Some(lock_timeout),
+        timeout: Some(timeout.try_into().context("timeout too large")?),
+        ..OfferParams::from_deadline_at(input, deadline, &stats, now)?
     };
+    tracing::debug!(
+        "m_cycles: {}, bidding_start: {:?}, ramp_up: {:?}, lock_timeout: {:?}, timeout: {:?}",
+        m_cycles,
+        offer.bidding_start,
+        offer.ramp_up_period,
+        offer.lock_timeout,
+        offer.timeout
+    );
 
-    let request = client
+    let mut request = client
         .new_request()
         .with_program(program.to_vec())
         .with_program_url(program_url.clone())?
         .with_env(env)
-        .with_cycles(input)
-        .with_journal(journal)
-        .with_offer(
-            OfferParams::builder()
-                .ramp_up_period(ramp_up)
-                .lock_timeout(lock_timeout)
-                .timeout(timeout)
-                .lock_collateral(args.lock_collateral_raw)
-                .bidding_start(bidding_start),
+        .with_offer(OfferParams { lock_collateral: Some(args.lock_collateral_raw), ..offer });
+    // Provide journal and cycles in order to skip preflighting, allowing us to send requests
+    // faster, unless preflight is requested with a cycle limit.
+    if args.preflight_cycle_limit.is_none() {
+        request =
+            request.with_cycles(input).with_journal(Journal::new(expected_journal.to_bytes()));
+    }
+    if let Some(callback) = callback {
+        request = request.with_requirements(
+            RequirementParams::builder()
+                .callback_address(callback)
+                .callback_gas_limit(args.callback_gas_limit),
         );
+    }
+    if args.groth16 {
+        request = request.with_groth16_proof();
+    }
 
     // Build the request, including preflight, and assigned the remaining fields.
     let request = client.build_request(request).await?;
@@ -309,6 +951,7 @@ async fn handle_request(
     );
 
     let submit_offchain = args.submit_offchain;
+    let max_price = request.offer.maxPrice;
 
     // Check balance and auto-deposit if needed for both onchain and offchain submissions
     if let Some(auto_deposit) = args.auto_deposit {
@@ -342,12 +985,32 @@ async fn handle_request(
         }
     }
 
+    // Measure the gas of onchain submissions as the drop in wallet balance, less the value sent
+    // to the market to cover the max price, and charge it to the budget.
+    let balances_before = match (&client.budget, submit_offchain) {
+        (Some(_), false) => {
+            let caller = client.caller();
+            Some((
+                client.provider().get_balance(caller).await?,
+                client.boundless_market.balance_of(caller).await?,
+            ))
+        }
+        _ => None,
+    };
+
     let (request_id, _) = if submit_offchain {
         client.submit_request_offchain(&request).await?
     } else {
         client.submit_request_onchain(&request).await?
     };
 
+    if let (Some(budget), Some((wallet_before, market_before))) = (&client.budget, balances_before)
+    {
+        let wallet_after = client.provider().get_balance(client.caller()).await?;
+        let value = max_price.saturating_sub(market_before);
+        budget.charge(wallet_before.saturating_sub(wallet_after).saturating_sub(value));
+    }
+
     if submit_offchain {
         tracing::info!(
             "Request 0x{request_id:x} submitted offchain to {}",
@@ -359,7 +1022,7 @@ async fn handle_request(
             client.deployment.boundless_market_address,
         );
     }
-    Ok(())
+    Ok((request, expected_journal))
 }
 
 #[cfg(test)]
@@ -382,10 +1045,11 @@ mod tests {
         let args = MainArgs {
             rpc_url: anvil.endpoint_url(),
             storage_config: StorageProviderConfig::dev_mode(),
-            private_key: ctx.customer_signer,
+            private_key: Some(ctx.customer_signer),
             deployment: Some(ctx.deployment.clone()),
             interval: 1,
             count: Some(2),
+            profile: None,
             min_price_per_mcycle: parse_ether("0.001").unwrap(),
             max_price_per_mcycle: parse_ether("0.002").unwrap(),
             lock_collateral_raw: parse_ether("0.0").unwrap(),
@@ -399,14 +1063,47 @@ mod tests {
             program: Some(LOOP_PATH.parse().unwrap()),
             input: None,
             input_max_mcycles: None,
+            preflight_cycle_limit: None,
             warn_balance_below: None,
             error_balance_below: None,
             auto_deposit: None,
             tx_timeout: 45,
             submit_offchain: false,
+            preset: None,
+            slo_lock_within: None,
+            slo_fulfill_within: None,
+            slo_target: 0.95,
+            slo_window: 20,
+            slo_min_samples: 5,
+            slo_exit_on_violation: false,
+            groth16: false,
+            verify_proofs: false,
+            verify_journals: false,
+            callback_address: None,
+            deploy_callback: false,
+            callback_gas_limit: 100000,
+            max_spend_eth_per_day: None,
+            seed: None,
+            reclaim_expired: false,
+            report: None,
+            report_format: None,
+            monitor: false,
+            monitor_requestors: Vec::new(),
+            monitor_from_block: None,
+            chaos_rpc: None,
+            chaos_storage: None,
+            #[cfg(feature = "self-fulfill")]
+            self_fulfill_after: None,
+            #[cfg(feature = "self-fulfill")]
+            prover_config: ProverConfig {
+                bento_api_key: None,
+                bento_api_url: "".to_string(),
+                use_default_prover: true,
+                skip_health_check: true,
+            },
         };
 
-        run(&args).await.unwrap();
+        run(&args, None).await.unwrap();
 
         // Check that the requests were submitted
         let filter = Filter::new()
@@ -425,4 +1122,62 @@ mod tests {
         });
         assert!(decoded_logs.count() == 2);
     }
+
+    #[test]
+    fn test_exceeds_preflight_cycle_limit() {
+        let err = anyhow::Error::from(ClientError::from(
+            anyhow::Error::new(PreflightError::CycleLimitExceeded(100))
+                .context("failed to preflight request"),
+        ));
+        assert!(exceeds_preflight_cycle_limit(&err));
+        assert!(!exceeds_preflight_cycle_limit(&anyhow::anyhow!("session limit exceeded")));
+    }
+
+    #[test]
+    fn test_seeded_inputs_are_deterministic() {
+        let private_key = hex::encode(PrivateKeySigner::random().to_bytes());
+        let args = MainArgs::try_parse_from([
+            "order-generator",
+            "--rpc-url",
+            "http://localhost:8545",
+            "--private-key",
+            &private_key,
+        ])
+        .unwrap();
+        let sequence = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..5).map(|_| generate_inputs(&args, &mut rng)).collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(42), sequence(42));
+        assert_ne!(sequence(42), sequence(43));
+        assert!(sequence(42).iter().all(|journal| journal.input % (1 << 20) == 0));
+    }
+
+    #[test]
+    fn test_preset_explicit_args_take_precedence() {
+        let private_key = hex::encode(PrivateKeySigner::random().to_bytes());
+        let matches = MainArgs::command()
+            .try_get_matches_from([
+                "order-generator",
+                "--rpc-url",
+                "http://localhost:8545",
+                "--private-key",
+                &private_key,
+                "--preset",
+                "sepolia-stress",
+                "--interval",
+                "42",
+            ])
+            .unwrap();
+        let mut args = MainArgs::from_arg_matches(&matches).unwrap();
+        args.preset.unwrap().apply(&mut args, &matches);
+
+        let values = Preset::SepoliaStress.values();
+        assert_eq!(args.interval, 42);
+        assert_eq!(args.min_price_per_mcycle, values.min_price_per_mcycle);
+        assert_eq!(args.max_price_per_mcycle, values.max_price_per_mcycle);
+        assert_eq!(args.input_max_mcycles, Some(values.input_max_mcycles));
+        assert_eq!(args.lock_timeout, values.lock_timeout);
+        assert_eq!(args.timeout, values.timeout);
+    }
 }
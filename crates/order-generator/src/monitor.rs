@@ -0,0 +1,146 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Monitor-only mode, tracking the requests of all requestors on the market without submitting any.
+
+use std::collections::HashMap;
+
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::Log,
+};
+use anyhow::Result;
+use boundless_market::{
+    client::Client,
+    contracts::{IBoundlessMarket::RequestSubmitted, ProofRequest, RequestId},
+};
+
+use crate::slo::{lock_and_fulfill_events, market_events};
+
+/// Activity of a monitored request on the market.
+#[derive(Debug)]
+pub(crate) enum Observation {
+    /// A request seen for the first time, either submitted onchain or locked after being
+    /// submitted offchain.
+    Request { request: ProofRequest, onchain: bool },
+    /// A monitored request was locked at the given timestamp.
+    Locked { request_id: U256, at: u64 },
+    /// A monitored request was fulfilled at the given timestamp.
+    Fulfilled { request_id: U256, at: u64 },
+}
+
+/// Time, in seconds, for which the requests observed by a [MarketMonitor] are kept in the run
+/// report after they expire, so that the statistics of a long-running monitor cover recent
+/// requests and its memory use stays bounded.
+pub(crate) const REPORT_RETENTION: u64 = 24 * 60 * 60;
+
+/// Watches the market for the requests of the given requestors, or of all requestors.
+///
+/// Requests submitted offchain are only visible onchain once locked, so those that expire
+/// without being locked, or that are fulfilled without being locked, are not observed.
+pub(crate) struct MarketMonitor {
+    requestors: Vec<Address>,
+    /// Expiry of each request observed so far, to ignore the requests of other requestors
+    /// and forget requests that can no longer be fulfilled.
+    seen: HashMap<U256, u64>,
+    next_block: Option<u64>,
+}
+
+impl MarketMonitor {
+    /// Monitor the requests of `requestors`, or of all requestors if empty, starting at
+    /// `from_block`, or the current block if unspecified.
+    pub(crate) fn new(requestors: Vec<Address>, from_block: Option<u64>) -> Self {
+        Self { requestors, seen: HashMap::new(), next_block: from_block }
+    }
+
+    fn is_monitored(&self, request_id: U256) -> bool {
+        self.requestors.is_empty()
+            || self.requestors.contains(&RequestId::from_lossy(request_id).addr)
+    }
+
+    /// Record a request, returning whether it was seen for the first time.
+    fn observe(&mut self, request: &ProofRequest) -> bool {
+        self.is_monitored(request.id)
+            && self.seen.insert(request.id, request.expires_at()).is_none()
+    }
+
+    /// Query the market activity of monitored requests since the last update.
+    ///
+    /// Requests are only marked as seen, and the blocks as scanned, once every query succeeded,
+    /// so that a failed update is retried over the same range by the next one.
+    pub(crate) async fn update(&mut self, client: &Client, now: u64) -> Result<Vec<Observation>> {
+        let to = client.provider().get_block_number().await?;
+        let from = *self.next_block.get_or_insert(to);
+        if to < from {
+            return Ok(Vec::new());
+        }
+        let submitted: Vec<(RequestSubmitted, Log)> = market_events(client, from, to).await?;
+        let events =
+            lock_and_fulfill_events(client, from, to, |request_id| self.is_monitored(request_id))
+                .await?;
+
+        let mut observations = Vec::new();
+        for (event, _) in submitted {
+            if self.observe(&event.request) {
+                observations.push(Observation::Request { request: event.request, onchain: true });
+            }
+        }
+        for (event, at) in events.locked {
+            if self.observe(&event.request) {
+                observations.push(Observation::Request { request: event.request, onchain: false });
+            }
+            observations.push(Observation::Locked { request_id: event.requestId, at });
+        }
+        for (event, at) in events.fulfilled {
+            if self.seen.contains_key(&event.requestId) {
+                observations.push(Observation::Fulfilled { request_id: event.requestId, at });
+            }
+        }
+        self.next_block = Some(to + 1);
+
+        // Expired requests can no longer be locked or fulfilled.
+        self.seen.retain(|_, expires_at| *expires_at >= now);
+        Ok(observations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use boundless_market::contracts::Offer;
+
+    use super::*;
+
+    fn request(requestor: Address, index: u32) -> ProofRequest {
+        ProofRequest {
+            id: RequestId::new(requestor, index).into(),
+            offer: Offer { rampUpStart: 1000, timeout: 600, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_requestor_filter() {
+        let (alice, bob) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        let mut monitor = MarketMonitor::new(vec![alice], None);
+        assert!(monitor.observe(&request(alice, 1)));
+        assert!(!monitor.observe(&request(alice, 1)), "request already observed");
+        assert!(!monitor.observe(&request(bob, 1)), "request of another requestor");
+
+        let mut monitor = MarketMonitor::new(Vec::new(), None);
+        assert!(monitor.observe(&request(alice, 1)));
+        assert!(monitor.observe(&request(bob, 1)));
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preset bundles of order generator settings for standardized load profiles.
+
+use alloy::primitives::{utils::parse_ether, U256};
+use clap::{parser::ValueSource, ArgMatches, ValueEnum};
+
+use crate::MainArgs;
+
+/// Named load profile that sets interval, pricing, cycle distribution, and timeouts.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Preset {
+    /// Infrequent, small requests to keep Sepolia provers exercised.
+    SepoliaLight,
+    /// Frequent requests across a wide cycle range, to load test Sepolia provers.
+    SepoliaStress,
+    /// Occasional small requests on Base mainnet, to canary production fulfillment.
+    BaseProdCanary,
+}
+
+/// Values set by a [Preset].
+#[derive(Clone, Debug)]
+pub(crate) struct PresetValues {
+    pub(crate) interval: u64,
+    pub(crate) min_price_per_mcycle: U256,
+    pub(crate) max_price_per_mcycle: U256,
+    /// Upper bound of the random cycle count, in millions of cycles.
+    pub(crate) input_max_mcycles: u64,
+    pub(crate) ramp_up: u32,
+    pub(crate) lock_timeout: u32,
+    pub(crate) timeout: u32,
+    pub(crate) seconds_per_mcycle: u32,
+    pub(crate) ramp_up_seconds_per_mcycle: u32,
+}
+
+impl Preset {
+    /// Values bundled with this preset.
+    pub(crate) fn values(self) -> PresetValues {
+        match self {
+            Preset::SepoliaLight => PresetValues {
+                interval: 300,
+                min_price_per_mcycle: parse_ether("0.0005").unwrap(),
+                max_price_per_mcycle: parse_ether("0.001").unwrap(),
+                input_max_mcycles: 100,
+                ramp_up: 240,
+                lock_timeout: 900,
+                timeout: 1800,
+                seconds_per_mcycle: 20,
+                ramp_up_seconds_per_mcycle: 20,
+            },
+            Preset::SepoliaStress => PresetValues {
+                interval: 10,
+                min_price_per_mcycle: parse_ether("0.001").unwrap(),
+                max_price_per_mcycle: parse_ether("0.005").unwrap(),
+                input_max_mcycles: 1000,
+                ramp_up: 120,
+                lock_timeout: 600,
+                timeout: 1200,
+                seconds_per_mcycle: 10,
+                ramp_up_seconds_per_mcycle: 10,
+            },
+            Preset::BaseProdCanary => PresetValues {
+                interval: 600,
+                min_price_per_mcycle: parse_ether("0.0001").unwrap(),
+                max_price_per_mcycle: parse_ether("0.0005").unwrap(),
+                input_max_mcycles: 50,
+                ramp_up: 300,
+                lock_timeout: 1200,
+                timeout: 2400,
+                seconds_per_mcycle: 30,
+                ramp_up_seconds_per_mcycle: 20,
+            },
+        }
+    }
+
+    /// Apply this preset to the given arguments.
+    ///
+    /// Values given explicitly on the command line or through the environment take precedence
+    /// over the preset, so a preset can be used as a base and adjusted with individual flags.
+    pub(crate) fn apply(self, args: &mut MainArgs, matches: &ArgMatches) {
+        let values = self.values();
+        let is_default = |id: &str| {
+            !matches!(
+                matches.value_source(id),
+                Some(ValueSource::CommandLine) | Some(ValueSource::EnvVariable)
+            )
+        };
+
+        if is_default("interval") {
+            args.interval = values.interval;
+        }
+        if is_default("min_price_per_mcycle") {
+            args.min_price_per_mcycle = values.min_price_per_mcycle;
+        }
+        if is_default("max_price_per_mcycle") {
+            args.max_price_per_mcycle = values.max_price_per_mcycle;
+        }
+        // The cycle distribution only applies when the cycle count is not fixed.
+        if is_default("input_max_mcycles") && args.input.is_none() && args.program.is_none() {
+            args.input_max_mcycles = Some(values.input_max_mcycles);
+        }
+        if is_default("ramp_up") {
+            args.ramp_up = values.ramp_up;
+        }
+        if is_default("lock_timeout") {
+            args.lock_timeout = values.lock_timeout;
+        }
+        if is_default("timeout") {
+            args.timeout = values.timeout;
+        }
+        if is_default("seconds_per_mcycle") {
+            args.seconds_per_mcycle = values.seconds_per_mcycle;
+        }
+        if is_default("ramp_up_seconds_per_mcycle") {
+            args.ramp_up_seconds_per_mcycle = values.ramp_up_seconds_per_mcycle;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Load profiles that vary the submission rate of the order generator over time.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::{bail, ensure, Context, Result};
+
+/// One phase of a [LoadProfile], over which the rate changes linearly from `from` to `to`.
+#[derive(Clone, Debug, PartialEq)]
+struct Phase {
+    duration: Duration,
+    /// Rate at the start of the phase, in requests per second.
+    from: f64,
+    /// Rate at the end of the phase, in requests per second.
+    to: f64,
+}
+
+/// Schedule of submission rates, made of consecutive phases.
+///
+/// Phases are separated by commas, and each is either a linear ramp between two rates or a
+/// constant rate held for a duration:
+///
+/// ```text
+/// ramp:10m:1rps-10rps,hold:5m:10rps,ramp:10m:10rps-1rps
+/// ```
+///
+/// Durations take an `s`, `m`, or `h` suffix, and rates an `rps` (requests per second) or `rpm`
+/// (requests per minute) suffix. The generator stops once the last phase ends.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LoadProfile {
+    phases: Vec<Phase>,
+}
+
+impl LoadProfile {
+    /// Index of the phase and submission rate, in requests per second, at `elapsed` since the
+    /// start of the profile. Returns `None` once the profile has ended.
+    pub(crate) fn rate_at(&self, elapsed: Duration) -> Option<(usize, f64)> {
+        let mut start = Duration::ZERO;
+        for (index, phase) in self.phases.iter().enumerate() {
+            let offset = elapsed.saturating_sub(start);
+            if offset < phase.duration {
+                let progress = offset.as_secs_f64() / phase.duration.as_secs_f64();
+                return Some((index, phase.from + (phase.to - phase.from) * progress));
+            }
+            start += phase.duration;
+        }
+        None
+    }
+
+    /// Total duration of the profile.
+    pub(crate) fn duration(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.duration).sum()
+    }
+}
+
+impl FromStr for LoadProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let phases = s
+            .split(',')
+            .map(|phase| parse_phase(phase).with_context(|| format!("invalid phase {phase:?}")))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { phases })
+    }
+}
+
+impl fmt::Display for LoadProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, phase) in self.phases.iter().enumerate() {
+            if index > 0 {
+                write!(f, ",")?;
+            }
+            let secs = phase.duration.as_secs();
+            if phase.from == phase.to {
+                write!(f, "hold:{secs}s:{}rps", phase.from)?;
+            } else {
+                write!(f, "ramp:{secs}s:{}rps-{}rps", phase.from, phase.to)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_phase(s: &str) -> Result<Phase> {
+    let mut parts = s.trim().splitn(3, ':');
+    let (Some(kind), Some(duration), Some(rates)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("expected <ramp|hold>:<duration>:<rate>");
+    };
+    let duration = parse_duration(duration)?;
+    let (from, to) = match kind {
+        "ramp" => {
+            let (from, to) =
+                rates.split_once('-').context("expected a ramp rate like 1rps-10rps")?;
+            (parse_rate(from)?, parse_rate(to)?)
+        }
+        "hold" => {
+            let rate = parse_rate(rates)?;
+            (rate, rate)
+        }
+        _ => bail!("unknown phase kind {kind:?}; expected ramp or hold"),
+    };
+    Ok(Phase { duration, from, to })
+}
+
+fn parse_duration(s: &str) -> Result<Duration> {
+    let (value, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let value: u64 = value.parse().with_context(|| format!("invalid duration {s:?}"))?;
+    let secs = match unit {
+        "s" => value,
+        "m" => value * 60,
+        "h" => value * 60 * 60,
+        _ => bail!("invalid duration {s:?}; expected a suffix of s, m, or h"),
+    };
+    ensure!(secs > 0, "duration must be positive");
+    Ok(Duration::from_secs(secs))
+}
+
+/// Parse a rate into requests per second.
+fn parse_rate(s: &str) -> Result<f64> {
+    let (value, per_secs) = if let Some(value) = s.strip_suffix("rps") {
+        (value, 1.0)
+    } else if let Some(value) = s.strip_suffix("rpm") {
+        (value, 60.0)
+    } else {
+        bail!("invalid rate {s:?}; expected a suffix of rps or rpm");
+    };
+    let value: f64 = value.parse().with_context(|| format!("invalid rate {s:?}"))?;
+    ensure!(value.is_finite() && value > 0.0, "rate must be positive");
+    Ok(value / per_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_profile() {
+        let profile: LoadProfile = "ramp:10m:1rps-10rps,hold:30s:30rpm".parse().unwrap();
+        assert_eq!(
+            profile.phases,
+            vec![
+                Phase { duration: Duration::from_secs(600), from: 1.0, to: 10.0 },
+                Phase { duration: Duration::from_secs(30), from: 0.5, to: 0.5 },
+            ]
+        );
+        assert_eq!(profile.duration(), Duration::from_secs(630));
+        assert_eq!(profile.to_string(), "ramp:600s:1rps-10rps,hold:30s:0.5rps");
+
+        assert!("ramp:10m:1rps".parse::<LoadProfile>().is_err());
+        assert!("ramp:10x:1rps-2rps".parse::<LoadProfile>().is_err());
+        assert!("hold:0s:1rps".parse::<LoadProfile>().is_err());
+        assert!("hold:1m:0rps".parse::<LoadProfile>().is_err());
+        assert!("spike:1m:1rps".parse::<LoadProfile>().is_err());
+    }
+
+    #[test]
+    fn rate_follows_schedule() {
+        let profile: LoadProfile = "ramp:100s:1rps-11rps,hold:10s:2rps".parse().unwrap();
+        assert_eq!(profile.rate_at(Duration::ZERO), Some((0, 1.0)));
+        assert_eq!(profile.rate_at(Duration::from_secs(50)), Some((0, 6.0)));
+        assert_eq!(profile.rate_at(Duration::from_secs(100)), Some((1, 2.0)));
+        assert_eq!(profile.rate_at(Duration::from_secs(105)), Some((1, 2.0)));
+        assert_eq!(profile.rate_at(Duration::from_secs(110)), None);
+    }
+}
@@ -0,0 +1,416 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Verification of delivered proofs, catching provers that fulfill with the wrong proof type,
+//! with an invalid seal, or with a journal other than the one the generated request expects.
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    primitives::{Bytes, FixedBytes, U256},
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol,
+    sol_types::SolEvent,
+};
+use anyhow::{Context, Result};
+use boundless_market::{
+    client::Client,
+    contracts::{
+        Fulfillment, FulfillmentData, IBoundlessMarket::ProofDelivered, ProofRequest,
+        UNSPECIFIED_SELECTOR,
+    },
+    log_query::query_events_chunked,
+    selector::SupportedSelectors,
+};
+use risc0_zkvm::sha::Digest;
+
+/// Number of blocks queried at once when scanning for delivered proofs.
+const EVENT_CHUNK_SIZE: u64 = 1000;
+
+sol! {
+    #[sol(rpc)]
+    interface IRiscZeroVerifier {
+        struct Receipt {
+            bytes seal;
+            bytes32 claimDigest;
+        }
+
+        function verifyIntegrity(Receipt calldata receipt) external view;
+    }
+}
+
+/// Journal committed by the loop guest for a generated request: the input cycle count followed
+/// by the random nonce, each as a little-endian u64.
+///
+/// The nonce makes the journal of every generated request distinct, so a proof delivered for
+/// one request cannot pass as the proof of another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ExpectedJournal {
+    pub(crate) input: u64,
+    pub(crate) nonce: u64,
+}
+
+impl ExpectedJournal {
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        [self.input.to_le_bytes(), self.nonce.to_le_bytes()].concat()
+    }
+
+    fn decode(journal: &[u8]) -> Option<Self> {
+        let (input, nonce) = journal.split_at_checked(8)?;
+        Some(Self {
+            input: u64::from_le_bytes(input.try_into().ok()?),
+            nonce: u64::from_le_bytes(nonce.try_into().ok()?),
+        })
+    }
+}
+
+impl std::fmt::Display for ExpectedJournal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input {} and nonce {}", self.input, self.nonce)
+    }
+}
+
+/// Reason a delivered proof failed verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ProofCheckFailure {
+    /// The seal is too short to contain a selector.
+    MissingSelector,
+    /// The seal selector does not match the selector in the request requirements.
+    SelectorMismatch { requested: FixedBytes<4>, delivered: FixedBytes<4> },
+    /// The seal was rejected by the verifier.
+    InvalidSeal(String),
+    /// The fulfillment does not include the journal.
+    MissingJournal,
+    /// The delivered journal does not match the input and nonce of the generated request.
+    JournalMismatch { expected: ExpectedJournal, delivered: Bytes },
+}
+
+impl ProofCheckFailure {
+    /// Whether the failure indicates an incorrect proof, rather than a bad proof type or seal.
+    pub(crate) fn is_critical(&self) -> bool {
+        matches!(self, Self::MissingJournal | Self::JournalMismatch { .. })
+    }
+}
+
+impl std::fmt::Display for ProofCheckFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSelector => write!(f, "seal does not contain a selector"),
+            Self::SelectorMismatch { requested, delivered } => {
+                write!(f, "requested selector {requested}, but the seal has selector {delivered}")
+            }
+            Self::InvalidSeal(err) => write!(f, "seal failed verification: {err}"),
+            Self::MissingJournal => write!(f, "fulfillment does not include the journal"),
+            Self::JournalMismatch { expected, delivered } => {
+                match ExpectedJournal::decode(delivered) {
+                    Some(decoded) => write!(
+                        f,
+                        "expected journal with {expected}, but the delivered journal has {decoded}"
+                    ),
+                    None => write!(
+                        f,
+                        "expected journal with {expected}, but the delivered journal is {delivered}"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Pending {
+    selector: FixedBytes<4>,
+    journal: ExpectedJournal,
+    expires_at: u64,
+}
+
+/// Tracks submitted requests and verifies the proofs delivered for them.
+pub(crate) struct ProofChecker {
+    /// Whether to check the selector and seal of delivered proofs, not only the journal.
+    check_seals: bool,
+    pending: HashMap<U256, Pending>,
+    next_block: Option<u64>,
+    /// Selector of set-inclusion proofs against the deployed set verifier.
+    inclusion_selector: Option<FixedBytes<4>>,
+    checked: u64,
+    failed: u64,
+}
+
+impl ProofChecker {
+    pub(crate) fn new(check_seals: bool) -> Self {
+        Self {
+            check_seals,
+            pending: HashMap::new(),
+            next_block: None,
+            inclusion_selector: None,
+            checked: 0,
+            failed: 0,
+        }
+    }
+
+    /// Whether no submitted request is still waiting for a proof.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Start tracking a request that was just submitted, with the journal its proof must commit.
+    pub(crate) async fn track(
+        &mut self,
+        client: &Client,
+        request: &ProofRequest,
+        journal: ExpectedJournal,
+    ) -> Result<()> {
+        if self.next_block.is_none() {
+            let block = client.provider().get_block_number().await?;
+            self.next_block = Some(block);
+        }
+        self.pending.insert(
+            request.id,
+            Pending {
+                selector: request.requirements.selector,
+                journal,
+                expires_at: request.expires_at(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Verify the proofs delivered for tracked requests since the last update.
+    pub(crate) async fn update(&mut self, client: &Client) -> Result<()> {
+        let Some(from) = self.next_block else {
+            return Ok(());
+        };
+        let provider = client.provider();
+        let to = provider.get_block_number().await?;
+        if to < from {
+            return Ok(());
+        }
+        let delivered: Vec<(ProofDelivered, Log)> = query_events_chunked(
+            &provider,
+            Filter::new()
+                .address(client.deployment.boundless_market_address)
+                .event_signature(ProofDelivered::SIGNATURE_HASH),
+            from..=to,
+            EVENT_CHUNK_SIZE,
+            |_| {},
+        )
+        .await?;
+        for (event, _) in delivered {
+            let Some(&pending) = self.pending.get(&event.requestId) else {
+                continue;
+            };
+            let request_id = event.requestId;
+            // If the check cannot be run, the request stays tracked and the blocks are scanned
+            // again by the next update. Requests already checked are not checked twice.
+            let result = match check_journal(pending.journal, &event.fulfillment) {
+                Ok(()) if self.check_seals => {
+                    self.check(client, pending.selector, &event.fulfillment).await?
+                }
+                result => result,
+            };
+            self.pending.remove(&request_id);
+            self.checked += 1;
+            match result {
+                Ok(()) => tracing::debug!(
+                    "Proof for request 0x{request_id:x} by {} verified",
+                    event.prover
+                ),
+                Err(failure) if failure.is_critical() => {
+                    self.failed += 1;
+                    tracing::error!(
+                        "[B-OG-JOURNAL] CRITICAL: Proof for request 0x{request_id:x} by {} is incorrect: {failure}",
+                        event.prover
+                    );
+                }
+                Err(failure) => {
+                    self.failed += 1;
+                    tracing::error!(
+                        "[B-OG-PROOF] Proof for request 0x{request_id:x} by {} is invalid: {failure}",
+                        event.prover
+                    );
+                }
+            }
+        }
+        self.next_block = Some(to + 1);
+
+        // Requests that expired without a delivered proof have nothing left to check.
+        let now = now();
+        self.pending.retain(|_, pending| pending.expires_at >= now);
+
+        tracing::info!(
+            "Proof checks: {} of {} delivered proofs failed verification",
+            self.failed,
+            self.checked
+        );
+        Ok(())
+    }
+
+    /// Check a delivered fulfillment against the selector of its request.
+    ///
+    /// Returns an error if the check could not be run, and `Ok(Err(_))` if the proof is invalid.
+    async fn check(
+        &mut self,
+        client: &Client,
+        requested: FixedBytes<4>,
+        fulfillment: &Fulfillment,
+    ) -> Result<Result<(), ProofCheckFailure>> {
+        let inclusion_selector = self.inclusion_selector(client).await?;
+        let Some(delivered) = seal_selector(&fulfillment.seal) else {
+            return Ok(Err(ProofCheckFailure::MissingSelector));
+        };
+        if let Err(failure) = check_selector(requested, delivered) {
+            return Ok(Err(failure));
+        }
+
+        // Set-inclusion seals are verified by the set verifier directly, which checks the
+        // inclusion path against its verified roots. Other seals go through the router.
+        let verifier = if delivered == inclusion_selector {
+            client.deployment.set_verifier_address
+        } else {
+            client
+                .deployment
+                .verifier_router_address
+                .context("no verifier router address to verify the seal with")?
+        };
+        let receipt = IRiscZeroVerifier::Receipt {
+            seal: fulfillment.seal.clone(),
+            claimDigest: fulfillment.claimDigest,
+        };
+        let result = IRiscZeroVerifier::new(verifier, client.provider())
+            .verifyIntegrity(receipt)
+            .call()
+            .await;
+        Ok(result.map(|_| ()).map_err(|err| ProofCheckFailure::InvalidSeal(err.to_string())))
+    }
+
+    async fn inclusion_selector(&mut self, client: &Client) -> Result<FixedBytes<4>> {
+        if let Some(selector) = self.inclusion_selector {
+            return Ok(selector);
+        }
+        let (image_id, _) = client
+            .set_verifier
+            .image_info()
+            .await
+            .context("failed to query set builder image ID")?;
+        let selectors = SupportedSelectors::new()
+            .with_set_builder_image_id(Digest::from(<[u8; 32]>::from(image_id)));
+        let selector =
+            *selectors.selectors.keys().next().expect("set builder selector was just added");
+        self.inclusion_selector = Some(selector);
+        Ok(selector)
+    }
+}
+
+/// Check that a delivered fulfillment commits to the expected journal.
+fn check_journal(
+    expected: ExpectedJournal,
+    fulfillment: &Fulfillment,
+) -> Result<(), ProofCheckFailure> {
+    match fulfillment.data() {
+        Ok(FulfillmentData::ImageIdAndJournal(_, journal)) => {
+            if journal.as_ref() == expected.to_bytes().as_slice() {
+                Ok(())
+            } else {
+                Err(ProofCheckFailure::JournalMismatch { expected, delivered: journal })
+            }
+        }
+        _ => Err(ProofCheckFailure::MissingJournal),
+    }
+}
+
+/// Selector in the first four bytes of a seal.
+fn seal_selector(seal: &[u8]) -> Option<FixedBytes<4>> {
+    seal.get(..4).map(FixedBytes::from_slice)
+}
+
+/// Check that the selector of a delivered seal matches the requested selector.
+///
+/// Requests without a selector accept any proof type. Otherwise, the selector of the seal must
+/// match exactly.
+fn check_selector(
+    requested: FixedBytes<4>,
+    delivered: FixedBytes<4>,
+) -> Result<(), ProofCheckFailure> {
+    if requested == UNSPECIFIED_SELECTOR || requested == delivered {
+        return Ok(());
+    }
+    Err(ProofCheckFailure::SelectorMismatch { requested, delivered })
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("system time before UNIX epoch").as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use boundless_market::contracts::Selector;
+
+    use super::*;
+
+    #[test]
+    fn test_check_selector() {
+        let groth16 = FixedBytes::from(Selector::groth16_latest() as u32);
+        let inclusion = FixedBytes::from(Selector::set_inclusion_latest() as u32);
+
+        assert_eq!(seal_selector(&[0xde, 0xad, 0xbe, 0xef, 0x00]), Some(0xdeadbeef_u32.into()));
+        assert_eq!(seal_selector(&[0x01, 0x02]), None);
+
+        assert!(check_selector(UNSPECIFIED_SELECTOR, inclusion).is_ok());
+        assert!(check_selector(UNSPECIFIED_SELECTOR, groth16).is_ok());
+        assert!(check_selector(groth16, groth16).is_ok());
+        assert_eq!(
+            check_selector(groth16, inclusion),
+            Err(ProofCheckFailure::SelectorMismatch { requested: groth16, delivered: inclusion })
+        );
+    }
+
+    #[test]
+    fn test_check_journal() {
+        let fulfillment = |data: FulfillmentData| {
+            let (data_type, data) = data.fulfillment_type_and_data();
+            Fulfillment {
+                id: U256::from(1),
+                requestDigest: Default::default(),
+                claimDigest: Default::default(),
+                fulfillmentDataType: data_type,
+                fulfillmentData: data.into(),
+                seal: Bytes::new(),
+            }
+        };
+        let image_id = Digest::from([1u32; 8]);
+        let expected = ExpectedJournal { input: 1 << 20, nonce: 42 };
+
+        let delivered =
+            fulfillment(FulfillmentData::from_image_id_and_journal(image_id, expected.to_bytes()));
+        assert_eq!(check_journal(expected, &delivered), Ok(()));
+
+        let other = ExpectedJournal { nonce: 43, ..expected };
+        let delivered =
+            fulfillment(FulfillmentData::from_image_id_and_journal(image_id, other.to_bytes()));
+        let failure = check_journal(expected, &delivered).unwrap_err();
+        assert!(failure.is_critical());
+        assert_eq!(
+            failure.to_string(),
+            "expected journal with input 1048576 and nonce 42, but the delivered journal has input 1048576 and nonce 43"
+        );
+
+        assert_eq!(
+            check_journal(expected, &fulfillment(FulfillmentData::None)),
+            Err(ProofCheckFailure::MissingJournal)
+        );
+    }
+}
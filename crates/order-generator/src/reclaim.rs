@@ -0,0 +1,169 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hygiene mode, reclaiming the payment of requests that expire unfulfilled.
+
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::U256;
+use anyhow::Result;
+use boundless_market::{
+    client::Client,
+    contracts::{
+        boundless_market::{MarketError, ReclaimOutcome},
+        ProofRequest,
+    },
+};
+
+/// Number of failed attempts after which a request is no longer reclaimed.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before retrying a request after its first failed attempt, in seconds. The delay doubles
+/// after each failure.
+const RETRY_BACKOFF: u64 = 30;
+
+/// A tracked request, waiting to expire or to be retried.
+struct Pending {
+    expires_at: u64,
+    /// Number of failed attempts to reclaim the request.
+    attempts: u32,
+    /// Timestamp before which the request is not retried.
+    retry_at: u64,
+}
+
+/// Tracks submitted requests and, once they expire, reclaims the payment escrowed for those that
+/// were locked but not fulfilled, so that it does not stay locked up in the market.
+#[derive(Default)]
+pub(crate) struct Reclaimer {
+    pending: HashMap<U256, Pending>,
+}
+
+impl Reclaimer {
+    /// Whether no tracked request is still waiting to expire.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Start tracking a request that was just submitted.
+    pub(crate) fn track(&mut self, request: &ProofRequest) {
+        self.pending.insert(
+            request.id,
+            Pending { expires_at: request.expires_at(), attempts: 0, retry_at: 0 },
+        );
+    }
+
+    /// Reclaim the payment of the tracked requests that expired.
+    ///
+    /// Requests that fail to be reclaimed are retried with an exponential backoff, and dropped
+    /// after [MAX_ATTEMPTS] failures.
+    pub(crate) async fn update(&mut self, client: &Client) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut expired = self.due(now);
+        expired.sort();
+
+        for request_id in expired {
+            match client.boundless_market.reclaim_expired(request_id).await {
+                Ok(outcome) => {
+                    log_outcome(request_id, &outcome);
+                    self.pending.remove(&request_id);
+                }
+                // The chain may lag behind the local clock.
+                Err(MarketError::RequestNotExpired(..)) => {
+                    tracing::debug!("Request 0x{request_id:x} not expired onchain yet")
+                }
+                Err(e) => self.record_failure(request_id, now, &e),
+            }
+        }
+        Ok(())
+    }
+
+    /// The tracked requests that expired and are not waiting for a retry.
+    fn due(&self, now: u64) -> Vec<U256> {
+        self.pending
+            .iter()
+            .filter(|(_, pending)| now > pending.expires_at && now >= pending.retry_at)
+            .map(|(&request_id, _)| request_id)
+            .collect()
+    }
+
+    /// Schedule a retry of the request after a failed attempt, or stop tracking it once it
+    /// failed [MAX_ATTEMPTS] times.
+    fn record_failure(&mut self, request_id: U256, now: u64, err: &MarketError) {
+        let Some(pending) = self.pending.get_mut(&request_id) else {
+            return;
+        };
+        pending.attempts += 1;
+        if pending.attempts >= MAX_ATTEMPTS {
+            tracing::error!(
+                "Giving up on reclaiming request 0x{request_id:x} after {} attempts: {err:?}",
+                pending.attempts
+            );
+            self.pending.remove(&request_id);
+            return;
+        }
+        let backoff = RETRY_BACKOFF << (pending.attempts - 1);
+        pending.retry_at = now + backoff;
+        tracing::warn!(
+            "Failed to reclaim request 0x{request_id:x}, retrying in {backoff}s: {err:?}"
+        );
+    }
+}
+
+fn log_outcome(request_id: U256, outcome: &ReclaimOutcome) {
+    match outcome {
+        ReclaimOutcome::Slashed { tx_hash, refunded: true, .. } => tracing::info!(
+            "Reclaimed the payment of expired request 0x{request_id:x} in tx {tx_hash}"
+        ),
+        ReclaimOutcome::Slashed { tx_hash, refunded: false, .. } => tracing::info!(
+            "Slashed request 0x{request_id:x}, fulfilled after its lock deadline, in tx {tx_hash}"
+        ),
+        ReclaimOutcome::AlreadySlashed => {
+            tracing::debug!("Request 0x{request_id:x} was already slashed")
+        }
+        ReclaimOutcome::NotLocked => {
+            tracing::debug!("Request 0x{request_id:x} expired without being locked")
+        }
+        ReclaimOutcome::Fulfilled => tracing::debug!("Request 0x{request_id:x} was fulfilled"),
+        _ => tracing::debug!("Nothing to reclaim for request 0x{request_id:x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_back_off_and_give_up() {
+        let mut reclaimer = Reclaimer::default();
+        let request_id = U256::from(1);
+        reclaimer.pending.insert(request_id, Pending { expires_at: 100, attempts: 0, retry_at: 0 });
+        assert!(reclaimer.due(100).is_empty());
+        assert_eq!(reclaimer.due(101), [request_id]);
+
+        let err = MarketError::RequestNotFound(request_id);
+        let mut now = 101;
+        for attempt in 1..MAX_ATTEMPTS {
+            reclaimer.record_failure(request_id, now, &err);
+            let backoff = RETRY_BACKOFF << (attempt - 1);
+            assert!(reclaimer.due(now + backoff - 1).is_empty());
+            now += backoff;
+            assert_eq!(reclaimer.due(now), [request_id]);
+        }
+        reclaimer.record_failure(request_id, now, &err);
+        assert!(reclaimer.is_idle());
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Summary report of a run of the order generator, for attaching to test run artifacts.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    path::Path,
+};
+
+use alloy::{
+    primitives::{utils::format_ether, U256},
+    providers::Provider,
+};
+use anyhow::{Context, Result};
+use boundless_market::{client::Client, contracts::ProofRequest};
+use clap::ValueEnum;
+
+use crate::slo::lock_and_fulfill_events;
+
+/// Maximum number of distinct errors listed in the report.
+const MAX_ERRORS: usize = 20;
+
+/// Format of the summary report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Format matching the extension of `path`, defaulting to markdown.
+    pub(crate) fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("html" | "htm") => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Submitted {
+    submitted_at: u64,
+    expires_at: u64,
+    max_price: U256,
+    onchain: bool,
+    locked_at: Option<u64>,
+    fulfilled_at: Option<u64>,
+    /// Whether the generator fulfilled the request itself, as no prover locked it.
+    self_fulfilled: bool,
+}
+
+/// Collects the submissions, outcomes, and errors of a run.
+#[derive(Debug)]
+pub(crate) struct RunReport {
+    started_at: u64,
+    seed: Option<u64>,
+    /// Whether the report covers the requests observed in monitor-only mode, whose latencies
+    /// are measured from their bidding start rather than their submission.
+    monitor: bool,
+    submitted: HashMap<U256, Submitted>,
+    failed: u64,
+    refused_by_budget: u64,
+    errors: BTreeMap<String, u64>,
+    next_block: Option<u64>,
+}
+
+/// A table of the report, rendered as markdown or HTML.
+struct Section {
+    title: &'static str,
+    headers: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+impl RunReport {
+    pub(crate) fn new(started_at: u64) -> Self {
+        Self {
+            started_at,
+            seed: None,
+            monitor: false,
+            submitted: HashMap::new(),
+            failed: 0,
+            refused_by_budget: 0,
+            errors: BTreeMap::new(),
+            next_block: None,
+        }
+    }
+
+    pub(crate) fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+
+    pub(crate) fn set_monitor(&mut self) {
+        self.monitor = true;
+    }
+
+    /// Record a request that was just submitted.
+    pub(crate) fn record_submitted(&mut self, request: &ProofRequest, onchain: bool, now: u64) {
+        self.submitted.insert(
+            request.id,
+            Submitted {
+                submitted_at: now,
+                expires_at: request.expires_at(),
+                max_price: request.offer.maxPrice,
+                onchain,
+                locked_at: None,
+                fulfilled_at: None,
+                self_fulfilled: false,
+            },
+        );
+    }
+
+    /// Record that a request was locked at `at`.
+    pub(crate) fn record_locked(&mut self, request_id: U256, at: u64) {
+        if let Some(request) = self.submitted.get_mut(&request_id) {
+            request.locked_at = Some(at);
+        }
+    }
+
+    /// Record that a request was fulfilled at `at`.
+    pub(crate) fn record_fulfilled(&mut self, request_id: U256, at: u64) {
+        if let Some(request) = self.submitted.get_mut(&request_id) {
+            request.fulfilled_at = Some(at);
+        }
+    }
+
+    /// Record that the generator fulfilled a request itself, as no prover locked it.
+    ///
+    /// Self-fulfilled requests are left out of the fulfillment latency, and reported apart.
+    #[cfg_attr(not(feature = "self-fulfill"), allow(dead_code))]
+    pub(crate) fn record_self_fulfilled(&mut self, request_id: U256) {
+        if let Some(request) = self.submitted.get_mut(&request_id) {
+            request.self_fulfilled = true;
+        }
+    }
+
+    /// Forget the requests that expired before `before`, returning how many were removed.
+    pub(crate) fn prune_expired(&mut self, before: u64) -> usize {
+        let count = self.submitted.len();
+        self.submitted.retain(|_, request| request.expires_at >= before);
+        count - self.submitted.len()
+    }
+
+    /// Log the fulfillment rate and latency of the requests so far.
+    pub(crate) fn log_summary(&self, now: u64) {
+        let requests: Vec<&Submitted> = self.submitted.values().collect();
+        let outcomes = Outcomes::of(&requests, now);
+        let by_provers: Vec<&Submitted> =
+            requests.iter().copied().filter(|request| !request.self_fulfilled).collect();
+        let latency = latencies(&by_provers, |request| request.fulfilled_at);
+        tracing::info!(
+            "{} requests: {} fulfilled, {} expired, {} pending, fulfillment rate {}, fulfillment latency p50 {}, p90 {}",
+            requests.len(),
+            outcomes.fulfilled,
+            outcomes.expired,
+            outcomes.pending,
+            outcomes.fulfillment_rate(),
+            latency[1],
+            latency[2]
+        );
+    }
+
+    /// Record a request that failed to be built or submitted.
+    pub(crate) fn record_error(&mut self, err: &anyhow::Error) {
+        self.failed += 1;
+        // Group errors by their root cause, which unlike the context does not embed request IDs.
+        let message = err.root_cause().to_string();
+        let message = message.lines().next().unwrap_or_default().to_string();
+        *self.errors.entry(message).or_default() += 1;
+    }
+
+    /// Record a request that was not submitted as it would exceed the spending budget.
+    pub(crate) fn record_budget_refusal(&mut self) {
+        self.refused_by_budget += 1;
+    }
+
+    /// Whether every submitted request was fulfilled or expired by `now`.
+    pub(crate) fn is_idle(&self, now: u64) -> bool {
+        self.submitted
+            .values()
+            .all(|request| request.fulfilled_at.is_some() || request.expires_at < now)
+    }
+
+    /// Record the lock and fulfillment events of submitted requests since the last update.
+    ///
+    /// The blocks are only marked as scanned once every query succeeded, so that a failed update
+    /// is retried over the same range by the next one.
+    pub(crate) async fn update(&mut self, client: &Client) -> Result<()> {
+        let to = client.provider().get_block_number().await?;
+        let Some(from) = self.next_block else {
+            self.next_block = Some(to + 1);
+            return Ok(());
+        };
+        if to < from {
+            return Ok(());
+        }
+        let events = lock_and_fulfill_events(client, from, to, |request_id| {
+            self.submitted.contains_key(&request_id)
+        })
+        .await?;
+        for (event, at) in events.locked {
+            self.record_locked(event.requestId, at);
+        }
+        for (event, at) in events.fulfilled {
+            self.record_fulfilled(event.requestId, at);
+        }
+        self.next_block = Some(to + 1);
+        Ok(())
+    }
+
+    /// Write the report in the given format to `path`.
+    pub(crate) fn write(&self, path: &Path, format: ReportFormat, now: u64) -> Result<()> {
+        std::fs::write(path, self.render(format, now))
+            .with_context(|| format!("failed to write report to {}", path.display()))
+    }
+
+    fn render(&self, format: ReportFormat, now: u64) -> String {
+        let sections = self.sections(now);
+        match format {
+            ReportFormat::Markdown => render_markdown(&sections),
+            ReportFormat::Html => render_html(&sections),
+        }
+    }
+
+    fn sections(&self, now: u64) -> Vec<Section> {
+        let requests: Vec<&Submitted> = self.submitted.values().collect();
+        let onchain = requests.iter().filter(|request| request.onchain).count();
+        let outcomes = Outcomes::of(&requests, now);
+        let locked = requests
+            .iter()
+            .filter(|request| request.locked_at.is_some() && request.fulfilled_at.is_none())
+            .count();
+        let (self_fulfilled, by_provers): (Vec<&Submitted>, Vec<&Submitted>) =
+            requests.iter().copied().partition(|request| request.self_fulfilled);
+
+        let mut lock_latency = vec!["Locked".to_string()];
+        lock_latency.extend(latencies(&requests, |request| request.locked_at));
+        let mut fulfill_latency = vec!["Fulfilled".to_string()];
+        fulfill_latency.extend(latencies(&by_provers, |request| request.fulfilled_at));
+        let mut self_fulfill_latency = vec!["Self-fulfilled".to_string()];
+        self_fulfill_latency.extend(latencies(&self_fulfilled, |request| request.fulfilled_at));
+
+        let committed: U256 = requests.iter().map(|request| request.max_price).sum();
+        let committed_fulfilled: U256 = requests
+            .iter()
+            .filter(|request| request.fulfilled_at.is_some())
+            .map(|request| request.max_price)
+            .sum();
+
+        let mut errors: Vec<(&String, &u64)> = self.errors.iter().collect();
+        errors.sort_by(|a, b| b.1.cmp(a.1));
+        let mut error_rows: Vec<Vec<String>> = errors
+            .iter()
+            .take(MAX_ERRORS)
+            .map(|(message, count)| vec![count.to_string(), message.to_string()])
+            .collect();
+        if errors.len() > MAX_ERRORS {
+            let other: u64 = errors[MAX_ERRORS..].iter().map(|(_, count)| **count).sum();
+            error_rows.push(vec![other.to_string(), "(other errors)".to_string()]);
+        }
+
+        vec![
+            Section {
+                title: "Run",
+                headers: &["Started at", "Duration", "Seed"],
+                rows: vec![vec![
+                    self.started_at.to_string(),
+                    format!("{}s", now.saturating_sub(self.started_at)),
+                    self.seed.map(|seed| seed.to_string()).unwrap_or_default(),
+                ]],
+            },
+            Section {
+                title: "Submissions",
+                headers: &["Submitted", "Onchain", "Offchain", "Failed", "Refused by budget"],
+                rows: vec![vec![
+                    requests.len().to_string(),
+                    onchain.to_string(),
+                    (requests.len() - onchain).to_string(),
+                    self.failed.to_string(),
+                    self.refused_by_budget.to_string(),
+                ]],
+            },
+            Section {
+                title: "Outcomes",
+                headers: &[
+                    "Fulfilled",
+                    "Expired",
+                    "Pending",
+                    "Locked, not fulfilled",
+                    "Fulfillment rate",
+                    "Self-fulfilled",
+                ],
+                rows: vec![vec![
+                    outcomes.fulfilled.to_string(),
+                    outcomes.expired.to_string(),
+                    outcomes.pending.to_string(),
+                    locked.to_string(),
+                    outcomes.fulfillment_rate(),
+                    self_fulfilled.len().to_string(),
+                ]],
+            },
+            Section {
+                title: if self.monitor {
+                    "Latency from bidding start"
+                } else {
+                    "Latency from submission"
+                },
+                headers: &["Event", "Samples", "p50", "p90", "p99", "Max"],
+                rows: vec![lock_latency, fulfill_latency, self_fulfill_latency],
+            },
+            Section {
+                title: "Spend",
+                headers: &["Committed (ETH)", "Committed by fulfilled requests (ETH)"],
+                rows: vec![vec![format_ether(committed), format_ether(committed_fulfilled)]],
+            },
+            Section { title: "Errors", headers: &["Count", "Error"], rows: error_rows },
+        ]
+    }
+}
+
+/// Number of requests by outcome.
+struct Outcomes {
+    fulfilled: usize,
+    expired: usize,
+    pending: usize,
+}
+
+impl Outcomes {
+    fn of(requests: &[&Submitted], now: u64) -> Self {
+        let fulfilled = requests.iter().filter(|request| request.fulfilled_at.is_some()).count();
+        let expired = requests
+            .iter()
+            .filter(|request| request.fulfilled_at.is_none() && request.expires_at < now)
+            .count();
+        Self { fulfilled, expired, pending: requests.len() - fulfilled - expired }
+    }
+
+    fn fulfillment_rate(&self) -> String {
+        match self.fulfilled + self.expired {
+            0 => "n/a".to_string(),
+            resolved => format!("{:.1}%", self.fulfilled as f64 / resolved as f64 * 100.0),
+        }
+    }
+}
+
+/// Sample count, and p50, p90, p99 and max latencies of the event at `at` of `requests`.
+fn latencies(requests: &[&Submitted], at: fn(&Submitted) -> Option<u64>) -> Vec<String> {
+    let mut samples: Vec<u64> = requests
+        .iter()
+        .filter_map(|request| Some(at(request)?.saturating_sub(request.submitted_at)))
+        .collect();
+    samples.sort_unstable();
+    let mut row = vec![samples.len().to_string()];
+    row.extend([0.5, 0.9, 0.99, 1.0].map(|q| match percentile(&samples, q) {
+        Some(value) => format!("{value}s"),
+        None => "n/a".to_string(),
+    }));
+    row
+}
+
+/// Nearest-rank percentile `q` of the sorted `samples`.
+fn percentile(samples: &[u64], q: f64) -> Option<u64> {
+    let rank = (q * samples.len() as f64).ceil() as usize;
+    samples.get(rank.clamp(1, samples.len().max(1)) - 1).copied()
+}
+
+fn render_markdown(sections: &[Section]) -> String {
+    let mut out = String::from("# Order generator report\n");
+    for section in sections {
+        write!(out, "\n## {}\n\n", section.title).unwrap();
+        if section.rows.is_empty() {
+            out.push_str("None\n");
+            continue;
+        }
+        writeln!(out, "| {} |", section.headers.join(" | ")).unwrap();
+        writeln!(out, "|{}", " --- |".repeat(section.headers.len())).unwrap();
+        for row in &section.rows {
+            let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            writeln!(out, "| {} |", cells.join(" | ")).unwrap();
+        }
+    }
+    out
+}
+
+fn render_html(sections: &[Section]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Order generator report</title></head>\n<body>\n<h1>Order generator report</h1>\n",
+    );
+    for section in sections {
+        writeln!(out, "<h2>{}</h2>", escape_html(section.title)).unwrap();
+        if section.rows.is_empty() {
+            out.push_str("<p>None</p>\n");
+            continue;
+        }
+        out.push_str("<table>\n<tr>");
+        for header in section.headers {
+            write!(out, "<th>{}</th>", escape_html(header)).unwrap();
+        }
+        out.push_str("</tr>\n");
+        for row in &section.rows {
+            out.push_str("<tr>");
+            for cell in row {
+                write!(out, "<td>{}</td>", escape_html(cell)).unwrap();
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use boundless_market::contracts::Offer;
+
+    use super::*;
+
+    fn request(id: u64, max_price: u64) -> ProofRequest {
+        ProofRequest {
+            id: U256::from(id),
+            offer: Offer {
+                maxPrice: U256::from(max_price),
+                rampUpStart: 1000,
+                timeout: 600,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn percentiles() {
+        let samples = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&samples, 0.5), Some(5));
+        assert_eq!(percentile(&samples, 0.9), Some(9));
+        assert_eq!(percentile(&samples, 1.0), Some(10));
+        assert_eq!(percentile(&[], 0.5), None);
+    }
+
+    #[test]
+    fn report() {
+        let mut report = RunReport::new(1000);
+        report.set_seed(7);
+        report.record_submitted(&request(1, 100), true, 1000);
+        report.record_submitted(&request(2, 200), false, 1010);
+        report.record_error(&anyhow::anyhow!("nonce too low").context("request 0x3 failed"));
+        report.record_budget_refusal();
+        report.record_fulfilled(U256::from(1), 1100);
+        assert!(!report.is_idle(1200));
+        report.record_fulfilled(U256::from(2), 1050);
+        report.record_self_fulfilled(U256::from(2));
+        let mut pruned = RunReport::new(1000);
+        pruned.record_submitted(&request(1, 100), true, 1000);
+        assert_eq!(pruned.prune_expired(1600), 0);
+        assert_eq!(pruned.prune_expired(1601), 1);
+        assert!(report.is_idle(1601));
+
+        let markdown = report.render(ReportFormat::Markdown, 1601);
+        assert!(markdown.contains("| 2 | 1 | 1 | 1 | 1 |"), "{markdown}");
+        assert!(markdown.contains("| 2 | 0 | 0 | 0 | 100.0% | 1 |"), "{markdown}");
+        assert!(markdown.contains("| Fulfilled | 1 | 100s | 100s | 100s | 100s |"), "{markdown}");
+        assert!(markdown.contains("| Self-fulfilled | 1 | 40s | 40s | 40s | 40s |"), "{markdown}");
+        assert!(markdown.contains("| 1 | nonce too low |"), "{markdown}");
+
+        let html = report.render(ReportFormat::Html, 1601);
+        assert!(html.contains("<td>nonce too low</td>"), "{html}");
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Self-fulfillment fallback, proving and fulfilling the requests that no prover locks.
+//!
+//! On quiet test networks, requests may go unlocked until they expire, which leaves the latency
+//! statistics of the generator with nothing to measure. In this mode, requests still unlocked a
+//! while after their bidding start are proven with the generator's own prover backend, and
+//! fulfilled as unlocked requests. Each request is proven in a task of its own, so that proving
+//! does not hold up the submission of new requests.
+//!
+//! Requires the `self-fulfill` feature, which pulls in the prover of the CLI.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::primitives::{Bytes, U256};
+use anyhow::{Context, Result};
+use boundless_cli::{config::ProverConfig, DefaultProver, OrderFulfilled};
+use boundless_market::{
+    client::Client,
+    contracts::{
+        boundless_market::{FulfillmentTx, UnlockedRequest},
+        ProofRequest, RequestStatus,
+    },
+    storage::fetch_url,
+};
+use tokio::task::JoinHandle;
+
+/// Tracks submitted requests and fulfills, with the generator's own prover, those that are still
+/// unlocked `after` seconds past their bidding start.
+pub(crate) struct SelfFulfiller {
+    prover: Arc<DefaultProver>,
+    after: u64,
+    /// Tracked requests, with their signature.
+    pending: HashMap<U256, (ProofRequest, Bytes)>,
+    /// Tasks proving and fulfilling tracked requests.
+    in_flight: HashMap<U256, JoinHandle<Result<()>>>,
+}
+
+impl SelfFulfiller {
+    /// Configure the prover backend, checking its health, and fetch the assessor and set builder
+    /// programs of the deployment.
+    pub(crate) async fn new(
+        client: &Client,
+        prover_config: &ProverConfig,
+        after: u64,
+    ) -> Result<Self> {
+        let backend = prover_config.configure_proving_backend_with_health_check().await?;
+
+        let (_, assessor_url) = client.boundless_market.image_info().await?;
+        let assessor_program =
+            fetch_url(&assessor_url).await.context("failed to fetch the assessor program")?;
+        let (_, set_builder_url) = client.set_verifier.image_info().await?;
+        let set_builder_program =
+            fetch_url(&set_builder_url).await.context("failed to fetch the set builder program")?;
+        let domain = client.boundless_market.eip712_domain().await?;
+
+        let prover = DefaultProver::new(
+            set_builder_program,
+            assessor_program,
+            client.boundless_market.caller(),
+            domain,
+        )?
+        .with_backend(backend);
+        tracing::info!("Fulfilling requests still unlocked {after}s after their bidding start");
+        Ok(Self {
+            prover: Arc::new(prover),
+            after,
+            pending: HashMap::new(),
+            in_flight: HashMap::new(),
+        })
+    }
+
+    /// Whether no tracked request is still waiting to be locked or self-fulfilled.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Start tracking a request that was just submitted, signing it for fulfillment.
+    pub(crate) async fn track(&mut self, client: &Client, request: &ProofRequest) -> Result<()> {
+        let signer = client.signer.as_ref().context("signer not set")?;
+        let chain_id = client.boundless_market.get_chain_id().await?;
+        let signature = request
+            .sign_request(signer, client.deployment.boundless_market_address, chain_id)
+            .await?;
+        self.pending.insert(request.id, (request.clone(), signature.as_bytes().into()));
+        Ok(())
+    }
+
+    /// Start proving the tracked requests that are due, and return the IDs of the requests whose
+    /// fulfillment completed since the last update.
+    ///
+    /// Requests that were locked, fulfilled, or expired are dropped. Requests that fail to be
+    /// proven or fulfilled are kept and retried on the next update, until they expire.
+    pub(crate) async fn update(&mut self, client: &Client) -> Result<Vec<U256>> {
+        let mut fulfilled = Vec::new();
+        let finished: Vec<U256> = self
+            .in_flight
+            .iter()
+            .filter(|(_, task)| task.is_finished())
+            .map(|(&request_id, _)| request_id)
+            .collect();
+        for request_id in finished {
+            let task = self.in_flight.remove(&request_id).expect("finished task is in flight");
+            match task.await {
+                Ok(Ok(())) => {
+                    tracing::info!("Fulfilled request 0x{request_id:x} ourselves");
+                    self.pending.remove(&request_id);
+                    fulfilled.push(request_id);
+                }
+                Ok(Err(e)) => tracing::warn!("Failed to fulfill request 0x{request_id:x}: {e:?}"),
+                Err(e) => tracing::warn!("Task fulfilling request 0x{request_id:x} failed: {e}"),
+            }
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut due: Vec<U256> = self
+            .pending
+            .iter()
+            .filter(|(request_id, (request, _))| {
+                !self.in_flight.contains_key(request_id)
+                    && now >= request.offer.rampUpStart + self.after
+            })
+            .map(|(&request_id, _)| request_id)
+            .collect();
+        due.sort();
+
+        for request_id in due {
+            let (request, signature) = &self.pending[&request_id];
+            let status =
+                client.boundless_market.get_status(request_id, Some(request.expires_at())).await?;
+            if status != RequestStatus::Unknown {
+                tracing::debug!(
+                    "Request 0x{request_id:x} is {status:?}, no need to fulfill it ourselves"
+                );
+                self.pending.remove(&request_id);
+                continue;
+            }
+
+            tracing::info!(
+                "Request 0x{request_id:x} still unlocked {}s after bidding start, fulfilling it ourselves",
+                now.saturating_sub(request.offer.rampUpStart)
+            );
+            let task = tokio::spawn(fulfill(
+                self.prover.clone(),
+                client.clone(),
+                request.clone(),
+                signature.clone(),
+            ));
+            self.in_flight.insert(request_id, task);
+        }
+        Ok(fulfilled)
+    }
+}
+
+/// Prove the request and fulfill it as an unlocked request.
+async fn fulfill(
+    prover: Arc<DefaultProver>,
+    client: Client,
+    request: ProofRequest,
+    signature: Bytes,
+) -> Result<()> {
+    let orders = [(request.clone(), signature.clone())];
+    let (fills, root_receipt, assessor_receipt) = prover.fulfill(&orders).await?;
+    let order_fulfilled = OrderFulfilled::new(fills, root_receipt, assessor_receipt)?;
+    let fulfillment_tx = FulfillmentTx::new(order_fulfilled.fills, order_fulfilled.assessorReceipt)
+        .with_submit_root(
+            client.deployment.set_verifier_address,
+            order_fulfilled.root,
+            order_fulfilled.seal,
+        )
+        .with_unlocked_requests(vec![UnlockedRequest::new(request, signature)]);
+    client.boundless_market.fulfill(fulfillment_tx).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::{node_bindings::Anvil, signers::Signer};
+    use boundless_market::{
+        contracts::{Offer, Predicate, RequestId, RequestInput, Requirements},
+        storage::StorageProviderConfig,
+    };
+    use boundless_test_utils::{
+        guests::{ECHO_ID, ECHO_PATH},
+        market::create_test_ctx,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "Generates a proof. Slow without RISC0_DEV_MODE=1"]
+    async fn test_self_fulfill() {
+        let anvil = Anvil::new().spawn();
+        let ctx = create_test_ctx(&anvil).await.unwrap();
+        let client = Client::builder()
+            .with_rpc_url(anvil.endpoint_url())
+            .with_storage_provider_config(&StorageProviderConfig::dev_mode())
+            .unwrap()
+            .with_deployment(ctx.deployment.clone())
+            .with_private_key(ctx.customer_signer.clone())
+            .build()
+            .await
+            .unwrap();
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let request = ProofRequest::new(
+            RequestId::new(ctx.customer_signer.address(), 0),
+            Requirements::new(Predicate::prefix_match(ECHO_ID, Bytes::default())),
+            format!("file://{ECHO_PATH}"),
+            RequestInput::builder().write_slice(&[0x41, 0x41, 0x41, 0x41]).build_inline().unwrap(),
+            Offer {
+                minPrice: U256::ZERO,
+                maxPrice: U256::from(1000),
+                rampUpStart: now,
+                timeout: 600,
+                lockTimeout: 600,
+                rampUpPeriod: 1,
+                lockCollateral: U256::ZERO,
+            },
+        );
+        ctx.customer_market.submit_request(&request, &ctx.customer_signer).await.unwrap();
+
+        let prover_config = ProverConfig {
+            bento_api_key: None,
+            bento_api_url: "".to_string(),
+            use_default_prover: true,
+            skip_health_check: true,
+        };
+        let mut fulfiller = SelfFulfiller::new(&client, &prover_config, 0).await.unwrap();
+        fulfiller.track(&client, &request).await.unwrap();
+        assert!(!fulfiller.is_idle());
+
+        // The first update only starts the proving task.
+        assert!(fulfiller.update(&client).await.unwrap().is_empty());
+        let fulfilled = loop {
+            let fulfilled = fulfiller.update(&client).await.unwrap();
+            if !fulfilled.is_empty() {
+                break fulfilled;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        assert_eq!(fulfilled, vec![request.id]);
+        assert!(fulfiller.is_idle());
+        assert!(ctx.customer_market.is_fulfilled(request.id).await.unwrap());
+    }
+}
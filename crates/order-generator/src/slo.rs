@@ -0,0 +1,366 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Latency SLO checks, turning the order generator into a synthetic monitoring probe.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    primitives::U256,
+    providers::Provider,
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use anyhow::{Context, Result};
+use boundless_market::{
+    client::Client,
+    contracts::IBoundlessMarket::{RequestFulfilled, RequestLocked},
+    log_query::query_events_chunked,
+};
+
+/// Number of blocks queried at once when scanning for lock and fulfillment events.
+const EVENT_CHUNK_SIZE: u64 = 1000;
+
+/// Latency objectives for submitted requests.
+#[derive(Clone, Debug)]
+pub(crate) struct SloConfig {
+    /// Seconds from submission within which a request should be locked.
+    pub(crate) lock_within: Option<u64>,
+    /// Seconds from submission within which a request should be fulfilled.
+    pub(crate) fulfill_within: Option<u64>,
+    /// Fraction of requests that must meet each objective.
+    pub(crate) target: f64,
+    /// Number of most recent requests with a known outcome to evaluate.
+    pub(crate) window: usize,
+    /// Minimum number of requests with a known outcome before the SLO is evaluated.
+    pub(crate) min_samples: usize,
+}
+
+/// Error returned when a latency objective is not met.
+#[derive(Debug)]
+pub(crate) struct SloViolation {
+    objective: &'static str,
+    within: u64,
+    achieved: f64,
+    target: f64,
+    samples: usize,
+}
+
+impl fmt::Display for SloViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SLO violated: {:.1}% of the last {} requests were {} within {}s, below the target of {:.1}%",
+            self.achieved * 100.0,
+            self.samples,
+            self.objective,
+            self.within,
+            self.target * 100.0
+        )
+    }
+}
+
+impl std::error::Error for SloViolation {}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Pending {
+    submitted_at: u64,
+    locked_at: Option<u64>,
+    fulfilled_at: Option<u64>,
+}
+
+/// Whether a request met each objective, or `None` if the objective is not configured.
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    lock_met: Option<bool>,
+    fulfill_met: Option<bool>,
+}
+
+/// Tracks submitted requests and evaluates the [SloConfig] over their outcomes.
+pub(crate) struct SloTracker {
+    config: SloConfig,
+    pending: HashMap<U256, Pending>,
+    samples: VecDeque<Sample>,
+    next_block: Option<u64>,
+}
+
+impl SloTracker {
+    pub(crate) fn new(config: SloConfig) -> Self {
+        Self { config, pending: HashMap::new(), samples: VecDeque::new(), next_block: None }
+    }
+
+    /// Whether no submitted request is still waiting for an outcome.
+    pub(crate) fn is_idle(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Start tracking a request that was just submitted.
+    pub(crate) async fn track(&mut self, client: &Client, request_id: U256) -> Result<()> {
+        if self.next_block.is_none() {
+            let block = client.provider().get_block_number().await?;
+            self.next_block = Some(block);
+        }
+        self.track_since(request_id, now());
+        Ok(())
+    }
+
+    /// Start tracking a request, with its latencies measured from `since`.
+    pub(crate) fn track_since(&mut self, request_id: U256, since: u64) {
+        self.pending.insert(request_id, Pending { submitted_at: since, ..Default::default() });
+    }
+
+    /// Record that a tracked request was locked at `at`.
+    pub(crate) fn record_locked(&mut self, request_id: U256, at: u64) {
+        if let Some(pending) = self.pending.get_mut(&request_id) {
+            pending.locked_at = Some(at);
+        }
+    }
+
+    /// Record that a tracked request was fulfilled at `at`.
+    pub(crate) fn record_fulfilled(&mut self, request_id: U256, at: u64) {
+        if let Some(pending) = self.pending.get_mut(&request_id) {
+            pending.fulfilled_at = Some(at);
+        }
+    }
+
+    /// Resolve the outcomes known by now, for requests whose events are recorded by the caller.
+    pub(crate) fn refresh(&mut self) {
+        self.resolve(now());
+    }
+
+    /// Record lock and fulfillment events of tracked requests since the last update.
+    pub(crate) async fn update(&mut self, client: &Client) -> Result<()> {
+        if let Some(from) = self.next_block {
+            let to = client.provider().get_block_number().await?;
+            if to >= from {
+                let events = lock_and_fulfill_events(client, from, to, |request_id| {
+                    self.pending.contains_key(&request_id)
+                })
+                .await?;
+                for (event, at) in events.locked {
+                    self.record_locked(event.requestId, at);
+                }
+                for (event, at) in events.fulfilled {
+                    self.record_fulfilled(event.requestId, at);
+                }
+                self.next_block = Some(to + 1);
+            }
+        }
+        self.resolve(now());
+        Ok(())
+    }
+
+    /// Move requests whose outcome is known for every objective into the evaluation window.
+    fn resolve(&mut self, now: u64) {
+        let (config, samples) = (&self.config, &mut self.samples);
+        self.pending.retain(|request_id, pending| {
+            // A request fulfilled without being locked counts as locked when it was fulfilled.
+            let lock_met = outcome(
+                pending.locked_at.or(pending.fulfilled_at),
+                pending.submitted_at,
+                config.lock_within,
+                now,
+            );
+            let fulfill_met =
+                outcome(pending.fulfilled_at, pending.submitted_at, config.fulfill_within, now);
+            let (Some(lock_met), Some(fulfill_met)) = (lock_met, fulfill_met) else {
+                return true;
+            };
+            tracing::debug!(
+                "Request 0x{request_id:x} SLO outcome: locked in time: {lock_met:?}, fulfilled in time: {fulfill_met:?}"
+            );
+            samples.push_back(Sample { lock_met, fulfill_met });
+            if samples.len() > config.window {
+                samples.pop_front();
+            }
+            false
+        });
+    }
+
+    /// Evaluate each objective over the requests in the window.
+    pub(crate) fn evaluate(&self) -> Result<(), SloViolation> {
+        if self.samples.len() < self.config.min_samples {
+            return Ok(());
+        }
+        self.check("locked", self.config.lock_within, |sample| sample.lock_met)?;
+        self.check("fulfilled", self.config.fulfill_within, |sample| sample.fulfill_met)
+    }
+
+    fn check(
+        &self,
+        objective: &'static str,
+        within: Option<u64>,
+        met: impl Fn(&Sample) -> Option<bool>,
+    ) -> Result<(), SloViolation> {
+        let Some(within) = within else {
+            return Ok(());
+        };
+        let outcomes: Vec<bool> = self.samples.iter().filter_map(&met).collect();
+        let achieved =
+            outcomes.iter().filter(|met| **met).count() as f64 / outcomes.len().max(1) as f64;
+        tracing::info!(
+            "SLO: {:.1}% of the last {} requests {} within {}s (target {:.1}%)",
+            achieved * 100.0,
+            outcomes.len(),
+            objective,
+            within,
+            self.config.target * 100.0
+        );
+        if achieved < self.config.target {
+            return Err(SloViolation {
+                objective,
+                within,
+                achieved,
+                target: self.config.target,
+                samples: outcomes.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Whether an event at `at` met an objective of `within` seconds from `submitted_at`.
+///
+/// Returns `None` while the outcome is not yet known, and `Some(None)` if there is no objective.
+fn outcome(
+    at: Option<u64>,
+    submitted_at: u64,
+    within: Option<u64>,
+    now: u64,
+) -> Option<Option<bool>> {
+    let Some(within) = within else {
+        return Some(None);
+    };
+    match at {
+        Some(at) => Some(Some(at.saturating_sub(submitted_at) <= within)),
+        None if now > submitted_at + within => Some(Some(false)),
+        None => None,
+    }
+}
+
+/// Query the events of type `E` emitted by the market in blocks `from` to `to`.
+pub(crate) async fn market_events<E: SolEvent>(
+    client: &Client,
+    from: u64,
+    to: u64,
+) -> Result<Vec<(E, Log)>> {
+    Ok(query_events_chunked(
+        client.provider(),
+        Filter::new()
+            .address(client.deployment.boundless_market_address)
+            .event_signature(E::SIGNATURE_HASH),
+        from..=to,
+        EVENT_CHUNK_SIZE,
+        |_| {},
+    )
+    .await?)
+}
+
+/// Lock and fulfillment events emitted by the market, with the timestamps of their blocks.
+pub(crate) struct LockFulfillEvents {
+    pub(crate) locked: Vec<(RequestLocked, u64)>,
+    pub(crate) fulfilled: Vec<(RequestFulfilled, u64)>,
+}
+
+/// Query the lock and fulfillment events of the requests accepted by `filter`, emitted by the
+/// market in blocks `from` to `to`.
+///
+/// Either every query succeeds or an error is returned, so callers can record the events and
+/// advance past `to` together.
+pub(crate) async fn lock_and_fulfill_events(
+    client: &Client,
+    from: u64,
+    to: u64,
+    filter: impl Fn(U256) -> bool,
+) -> Result<LockFulfillEvents> {
+    let provider = client.provider();
+    let mut events = LockFulfillEvents { locked: Vec::new(), fulfilled: Vec::new() };
+    let locked: Vec<(RequestLocked, Log)> = market_events(client, from, to).await?;
+    for (event, log) in locked {
+        if filter(event.requestId) {
+            let at = block_timestamp(&provider, &log).await?;
+            events.locked.push((event, at));
+        }
+    }
+    let fulfilled: Vec<(RequestFulfilled, Log)> = market_events(client, from, to).await?;
+    for (event, log) in fulfilled {
+        if filter(event.requestId) {
+            let at = block_timestamp(&provider, &log).await?;
+            events.fulfilled.push((event, at));
+        }
+    }
+    Ok(events)
+}
+
+/// Timestamp of the block containing `log`.
+pub(crate) async fn block_timestamp(provider: &impl Provider, log: &Log) -> Result<u64> {
+    if let Some(timestamp) = log.block_timestamp {
+        return Ok(timestamp);
+    }
+    let block_number = log.block_number.context("log is missing its block number")?;
+    let block = provider
+        .get_block_by_number(block_number.into())
+        .await?
+        .with_context(|| format!("block {block_number} not found"))?;
+    Ok(block.header.timestamp)
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("system time before UNIX epoch").as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> SloTracker {
+        SloTracker::new(SloConfig {
+            lock_within: Some(60),
+            fulfill_within: Some(600),
+            target: 0.5,
+            window: 4,
+            min_samples: 2,
+        })
+    }
+
+    #[test]
+    fn test_slo_evaluation() {
+        let mut tracker = tracker();
+        let pending =
+            |locked_at, fulfilled_at| Pending { submitted_at: 1000, locked_at, fulfilled_at };
+        tracker.pending.insert(U256::from(1), pending(Some(1030), Some(1200)));
+        tracker.pending.insert(U256::from(2), pending(Some(1030), None));
+
+        // The second request has not been fulfilled yet, and is still within its objective.
+        tracker.resolve(1100);
+        assert_eq!(tracker.samples.len(), 1);
+        assert!(!tracker.is_idle());
+        assert!(tracker.evaluate().is_ok(), "not enough samples to evaluate");
+
+        // Once its fulfillment objective has passed, it counts as a miss.
+        tracker.resolve(1601);
+        assert!(tracker.is_idle());
+        assert!(tracker.evaluate().is_ok(), "half of the requests met the objective");
+
+        tracker.pending.insert(U256::from(3), pending(None, None));
+        tracker.resolve(1601);
+        let violation = tracker.evaluate().unwrap_err();
+        assert_eq!(violation.objective, "fulfilled");
+        assert_eq!(violation.samples, 3);
+    }
+}
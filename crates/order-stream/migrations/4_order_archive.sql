@@ -0,0 +1,20 @@
+ALTER TABLE orders ADD COLUMN expires_at BIGINT;
+
+UPDATE orders SET expires_at =
+    (order_data->'request'->'offer'->>'rampUpStart')::BIGINT
+    + (order_data->'request'->'offer'->>'timeout')::BIGINT;
+
+CREATE INDEX orders_expires_at_idx ON orders (expires_at);
+
+CREATE TABLE archived_orders (
+    id BIGINT NOT NULL PRIMARY KEY,
+    request_id TEXT NOT NULL,
+    request_digest TEXT NOT NULL,
+    order_data JSONB NOT NULL,
+    created_at TIMESTAMPTZ,
+    expires_at BIGINT,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reason TEXT NOT NULL
+);
+
+CREATE INDEX archived_orders_created_at_idx ON archived_orders (created_at);
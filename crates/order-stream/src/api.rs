@@ -14,12 +14,23 @@
 
 use alloy::primitives::Address;
 use anyhow::Context;
-use axum::extract::{Json, Path, Query, State};
-use boundless_market::order_stream_client::{
-    ErrMsg, Nonce, OrderData, SubmitOrderRes, AUTH_GET_NONCE, HEALTH_CHECK, ORDER_LIST_PATH,
-    ORDER_SUBMISSION_PATH,
+use axum::{
+    body::Body,
+    extract::{Json, Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
 };
+use boundless_market::{
+    contracts::IBoundlessMarket,
+    order_stream_client::{
+        ArchivedOrderData, ErrMsg, Nonce, OrderData, SubmitOrderRes, ValidateOrderRes,
+        ARCHIVE_PAGE_SIZE, AUTH_GET_NONCE, HEALTH_CHECK, ORDER_ARCHIVE_PATH, ORDER_LIST_PATH,
+        ORDER_SUBMISSION_PATH, ORDER_VALIDATION_PATH,
+    },
+};
+use futures_util::StreamExt;
 use serde::Deserialize;
+use sqlx::types::chrono::{DateTime, Utc};
 use std::sync::Arc;
 use utoipa::IntoParams;
 
@@ -34,6 +45,8 @@ use crate::{
     request_body = Order,
     responses(
         (status = 200, description = "Order submission response", body = SubmitOrderRes),
+        (status = 400, description = "Invalid or expired order", body = ErrMsg),
+        (status = 409, description = "Order was already submitted", body = ErrMsg),
         (status = 500, description = "Internal error", body = ErrMsg)
     )
 )]
@@ -42,15 +55,90 @@ pub(crate) async fn submit_order(
     State(state): State<Arc<AppState>>,
     Json(order): Json<Order>,
 ) -> Result<Json<SubmitOrderRes>, AppError> {
-    // Validate the order
-    order.validate(state.config.market_address, state.chain_id)?;
+    check_order(&state, &order)?;
     let order_req_id = order.request.id;
-    let order_id = state.db.add_order(order).await.context("failed to add order to db")?;
+    let order_id = match state.db.add_order(order).await {
+        Ok(order_id) => order_id,
+        Err(OrderDbErr::DuplicateOrder(digest)) => return Err(AppError::DuplicateOrder(digest)),
+        Err(err) => {
+            return Err(anyhow::Error::from(err).context("failed to add order to db").into())
+        }
+    };
 
     tracing::debug!("Order 0x{order_req_id:x} - [{order_id}] submitted",);
     Ok(Json(SubmitOrderRes { status: "success".into(), request_id: order_req_id }))
 }
 
+/// Checks the signature and deployment of the order, and the expiry of its request.
+fn check_order(state: &AppState, order: &Order) -> Result<(), AppError> {
+    order.validate(state.config.market_address, state.chain_id)?;
+    if order.request.is_expired() {
+        return Err(AppError::ExpiredOrder(order.request.id));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = ORDER_VALIDATION_PATH,
+    request_body = Order,
+    responses(
+        (status = 200, description = "Order passed validation", body = ValidateOrderRes),
+        (status = 400, description = "Invalid or expired order, or insufficient balance", body = ErrMsg),
+        (status = 409, description = "Order was already submitted, or its request ID used", body = ErrMsg),
+        (status = 429, description = "Too many validation requests", body = ErrMsg),
+        (status = 500, description = "Internal error", body = ErrMsg)
+    )
+)]
+/// Runs the checks of submission on an order, without persisting it.
+///
+/// In addition to the checks of submission, checks that the request ID was not used onchain, and
+/// that the market balance of the requestor covers the max price of the request.
+pub(crate) async fn validate_order(
+    State(state): State<Arc<AppState>>,
+    Json(order): Json<Order>,
+) -> Result<Json<ValidateOrderRes>, AppError> {
+    if !state.validation_limiter.try_acquire() {
+        return Err(AppError::RateLimited);
+    }
+    check_order(&state, &order)?;
+    let request_id = order.request.id;
+    if state.db.order_exists(order.request_digest).await.context("Failed to query DB")? {
+        return Err(AppError::DuplicateOrder(order.request_digest));
+    }
+
+    let market = IBoundlessMarket::new(state.config.market_address, state.rpc_provider.clone());
+    let locked = market
+        .requestIsLocked(request_id)
+        .call()
+        .await
+        .context("Failed to check if the request is locked")?;
+    let fulfilled = market
+        .requestIsFulfilled(request_id)
+        .call()
+        .await
+        .context("Failed to check if the request is fulfilled")?;
+    if locked || fulfilled {
+        return Err(AppError::RequestIdUsed(request_id));
+    }
+    let requestor = order.request.client_address();
+    let balance = market
+        .balanceOf(requestor)
+        .call()
+        .await
+        .context("Failed to query the requestor balance")?;
+    if balance < order.request.offer.maxPrice {
+        return Err(AppError::InsufficientBalance {
+            requestor,
+            balance,
+            max_price: order.request.offer.maxPrice,
+        });
+    }
+
+    tracing::debug!("Order 0x{request_id:x} passed validation");
+    Ok(Json(ValidateOrderRes { request_id, request_digest: order.request_digest }))
+}
+
 const MAX_ORDERS: u64 = 1000;
 
 /// Paging query parameters
@@ -60,6 +148,10 @@ pub struct Pagination {
     offset: u64,
     /// Limit of orders returned, max 1000
     limit: u64,
+    /// List from the newest orders, starting at order id `offset` and going back. An offset of 0
+    /// starts at the newest order.
+    #[serde(default)]
+    desc: bool,
 }
 
 #[utoipa::path(
@@ -83,7 +175,12 @@ pub(crate) async fn list_orders(
     let limit = i64::try_from(limit).map_err(|_| AppError::QueryParamErr("limit"))?;
     let offset = i64::try_from(paging.offset).map_err(|_| AppError::QueryParamErr("index"))?;
 
-    let results = state.db.list_orders(offset, limit).await.context("Failed to query DB")?;
+    let results = if paging.desc {
+        state.db.list_orders_desc(offset, limit).await
+    } else {
+        state.db.list_orders(offset, limit).await
+    }
+    .context("Failed to query DB")?;
     Ok(Json(results))
 }
 
@@ -108,6 +205,71 @@ pub(crate) async fn find_orders_by_request_id(
     Ok(Json(results))
 }
 
+/// Submission time range and page of an archive export
+#[derive(Deserialize, IntoParams)]
+pub struct ArchiveRange {
+    /// Only export orders submitted at or after this UNIX timestamp
+    from: Option<u64>,
+    /// Only export orders submitted before this UNIX timestamp
+    to: Option<u64>,
+    /// Only export orders with an order id above this one, e.g. the last id of the previous page
+    after: Option<i64>,
+    /// Limit of orders exported, max 1000
+    limit: Option<u64>,
+}
+
+fn parse_timestamp(
+    timestamp: Option<u64>,
+    name: &'static str,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    timestamp
+        .map(|timestamp| {
+            i64::try_from(timestamp)
+                .ok()
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .ok_or(AppError::QueryParamErr(name))
+        })
+        .transpose()
+}
+
+#[utoipa::path(
+    get,
+    path = ORDER_ARCHIVE_PATH,
+    params(
+        ArchiveRange,
+    ),
+    responses(
+        (status = 200, description = "archived orders, one JSON object per line", body = Vec<ArchivedOrderData>, content_type = "application/x-ndjson"),
+        (status = 400, description = "Invalid time range", body = ErrMsg),
+        (status = 429, description = "Too many export requests", body = ErrMsg),
+        (status = 500, description = "Internal error", body = ErrMsg)
+    )
+)]
+/// Exports a page of the archived orders submitted within a time range, as JSON lines.
+///
+/// Orders are exported by increasing id. Pass the id of the last order of a page as `after` to
+/// export the next page.
+pub(crate) async fn export_archive(
+    State(state): State<Arc<AppState>>,
+    Query(range): Query<ArchiveRange>,
+) -> Result<Response, AppError> {
+    if !state.archive_limiter.try_acquire() {
+        return Err(AppError::RateLimited);
+    }
+    let from = parse_timestamp(range.from, "from")?;
+    let to = parse_timestamp(range.to, "to")?;
+    let after = range.after.unwrap_or(0);
+    let limit = range.limit.unwrap_or(ARCHIVE_PAGE_SIZE).min(ARCHIVE_PAGE_SIZE);
+    let limit = i64::try_from(limit).map_err(|_| AppError::QueryParamErr("limit"))?;
+
+    let lines = state.db.archive_stream(from, to, after, limit).map(|order| {
+        let mut line = serde_json::to_vec(&order?)?;
+        line.push(b'\n');
+        Ok::<_, OrderDbErr>(line)
+    });
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response())
+}
+
 #[utoipa::path(
     get,
     path = format!("{}/<addr>", AUTH_GET_NONCE),
@@ -0,0 +1,97 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use boundless_market::{contracts::IBoundlessMarket, order_stream_client::ArchiveReason};
+use futures_util::future::join_all;
+
+use crate::AppState;
+
+/// Number of live orders checked for fulfillment at once.
+const FULFILLMENT_CHECK_PAGE: i64 = 100;
+
+/// Periodically move expired and fulfilled orders to the archive, until shutdown.
+///
+/// Archived orders are no longer listed by the order APIs, but can be exported by submission time
+/// for retention and offline analysis.
+pub(crate) async fn run_archival(state: Arc<AppState>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = state.shutdown.cancelled() => {
+                tracing::info!("Stopping order archival");
+                return;
+            }
+        }
+        if let Err(err) = archive_orders(&state).await {
+            tracing::warn!("Order archival failed: {err:?}");
+        }
+    }
+}
+
+async fn archive_orders(state: &AppState) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let expired =
+        state.db.archive_expired_orders(now).await.context("Failed to archive expired orders")?;
+
+    // The remaining orders have not expired. Check which of them were fulfilled onchain.
+    let market = IBoundlessMarket::new(state.config.market_address, state.rpc_provider.clone());
+    let mut fulfilled = 0;
+    let mut offset = 0;
+    loop {
+        let orders = state
+            .db
+            .list_orders(offset, FULFILLMENT_CHECK_PAGE)
+            .await
+            .context("Failed to list orders")?;
+        let Some(last) = orders.last() else {
+            break;
+        };
+        offset = last.id + 1;
+
+        let checks = join_all(
+            orders.iter().map(|order| market.requestIsFulfilled(order.order.request.id).call()),
+        )
+        .await;
+        let mut ids = vec![];
+        for (order, check) in orders.iter().zip(checks) {
+            match check {
+                Ok(true) => ids.push(order.id),
+                Ok(false) => {}
+                Err(err) => tracing::warn!(
+                    "Failed to check fulfillment of request 0x{:x}: {err}",
+                    order.order.request.id
+                ),
+            }
+        }
+        if !ids.is_empty() {
+            fulfilled += state
+                .db
+                .archive_orders(&ids, ArchiveReason::Fulfilled)
+                .await
+                .context("Failed to archive fulfilled orders")?;
+        }
+    }
+
+    if expired + fulfilled > 0 {
+        tracing::info!("Archived {expired} expired and {fulfilled} fulfilled orders");
+    }
+    Ok(())
+}
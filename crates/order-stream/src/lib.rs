@@ -19,22 +19,26 @@ use std::time::{Duration, Instant};
 use alloy::providers::fillers::{ChainIdFiller, FillProvider, JoinFill};
 use alloy::providers::Identity;
 use alloy::{
-    primitives::{utils::parse_ether, Address, U256},
+    primitives::{utils::parse_ether, Address, B256, U256},
     providers::{Provider, ProviderBuilder, RootProvider},
     rpc::client::RpcClient,
     transports::layers::RetryBackoffLayer,
 };
-use anyhow::{Context, Error as AnyhowErr, Result};
+use anyhow::{ensure, Context, Error as AnyhowErr, Result};
 use axum::{
     extract::Json,
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
-use boundless_market::order_stream_client::{
-    AuthMsg, ErrMsg, Order, OrderError, AUTH_GET_NONCE, HEALTH_CHECK, ORDER_LIST_PATH,
-    ORDER_SUBMISSION_PATH, ORDER_WS_PATH,
+use boundless_market::{
+    order_stream_client::{
+        ArchiveReason, ArchivedOrderData, AuthMsg, ErrMsg, Order, OrderError, AUTH_GET_NONCE,
+        HEALTH_CHECK, ORDER_ARCHIVE_PATH, ORDER_LIST_PATH, ORDER_SUBMISSION_PATH,
+        ORDER_VALIDATION_PATH, ORDER_WS_PATH,
+    },
+    rate_limit_layer::{parse_requests_per_second, RateLimiter},
 };
 use clap::Parser;
 use reqwest::Url;
@@ -49,12 +53,14 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 mod api;
+mod archive;
 mod order_db;
 mod ws;
 
 use api::{
-    __path_find_orders_by_request_id, __path_get_nonce, __path_health, __path_list_orders,
-    __path_submit_order, find_orders_by_request_id, get_nonce, health, list_orders, submit_order,
+    __path_export_archive, __path_find_orders_by_request_id, __path_get_nonce, __path_health,
+    __path_list_orders, __path_submit_order, __path_validate_order, export_archive,
+    find_orders_by_request_id, get_nonce, health, list_orders, submit_order, validate_order,
 };
 use order_db::OrderDb;
 use ws::{__path_websocket_handler, start_broadcast_task, websocket_handler, ConnectionsMap};
@@ -71,6 +77,23 @@ pub enum AppError {
     #[error("address not found")]
     AddrNotFound(Address),
 
+    #[error("order with digest {0} was already submitted")]
+    DuplicateOrder(B256),
+
+    #[error("request 0x{0:x} has expired")]
+    ExpiredOrder(U256),
+
+    #[error("request 0x{0:x} was already locked or fulfilled onchain")]
+    RequestIdUsed(U256),
+
+    #[error(
+        "requestor {requestor} has a market balance of {balance}, below the max price {max_price}"
+    )]
+    InsufficientBalance { requestor: Address, balance: U256, max_price: U256 },
+
+    #[error("too many requests, retry later")]
+    RateLimited,
+
     #[error("internal error")]
     InternalErr(AnyhowErr),
 }
@@ -81,6 +104,11 @@ impl AppError {
             Self::InvalidOrder(_) => "InvalidOrder",
             Self::QueryParamErr(_) => "QueryParamErr",
             Self::AddrNotFound(_) => "AddrNotFound",
+            Self::DuplicateOrder(_) => "DuplicateOrder",
+            Self::ExpiredOrder(_) => "ExpiredOrder",
+            Self::RequestIdUsed(_) => "RequestIdUsed",
+            Self::InsufficientBalance { .. } => "InsufficientBalance",
+            Self::RateLimited => "RateLimited",
             Self::InternalErr(_) => "InternalErr",
         }
         .into()
@@ -102,13 +130,23 @@ impl From<OrderError> for AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let code = match self {
-            Self::InvalidOrder(_) | Self::QueryParamErr(_) => StatusCode::BAD_REQUEST,
+            Self::InvalidOrder(_)
+            | Self::QueryParamErr(_)
+            | Self::ExpiredOrder(_)
+            | Self::InsufficientBalance { .. } => StatusCode::BAD_REQUEST,
             Self::AddrNotFound(_) => StatusCode::NOT_FOUND,
+            Self::DuplicateOrder(_) | Self::RequestIdUsed(_) => StatusCode::CONFLICT,
+            Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
             Self::InternalErr(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
         tracing::error!("api error, code {code}: {self:?}");
 
-        (code, Json(ErrMsg { r#type: self.type_str(), msg: self.to_string() })).into_response()
+        let mut response =
+            (code, Json(ErrMsg { r#type: self.type_str(), msg: self.to_string() })).into_response();
+        if let Self::RateLimited = self {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+        }
+        response
     }
 }
 
@@ -170,6 +208,25 @@ pub struct Args {
     /// From the `RetryBackoffLayer` of Alloy
     #[clap(long, default_value_t = 100)]
     pub rpc_retry_cu: u64,
+
+    /// Interval between moving expired and fulfilled orders to the archive (in seconds)
+    ///
+    /// Archived orders can be exported by submission time. Archival is disabled if not set.
+    #[clap(long, env)]
+    archive_interval: Option<u64>,
+
+    /// Maximum rate of order validation requests, across all clients (in requests per second)
+    ///
+    /// Each validation queries the RPC node, so requests above this rate are rejected.
+    #[clap(long, env, default_value_t = 10.0, value_parser = parse_requests_per_second)]
+    validation_rate_limit: f64,
+
+    /// Maximum rate of archive export requests, across all clients (in requests per second)
+    ///
+    /// Each request exports a page of up to 1000 orders, so requests above this rate are
+    /// rejected.
+    #[clap(long, env, default_value_t = 2.0, value_parser = parse_requests_per_second)]
+    archive_rate_limit: f64,
 }
 
 /// Configuration struct
@@ -198,6 +255,12 @@ pub struct Config {
     pub rpc_retry_backoff: u64,
     /// RPC HTTP retry compute-unit per second
     pub rpc_retry_cu: u64,
+    /// Interval between order archival runs (in seconds), disabled if None
+    pub archive_interval: Option<u64>,
+    /// Maximum rate of order validation requests (in requests per second)
+    pub validation_rate_limit: f64,
+    /// Maximum rate of archive export requests (in requests per second)
+    pub archive_rate_limit: f64,
 }
 
 impl Config {
@@ -220,6 +283,9 @@ pub struct ConfigBuilder {
     rpc_retry_max: Option<u32>,
     rpc_retry_backoff: Option<u64>,
     rpc_retry_cu: Option<u64>,
+    archive_interval: Option<u64>,
+    validation_rate_limit: Option<f64>,
+    archive_rate_limit: Option<f64>,
 }
 
 impl ConfigBuilder {
@@ -278,6 +344,21 @@ impl ConfigBuilder {
         Self { rpc_retry_cu: Some(cu), ..self }
     }
 
+    /// Set the order archival interval
+    pub fn archive_interval(self, interval: u64) -> Self {
+        Self { archive_interval: Some(interval), ..self }
+    }
+
+    /// Set the maximum rate of order validation requests
+    pub fn validation_rate_limit(self, requests_per_second: f64) -> Self {
+        Self { validation_rate_limit: Some(requests_per_second), ..self }
+    }
+
+    /// Set the maximum rate of archive export requests
+    pub fn archive_rate_limit(self, requests_per_second: f64) -> Self {
+        Self { archive_rate_limit: Some(requests_per_second), ..self }
+    }
+
     /// Build the Config with default values for any unset fields
     pub fn build(self) -> Result<Config, ConfigError> {
         Ok(Config {
@@ -294,6 +375,9 @@ impl ConfigBuilder {
             rpc_retry_max: self.rpc_retry_max.unwrap_or(10),
             rpc_retry_backoff: self.rpc_retry_backoff.unwrap_or(1000),
             rpc_retry_cu: self.rpc_retry_cu.unwrap_or(100),
+            archive_interval: self.archive_interval,
+            validation_rate_limit: self.validation_rate_limit.unwrap_or(10.0),
+            archive_rate_limit: self.archive_rate_limit.unwrap_or(2.0),
         })
     }
 }
@@ -311,6 +395,9 @@ impl From<&Args> for Config {
             rpc_retry_max: args.rpc_retry_max,
             rpc_retry_backoff: args.rpc_retry_backoff,
             rpc_retry_cu: args.rpc_retry_cu,
+            archive_interval: args.archive_interval,
+            validation_rate_limit: args.validation_rate_limit,
+            archive_rate_limit: args.archive_rate_limit,
         }
     }
 }
@@ -321,6 +408,15 @@ pub enum ConfigError {
     MissingRequiredField(&'static str),
 }
 
+/// Build a [RateLimiter] allowing a burst of one second of requests.
+fn rate_limiter(requests_per_second: f64) -> Result<RateLimiter> {
+    ensure!(
+        requests_per_second.is_finite() && requests_per_second > 0.0,
+        "rate limit must be positive"
+    );
+    Ok(RateLimiter::new(requests_per_second, requests_per_second.ceil() as u32))
+}
+
 type ReadOnlyProvider = FillProvider<JoinFill<Identity, ChainIdFiller>, RootProvider>;
 
 /// Application state struct
@@ -339,6 +435,10 @@ pub struct AppState {
     chain_id: u64,
     /// Cancelation tokens set when a graceful shutdown is triggered
     shutdown: CancellationToken,
+    /// Rate limiter shared by all order validation requests
+    validation_limiter: RateLimiter,
+    /// Rate limiter shared by all archive export requests
+    archive_limiter: RateLimiter,
 }
 
 impl AppState {
@@ -363,6 +463,10 @@ impl AppState {
         };
         let chain_id =
             rpc_provider.get_chain_id().await.context("Failed to fetch chain_id from RPC")?;
+        let validation_limiter =
+            rate_limiter(config.validation_rate_limit).context("Invalid validation rate limit")?;
+        let archive_limiter =
+            rate_limiter(config.archive_rate_limit).context("Invalid archive rate limit")?;
 
         Ok(Arc::new(Self {
             db,
@@ -372,6 +476,8 @@ impl AppState {
             config: config.clone(),
             chain_id,
             shutdown: CancellationToken::new(),
+            validation_limiter,
+            archive_limiter,
         }))
     }
 
@@ -421,13 +527,15 @@ const MAX_ORDER_SIZE: usize = 100 * 1024; // 100 KiB
 #[openapi(
     paths(
         submit_order,
+        validate_order,
         list_orders,
         find_orders_by_request_id,
+        export_archive,
         get_nonce,
         health,
         websocket_handler
     ),
-    components(schemas(AuthMsg)),
+    components(schemas(AuthMsg, ArchivedOrderData, ArchiveReason)),
     info(
         title = "Boundless Order Stream service",
         description = r#"
@@ -443,9 +551,11 @@ pub fn app(state: Arc<AppState>) -> Router {
     let body_size_limit = RequestBodyLimitLayer::new(MAX_ORDER_SIZE);
 
     Router::new()
-        .route(ORDER_SUBMISSION_PATH, post(submit_order).layer(body_size_limit))
+        .route(ORDER_SUBMISSION_PATH, post(submit_order).layer(body_size_limit.clone()))
+        .route(ORDER_VALIDATION_PATH, post(validate_order).layer(body_size_limit))
         .route(ORDER_LIST_PATH, get(list_orders))
         .route(&format!("{ORDER_LIST_PATH}/{{request_id}}"), get(find_orders_by_request_id))
+        .route(ORDER_ARCHIVE_PATH, get(export_archive))
         .route(&format!("{AUTH_GET_NONCE}{{addr}}"), get(get_nonce))
         .route(ORDER_WS_PATH, get(websocket_handler))
         .route(HEALTH_CHECK, get(health))
@@ -491,6 +601,10 @@ pub async fn run_from_parts(
         }
     });
 
+    if let Some(interval) = app_state.config.archive_interval {
+        tokio::spawn(archive::run_archival(app_state.clone(), Duration::from_secs(interval)));
+    }
+
     tracing::info!("REST API listening on: {}", listener.local_addr().unwrap());
     axum::serve(listener, self::app(app_state.clone()))
         .with_graceful_shutdown(async { shutdown_signal(app_state).await })
@@ -535,7 +649,7 @@ mod tests {
             hit_points::default_allowance, Offer, Predicate, ProofRequest, RequestId, Requirements,
         },
         input::GuestEnv,
-        order_stream_client::{order_stream, OrderStreamClient},
+        order_stream_client::{order_stream, OrderEncoding, OrderStreamClient, OrderStreamError},
     };
     use boundless_test_utils::market::{create_test_ctx, TestCtx};
 
@@ -543,7 +657,10 @@ mod tests {
     use reqwest::Url;
     use risc0_zkvm::sha::Digest;
     use sqlx::PgPool;
-    use std::net::{Ipv4Addr, SocketAddr};
+    use std::{
+        net::{Ipv4Addr, SocketAddr},
+        time::{SystemTime, UNIX_EPOCH},
+    };
     use tokio::task::JoinHandle;
 
     /// Test setup helper that creates common test infrastructure
@@ -582,6 +699,9 @@ mod tests {
             rpc_retry_max: 10,
             rpc_retry_backoff: 1000,
             rpc_retry_cu: 100,
+            archive_interval: None,
+            validation_rate_limit: 10.0,
+            archive_rate_limit: 2.0,
         };
 
         let app_state = AppState::new(&config, Some(pool)).await.unwrap();
@@ -598,7 +718,7 @@ mod tests {
             Offer {
                 minPrice: U256::from(20000000000000u64),
                 maxPrice: U256::from(40000000000000u64),
-                rampUpStart: 1,
+                rampUpStart: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
                 timeout: 100,
                 lockTimeout: 100,
                 rampUpPeriod: 1,
@@ -679,7 +799,11 @@ mod tests {
             app_state.config.market_address,
             app_state.chain_id,
         );
-        let customer_socket = customer_client.connect_async(&ctx.customer_signer).await.unwrap();
+        // The customer receives compressed binary frames, which must decode to the same orders.
+        let customer_socket = customer_client
+            .connect_async_with_encoding(&ctx.customer_signer, OrderEncoding::MsgPackDeflate)
+            .await
+            .unwrap();
         let stream_task = tokio::spawn(async move {
             let mut stream = order_stream(socket);
             let mut customer_order_stream = order_stream(customer_socket);
@@ -773,6 +897,254 @@ mod tests {
         server_handle.abort();
     }
 
+    #[sqlx::test]
+    async fn rest_submit_without_websocket(pool: PgPool) {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (app_state, ctx, _anvil) = setup_test_env(pool, 1, Some(&listener)).await;
+
+        let url = Url::parse(&format!("http://{addr}")).unwrap();
+        let client = OrderStreamClient::new(
+            url.clone(),
+            app_state.config.market_address,
+            app_state.chain_id,
+        );
+        let app_state_clone = app_state.clone();
+        let server_handle = tokio::spawn(async move {
+            self::run_from_parts(app_state_clone, listener).await.unwrap();
+        });
+        wait_for_server_health(&client, &addr, 5).await;
+
+        // Submit a pre-signed order over REST, without opening a WebSocket connection.
+        let request = new_request(1, &ctx.prover_signer.address());
+        let signature = request
+            .sign_request(&ctx.prover_signer, app_state.config.market_address, app_state.chain_id)
+            .await
+            .unwrap();
+        let request_digest =
+            request.signing_hash(app_state.config.market_address, app_state.chain_id).unwrap();
+        let order = Order::new(request.clone(), request_digest, signature);
+        let res = client.submit_order(&order).await.unwrap();
+        assert_eq!(res.request_id, request.id);
+
+        // Submitting the same order again is reported as a replay.
+        let err = client.submit_order(&order).await.unwrap_err();
+        assert!(matches!(err, OrderStreamError::Replayed { .. }), "unexpected error: {err:?}");
+
+        // An order signed for another chain passes local validation, but the server rejects it.
+        let wrong_chain_client =
+            OrderStreamClient::new(url, app_state.config.market_address, app_state.chain_id + 1);
+        let err = wrong_chain_client
+            .submit_request(&new_request(2, &ctx.prover_signer.address()), &ctx.prover_signer)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, OrderStreamError::Rejected { status: 400, .. }),
+            "unexpected error: {err:?}"
+        );
+
+        server_handle.abort();
+    }
+
+    #[sqlx::test]
+    async fn rest_validate_order(pool: PgPool) {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (app_state, ctx, _anvil) = setup_test_env(pool, 1, Some(&listener)).await;
+
+        let client = OrderStreamClient::new(
+            Url::parse(&format!("http://{addr}")).unwrap(),
+            app_state.config.market_address,
+            app_state.chain_id,
+        );
+        let app_state_clone = app_state.clone();
+        let server_handle = tokio::spawn(async move {
+            self::run_from_parts(app_state_clone, listener).await.unwrap();
+        });
+        wait_for_server_health(&client, &addr, 5).await;
+
+        // The customer has nothing deposited to pay for the request.
+        let customer = ctx.customer_signer.address();
+        let request = new_request(1, &customer);
+        let err = client.validate_request(&request, &ctx.customer_signer, None).await.unwrap_err();
+        assert!(
+            matches!(err, OrderStreamError::Rejected { status: 400, ref msg } if msg.contains("balance")),
+            "unexpected error: {err:?}"
+        );
+
+        ctx.customer_market.deposit(parse_ether("1").unwrap()).await.unwrap();
+        let res = client.validate_request(&request, &ctx.customer_signer, None).await.unwrap();
+        assert_eq!(res.request_id, request.id);
+
+        // Validation does not persist the order, which can still be submitted once.
+        assert!(app_state
+            .db
+            .find_orders_by_request_id(request.id.to_string())
+            .await
+            .unwrap()
+            .is_empty());
+        client.submit_request(&request, &ctx.customer_signer).await.unwrap();
+        let err = client.validate_request(&request, &ctx.customer_signer, None).await.unwrap_err();
+        assert!(matches!(err, OrderStreamError::Replayed { .. }), "unexpected error: {err:?}");
+
+        // A request already locked onchain cannot be used again.
+        let request = new_request(2, &customer);
+        let client_sig = request
+            .sign_request(&ctx.customer_signer, app_state.config.market_address, app_state.chain_id)
+            .await
+            .unwrap();
+        ctx.prover_market.lock_request(&request, client_sig.as_bytes(), None).await.unwrap();
+        let err = client.validate_request(&request, &ctx.customer_signer, None).await.unwrap_err();
+        assert!(matches!(err, OrderStreamError::RequestIdUsed { .. }), "unexpected error: {err:?}");
+
+        // An order signed for another market is rejected by the server.
+        let wrong_market_client = OrderStreamClient::new(
+            Url::parse(&format!("http://{addr}")).unwrap(),
+            Address::repeat_byte(0x42),
+            app_state.chain_id,
+        );
+        let err = wrong_market_client
+            .validate_request(&new_request(3, &customer), &ctx.customer_signer, None)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, OrderStreamError::Rejected { status: 400, .. }),
+            "unexpected error: {err:?}"
+        );
+
+        server_handle.abort();
+    }
+
+    #[sqlx::test]
+    async fn rest_validate_order_rate_limited(pool: PgPool) {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (app_state, ctx, _anvil) = setup_test_env(pool.clone(), 1, Some(&listener)).await;
+        // Allow a single validation, with no refill within the test.
+        let config = Config { validation_rate_limit: 0.001, ..app_state.config.clone() };
+        let app_state = AppState::new(&config, Some(pool)).await.unwrap();
+
+        let client = OrderStreamClient::new(
+            Url::parse(&format!("http://{addr}")).unwrap(),
+            app_state.config.market_address,
+            app_state.chain_id,
+        );
+        let server_handle = tokio::spawn(async move {
+            self::run_from_parts(app_state, listener).await.unwrap();
+        });
+        wait_for_server_health(&client, &addr, 5).await;
+
+        ctx.customer_market.deposit(parse_ether("1").unwrap()).await.unwrap();
+        let customer = ctx.customer_signer.address();
+        client
+            .validate_request(&new_request(1, &customer), &ctx.customer_signer, None)
+            .await
+            .unwrap();
+        let err = client
+            .validate_request(&new_request(2, &customer), &ctx.customer_signer, None)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, OrderStreamError::RateLimited { retry_after: Some(_) }),
+            "unexpected error: {err:?}"
+        );
+
+        server_handle.abort();
+    }
+
+    #[sqlx::test]
+    async fn rest_export_archive(pool: PgPool) {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (app_state, ctx, _anvil) = setup_test_env(pool, 1, Some(&listener)).await;
+
+        let base_url = Url::parse(&format!("http://{addr}")).unwrap();
+        let client = OrderStreamClient::new(
+            base_url.clone(),
+            app_state.config.market_address,
+            app_state.chain_id,
+        );
+        let app_state_clone = app_state.clone();
+        let server_handle = tokio::spawn(async move {
+            self::run_from_parts(app_state_clone, listener).await.unwrap();
+        });
+        wait_for_server_health(&client, &addr, 5).await;
+
+        let customer = ctx.customer_signer.address();
+        for idx in 1..=3 {
+            client
+                .submit_request(&new_request(idx, &customer), &ctx.customer_signer)
+                .await
+                .unwrap();
+        }
+        let ids: Vec<i64> =
+            app_state.db.list_orders(0, 10).await.unwrap().iter().map(|order| order.id).collect();
+        app_state.db.archive_orders(&ids, ArchiveReason::Fulfilled).await.unwrap();
+
+        let export_page = |after: i64| {
+            let mut url = base_url.join(ORDER_ARCHIVE_PATH).unwrap();
+            url.query_pairs_mut()
+                .append_pair("after", &after.to_string())
+                .append_pair("limit", "2");
+            async move {
+                let body = reqwest::get(url).await.unwrap().error_for_status().unwrap();
+                let body = body.text().await.unwrap();
+                body.lines()
+                    .map(|line| serde_json::from_str::<ArchivedOrderData>(line).unwrap().id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        let page = export_page(0).await;
+        assert_eq!(page, ids[..2]);
+        assert_eq!(export_page(page[1]).await, ids[2..]);
+
+        let mut out = Vec::new();
+        assert_eq!(client.download_archive(None, None, &mut out).await.unwrap(), 3);
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 3);
+
+        server_handle.abort();
+    }
+
+    #[sqlx::test]
+    async fn rest_export_archive_rate_limited(pool: PgPool) {
+        let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (app_state, _ctx, _anvil) = setup_test_env(pool.clone(), 1, Some(&listener)).await;
+        // Allow a single export, with no refill within the test.
+        let config = Config { archive_rate_limit: 0.001, ..app_state.config.clone() };
+        let app_state = AppState::new(&config, Some(pool)).await.unwrap();
+
+        let base_url = Url::parse(&format!("http://{addr}")).unwrap();
+        let client = OrderStreamClient::new(
+            base_url.clone(),
+            app_state.config.market_address,
+            app_state.chain_id,
+        );
+        let server_handle = tokio::spawn(async move {
+            self::run_from_parts(app_state, listener).await.unwrap();
+        });
+        wait_for_server_health(&client, &addr, 5).await;
+
+        let url = base_url.join(ORDER_ARCHIVE_PATH).unwrap();
+        let res = reqwest::get(url.clone()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = reqwest::get(url).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(res.headers().contains_key(header::RETRY_AFTER));
+
+        server_handle.abort();
+    }
+
     #[sqlx::test]
     async fn test_pending_connection_timeout(pool: PgPool) {
         // No need for a listener in this test
@@ -12,10 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloy::primitives::Address;
+use alloy::primitives::{Address, B256};
 use async_stream::stream;
-use boundless_market::order_stream_client::Order;
-use futures_util::Stream;
+use boundless_market::order_stream_client::{ArchiveReason, Order};
+use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use sqlx::{
     postgres::{PgListener, PgPool, PgPoolOptions},
@@ -48,6 +48,9 @@ pub enum OrderDbErr {
 
     #[error("Json serialization error {0}")]
     JsonErr(#[from] serde_json::Error),
+
+    #[error("Order already exists: {0}")]
+    DuplicateOrder(B256),
 }
 
 #[derive(Serialize, Deserialize, sqlx::FromRow, Debug)]
@@ -58,6 +61,17 @@ pub struct DbOrder {
     pub created_at: Option<DateTime<Utc>>,
 }
 
+/// An order moved to the archive, see [OrderDb::archive_expired_orders].
+#[derive(Serialize, Deserialize, sqlx::FromRow, Debug)]
+pub struct DbArchivedOrder {
+    pub id: i64,
+    #[sqlx(rename = "order_data", json)]
+    pub order: Order,
+    pub created_at: Option<DateTime<Utc>>,
+    pub archived_at: DateTime<Utc>,
+    pub reason: String,
+}
+
 pub struct OrderDb {
     pool: PgPool,
 }
@@ -66,6 +80,8 @@ const ORDER_CHANNEL: &str = "new_orders";
 
 pub type OrderStream = Pin<Box<dyn Stream<Item = Result<DbOrder, OrderDbErr>> + Send>>;
 
+pub type ArchiveStream = Pin<Box<dyn Stream<Item = Result<DbArchivedOrder, OrderDbErr>> + Send>>;
+
 impl OrderDb {
     /// Constructs a [OrderDb] from an existing [PgPool]
     ///
@@ -174,13 +190,20 @@ impl OrderDb {
     pub async fn add_order(&self, order: Order) -> Result<i64, OrderDbErr> {
         let mut txn = self.pool.begin().await?;
         let row_res: Option<(i64, DateTime<Utc>)> = sqlx::query_as(
-            "INSERT INTO orders (request_id, request_digest, order_data, created_at) VALUES ($1, $2, $3, NOW()) RETURNING id, created_at",
+            "INSERT INTO orders (request_id, request_digest, order_data, created_at, expires_at) VALUES ($1, $2, $3, NOW(), $4) RETURNING id, created_at",
         )
         .bind(order.request.id.to_string())
         .bind(order.request_digest.to_string())
         .bind(sqlx::types::Json(order.clone()))
+        .bind(order.request.expires_at() as i64)
         .fetch_optional(&mut *txn)
-        .await?;
+        .await
+        .map_err(|err| match err {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                OrderDbErr::DuplicateOrder(order.request_digest)
+            }
+            err => err.into(),
+        })?;
 
         let Some(row) = row_res else {
             return Err(OrderDbErr::NoRows("new order"));
@@ -231,6 +254,17 @@ impl OrderDb {
         Ok(rows)
     }
 
+    /// Whether an order with the given request digest was submitted
+    pub async fn order_exists(&self, request_digest: B256) -> Result<bool, OrderDbErr> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM orders WHERE request_digest = $1)")
+                .bind(request_digest.to_string())
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(exists)
+    }
+
     /// List orders with pagination
     ///
     /// Lists all orders the the database with a size bound and start id. The index_id will be
@@ -245,6 +279,26 @@ impl OrderDb {
         Ok(rows)
     }
 
+    /// List orders with pagination, from the newest
+    ///
+    /// Lists at most `size` orders with an ID of at most `index_id`, newest first. An `index_id`
+    /// of 0 starts at the newest order.
+    pub async fn list_orders_desc(
+        &self,
+        index_id: i64,
+        size: i64,
+    ) -> Result<Vec<DbOrder>, OrderDbErr> {
+        let rows: Vec<DbOrder> = sqlx::query_as(
+            "SELECT * FROM orders WHERE ($1 = 0 OR id <= $1) ORDER BY id DESC LIMIT $2",
+        )
+        .bind(index_id)
+        .bind(size)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
     /// Returns a stream of new orders from the DB
     ///
     /// listens to the new orders and emits them as a async Stream
@@ -260,6 +314,79 @@ impl OrderDb {
         }))
     }
 
+    /// Move the orders whose request expired before `now` to the archive
+    ///
+    /// Returns the number of archived orders.
+    pub async fn archive_expired_orders(&self, now: u64) -> Result<u64, OrderDbErr> {
+        let res = sqlx::query(
+            "WITH moved AS (DELETE FROM orders WHERE expires_at < $1 RETURNING *) \
+             INSERT INTO archived_orders (id, request_id, request_digest, order_data, created_at, expires_at, reason) \
+             SELECT id, request_id, request_digest, order_data, created_at, expires_at, $2 FROM moved",
+        )
+        .bind(now as i64)
+        .bind(ArchiveReason::Expired.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    /// Move the orders with the given DB ids to the archive
+    ///
+    /// Used for orders whose request was fulfilled before expiring. Returns the number of
+    /// archived orders.
+    pub async fn archive_orders(
+        &self,
+        ids: &[i64],
+        reason: ArchiveReason,
+    ) -> Result<u64, OrderDbErr> {
+        let res = sqlx::query(
+            "WITH moved AS (DELETE FROM orders WHERE id = ANY($1) RETURNING *) \
+             INSERT INTO archived_orders (id, request_id, request_digest, order_data, created_at, expires_at, reason) \
+             SELECT id, request_id, request_digest, order_data, created_at, expires_at, $2 FROM moved",
+        )
+        .bind(ids)
+        .bind(reason.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+
+    /// Returns a stream of at most `limit` archived orders submitted within `[from, to)`, with
+    /// an id above `after`
+    ///
+    /// Orders are streamed in the order they were submitted, so that the id of the last order
+    /// streamed can be passed as `after` to fetch the next page. Orders submitted before
+    /// submission times were recorded are only included if the range is unbounded.
+    pub fn archive_stream(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        after: i64,
+        limit: i64,
+    ) -> ArchiveStream {
+        let pool = self.pool.clone();
+        Box::pin(stream! {
+            let mut rows = sqlx::query_as::<_, DbArchivedOrder>(
+                "SELECT * FROM archived_orders \
+                 WHERE ($1::TIMESTAMPTZ IS NULL OR created_at >= $1) \
+                 AND ($2::TIMESTAMPTZ IS NULL OR created_at < $2) \
+                 AND id > $3 \
+                 ORDER BY id \
+                 LIMIT $4",
+            )
+            .bind(from)
+            .bind(to)
+            .bind(after)
+            .bind(limit)
+            .fetch(&pool);
+            while let Some(row) = rows.next().await {
+                yield row.map_err(OrderDbErr::from);
+            }
+        })
+    }
+
     /// Simple health check to test postgesql connectivity
     pub async fn health_check(&self) -> Result<(), OrderDbErr> {
         sqlx::query("SELECT COUNT(*) FROM orders LIMIT 1").execute(&self.pool).await?;
@@ -274,8 +401,12 @@ mod tests {
         signers::local::LocalSigner,
         sol_types::SolStruct,
     };
-    use boundless_market::contracts::{
-        eip712_domain, Offer, Predicate, ProofRequest, RequestInput, RequestInputType, Requirements,
+    use boundless_market::{
+        contracts::{
+            eip712_domain, Offer, Predicate, ProofRequest, RequestInput, RequestInputType,
+            Requirements,
+        },
+        order_stream_client::RequestMetadata,
     };
     use futures_util::StreamExt;
     use risc0_zkvm::sha::Digest;
@@ -360,6 +491,45 @@ mod tests {
         assert_eq!(order_id, 1);
     }
 
+    #[sqlx::test]
+    async fn add_duplicate_order(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+
+        let order = create_order(U256::from(1)).await;
+        db.add_order(order.clone()).await.unwrap();
+        let err = db.add_order(order.clone()).await.unwrap_err();
+        assert!(
+            matches!(err, OrderDbErr::DuplicateOrder(digest) if digest == order.request_digest)
+        );
+    }
+
+    #[sqlx::test]
+    async fn order_exists(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+
+        let order = create_order(U256::from(1)).await;
+        assert!(!db.order_exists(order.request_digest).await.unwrap());
+        db.add_order(order.clone()).await.unwrap();
+        assert!(db.order_exists(order.request_digest).await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn order_metadata_roundtrip(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+
+        let signer = LocalSigner::random();
+        let order = create_order(U256::from(1)).await;
+        let metadata = RequestMetadata::default().with_app_name("test-app").with_label("k", "v");
+        let order = order
+            .clone()
+            .with_metadata(metadata.sign(order.request_digest, &signer).await.unwrap());
+        let order_id = db.add_order(order.clone()).await.unwrap();
+
+        let orders = db.list_orders(order_id, 1).await.unwrap();
+        assert_eq!(orders[0].order, order);
+        assert_eq!(orders[0].order.metadata.as_ref().unwrap().metadata, metadata);
+    }
+
     #[sqlx::test]
     async fn del_order(pool: PgPool) {
         let db = OrderDb::from_pool(pool).await.unwrap();
@@ -394,6 +564,20 @@ mod tests {
         assert_eq!(orders[0].id, order_id);
     }
 
+    #[sqlx::test]
+    async fn list_orders_page_backward(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+        let mut ids = vec![];
+        for idx in 1..=3 {
+            ids.push(db.add_order(create_order(U256::from(idx)).await).await.unwrap());
+        }
+
+        let newest = db.list_orders_desc(0, 2).await.unwrap();
+        assert_eq!(newest.iter().map(|order| order.id).collect::<Vec<_>>(), [ids[2], ids[1]]);
+        let older = db.list_orders_desc(ids[1] - 1, 2).await.unwrap();
+        assert_eq!(older.iter().map(|order| order.id).collect::<Vec<_>>(), [ids[0]]);
+    }
+
     #[sqlx::test]
     async fn list_after_del(pool: PgPool) {
         let db = OrderDb::from_pool(pool).await.unwrap();
@@ -408,6 +592,59 @@ mod tests {
         assert_eq!(orders[0].id, order_id_2);
     }
 
+    #[sqlx::test]
+    async fn archive_expired_orders(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+        let order = create_order(U256::from(1)).await;
+        let order_id = db.add_order(order.clone()).await.unwrap();
+
+        // The order expires at 1000.
+        assert_eq!(db.archive_expired_orders(1000).await.unwrap(), 0);
+        assert_eq!(db.archive_expired_orders(1001).await.unwrap(), 1);
+        assert!(db.list_orders(0, 10).await.unwrap().is_empty());
+
+        let archived: Vec<_> = db.archive_stream(None, None, 0, 10).collect().await;
+        assert_eq!(archived.len(), 1);
+        let archived = archived.into_iter().next().unwrap().unwrap();
+        assert_eq!(archived.id, order_id);
+        assert_eq!(archived.order, order);
+        assert_eq!(archived.reason, "expired");
+    }
+
+    #[sqlx::test]
+    async fn archive_stream_range(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+        let order_id = db.add_order(create_order(U256::from(1)).await).await.unwrap();
+        let _order_id_2 = db.add_order(create_order(U256::from(2)).await).await.unwrap();
+        assert_eq!(db.archive_orders(&[order_id], ArchiveReason::Fulfilled).await.unwrap(), 1);
+        assert_eq!(db.list_orders(0, 10).await.unwrap().len(), 1);
+
+        let now = Utc::now();
+        let before: Vec<_> = db.archive_stream(None, Some(now), 0, 10).collect().await;
+        assert_eq!(before.len(), 1);
+        let archived = before.into_iter().next().unwrap().unwrap();
+        assert_eq!(archived.id, order_id);
+        assert_eq!(archived.reason, "fulfilled");
+        assert_eq!(db.archive_stream(Some(now), None, 0, 10).count().await, 0);
+    }
+
+    #[sqlx::test]
+    async fn archive_stream_paging(pool: PgPool) {
+        let db = OrderDb::from_pool(pool).await.unwrap();
+        let mut ids = vec![];
+        for idx in 1..=3 {
+            ids.push(db.add_order(create_order(U256::from(idx)).await).await.unwrap());
+        }
+        assert_eq!(db.archive_orders(&ids, ArchiveReason::Fulfilled).await.unwrap(), 3);
+
+        let page: Vec<_> = db.archive_stream(None, None, 0, 2).collect().await;
+        let page: Vec<i64> = page.into_iter().map(|order| order.unwrap().id).collect();
+        assert_eq!(page, ids[..2]);
+        let page: Vec<_> = db.archive_stream(None, None, page[1], 2).collect().await;
+        let page: Vec<i64> = page.into_iter().map(|order| order.unwrap().id).collect();
+        assert_eq!(page, ids[2..]);
+    }
+
     #[sqlx::test]
     async fn order_stream(pool: PgPool) {
         let db = Arc::new(OrderDb::from_pool(pool).await.unwrap());
@@ -24,7 +24,9 @@ use axum::{
 };
 use boundless_market::{
     contracts::IBoundlessMarket,
-    order_stream_client::{AuthMsg, ErrMsg, ORDER_WS_PATH},
+    order_stream_client::{
+        AuthMsg, EncodedOrder, ErrMsg, OrderEncoding, ORDER_ENCODING_HEADER, ORDER_WS_PATH,
+    },
 };
 use futures_util::{SinkExt, StreamExt};
 use rand::{seq::SliceRandom, Rng};
@@ -36,7 +38,8 @@ use crate::order_db::{DbOrder, OrderDbErr, OrderStream};
 use crate::{AppError, AppState};
 
 pub(crate) struct ClientConnection {
-    sender: mpsc::Sender<String>, // Channel to send messages to this client
+    sender: mpsc::Sender<Message>, // Channel to send messages to this client
+    encoding: OrderEncoding,       // Encoding of the orders sent to this client
 }
 
 pub(crate) type ConnectionsMap = HashMap<Address, ClientConnection>;
@@ -53,6 +56,10 @@ fn parse_auth_msg(value: &HeaderValue) -> Result<AuthMsg> {
         (
             "X-Auth-Data" = AuthMsg, 
             description = "SIWE authentication message (AuthMsg) as a JSON object"
+        ),
+        (
+            "X-Order-Encoding" = Option<String>,
+            description = "Encoding of the orders sent over the socket: json (default), msgpack or msgpack-deflate"
         )
     ),
     responses(
@@ -83,6 +90,22 @@ pub(crate) async fn websocket_handler(
         }
     };
 
+    let encoding = match headers.get(ORDER_ENCODING_HEADER) {
+        Some(value) => match value
+            .to_str()
+            .map_err(anyhow::Error::from)
+            .and_then(|v| v.parse::<OrderEncoding>())
+        {
+            Ok(encoding) => encoding,
+            Err(err) => {
+                tracing::warn!("Invalid order encoding: {err:?}");
+                return Ok((StatusCode::BAD_REQUEST, format!("Invalid order encoding: {err}"))
+                    .into_response());
+            }
+        },
+        None => OrderEncoding::Json,
+    };
+
     let client_addr = auth_msg.address();
     let addr_nonce = match state.db.get_nonce(client_addr).await {
         Ok(res) => res,
@@ -164,36 +187,53 @@ pub(crate) async fn websocket_handler(
     }
 
     // Proceed with WebSocket upgrade
-    tracing::info!("New webSocket connection from {client_addr}");
+    tracing::info!("New webSocket connection from {client_addr} ({encoding} encoding)");
     Ok(ws
         .on_failed_upgrade(move |error| {
             tracing::warn!("Failed to upgrade connection for {client_addr}: {error:?}");
         })
-        .on_upgrade(move |socket| websocket_connection(socket, client_addr, state)))
+        .on_upgrade(move |socket| websocket_connection(socket, client_addr, encoding, state)))
+}
+
+// Encode an order as a WebSocket message in the given encoding.
+fn encode_order(db_order: &DbOrder, encoding: OrderEncoding) -> Result<Message> {
+    Ok(match encoding.encode(db_order)? {
+        EncodedOrder::Text(text) => Message::Text(text.into()),
+        EncodedOrder::Binary(bytes) => Message::Binary(bytes.into()),
+    })
 }
 
 // Function to broadcast an order to all WebSocket clients in random order
 async fn broadcast_order(db_order: &DbOrder, state: Arc<AppState>) {
-    let order_json = match serde_json::to_string(&db_order) {
-        Ok(order_json) => order_json,
-        Err(err) => {
-            tracing::error!("Failed to serialize order 0x{:x}: {}", db_order.order.request.id, err);
-            return;
-        }
-    };
-
     // Shuffle the connections
     let connections_list = {
         let connections = state.connections.read().await;
-        let mut connections_list: Vec<_> =
-            connections.iter().map(|(addr, conn)| (*addr, conn.sender.clone())).collect();
+        let mut connections_list: Vec<_> = connections
+            .iter()
+            .map(|(addr, conn)| (*addr, conn.sender.clone(), conn.encoding))
+            .collect();
         connections_list.shuffle(&mut rand::rng());
         connections_list
     };
 
+    // Each order is encoded at most once per encoding in use.
+    let mut messages: HashMap<OrderEncoding, Message> = HashMap::new();
     let mut clients_to_remove = Vec::new();
-    for (address, sender) in connections_list {
-        match sender.try_send(order_json.clone()) {
+    for (address, sender, encoding) in connections_list {
+        let message = match messages.entry(encoding) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => match encode_order(db_order, encoding) {
+                Ok(message) => entry.insert(message).clone(),
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to serialize order 0x{:x} as {encoding}: {err:?}",
+                        db_order.order.request.id
+                    );
+                    continue;
+                }
+            },
+        };
+        match sender.try_send(message) {
             Ok(_) => {}
             Err(mpsc::error::TrySendError::Full(_)) => {
                 tracing::warn!("Client {}'s message queue is full, message dropped", address);
@@ -218,10 +258,15 @@ async fn broadcast_order(db_order: &DbOrder, state: Arc<AppState>) {
     tracing::debug!("Order 0x{:x} broadcasted", db_order.order.request.id);
 }
 
-async fn websocket_connection(socket: WebSocket, address: Address, state: Arc<AppState>) {
+async fn websocket_connection(
+    socket: WebSocket,
+    address: Address,
+    encoding: OrderEncoding,
+    state: Arc<AppState>,
+) {
     let (mut sender_ws, mut recver_ws) = socket.split();
 
-    let (sender_channel, mut receiver_channel) = mpsc::channel::<String>(state.config.queue_size);
+    let (sender_channel, mut receiver_channel) = mpsc::channel::<Message>(state.config.queue_size);
 
     let is_connected;
     // Add sender to the list of connections
@@ -234,7 +279,7 @@ async fn websocket_connection(socket: WebSocket, address: Address, state: Arc<Ap
             }
             Entry::Vacant(entry) => {
                 is_connected = false;
-                entry.insert(ClientConnection { sender: sender_channel.clone() });
+                entry.insert(ClientConnection { sender: sender_channel.clone(), encoding });
             }
         }
     }
@@ -258,7 +303,7 @@ async fn websocket_connection(socket: WebSocket, address: Address, state: Arc<Ap
             msg = receiver_channel.recv() => {
                 match msg {
                     Some(msg) => {
-                        match sender_ws.send(Message::Text(msg.into())).await {
+                        match sender_ws.send(msg).await {
                             Ok(_) => {
                                 // Reset the error counter on successful send
                                 errors_counter = 0;
@@ -0,0 +1,2 @@
+CREATE INDEX orders_lock_expires_at ON orders (state, lock_expires_at);
+CREATE INDEX orders_expires_at ON orders (state, expires_at);
@@ -0,0 +1,4 @@
+ALTER TABLE orders ADD COLUMN partner TEXT;
+ALTER TABLE orders ADD COLUMN slash_after BIGINT;
+ALTER TABLE orders ADD COLUMN notified_at BIGINT;
+CREATE INDEX orders_partner ON orders (state, partner, notified_at);
@@ -0,0 +1,2 @@
+ALTER TABLE orders ADD COLUMN state TEXT NOT NULL DEFAULT 'tracked';
+ALTER TABLE orders ADD COLUMN slash_tx_hash TEXT;
@@ -0,0 +1,5 @@
+CREATE TABLE event_gaps (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT NOT NULL
+);
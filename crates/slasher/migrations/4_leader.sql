@@ -0,0 +1,5 @@
+CREATE TABLE leader (
+    id INTEGER PRIMARY KEY,
+    instance_id TEXT NOT NULL,
+    heartbeat_at BIGINT NOT NULL
+);
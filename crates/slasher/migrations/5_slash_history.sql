@@ -0,0 +1,9 @@
+CREATE TABLE slash_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id TEXT NOT NULL,
+    state TEXT NOT NULL,
+    slash_tx_hash TEXT,
+    recorded_at BIGINT NOT NULL
+);
+
+CREATE INDEX slash_history_order_id ON slash_history (order_id);
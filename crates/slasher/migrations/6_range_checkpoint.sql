@@ -0,0 +1,5 @@
+CREATE TABLE range_checkpoint (
+    id INTEGER PRIMARY KEY,
+    range_from BIGINT NOT NULL,
+    block BIGINT NOT NULL
+);
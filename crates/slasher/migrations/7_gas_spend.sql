@@ -0,0 +1,9 @@
+CREATE TABLE gas_spend (
+    tx_hash TEXT PRIMARY KEY,
+    order_id TEXT NOT NULL,
+    gas_used BIGINT NOT NULL,
+    cost_wei TEXT NOT NULL,
+    recorded_at BIGINT NOT NULL
+);
+
+CREATE INDEX gas_spend_recorded_at ON gas_spend (recorded_at);
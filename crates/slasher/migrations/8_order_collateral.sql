@@ -0,0 +1 @@
+ALTER TABLE orders ADD COLUMN collateral_wei TEXT;
@@ -0,0 +1,5 @@
+CREATE TABLE deployment (
+    id INTEGER PRIMARY KEY,
+    chain_id BIGINT NOT NULL,
+    market_address TEXT NOT NULL
+);
@@ -0,0 +1,311 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Admin commands over the tracked order DB.
+//!
+//! Lets operators inspect the tracked orders, and add or remove orders by hand to reconcile the
+//! tracked set after an incident, without writing SQL against the SQLite file. Changes are
+//! confirmed interactively unless `--yes` is given.
+
+use std::{
+    fmt::Write as _,
+    io::{IsTerminal, Write},
+    str::FromStr,
+    sync::Arc,
+};
+
+use alloy::primitives::U256;
+use anyhow::{bail, ensure, Context, Result};
+use clap::{Args, Subcommand};
+use sqlx::sqlite::SqliteConnectOptions;
+
+use crate::{
+    clock::{Clock, SystemClock},
+    db::{DbObj, ExpiryStage, OrderFilter, OrderState, SqliteDb, TrackedOrder},
+};
+
+/// Number of slash outcomes shown with an order.
+const SHOWN_SLASH_HISTORY: u64 = 10;
+
+/// Arguments of the `db` command.
+#[derive(Args, Clone, Debug)]
+pub struct DbArgs {
+    /// DB connection string of the slasher, e.g. `sqlite:slasher.db`.
+    #[clap(long, env)]
+    pub db: String,
+    /// Apply changes without asking for confirmation.
+    #[clap(long, short, global = true)]
+    pub yes: bool,
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+/// Admin commands over the tracked order DB.
+#[derive(Subcommand, Clone, Debug)]
+pub enum DbCommand {
+    /// List tracked orders, ordered by expiry.
+    List {
+        /// Only list orders in this state: tracked, slashing_inflight, slashed, or failed.
+        #[clap(long, value_parser = parse_state)]
+        state: Option<OrderState>,
+        /// Only list tracked orders in this stage as of now: locked, lock_expired, or expired.
+        #[clap(long, value_parser = parse_stage, conflicts_with = "state")]
+        stage: Option<ExpiryStage>,
+        /// Number of orders to skip.
+        #[clap(long, default_value = "0")]
+        offset: u64,
+        /// Maximum number of orders to list.
+        #[clap(long, default_value = "100")]
+        limit: u64,
+    },
+    /// Show an order with its collateral and slash history.
+    Show {
+        /// ID of the request.
+        request_id: U256,
+    },
+    /// Stop tracking an order, so that it is never slashed.
+    Remove {
+        /// ID of the request.
+        request_id: U256,
+    },
+    /// Track an order, so that it is slashed once it expires, unless it was fulfilled.
+    Add {
+        /// ID of the request.
+        request_id: U256,
+        /// Time, in seconds since the UNIX epoch, at which the request expires.
+        #[clap(long)]
+        expires_at: u64,
+        /// Time, in seconds since the UNIX epoch, at which the lock on the request expires.
+        #[clap(long)]
+        lock_expires_at: u64,
+    },
+}
+
+fn parse_state(name: &str) -> Result<OrderState, String> {
+    OrderState::from_name(name).ok_or_else(|| format!("unknown state {name}"))
+}
+
+fn parse_stage(name: &str) -> Result<ExpiryStage, String> {
+    ExpiryStage::from_name(name).ok_or_else(|| format!("unknown stage {name}"))
+}
+
+impl DbArgs {
+    /// Run the command against the DB, printing its output.
+    pub async fn run(&self) -> Result<()> {
+        // Opening a DB creates it if missing, which would hide a mistyped path.
+        let path = SqliteConnectOptions::from_str(&self.db)
+            .context("invalid DB connection string")?
+            .get_filename()
+            .to_path_buf();
+        ensure!(path.exists(), "DB file {} does not exist", path.display());
+        let db: DbObj = Arc::new(SqliteDb::new(&self.db).await?);
+
+        let yes = self.yes;
+        let output = self
+            .command
+            .execute(&db, SystemClock.now(), &mut |prompt| {
+                if yes {
+                    Ok(true)
+                } else {
+                    confirm(prompt)
+                }
+            })
+            .await?;
+        println!("{output}");
+        Ok(())
+    }
+}
+
+/// Ask the user to confirm a change on the terminal.
+fn confirm(prompt: &str) -> Result<bool> {
+    if !std::io::stdin().is_terminal() {
+        bail!("no terminal to confirm the change on; pass --yes to apply it");
+    }
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+impl DbCommand {
+    /// Run the command against `db` at time `now`, returning its output.
+    ///
+    /// Changes are only applied if `confirm` returns true for the given prompt.
+    pub async fn execute(
+        &self,
+        db: &DbObj,
+        now: u64,
+        confirm: &mut (dyn FnMut(&str) -> Result<bool> + Send),
+    ) -> Result<String> {
+        match self {
+            Self::List { state, stage, offset, limit } => {
+                let filter = match stage {
+                    Some(stage) => stage.filter(now),
+                    None => OrderFilter { state: *state, ..Default::default() },
+                };
+                let orders = db.list_orders(&filter, *offset, *limit).await?;
+                if orders.is_empty() {
+                    return Ok("No orders found".to_string());
+                }
+                let mut out = format!(
+                    "{:<66}  {:>12}  {:>15}  {:<17}  {}",
+                    "request id", "expires at", "lock expires at", "state", "stage"
+                );
+                for order in orders {
+                    write!(
+                        out,
+                        "\n{:<66}  {:>12}  {:>15}  {:<17}  {}",
+                        format!("0x{:x}", order.id),
+                        order.expires_at,
+                        order.lock_expires_at,
+                        order.state.as_str(),
+                        stage_of(&order, now)
+                    )?;
+                }
+                Ok(out)
+            }
+            Self::Show { request_id } => {
+                let order = get_tracked_order(db, *request_id).await?;
+                let collateral = db.get_order_collateral(*request_id).await?;
+                let history =
+                    db.get_slash_history(Some(*request_id), 0, SHOWN_SLASH_HISTORY).await?;
+
+                let mut out = format!("Order 0x{request_id:x}:");
+                write!(out, "\n  state:           {}", order.state.as_str())?;
+                if let OrderState::SlashingInflight(tx_hash) = order.state {
+                    write!(out, "\n  slash tx:        {tx_hash}")?;
+                }
+                write!(out, "\n  stage:           {}", stage_of(&order, now))?;
+                write!(out, "\n  expires at:      {}", order.expires_at)?;
+                write!(out, "\n  lock expires at: {}", order.lock_expires_at)?;
+                match collateral {
+                    Some(collateral) => {
+                        write!(out, "\n  collateral:      {collateral} (raw units)")?
+                    }
+                    None => write!(out, "\n  collateral:      unknown")?,
+                }
+                if !history.is_empty() {
+                    write!(out, "\n  slash history:")?;
+                    for record in history {
+                        write!(
+                            out,
+                            "\n    {}  {:<7}  {}",
+                            record.recorded_at,
+                            record.state.as_str(),
+                            record.tx_hash.map(|hash| hash.to_string()).unwrap_or_default()
+                        )?;
+                    }
+                }
+                Ok(out)
+            }
+            Self::Remove { request_id } => {
+                let order = get_tracked_order(db, *request_id).await?;
+                let mut prompt = format!(
+                    "Remove order 0x{request_id:x} ({}, expires at {})? It will never be slashed.",
+                    order.state.as_str(),
+                    order.expires_at
+                );
+                if let OrderState::SlashingInflight(tx_hash) = order.state {
+                    write!(prompt, " Its slash transaction {tx_hash} may still land.")?;
+                }
+                if !confirm(&prompt)? {
+                    return Ok("Aborted".to_string());
+                }
+                db.remove_order(*request_id).await?;
+                Ok(format!("Removed order 0x{request_id:x}"))
+            }
+            Self::Add { request_id, expires_at, lock_expires_at } => {
+                ensure!(*lock_expires_at > 0, "--lock-expires-at must be positive");
+                ensure!(
+                    lock_expires_at <= expires_at,
+                    "--lock-expires-at must not be after --expires-at"
+                );
+                if db.order_exists(*request_id).await? {
+                    bail!(
+                        "order 0x{request_id:x} is already tracked; remove it first to replace it"
+                    );
+                }
+                let prompt = format!(
+                    "Track order 0x{request_id:x}, with its lock expiring at {lock_expires_at} and \
+                     the request at {expires_at}? It will be slashed once it expires, unless it \
+                     was fulfilled."
+                );
+                if !confirm(&prompt)? {
+                    return Ok("Aborted".to_string());
+                }
+                db.add_order(*request_id, *expires_at, *lock_expires_at).await?;
+                Ok(format!("Added order 0x{request_id:x}"))
+            }
+        }
+    }
+}
+
+async fn get_tracked_order(db: &DbObj, id: U256) -> Result<TrackedOrder> {
+    let Some((expires_at, lock_expires_at)) = db.get_order(id).await? else {
+        bail!("order 0x{id:x} is not in the DB");
+    };
+    let state = db.get_order_state(id).await?.context("order has no state")?;
+    Ok(TrackedOrder { id, expires_at, lock_expires_at, state })
+}
+
+/// Stage of a tracked order at `now`, or `-` for orders that are no longer tracked.
+fn stage_of(order: &TrackedOrder, now: u64) -> &'static str {
+    match order.state {
+        OrderState::Tracked => {
+            ExpiryStage::at(order.expires_at, order.lock_expires_at, now).as_str()
+        }
+        _ => "-",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    #[sqlx::test]
+    async fn add_show_remove(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let id = U256::from(0xabc);
+        let add = DbCommand::Add { request_id: id, expires_at: 200, lock_expires_at: 100 };
+
+        // Nothing changes unless confirmed.
+        assert_eq!(add.execute(&db, 150, &mut |_| Ok(false)).await.unwrap(), "Aborted");
+        assert!(!db.order_exists(id).await.unwrap());
+
+        add.execute(&db, 150, &mut |_| Ok(true)).await.unwrap();
+        assert_eq!(db.get_order(id).await.unwrap(), Some((200, 100)));
+        add.execute(&db, 150, &mut |_| Ok(true)).await.unwrap_err();
+
+        let shown = DbCommand::Show { request_id: id }.execute(&db, 150, &mut |_| Ok(true));
+        let shown = shown.await.unwrap();
+        assert!(shown.contains("state:           tracked"));
+        assert!(shown.contains("stage:           lock_expired"));
+
+        let list = DbCommand::List {
+            state: None,
+            stage: Some(ExpiryStage::LockExpired),
+            offset: 0,
+            limit: 10,
+        };
+        assert!(list.execute(&db, 150, &mut |_| Ok(true)).await.unwrap().contains("0xabc"));
+        assert_eq!(list.execute(&db, 250, &mut |_| Ok(true)).await.unwrap(), "No orders found");
+
+        let remove = DbCommand::Remove { request_id: id };
+        remove.execute(&db, 150, &mut |_| Ok(true)).await.unwrap();
+        assert!(!db.order_exists(id).await.unwrap());
+        remove.execute(&db, 150, &mut |_| Ok(true)).await.unwrap_err();
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only HTTP API over the orders tracked by the slasher.
+//!
+//! Serves the tracked orders with their expiries and state, and the history of slash outcomes,
+//! so dashboards can show upcoming slash opportunities without access to the DB.
+//!
+//! - `GET /orders?state=&stage=&expires_after=&expires_before=&offset=&limit=`
+//! - `GET /orders/{request_id}`
+//! - `GET /stages`
+//! - `GET /slashes?request_id=&offset=&limit=`
+//! - `GET /gas?since=`
+
+use std::{net::SocketAddr, str::FromStr};
+
+use alloy::primitives::{B256, U256};
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    db::{
+        DbError, DbObj, ExpiryStage, GasSpend, OrderFilter, OrderState, SlashRecord, StageCounts,
+        TrackedOrder,
+    },
+};
+
+/// Number of items returned per page if no limit is given.
+pub const DEFAULT_PAGE_LIMIT: u64 = 100;
+/// Maximum number of items returned per page.
+pub const MAX_PAGE_LIMIT: u64 = 1000;
+
+#[derive(Debug, thiserror::Error)]
+enum ApiError {
+    #[error("invalid query parameter: {0}")]
+    BadRequest(String),
+    #[error("order 0x{0:x} not found")]
+    NotFound(U256),
+    #[error("DB error: {0}")]
+    Db(#[from] DbError),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let code = match self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Db(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        if code.is_server_error() {
+            tracing::error!("API error: {self}");
+        }
+        (code, Json(ErrorResponse { error: self.to_string() })).into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    #[serde(default)]
+    offset: u64,
+    limit: Option<u64>,
+}
+
+impl Pagination {
+    fn limit(&self) -> u64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OrdersQuery {
+    state: Option<String>,
+    /// Only list tracked orders in this [ExpiryStage] as of now.
+    stage: Option<String>,
+    expires_before: Option<u64>,
+    expires_after: Option<u64>,
+    #[serde(flatten)]
+    page: Pagination,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlashesQuery {
+    request_id: Option<String>,
+    #[serde(flatten)]
+    page: Pagination,
+}
+
+#[derive(Debug, Deserialize)]
+struct GasQuery {
+    since: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Page<T> {
+    items: Vec<T>,
+    offset: u64,
+    limit: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct OrderResponse {
+    request_id: String,
+    expires_at: u64,
+    lock_expires_at: u64,
+    state: &'static str,
+    slash_tx_hash: Option<B256>,
+}
+
+impl From<TrackedOrder> for OrderResponse {
+    fn from(order: TrackedOrder) -> Self {
+        Self {
+            request_id: format!("0x{:x}", order.id),
+            expires_at: order.expires_at,
+            lock_expires_at: order.lock_expires_at,
+            state: order.state.as_str(),
+            slash_tx_hash: match order.state {
+                OrderState::SlashingInflight(tx_hash) => Some(tx_hash),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StagesResponse {
+    locked: u64,
+    /// Orders whose lock expired but the request has not, which become slashable once it does.
+    slashable_soon: u64,
+    expired: u64,
+}
+
+impl From<StageCounts> for StagesResponse {
+    fn from(counts: StageCounts) -> Self {
+        Self { locked: counts.locked, slashable_soon: counts.lock_expired, expired: counts.expired }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SlashResponse {
+    request_id: String,
+    state: &'static str,
+    slash_tx_hash: Option<B256>,
+    recorded_at: u64,
+}
+
+impl From<SlashRecord> for SlashResponse {
+    fn from(record: SlashRecord) -> Self {
+        Self {
+            request_id: format!("0x{:x}", record.id),
+            state: record.state.as_str(),
+            slash_tx_hash: record.tx_hash,
+            recorded_at: record.recorded_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct GasResponse {
+    txs: u64,
+    gas_used: u64,
+    cost_wei: String,
+}
+
+impl From<GasSpend> for GasResponse {
+    fn from(spend: GasSpend) -> Self {
+        Self { txs: spend.txs, gas_used: spend.gas_used, cost_wei: spend.cost.to_string() }
+    }
+}
+
+fn parse_request_id(id: &str) -> Result<U256, ApiError> {
+    U256::from_str(id).map_err(|_| ApiError::BadRequest(format!("request ID {id}")))
+}
+
+async fn list_orders(
+    State(db): State<DbObj>,
+    Query(query): Query<OrdersQuery>,
+) -> Result<Json<Page<OrderResponse>>, ApiError> {
+    let state = query
+        .state
+        .as_deref()
+        .map(|name| {
+            OrderState::from_name(name).ok_or_else(|| ApiError::BadRequest(format!("state {name}")))
+        })
+        .transpose()?;
+    let stage = query
+        .stage
+        .as_deref()
+        .map(|name| {
+            ExpiryStage::from_name(name)
+                .ok_or_else(|| ApiError::BadRequest(format!("stage {name}")))
+        })
+        .transpose()?;
+    if stage.is_some() && state.is_some_and(|state| state != OrderState::Tracked) {
+        return Err(ApiError::BadRequest("stage only applies to tracked orders".to_string()));
+    }
+    let stage_filter = stage.map(|stage| stage.filter(SystemClock.now())).unwrap_or_default();
+    // The stage filter is combined with the expiry bounds of the query, keeping the tighter one.
+    let filter = OrderFilter {
+        state: state.or(stage_filter.state),
+        expires_before: min_bound(query.expires_before, stage_filter.expires_before),
+        expires_after: max_bound(query.expires_after, stage_filter.expires_after),
+        ..stage_filter
+    };
+    let limit = query.page.limit();
+    let orders = db.list_orders(&filter, query.page.offset, limit).await?;
+    Ok(Json(Page {
+        items: orders.into_iter().map(Into::into).collect(),
+        offset: query.page.offset,
+        limit,
+    }))
+}
+
+fn min_bound(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    a.into_iter().chain(b).min()
+}
+
+fn max_bound(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    a.into_iter().chain(b).max()
+}
+
+async fn get_stages(State(db): State<DbObj>) -> Result<Json<StagesResponse>, ApiError> {
+    Ok(Json(db.count_stages(SystemClock.now()).await?.into()))
+}
+
+async fn get_order(
+    State(db): State<DbObj>,
+    Path(request_id): Path<String>,
+) -> Result<Json<OrderResponse>, ApiError> {
+    let id = parse_request_id(&request_id)?;
+    let (expires_at, lock_expires_at) = db.get_order(id).await?.ok_or(ApiError::NotFound(id))?;
+    let state = db.get_order_state(id).await?.ok_or(ApiError::NotFound(id))?;
+    Ok(Json(TrackedOrder { id, expires_at, lock_expires_at, state }.into()))
+}
+
+async fn list_slashes(
+    State(db): State<DbObj>,
+    Query(query): Query<SlashesQuery>,
+) -> Result<Json<Page<SlashResponse>>, ApiError> {
+    let id = query.request_id.as_deref().map(parse_request_id).transpose()?;
+    let limit = query.page.limit();
+    let records = db.get_slash_history(id, query.page.offset, limit).await?;
+    Ok(Json(Page {
+        items: records.into_iter().map(Into::into).collect(),
+        offset: query.page.offset,
+        limit,
+    }))
+}
+
+async fn get_gas(
+    State(db): State<DbObj>,
+    Query(query): Query<GasQuery>,
+) -> Result<Json<GasResponse>, ApiError> {
+    Ok(Json(db.get_gas_spend(query.since).await?.into()))
+}
+
+/// Router serving the read-only API over the given DB.
+pub fn router(db: DbObj) -> Router {
+    Router::new()
+        .route("/orders", get(list_orders))
+        .route("/orders/{request_id}", get(get_order))
+        .route("/stages", get(get_stages))
+        .route("/slashes", get(list_slashes))
+        .route("/gas", get(get_gas))
+        .with_state(db)
+}
+
+/// Serve the read-only API over the given DB on `addr` until the process exits.
+pub async fn serve(addr: SocketAddr, db: DbObj) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind API to {addr}"))?;
+    tracing::info!("Serving slasher API on {addr}");
+    axum::serve(listener, router(db)).await.context("slasher API failed")
+}
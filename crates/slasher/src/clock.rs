@@ -0,0 +1,116 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sources of the current time and of the chain's blocks, injectable to test the service.
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{network::Ethereum, providers::Provider};
+use async_trait::async_trait;
+use boundless_market::stale_rpc_layer::{StaleRpcError, StaleRpcGuard};
+
+use crate::ServiceError;
+
+/// Source of the wall-clock time, used for the leader lease.
+pub trait Clock: Send + Sync {
+    /// Current UNIX timestamp, in seconds.
+    fn now(&self) -> u64;
+}
+
+/// [Clock] reading the system time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+}
+
+/// [Clock] that only moves when set, for deterministic tests.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(now: u64) -> Self {
+        Self { now: AtomicU64::new(now) }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::Relaxed)
+    }
+}
+
+/// Source of the head block and of block timestamps, which drive expiry of requests.
+#[async_trait]
+pub trait BlockSource: Send + Sync {
+    /// Number of the head block.
+    async fn block_number(&self) -> Result<u64, ServiceError>;
+
+    /// Timestamp of the given block.
+    async fn block_timestamp(&self, block_number: u64) -> Result<u64, ServiceError>;
+}
+
+/// [BlockSource] querying an RPC provider, refusing to return the head block of a lagging RPC.
+#[derive(Clone)]
+pub struct RpcBlockSource<P> {
+    provider: P,
+    stale_rpc_guard: StaleRpcGuard,
+}
+
+impl<P> RpcBlockSource<P> {
+    pub fn new(provider: P, stale_rpc_guard: StaleRpcGuard) -> Self {
+        Self { provider, stale_rpc_guard }
+    }
+}
+
+#[async_trait]
+impl<P> BlockSource for RpcBlockSource<P>
+where
+    P: Provider<Ethereum> + 'static,
+{
+    async fn block_number(&self) -> Result<u64, ServiceError> {
+        self.stale_rpc_guard.block_number(&self.provider).await.map_err(|err| {
+            match StaleRpcError::from_transport_error(&err) {
+                Some(stale) => ServiceError::StaleRpc(stale.clone()),
+                None => ServiceError::RpcError(err),
+            }
+        })
+    }
+
+    async fn block_timestamp(&self, block_number: u64) -> Result<u64, ServiceError> {
+        Ok(self
+            .provider
+            .get_block_by_number(block_number.into())
+            .await?
+            .ok_or(ServiceError::BlockTimestampNotFound(block_number))?
+            .header
+            .timestamp)
+    }
+}
@@ -12,9 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashSet, str::FromStr, sync::Arc};
 
-use alloy::primitives::U256;
+use alloy::primitives::{Address, B256, U256};
 use async_trait::async_trait;
 use sqlx::{
     sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
@@ -35,6 +35,221 @@ pub enum DbError {
 
     #[error("Failed to set last block")]
     SetBlockFail,
+
+    #[error("Invalid order state: {0}")]
+    BadOrderState(String),
+
+    #[error("Order not found: 0x{0:x}")]
+    OrderNotFound(U256),
+
+    #[error("Invalid market address: {0}")]
+    BadAddress(String),
+}
+
+/// Processing state of a tracked order.
+///
+/// Orders move from [OrderState::Tracked] to [OrderState::SlashingInflight] once a slash
+/// transaction is broadcast, and to [OrderState::Slashed] or [OrderState::Failed] once its outcome
+/// is known. Recording the transaction hash before waiting for it lets the service resolve
+/// in-flight slashes after a restart instead of sending a duplicate transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderState {
+    /// The order is locked and waiting to expire.
+    Tracked,
+    /// A slash transaction with the given hash was broadcast but its outcome is not yet known.
+    SlashingInflight(B256),
+    /// The order has been slashed.
+    Slashed,
+    /// The order cannot be slashed, e.g. because it was fulfilled or is not locked.
+    Failed,
+}
+
+impl OrderState {
+    /// Name of the state, as stored in the DB.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderState::Tracked => "tracked",
+            OrderState::SlashingInflight(_) => "slashing_inflight",
+            OrderState::Slashed => "slashed",
+            OrderState::Failed => "failed",
+        }
+    }
+
+    /// Parse a state name, as returned by [OrderState::as_str].
+    ///
+    /// The transaction hash of a parsed [OrderState::SlashingInflight] is zero, which is enough
+    /// to filter orders by state.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tracked" => Some(OrderState::Tracked),
+            "slashing_inflight" => Some(OrderState::SlashingInflight(B256::ZERO)),
+            "slashed" => Some(OrderState::Slashed),
+            "failed" => Some(OrderState::Failed),
+            _ => None,
+        }
+    }
+
+    fn is_final(&self) -> bool {
+        matches!(self, OrderState::Slashed | OrderState::Failed)
+    }
+
+    fn tx_hash(&self) -> Option<B256> {
+        match self {
+            OrderState::SlashingInflight(tx_hash) => Some(*tx_hash),
+            _ => None,
+        }
+    }
+
+    fn from_columns(state: &str, tx_hash: Option<String>) -> Result<Self, DbError> {
+        match state {
+            "tracked" => Ok(OrderState::Tracked),
+            "slashing_inflight" => {
+                let tx_hash = tx_hash.ok_or_else(|| DbError::BadOrderState(state.to_string()))?;
+                let tx_hash =
+                    tx_hash.parse().map_err(|_| DbError::BadOrderState(tx_hash.clone()))?;
+                Ok(OrderState::SlashingInflight(tx_hash))
+            }
+            "slashed" => Ok(OrderState::Slashed),
+            "failed" => Ok(OrderState::Failed),
+            _ => Err(DbError::BadOrderState(state.to_string())),
+        }
+    }
+}
+
+/// An order in the DB, with its expiries and processing state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackedOrder {
+    pub id: U256,
+    pub expires_at: u64,
+    pub lock_expires_at: u64,
+    pub state: OrderState,
+}
+
+/// Stage of a tracked order relative to its two expiries, as of a given time.
+///
+/// The lock of an order expires first, after which the request can still be fulfilled by any
+/// prover, but the locking prover can only be slashed once the request itself expires. Orders
+/// whose lock expired are reported as slashable soon, and only expired orders are slashed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpiryStage {
+    /// The lock has not expired yet.
+    Locked,
+    /// The lock expired, but the request has not, so the order is not slashable yet.
+    LockExpired,
+    /// The request expired, so the order is slashable.
+    Expired,
+}
+
+impl ExpiryStage {
+    /// Stage of an order with the given expiries at `current_timestamp`.
+    ///
+    /// Like the market, an expiry is reached once the current timestamp is past it.
+    pub fn at(expires_at: u64, lock_expires_at: u64, current_timestamp: u64) -> Self {
+        if current_timestamp > expires_at {
+            ExpiryStage::Expired
+        } else if current_timestamp > lock_expires_at {
+            ExpiryStage::LockExpired
+        } else {
+            ExpiryStage::Locked
+        }
+    }
+
+    /// Name of the stage, as used by the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpiryStage::Locked => "locked",
+            ExpiryStage::LockExpired => "lock_expired",
+            ExpiryStage::Expired => "expired",
+        }
+    }
+
+    /// Parse a stage name, as returned by [ExpiryStage::as_str].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "locked" => Some(ExpiryStage::Locked),
+            "lock_expired" => Some(ExpiryStage::LockExpired),
+            "expired" => Some(ExpiryStage::Expired),
+            _ => None,
+        }
+    }
+
+    /// Filter listing the tracked orders in this stage at `current_timestamp`.
+    pub fn filter(&self, current_timestamp: u64) -> OrderFilter {
+        let tracked = OrderFilter { state: Some(OrderState::Tracked), ..Default::default() };
+        match self {
+            ExpiryStage::Locked => {
+                OrderFilter { lock_expires_after: Some(current_timestamp), ..tracked }
+            }
+            ExpiryStage::LockExpired => OrderFilter {
+                lock_expires_before: Some(current_timestamp),
+                expires_after: Some(current_timestamp),
+                ..tracked
+            },
+            ExpiryStage::Expired => {
+                OrderFilter { expires_before: Some(current_timestamp), ..tracked }
+            }
+        }
+    }
+}
+
+/// Number of tracked orders in each [ExpiryStage].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StageCounts {
+    pub locked: u64,
+    /// Orders whose lock expired but the request has not, i.e. slashable soon.
+    pub lock_expired: u64,
+    pub expired: u64,
+}
+
+/// Final outcome of processing an expired order, recorded when it is slashed or fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlashRecord {
+    pub id: U256,
+    /// Either [OrderState::Slashed] or [OrderState::Failed].
+    pub state: OrderState,
+    /// Hash of the slash transaction sent by this service, if any.
+    pub tx_hash: Option<B256>,
+    /// Time, in seconds since the UNIX epoch, the outcome was recorded.
+    pub recorded_at: u64,
+}
+
+/// Gas spent on slash transactions, summed over a period.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GasSpend {
+    /// Number of slash transactions, including reverted ones.
+    pub txs: u64,
+    /// Total gas used.
+    pub gas_used: u64,
+    /// Total cost in wei.
+    pub cost: U256,
+}
+
+/// A tracked order locked by a partner prover, whose slash is deferred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartnerOrder {
+    pub id: U256,
+    /// Address of the partner prover that locked the order.
+    pub prover: Address,
+    pub expires_at: u64,
+    pub lock_expires_at: u64,
+    /// Time, in seconds since the UNIX epoch, after which the order may be slashed.
+    pub slash_after: u64,
+}
+
+/// Filter applied when listing orders.
+#[derive(Clone, Debug, Default)]
+pub struct OrderFilter {
+    /// Only list orders in this state. The transaction hash of a
+    /// [OrderState::SlashingInflight] filter is ignored.
+    pub state: Option<OrderState>,
+    /// Only list orders expiring before this timestamp.
+    pub expires_before: Option<u64>,
+    /// Only list orders expiring at or after this timestamp.
+    pub expires_after: Option<u64>,
+    /// Only list orders whose lock expires before this timestamp.
+    pub lock_expires_before: Option<u64>,
+    /// Only list orders whose lock expires at or after this timestamp.
+    pub lock_expires_after: Option<u64>,
 }
 
 #[async_trait]
@@ -49,14 +264,98 @@ pub trait SlasherDb {
     async fn remove_order(&self, id: U256) -> Result<(), DbError>;
     async fn order_exists(&self, id: U256) -> Result<bool, DbError>;
     async fn get_expired_orders(&self, current_timestamp: u64) -> Result<Vec<U256>, DbError>;
+    /// Get the tracked orders whose lock expired before `current_timestamp`.
+    async fn get_lock_expired_orders(&self, current_timestamp: u64) -> Result<Vec<U256>, DbError>;
+    /// Count the tracked orders in each [ExpiryStage] at `current_timestamp`.
+    async fn count_stages(&self, current_timestamp: u64) -> Result<StageCounts, DbError>;
+    async fn get_order_state(&self, id: U256) -> Result<Option<OrderState>, DbError>;
+    async fn set_order_state(&self, id: U256, state: OrderState) -> Result<(), DbError>;
+    async fn get_inflight_orders(&self) -> Result<Vec<(U256, B256)>, DbError>;
+    /// Record the collateral locked by the prover of an order. A no-op if the order is not tracked.
+    async fn set_order_collateral(&self, id: U256, collateral: U256) -> Result<(), DbError>;
+    /// Get the collateral of an order, if the order is tracked and its collateral is recorded.
+    async fn get_order_collateral(&self, id: U256) -> Result<Option<U256>, DbError>;
+    /// Record that an order was locked by the given partner prover, deferring its slash until
+    /// after `slash_after`. A no-op if the order is not tracked.
+    async fn set_order_partner(
+        &self,
+        id: U256,
+        prover: Address,
+        slash_after: u64,
+    ) -> Result<(), DbError>;
+    /// Get the partner prover of an order, if it is tracked and was locked by a partner.
+    async fn get_order_partner(&self, id: U256) -> Result<Option<Address>, DbError>;
+    /// Get the IDs of the orders in the given state that were locked by a partner.
+    async fn get_partner_order_ids(&self, state: OrderState) -> Result<HashSet<U256>, DbError>;
+    /// Get the tracked partner orders whose lock expired before `current_timestamp` and whose
+    /// partner was not yet notified.
+    async fn get_unnotified_partner_orders(
+        &self,
+        current_timestamp: u64,
+    ) -> Result<Vec<PartnerOrder>, DbError>;
+    /// Record that the partner of an order was notified at `notified_at`.
+    async fn set_order_notified(&self, id: U256, notified_at: u64) -> Result<(), DbError>;
+    /// List orders matching the filter, ordered by expiry.
+    async fn list_orders(
+        &self,
+        filter: &OrderFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<TrackedOrder>, DbError>;
+    /// List recorded slash outcomes, most recent first, optionally only those of one order.
+    async fn get_slash_history(
+        &self,
+        id: Option<U256>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<SlashRecord>, DbError>;
+    /// Record the gas spent by a slash transaction. Recording the same transaction again is a
+    /// no-op.
+    async fn add_gas_spend(
+        &self,
+        id: U256,
+        tx_hash: B256,
+        gas_used: u64,
+        cost: U256,
+        recorded_at: u64,
+    ) -> Result<(), DbError>;
+    /// Sum the gas spent on slash transactions recorded at or after `since`, or ever if `None`.
+    async fn get_gas_spend(&self, since: Option<u64>) -> Result<GasSpend, DbError>;
+    /// Delete slashed and failed orders that expired before `before`, along with the slash
+    /// history and gas spend recorded before it, returning the number of rows deleted.
+    async fn prune(&self, before: u64) -> Result<u64, DbError>;
+    /// Reclaim the space freed by deleted rows and refresh the query planner statistics.
+    async fn compact(&self) -> Result<(), DbError>;
+    async fn add_event_gap(&self, from_block: u64, to_block: u64) -> Result<(), DbError>;
+    async fn get_event_gaps(&self) -> Result<Vec<(u64, u64)>, DbError>; // (from_block, to_block)
+    async fn remove_event_gap(&self, from_block: u64, to_block: u64) -> Result<(), DbError>;
+    async fn claim_leadership(
+        &self,
+        instance_id: &str,
+        now: u64,
+        stale_after: u64,
+    ) -> Result<bool, DbError>;
+    async fn get_leader(&self) -> Result<Option<(String, u64)>, DbError>; // (instance_id, heartbeat_at)
 
     async fn get_last_block(&self) -> Result<Option<u64>, DbError>;
     async fn set_last_block(&self, block_numb: u64) -> Result<(), DbError>;
+
+    /// Get the progress through the range being processed: blocks `range_from` to `block` are done.
+    async fn get_range_checkpoint(&self) -> Result<Option<(u64, u64)>, DbError>; // (range_from, block)
+    async fn set_range_checkpoint(&self, range_from: u64, block: u64) -> Result<(), DbError>;
+    async fn clear_range_checkpoint(&self) -> Result<(), DbError>;
+
+    /// Get the chain ID and market address the DB was used with.
+    async fn get_deployment(&self) -> Result<Option<(u64, Address)>, DbError>;
+    async fn set_deployment(&self, chain_id: u64, market_address: Address) -> Result<(), DbError>;
 }
 
 pub type DbObj = Arc<dyn SlasherDb + Send + Sync>;
 
 const SQL_BLOCK_KEY: i64 = 0;
+const SQL_LEADER_KEY: i64 = 0;
+const SQL_CHECKPOINT_KEY: i64 = 0;
+const SQL_DEPLOYMENT_KEY: i64 = 0;
 
 pub struct SqliteDb {
     pool: SqlitePool,
@@ -98,6 +397,10 @@ struct DbOrder {
     id: String,
 }
 
+fn parse_order_id(id: &str) -> Result<U256, sqlx::Error> {
+    U256::from_str_radix(id, 16).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
 #[async_trait]
 impl SlasherDb for SqliteDb {
     async fn add_order(
@@ -159,15 +462,431 @@ impl SlasherDb for SqliteDb {
     }
 
     async fn get_expired_orders(&self, current_timestamp: u64) -> Result<Vec<U256>, DbError> {
-        let orders: Vec<DbOrder> = sqlx::query_as("SELECT id FROM orders WHERE $1 > expires_at")
-            .bind(current_timestamp as i64)
+        // Orders locked by partners are only returned once their grace period is over.
+        let orders: Vec<DbOrder> = sqlx::query_as(
+            "SELECT id FROM orders WHERE $1 > expires_at AND state = $2 \
+             AND (slash_after IS NULL OR $1 > slash_after)",
+        )
+        .bind(current_timestamp as i64)
+        .bind(OrderState::Tracked.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(orders.into_iter().map(|x| parse_order_id(&x.id)).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn get_lock_expired_orders(&self, current_timestamp: u64) -> Result<Vec<U256>, DbError> {
+        let orders: Vec<DbOrder> =
+            sqlx::query_as("SELECT id FROM orders WHERE $1 > lock_expires_at AND state = $2")
+                .bind(current_timestamp as i64)
+                .bind(OrderState::Tracked.as_str())
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(orders.into_iter().map(|x| parse_order_id(&x.id)).collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn count_stages(&self, current_timestamp: u64) -> Result<StageCounts, DbError> {
+        let row = sqlx::query(
+            "SELECT \
+             COUNT(CASE WHEN $1 <= lock_expires_at AND $1 <= expires_at THEN 1 END) AS locked, \
+             COUNT(CASE WHEN $1 > lock_expires_at AND $1 <= expires_at THEN 1 END) AS lock_expired, \
+             COUNT(CASE WHEN $1 > expires_at THEN 1 END) AS expired \
+             FROM orders WHERE state = $2",
+        )
+        .bind(current_timestamp as i64)
+        .bind(OrderState::Tracked.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        let locked: i64 = row.try_get("locked")?;
+        let lock_expired: i64 = row.try_get("lock_expired")?;
+        let expired: i64 = row.try_get("expired")?;
+        Ok(StageCounts {
+            locked: locked as u64,
+            lock_expired: lock_expired as u64,
+            expired: expired as u64,
+        })
+    }
+
+    async fn get_order_state(&self, id: U256) -> Result<Option<OrderState>, DbError> {
+        let res = sqlx::query("SELECT state, slash_tx_hash FROM orders WHERE id = $1")
+            .bind(format!("{id:x}"))
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = res else {
+            return Ok(None);
+        };
+        let state: String = row.try_get("state")?;
+        let tx_hash: Option<String> = row.try_get("slash_tx_hash")?;
+        Ok(Some(OrderState::from_columns(&state, tx_hash)?))
+    }
+
+    async fn set_order_state(&self, id: U256, state: OrderState) -> Result<(), DbError> {
+        tracing::trace!("Setting state of order 0x{:x} to {:?}", id, state);
+        if state.is_final() {
+            // Record the outcome before the update clears the hash of the in-flight slash.
+            sqlx::query(
+                "INSERT INTO slash_history (order_id, state, slash_tx_hash, recorded_at) \
+                 SELECT id, $1, slash_tx_hash, CAST(strftime('%s', 'now') AS INTEGER) \
+                 FROM orders WHERE id = $2 AND state != $1",
+            )
+            .bind(state.as_str())
+            .bind(format!("{id:x}"))
+            .execute(&self.pool)
+            .await?;
+        }
+        let res = sqlx::query("UPDATE orders SET state = $1, slash_tx_hash = $2 WHERE id = $3")
+            .bind(state.as_str())
+            .bind(state.tx_hash().map(|tx_hash| tx_hash.to_string()))
+            .bind(format!("{id:x}"))
+            .execute(&self.pool)
+            .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(DbError::OrderNotFound(id));
+        }
+
+        Ok(())
+    }
+
+    async fn get_inflight_orders(&self) -> Result<Vec<(U256, B256)>, DbError> {
+        let rows = sqlx::query("SELECT id, slash_tx_hash FROM orders WHERE state = $1")
+            .bind(OrderState::SlashingInflight(B256::ZERO).as_str())
             .fetch_all(&self.pool)
             .await?;
 
-        Ok(orders
-            .into_iter()
-            .map(|x| U256::from_str_radix(&x.id, 16).map_err(|e| sqlx::Error::Decode(Box::new(e))))
-            .collect::<Result<Vec<_>, sqlx::Error>>()?)
+        rows.into_iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                let tx_hash: Option<String> = row.try_get("slash_tx_hash")?;
+                let tx_hash = tx_hash
+                    .as_deref()
+                    .and_then(|tx_hash| tx_hash.parse().ok())
+                    .ok_or_else(|| DbError::BadOrderState(format!("slash tx hash of 0x{id}")))?;
+                Ok((parse_order_id(&id)?, tx_hash))
+            })
+            .collect()
+    }
+
+    async fn set_order_collateral(&self, id: U256, collateral: U256) -> Result<(), DbError> {
+        sqlx::query("UPDATE orders SET collateral_wei = $1 WHERE id = $2")
+            .bind(collateral.to_string())
+            .bind(format!("{id:x}"))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_order_collateral(&self, id: U256) -> Result<Option<U256>, DbError> {
+        let collateral: Option<Option<String>> =
+            sqlx::query_scalar("SELECT collateral_wei FROM orders WHERE id = $1")
+                .bind(format!("{id:x}"))
+                .fetch_optional(&self.pool)
+                .await?;
+        collateral
+            .flatten()
+            .map(|collateral| {
+                U256::from_str_radix(&collateral, 10)
+                    .map_err(|e| DbError::from(sqlx::Error::Decode(Box::new(e))))
+            })
+            .transpose()
+    }
+
+    async fn set_order_partner(
+        &self,
+        id: U256,
+        prover: Address,
+        slash_after: u64,
+    ) -> Result<(), DbError> {
+        sqlx::query("UPDATE orders SET partner = $1, slash_after = $2 WHERE id = $3")
+            .bind(prover.to_string())
+            .bind(slash_after as i64)
+            .bind(format!("{id:x}"))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_order_partner(&self, id: U256) -> Result<Option<Address>, DbError> {
+        let partner: Option<Option<String>> =
+            sqlx::query_scalar("SELECT partner FROM orders WHERE id = $1")
+                .bind(format!("{id:x}"))
+                .fetch_optional(&self.pool)
+                .await?;
+        partner
+            .flatten()
+            .map(|partner| partner.parse().map_err(|_| DbError::BadAddress(partner)))
+            .transpose()
+    }
+
+    async fn get_partner_order_ids(&self, state: OrderState) -> Result<HashSet<U256>, DbError> {
+        let ids: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM orders WHERE state = $1 AND partner IS NOT NULL")
+                .bind(state.as_str())
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(ids.iter().map(|id| parse_order_id(id)).collect::<Result<_, _>>()?)
+    }
+
+    async fn get_unnotified_partner_orders(
+        &self,
+        current_timestamp: u64,
+    ) -> Result<Vec<PartnerOrder>, DbError> {
+        let rows = sqlx::query(
+            "SELECT id, partner, expires_at, lock_expires_at, slash_after FROM orders \
+             WHERE state = $1 AND partner IS NOT NULL AND notified_at IS NULL \
+             AND $2 > lock_expires_at ORDER BY lock_expires_at",
+        )
+        .bind(OrderState::Tracked.as_str())
+        .bind(current_timestamp as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                let partner: String = row.try_get("partner")?;
+                let expires_at: i64 = row.try_get("expires_at")?;
+                let lock_expires_at: i64 = row.try_get("lock_expires_at")?;
+                let slash_after: Option<i64> = row.try_get("slash_after")?;
+                Ok(PartnerOrder {
+                    id: parse_order_id(&id)?,
+                    prover: partner.parse().map_err(|_| DbError::BadAddress(partner))?,
+                    expires_at: expires_at as u64,
+                    lock_expires_at: lock_expires_at as u64,
+                    slash_after: slash_after.unwrap_or(expires_at) as u64,
+                })
+            })
+            .collect()
+    }
+
+    async fn set_order_notified(&self, id: U256, notified_at: u64) -> Result<(), DbError> {
+        sqlx::query("UPDATE orders SET notified_at = $1 WHERE id = $2")
+            .bind(notified_at as i64)
+            .bind(format!("{id:x}"))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_orders(
+        &self,
+        filter: &OrderFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<TrackedOrder>, DbError> {
+        let rows = sqlx::query(
+            "SELECT id, expires_at, lock_expires_at, state, slash_tx_hash FROM orders \
+             WHERE ($1 IS NULL OR state = $1) \
+             AND ($2 IS NULL OR expires_at < $2) \
+             AND ($3 IS NULL OR expires_at >= $3) \
+             AND ($4 IS NULL OR lock_expires_at < $4) \
+             AND ($5 IS NULL OR lock_expires_at >= $5) \
+             ORDER BY expires_at, id LIMIT $6 OFFSET $7",
+        )
+        .bind(filter.state.map(|state| state.as_str()))
+        .bind(filter.expires_before.map(|ts| ts as i64))
+        .bind(filter.expires_after.map(|ts| ts as i64))
+        .bind(filter.lock_expires_before.map(|ts| ts as i64))
+        .bind(filter.lock_expires_after.map(|ts| ts as i64))
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id: String = row.try_get("id")?;
+                let expires_at: i64 = row.try_get("expires_at")?;
+                let lock_expires_at: i64 = row.try_get("lock_expires_at")?;
+                let state: String = row.try_get("state")?;
+                let tx_hash: Option<String> = row.try_get("slash_tx_hash")?;
+                Ok(TrackedOrder {
+                    id: parse_order_id(&id)?,
+                    expires_at: expires_at as u64,
+                    lock_expires_at: lock_expires_at as u64,
+                    state: OrderState::from_columns(&state, tx_hash)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_slash_history(
+        &self,
+        id: Option<U256>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<SlashRecord>, DbError> {
+        let rows = sqlx::query(
+            "SELECT order_id, state, slash_tx_hash, recorded_at FROM slash_history \
+             WHERE ($1 IS NULL OR order_id = $1) \
+             ORDER BY id DESC LIMIT $2 OFFSET $3",
+        )
+        .bind(id.map(|id| format!("{id:x}")))
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let id: String = row.try_get("order_id")?;
+                let state: String = row.try_get("state")?;
+                let tx_hash: Option<String> = row.try_get("slash_tx_hash")?;
+                let recorded_at: i64 = row.try_get("recorded_at")?;
+                Ok(SlashRecord {
+                    id: parse_order_id(&id)?,
+                    state: OrderState::from_columns(&state, None)?,
+                    tx_hash: tx_hash.and_then(|tx_hash| tx_hash.parse().ok()),
+                    recorded_at: recorded_at as u64,
+                })
+            })
+            .collect()
+    }
+
+    async fn add_gas_spend(
+        &self,
+        id: U256,
+        tx_hash: B256,
+        gas_used: u64,
+        cost: U256,
+        recorded_at: u64,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO gas_spend (tx_hash, order_id, gas_used, cost_wei, recorded_at) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(tx_hash.to_string())
+        .bind(format!("{id:x}"))
+        .bind(gas_used as i64)
+        .bind(cost.to_string())
+        .bind(recorded_at as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_gas_spend(&self, since: Option<u64>) -> Result<GasSpend, DbError> {
+        // Costs are stored as decimal strings, as they may not fit in an SQLite integer, and so
+        // are summed here rather than in the query.
+        let rows = sqlx::query(
+            "SELECT gas_used, cost_wei FROM gas_spend WHERE ($1 IS NULL OR recorded_at >= $1)",
+        )
+        .bind(since.map(|since| since as i64))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut spend = GasSpend::default();
+        for row in rows {
+            let gas_used: i64 = row.try_get("gas_used")?;
+            let cost: String = row.try_get("cost_wei")?;
+            spend.txs += 1;
+            spend.gas_used += gas_used as u64;
+            spend.cost +=
+                U256::from_str_radix(&cost, 10).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+        }
+        Ok(spend)
+    }
+
+    async fn prune(&self, before: u64) -> Result<u64, DbError> {
+        let mut tx = self.pool.begin().await?;
+        let orders = sqlx::query("DELETE FROM orders WHERE expires_at < $1 AND state IN ($2, $3)")
+            .bind(before as i64)
+            .bind(OrderState::Slashed.as_str())
+            .bind(OrderState::Failed.as_str())
+            .execute(&mut *tx)
+            .await?;
+        let history = sqlx::query("DELETE FROM slash_history WHERE recorded_at < $1")
+            .bind(before as i64)
+            .execute(&mut *tx)
+            .await?;
+        let gas_spend = sqlx::query("DELETE FROM gas_spend WHERE recorded_at < $1")
+            .bind(before as i64)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(orders.rows_affected() + history.rows_affected() + gas_spend.rows_affected())
+    }
+
+    async fn compact(&self) -> Result<(), DbError> {
+        // VACUUM cannot run inside a transaction, so these run as separate statements.
+        sqlx::query("VACUUM").execute(&self.pool).await?;
+        sqlx::query("ANALYZE").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn add_event_gap(&self, from_block: u64, to_block: u64) -> Result<(), DbError> {
+        tracing::trace!("Adding event gap: {} to {}", from_block, to_block);
+        sqlx::query("INSERT INTO event_gaps (from_block, to_block) VALUES ($1, $2)")
+            .bind(from_block as i64)
+            .bind(to_block as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_event_gaps(&self) -> Result<Vec<(u64, u64)>, DbError> {
+        let rows = sqlx::query("SELECT from_block, to_block FROM event_gaps ORDER BY from_block")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let from_block: i64 = row.try_get("from_block")?;
+                let to_block: i64 = row.try_get("to_block")?;
+                Ok((from_block as u64, to_block as u64))
+            })
+            .collect()
+    }
+
+    async fn remove_event_gap(&self, from_block: u64, to_block: u64) -> Result<(), DbError> {
+        tracing::trace!("Removing event gap: {} to {}", from_block, to_block);
+        sqlx::query("DELETE FROM event_gaps WHERE from_block = $1 AND to_block = $2")
+            .bind(from_block as i64)
+            .bind(to_block as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Take or renew the leader lease in a single statement, so two instances cannot both hold
+    // it. The lease is granted if it is unheld, already held by the instance, or its last
+    // heartbeat is older than `stale_after` seconds.
+    async fn claim_leadership(
+        &self,
+        instance_id: &str,
+        now: u64,
+        stale_after: u64,
+    ) -> Result<bool, DbError> {
+        let res = sqlx::query(
+            "INSERT INTO leader (id, instance_id, heartbeat_at) VALUES ($1, $2, $3) \
+             ON CONFLICT(id) DO UPDATE SET instance_id = excluded.instance_id, heartbeat_at = excluded.heartbeat_at \
+             WHERE leader.instance_id = excluded.instance_id OR leader.heartbeat_at < $4",
+        )
+        .bind(SQL_LEADER_KEY)
+        .bind(instance_id)
+        .bind(now as i64)
+        .bind(now.saturating_sub(stale_after) as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    async fn get_leader(&self) -> Result<Option<(String, u64)>, DbError> {
+        let res = sqlx::query("SELECT instance_id, heartbeat_at FROM leader WHERE id = $1")
+            .bind(SQL_LEADER_KEY)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = res else {
+            return Ok(None);
+        };
+        let instance_id: String = row.try_get("instance_id")?;
+        let heartbeat_at: i64 = row.try_get("heartbeat_at")?;
+        Ok(Some((instance_id, heartbeat_at as u64)))
     }
 
     async fn get_last_block(&self) -> Result<Option<u64>, DbError> {
@@ -198,12 +917,69 @@ impl SlasherDb for SqliteDb {
 
         Ok(())
     }
+
+    async fn get_range_checkpoint(&self) -> Result<Option<(u64, u64)>, DbError> {
+        let res = sqlx::query("SELECT range_from, block FROM range_checkpoint WHERE id = $1")
+            .bind(SQL_CHECKPOINT_KEY)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = res else {
+            return Ok(None);
+        };
+        let range_from: i64 = row.try_get("range_from")?;
+        let block: i64 = row.try_get("block")?;
+        Ok(Some((range_from as u64, block as u64)))
+    }
+
+    async fn set_range_checkpoint(&self, range_from: u64, block: u64) -> Result<(), DbError> {
+        sqlx::query("REPLACE INTO range_checkpoint (id, range_from, block) VALUES ($1, $2, $3)")
+            .bind(SQL_CHECKPOINT_KEY)
+            .bind(range_from as i64)
+            .bind(block as i64)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn clear_range_checkpoint(&self) -> Result<(), DbError> {
+        sqlx::query("DELETE FROM range_checkpoint WHERE id = $1")
+            .bind(SQL_CHECKPOINT_KEY)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_deployment(&self) -> Result<Option<(u64, Address)>, DbError> {
+        let res = sqlx::query("SELECT chain_id, market_address FROM deployment WHERE id = $1")
+            .bind(SQL_DEPLOYMENT_KEY)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = res else {
+            return Ok(None);
+        };
+        let chain_id: i64 = row.try_get("chain_id")?;
+        let address: String = row.try_get("market_address")?;
+        let address = address.parse().map_err(|_err| DbError::BadAddress(address))?;
+        Ok(Some((chain_id as u64, address)))
+    }
+
+    async fn set_deployment(&self, chain_id: u64, market_address: Address) -> Result<(), DbError> {
+        sqlx::query("REPLACE INTO deployment (id, chain_id, market_address) VALUES ($1, $2, $3)")
+            .bind(SQL_DEPLOYMENT_KEY)
+            .bind(chain_id as i64)
+            .bind(market_address.to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloy::primitives::U256;
+    use alloy::primitives::{B256, U256};
 
     #[sqlx::test]
     async fn add_order(pool: SqlitePool) {
@@ -220,6 +996,57 @@ mod tests {
         assert!(!db.order_exists(id).await.unwrap());
     }
 
+    #[sqlx::test]
+    async fn order_collateral(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let id = U256::from(1);
+        db.add_order(id, 10, 5).await.unwrap();
+        assert_eq!(db.get_order_collateral(id).await.unwrap(), None);
+
+        db.set_order_collateral(id, U256::from(1000)).await.unwrap();
+        assert_eq!(db.get_order_collateral(id).await.unwrap(), Some(U256::from(1000)));
+
+        // Untracked orders have no collateral.
+        db.set_order_collateral(U256::from(2), U256::from(1000)).await.unwrap();
+        assert_eq!(db.get_order_collateral(U256::from(2)).await.unwrap(), None);
+    }
+
+    #[sqlx::test]
+    async fn partner_orders(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let prover = Address::repeat_byte(1);
+        db.add_order(U256::from(1), 10, 5).await.unwrap();
+        db.add_order(U256::from(2), 10, 5).await.unwrap();
+        db.set_order_partner(U256::from(2), prover, 20).await.unwrap();
+        assert_eq!(db.get_order_partner(U256::from(1)).await.unwrap(), None);
+        assert_eq!(db.get_order_partner(U256::from(2)).await.unwrap(), Some(prover));
+        assert_eq!(
+            db.get_partner_order_ids(OrderState::Tracked).await.unwrap(),
+            HashSet::from([U256::from(2)])
+        );
+        assert!(db.get_partner_order_ids(OrderState::Slashed).await.unwrap().is_empty());
+
+        // The partner order is due for notification once its lock expires.
+        assert!(db.get_unnotified_partner_orders(5).await.unwrap().is_empty());
+        let due = db.get_unnotified_partner_orders(6).await.unwrap();
+        assert_eq!(
+            due,
+            vec![PartnerOrder {
+                id: U256::from(2),
+                prover,
+                expires_at: 10,
+                lock_expires_at: 5,
+                slash_after: 20
+            }]
+        );
+        db.set_order_notified(U256::from(2), 6).await.unwrap();
+        assert!(db.get_unnotified_partner_orders(6).await.unwrap().is_empty());
+
+        // Its slash is deferred until after the grace period.
+        assert_eq!(db.get_expired_orders(11).await.unwrap(), vec![U256::from(1)]);
+        assert_eq!(db.get_expired_orders(21).await.unwrap().len(), 2);
+    }
+
     #[sqlx::test]
     async fn drop_order(pool: SqlitePool) {
         let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
@@ -260,6 +1087,197 @@ mod tests {
         assert_eq!(id, db_order[0]);
     }
 
+    #[sqlx::test]
+    async fn get_lock_expired_orders(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let id = U256::ZERO;
+        db.add_order(id, 10, 5).await.unwrap();
+
+        assert!(db.get_lock_expired_orders(5).await.unwrap().is_empty());
+        assert_eq!(db.get_lock_expired_orders(6).await.unwrap(), vec![id]);
+
+        db.set_order_state(id, OrderState::Slashed).await.unwrap();
+        assert!(db.get_lock_expired_orders(6).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    async fn expiry_stages(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        // Locks expire at 50 and 100, and requests at 100 and 200.
+        db.add_order(U256::from(1), 100, 50).await.unwrap();
+        db.add_order(U256::from(2), 200, 100).await.unwrap();
+
+        assert_eq!(ExpiryStage::at(100, 50, 50), ExpiryStage::Locked);
+        assert_eq!(ExpiryStage::at(100, 50, 51), ExpiryStage::LockExpired);
+        assert_eq!(ExpiryStage::at(100, 50, 101), ExpiryStage::Expired);
+
+        let counts = db.count_stages(101).await.unwrap();
+        assert_eq!(counts, StageCounts { locked: 0, lock_expired: 1, expired: 1 });
+        let soon = db.list_orders(&ExpiryStage::LockExpired.filter(101), 0, 10).await.unwrap();
+        assert_eq!(soon.iter().map(|order| order.id).collect::<Vec<_>>(), [U256::from(2)]);
+        let expired = db.list_orders(&ExpiryStage::Expired.filter(101), 0, 10).await.unwrap();
+        assert_eq!(expired.iter().map(|order| order.id).collect::<Vec<_>>(), [U256::from(1)]);
+        // Slashable soon orders are not slashed yet.
+        assert_eq!(db.get_expired_orders(101).await.unwrap(), vec![U256::from(1)]);
+
+        // Finished orders are in no stage.
+        db.set_order_state(U256::from(1), OrderState::Slashed).await.unwrap();
+        let counts = db.count_stages(50).await.unwrap();
+        assert_eq!(counts, StageCounts { locked: 1, lock_expired: 0, expired: 0 });
+        let locked = db.list_orders(&ExpiryStage::Locked.filter(50), 0, 10).await.unwrap();
+        assert_eq!(locked.iter().map(|order| order.id).collect::<Vec<_>>(), [U256::from(2)]);
+    }
+
+    #[sqlx::test]
+    async fn order_state_transitions(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let id = U256::ZERO;
+        let tx_hash = B256::repeat_byte(1);
+        db.add_order(id, 10, 5).await.unwrap();
+        assert_eq!(db.get_order_state(id).await.unwrap(), Some(OrderState::Tracked));
+        assert_eq!(db.get_expired_orders(11).await.unwrap(), vec![id]);
+
+        // In-flight orders are not returned as expired, so they are not slashed twice.
+        db.set_order_state(id, OrderState::SlashingInflight(tx_hash)).await.unwrap();
+        assert_eq!(
+            db.get_order_state(id).await.unwrap(),
+            Some(OrderState::SlashingInflight(tx_hash))
+        );
+        assert_eq!(db.get_inflight_orders().await.unwrap(), vec![(id, tx_hash)]);
+        assert!(db.get_expired_orders(11).await.unwrap().is_empty());
+
+        db.set_order_state(id, OrderState::Slashed).await.unwrap();
+        assert_eq!(db.get_order_state(id).await.unwrap(), Some(OrderState::Slashed));
+        assert!(db.get_inflight_orders().await.unwrap().is_empty());
+        assert!(db.get_expired_orders(11).await.unwrap().is_empty());
+
+        // Re-adding a finished order must not reset its state.
+        db.add_order(id, 10, 5).await.unwrap();
+        assert_eq!(db.get_order_state(id).await.unwrap(), Some(OrderState::Slashed));
+
+        assert!(matches!(
+            db.set_order_state(U256::from(1), OrderState::Failed).await,
+            Err(DbError::OrderNotFound(_))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn list_orders(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        for i in 1..=4u64 {
+            db.add_order(U256::from(i), 100 * i, 50 * i).await.unwrap();
+        }
+        db.set_order_state(U256::from(2), OrderState::Slashed).await.unwrap();
+
+        let all = db.list_orders(&OrderFilter::default(), 0, 10).await.unwrap();
+        assert_eq!(
+            all.iter().map(|order| order.id).collect::<Vec<_>>(),
+            [1, 2, 3, 4].map(U256::from)
+        );
+        assert_eq!(
+            all[0],
+            TrackedOrder {
+                id: U256::from(1),
+                expires_at: 100,
+                lock_expires_at: 50,
+                state: OrderState::Tracked
+            }
+        );
+
+        let page = db.list_orders(&OrderFilter::default(), 1, 2).await.unwrap();
+        assert_eq!(page.iter().map(|order| order.id).collect::<Vec<_>>(), [2, 3].map(U256::from));
+
+        let tracked = OrderFilter { state: Some(OrderState::Tracked), ..Default::default() };
+        let tracked = db.list_orders(&tracked, 0, 10).await.unwrap();
+        assert_eq!(
+            tracked.iter().map(|order| order.id).collect::<Vec<_>>(),
+            [1, 3, 4].map(U256::from)
+        );
+
+        let window = OrderFilter {
+            expires_after: Some(200),
+            expires_before: Some(400),
+            ..Default::default()
+        };
+        let window = db.list_orders(&window, 0, 10).await.unwrap();
+        assert_eq!(window.iter().map(|order| order.id).collect::<Vec<_>>(), [2, 3].map(U256::from));
+    }
+
+    #[sqlx::test]
+    async fn slash_history(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let tx_hash = B256::repeat_byte(1);
+        db.add_order(U256::from(1), 10, 5).await.unwrap();
+        db.add_order(U256::from(2), 10, 5).await.unwrap();
+
+        db.set_order_state(U256::from(1), OrderState::SlashingInflight(tx_hash)).await.unwrap();
+        db.set_order_state(U256::from(1), OrderState::Slashed).await.unwrap();
+        // Setting a final state again does not record a second outcome.
+        db.set_order_state(U256::from(1), OrderState::Slashed).await.unwrap();
+        db.set_order_state(U256::from(2), OrderState::Failed).await.unwrap();
+
+        let history = db.get_slash_history(None, 0, 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].id, U256::from(2));
+        assert_eq!(history[0].state, OrderState::Failed);
+        assert_eq!(history[0].tx_hash, None);
+        assert_eq!(history[1].id, U256::from(1));
+        assert_eq!(history[1].state, OrderState::Slashed);
+        assert_eq!(history[1].tx_hash, Some(tx_hash));
+
+        let history = db.get_slash_history(Some(U256::from(1)), 0, 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].id, U256::from(1));
+    }
+
+    #[sqlx::test]
+    async fn gas_spend(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        assert_eq!(db.get_gas_spend(None).await.unwrap(), GasSpend::default());
+
+        db.add_gas_spend(U256::from(1), B256::repeat_byte(1), 100, U256::from(1000), 10)
+            .await
+            .unwrap();
+        db.add_gas_spend(U256::from(2), B256::repeat_byte(2), 200, U256::from(3000), 20)
+            .await
+            .unwrap();
+        // Recording a transaction twice does not count it twice.
+        db.add_gas_spend(U256::from(2), B256::repeat_byte(2), 200, U256::from(3000), 30)
+            .await
+            .unwrap();
+
+        let total = db.get_gas_spend(None).await.unwrap();
+        assert_eq!(total, GasSpend { txs: 2, gas_used: 300, cost: U256::from(4000) });
+        let recent = db.get_gas_spend(Some(15)).await.unwrap();
+        assert_eq!(recent, GasSpend { txs: 1, gas_used: 200, cost: U256::from(3000) });
+    }
+
+    #[sqlx::test]
+    async fn prune(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        for i in 1..=4u64 {
+            db.add_order(U256::from(i), 100 * i, 50 * i).await.unwrap();
+        }
+        db.set_order_state(U256::from(1), OrderState::Slashed).await.unwrap();
+        db.set_order_state(U256::from(3), OrderState::Failed).await.unwrap();
+        db.add_gas_spend(U256::from(1), B256::repeat_byte(1), 100, U256::from(1000), 10)
+            .await
+            .unwrap();
+
+        // Only finished orders expired before the cutoff are deleted; order 2 is still tracked
+        // and order 3 expires after the cutoff.
+        assert_eq!(db.prune(250).await.unwrap(), 2);
+        assert!(!db.order_exists(U256::from(1)).await.unwrap());
+        assert!(db.order_exists(U256::from(2)).await.unwrap());
+        assert!(db.order_exists(U256::from(3)).await.unwrap());
+        assert_eq!(db.get_gas_spend(None).await.unwrap(), GasSpend::default());
+        // The slash history was recorded now, well after the cutoff.
+        assert_eq!(db.get_slash_history(None, 0, 10).await.unwrap().len(), 2);
+
+        db.compact().await.unwrap();
+        assert_eq!(db.list_orders(&OrderFilter::default(), 0, 10).await.unwrap().len(), 3);
+    }
+
     #[sqlx::test]
     async fn set_get_block(pool: SqlitePool) {
         let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
@@ -277,6 +1295,32 @@ mod tests {
         assert_eq!(block_numb, db_block);
     }
 
+    #[sqlx::test]
+    async fn range_checkpoint(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        assert_eq!(db.get_range_checkpoint().await.unwrap(), None);
+
+        db.set_range_checkpoint(100, 149).await.unwrap();
+        db.set_range_checkpoint(100, 199).await.unwrap();
+        assert_eq!(db.get_range_checkpoint().await.unwrap(), Some((100, 199)));
+
+        db.clear_range_checkpoint().await.unwrap();
+        assert_eq!(db.get_range_checkpoint().await.unwrap(), None);
+    }
+
+    #[sqlx::test]
+    async fn event_gaps(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        assert!(db.get_event_gaps().await.unwrap().is_empty());
+
+        db.add_event_gap(200, 299).await.unwrap();
+        db.add_event_gap(100, 199).await.unwrap();
+        assert_eq!(db.get_event_gaps().await.unwrap(), vec![(100, 199), (200, 299)]);
+
+        db.remove_event_gap(100, 199).await.unwrap();
+        assert_eq!(db.get_event_gaps().await.unwrap(), vec![(200, 299)]);
+    }
+
     #[sqlx::test]
     async fn get_existing_order(pool: SqlitePool) {
         let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
@@ -306,4 +1350,35 @@ mod tests {
         db.remove_order(id).await.unwrap();
         assert!(!db.order_exists(id).await.unwrap());
     }
+
+    #[sqlx::test]
+    async fn leadership(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        assert_eq!(db.get_leader().await.unwrap(), None);
+
+        // The first instance takes the unheld lease, and the second cannot take it while fresh.
+        assert!(db.claim_leadership("primary", 100, 30).await.unwrap());
+        assert!(!db.claim_leadership("standby", 110, 30).await.unwrap());
+        assert!(db.claim_leadership("primary", 120, 30).await.unwrap());
+        assert_eq!(db.get_leader().await.unwrap(), Some(("primary".to_string(), 120)));
+
+        // Once the heartbeat goes stale, the standby takes over and the primary is locked out.
+        assert!(!db.claim_leadership("standby", 150, 30).await.unwrap());
+        assert!(db.claim_leadership("standby", 151, 30).await.unwrap());
+        assert!(!db.claim_leadership("primary", 152, 30).await.unwrap());
+        assert_eq!(db.get_leader().await.unwrap(), Some(("standby".to_string(), 151)));
+    }
+
+    #[sqlx::test]
+    async fn deployment(pool: SqlitePool) {
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        assert_eq!(db.get_deployment().await.unwrap(), None);
+
+        let market = Address::repeat_byte(1);
+        db.set_deployment(8453, market).await.unwrap();
+        assert_eq!(db.get_deployment().await.unwrap(), Some((8453, market)));
+
+        db.set_deployment(84532, Address::repeat_byte(2)).await.unwrap();
+        assert_eq!(db.get_deployment().await.unwrap(), Some((84532, Address::repeat_byte(2))));
+    }
 }
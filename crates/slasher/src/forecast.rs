@@ -0,0 +1,190 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Forecast of the slashes due over an upcoming window.
+//!
+//! Lists the tracked orders expiring within the window with their collateral, and estimates the
+//! gas needed to slash them at the current gas price, so operators can provision the wallet
+//! balance ahead of demand spikes.
+
+use std::fmt;
+
+use alloy::primitives::{utils::format_ether, I256, U256};
+use serde::Serialize;
+
+use crate::db::TrackedOrder;
+
+/// Share of the collateral of a slashed order transferred to the market treasury, rather than
+/// burned, as a fraction `(numerator, denominator)`. Mirrors `SLASHING_BURN_BPS` in the
+/// BoundlessMarket contract.
+const TREASURY_SHARE: (u64, u64) = (1, 2);
+
+/// Gas assumed per slash transaction before any slash has been recorded.
+pub const DEFAULT_SLASH_GAS: u64 = 150_000;
+
+/// A tracked order due to be slashed within the forecast window.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ForecastOrder {
+    /// ID of the request.
+    pub request_id: U256,
+    /// Time, in seconds since the UNIX epoch, at which the request expires.
+    pub expires_at: u64,
+    /// Collateral locked by the prover, if recorded when the order was tracked.
+    pub collateral: Option<U256>,
+    /// Collateral transferred to the market treasury by the slash.
+    pub treasury_share: U256,
+    /// Estimated cost of the slash transaction.
+    pub gas_cost: U256,
+    /// Treasury share net of the gas cost.
+    pub net: I256,
+}
+
+/// Slashes due within a window, with the gas needed to send them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Forecast {
+    /// Start of the window, in seconds since the UNIX epoch.
+    pub from: u64,
+    /// End of the window, in seconds since the UNIX epoch.
+    pub until: u64,
+    /// Gas price the costs are estimated at, in wei.
+    pub gas_price: u128,
+    /// Gas assumed per slash transaction.
+    pub gas_per_slash: u64,
+    /// Orders due to be slashed, ordered by expiry.
+    pub orders: Vec<ForecastOrder>,
+    /// Sum of the known collateral of the orders.
+    pub total_collateral: U256,
+    /// Estimated cost of all the slash transactions, i.e. the wallet balance they need.
+    pub total_gas_cost: U256,
+    /// Sum of the treasury shares net of the gas costs.
+    pub total_net: I256,
+}
+
+impl Forecast {
+    /// Build the forecast of the given orders, which expire before `until`, along with their
+    /// collateral.
+    ///
+    /// Orders fulfilled after their lock deadline pay the unburned collateral to the fulfilling
+    /// prover rather than the treasury. This is only known at slash time, so the treasury share
+    /// assumes every order expires unfulfilled.
+    pub fn new(
+        from: u64,
+        until: u64,
+        gas_price: u128,
+        gas_per_slash: u64,
+        orders: impl IntoIterator<Item = (TrackedOrder, Option<U256>)>,
+    ) -> Self {
+        let gas_cost = U256::from(gas_price) * U256::from(gas_per_slash);
+        let orders: Vec<_> = orders
+            .into_iter()
+            .map(|(order, collateral)| {
+                let treasury_share = collateral.unwrap_or_default() * U256::from(TREASURY_SHARE.0)
+                    / U256::from(TREASURY_SHARE.1);
+                ForecastOrder {
+                    request_id: order.id,
+                    expires_at: order.expires_at,
+                    collateral,
+                    treasury_share,
+                    gas_cost,
+                    net: I256::from_raw(treasury_share) - I256::from_raw(gas_cost),
+                }
+            })
+            .collect();
+        Self {
+            from,
+            until,
+            gas_price,
+            gas_per_slash,
+            total_collateral: orders.iter().filter_map(|order| order.collateral).sum(),
+            total_gas_cost: orders.iter().map(|order| order.gas_cost).sum(),
+            total_net: orders.iter().fold(I256::ZERO, |total, order| total + order.net),
+            orders,
+        }
+    }
+}
+
+impl fmt::Display for Forecast {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} slashes due until {} at {} gwei, {} gas each",
+            self.orders.len(),
+            self.until,
+            self.gas_price as f64 / 1e9,
+            self.gas_per_slash
+        )?;
+        writeln!(
+            f,
+            "{:<66}  {:>12}  {:>22}  {:>22}  {:>22}",
+            "request_id", "expires_at", "collateral", "gas_cost", "net"
+        )?;
+        for order in &self.orders {
+            writeln!(
+                f,
+                "{:<66}  {:>12}  {:>22}  {:>22}  {:>22}",
+                format!("0x{:x}", order.request_id),
+                order.expires_at,
+                order.collateral.map(format_ether).unwrap_or_else(|| "unknown".to_string()),
+                format_ether(order.gas_cost),
+                format_signed_ether(order.net),
+            )?;
+        }
+        write!(
+            f,
+            "Total collateral: {} ETH, gas needed: {} ETH, net revenue: {} ETH",
+            format_ether(self.total_collateral),
+            format_ether(self.total_gas_cost),
+            format_signed_ether(self.total_net)
+        )
+    }
+}
+
+fn format_signed_ether(value: I256) -> String {
+    let sign = if value.is_negative() { "-" } else { "" };
+    format!("{sign}{}", format_ether(value.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::OrderState;
+
+    fn order(id: u64, expires_at: u64) -> TrackedOrder {
+        TrackedOrder {
+            id: U256::from(id),
+            expires_at,
+            lock_expires_at: expires_at,
+            state: OrderState::Tracked,
+        }
+    }
+
+    #[test]
+    fn forecast_totals() {
+        let forecast = Forecast::new(
+            0,
+            100,
+            10,
+            100,
+            [(order(1, 10), Some(U256::from(5000))), (order(2, 20), None)],
+        );
+        assert_eq!(forecast.orders[0].treasury_share, U256::from(2500));
+        assert_eq!(forecast.orders[0].gas_cost, U256::from(1000));
+        assert_eq!(forecast.orders[0].net, I256::from_raw(U256::from(1500)));
+        // Orders without a recorded collateral only count their gas.
+        assert_eq!(forecast.orders[1].net, -I256::from_raw(U256::from(1000)));
+
+        assert_eq!(forecast.total_collateral, U256::from(5000));
+        assert_eq!(forecast.total_gas_cost, U256::from(2000));
+        assert_eq!(forecast.total_net, I256::from_raw(U256::from(500)));
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The events of the market read from the DB of the Boundless indexer, instead of queried from
+//! the RPC.
+//!
+//! Operators who already run the indexer can point the service at its DB with
+//! [crate::SlashService::with_indexer], which cuts the log queries of the service. Blocks are
+//! then only processed once the indexer has indexed them. The indexer only indexes the Boundless
+//! market, so this source can not be combined with a custom [crate::target::SlashTarget].
+
+use std::str::FromStr;
+
+use alloy::primitives::{Address, U256};
+use boundless_market::contracts::RequestId;
+use sqlx::{
+    any::{install_default_drivers, AnyConnectOptions, AnyPoolOptions, AnyRow},
+    AnyPool, Row,
+};
+
+use crate::{target::LockedOrder, ServiceError};
+
+/// A fulfilled or slashed request, as indexed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexedEvent {
+    /// ID of the request.
+    pub request_id: U256,
+    /// Block the event was emitted in.
+    pub block_number: u64,
+    /// Timestamp of the block the event was emitted in.
+    pub block_timestamp: u64,
+}
+
+/// Read-only access to the DB of the Boundless indexer.
+#[derive(Clone, Debug)]
+pub struct IndexerSource {
+    pool: AnyPool,
+}
+
+impl IndexerSource {
+    /// Connect to the DB of the indexer, with a `postgres://` or `sqlite:` URL.
+    ///
+    /// The schema is owned by the indexer, so no migrations are run.
+    pub async fn connect(conn_str: &str) -> Result<Self, ServiceError> {
+        install_default_drivers();
+        let opts = AnyConnectOptions::from_str(conn_str)?;
+        let pool = AnyPoolOptions::new().max_connections(2).connect_with(opts).await?;
+        Ok(Self::from_pool(pool))
+    }
+
+    /// Use an existing pool to the DB of the indexer.
+    pub fn from_pool(pool: AnyPool) -> Self {
+        Self { pool }
+    }
+
+    /// Last block indexed, if the indexer has started.
+    pub async fn last_block(&self) -> Result<Option<u64>, ServiceError> {
+        let row = sqlx::query("SELECT block FROM last_block WHERE id = 0")
+            .fetch_optional(&self.pool)
+            .await?;
+        row.map(|row| {
+            let block: String = row.try_get("block")?;
+            block
+                .parse::<u64>()
+                .map_err(|_| ServiceError::IndexerError(format!("invalid last block {block}")))
+        })
+        .transpose()
+    }
+
+    /// Orders locked from `from_block` to `to_block`, with the block they were locked in.
+    ///
+    /// Fails if the request of a lock is not indexed yet, rather than missing the order.
+    pub async fn locked_orders(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<(LockedOrder, u64)>, ServiceError> {
+        let rows = sqlx::query(
+            "SELECT l.request_id, l.prover_address, l.block_number, p.expires_at, p.lock_end,
+                p.lock_collateral
+            FROM request_locked_events l
+            LEFT JOIN proof_requests p ON p.request_digest = l.request_digest
+            WHERE l.block_number BETWEEN $1 AND $2
+            ORDER BY l.block_number",
+        )
+        .bind(from_block as i64)
+        .bind(to_block as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let request_id = parse_request_id(row)?;
+                let prover: String = row.try_get("prover_address")?;
+                let prover = Address::from_str(&prover).map_err(|_| {
+                    ServiceError::IndexerError(format!("invalid prover address {prover}"))
+                })?;
+                let (Some(expires_at), Some(lock_end), Some(collateral)) = (
+                    row.try_get::<Option<i64>, _>("expires_at")?,
+                    row.try_get::<Option<i64>, _>("lock_end")?,
+                    row.try_get::<Option<String>, _>("lock_collateral")?,
+                ) else {
+                    return Err(ServiceError::IndexerError(format!(
+                        "request 0x{request_id:x} is locked but not indexed yet"
+                    )));
+                };
+                let collateral = U256::from_str(&collateral).map_err(|_| {
+                    ServiceError::IndexerError(format!("invalid collateral {collateral}"))
+                })?;
+                let order = LockedOrder {
+                    request_id,
+                    requestor: RequestId::from_lossy(request_id).addr,
+                    prover,
+                    expires_at: expires_at as u64,
+                    lock_expires_at: lock_end as u64,
+                    collateral,
+                };
+                Ok((order, row.try_get::<i64, _>("block_number")? as u64))
+            })
+            .collect()
+    }
+
+    /// Requests fulfilled from `from_block` to `to_block`.
+    pub async fn fulfilled(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedEvent>, ServiceError> {
+        self.events("request_fulfilled_events", from_block, to_block).await
+    }
+
+    /// Requests whose prover was slashed from `from_block` to `to_block`.
+    pub async fn slashed(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedEvent>, ServiceError> {
+        self.events("prover_slashed_events", from_block, to_block).await
+    }
+
+    async fn events(
+        &self,
+        table: &'static str,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<IndexedEvent>, ServiceError> {
+        let rows = sqlx::query(&format!(
+            "SELECT request_id, block_number, block_timestamp FROM {table}
+            WHERE block_number BETWEEN $1 AND $2
+            ORDER BY block_number"
+        ))
+        .bind(from_block as i64)
+        .bind(to_block as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(IndexedEvent {
+                    request_id: parse_request_id(row)?,
+                    block_number: row.try_get::<i64, _>("block_number")? as u64,
+                    block_timestamp: row.try_get::<i64, _>("block_timestamp")? as u64,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse the request ID of a row, which the indexer stores as hex without a prefix.
+fn parse_request_id(row: &AnyRow) -> Result<U256, ServiceError> {
+    let request_id: String = row.try_get("request_id")?;
+    U256::from_str_radix(&request_id, 16)
+        .map_err(|_| ServiceError::IndexerError(format!("invalid request ID {request_id}")))
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::*;
+
+    /// An empty indexer DB, with the schema created by the migrations of the indexer.
+    pub(crate) async fn source() -> IndexerSource {
+        install_default_drivers();
+        let pool =
+            AnyPoolOptions::new().max_connections(1).connect("sqlite::memory:").await.unwrap();
+        sqlx::migrate!("../indexer/migrations").run(&pool).await.unwrap();
+        IndexerSource::from_pool(pool)
+    }
+
+    pub(crate) async fn set_last_block(source: &IndexerSource, block: u64) {
+        sqlx::query(
+            "INSERT INTO last_block (id, block) VALUES (0, $1)
+            ON CONFLICT (id) DO UPDATE SET block = $1",
+        )
+        .bind(block.to_string())
+        .execute(&source.pool)
+        .await
+        .unwrap();
+    }
+
+    async fn insert_tx(source: &IndexerSource, tx_hash: &str, block: u64, timestamp: u64) {
+        sqlx::query(
+            "INSERT INTO transactions (tx_hash, block_number, from_address, block_timestamp)
+            VALUES ($1, $2, '0', $3)",
+        )
+        .bind(tx_hash)
+        .bind(block as i64)
+        .bind(timestamp as i64)
+        .execute(&source.pool)
+        .await
+        .unwrap();
+    }
+
+    /// Index the lock of `order` at `block`, without indexing its request.
+    pub(crate) async fn insert_lock(
+        source: &IndexerSource,
+        order: &LockedOrder,
+        block: u64,
+        timestamp: u64,
+    ) {
+        let request_id = format!("{:x}", order.request_id);
+        let tx_hash = format!("lock-{request_id}");
+        insert_tx(source, &tx_hash, block, timestamp).await;
+        sqlx::query(
+            "INSERT INTO request_locked_events (request_digest, request_id, prover_address,
+                tx_hash, block_number, block_timestamp)
+            VALUES ($1, $1, $2, $3, $4, $5)",
+        )
+        .bind(&request_id)
+        .bind(format!("{:x}", order.prover))
+        .bind(&tx_hash)
+        .bind(block as i64)
+        .bind(timestamp as i64)
+        .execute(&source.pool)
+        .await
+        .unwrap();
+    }
+
+    /// Index the request of `order`, as submitted at `block`.
+    pub(crate) async fn insert_request(
+        source: &IndexerSource,
+        order: &LockedOrder,
+        block: u64,
+        timestamp: u64,
+    ) {
+        let request_id = format!("{:x}", order.request_id);
+        let tx_hash = format!("submit-{request_id}");
+        insert_tx(source, &tx_hash, block, timestamp).await;
+        sqlx::query(
+            "INSERT INTO proof_requests (request_digest, request_id, client_address,
+                predicate_type, predicate_data, selector, input_type, input_data, min_price,
+                max_price, lock_collateral, bidding_start, expires_at, lock_end, ramp_up_period,
+                tx_hash, block_number, block_timestamp)
+            VALUES ($1, $1, $2, 'DigestMatch', '', '', 'Inline', '', '0', '0', $3, 0, $4, $5, 0,
+                $6, $7, $8)",
+        )
+        .bind(&request_id)
+        .bind(format!("{:x}", order.requestor))
+        .bind(order.collateral.to_string())
+        .bind(order.expires_at as i64)
+        .bind(order.lock_expires_at as i64)
+        .bind(&tx_hash)
+        .bind(block as i64)
+        .bind(timestamp as i64)
+        .execute(&source.pool)
+        .await
+        .unwrap();
+    }
+
+    /// Index the fulfillment of a request at `block`.
+    pub(crate) async fn insert_fulfilled(
+        source: &IndexerSource,
+        request_id: U256,
+        block: u64,
+        timestamp: u64,
+    ) {
+        let request_id = format!("{request_id:x}");
+        let tx_hash = format!("fulfill-{request_id}");
+        insert_tx(source, &tx_hash, block, timestamp).await;
+        sqlx::query(
+            "INSERT INTO request_fulfilled_events (request_digest, request_id, tx_hash,
+                block_number, block_timestamp)
+            VALUES ($1, $1, $2, $3, $4)",
+        )
+        .bind(&request_id)
+        .bind(&tx_hash)
+        .bind(block as i64)
+        .bind(timestamp as i64)
+        .execute(&source.pool)
+        .await
+        .unwrap();
+    }
+
+    /// Index the slash of the prover of a request at `block`.
+    pub(crate) async fn insert_slashed(
+        source: &IndexerSource,
+        request_id: U256,
+        block: u64,
+        timestamp: u64,
+    ) {
+        let request_id = format!("{request_id:x}");
+        let tx_hash = format!("slash-{request_id}");
+        insert_tx(source, &tx_hash, block, timestamp).await;
+        sqlx::query(
+            "INSERT INTO prover_slashed_events (request_id, prover_address, burn_value,
+                transfer_value, collateral_recipient, tx_hash, block_number, block_timestamp)
+            VALUES ($1, '0', '0', '0', '0', $2, $3, $4)",
+        )
+        .bind(&request_id)
+        .bind(&tx_hash)
+        .bind(block as i64)
+        .bind(timestamp as i64)
+        .execute(&source.pool)
+        .await
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{test_utils::*, *};
+
+    #[tokio::test]
+    async fn reads_indexed_events() {
+        let source = source().await;
+        assert_eq!(source.last_block().await.unwrap(), None);
+        set_last_block(&source, 120).await;
+        assert_eq!(source.last_block().await.unwrap(), Some(120));
+
+        let requestor = Address::repeat_byte(2);
+        let request_id = RequestId::u256(requestor, 7);
+        let order = LockedOrder {
+            request_id,
+            requestor,
+            prover: Address::repeat_byte(3),
+            expires_at: 1300,
+            lock_expires_at: 1100,
+            collateral: U256::from(5),
+        };
+        insert_lock(&source, &order, 100, 1200).await;
+        // The request of the lock is not indexed yet.
+        source.locked_orders(90, 110).await.unwrap_err();
+
+        insert_request(&source, &order, 95, 1140).await;
+        assert_eq!(source.locked_orders(90, 110).await.unwrap(), vec![(order, 100)]);
+        assert!(source.locked_orders(101, 110).await.unwrap().is_empty());
+
+        insert_slashed(&source, request_id, 105, 1400).await;
+        assert_eq!(
+            source.slashed(100, 110).await.unwrap(),
+            vec![IndexedEvent { request_id, block_number: 105, block_timestamp: 1400 }]
+        );
+        assert!(source.fulfilled(100, 110).await.unwrap().is_empty());
+    }
+}
@@ -12,30 +12,65 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{cmp::min, sync::Arc};
+use std::{
+    cmp::min,
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 use alloy::{
     network::{Ethereum, EthereumWallet},
     primitives::{Address, B256, U256},
     providers::{
         fillers::{ChainIdFiller, JoinFill},
-        Identity, Provider, ProviderBuilder, RootProvider,
+        DynProvider, Identity, Provider, ProviderBuilder, RootProvider,
     },
+    rpc::types::{Filter, Log, TransactionReceipt},
     signers::local::PrivateKeySigner,
     transports::{RpcError, TransportErrorKind},
 };
 use boundless_market::{
     balance_alerts_layer::{BalanceAlertConfig, BalanceAlertLayer, BalanceAlertProvider},
-    contracts::boundless_market::{BoundlessMarketService, MarketError},
+    client::ClientPreset,
+    contracts::boundless_market::{
+        BoundlessMarketService, ConfirmationPolicy, MarketError, MarketTxType,
+    },
     dynamic_gas_filler::DynamicGasFiller,
+    http_client::{rpc_client_with_http, HttpClientConfig},
+    log_query::ChunkedLogQuery,
     nonce_layer::NonceProvider,
+    stale_rpc_layer::{StaleRpcConfig, StaleRpcError, StaleRpcGuard},
 };
-use db::{DbError, DbObj, SqliteDb};
+use clock::{BlockSource, Clock, RpcBlockSource, SystemClock};
+use db::{DbError, OrderFilter, SqliteDb, TrackedOrder};
+use forecast::Forecast;
+use futures_util::future::try_join_all;
+use indexer::IndexerSource;
+use reconcile::{OnchainOrder, Reconciliation, RemovedOrder, RECONCILE_BATCH_SIZE};
+use serde::Serialize;
+use target::{BoundlessMarketTarget, LockedOrder, SlashTarget, TargetError};
 use thiserror::Error;
 use tokio::time::Duration;
+use tracing::instrument;
 use url::Url;
 
+pub mod admin;
+pub mod api;
+pub mod clock;
 mod db;
+pub mod forecast;
+pub mod indexer;
+pub mod reconcile;
+pub mod target;
+mod tunables;
+
+pub use db::{DbObj, ExpiryStage, OrderState, SlasherDb};
+pub use tunables::Tunables;
+use tunables::TunablesWatcher;
 
 type ProviderWallet = NonceProvider<
     JoinFill<JoinFill<Identity, ChainIdFiller>, DynamicGasFiller>,
@@ -50,6 +85,9 @@ pub enum ServiceError {
     #[error("Boundless market error: {0}")]
     BoundlessMarketError(#[from] MarketError),
 
+    #[error("Slash target error: {0}")]
+    TargetError(#[from] TargetError),
+
     #[error("RPC error: {0}")]
     RpcError(#[from] RpcError<TransportErrorKind>),
 
@@ -76,13 +114,92 @@ pub enum ServiceError {
 
     #[error("Slash reverted for request 0x{0:x}, tx_hash: {1:?}")]
     SlashRevert(U256, B256),
+
+    #[error("Event history of blocks {0} to {1} is pruned and no archive RPC is available")]
+    PrunedHistory(u64, u64),
+
+    #[error("{0}")]
+    StaleRpc(StaleRpcError),
+
+    #[error("Deployment mismatch: {0}")]
+    DeploymentMismatch(String),
+
+    #[error("Indexer DB error: {0}")]
+    IndexerDbError(#[from] sqlx::Error),
+
+    #[error("Indexer error: {0}")]
+    IndexerError(String),
+}
+
+impl ServiceError {
+    /// Whether the service should retry the failed operation, rather than stop.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            ServiceError::DatabaseError(_)
+            | ServiceError::InsufficientFunds(_)
+            | ServiceError::MaxRetries
+            | ServiceError::TransactionDecodingError(_)
+            | ServiceError::BlockNumberNotFound
+            | ServiceError::RequestNotExpired
+            | ServiceError::PrunedHistory(_, _)
+            | ServiceError::DeploymentMismatch(_) => false,
+            ServiceError::BoundlessMarketError(_)
+            | ServiceError::TargetError(_)
+            | ServiceError::SlashRevert(_, _)
+            | ServiceError::EventQueryError(_)
+            | ServiceError::RpcError(_)
+            | ServiceError::BlockTimestampNotFound(_)
+            | ServiceError::StaleRpc(_)
+            | ServiceError::IndexerDbError(_)
+            | ServiceError::IndexerError(_) => true,
+        }
+    }
+}
+
+/// JSON-RPC error code returned by nodes for history they pruned, as introduced with EIP-4444.
+const PRUNED_HISTORY_CODE: i64 = 4444;
+
+/// Whether the node responded that it no longer has the history of the queried range.
+///
+/// Only error responses of the node are considered, so that a transport error or a revert
+/// mentioning pruning is not mistaken for pruned history.
+fn is_pruned_history_error(err: &alloy::contract::Error) -> bool {
+    match err {
+        alloy::contract::Error::TransportError(RpcError::ErrorResp(payload)) => {
+            payload.code == PRUNED_HISTORY_CODE
+        }
+        _ => false,
+    }
 }
 
 #[derive(Clone)]
 pub struct SlashService<P> {
+    /// Boundless market at the address of the [SlashTarget].
+    ///
+    /// For a custom target set with [SlashService::from_target] or [SlashService::with_target],
+    /// this only binds the address of the target, without a caller to send transactions from.
+    #[deprecated(note = "the service acts on its SlashTarget, use the provider for RPC calls")]
     pub boundless_market: BoundlessMarketService<P>,
+    /// Provider for the RPC, used to query events and transactions.
+    pub provider: P,
     pub db: DbObj,
     pub config: SlashServiceConfig,
+    /// Provider for the archive RPC, used to query event history pruned by the main RPC.
+    pub archive_provider: Option<DynProvider>,
+    /// Whether this instance held the leader lease as of the last poll.
+    is_leader: Arc<AtomicBool>,
+    /// Whether slashing was paused by the daily gas budget as of the last check.
+    budget_exhausted: Arc<AtomicBool>,
+    /// Source of the wall-clock time.
+    clock: Arc<dyn Clock>,
+    /// Source of the head block and block timestamps.
+    block_source: Arc<dyn BlockSource>,
+    /// HTTP client for the partner webhook.
+    webhook_client: reqwest::Client,
+    /// Market contract whose orders are slashed.
+    target: Arc<dyn SlashTarget>,
+    /// DB of the Boundless indexer, read for events instead of querying the RPC for logs.
+    indexer: Option<Arc<IndexerSource>>,
 }
 
 #[derive(Clone)]
@@ -92,8 +209,123 @@ pub struct SlashServiceConfig {
     pub balance_warn_threshold: Option<U256>,
     pub balance_error_threshold: Option<U256>,
     pub skip_addresses: Vec<Address>,
+    /// Requestors whose requests are not enforced, regardless of which prover locked them.
+    pub skip_requestors: Vec<Address>,
+    /// If not empty, only requests from these requestors are enforced.
+    pub allow_requestors: Vec<Address>,
     pub tx_timeout: Duration,
     pub max_block_range: u64,
+    /// Number of blocks whose events are held in memory and processed at once.
+    ///
+    /// A block range is processed page by page, checkpointing progress after each page, so that
+    /// backfills run in bounded memory and resume from the last page after a failure. Pages are
+    /// at most `max_block_range` blocks, also when it is lowered by reloaded [Tunables].
+    pub page_size: u64,
+    pub http_client: HttpClientConfig,
+    pub archive_rpc_url: Option<Url>,
+    /// Run as one of several instances sharing a DB, where only the leader sends slashes.
+    pub standby: Option<StandbyConfig>,
+    /// Check the age of the head block reported by the RPC before acting on it.
+    pub check_head_age: bool,
+    /// Maximum age of the head block. If `None`, the default for the chain is used.
+    pub max_head_age: Option<Duration>,
+    /// URL of a second RPC endpoint to compare the head block against.
+    pub reference_rpc_url: Option<Url>,
+    /// Maximum number of blocks the RPC may be behind the reference endpoint.
+    pub max_block_lag: u64,
+    /// Number of blocks an event must be buried under before it is processed.
+    ///
+    /// Blocks are processed up to `head - confirmations`, so that events which are later
+    /// reorged away are not acted on. Zero processes events as soon as they are in the head block.
+    pub confirmations: u64,
+    /// Number of blocks, including the one it is included in, to wait for before a slash
+    /// transaction is considered confirmed.
+    pub slash_confirmations: u64,
+    /// TOML file of [Tunables], which is watched and applied without restarting the service.
+    pub config_file: Option<PathBuf>,
+    /// Maximum cost, in wei, of the slash transactions sent in any 24 hour window.
+    ///
+    /// Slashing pauses once the budget is spent, and resumes as older transactions leave the
+    /// window. Orders keep being tracked while paused, so none are missed.
+    pub daily_gas_budget: Option<U256>,
+    /// Age after which slashed and failed orders, and the slash history and gas spend records,
+    /// are pruned from the DB. Nothing is pruned if `None`.
+    pub retention: Option<Duration>,
+    /// Interval between compactions of the DB, which reclaim the space freed by pruning and
+    /// refresh the query planner statistics. The DB is never compacted if `None`.
+    pub compact_interval: Option<Duration>,
+    /// Interval between on-chain checks of the tracked orders whose lock expired, which removes
+    /// those already slashed without their event being seen, for example in a pruned range of
+    /// history. Orders are never re-checked if `None`.
+    pub recheck_interval: Option<Duration>,
+    /// Notify partner provers of their expired locks, and defer slashing them.
+    pub partners: Option<PartnerConfig>,
+}
+
+impl SlashServiceConfig {
+    /// Whether requests from the given requestor are enforced, according to
+    /// [Self::skip_requestors] and [Self::allow_requestors].
+    pub fn enforces_requestor(&self, requestor: Address) -> bool {
+        !self.skip_requestors.contains(&requestor)
+            && (self.allow_requestors.is_empty() || self.allow_requestors.contains(&requestor))
+    }
+}
+
+/// Length of the window the daily gas budget applies to.
+const GAS_BUDGET_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Interval between prunes of the DB, when a retention is set.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Configuration of the notify-and-defer mode for partner provers.
+///
+/// Orders locked by a partner are not slashed as soon as they expire. Once the lock of such an
+/// order expires, a [PartnerNotification] is posted to `webhook_url`, and the order is only
+/// slashed `grace_period` after the request expires, if it is still unfulfilled and unslashed.
+/// Orders locked by a partner and fulfilled after their lock expired are not slashed at all.
+#[derive(Clone, Debug)]
+pub struct PartnerConfig {
+    /// Addresses of the partner provers.
+    pub addresses: Vec<Address>,
+    /// URL to post notifications to. Notifications are only logged if `None`.
+    pub webhook_url: Option<Url>,
+    /// Time after the expiry of a request locked by a partner before it is slashed.
+    pub grace_period: Duration,
+}
+
+/// Notification posted to the partner webhook once the lock of an order locked by a partner
+/// expired.
+#[derive(Clone, Debug, Serialize)]
+pub struct PartnerNotification {
+    /// ID of the request, as a hex string.
+    pub request_id: String,
+    /// Address of the partner prover that locked the request.
+    pub prover: Address,
+    /// Time, in seconds since the UNIX epoch, at which the lock expired.
+    pub lock_expires_at: u64,
+    /// Time, in seconds since the UNIX epoch, at which the request expires.
+    pub expires_at: u64,
+    /// Time, in seconds since the UNIX epoch, after which the request is slashed unless it is
+    /// fulfilled.
+    pub slash_after: u64,
+}
+
+/// Timeout of a request to the partner webhook.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configuration of warm standby mode.
+///
+/// Instances sharing a DB compete for a leader lease kept in the DB. The leader renews the lease
+/// with a heartbeat on every poll, while the other instances keep processing events but do not
+/// send slash transactions. If the leader's heartbeat is older than `stale_after`, the next
+/// instance to poll takes over the lease. `stale_after` must exceed the transaction timeout so
+/// that a leader does not lose the lease while waiting for a slash to confirm.
+#[derive(Clone, Debug)]
+pub struct StandbyConfig {
+    /// Unique identifier of this instance.
+    pub instance_id: String,
+    /// Age after which the leader's heartbeat is considered stale.
+    pub stale_after: Duration,
 }
 
 impl SlashService<ProviderWallet> {
@@ -108,28 +340,67 @@ impl SlashService<ProviderWallet> {
         let wallet = EthereumWallet::from(private_key.clone());
 
         let signer_address = wallet.default_signer().address();
+        // Unset balance thresholds and the transaction pricing follow the requestor defaults of the
+        // client, as the slasher is not racing other transactions.
+        let preset = ClientPreset::Requestor;
         let balance_alerts_layer = BalanceAlertLayer::new(BalanceAlertConfig {
             watch_address: signer_address,
-            warn_threshold: config.balance_warn_threshold,
-            error_threshold: config.balance_error_threshold,
+            warn_threshold: config
+                .balance_warn_threshold
+                .or(preset.balance_alerts().warn_threshold),
+            error_threshold: config
+                .balance_error_threshold
+                .or(preset.balance_alerts().error_threshold),
+        });
+
+        // Reuse a single pooled HTTP client for all RPC calls made by the service.
+        let http_client = config.http_client.build().map_err(TransportErrorKind::custom)?;
+
+        let archive_provider = config.archive_rpc_url.clone().map(|archive_rpc_url| {
+            ProviderBuilder::new()
+                .disable_recommended_fillers()
+                .connect_client(rpc_client_with_http(http_client.clone(), archive_rpc_url))
+                .erased()
         });
 
-        let dynamic_gas_filler = DynamicGasFiller::new(0.2, 0.05, 2.0, signer_address);
+        let mut stale_rpc_config = StaleRpcConfig::default()
+            .with_check_head_age(config.check_head_age)
+            .with_max_block_lag(config.max_block_lag);
+        if let Some(max_head_age) = config.max_head_age {
+            stale_rpc_config = stale_rpc_config.with_max_head_age(max_head_age);
+        }
+        if let Some(reference_rpc_url) = config.reference_rpc_url.clone() {
+            stale_rpc_config = stale_rpc_config.with_reference(
+                ProviderBuilder::new()
+                    .disable_recommended_fillers()
+                    .connect_client(rpc_client_with_http(http_client.clone(), reference_rpc_url))
+                    .erased(),
+            );
+        }
+
+        let dynamic_gas_filler = preset.gas_filler().filler(signer_address);
         let base_provider = ProviderBuilder::new()
             .disable_recommended_fillers()
             .filler(ChainIdFiller::default())
             .filler(dynamic_gas_filler)
             .layer(balance_alerts_layer)
-            .connect_http(rpc_url);
+            .connect_client(config.http_client.rpc_client(http_client, rpc_url));
         let provider = NonceProvider::new(base_provider, wallet.clone());
 
         let boundless_market =
             BoundlessMarketService::new(boundless_market_address, provider.clone(), caller)
-                .with_timeout(config.tx_timeout);
+                .with_timeout(config.tx_timeout)
+                .with_confirmation_policy(
+                    MarketTxType::Slash,
+                    ConfirmationPolicy::new(config.slash_confirmations, config.tx_timeout),
+                );
 
         let db: DbObj = Arc::new(SqliteDb::new(db_conn).await.unwrap());
 
-        Ok(Self { boundless_market, db, config })
+        let block_source = RpcBlockSource::new(provider, StaleRpcGuard::new(stale_rpc_config));
+        Ok(Self::from_parts(boundless_market, db, config)
+            .with_archive_provider(archive_provider)
+            .with_block_source(Arc::new(block_source)))
     }
 }
 
@@ -137,17 +408,331 @@ impl<P> SlashService<P>
 where
     P: Provider<Ethereum> + 'static + Clone,
 {
-    pub async fn run(self, starting_block: Option<u64>) -> Result<(), ServiceError> {
+    /// Create a service from its market and DB, reading time from the system clock and blocks
+    /// from the market's provider.
+    #[allow(deprecated)]
+    pub fn from_parts(
+        boundless_market: BoundlessMarketService<P>,
+        db: DbObj,
+        config: SlashServiceConfig,
+    ) -> Self {
+        let provider = boundless_market.instance().provider().clone();
+        let target = Arc::new(BoundlessMarketTarget::new(boundless_market.clone()));
+        Self { boundless_market, ..Self::from_target(provider, target, db, config) }
+    }
+
+    /// Create a service enforcing the given [SlashTarget], such as a fork of the market with
+    /// modified events, reading time from the system clock and blocks from the provider.
+    #[allow(deprecated)]
+    pub fn from_target(
+        provider: P,
+        target: Arc<dyn SlashTarget>,
+        db: DbObj,
+        config: SlashServiceConfig,
+    ) -> Self {
+        let block_source = RpcBlockSource::new(provider.clone(), StaleRpcGuard::default());
+        Self {
+            boundless_market: BoundlessMarketService::new(
+                target.address(),
+                provider.clone(),
+                Address::ZERO,
+            ),
+            provider,
+            db,
+            config,
+            archive_provider: None,
+            is_leader: Arc::new(AtomicBool::new(false)),
+            budget_exhausted: Arc::new(AtomicBool::new(false)),
+            clock: Arc::new(SystemClock),
+            block_source: Arc::new(block_source),
+            webhook_client: reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            target,
+            indexer: None,
+        }
+    }
+
+    /// Set the provider for the archive RPC.
+    pub fn with_archive_provider(self, archive_provider: Option<DynProvider>) -> Self {
+        Self { archive_provider, ..self }
+    }
+
+    /// Set the [Clock] used for the leader lease.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self { clock, ..self }
+    }
+
+    /// Set the [BlockSource] for the head block and block timestamps.
+    pub fn with_block_source(self, block_source: Arc<dyn BlockSource>) -> Self {
+        Self { block_source, ..self }
+    }
+
+    /// Set the [SlashTarget] for a market other than the Boundless market, such as a fork with
+    /// modified events.
+    #[allow(deprecated)]
+    pub fn with_target(self, target: Arc<dyn SlashTarget>) -> Self {
+        let boundless_market =
+            BoundlessMarketService::new(target.address(), self.provider.clone(), Address::ZERO);
+        Self { target, boundless_market, ..self }
+    }
+
+    /// Read the locked, fulfilled, and slashed events from the DB of the Boundless indexer,
+    /// rather than querying the RPC for logs. Blocks are only processed once indexed.
+    ///
+    /// The indexer must index the same Boundless market as the service, so the source can not
+    /// be combined with a custom [SlashTarget].
+    pub fn with_indexer(self, indexer: Option<IndexerSource>) -> Self {
+        Self { indexer: indexer.map(Arc::new), ..self }
+    }
+
+    /// Forecast the slashes due within `horizon` from now, at the current gas price.
+    ///
+    /// Tracked orders that already expired are included, as they are slashed next. The gas per
+    /// slash is the average of the slashes recorded so far, or [forecast::DEFAULT_SLASH_GAS].
+    pub async fn forecast(&self, horizon: Duration) -> Result<Forecast, ServiceError> {
+        let now = self.clock.now();
+        let until = now + horizon.as_secs();
+        let filter = OrderFilter {
+            state: Some(OrderState::Tracked),
+            expires_before: Some(until),
+            ..Default::default()
+        };
+        let mut orders = Vec::new();
+        loop {
+            let page =
+                self.db.list_orders(&filter, orders.len() as u64, api::MAX_PAGE_LIMIT).await?;
+            let done = (page.len() as u64) < api::MAX_PAGE_LIMIT;
+            for order in page {
+                let collateral = self.db.get_order_collateral(order.id).await?;
+                orders.push((order, collateral));
+            }
+            if done {
+                break;
+            }
+        }
+
+        let spend = self.db.get_gas_spend(None).await?;
+        let gas_per_slash =
+            spend.gas_used.checked_div(spend.txs).unwrap_or(forecast::DEFAULT_SLASH_GAS);
+        let gas_price = self.provider.get_gas_price().await?;
+        Ok(Forecast::new(now, until, gas_price, gas_per_slash, orders))
+    }
+
+    /// Cross-check the tracked orders against their on-chain state, and re-scan the locked events
+    /// of `range` for orders missing from the DB.
+    ///
+    /// Tracked orders that are not locked, already slashed, or otherwise no longer slashable are
+    /// removed, and locked orders of `range` that may still be slashed are added. On-chain state
+    /// is queried in batches of [reconcile::RECONCILE_BATCH_SIZE]. With `dry_run`, the
+    /// discrepancies are only reported.
+    #[instrument(skip_all, fields(phase = "reconcile"))]
+    pub async fn reconcile(
+        &self,
+        range: Option<RangeInclusive<u64>>,
+        dry_run: bool,
+    ) -> Result<Reconciliation, ServiceError> {
+        let mut report = Reconciliation {
+            dry_run,
+            scanned_range: range.as_ref().map(|range| (*range.start(), *range.end())),
+            ..Default::default()
+        };
+
+        let filter = OrderFilter { state: Some(OrderState::Tracked), ..Default::default() };
+        let mut orders: Vec<TrackedOrder> = Vec::new();
+        loop {
+            let page =
+                self.db.list_orders(&filter, orders.len() as u64, api::MAX_PAGE_LIMIT).await?;
+            let done = (page.len() as u64) < api::MAX_PAGE_LIMIT;
+            orders.extend(page);
+            if done {
+                break;
+            }
+        }
+        let partner_orders = self.db.get_partner_order_ids(OrderState::Tracked).await?;
+        for batch in orders.chunks(RECONCILE_BATCH_SIZE) {
+            let states =
+                try_join_all(batch.iter().map(|order| self.onchain_order(order.id))).await?;
+            for (order, state) in batch.iter().zip(states) {
+                let partner = partner_orders.contains(&order.id);
+                let Some(reason) = state.untracked_reason(partner) else {
+                    continue;
+                };
+                tracing::info!(
+                    "Tracked request 0x{:x} does not match its on-chain state ({}), removing it",
+                    order.id,
+                    reason.as_str()
+                );
+                if !dry_run {
+                    self.remove_order(order.id).await?;
+                }
+                report.removed.push(RemovedOrder { request_id: order.id, reason });
+            }
+        }
+        report.checked = orders.len();
+
+        let Some(range) = range else {
+            return Ok(report);
+        };
+        let logs =
+            self.query_logs(self.target.locked_filter(), *range.start(), *range.end()).await?;
+        report.added = self.add_missed_orders(logs, dry_run).await?;
+        Ok(report)
+    }
+
+    // Add the orders of the given locked events that are missing from the DB and may still be
+    // slashed, returning their request IDs. With `dry_run`, the orders are only returned.
+    async fn add_missed_orders(
+        &self,
+        logs: Vec<Log>,
+        dry_run: bool,
+    ) -> Result<Vec<U256>, ServiceError> {
+        let mut missed = Vec::new();
+        for log in logs {
+            let order = self.target.decode_locked(&log)?;
+            if self.config.skip_addresses.contains(&order.prover)
+                || !self.config.enforces_requestor(order.requestor)
+                || self.db.order_exists(order.request_id).await?
+            {
+                continue;
+            }
+            missed.push((order, log));
+        }
+        let mut added = Vec::new();
+        for batch in missed.chunks(RECONCILE_BATCH_SIZE) {
+            let states =
+                try_join_all(batch.iter().map(|(order, _)| self.onchain_order(order.request_id)))
+                    .await?;
+            for ((order, log), state) in batch.iter().zip(states) {
+                let partner = self
+                    .config
+                    .partners
+                    .as_ref()
+                    .is_some_and(|partners| partners.addresses.contains(&order.prover));
+                if state.untracked_reason(partner).is_some() {
+                    continue;
+                }
+                tracing::info!(
+                    "Request 0x{:x} is missing from the DB, adding it",
+                    order.request_id
+                );
+                if !dry_run {
+                    self.process_locked_event(order.clone(), log.clone()).await?;
+                }
+                added.push(order.request_id);
+            }
+        }
+        Ok(added)
+    }
+
+    // Query the on-chain state of a request.
+    async fn onchain_order(&self, request_id: U256) -> Result<OnchainOrder, ServiceError> {
+        let locked = self.target.is_locked(request_id).await?;
+        if !locked {
+            return Ok(OnchainOrder::default());
+        }
+        Ok(OnchainOrder {
+            locked,
+            slashed: self.target.is_slashed(request_id).await?,
+            fulfilled: self.target.is_fulfilled(request_id).await?,
+            fulfilled_during_lock: self.target.is_fulfilled_during_lock(request_id).await?,
+        })
+    }
+
+    /// Check that the market address has code on a chain with the expected ID, and that the DB
+    /// was not previously used with another deployment.
+    ///
+    /// The chain ID and market address are recorded in the DB on first use. If `force` is set, a
+    /// DB recorded with another deployment is re-assigned to this one instead of refused.
+    pub async fn validate_deployment(
+        &self,
+        expected_chain_id: Option<u64>,
+        force: bool,
+    ) -> Result<(), ServiceError> {
+        let provider = &self.provider;
+        let market_address = self.target.address();
+
+        let chain_id = provider.get_chain_id().await?;
+        if let Some(expected) = expected_chain_id {
+            if chain_id != expected {
+                return Err(ServiceError::DeploymentMismatch(format!(
+                    "RPC is on chain {chain_id}, expected chain {expected}"
+                )));
+            }
+        }
+        if provider.get_code_at(market_address).await?.is_empty() {
+            return Err(ServiceError::DeploymentMismatch(format!(
+                "no contract deployed at market address {market_address} on chain {chain_id}"
+            )));
+        }
+
+        self.check_deployment_record(chain_id, market_address, force).await
+    }
+
+    async fn check_deployment_record(
+        &self,
+        chain_id: u64,
+        market_address: Address,
+        force: bool,
+    ) -> Result<(), ServiceError> {
+        match self.db.get_deployment().await? {
+            Some(recorded) if recorded == (chain_id, market_address) => return Ok(()),
+            Some((recorded_chain_id, recorded_address)) if !force => {
+                return Err(ServiceError::DeploymentMismatch(format!(
+                    "DB was used with market {recorded_address} on chain {recorded_chain_id}, \
+                     not market {market_address} on chain {chain_id}; use --force to re-assign it"
+                )));
+            }
+            Some((recorded_chain_id, recorded_address)) => tracing::warn!(
+                "Re-assigning DB used with market {recorded_address} on chain {recorded_chain_id} \
+                 to market {market_address} on chain {chain_id}"
+            ),
+            None => tracing::info!(
+                "Recording market {market_address} on chain {chain_id} as the DB's deployment"
+            ),
+        }
+        self.db.set_deployment(chain_id, market_address).await?;
+        Ok(())
+    }
+
+    pub async fn run(mut self, starting_block: Option<u64>) -> Result<(), ServiceError> {
+        let mut watcher = self.config.config_file.as_ref().map(TunablesWatcher::new);
+        if let Some(watcher) = &mut watcher {
+            self.reload_tunables(watcher);
+        }
+
+        // Resolve slashes that were in flight when the service last stopped before sending any
+        // new transactions. In standby mode, this is done by whichever instance takes the lead.
+        if self.config.standby.is_none() {
+            self.recover_inflight_slashes().await?;
+        }
+
+        if let Err(err) = self.backfill_event_gaps().await {
+            tracing::warn!("Failed to backfill the events of pruned blocks: {err:?}");
+        }
+
         let mut interval = tokio::time::interval(self.config.interval);
-        let current_block = self.current_block().await?;
+        let current_block = self.confirmed_block().await?;
         let last_processed_block = self.get_last_processed_block().await?.unwrap_or(current_block);
         let mut from_block = min(starting_block.unwrap_or(last_processed_block), current_block);
 
+        // Prune on startup, but only compact after a full interval so that restarts do not
+        // each rewrite the DB.
+        let mut last_prune = 0;
+        let mut last_compact = self.clock.now();
+        let mut last_recheck = 0;
+
         let mut attempt = 0;
         loop {
             interval.tick().await;
+            if let Some(watcher) = &mut watcher {
+                self.reload_tunables(watcher);
+            }
+            self.maintain_db(&mut last_prune, &mut last_compact).await;
+            self.recheck_orders(&mut last_recheck).await;
 
-            match self.current_block().await {
+            match self.confirmed_block().await {
                 Ok(to_block) => {
                     if to_block < from_block {
                         continue;
@@ -159,7 +744,7 @@ where
 
                     if chunk_to < to_block {
                         tracing::info!(
-                            "Processing blocks from {} to {} (chunked, current block: {})",
+                            "Processing blocks from {} to {} (chunked, confirmed block: {})",
                             from_block,
                             chunk_to,
                             to_block,
@@ -173,38 +758,25 @@ where
                             attempt = 0;
                             from_block = chunk_to + 1;
                         }
-                        Err(e) => match e {
-                            // Irrecoverable errors
-                            ServiceError::DatabaseError(_)
-                            | ServiceError::InsufficientFunds(_)
-                            | ServiceError::MaxRetries
-                            | ServiceError::TransactionDecodingError(_)
-                            | ServiceError::BlockNumberNotFound
-                            | ServiceError::RequestNotExpired => {
-                                tracing::error!(
-                                    "Failed to process blocks from {} to {}: {:?}",
-                                    from_block,
-                                    to_block,
-                                    e
-                                );
-                                return Err(e);
-                            }
-                            // Recoverable errors
-                            ServiceError::BoundlessMarketError(_)
-                            | ServiceError::SlashRevert(_, _)
-                            | ServiceError::EventQueryError(_)
-                            | ServiceError::RpcError(_)
-                            | ServiceError::BlockTimestampNotFound(_) => {
-                                attempt += 1;
-                                tracing::warn!(
-                                    "Failed to process blocks from {} to {}: {:?}, attempt number {}",
-                                    from_block,
-                                    to_block,
-                                    e,
-                                    attempt
-                                );
-                            }
-                        },
+                        Err(e) if e.is_recoverable() => {
+                            attempt += 1;
+                            tracing::warn!(
+                                "Failed to process blocks from {} to {}: {:?}, attempt number {}",
+                                from_block,
+                                to_block,
+                                e,
+                                attempt
+                            );
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to process blocks from {} to {}: {:?}",
+                                from_block,
+                                to_block,
+                                e
+                            );
+                            return Err(e);
+                        }
                     }
                 }
                 Err(e) => {
@@ -223,23 +795,286 @@ where
         }
     }
 
+    /// Apply the tunables from the config file if it changed since the last reload.
+    ///
+    /// An invalid file is logged and ignored, keeping the current settings.
+    fn reload_tunables(&mut self, watcher: &mut TunablesWatcher) {
+        match watcher.poll() {
+            Ok(Some(tunables)) => {
+                for change in tunables.apply(&mut self.config) {
+                    tracing::info!("Applied config change: {change}");
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                tracing::error!("Failed to reload config, keeping current settings: {err:?}")
+            }
+        }
+    }
+
+    /// Prune and compact the DB when due, given the times of the last prune and compaction.
+    ///
+    /// Failures are logged and retried on the next poll rather than stopping the service.
+    async fn maintain_db(&self, last_prune: &mut u64, last_compact: &mut u64) {
+        let now = self.clock.now();
+        if let Some(retention) = self.config.retention {
+            if now.saturating_sub(*last_prune) >= PRUNE_INTERVAL.as_secs() {
+                match self.db.prune(now.saturating_sub(retention.as_secs())).await {
+                    Ok(rows) => {
+                        tracing::info!(
+                            "Pruned {rows} rows older than {}s from the DB",
+                            retention.as_secs()
+                        );
+                        *last_prune = now;
+                    }
+                    Err(err) => tracing::warn!("Failed to prune the DB: {err:?}"),
+                }
+            }
+        }
+        if let Some(compact_interval) = self.config.compact_interval {
+            if now.saturating_sub(*last_compact) >= compact_interval.as_secs() {
+                match self.db.compact().await {
+                    Ok(()) => {
+                        tracing::info!("Compacted the DB");
+                        *last_compact = now;
+                    }
+                    Err(err) => tracing::warn!("Failed to compact the DB: {err:?}"),
+                }
+            }
+        }
+    }
+
+    /// Remove the tracked orders whose lock expired and which are already slashed on-chain, when
+    /// due given the time of the last re-check.
+    ///
+    /// Slashes are normally seen as [ProverSlashed] events, but events in a range that could not
+    /// be queried are missed, leaving the order to be slashed again and fail. Failures are logged
+    /// rather than stopping the service, and retried once the interval elapsed again, so that an
+    /// unreachable RPC is not queried for every tracked order on each poll.
+    #[instrument(skip_all, fields(phase = "recheck"))]
+    async fn recheck_orders(&self, last_recheck: &mut u64) {
+        let Some(recheck_interval) = self.config.recheck_interval else {
+            return;
+        };
+        let now = self.clock.now();
+        if now.saturating_sub(*last_recheck) < recheck_interval.as_secs() {
+            return;
+        }
+        *last_recheck = now;
+        let orders = match self.db.get_lock_expired_orders(now).await {
+            Ok(orders) => orders,
+            Err(err) => {
+                tracing::warn!("Failed to list orders to re-check: {err:?}");
+                return;
+            }
+        };
+
+        let mut removed = 0;
+        for request_id in orders {
+            match self.target.is_slashed(request_id).await {
+                Ok(true) => {
+                    tracing::info!(
+                        "Request 0x{:x} was slashed without its event being seen, removing it",
+                        request_id
+                    );
+                    if let Err(err) = self.remove_order(request_id).await {
+                        tracing::warn!("Failed to remove request 0x{:x}: {err:?}", request_id);
+                        return;
+                    }
+                    removed += 1;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::warn!("Failed to re-check request 0x{:x}: {err:?}", request_id);
+                    return;
+                }
+            }
+        }
+        tracing::debug!("Re-checked tracked orders, removed {removed}");
+    }
+
+    #[instrument(skip_all, fields(block_range = %format!("{from}-{to}")))]
     async fn process_blocks(&self, from: u64, to: u64) -> Result<(), ServiceError> {
-        // First check for new locked in requests
-        self.process_locked_events(from, to).await?;
+        // Skip the pages already processed by a previous attempt at this range, which may have
+        // been made before a restart.
+        let mut page_from = match self.db.get_range_checkpoint().await? {
+            Some((range_from, block)) if range_from <= from && (from..=to).contains(&block) => {
+                tracing::info!(
+                    "Resuming processing of blocks {} to {} after block {}",
+                    from,
+                    to,
+                    block
+                );
+                block + 1
+            }
+            _ => from,
+        };
 
-        // Then check for fulfilled/slashed events
-        self.process_fulfilled_events(from, to).await?;
-        self.process_slashed_events(from, to).await?;
+        let page_size = self.config.page_size.min(self.config.max_block_range).max(1);
+        while page_from <= to {
+            let page_to = min(page_from.saturating_add(page_size - 1), to);
+            match self.process_events(page_from, page_to).await {
+                Err(ServiceError::PrunedHistory(gap_from, gap_to)) => {
+                    // Record the gap and move on rather than retrying a range that can never be read.
+                    tracing::error!(
+                        "Event history of blocks {} to {} is pruned and no archive RPC is configured, recording the gap",
+                        gap_from,
+                        gap_to
+                    );
+                    self.db.add_event_gap(gap_from, gap_to).await?;
+                }
+                res => res?,
+            }
+            self.db.set_range_checkpoint(from, page_to).await?;
+            page_from = page_to + 1;
+        }
 
-        // Run the slashing task for expired requests
-        self.process_expired_requests(to).await?;
+        // Run the slashing task for expired requests, unless another instance is the leader
+        if self.claim_leadership().await? {
+            self.process_expired_requests(to).await?;
+        }
 
         // Update the last processed block
         self.update_last_processed_block(to).await?;
+        self.db.clear_range_checkpoint().await?;
 
         Ok(())
     }
 
+    #[instrument(skip_all, fields(page = %format!("{from}-{to}")))]
+    async fn process_events(&self, from: u64, to: u64) -> Result<(), ServiceError> {
+        // A query of pruned history does not stop the others, whose events may still be
+        // available. The gap is returned once all of them ran.
+        let mut pruned = None;
+        let mut record = |result: Result<(), ServiceError>| match result {
+            Err(err @ ServiceError::PrunedHistory(_, _)) => {
+                pruned = Some(err);
+                Ok(())
+            }
+            result => result,
+        };
+
+        // First check for new locked in requests
+        record(self.process_locked_events(from, to).await)?;
+
+        // Then check for fulfilled/slashed events
+        record(self.process_fulfilled_events(from, to).await)?;
+        record(self.process_slashed_events(from, to).await)?;
+        pruned.map_or(Ok(()), Err)
+    }
+
+    /// Add the orders locked in the block ranges recorded as gaps, reading their events from the
+    /// archive RPC, and remove each gap once done.
+    ///
+    /// The fulfillment or slash of an order locked in a gap may be in blocks already processed,
+    /// so orders are only added if they can still be slashed on-chain, as by [Self::reconcile].
+    /// Without an archive RPC, the gaps are only reported.
+    async fn backfill_event_gaps(&self) -> Result<(), ServiceError> {
+        let gaps = self.db.get_event_gaps().await?;
+        let Some(archive_provider) = &self.archive_provider else {
+            for (from_block, to_block) in gaps {
+                tracing::warn!(
+                    "Events of blocks {} to {} were not processed as their history was pruned; set an archive RPC to backfill them",
+                    from_block,
+                    to_block
+                );
+            }
+            return Ok(());
+        };
+        let query = ChunkedLogQuery::new(self.config.max_block_range);
+        for (from_block, to_block) in gaps {
+            tracing::info!(
+                "Backfilling the events of blocks {} to {} from the archive RPC",
+                from_block,
+                to_block
+            );
+            let logs = query
+                .query_logs(
+                    archive_provider,
+                    self.target.locked_filter(),
+                    from_block..=to_block,
+                    |_| {},
+                )
+                .await?;
+            let added = self.add_missed_orders(logs, false).await?;
+            tracing::info!(
+                "Backfilled blocks {} to {}, adding {} orders",
+                from_block,
+                to_block,
+                added.len()
+            );
+            self.db.remove_event_gap(from_block, to_block).await?;
+        }
+        Ok(())
+    }
+
+    // Query the logs matching the filter, falling back to the archive RPC if the main RPC has
+    // pruned the history of the range.
+    async fn query_logs(
+        &self,
+        filter: Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<Log>, ServiceError> {
+        let query = ChunkedLogQuery::new(self.config.max_block_range);
+        let result =
+            query.query_logs(&self.provider, filter.clone(), from_block..=to_block, |_| {}).await;
+        match result {
+            Err(err) if is_pruned_history_error(&err) => {
+                let Some(archive_provider) = &self.archive_provider else {
+                    return Err(ServiceError::PrunedHistory(from_block, to_block));
+                };
+                tracing::warn!(
+                    "Event history of blocks {} to {} is pruned, querying the archive RPC: {}",
+                    from_block,
+                    to_block,
+                    err
+                );
+                Ok(query
+                    .query_logs(archive_provider, filter, from_block..=to_block, |_| {})
+                    .await?)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    // Take or renew the leader lease if running in standby mode, returning whether this instance
+    // is the leader and so may send slash transactions.
+    async fn claim_leadership(&self) -> Result<bool, ServiceError> {
+        let Some(standby) = &self.config.standby else {
+            return Ok(true);
+        };
+        let now = self.clock.now();
+        let is_leader = self
+            .db
+            .claim_leadership(&standby.instance_id, now, standby.stale_after.as_secs())
+            .await?;
+
+        let was_leader = self.is_leader.swap(is_leader, Ordering::Relaxed);
+        match (was_leader, is_leader) {
+            (false, true) => {
+                tracing::info!("Instance {} is now the leader", standby.instance_id);
+            }
+            (true, false) => {
+                tracing::warn!(
+                    "Instance {} lost the leader lease, standing by",
+                    standby.instance_id
+                );
+            }
+            (false, false) => {
+                if let Some((leader, heartbeat_at)) = self.db.get_leader().await? {
+                    tracing::debug!(
+                        "Standing by for leader {}, last heartbeat {}s ago",
+                        leader,
+                        now.saturating_sub(heartbeat_at)
+                    );
+                }
+            }
+            (true, true) => {}
+        }
+        Ok(is_leader)
+    }
+
     async fn get_last_processed_block(&self) -> Result<Option<u64>, ServiceError> {
         Ok(self.db.get_last_block().await?)
     }
@@ -248,20 +1083,31 @@ where
         Ok(self.db.set_last_block(block_number).await?)
     }
 
+    #[instrument(skip_all, fields(phase = "locked", events = tracing::field::Empty))]
     async fn process_locked_events(
         &self,
         from_block: u64,
         to_block: u64,
     ) -> Result<(), ServiceError> {
-        let event_filter = self
-            .boundless_market
-            .instance()
-            .RequestLocked_filter()
-            .from_block(from_block)
-            .to_block(to_block);
+        if let Some(indexer) = &self.indexer {
+            let orders = indexer.locked_orders(from_block, to_block).await?;
+            tracing::Span::current().record("events", orders.len());
+            tracing::info!(
+                "Found {} indexed locked events from block {} to block {}",
+                orders.len(),
+                from_block,
+                to_block
+            );
+            for (order, block_number) in orders {
+                let log_data = Log { block_number: Some(block_number), ..Default::default() };
+                self.process_locked_event(order, log_data).await?;
+            }
+            return Ok(());
+        }
 
         // Query the logs for the event
-        let logs = event_filter.query().await?;
+        let logs = self.query_logs(self.target.locked_filter(), from_block, to_block).await?;
+        tracing::Span::current().record("events", logs.len());
         tracing::info!(
             "Found {} locked events from block {} to block {}",
             logs.len(),
@@ -269,50 +1115,95 @@ where
             to_block
         );
 
-        for (event, log_data) in logs {
-            let prover = event.prover;
+        for log_data in logs {
+            let order = self.target.decode_locked(&log_data)?;
+            self.process_locked_event(order, log_data).await?;
+        }
 
-            // Skip if sender is in the skip list
-            if self.config.skip_addresses.contains(&prover) {
-                tracing::info!(
-                    "Skipping locked event from prover: {:?} for request: 0x{:x}",
-                    prover,
-                    event.requestId
-                );
-                continue;
-            }
+        Ok(())
+    }
 
-            tracing::debug!(
-                "Processing locked event from prover: {:?} for request: 0x{:x} found at block {:?}",
+    #[instrument(skip_all, fields(request_id = %format!("0x{:x}", order.request_id), prover = %order.prover))]
+    async fn process_locked_event(
+        &self,
+        order: LockedOrder,
+        log_data: Log,
+    ) -> Result<(), ServiceError> {
+        let prover = order.prover;
+
+        // Skip if sender is in the skip list
+        if self.config.skip_addresses.contains(&prover) {
+            tracing::info!(
+                "Skipping locked event from prover: {:?} for request: 0x{:x}",
                 prover,
-                event.requestId,
-                log_data.block_number
+                order.request_id
             );
+            return Ok(());
+        }
 
-            let request = event.request.clone();
-            let expires_at = request.expires_at();
-            let lock_expires_at = request.offer.rampUpStart + request.offer.lockTimeout as u64;
-
-            self.add_order(event.requestId, expires_at, lock_expires_at).await?;
+        if !self.config.enforces_requestor(order.requestor) {
+            tracing::info!(
+                "Skipping locked event from requestor: {:?} for request: 0x{:x}",
+                order.requestor,
+                order.request_id
+            );
+            return Ok(());
         }
 
+        tracing::debug!(
+            "Processing locked event from prover: {:?} for request: 0x{:x} found at block {:?}",
+            prover,
+            order.request_id,
+            log_data.block_number
+        );
+
+        self.add_order(order.request_id, order.expires_at, order.lock_expires_at).await?;
+        self.db.set_order_collateral(order.request_id, order.collateral).await?;
+
+        if let Some(partners) = &self.config.partners {
+            if partners.addresses.contains(&prover) {
+                let slash_after = order.expires_at + partners.grace_period.as_secs();
+                tracing::info!(
+                    "Request 0x{:x} was locked by a partner; deferring its slash until {}",
+                    order.request_id,
+                    slash_after
+                );
+                self.db.set_order_partner(order.request_id, prover, slash_after).await?;
+            }
+        }
         Ok(())
     }
 
+    #[instrument(skip_all, fields(phase = "slashed", events = tracing::field::Empty))]
     async fn process_slashed_events(
         &self,
         from_block: u64,
         to_block: u64,
     ) -> Result<(), ServiceError> {
-        let event_filter = self
-            .boundless_market
-            .instance()
-            .ProverSlashed_filter()
-            .from_block(from_block)
-            .to_block(to_block);
+        if let Some(indexer) = &self.indexer {
+            let events = indexer.slashed(from_block, to_block).await?;
+            tracing::Span::current().record("events", events.len());
+            tracing::info!(
+                "Found {} indexed slashed events from block {} to block {}",
+                events.len(),
+                from_block,
+                to_block
+            );
+            for event in events {
+                tracing::debug!(
+                    request_id = %format!("0x{:x}", event.request_id),
+                    "Processing slashed event for request: 0x{:x} found at block {}",
+                    event.request_id,
+                    event.block_number
+                );
+                self.remove_order(event.request_id).await?;
+            }
+            return Ok(());
+        }
 
         // Query the logs for the event
-        let logs = event_filter.query().await?;
+        let logs = self.query_logs(self.target.slashed_filter(), from_block, to_block).await?;
+        tracing::Span::current().record("events", logs.len());
         tracing::info!(
             "Found {} slashed events from block {} to block {}",
             logs.len(),
@@ -320,32 +1211,49 @@ where
             to_block
         );
 
-        for (log, log_data) in logs {
+        for log_data in logs {
+            let request_id = self.target.decode_slashed(&log_data)?;
             tracing::debug!(
+                request_id = %format!("0x{:x}", request_id),
                 "Processing slashed event for request: 0x{:x} found at block {}",
-                log.requestId,
+                request_id,
                 log_data.block_number.unwrap_or(0)
             );
-            self.remove_order(log.requestId).await?;
+            self.remove_order(request_id).await?;
         }
 
         Ok(())
     }
 
+    #[instrument(skip_all, fields(phase = "fulfilled", events = tracing::field::Empty))]
     async fn process_fulfilled_events(
         &self,
         from_block: u64,
         to_block: u64,
     ) -> Result<(), ServiceError> {
-        let event_filter = self
-            .boundless_market
-            .instance()
-            .RequestFulfilled_filter()
-            .from_block(from_block)
-            .to_block(to_block);
+        if let Some(indexer) = &self.indexer {
+            let events = indexer.fulfilled(from_block, to_block).await?;
+            tracing::Span::current().record("events", events.len());
+            tracing::info!(
+                "Found {} indexed fulfilled events from block {} to block {}",
+                events.len(),
+                from_block,
+                to_block
+            );
+            for event in events {
+                let log_data = Log {
+                    block_number: Some(event.block_number),
+                    block_timestamp: Some(event.block_timestamp),
+                    ..Default::default()
+                };
+                self.process_fulfilled_event(event.request_id, log_data).await?;
+            }
+            return Ok(());
+        }
 
         // Query the logs for the event
-        let logs = event_filter.query().await?;
+        let logs = self.query_logs(self.target.fulfilled_filter(), from_block, to_block).await?;
+        tracing::Span::current().record("events", logs.len());
         tracing::info!(
             "Found {} fulfilled events from block {} to block {}",
             logs.len(),
@@ -353,46 +1261,64 @@ where
             to_block
         );
 
-        for (log, log_data) in logs {
-            tracing::debug!(
-                "Processing fulfilled event for request: 0x{:x} found at block {}",
-                log.requestId,
-                log_data.block_number.unwrap_or(0)
-            );
-            let current_ts = if let Some(current_ts) = log_data.block_timestamp {
-                current_ts
-            } else {
-                let bn = log_data.block_number.ok_or(ServiceError::BlockNumberNotFound)?;
-                self.block_timestamp(bn).await?
-            };
-            let (_, lock_expires_at) = match self.db.get_order(log.requestId).await? {
-                Some(order_data) => order_data,
-                None => {
-                    tracing::warn!(
-                        "Order not found in database for fulfilled request: 0x{:x}, skipping",
-                        log.requestId
-                    );
-                    continue;
-                }
-            };
-            if current_ts <= lock_expires_at {
-                tracing::debug!(
-                    "Request was fulfilled before lock expired. Removing from db: 0x{:x}",
-                    log.requestId
-                );
-                self.remove_order(log.requestId).await?;
-            } else {
-                tracing::debug!(
-                    "Request was fulfilled after lock expired. Not removing from db: 0x{:x}",
-                    log.requestId
+        for log_data in logs {
+            let request_id = self.target.decode_fulfilled(&log_data)?;
+            self.process_fulfilled_event(request_id, log_data).await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(request_id = %format!("0x{request_id:x}")))]
+    async fn process_fulfilled_event(
+        &self,
+        request_id: U256,
+        log_data: Log,
+    ) -> Result<(), ServiceError> {
+        tracing::debug!(
+            "Processing fulfilled event for request: 0x{:x} found at block {}",
+            request_id,
+            log_data.block_number.unwrap_or(0)
+        );
+        let current_ts = if let Some(current_ts) = log_data.block_timestamp {
+            current_ts
+        } else {
+            let bn = log_data.block_number.ok_or(ServiceError::BlockNumberNotFound)?;
+            self.block_timestamp(bn).await?
+        };
+        let (_, lock_expires_at) = match self.db.get_order(request_id).await? {
+            Some(order_data) => order_data,
+            None => {
+                tracing::warn!(
+                    "Order not found in database for fulfilled request: 0x{:x}, skipping",
+                    request_id
                 );
+                return Ok(());
             }
+        };
+        if current_ts <= lock_expires_at {
+            tracing::debug!(
+                "Request was fulfilled before lock expired. Removing from db: 0x{:x}",
+                request_id
+            );
+            self.remove_order(request_id).await?;
+        } else if self.db.get_order_partner(request_id).await?.is_some() {
+            tracing::info!(
+                "Request locked by a partner was fulfilled after its lock expired. Not slashing: 0x{:x}",
+                request_id
+            );
+            self.remove_order(request_id).await?;
+        } else {
+            tracing::debug!(
+                "Request was fulfilled after lock expired. Not removing from db: 0x{:x}",
+                request_id
+            );
         }
-
         Ok(())
     }
 
     // Insert request into database
+    #[instrument(skip_all, name = "db_write")]
     async fn add_order(
         &self,
         request_id: U256,
@@ -404,87 +1330,318 @@ where
     }
 
     // Remove request from database
+    #[instrument(skip_all, name = "db_write")]
     async fn remove_order(&self, request_id: U256) -> Result<(), ServiceError> {
         tracing::debug!("Removing request: 0x{:x}", request_id);
         Ok(self.db.remove_order(request_id).await?)
     }
 
-    async fn process_expired_requests(&self, current_block: u64) -> Result<(), ServiceError> {
+    // Update the processing state of a request
+    #[instrument(skip_all, name = "db_write")]
+    async fn set_order_state(
+        &self,
+        request_id: U256,
+        state: OrderState,
+    ) -> Result<(), ServiceError> {
+        tracing::debug!("Setting state of request 0x{:x} to {:?}", request_id, state);
+        Ok(self.db.set_order_state(request_id, state).await?)
+    }
+
+    // Resolve requests with a slash transaction in flight by checking the status of the
+    // transaction. Requests whose transaction was dropped or reverted without slashing are
+    // returned to the tracked state so they are retried.
+    #[instrument(skip_all, fields(phase = "recover"))]
+    async fn recover_inflight_slashes(&self) -> Result<(), ServiceError> {
+        let provider = &self.provider;
+        for (request_id, tx_hash) in self.db.get_inflight_orders().await? {
+            tracing::debug!(
+                request_id = %format!("0x{request_id:x}"),
+                "Checking in-flight slash tx 0x{:x} for request 0x{:x}",
+                tx_hash,
+                request_id
+            );
+            let receipt = provider.get_transaction_receipt(tx_hash).await?;
+            if let Some(receipt) = &receipt {
+                self.record_gas_spend(request_id, receipt).await?;
+            }
+            let next_state = match receipt {
+                Some(receipt) if receipt.status() => OrderState::Slashed,
+                None if provider.get_transaction_by_hash(tx_hash).await?.is_some() => {
+                    tracing::debug!(
+                        "Slash tx 0x{:x} for request 0x{:x} is still pending",
+                        tx_hash,
+                        request_id
+                    );
+                    continue;
+                }
+                _ if self.target.is_slashed(request_id).await? => OrderState::Slashed,
+                _ => {
+                    tracing::warn!(
+                        "Slash tx 0x{:x} for request 0x{:x} was dropped or reverted, retrying",
+                        tx_hash,
+                        request_id
+                    );
+                    OrderState::Tracked
+                }
+            };
+            if next_state == OrderState::Slashed {
+                tracing::info!(
+                    "Recovered slash of request 0x{:x} by tx 0x{:x}",
+                    request_id,
+                    tx_hash
+                );
+            }
+            self.set_order_state(request_id, next_state).await?;
+        }
+        Ok(())
+    }
+
+    // Slash the request, recording the tx hash before waiting for it to confirm so that a restart
+    // resolves the tx instead of sending another one. The outer error is returned if the state
+    // could not be recorded.
+    async fn slash(&self, request_id: U256) -> Result<Result<(), TargetError>, ServiceError> {
+        let tx_hash = match self.target.is_slashed(request_id).await {
+            Ok(true) => return Ok(Err(TargetError::RequestIsSlashed(request_id))),
+            Ok(false) => match self.target.send_slash(request_id).await {
+                Ok(tx_hash) => tx_hash,
+                Err(err) => return Ok(Err(err)),
+            },
+            Err(err) => return Ok(Err(err)),
+        };
+        self.set_order_state(request_id, OrderState::SlashingInflight(tx_hash)).await?;
+        let result = self.target.confirm_slash(tx_hash).await;
+
+        // Reverted transactions cost gas too, so the spend is recorded whatever the outcome.
+        let provider = &self.provider;
+        match provider.get_transaction_receipt(tx_hash).await {
+            Ok(Some(receipt)) => self.record_gas_spend(request_id, &receipt).await?,
+            Ok(None) => tracing::debug!("Slash tx 0x{:x} has no receipt yet", tx_hash),
+            Err(err) => {
+                tracing::warn!("Failed to fetch receipt of slash tx 0x{:x}: {err}", tx_hash)
+            }
+        }
+        Ok(result)
+    }
+
+    // Record the gas spent by a slash transaction of the given request.
+    async fn record_gas_spend(
+        &self,
+        request_id: U256,
+        receipt: &TransactionReceipt,
+    ) -> Result<(), ServiceError> {
+        let cost = U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price);
+        tracing::debug!(
+            "Slash tx 0x{:x} for request 0x{:x} used {} gas, costing {} wei",
+            receipt.transaction_hash,
+            request_id,
+            receipt.gas_used,
+            cost
+        );
+        self.db
+            .add_gas_spend(
+                request_id,
+                receipt.transaction_hash,
+                receipt.gas_used,
+                cost,
+                self.clock.now(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Check whether the gas spent on slashes in the last day reached the daily budget, alerting
+    // when slashing pauses and logging when it resumes.
+    async fn gas_budget_exhausted(&self) -> Result<bool, ServiceError> {
+        let Some(budget) = self.config.daily_gas_budget else {
+            return Ok(false);
+        };
+        let since = self.clock.now().saturating_sub(GAS_BUDGET_WINDOW.as_secs());
+        let spent = self.db.get_gas_spend(Some(since)).await?;
+        let exhausted = spent.cost >= budget;
+        let was_exhausted = self.budget_exhausted.swap(exhausted, Ordering::Relaxed);
+        match (was_exhausted, exhausted) {
+            (false, true) => tracing::error!(
+                "Daily gas budget of {} wei exhausted by {} slash txs costing {} wei; pausing slashing",
+                budget,
+                spent.txs,
+                spent.cost
+            ),
+            (true, false) => tracing::info!(
+                "Gas spent in the last day ({} wei) is below the daily budget of {} wei; resuming slashing",
+                spent.cost,
+                budget
+            ),
+            (true, true) => tracing::debug!("Slashing paused by the daily gas budget"),
+            (false, false) => {}
+        }
+        Ok(exhausted)
+    }
+
+    /// Slash the tracked requests that expired as of the given block, first resolving any slash
+    /// transactions left in flight.
+    #[instrument(
+        skip_all,
+        fields(phase = "slash", expired = tracing::field::Empty, slashes_attempted = tracing::field::Empty)
+    )]
+    pub async fn process_expired_requests(&self, current_block: u64) -> Result<(), ServiceError> {
+        // Resolve slashes left in flight by a previous attempt, e.g. after a confirmation timeout
+        self.recover_inflight_slashes().await?;
+
+        // Requests are slashable once the block timestamp is past their expiry. Those whose lock
+        // expired before are only reported, as slashing them would revert.
+        let timestamp = self.block_timestamp(current_block).await?;
+        let counts = self.db.count_stages(timestamp).await?;
+        tracing::debug!(
+            "Tracked orders: {} locked, {} slashable soon, {} expired",
+            counts.locked,
+            counts.lock_expired,
+            counts.expired
+        );
+
+        self.notify_partners(timestamp).await?;
+
         // Find expired requests
-        let expired =
-            self.db.get_expired_orders(self.block_timestamp(current_block).await?).await?;
+        let expired = self.db.get_expired_orders(timestamp).await?;
+        let span = tracing::Span::current();
+        span.record("expired", expired.len());
 
+        let mut attempted = 0;
         for request_id in expired {
-            tracing::debug!("About to slash expired request: 0x{:x}", request_id);
-            match self.boundless_market.slash(request_id).await {
-                Ok(_) => {
-                    tracing::info!("Slashing successful for request 0x{:x}", request_id);
-                    self.remove_order(request_id).await?;
-                }
-                Err(MarketError::RequestIsSlashed(request_id)) => {
-                    tracing::warn!("Request 0x{:x} is already slashed, removing", request_id);
-                    self.remove_order(request_id).await?;
+            // Checked before each slash, as a single poll may find many expired requests.
+            if self.gas_budget_exhausted().await? {
+                break;
+            }
+            // Renew the lease before each slash, so that an instance whose lease was taken over
+            // during a long poll does not send slashes concurrently with the new leader.
+            if !self.claim_leadership().await? {
+                break;
+            }
+            attempted += 1;
+            span.record("slashes_attempted", attempted);
+            self.slash_expired(request_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Notify the partners of their orders whose lock expired as of the given timestamp.
+    ///
+    /// Failed notifications are retried on the next poll. They do not hold back the slash once
+    /// the grace period is over.
+    async fn notify_partners(&self, timestamp: u64) -> Result<(), ServiceError> {
+        let Some(partners) = &self.config.partners else {
+            return Ok(());
+        };
+        for order in self.db.get_unnotified_partner_orders(timestamp).await? {
+            let notification = PartnerNotification {
+                request_id: format!("0x{:x}", order.id),
+                prover: order.prover,
+                lock_expires_at: order.lock_expires_at,
+                expires_at: order.expires_at,
+                slash_after: order.slash_after,
+            };
+            tracing::info!(
+                request_id = %notification.request_id,
+                prover = %order.prover,
+                "Lock of request {} by partner {} expired; slashing after {} unless fulfilled",
+                notification.request_id,
+                order.prover,
+                order.slash_after
+            );
+            if let Some(url) = &partners.webhook_url {
+                let sent = self
+                    .webhook_client
+                    .post(url.clone())
+                    .json(&notification)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                if let Err(err) = sent {
+                    tracing::warn!(
+                        "Failed to notify partner of request {}: {}",
+                        notification.request_id,
+                        err
+                    );
+                    continue;
                 }
-                Err(MarketError::SlashRevert(tx_hash)) => {
-                    // If already slashed should be caught by the error above, but double check here in case race condition
-                    // caused the previous call to miss the slashing.
-                    let slashed = self.boundless_market.is_slashed(request_id).await?;
-                    if slashed {
-                        tracing::warn!("Tx 0x{:x} reverted when slashing request 0x{:x}. Request is already slashed, removing", tx_hash, request_id);
-                        self.remove_order(request_id).await?;
-                    } else {
-                        // Only warn as we've seen eventual consistency issues where the request actually was slashed.
-                        // Logic will retry and should succeed in this case. If retrys fail, it will error out.
-                        tracing::warn!("Tx 0x{:x} for request 0x{:x} reverted and request is not slashed already", tx_hash, request_id);
-                        return Err(ServiceError::SlashRevert(request_id, tx_hash));
-                    }
+            }
+            self.db.set_order_notified(order.id, self.clock.now()).await?;
+        }
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(request_id = %format!("0x{request_id:x}")))]
+    async fn slash_expired(&self, request_id: U256) -> Result<(), ServiceError> {
+        tracing::debug!("About to slash expired request: 0x{:x}", request_id);
+        match self.slash(request_id).await? {
+            Ok(_) => {
+                tracing::info!("Slashing successful for request 0x{:x}", request_id);
+                self.set_order_state(request_id, OrderState::Slashed).await?;
+            }
+            Err(TargetError::RequestIsSlashed(request_id)) => {
+                tracing::warn!("Request 0x{:x} is already slashed", request_id);
+                self.set_order_state(request_id, OrderState::Slashed).await?;
+            }
+            Err(TargetError::SlashReverted(tx_hash)) => {
+                // If already slashed should be caught by the error above, but double check here in case race condition
+                // caused the previous call to miss the slashing.
+                let slashed = self.target.is_slashed(request_id).await?;
+                if slashed {
+                    tracing::warn!("Tx 0x{:x} reverted when slashing request 0x{:x}. Request is already slashed", tx_hash, request_id);
+                    self.set_order_state(request_id, OrderState::Slashed).await?;
+                } else {
+                    // Only warn as we've seen eventual consistency issues where the request actually was slashed.
+                    // Logic will retry and should succeed in this case. If retrys fail, it will error out.
+                    tracing::warn!(
+                        "Tx 0x{:x} for request 0x{:x} reverted and request is not slashed already",
+                        tx_hash,
+                        request_id
+                    );
+                    self.set_order_state(request_id, OrderState::Tracked).await?;
+                    return Err(ServiceError::SlashRevert(request_id, tx_hash));
                 }
-                Err(MarketError::LogNotEmitted(tx_hash, err)) => {
-                    let slashed = self.boundless_market.is_slashed(request_id).await?;
-                    if slashed {
-                        tracing::warn!("Tx 0x{:x} did not emit expected Slashed event for request 0x{:x} [{}]. Request is already slashed, removing", tx_hash, request_id, err);
-                        self.remove_order(request_id).await?;
-                    } else {
-                        tracing::warn!("Tx 0x{:x} for request 0x{:x} did not emit expected Slashed event [{}]. Request is not slashed already", tx_hash, request_id, err);
-                        return Err(ServiceError::SlashRevert(request_id, tx_hash));
-                    }
+            }
+            Err(TargetError::SlashNotEmitted(tx_hash, err)) => {
+                let slashed = self.target.is_slashed(request_id).await?;
+                if slashed {
+                    tracing::warn!("Tx 0x{:x} did not emit expected Slashed event for request 0x{:x} [{}]. Request is already slashed", tx_hash, request_id, err);
+                    self.set_order_state(request_id, OrderState::Slashed).await?;
+                } else {
+                    tracing::warn!("Tx 0x{:x} for request 0x{:x} did not emit expected Slashed event [{}]. Request is not slashed already", tx_hash, request_id, err);
+                    self.set_order_state(request_id, OrderState::Tracked).await?;
+                    return Err(ServiceError::SlashRevert(request_id, tx_hash));
                 }
-                Err(err) => {
-                    let err_msg = err.to_string();
-                    if err_msg.contains("RequestIsSlashed")
-                        || err_msg.contains("RequestIsFulfilled")
-                    {
-                        tracing::warn!(
-                            "Request was either fulfilled before lock expiry, or has already been slashed, removing 0x{:x}, reason: {}",
-                            request_id,
-                            err_msg
-                        );
-                        self.remove_order(request_id).await?;
-                    } else if err_msg.contains("RequestIsNotExpired") {
-                        // This should not happen
-                        tracing::error!("Request 0x{:x} is not expired yet", request_id);
-                        return Err(ServiceError::RequestNotExpired);
-                    } else if err_msg.contains("insufficient funds")
-                        || err_msg.contains("gas required exceeds allowance")
-                    {
-                        tracing::error!(
-                            "Insufficient funds for slashing request 0x{:x}",
-                            request_id
-                        );
-                        // Return as this is irrecoverable
-                        return Err(ServiceError::InsufficientFunds(err_msg));
-                    } else if err_msg.contains("RequestIsNotLocked") {
-                        tracing::error!(
-                            "Request 0x{:x} was marked for slashing but was not locked. Removing.",
-                            request_id
-                        );
-                        self.remove_order(request_id).await?;
-                    } else {
-                        // Any other error should be RPC related so we can retry
-                        // Only warn as logic will retry. If retrys fail, it will error out.
-                        tracing::warn!("Failed to slash request 0x{:x}: {}", request_id, err);
-                        return Err(ServiceError::BoundlessMarketError(err));
-                    }
+            }
+            Err(err) => {
+                let err_msg = err.to_string();
+                if err_msg.contains("RequestIsSlashed") || err_msg.contains("RequestIsFulfilled") {
+                    tracing::warn!(
+                        "Request was either fulfilled before lock expiry, or has already been slashed, 0x{:x}, reason: {}",
+                        request_id,
+                        err_msg
+                    );
+                    self.set_order_state(request_id, OrderState::Failed).await?;
+                } else if err_msg.contains("RequestIsNotExpired") {
+                    // This should not happen
+                    tracing::error!("Request 0x{:x} is not expired yet", request_id);
+                    return Err(ServiceError::RequestNotExpired);
+                } else if err_msg.contains("insufficient funds")
+                    || err_msg.contains("gas required exceeds allowance")
+                {
+                    tracing::error!("Insufficient funds for slashing request 0x{:x}", request_id);
+                    // Return as this is irrecoverable
+                    return Err(ServiceError::InsufficientFunds(err_msg));
+                } else if err_msg.contains("RequestIsNotLocked") {
+                    tracing::error!(
+                        "Request 0x{:x} was marked for slashing but was not locked",
+                        request_id
+                    );
+                    self.set_order_state(request_id, OrderState::Failed).await?;
+                } else {
+                    // Any other error should be RPC related so we can retry
+                    // Only warn as logic will retry. If retrys fail, it will error out.
+                    tracing::warn!("Failed to slash request 0x{:x}: {}", request_id, err);
+                    return Err(ServiceError::TargetError(err));
                 }
             }
         }
@@ -492,19 +1649,635 @@ where
         Ok(())
     }
 
-    async fn current_block(&self) -> Result<u64, ServiceError> {
-        Ok(self.boundless_market.instance().provider().get_block_number().await?)
+    /// Get the latest block that has the configured number of confirmations, and, if reading
+    /// events from the indexer, that it has indexed.
+    pub async fn confirmed_block(&self) -> Result<u64, ServiceError> {
+        let confirmed =
+            self.block_source.block_number().await?.saturating_sub(self.config.confirmations);
+        let Some(indexer) = &self.indexer else {
+            return Ok(confirmed);
+        };
+        let indexed = indexer.last_block().await?.ok_or_else(|| {
+            ServiceError::IndexerError("the indexer has not indexed any block yet".to_string())
+        })?;
+        Ok(min(confirmed, indexed))
     }
 
     async fn block_timestamp(&self, block_number: u64) -> Result<u64, ServiceError> {
-        Ok(self
-            .boundless_market
-            .instance()
-            .provider()
-            .get_block_by_number(block_number.into())
-            .await?
-            .ok_or_else(|| ServiceError::BlockTimestampNotFound(block_number))?
-            .header
-            .timestamp)
+        self.block_source.block_timestamp(block_number).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+
+    use alloy::{
+        providers::RootProvider,
+        rpc::json_rpc::ErrorPayload,
+        transports::{mock::Asserter, TransportErrorKind},
+    };
+    use boundless_market::http_client::HttpClientConfig;
+    use sqlx::SqlitePool;
+
+    use super::*;
+    use crate::clock::ManualClock;
+
+    const BLOCK_TIME: u64 = 12;
+
+    /// Block source with a fixed head, block timestamps at [BLOCK_TIME] intervals, and head
+    /// queries that fail after the first `fail_after`.
+    struct FakeBlockSource {
+        head: u64,
+        fail_after: Option<u64>,
+        head_queries: AtomicU64,
+    }
+
+    impl FakeBlockSource {
+        fn new(head: u64) -> Self {
+            Self { head, fail_after: None, head_queries: AtomicU64::new(0) }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BlockSource for FakeBlockSource {
+        async fn block_number(&self) -> Result<u64, ServiceError> {
+            let queries = self.head_queries.fetch_add(1, Ordering::Relaxed) + 1;
+            if self.fail_after.is_some_and(|fail_after| queries > fail_after) {
+                return Err(TransportErrorKind::backend_gone().into());
+            }
+            Ok(self.head)
+        }
+
+        async fn block_timestamp(&self, block_number: u64) -> Result<u64, ServiceError> {
+            Ok(block_number * BLOCK_TIME)
+        }
+    }
+
+    fn config() -> SlashServiceConfig {
+        SlashServiceConfig {
+            interval: Duration::from_millis(1),
+            retries: 2,
+            balance_warn_threshold: None,
+            balance_error_threshold: None,
+            skip_addresses: vec![],
+            skip_requestors: vec![],
+            allow_requestors: vec![],
+            tx_timeout: Duration::from_secs(120),
+            max_block_range: 500,
+            page_size: 100,
+            http_client: HttpClientConfig::default(),
+            archive_rpc_url: None,
+            standby: None,
+            check_head_age: false,
+            max_head_age: None,
+            reference_rpc_url: None,
+            max_block_lag: 10,
+            confirmations: 0,
+            slash_confirmations: 1,
+            config_file: None,
+            daily_gas_budget: None,
+            retention: None,
+            compact_interval: None,
+            recheck_interval: None,
+            partners: None,
+        }
+    }
+
+    // Build a service whose provider points at a closed port, so that any RPC call fails.
+    async fn service(
+        pool: SqlitePool,
+        config: SlashServiceConfig,
+        block_source: Arc<dyn BlockSource>,
+    ) -> SlashService<RootProvider> {
+        let provider = RootProvider::new_http("http://127.0.0.1:1".parse().unwrap());
+        let boundless_market = BoundlessMarketService::new(Address::ZERO, provider, Address::ZERO);
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        SlashService::from_parts(boundless_market, db, config).with_block_source(block_source)
+    }
+
+    #[test]
+    fn error_classification() {
+        assert!(!ServiceError::MaxRetries.is_recoverable());
+        assert!(!ServiceError::InsufficientFunds("out of gas".into()).is_recoverable());
+        assert!(!ServiceError::RequestNotExpired.is_recoverable());
+        assert!(!ServiceError::BlockNumberNotFound.is_recoverable());
+        assert!(ServiceError::BlockTimestampNotFound(1).is_recoverable());
+        assert!(!ServiceError::PrunedHistory(1, 2).is_recoverable());
+        assert!(ServiceError::SlashRevert(U256::ZERO, B256::ZERO).is_recoverable());
+        assert!(ServiceError::RpcError(TransportErrorKind::backend_gone()).is_recoverable());
+    }
+
+    fn pruned_history(code: i64, message: &'static str) -> RpcError<TransportErrorKind> {
+        RpcError::ErrorResp(ErrorPayload { code, message: message.into(), data: None })
+    }
+
+    fn mocked_provider(asserter: &Asserter) -> DynProvider {
+        ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .connect_mocked_client(asserter.clone())
+            .erased()
+    }
+
+    #[test]
+    fn pruned_history_errors() {
+        let pruned = pruned_history(PRUNED_HISTORY_CODE, "pruned history unavailable");
+        assert!(is_pruned_history_error(&pruned.into()));
+        // Other errors mentioning pruning are not mistaken for pruned history.
+        let other = pruned_history(-32000, "receipts of pruned blocks are not indexed");
+        assert!(!is_pruned_history_error(&other.into()));
+        let transport = TransportErrorKind::custom_str("pruned");
+        assert!(!is_pruned_history_error(&transport.into()));
+    }
+
+    #[sqlx::test]
+    async fn pruned_history_falls_back_to_archive(pool: SqlitePool) {
+        let (main, archive) = (Asserter::new(), Asserter::new());
+        let boundless_market =
+            BoundlessMarketService::new(Address::ZERO, mocked_provider(&main), Address::ZERO);
+        let db: DbObj = Arc::new(SqliteDb::from(pool).await.unwrap());
+        let service = SlashService::from_parts(boundless_market, db, config())
+            .with_archive_provider(Some(mocked_provider(&archive)));
+        let filter = service.target.locked_filter();
+
+        // The chunked query retries each failed chunk before giving up.
+        for _ in 0..4 {
+            main.push_failure(ErrorPayload {
+                code: PRUNED_HISTORY_CODE,
+                message: "pruned history unavailable".into(),
+                data: None,
+            });
+        }
+        archive.push_success(&Vec::<Log>::new());
+        assert!(service.query_logs(filter.clone(), 1, 100).await.unwrap().is_empty());
+
+        for _ in 0..4 {
+            main.push_failure(ErrorPayload {
+                code: PRUNED_HISTORY_CODE,
+                message: "pruned history unavailable".into(),
+                data: None,
+            });
+        }
+        let service = service.with_archive_provider(None);
+        let err = service.query_logs(filter, 1, 100).await.unwrap_err();
+        assert!(matches!(err, ServiceError::PrunedHistory(1, 100)), "{err:?}");
+    }
+
+    #[sqlx::test]
+    async fn event_gaps_are_backfilled(pool: SqlitePool) {
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100))).await;
+        service.db.add_event_gap(10, 20).await.unwrap();
+
+        // Without an archive RPC, the gap is kept.
+        service.backfill_event_gaps().await.unwrap();
+        assert_eq!(service.db.get_event_gaps().await.unwrap(), vec![(10, 20)]);
+
+        let archive = Asserter::new();
+        archive.push_success(&Vec::<Log>::new());
+        let service = service.with_archive_provider(Some(mocked_provider(&archive)));
+        service.backfill_event_gaps().await.unwrap();
+        assert!(service.db.get_event_gaps().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn requestor_lists() {
+        let own = Address::repeat_byte(1);
+        let partner = Address::repeat_byte(2);
+        let other = Address::repeat_byte(3);
+
+        let config = SlashServiceConfig { skip_requestors: vec![own], ..config() };
+        assert!(!config.enforces_requestor(own));
+        assert!(config.enforces_requestor(other));
+
+        let config = SlashServiceConfig {
+            skip_requestors: vec![own],
+            allow_requestors: vec![own, partner],
+            ..config()
+        };
+        assert!(!config.enforces_requestor(own));
+        assert!(config.enforces_requestor(partner));
+        assert!(!config.enforces_requestor(other));
+    }
+
+    #[sqlx::test]
+    async fn confirmed_block(pool: SqlitePool) {
+        let config = SlashServiceConfig { confirmations: 5, ..config() };
+        let service = service(pool, config, Arc::new(FakeBlockSource::new(100))).await;
+        assert_eq!(service.confirmed_block().await.unwrap(), 95);
+    }
+
+    #[sqlx::test]
+    async fn confirmed_block_waits_for_indexer(pool: SqlitePool) {
+        let indexer = indexer::test_utils::source().await;
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100)))
+            .await
+            .with_indexer(Some(indexer.clone()));
+
+        // Nothing is confirmed before the indexer has indexed a block.
+        let err = service.confirmed_block().await.unwrap_err();
+        assert!(matches!(err, ServiceError::IndexerError(_)), "{err:?}");
+        assert!(err.is_recoverable());
+
+        // The indexer lags behind the RPC.
+        indexer::test_utils::set_last_block(&indexer, 90).await;
+        assert_eq!(service.confirmed_block().await.unwrap(), 90);
+
+        // The indexer is ahead of the RPC.
+        indexer::test_utils::set_last_block(&indexer, 120).await;
+        assert_eq!(service.confirmed_block().await.unwrap(), 100);
+    }
+
+    #[sqlx::test]
+    async fn process_blocks_from_indexer(pool: SqlitePool) {
+        use boundless_market::contracts::RequestId;
+        use indexer::test_utils::*;
+
+        let indexer = source().await;
+        // The provider is unreachable, so any event read from the RPC would fail.
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100)))
+            .await
+            .with_indexer(Some(indexer.clone()));
+        let order = |index: u32| LockedOrder {
+            request_id: RequestId::u256(Address::repeat_byte(2), index),
+            requestor: Address::repeat_byte(2),
+            prover: Address::repeat_byte(3),
+            expires_at: 1000,
+            lock_expires_at: 500,
+            collateral: U256::from(5),
+        };
+        let (fulfilled, expired, slashed) = (order(1), order(2), order(3));
+        for order in [&fulfilled, &expired, &slashed] {
+            insert_request(&indexer, order, 10, 120).await;
+            insert_lock(&indexer, order, 11, 132).await;
+        }
+        insert_fulfilled(&indexer, fulfilled.request_id, 20, 240).await;
+        insert_slashed(&indexer, slashed.request_id, 30, 360).await;
+        set_last_block(&indexer, 40).await;
+
+        service.process_blocks(1, 40).await.unwrap();
+        assert_eq!(service.get_last_processed_block().await.unwrap(), Some(40));
+        // Only the order neither fulfilled during its lock nor slashed is still tracked.
+        assert!(!service.db.order_exists(fulfilled.request_id).await.unwrap());
+        assert!(service.db.order_exists(expired.request_id).await.unwrap());
+        assert!(!service.db.order_exists(slashed.request_id).await.unwrap());
+        assert_eq!(service.db.get_order(expired.request_id).await.unwrap(), Some((1000, 500)));
+    }
+
+    #[sqlx::test]
+    async fn deployment_record(pool: SqlitePool) {
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100))).await;
+        let market = Address::repeat_byte(1);
+
+        // The first deployment seen is recorded, and accepted from then on.
+        service.check_deployment_record(1, market, false).await.unwrap();
+        service.check_deployment_record(1, market, false).await.unwrap();
+        assert_eq!(service.db.get_deployment().await.unwrap(), Some((1, market)));
+
+        let err = service.check_deployment_record(2, market, false).await.unwrap_err();
+        assert!(matches!(err, ServiceError::DeploymentMismatch(_)), "{err:?}");
+        assert!(!err.is_recoverable());
+        let other = Address::repeat_byte(2);
+        let err = service.check_deployment_record(1, other, false).await.unwrap_err();
+        assert!(matches!(err, ServiceError::DeploymentMismatch(_)), "{err:?}");
+
+        // Forcing re-assigns the DB to the new deployment.
+        service.check_deployment_record(1, other, true).await.unwrap();
+        assert_eq!(service.db.get_deployment().await.unwrap(), Some((1, other)));
+        service.check_deployment_record(1, market, false).await.unwrap_err();
+    }
+
+    #[sqlx::test]
+    async fn expiry_uses_block_timestamp(pool: SqlitePool) {
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100))).await;
+        let request_id = U256::from(1);
+        service.add_order(request_id, 1000, 500).await.unwrap();
+
+        // Block 83 is at 996s, before the request expires, so nothing is slashed.
+        service.process_expired_requests(83).await.unwrap();
+        assert_eq!(
+            service.db.get_order_state(request_id).await.unwrap(),
+            Some(OrderState::Tracked)
+        );
+
+        // Block 84 is at 1008s, after the request expires. The slash fails as the RPC is
+        // unreachable, which is retried.
+        let err = service.process_expired_requests(84).await.unwrap_err();
+        assert!(matches!(err, ServiceError::TargetError(_)), "{err:?}");
+        assert!(err.is_recoverable());
+        assert_eq!(
+            service.db.get_order_state(request_id).await.unwrap(),
+            Some(OrderState::Tracked)
+        );
+    }
+
+    /// Market fork on which every request is locked, recording the slashes sent to it.
+    #[derive(Default)]
+    struct FakeTarget {
+        slashed: std::sync::Mutex<Vec<U256>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SlashTarget for FakeTarget {
+        fn address(&self) -> Address {
+            Address::repeat_byte(0xfa)
+        }
+
+        fn locked_filter(&self) -> Filter {
+            Filter::new()
+        }
+
+        fn decode_locked(&self, _log: &Log) -> Result<LockedOrder, ServiceError> {
+            unimplemented!("no events are queried")
+        }
+
+        fn fulfilled_filter(&self) -> Filter {
+            Filter::new()
+        }
+
+        fn decode_fulfilled(&self, _log: &Log) -> Result<U256, ServiceError> {
+            unimplemented!("no events are queried")
+        }
+
+        fn slashed_filter(&self) -> Filter {
+            Filter::new()
+        }
+
+        fn decode_slashed(&self, _log: &Log) -> Result<U256, ServiceError> {
+            unimplemented!("no events are queried")
+        }
+
+        async fn is_locked(&self, _request_id: U256) -> Result<bool, TargetError> {
+            Ok(true)
+        }
+
+        async fn is_fulfilled(&self, _request_id: U256) -> Result<bool, TargetError> {
+            Ok(false)
+        }
+
+        async fn is_slashed(&self, request_id: U256) -> Result<bool, TargetError> {
+            Ok(self.slashed.lock().unwrap().contains(&request_id))
+        }
+
+        async fn is_fulfilled_during_lock(&self, _request_id: U256) -> Result<bool, TargetError> {
+            Ok(false)
+        }
+
+        async fn send_slash(&self, request_id: U256) -> Result<B256, TargetError> {
+            self.slashed.lock().unwrap().push(request_id);
+            Ok(B256::with_last_byte(1))
+        }
+
+        async fn confirm_slash(&self, _tx_hash: B256) -> Result<(), TargetError> {
+            Ok(())
+        }
+    }
+
+    #[sqlx::test]
+    async fn slashes_through_custom_target(pool: SqlitePool) {
+        let target = Arc::new(FakeTarget::default());
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100)))
+            .await
+            .with_target(target.clone());
+        #[allow(deprecated)]
+        let market_address = *service.boundless_market.instance().address();
+        assert_eq!(market_address, target.address());
+
+        // Block 84 is at 1008s, after the request expires. The slash goes through the target,
+        // while the RPC is unreachable.
+        let request_id = U256::from(1);
+        service.add_order(request_id, 1000, 500).await.unwrap();
+        service.process_expired_requests(84).await.unwrap();
+        assert_eq!(*target.slashed.lock().unwrap(), vec![request_id]);
+        assert_eq!(
+            service.db.get_order_state(request_id).await.unwrap(),
+            Some(OrderState::Slashed)
+        );
+    }
+
+    #[sqlx::test]
+    async fn fulfilled_event_timestamp(pool: SqlitePool) {
+        let service = service(pool, config(), Arc::new(FakeBlockSource::new(100))).await;
+        let log =
+            |block_number: u64| Log { block_number: Some(block_number), ..Default::default() };
+
+        // Fulfilled at 492s, before the lock expired at 500s, so the request can not be slashed.
+        service.add_order(U256::from(1), 1000, 500).await.unwrap();
+        service.process_fulfilled_event(U256::from(1), log(41)).await.unwrap();
+        assert!(!service.db.order_exists(U256::from(1)).await.unwrap());
+
+        // Fulfilled at 504s, after the lock expired, so the locking prover is still slashed.
+        service.add_order(U256::from(2), 1000, 500).await.unwrap();
+        service.process_fulfilled_event(U256::from(2), log(42)).await.unwrap();
+        assert!(service.db.order_exists(U256::from(2)).await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn partner_orders_are_notified_and_deferred(pool: SqlitePool) {
+        let partner = Address::repeat_byte(1);
+        let config = SlashServiceConfig {
+            partners: Some(PartnerConfig {
+                addresses: vec![partner],
+                webhook_url: None,
+                grace_period: Duration::from_secs(600),
+            }),
+            ..config()
+        };
+        let service = service(pool, config, Arc::new(FakeBlockSource::new(100))).await;
+        let request_id = U256::from(1);
+        service.add_order(request_id, 1000, 500).await.unwrap();
+        service.db.set_order_partner(request_id, partner, 1600).await.unwrap();
+
+        // Block 84 is at 1008s, after the request expired, but within the grace period. The
+        // partner is notified and nothing is slashed.
+        service.process_expired_requests(84).await.unwrap();
+        assert!(service.db.get_unnotified_partner_orders(1008).await.unwrap().is_empty());
+        assert_eq!(
+            service.db.get_order_state(request_id).await.unwrap(),
+            Some(OrderState::Tracked)
+        );
+
+        // Block 134 is at 1608s, after the grace period, so the slash is attempted and fails as
+        // the RPC is unreachable.
+        let err = service.process_expired_requests(134).await.unwrap_err();
+        assert!(matches!(err, ServiceError::TargetError(_)), "{err:?}");
+
+        // Fulfilled at 504s, after the lock expired, so the partner is not slashed.
+        let log = Log { block_number: Some(42), ..Default::default() };
+        service.process_fulfilled_event(request_id, log).await.unwrap();
+        assert!(!service.db.order_exists(request_id).await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn leadership_follows_clock(pool: SqlitePool) {
+        let clock = Arc::new(ManualClock::new(1000));
+        let block_source = Arc::new(FakeBlockSource::new(100));
+        let standby = |instance_id: &str| SlashServiceConfig {
+            standby: Some(StandbyConfig {
+                instance_id: instance_id.into(),
+                stale_after: Duration::from_secs(60),
+            }),
+            ..config()
+        };
+        let first =
+            service(pool, standby("first"), block_source.clone()).await.with_clock(clock.clone());
+        let second = SlashService {
+            config: standby("second"),
+            is_leader: Arc::new(AtomicBool::new(false)),
+            ..first.clone()
+        };
+
+        assert!(first.claim_leadership().await.unwrap());
+        clock.advance(30);
+        assert!(!second.claim_leadership().await.unwrap());
+
+        // The first instance stops renewing its lease, which the second takes over once stale.
+        clock.advance(20);
+        assert!(!second.claim_leadership().await.unwrap());
+        clock.advance(20);
+        assert!(second.claim_leadership().await.unwrap());
+        assert!(!first.claim_leadership().await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn slash_requires_lease(pool: SqlitePool) {
+        let clock = Arc::new(ManualClock::new(1000));
+        let block_source = Arc::new(FakeBlockSource::new(100));
+        let standby = |instance_id: &str| SlashServiceConfig {
+            standby: Some(StandbyConfig {
+                instance_id: instance_id.into(),
+                stale_after: Duration::from_secs(60),
+            }),
+            ..config()
+        };
+        let first =
+            service(pool, standby("first"), block_source.clone()).await.with_clock(clock.clone());
+        let second = SlashService {
+            config: standby("second"),
+            is_leader: Arc::new(AtomicBool::new(false)),
+            ..first.clone()
+        };
+        first.add_order(U256::from(1), 1000, 500).await.unwrap();
+
+        // The second instance takes over the lease of the first, which then sends no slash even
+        // though the request expired. A slash would fail as the RPC is unreachable.
+        assert!(first.claim_leadership().await.unwrap());
+        clock.advance(70);
+        assert!(second.claim_leadership().await.unwrap());
+        first.process_expired_requests(84).await.unwrap();
+        assert_eq!(
+            first.db.get_order_state(U256::from(1)).await.unwrap(),
+            Some(OrderState::Tracked)
+        );
+    }
+
+    #[sqlx::test]
+    async fn gas_budget_pauses_slashing(pool: SqlitePool) {
+        let clock = Arc::new(ManualClock::new(100_000));
+        let block_source = Arc::new(FakeBlockSource::new(100));
+        let config = SlashServiceConfig { daily_gas_budget: Some(U256::from(1000)), ..config() };
+        let service = service(pool, config, block_source).await.with_clock(clock.clone());
+        assert!(!service.gas_budget_exhausted().await.unwrap());
+
+        service
+            .db
+            .add_gas_spend(U256::from(1), B256::repeat_byte(1), 21000, U256::from(600), clock.now())
+            .await
+            .unwrap();
+        assert!(!service.gas_budget_exhausted().await.unwrap());
+        clock.advance(3600);
+        service
+            .db
+            .add_gas_spend(U256::from(2), B256::repeat_byte(2), 21000, U256::from(400), clock.now())
+            .await
+            .unwrap();
+        assert!(service.gas_budget_exhausted().await.unwrap());
+
+        // Slashing resumes once the first transaction leaves the window.
+        clock.advance(GAS_BUDGET_WINDOW.as_secs() - 3600 + 1);
+        assert!(!service.gas_budget_exhausted().await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn recheck_orders_retries_after_interval(pool: SqlitePool) {
+        let clock = Arc::new(ManualClock::new(1000));
+        let block_source = Arc::new(FakeBlockSource::new(100));
+        let config =
+            SlashServiceConfig { recheck_interval: Some(Duration::from_secs(60)), ..config() };
+        let service = service(pool, config, block_source).await.with_clock(clock.clone());
+
+        // Orders whose lock has not expired are not checked, so no RPC call is made.
+        service.db.add_order(U256::from(1), 2000, 1500).await.unwrap();
+        let mut last_recheck = 0;
+        service.recheck_orders(&mut last_recheck).await;
+        assert_eq!(last_recheck, clock.now());
+
+        // The lock expired, but the RPC is unreachable, so the order is kept. The failed re-check
+        // still counts, so it is only retried once the interval elapsed again.
+        clock.advance(600);
+        service.recheck_orders(&mut last_recheck).await;
+        assert_eq!(last_recheck, clock.now());
+        assert!(service.db.order_exists(U256::from(1)).await.unwrap());
+        let checked_at = last_recheck;
+        clock.advance(30);
+        service.recheck_orders(&mut last_recheck).await;
+        assert_eq!(last_recheck, checked_at);
+        clock.advance(30);
+        service.recheck_orders(&mut last_recheck).await;
+        assert_eq!(last_recheck, clock.now());
+    }
+
+    #[sqlx::test]
+    async fn maintain_db_prunes_hourly(pool: SqlitePool) {
+        let day = 24 * 60 * 60;
+        let clock = Arc::new(ManualClock::new(10 * day));
+        let block_source = Arc::new(FakeBlockSource::new(100));
+        let config = SlashServiceConfig {
+            retention: Some(Duration::from_secs(day)),
+            compact_interval: Some(Duration::from_secs(day)),
+            ..config()
+        };
+        let service = service(pool, config, block_source).await.with_clock(clock.clone());
+        service.db.add_order(U256::from(1), 5 * day, 5 * day).await.unwrap();
+        service.db.set_order_state(U256::from(1), OrderState::Slashed).await.unwrap();
+        service.db.add_order(U256::from(2), 9 * day + 1, 9 * day + 1).await.unwrap();
+        service.db.set_order_state(U256::from(2), OrderState::Failed).await.unwrap();
+
+        let (mut last_prune, mut last_compact) = (0, clock.now());
+        service.maintain_db(&mut last_prune, &mut last_compact).await;
+        assert_eq!(last_prune, clock.now());
+        assert!(!service.db.order_exists(U256::from(1)).await.unwrap());
+        assert!(service.db.order_exists(U256::from(2)).await.unwrap());
+
+        // Order 2 is past the retention, but the next prune is not due for an hour.
+        clock.advance(day);
+        last_prune = clock.now() - 60;
+        service.maintain_db(&mut last_prune, &mut last_compact).await;
+        assert!(service.db.order_exists(U256::from(2)).await.unwrap());
+        assert_eq!(last_compact, clock.now());
+
+        clock.advance(PRUNE_INTERVAL.as_secs());
+        service.maintain_db(&mut last_prune, &mut last_compact).await;
+        assert!(!service.db.order_exists(U256::from(2)).await.unwrap());
+    }
+
+    #[sqlx::test]
+    async fn run_gives_up_after_retries(pool: SqlitePool) {
+        let block_source =
+            Arc::new(FakeBlockSource { fail_after: Some(1), ..FakeBlockSource::new(100) });
+        let service = service(pool, config(), block_source.clone()).await;
+
+        let err = service.run(None).await.unwrap_err();
+        assert!(matches!(err, ServiceError::MaxRetries), "{err:?}");
+        // The head is read once at startup, then once per attempt until the retries run out.
+        assert_eq!(block_source.head_queries.load(Ordering::Relaxed), 1 + 3);
+    }
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new(10);
+        clock.advance(5);
+        assert_eq!(clock.now(), 15);
+        clock.set(1);
+        assert_eq!(clock.now(), 1);
     }
 }
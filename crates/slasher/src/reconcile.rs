@@ -0,0 +1,156 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reconciliation of the tracked orders with their on-chain state.
+//!
+//! Cross-checks the orders tracked in the DB against the market, removing those that can no
+//! longer be slashed, and re-scans the locked events of a block range to track the orders that
+//! were missed. This is a safety valve for operators after a bug or a range of skipped events.
+
+use std::fmt;
+
+use alloy::primitives::U256;
+use serde::Serialize;
+
+/// Number of orders whose on-chain state is queried concurrently.
+pub const RECONCILE_BATCH_SIZE: usize = 20;
+
+/// On-chain state of a request, as far as slashing is concerned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OnchainOrder {
+    /// Whether the request was locked.
+    pub locked: bool,
+    /// Whether the prover that locked the request was slashed.
+    pub slashed: bool,
+    /// Whether the request was fulfilled.
+    pub fulfilled: bool,
+    /// Whether the prover that locked the request fulfilled it before the lock expired, as
+    /// recorded in the flags of the request lock.
+    pub fulfilled_during_lock: bool,
+}
+
+impl OnchainOrder {
+    /// Why the order should not be tracked, or `None` if it may still be slashed.
+    ///
+    /// An order fulfilled after its lock expired is kept, as the locking prover is still slashed,
+    /// unless it was locked by a partner.
+    pub fn untracked_reason(&self, partner: bool) -> Option<Discrepancy> {
+        if !self.locked {
+            Some(Discrepancy::NotLocked)
+        } else if self.slashed {
+            Some(Discrepancy::Slashed)
+        } else if self.fulfilled_during_lock {
+            Some(Discrepancy::FulfilledDuringLock)
+        } else if self.fulfilled && partner {
+            Some(Discrepancy::FulfilledByPartner)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reason a tracked order does not match its on-chain state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// The request is not locked in the market.
+    NotLocked,
+    /// The prover was already slashed.
+    Slashed,
+    /// The request was fulfilled before its lock expired.
+    FulfilledDuringLock,
+    /// The request was locked by a partner, and fulfilled.
+    FulfilledByPartner,
+}
+
+impl Discrepancy {
+    /// Name of the discrepancy, as shown in reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotLocked => "not_locked",
+            Self::Slashed => "slashed",
+            Self::FulfilledDuringLock => "fulfilled_during_lock",
+            Self::FulfilledByPartner => "fulfilled_by_partner",
+        }
+    }
+}
+
+/// A tracked order removed by the reconciliation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RemovedOrder {
+    /// ID of the request.
+    pub request_id: U256,
+    /// Why the order was removed.
+    pub reason: Discrepancy,
+}
+
+/// Changes made to the tracked orders by a reconciliation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Reconciliation {
+    /// Whether the changes were only reported, rather than applied.
+    pub dry_run: bool,
+    /// Number of tracked orders checked against the chain.
+    pub checked: usize,
+    /// Block range whose locked events were re-scanned, if any.
+    pub scanned_range: Option<(u64, u64)>,
+    /// Tracked orders removed, as they can no longer be slashed.
+    pub removed: Vec<RemovedOrder>,
+    /// Orders of the scanned range that were missing from the DB and added.
+    pub added: Vec<U256>,
+}
+
+impl fmt::Display for Reconciliation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (remove, add) =
+            if self.dry_run { ("To remove", "To add") } else { ("Removed", "Added") };
+        write!(f, "Checked {} tracked orders", self.checked)?;
+        if let Some((from, to)) = self.scanned_range {
+            write!(f, ", scanned blocks {from} to {to} for missed orders")?;
+        }
+        write!(f, "\n{remove}: {}", self.removed.len())?;
+        for order in &self.removed {
+            write!(f, "\n  0x{:x}  {}", order.request_id, order.reason.as_str())?;
+        }
+        write!(f, "\n{add}: {}", self.added.len())?;
+        for request_id in &self.added {
+            write!(f, "\n  0x{request_id:x}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untracked_reason() {
+        let locked = OnchainOrder { locked: true, ..Default::default() };
+        assert_eq!(locked.untracked_reason(false), None);
+        assert_eq!(OnchainOrder::default().untracked_reason(false), Some(Discrepancy::NotLocked));
+        assert_eq!(
+            OnchainOrder { slashed: true, ..locked }.untracked_reason(false),
+            Some(Discrepancy::Slashed)
+        );
+
+        // A fulfillment after the lock expired only clears partners.
+        let fulfilled = OnchainOrder { fulfilled: true, ..locked };
+        assert_eq!(
+            OnchainOrder { fulfilled_during_lock: true, ..fulfilled }.untracked_reason(false),
+            Some(Discrepancy::FulfilledDuringLock)
+        );
+        assert_eq!(fulfilled.untracked_reason(false), None);
+        assert_eq!(fulfilled.untracked_reason(true), Some(Discrepancy::FulfilledByPartner));
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The market contract enforced by the service, behind a trait so that forks running a modified
+//! market can plug in an adapter.
+//!
+//! [BoundlessMarketTarget] enforces the Boundless market. A market with other events, or another
+//! way of computing the expiry of an order, implements [SlashTarget] and is given to the service
+//! with [crate::SlashService::from_target] or [crate::SlashService::with_target].
+
+use alloy::{
+    network::Ethereum,
+    primitives::{Address, B256, U256},
+    providers::{PendingTransactionBuilder, Provider},
+    rpc::types::{Filter, Log},
+};
+use async_trait::async_trait;
+use boundless_market::contracts::{
+    boundless_market::{BoundlessMarketService, MarketError},
+    IBoundlessMarket::{ProverSlashed, RequestFulfilled, RequestLocked},
+    RequestId,
+};
+use thiserror::Error;
+
+use crate::ServiceError;
+
+/// Error of a [SlashTarget].
+#[derive(Error, Debug)]
+pub enum TargetError {
+    /// The prover that locked the request was already slashed.
+    #[error("request 0x{0:x} is already slashed")]
+    RequestIsSlashed(U256),
+
+    /// The slash transaction reverted.
+    #[error("slash tx {0} reverted")]
+    SlashReverted(B256),
+
+    /// The slash transaction confirmed without emitting the slash event.
+    #[error("slash tx {0} did not emit the slash event: {1}")]
+    SlashNotEmitted(B256, String),
+
+    /// Any other error, such as a failed RPC call or a transaction rejected by the node.
+    ///
+    /// Reverts of the market are recognized by the name of their error in the message.
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<MarketError> for TargetError {
+    fn from(err: MarketError) -> Self {
+        match err {
+            MarketError::RequestIsSlashed(request_id) => Self::RequestIsSlashed(request_id),
+            MarketError::SlashRevert(tx_hash) => Self::SlashReverted(tx_hash),
+            MarketError::LogNotEmitted(tx_hash, err) => {
+                Self::SlashNotEmitted(tx_hash, err.to_string())
+            }
+            err => Self::Other(err.into()),
+        }
+    }
+}
+
+/// An order locked in the market, as decoded from a lock event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockedOrder {
+    /// ID of the request.
+    pub request_id: U256,
+    /// Address of the requestor.
+    pub requestor: Address,
+    /// Address of the prover that locked the request.
+    pub prover: Address,
+    /// Time, in seconds since the UNIX epoch, at which the request expires.
+    pub expires_at: u64,
+    /// Time, in seconds since the UNIX epoch, at which the lock expires.
+    pub lock_expires_at: u64,
+    /// Collateral put up by the prover to lock the request.
+    pub collateral: U256,
+}
+
+/// The market contract whose locked orders are slashed once expired.
+///
+/// Events are matched with filters and decoded from raw logs, and slash transactions are tracked
+/// by their hash, so that the service does not depend on the types of the market.
+#[async_trait]
+pub trait SlashTarget: Send + Sync {
+    /// Address of the market contract.
+    fn address(&self) -> Address;
+
+    /// Filter matching the events of locked requests.
+    fn locked_filter(&self) -> Filter;
+
+    /// Decode a log matched by [Self::locked_filter], computing the expiry of the order.
+    fn decode_locked(&self, log: &Log) -> Result<LockedOrder, ServiceError>;
+
+    /// Filter matching the events of fulfilled requests.
+    fn fulfilled_filter(&self) -> Filter;
+
+    /// Decode the ID of the request of a log matched by [Self::fulfilled_filter].
+    fn decode_fulfilled(&self, log: &Log) -> Result<U256, ServiceError>;
+
+    /// Filter matching the events of slashed requests.
+    fn slashed_filter(&self) -> Filter;
+
+    /// Decode the ID of the request of a log matched by [Self::slashed_filter].
+    fn decode_slashed(&self, log: &Log) -> Result<U256, ServiceError>;
+
+    /// Whether the request is locked.
+    async fn is_locked(&self, request_id: U256) -> Result<bool, TargetError>;
+
+    /// Whether the request is fulfilled.
+    async fn is_fulfilled(&self, request_id: U256) -> Result<bool, TargetError>;
+
+    /// Whether the prover that locked the request was slashed.
+    async fn is_slashed(&self, request_id: U256) -> Result<bool, TargetError>;
+
+    /// Whether the prover that locked the request fulfilled it before the lock expired, and so
+    /// can not be slashed.
+    async fn is_fulfilled_during_lock(&self, request_id: U256) -> Result<bool, TargetError>;
+
+    /// Send a transaction slashing the prover that locked the request, returning its hash.
+    async fn send_slash(&self, request_id: U256) -> Result<B256, TargetError>;
+
+    /// Wait for a slash transaction sent by [Self::send_slash] to confirm, failing if it reverted
+    /// or did not slash.
+    async fn confirm_slash(&self, tx_hash: B256) -> Result<(), TargetError>;
+}
+
+/// [SlashTarget] enforcing the Boundless market.
+#[derive(Clone)]
+pub struct BoundlessMarketTarget<P> {
+    market: BoundlessMarketService<P>,
+}
+
+impl<P> BoundlessMarketTarget<P> {
+    pub fn new(market: BoundlessMarketService<P>) -> Self {
+        Self { market }
+    }
+}
+
+#[async_trait]
+impl<P> SlashTarget for BoundlessMarketTarget<P>
+where
+    P: Provider<Ethereum> + 'static + Clone,
+{
+    fn address(&self) -> Address {
+        *self.market.instance().address()
+    }
+
+    fn locked_filter(&self) -> Filter {
+        self.market.instance().RequestLocked_filter().filter
+    }
+
+    fn decode_locked(&self, log: &Log) -> Result<LockedOrder, ServiceError> {
+        let event = log.log_decode::<RequestLocked>()?.inner.data;
+        Ok(LockedOrder {
+            request_id: event.requestId,
+            requestor: RequestId::from_lossy(event.requestId).addr,
+            prover: event.prover,
+            expires_at: event.request.expires_at(),
+            lock_expires_at: event.request.offer.rampUpStart
+                + event.request.offer.lockTimeout as u64,
+            collateral: event.request.offer.lockCollateral,
+        })
+    }
+
+    fn fulfilled_filter(&self) -> Filter {
+        self.market.instance().RequestFulfilled_filter().filter
+    }
+
+    fn decode_fulfilled(&self, log: &Log) -> Result<U256, ServiceError> {
+        Ok(log.log_decode::<RequestFulfilled>()?.inner.data.requestId)
+    }
+
+    fn slashed_filter(&self) -> Filter {
+        self.market.instance().ProverSlashed_filter().filter
+    }
+
+    fn decode_slashed(&self, log: &Log) -> Result<U256, ServiceError> {
+        Ok(log.log_decode::<ProverSlashed>()?.inner.data.requestId)
+    }
+
+    async fn is_locked(&self, request_id: U256) -> Result<bool, TargetError> {
+        Ok(self.market.is_locked(request_id).await?)
+    }
+
+    async fn is_fulfilled(&self, request_id: U256) -> Result<bool, TargetError> {
+        Ok(self.market.is_fulfilled(request_id).await?)
+    }
+
+    async fn is_slashed(&self, request_id: U256) -> Result<bool, TargetError> {
+        Ok(self.market.is_slashed(request_id).await?)
+    }
+
+    async fn is_fulfilled_during_lock(&self, request_id: U256) -> Result<bool, TargetError> {
+        Ok(self.market.get_request_lock(request_id).await?.is_prover_paid_during_lock())
+    }
+
+    async fn send_slash(&self, request_id: U256) -> Result<B256, TargetError> {
+        Ok(*self.market.send_slash(request_id).await?.tx_hash())
+    }
+
+    async fn confirm_slash(&self, tx_hash: B256) -> Result<(), TargetError> {
+        let provider = self.market.instance().provider().root().clone();
+        let pending_tx = PendingTransactionBuilder::<Ethereum>::new(provider, tx_hash);
+        self.market.confirm_slash(pending_tx).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{providers::RootProvider, sol_types::SolEvent};
+    use boundless_market::contracts::{Offer, ProofRequest};
+
+    use super::*;
+
+    #[test]
+    fn decode_locked() {
+        let provider = RootProvider::new_http("http://127.0.0.1:1".parse().unwrap());
+        let market = Address::repeat_byte(1);
+        let target = BoundlessMarketTarget::new(BoundlessMarketService::new(
+            market,
+            provider,
+            Address::ZERO,
+        ));
+
+        let requestor = Address::repeat_byte(2);
+        let request_id = RequestId::u256(requestor, 7);
+        let event = RequestLocked {
+            requestId: request_id,
+            prover: Address::repeat_byte(3),
+            request: ProofRequest {
+                id: request_id,
+                offer: Offer {
+                    rampUpStart: 1000,
+                    lockTimeout: 100,
+                    timeout: 300,
+                    lockCollateral: U256::from(5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            clientSignature: Default::default(),
+        };
+        let log = Log {
+            inner: alloy::primitives::Log { address: market, data: event.encode_log_data() },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            target.decode_locked(&log).unwrap(),
+            LockedOrder {
+                request_id,
+                requestor,
+                prover: Address::repeat_byte(3),
+                expires_at: 1300,
+                lock_expires_at: 1100,
+                collateral: U256::from(5),
+            }
+        );
+        // Logs of other events are not mistaken for locks.
+        target.decode_fulfilled(&log).unwrap_err();
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tunables of the slasher that can be changed at runtime through a config file.
+
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use alloy::primitives::Address;
+use anyhow::{ensure, Context, Result};
+use serde::Deserialize;
+
+use crate::SlashServiceConfig;
+
+/// Settings read from the config file, overriding those given on the command line.
+///
+/// Any setting left out of the file keeps its current value.
+///
+/// ```toml
+/// skip_addresses = ["0x0000000000000000000000000000000000000001"]
+/// skip_requestors = ["0x0000000000000000000000000000000000000002"]
+/// max_block_range = 1000
+/// retries = 10
+/// confirmations = 2
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Tunables {
+    /// Addresses to skip when processing locked events.
+    pub skip_addresses: Option<Vec<Address>>,
+    /// Requestors whose requests are not enforced.
+    pub skip_requestors: Option<Vec<Address>>,
+    /// If not empty, only requests from these requestors are enforced.
+    pub allow_requestors: Option<Vec<Address>>,
+    /// Maximum block range to query in a single request.
+    pub max_block_range: Option<u64>,
+    /// Number of retries before quitting after an error.
+    pub retries: Option<u32>,
+    /// Number of blocks an event must be buried under before it is processed.
+    pub confirmations: Option<u64>,
+}
+
+impl Tunables {
+    /// Parse and validate tunables from the contents of a TOML file.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let tunables: Self = toml::from_str(contents)?;
+        ensure!(tunables.max_block_range != Some(0), "max_block_range must be positive");
+        Ok(tunables)
+    }
+
+    /// Apply the tunables to the config, returning a description of each changed setting.
+    pub fn apply(&self, config: &mut SlashServiceConfig) -> Vec<String> {
+        let mut changes = Vec::new();
+        if let Some(skip_addresses) = &self.skip_addresses {
+            if *skip_addresses != config.skip_addresses {
+                changes.push(format!("skip_addresses = {skip_addresses:?}"));
+                config.skip_addresses = skip_addresses.clone();
+            }
+        }
+        if let Some(skip_requestors) = &self.skip_requestors {
+            if *skip_requestors != config.skip_requestors {
+                changes.push(format!("skip_requestors = {skip_requestors:?}"));
+                config.skip_requestors = skip_requestors.clone();
+            }
+        }
+        if let Some(allow_requestors) = &self.allow_requestors {
+            if *allow_requestors != config.allow_requestors {
+                changes.push(format!("allow_requestors = {allow_requestors:?}"));
+                config.allow_requestors = allow_requestors.clone();
+            }
+        }
+        if let Some(max_block_range) = self.max_block_range {
+            if max_block_range != config.max_block_range {
+                changes.push(format!("max_block_range = {max_block_range}"));
+                config.max_block_range = max_block_range;
+            }
+        }
+        if let Some(retries) = self.retries {
+            if retries != config.retries {
+                changes.push(format!("retries = {retries}"));
+                config.retries = retries;
+            }
+        }
+        if let Some(confirmations) = self.confirmations {
+            if confirmations != config.confirmations {
+                changes.push(format!("confirmations = {confirmations}"));
+                config.confirmations = confirmations;
+            }
+        }
+        changes
+    }
+}
+
+/// Watches the config file for changes by polling its modification time.
+#[derive(Clone, Debug)]
+pub(crate) struct TunablesWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+}
+
+impl TunablesWatcher {
+    pub(crate) fn new(path: impl AsRef<Path>) -> Self {
+        Self { path: path.as_ref().to_path_buf(), last_modified: None }
+    }
+
+    /// Load the tunables if the file changed since the last call.
+    ///
+    /// Returns `Ok(None)` if the file is unchanged. The first call always loads the file.
+    pub(crate) fn poll(&mut self) -> Result<Option<Tunables>> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .with_context(|| format!("failed to stat config file {}", self.path.display()))?;
+        if self.last_modified == Some(modified) {
+            return Ok(None);
+        }
+        // Record the modification time even if the file is invalid, so that an invalid file is
+        // reported once rather than on every poll.
+        self.last_modified = Some(modified);
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read config file {}", self.path.display()))?;
+        let tunables = Tunables::parse(&contents)
+            .with_context(|| format!("invalid config file {}", self.path.display()))?;
+        Ok(Some(tunables))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::primitives::address;
+    use boundless_market::http_client::HttpClientConfig;
+
+    use super::*;
+
+    fn config() -> SlashServiceConfig {
+        SlashServiceConfig {
+            interval: Duration::from_secs(5),
+            retries: 10,
+            balance_warn_threshold: None,
+            balance_error_threshold: None,
+            skip_addresses: vec![],
+            skip_requestors: vec![],
+            allow_requestors: vec![],
+            tx_timeout: Duration::from_secs(120),
+            max_block_range: 500,
+            page_size: 100,
+            http_client: HttpClientConfig::default(),
+            archive_rpc_url: None,
+            standby: None,
+            check_head_age: false,
+            max_head_age: None,
+            reference_rpc_url: None,
+            max_block_lag: 10,
+            confirmations: 0,
+            slash_confirmations: 1,
+            config_file: None,
+            daily_gas_budget: None,
+            retention: None,
+            compact_interval: None,
+            recheck_interval: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_tunables() {
+        let mut config = config();
+        let tunables = Tunables::parse(
+            r#"
+            skip_addresses = ["0x0000000000000000000000000000000000000001"]
+            max_block_range = 1000
+            "#,
+        )
+        .unwrap();
+
+        let changes = tunables.apply(&mut config);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            config.skip_addresses,
+            vec![address!("0x0000000000000000000000000000000000000001")]
+        );
+        assert_eq!(config.max_block_range, 1000);
+        assert_eq!(config.retries, 10);
+
+        // Applying the same tunables again changes nothing.
+        assert!(tunables.apply(&mut config).is_empty());
+
+        assert!(Tunables::parse("max_block_range = 0").is_err());
+        assert!(Tunables::parse("interval = 1").is_err());
+    }
+
+    #[test]
+    fn test_watcher_reloads_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("slasher.toml");
+        std::fs::write(&path, "retries = 3").unwrap();
+
+        let mut watcher = TunablesWatcher::new(&path);
+        let tunables = watcher.poll().unwrap().unwrap();
+        assert_eq!(tunables.retries, Some(3));
+        assert!(watcher.poll().unwrap().is_none());
+
+        std::fs::write(&path, "retries = 4").unwrap();
+        // Make sure the modification time changes on filesystems with coarse timestamps.
+        let modified = SystemTime::now() + Duration::from_secs(1);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+        let tunables = watcher.poll().unwrap().unwrap();
+        assert_eq!(tunables.retries, Some(4));
+    }
+}
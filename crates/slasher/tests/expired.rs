@@ -0,0 +1,248 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests of the slasher against each on-chain state an expired request can be in.
+
+use std::time::Duration;
+
+use alloy::{
+    node_bindings::{Anvil, AnvilInstance},
+    primitives::U256,
+    providers::{Provider, WalletProvider},
+    signers::local::PrivateKeySigner,
+};
+use boundless_market::http_client::HttpClientConfig;
+use boundless_slasher::{
+    reconcile::Discrepancy, OrderState, ServiceError, SlashService, SlashServiceConfig,
+};
+use boundless_test_utils::{
+    market::{create_test_ctx, TestCtx},
+    slashing::{setup, SlashFixture, SlashScenario},
+};
+
+fn config() -> SlashServiceConfig {
+    SlashServiceConfig {
+        interval: Duration::from_secs(1),
+        retries: 1,
+        balance_warn_threshold: None,
+        balance_error_threshold: None,
+        skip_addresses: vec![],
+        skip_requestors: vec![],
+        allow_requestors: vec![],
+        tx_timeout: Duration::from_secs(30),
+        max_block_range: 1000,
+        page_size: 1000,
+        http_client: HttpClientConfig::default(),
+        archive_rpc_url: None,
+        standby: None,
+        check_head_age: false,
+        max_head_age: None,
+        reference_rpc_url: None,
+        max_block_lag: 10,
+        confirmations: 0,
+        slash_confirmations: 1,
+        config_file: None,
+        daily_gas_budget: None,
+        retention: None,
+        compact_interval: None,
+        recheck_interval: None,
+        partners: None,
+    }
+}
+
+// Set up a request in the given scenario, and a slasher tracking it with the given signer.
+async fn slasher_with<P>(
+    anvil: &AnvilInstance,
+    ctx: &TestCtx<P>,
+    signer: &PrivateKeySigner,
+    config: SlashServiceConfig,
+    scenario: SlashScenario,
+) -> (SlashService<impl Provider + Clone + 'static>, SlashFixture)
+where
+    P: Provider + WalletProvider + Clone + 'static,
+{
+    let fixture = setup(ctx, 1, scenario).await.unwrap();
+    let service = SlashService::new(
+        anvil.endpoint_url(),
+        signer,
+        ctx.deployment.boundless_market_address,
+        "sqlite::memory:",
+        config,
+    )
+    .await
+    .unwrap();
+    service
+        .db
+        .add_order(
+            fixture.request.id,
+            fixture.request.expires_at(),
+            fixture.request.lock_expires_at(),
+        )
+        .await
+        .unwrap();
+    (service, fixture)
+}
+
+// Run a slasher against a request in the given scenario, returning the result and the state the
+// request is left in.
+async fn process(scenario: SlashScenario) -> (Result<(), ServiceError>, Option<OrderState>) {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let signer: PrivateKeySigner = anvil.keys()[3].clone().into();
+    let (service, fixture) = slasher_with(&anvil, &ctx, &signer, config(), scenario).await;
+
+    let block = ctx.customer_provider.get_block_number().await.unwrap();
+    let result = service.process_expired_requests(block).await;
+    (result, service.db.get_order_state(fixture.request.id).await.unwrap())
+}
+
+// Reconcile a slasher tracking a request in the given scenario, returning why the request was
+// removed, if it was.
+async fn reconcile(scenario: SlashScenario) -> Option<Discrepancy> {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let signer: PrivateKeySigner = anvil.keys()[3].clone().into();
+    let (service, fixture) = slasher_with(&anvil, &ctx, &signer, config(), scenario).await;
+
+    let report = service.reconcile(None, false).await.unwrap();
+    assert_eq!(report.checked, 1);
+    let removed = report.removed.first().map(|order| order.reason);
+    assert_eq!(service.db.order_exists(fixture.request.id).await.unwrap(), removed.is_none());
+    removed
+}
+
+#[tokio::test]
+async fn slashes_expired() {
+    let (result, state) = process(SlashScenario::Expired).await;
+    result.unwrap();
+    assert_eq!(state, Some(OrderState::Slashed));
+}
+
+#[tokio::test]
+async fn already_slashed() {
+    let (result, state) = process(SlashScenario::AlreadySlashed).await;
+    result.unwrap();
+    assert_eq!(state, Some(OrderState::Slashed));
+}
+
+#[tokio::test]
+async fn slashes_fulfilled_after_lock_expiry() {
+    let (result, state) = process(SlashScenario::FulfilledAfterLockExpiry).await;
+    result.unwrap();
+    assert_eq!(state, Some(OrderState::Slashed));
+}
+
+#[tokio::test]
+async fn fulfilled_before_lock_expiry() {
+    let (result, state) = process(SlashScenario::FulfilledBeforeLockExpiry).await;
+    result.unwrap();
+    assert_eq!(state, Some(OrderState::Failed));
+}
+
+#[tokio::test]
+async fn not_locked() {
+    let (result, state) = process(SlashScenario::NotLocked).await;
+    result.unwrap();
+    assert_eq!(state, Some(OrderState::Failed));
+}
+
+#[tokio::test]
+async fn not_expired() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let signer: PrivateKeySigner = anvil.keys()[3].clone().into();
+    let (service, fixture) =
+        slasher_with(&anvil, &ctx, &signer, config(), SlashScenario::NotExpired).await;
+
+    // Track the request as if it had expired when bidding started, which the market rejects.
+    let request = &fixture.request;
+    service.db.remove_order(request.id).await.unwrap();
+    service
+        .db
+        .add_order(request.id, request.offer.rampUpStart, request.offer.rampUpStart)
+        .await
+        .unwrap();
+
+    let block = ctx.customer_provider.get_block_number().await.unwrap();
+    let err = service.process_expired_requests(block).await.unwrap_err();
+    assert!(matches!(err, ServiceError::RequestNotExpired), "{err:?}");
+    assert!(!err.is_recoverable());
+    assert_eq!(service.db.get_order_state(request.id).await.unwrap(), Some(OrderState::Tracked));
+}
+
+#[tokio::test]
+async fn insufficient_funds() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let signer = PrivateKeySigner::random();
+    let (service, fixture) =
+        slasher_with(&anvil, &ctx, &signer, config(), SlashScenario::Expired).await;
+
+    let block = ctx.customer_provider.get_block_number().await.unwrap();
+    let err = service.process_expired_requests(block).await.unwrap_err();
+    assert!(matches!(err, ServiceError::InsufficientFunds(_)), "{err:?}");
+    assert_eq!(
+        service.db.get_order_state(fixture.request.id).await.unwrap(),
+        Some(OrderState::Tracked)
+    );
+}
+
+#[tokio::test]
+async fn gas_budget_exhausted() {
+    let anvil = Anvil::new().spawn();
+    let ctx = create_test_ctx(&anvil).await.unwrap();
+    let signer: PrivateKeySigner = anvil.keys()[3].clone().into();
+    let config = SlashServiceConfig { daily_gas_budget: Some(U256::ZERO), ..config() };
+    let (service, fixture) =
+        slasher_with(&anvil, &ctx, &signer, config, SlashScenario::Expired).await;
+
+    // The request stays tracked, to be slashed once the budget allows.
+    let block = ctx.customer_provider.get_block_number().await.unwrap();
+    service.process_expired_requests(block).await.unwrap();
+    assert_eq!(
+        service.db.get_order_state(fixture.request.id).await.unwrap(),
+        Some(OrderState::Tracked)
+    );
+    assert!(!ctx.customer_market.is_slashed(fixture.request.id).await.unwrap());
+}
+
+#[tokio::test]
+async fn reconcile_keeps_expired() {
+    assert_eq!(reconcile(SlashScenario::Expired).await, None);
+}
+
+#[tokio::test]
+async fn reconcile_removes_slashed() {
+    assert_eq!(reconcile(SlashScenario::AlreadySlashed).await, Some(Discrepancy::Slashed));
+}
+
+#[tokio::test]
+async fn reconcile_keeps_fulfilled_after_lock_expiry() {
+    assert_eq!(reconcile(SlashScenario::FulfilledAfterLockExpiry).await, None);
+}
+
+#[tokio::test]
+async fn reconcile_removes_fulfilled_before_lock_expiry() {
+    // The lock has expired by now, which the lock flags still tell apart from a fulfillment
+    // after the lock expired.
+    assert_eq!(
+        reconcile(SlashScenario::FulfilledBeforeLockExpiry).await,
+        Some(Discrepancy::FulfilledDuringLock)
+    );
+}
+
+#[tokio::test]
+async fn reconcile_removes_not_locked() {
+    assert_eq!(reconcile(SlashScenario::NotLocked).await, Some(Discrepancy::NotLocked));
+}
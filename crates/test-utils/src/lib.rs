@@ -21,6 +21,8 @@
 pub mod market;
 #[cfg(feature = "povw")]
 pub mod povw;
+pub mod predicate;
+pub mod slashing;
 pub mod verifier;
 pub mod zkc;
 
@@ -15,7 +15,7 @@
 use crate::guests::{
     ASSESSOR_GUEST_ID, ASSESSOR_GUEST_PATH, ECHO_ID, SET_BUILDER_ID, SET_BUILDER_PATH,
 };
-use crate::verifier::setup_verifiers;
+use crate::verifier::is_dev_mode;
 use alloy::{
     network::EthereumWallet,
     node_bindings::AnvilInstance,
@@ -36,7 +36,7 @@ use boundless_market::{
         AssessorCommitment, AssessorJournal, Fulfillment, FulfillmentData, FulfillmentDataType,
         ProofRequest,
     },
-    deployments::Deployment,
+    deployments::{bootstrap, BootstrapParams, Deployment, VerifierKind},
     dynamic_gas_filler::DynamicGasFiller,
     nonce_layer::NonceProvider,
 };
@@ -164,32 +164,32 @@ pub async fn deploy_contracts(
         .connect(&anvil.endpoint())
         .await?;
 
-    // Deploy and setup verifiers
-    let (verifier_router, set_verifier, _groth16_verifier) = setup_verifiers(
-        deployer_provider.clone(),
+    let verifier = match is_dev_mode() {
+        true => VerifierKind::Mock,
+        false => VerifierKind::Groth16,
+    };
+    let params = BootstrapParams::new(
         deployer_address,
-        <[u8; 32]>::from(set_builder_id).into(),
+        set_builder_id,
         set_builder_url,
-    )
-    .await?;
-
-    let hit_points = deploy_hit_points(deployer_address, &deployer_provider).await?;
-    let boundless_market = deploy_boundless_market(
-        deployer_address,
-        &deployer_provider,
-        verifier_router,
-        hit_points,
         assessor_guest_id,
         assessor_guest_url,
-        None,
     )
-    .await?;
+    .with_verifier(verifier);
+    let deployment = bootstrap(&deployer_provider, params).await?;
+    let verifier_router = deployment.verifier_router_address.context("missing verifier router")?;
+    let hit_points = deployment.collateral_token_address.context("missing collateral token")?;
 
     // Mine forward some blocks using the provider
     deployer_provider.anvil_mine(Some(10), Some(2)).await.unwrap();
     deployer_provider.anvil_set_interval_mining(2).await.unwrap();
 
-    Ok((verifier_router, set_verifier, hit_points, boundless_market))
+    Ok((
+        verifier_router,
+        deployment.set_verifier_address,
+        hit_points,
+        deployment.boundless_market_address,
+    ))
 }
 
 // Spin up a test deployment with a RiscZeroMockVerifier if in dev mode or
@@ -0,0 +1,222 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Property testing of predicate evaluation.
+//!
+//! [PredicateHarness] checks that [Predicate::eval] agrees with the evaluation of the market
+//! contract, by calling a deployed `PredicateEvaluator` with `eth_call`. Teams using custom
+//! predicates can run it with random journals, or with mutations of journals they expect to
+//! match, to catch cases where a request would be accepted offchain but rejected onchain.
+
+use alloy::{
+    primitives::{Address, Bytes},
+    providers::Provider,
+    sol,
+};
+use anyhow::{ensure, Context};
+use boundless_market::contracts::{FulfillmentData, Predicate, RequestPredicate};
+use rand::Rng;
+use risc0_zkvm::sha::{Digest, Digestible, Impl as ShaImpl, Sha256};
+use risc0_zkvm::ReceiptClaim;
+
+// NOTE: This requires running `forge build` before running this test.
+sol!(
+    #[sol(rpc)]
+    PredicateEvaluator,
+    "../../out/PredicateEvaluator.sol/PredicateEvaluator.json"
+);
+
+/// Maximum length of the random journals generated by [random_journal].
+pub const MAX_JOURNAL_LEN: usize = 256;
+
+/// Checks [Predicate::eval] against the onchain evaluation of predicates.
+pub struct PredicateHarness<P> {
+    evaluator: PredicateEvaluator::PredicateEvaluatorInstance<P>,
+}
+
+/// Counts of the cases checked by [PredicateHarness::fuzz].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FuzzReport {
+    /// Number of cases the predicate matched.
+    pub matched: usize,
+    /// Number of cases the predicate did not match.
+    pub rejected: usize,
+}
+
+impl<P: Provider + Clone> PredicateHarness<P> {
+    /// Deploy the evaluator contract with the given provider.
+    pub async fn deploy(provider: P) -> anyhow::Result<Self> {
+        let evaluator = PredicateEvaluator::deploy(provider)
+            .await
+            .context("failed to deploy PredicateEvaluator contract")?;
+        Ok(Self { evaluator })
+    }
+
+    /// Use an evaluator contract already deployed at the given address.
+    pub fn new(address: Address, provider: P) -> Self {
+        Self { evaluator: PredicateEvaluator::new(address, provider) }
+    }
+
+    /// Evaluate the predicate onchain against the journal of a successful execution of the image.
+    pub async fn eval_onchain(
+        &self,
+        predicate: &Predicate,
+        image_id: Digest,
+        journal: &[u8],
+    ) -> anyhow::Result<bool> {
+        let predicate = RequestPredicate::from(predicate.clone());
+        let matched = self
+            .evaluator
+            .eval(
+                predicate.predicateType as u8,
+                predicate.data,
+                <[u8; 32]>::from(image_id).into(),
+                Bytes::copy_from_slice(journal),
+            )
+            .call()
+            .await
+            .context("failed to evaluate predicate onchain")?;
+        Ok(matched)
+    }
+
+    /// Check that the offchain and onchain evaluations of the predicate agree on the journal,
+    /// returning whether the predicate matched.
+    pub async fn check(
+        &self,
+        predicate: &Predicate,
+        image_id: Digest,
+        journal: &[u8],
+    ) -> anyhow::Result<bool> {
+        let fulfillment_data =
+            FulfillmentData::from_image_id_and_journal(image_id, journal.to_vec());
+        let offchain = predicate.eval(&fulfillment_data).is_some();
+        let onchain = self.eval_onchain(predicate, image_id, journal).await?;
+        ensure!(
+            offchain == onchain,
+            "predicate {predicate:?} evaluated to {offchain} offchain but {onchain} onchain, for \
+             image ID {image_id} and journal {}",
+            Bytes::copy_from_slice(journal)
+        );
+        Ok(offchain)
+    }
+
+    /// Check the predicate against `iterations` journals generated from `sample`, a journal of
+    /// `image_id` that is expected to match.
+    ///
+    /// The journals are the sample itself, mutations of it (flipped, truncated, and extended),
+    /// and random journals, each also checked with a random image ID. Fails on the first journal
+    /// on which the evaluations disagree.
+    pub async fn fuzz(
+        &self,
+        predicate: &Predicate,
+        image_id: Digest,
+        sample: &[u8],
+        iterations: usize,
+        rng: &mut impl Rng,
+    ) -> anyhow::Result<FuzzReport> {
+        let mut report = FuzzReport::default();
+        for i in 0..iterations {
+            let journal = match i {
+                0 => sample.to_vec(),
+                _ if i % 2 == 1 => mutate_journal(sample, rng),
+                _ => random_journal(rng),
+            };
+            for image_id in [image_id, random_digest(rng)] {
+                match self.check(predicate, image_id, &journal).await? {
+                    true => report.matched += 1,
+                    false => report.rejected += 1,
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// A random digest, e.g. for use as an image ID.
+pub fn random_digest(rng: &mut impl Rng) -> Digest {
+    Digest::from(rng.random::<[u8; 32]>())
+}
+
+/// A random journal of up to [MAX_JOURNAL_LEN] bytes.
+pub fn random_journal(rng: &mut impl Rng) -> Vec<u8> {
+    let len = rng.random_range(0..=MAX_JOURNAL_LEN);
+    (0..len).map(|_| rng.random()).collect()
+}
+
+/// A random mutation of the journal: a flipped bit, a truncation, or an extension.
+pub fn mutate_journal(journal: &[u8], rng: &mut impl Rng) -> Vec<u8> {
+    let mut journal = journal.to_vec();
+    match rng.random_range(0..3) {
+        0 if !journal.is_empty() => {
+            let index = rng.random_range(0..journal.len());
+            journal[index] ^= 1 << rng.random_range(0..8);
+        }
+        1 if !journal.is_empty() => {
+            journal.truncate(rng.random_range(0..journal.len()));
+        }
+        _ => {
+            let extra = rng.random_range(1..=32);
+            journal.extend((0..extra).map(|_| rng.random::<u8>()));
+        }
+    }
+    journal
+}
+
+/// A random predicate of any type over a random journal, with that image ID and journal.
+///
+/// The predicate matches the returned journal, so mutations of it exercise both outcomes.
+pub fn random_predicate(rng: &mut impl Rng) -> (Predicate, Digest, Vec<u8>) {
+    let image_id = random_digest(rng);
+    let journal = random_journal(rng);
+    let predicate = match rng.random_range(0..3) {
+        0 => Predicate::digest_match(image_id, *ShaImpl::hash_bytes(&journal)),
+        1 => {
+            let prefix_len = rng.random_range(0..=journal.len());
+            Predicate::prefix_match(image_id, journal[..prefix_len].to_vec())
+        }
+        _ => Predicate::claim_digest_match(ReceiptClaim::ok(image_id, journal.clone()).digest()),
+    };
+    (predicate, image_id, journal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        network::EthereumWallet, node_bindings::Anvil, providers::ProviderBuilder,
+        signers::local::PrivateKeySigner,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[tokio::test]
+    async fn test_predicate_eval_matches_onchain() -> anyhow::Result<()> {
+        let anvil = Anvil::new().spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect(&anvil.endpoint())
+            .await?;
+        let harness = PredicateHarness::deploy(provider).await?;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..8 {
+            let (predicate, image_id, journal) = random_predicate(&mut rng);
+            assert!(harness.check(&predicate, image_id, &journal).await?);
+            let report = harness.fuzz(&predicate, image_id, &journal, 8, &mut rng).await?;
+            assert!(report.matched >= 1);
+            assert!(report.rejected >= 1);
+        }
+        Ok(())
+    }
+}
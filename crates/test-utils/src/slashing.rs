@@ -0,0 +1,177 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixtures that put requests into the on-chain states a slasher has to handle.
+//!
+//! Each [SlashScenario] is set up against the deployment of a [TestCtx], using Anvil's time
+//! controls to move the chain past the lock and request deadlines instead of waiting for them.
+
+use alloy::{
+    primitives::{Bytes, U256},
+    providers::{ext::AnvilApi, Provider},
+    rpc::types::BlockNumberOrTag,
+};
+use anyhow::{Context, Result};
+use boundless_market::contracts::{
+    boundless_market::FulfillmentTx, AssessorReceipt, FulfillmentDataType, Offer, Predicate,
+    ProofRequest, RequestId, RequestInput, Requirements,
+};
+use risc0_zkvm::sha::Digest;
+
+use crate::{
+    guests::ECHO_ID,
+    market::{mock_singleton, TestCtx},
+};
+
+/// Number of seconds after bidding starts that the lock of a fixture request expires.
+pub const LOCK_TIMEOUT: u32 = 100;
+
+/// Number of seconds after bidding starts that a fixture request expires.
+pub const TIMEOUT: u32 = 200;
+
+/// On-chain state of a request, as set up by [setup].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlashScenario {
+    /// Locked and expired unfulfilled, so the locking prover can be slashed.
+    Expired,
+    /// Locked, expired unfulfilled, and already slashed.
+    AlreadySlashed,
+    /// Locked, and fulfilled after the lock expired but before the request did. The locking
+    /// prover can still be slashed once the request expires.
+    FulfilledAfterLockExpiry,
+    /// Locked and fulfilled before the lock expired, so it can not be slashed.
+    FulfilledBeforeLockExpiry,
+    /// Expired without ever being locked, so there is no prover to slash.
+    NotLocked,
+    /// Locked, and not yet expired.
+    NotExpired,
+}
+
+/// A request set up in a [SlashScenario].
+#[derive(Clone, Debug)]
+pub struct SlashFixture {
+    pub request: ProofRequest,
+    pub client_signature: Bytes,
+}
+
+/// Set up a request with the given index in the given scenario.
+///
+/// The request is free and locked without collateral, so that fixtures can be set up without
+/// funding the prover. Scenarios that require the request to be expired leave the chain just
+/// past its deadline.
+pub async fn setup<P>(ctx: &TestCtx<P>, idx: u32, scenario: SlashScenario) -> Result<SlashFixture>
+where
+    P: Provider + Clone + 'static,
+{
+    let now = latest_timestamp(ctx).await?;
+    let request = ProofRequest::new(
+        RequestId::new(ctx.customer_signer.address(), idx),
+        Requirements::new(Predicate::prefix_match(Digest::from(ECHO_ID), Bytes::default())),
+        "http://image_uri.null",
+        RequestInput::builder().build_inline()?,
+        Offer {
+            minPrice: U256::ZERO,
+            maxPrice: U256::from(1),
+            rampUpStart: now,
+            rampUpPeriod: 1,
+            lockTimeout: LOCK_TIMEOUT,
+            timeout: TIMEOUT,
+            lockCollateral: U256::ZERO,
+        },
+    );
+    let chain_id = ctx.customer_provider.get_chain_id().await?;
+    let client_signature: Bytes = request
+        .sign_request(&ctx.customer_signer, ctx.deployment.boundless_market_address, chain_id)
+        .await?
+        .as_bytes()
+        .into();
+
+    if scenario != SlashScenario::NotLocked {
+        ctx.customer_market.deposit(request.offer.maxPrice).await?;
+        ctx.prover_market
+            .lock_request(&request, client_signature.clone(), None)
+            .await
+            .context("failed to lock request")?;
+    }
+
+    match scenario {
+        SlashScenario::Expired | SlashScenario::NotLocked => {
+            advance_past(ctx, request.expires_at()).await?;
+        }
+        SlashScenario::AlreadySlashed => {
+            advance_past(ctx, request.expires_at()).await?;
+            ctx.customer_market.slash(request.id).await.context("failed to slash request")?;
+        }
+        SlashScenario::FulfilledAfterLockExpiry => {
+            advance_past(ctx, request.lock_expires_at()).await?;
+            fulfill(ctx, &request).await?;
+            advance_past(ctx, request.expires_at()).await?;
+        }
+        SlashScenario::FulfilledBeforeLockExpiry => {
+            fulfill(ctx, &request).await?;
+            advance_past(ctx, request.expires_at()).await?;
+        }
+        SlashScenario::NotExpired => {}
+    }
+
+    Ok(SlashFixture { request, client_signature })
+}
+
+/// Fulfill a request with a mock proof, delivered by the prover of the [TestCtx].
+pub async fn fulfill<P>(ctx: &TestCtx<P>, request: &ProofRequest) -> Result<()>
+where
+    P: Provider + Clone + 'static,
+{
+    let domain = ctx.prover_market.eip712_domain().await?;
+    let (root, set_verifier_seal, fulfillment, assessor_seal) = mock_singleton(
+        request,
+        domain.alloy_struct(),
+        ctx.prover_signer.address(),
+        FulfillmentDataType::ImageIdAndJournal,
+    );
+    ctx.set_verifier.submit_merkle_root(root, set_verifier_seal).await?;
+    let assessor_receipt = AssessorReceipt {
+        seal: assessor_seal,
+        selectors: vec![],
+        prover: ctx.prover_signer.address(),
+        callbacks: vec![],
+    };
+    ctx.prover_market
+        .fulfill(FulfillmentTx::new(vec![fulfillment], assessor_receipt))
+        .await
+        .context("failed to fulfill request")?;
+    Ok(())
+}
+
+/// Move the chain's time forward until the latest block is after the given timestamp.
+pub async fn advance_past<P>(ctx: &TestCtx<P>, timestamp: u64) -> Result<()>
+where
+    P: Provider + Clone + 'static,
+{
+    let now = latest_timestamp(ctx).await?;
+    if now <= timestamp {
+        ctx.customer_provider.anvil_increase_time(timestamp - now + 1).await?;
+        ctx.customer_provider.anvil_mine(Some(1), None).await?;
+    }
+    Ok(())
+}
+
+async fn latest_timestamp<P: Provider>(ctx: &TestCtx<P>) -> Result<u64> {
+    let block = ctx
+        .customer_provider
+        .get_block_by_number(BlockNumberOrTag::Latest)
+        .await?
+        .context("latest block not found")?;
+    Ok(block.header.timestamp)
+}